@@ -0,0 +1,40 @@
+//! Times converting grayscale images to display RAM, without a display attached.
+//! Run with `cargo bench`.
+use nanohat_oled::{Oled, OledError, OledInterface, OLED_HEIGHT, OLED_WIDTH};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// An interface that discards everything sent to it
+struct NullInterface;
+
+impl OledInterface for NullInterface {
+    fn send_command(&mut self, _command: u8) -> Result<(), OledError> {
+        Ok(())
+    }
+
+    fn send_data(&mut self, _data: &[u8]) -> Result<(), OledError> {
+        Ok(())
+    }
+}
+
+/// Runs `f` repeatedly for about a second, and returns the average time per run
+fn time<F: FnMut()>(mut f: F) -> Duration {
+    let mut runs = 0;
+    let start = Instant::now();
+    while start.elapsed() < Duration::from_secs(1) {
+        f();
+        runs += 1;
+    }
+    start.elapsed() / runs
+}
+
+fn main() {
+    let image: Vec<u8> = (0..(OLED_WIDTH * OLED_HEIGHT) as usize)
+        .map(|pixel| (pixel * 7 % 256) as u8)
+        .collect();
+    let mut oled = Oled::new(NullInterface);
+    let per_frame = time(|| oled.draw_image(black_box(&image), 0x80).unwrap());
+    println!("draw_image: {:?} per frame", per_frame);
+    let per_frame = time(|| oled.draw_fn(|x, y| black_box((x ^ y) & 1 == 0)).unwrap());
+    println!("draw_fn:    {:?} per frame", per_frame);
+}
@@ -0,0 +1,68 @@
+//! Times sending framebuffers to display RAM, the whole frame and just the part
+//! that changed, without a display attached. Run with `cargo bench`.
+use nanohat_oled::{BuiltinFont, Framebuffer, Oled, OledError, OledInterface};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// An interface that discards everything sent to it, in transfers as small as
+/// an SMBus block write, as many I2C adapters manage
+struct NullInterface;
+
+impl OledInterface for NullInterface {
+    fn send_command(&mut self, _command: u8) -> Result<(), OledError> {
+        Ok(())
+    }
+
+    fn send_data(&mut self, data: &[u8]) -> Result<(), OledError> {
+        black_box(data);
+        Ok(())
+    }
+
+    fn max_transfer_len(&self) -> usize {
+        31
+    }
+}
+
+/// Runs `f` repeatedly for about a second, and returns the average time per run
+fn time<F: FnMut()>(mut f: F) -> Duration {
+    let mut runs = 0;
+    let start = Instant::now();
+    while start.elapsed() < Duration::from_secs(1) {
+        f();
+        runs += 1;
+    }
+    start.elapsed() / runs
+}
+
+fn main() {
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    let mut frames = [Framebuffer::new(), Framebuffer::new()];
+    frames[0].fill_rect(0, 0, 128, 64, true);
+    let mut shown = 0;
+    let per_frame = time(|| {
+        shown ^= 1;
+        oled.flush_all(black_box(&frames[shown])).unwrap()
+    });
+    println!("flush_all:         {:?} per frame", per_frame);
+    let per_frame = time(|| {
+        shown ^= 1;
+        oled.flush(black_box(&frames[shown])).unwrap()
+    });
+    println!("flush, all new:    {:?} per frame", per_frame);
+    // a line of text scrolling along, as a marquee redraws it
+    let mut frame = Framebuffer::new();
+    let mut offset = 0;
+    let per_frame = time(|| {
+        offset = (offset + 1) % 128;
+        frame.fill_rect(0, 24, 128, 8, false);
+        frame.draw_text(128 - offset, 24, "Now playing", &BuiltinFont::MONOSPACE);
+        oled.flush(black_box(&frame)).unwrap()
+    });
+    println!("flush, one line:   {:?} per frame", per_frame);
+    let per_frame = time(|| {
+        oled.set_text_xy(0, 3).unwrap();
+        oled.put_string(black_box("Now playing")).unwrap();
+    });
+    println!("put_string:        {:?} per line", per_frame);
+}
@@ -0,0 +1,243 @@
+//! Sequences of sprites shown one after another, each for its own length of time
+use core::time::Duration;
+
+use crate::{BlitOp, Oled, OledError, OledInterface, Sprite};
+
+/// Where an animation's frames come from
+#[derive(Clone, Copy, Debug)]
+enum Frames<'a> {
+    /// One sprite per frame
+    List(&'a [Sprite<'a>]),
+    /// A sprite sheet, with frames stacked top to bottom, each `height` pixels high
+    Sheet { sheet: Sprite<'a>, height: u16 },
+}
+
+/// An animation, eg a spinner, drawn as a series of [`Sprite`](struct.Sprite.html)s
+/// at the same position on the display.
+///
+/// The animation doesn't read a clock itself: [`tick()`](#method.tick) is told how
+/// much time has passed, draws whichever frame is due, and says how long until the
+/// next one, so it can be driven from any event loop, including on `no_std`.
+/// With `std`, [`play()`](#method.play) does the waiting too.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{Animation, Oled, OledResult, Sprite};
+/// # use std::time::Duration;
+/// # fn main() -> OledResult {
+/// // four 8x8 frames, stacked in one sprite sheet
+/// const SPINNER: Sprite = Sprite::new(8, 32, &[0x18; 32]);
+///
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// let durations = [Duration::from_millis(100)];
+/// let mut spinner = Animation::from_sheet(SPINNER, 8, &durations)?
+///     .with_position(120, 0)
+///     .with_loops(Some(10));
+/// spinner.play(&mut oled)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Animation<'a> {
+    /// The frames, in order
+    frames: Frames<'a>,
+    /// How long each frame is shown for, or a single duration for all of them
+    durations: &'a [Duration],
+    /// Top left corner the frames are drawn at
+    position: (u16, u16),
+    /// How frames are combined with the display
+    op: BlitOp,
+    /// Number of times to play through the frames, or `None` to repeat forever
+    loops: Option<u32>,
+    /// Frame currently due
+    index: usize,
+    /// Time spent on the current frame so far
+    elapsed: Duration,
+    /// Times played through so far
+    completed: u32,
+    /// Frame last drawn, if any
+    drawn: Option<usize>,
+}
+
+impl<'a> Animation<'a> {
+    /// Creates an animation from a sprite per frame.
+    /// `durations` gives how long each frame is shown, or holds a single duration
+    /// to use for all of them. The animation is drawn at the top left corner,
+    /// replacing what is there, and repeats forever unless
+    /// [`with_loops()`](#method.with_loops) says otherwise.
+    ///
+    /// Returns `InvalidArgument` if there are no frames, `durations` doesn't
+    /// match them, or every frame has a zero duration.
+    pub fn new(frames: &'a [Sprite<'a>], durations: &'a [Duration]) -> Result<Self, OledError> {
+        Self::with_frames(Frames::List(frames), frames.len(), durations)
+    }
+
+    /// Creates an animation from a sprite sheet holding every frame, stacked top to
+    /// bottom, each `frame_height` pixels high. Otherwise as for
+    /// [`new()`](#method.new); also returns `InvalidArgument` if the sheet's height
+    /// isn't a whole number of frames.
+    pub fn from_sheet(
+        sheet: Sprite<'a>,
+        frame_height: u16,
+        durations: &'a [Duration],
+    ) -> Result<Self, OledError> {
+        let (_, height) = sheet.dimensions();
+        if frame_height == 0 || height % frame_height != 0 {
+            return Err(OledError::InvalidArgument(
+                "sprite sheet isn't a whole number of frames",
+            ));
+        }
+        let count = (height / frame_height) as usize;
+        let frames = Frames::Sheet {
+            sheet,
+            height: frame_height,
+        };
+        Self::with_frames(frames, count, durations)
+    }
+
+    /// Checks the durations against the number of frames
+    fn with_frames(
+        frames: Frames<'a>,
+        count: usize,
+        durations: &'a [Duration],
+    ) -> Result<Self, OledError> {
+        if count == 0 {
+            return Err(OledError::InvalidArgument("animation has no frames"));
+        }
+        if durations.len() != 1 && durations.len() != count {
+            return Err(OledError::InvalidArgument(
+                "need one duration, or one per frame",
+            ));
+        }
+        if durations.iter().all(|duration| duration.is_zero()) {
+            return Err(OledError::InvalidArgument("animation takes no time"));
+        }
+        Ok(Animation {
+            frames,
+            durations,
+            position: (0, 0),
+            op: BlitOp::Copy,
+            loops: None,
+            index: 0,
+            elapsed: Duration::ZERO,
+            completed: 0,
+            drawn: None,
+        })
+    }
+
+    /// Draws the frames with their top left corner at `x`, `y`
+    pub fn with_position(self, x: u16, y: u16) -> Self {
+        Animation {
+            position: (x, y),
+            ..self
+        }
+    }
+
+    /// Combines the frames with the display using `op`, rather than replacing it
+    pub fn with_op(self, op: BlitOp) -> Self {
+        Animation { op, ..self }
+    }
+
+    /// Plays through the frames `loops` times, then stops on the last one,
+    /// or repeats forever if `None`
+    pub fn with_loops(self, loops: Option<u32>) -> Self {
+        Animation { loops, ..self }
+    }
+
+    /// Number of frames
+    pub fn len(&self) -> usize {
+        match self.frames {
+            Frames::List(frames) => frames.len(),
+            Frames::Sheet { sheet, height } => (sheet.dimensions().1 / height) as usize,
+        }
+    }
+
+    /// Always false, since an animation has at least one frame
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Index of the frame that is currently due
+    pub fn current_frame(&self) -> usize {
+        self.index
+    }
+
+    /// Whether every loop has been played, leaving the last frame on the display
+    pub fn is_finished(&self) -> bool {
+        self.loops.is_some_and(|loops| self.completed >= loops)
+    }
+
+    /// Goes back to the first frame, so the next tick draws it and starts over
+    pub fn reset(&mut self) {
+        self.index = 0;
+        self.elapsed = Duration::ZERO;
+        self.completed = 0;
+        self.drawn = None;
+    }
+
+    /// How long frame `index` is shown
+    fn duration(&self, index: usize) -> Duration {
+        self.durations[index.min(self.durations.len() - 1)]
+    }
+
+    /// The sprite for frame `index`
+    fn frame(&self, index: usize) -> Sprite<'a> {
+        match self.frames {
+            Frames::List(frames) => frames[index],
+            Frames::Sheet { sheet, height } => sheet.rows(index as u16 * height, height),
+        }
+    }
+
+    /// Moves the animation on by `elapsed`, the time since the last tick, and draws
+    /// the frame that is then due, if it isn't already on the display.
+    /// The first tick always draws the first frame, so it can be passed
+    /// `Duration::ZERO`.
+    ///
+    /// Returns how long until the next frame is due, or `None` once the animation
+    /// has finished.
+    pub fn tick<I: OledInterface>(
+        &mut self,
+        oled: &mut Oled<I>,
+        elapsed: Duration,
+    ) -> Result<Option<Duration>, OledError> {
+        self.elapsed += elapsed;
+        while !self.is_finished() && self.elapsed >= self.duration(self.index) {
+            self.elapsed -= self.duration(self.index);
+            if self.index + 1 < self.len() {
+                self.index += 1;
+            } else {
+                self.completed += 1;
+                if !self.is_finished() {
+                    self.index = 0;
+                }
+            }
+        }
+        if self.drawn != Some(self.index) {
+            let (x, y) = self.position;
+            oled.blit_sprite(&self.frame(self.index), x, y, self.op)?;
+            self.drawn = Some(self.index);
+        }
+        if self.is_finished() {
+            Ok(None)
+        } else {
+            Ok(Some(self.duration(self.index) - self.elapsed))
+        }
+    }
+
+    /// Plays the animation from the start, sleeping between frames, and returns
+    /// once it has finished. An animation set to repeat forever only returns if
+    /// drawing fails.
+    #[cfg(feature = "std")]
+    pub fn play<I: OledInterface>(&mut self, oled: &mut Oled<I>) -> Result<(), OledError> {
+        self.reset();
+        let mut last = std::time::Instant::now();
+        let mut elapsed = Duration::ZERO;
+        while let Some(wait) = self.tick(oled, elapsed)? {
+            std::thread::sleep(wait);
+            let now = std::time::Instant::now();
+            elapsed = now - last;
+            last = now;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,424 @@
+//! A runtime tying the display, the buttons and a stack of screens together
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use crate::idle::BoxedScreensaver;
+use crate::{
+    ButtonEvent, Framebuffer, Gesture, GestureDetector, IdleManager, Input, Oled, OledError,
+    OledInterface, OledResult, Rect, Widget,
+};
+
+/// What an [`App`](struct.App.html) should do after a
+/// [`Screen`](trait.Screen.html) has handled a gesture or a tick
+pub enum Transition {
+    /// Keep showing the same screen
+    Stay,
+    /// Show a new screen on top of this one, eg a submenu
+    Push(Box<dyn Screen>),
+    /// Show a new screen in place of this one
+    Replace(Box<dyn Screen>),
+    /// Go back to the screen underneath this one, ending the app if there
+    /// isn't one
+    Pop,
+    /// End the app
+    Quit,
+}
+
+/// A whole screen of an [`App`](struct.App.html), eg a menu or a status page,
+/// which is a [`Widget`](trait.Widget.html) drawn across the whole display
+/// that also handles the buttons and time passing.
+///
+/// Only the screen on top of the app's stack is shown, and is given gestures
+/// and ticks; it's rendered when it [needs it](trait.Widget.html#method.needs_render)
+/// and whenever it comes to the top.
+pub trait Screen: Widget {
+    /// Handles a gesture made with the buttons
+    fn handle(&mut self, gesture: Gesture) -> Transition;
+
+    /// Called each time the screen comes to the top of the stack, eg to
+    /// refresh what it shows. By default, nothing happens.
+    fn on_enter(&mut self) {}
+
+    /// Called each time the screen stops being the one on top, whether it's
+    /// covered by another, replaced, popped, or the app ends, eg to stop
+    /// polling something only it shows. By default, nothing happens.
+    fn on_exit(&mut self) {}
+
+    /// Handles time passing, `elapsed` since the last tick, eg to update a
+    /// clock. By default, nothing happens.
+    fn tick(&mut self, elapsed: Duration) -> Transition {
+        let _ = elapsed;
+        Transition::Stay
+    }
+}
+
+/// A stack of [`Screen`](trait.Screen.html)s, of which only the one on top is
+/// shown and handles gestures and ticks, as used by [`App`](struct.App.html).
+/// It can also be driven directly, eg with gestures from a loop of one's own:
+/// each screen's [`on_enter()`](trait.Screen.html#method.on_enter) and
+/// [`on_exit()`](trait.Screen.html#method.on_exit) are called as it comes to
+/// the top and leaves it, and [`draw()`](#method.draw) renders the screen on
+/// top whenever it needs it or has just come to the top.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{
+/// #     Buttons, GestureDetector, Oled, OledError, Screen, ScreenManager,
+/// # };
+/// # use std::{thread::sleep, time::Duration};
+/// # fn main() -> Result<(), OledError> {
+/// # let status: Box<dyn Screen> = unimplemented!();
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// let (mut buttons, mut gestures) = (Buttons::new()?, GestureDetector::new());
+/// let mut screens = ScreenManager::new(status);
+/// while !screens.is_empty() {
+///     for gesture in gestures.poll(&mut buttons)? {
+///         screens.handle(gesture);
+///     }
+///     screens.draw(&mut oled)?;
+///     sleep(Duration::from_millis(10));
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct ScreenManager {
+    /// Screens, with the one shown last
+    screens: Vec<Box<dyn Screen>>,
+    /// Whether the shown screen must be rendered, whether or not it changed
+    redraw: bool,
+}
+
+impl ScreenManager {
+    /// Creates a stack of just `screen`, which is entered straight away
+    pub fn new(mut screen: Box<dyn Screen>) -> Self {
+        screen.on_enter();
+        ScreenManager {
+            screens: vec![screen],
+            redraw: true,
+        }
+    }
+
+    /// Shows `screen` on top of the one shown now, eg a submenu
+    pub fn push(&mut self, mut screen: Box<dyn Screen>) {
+        if let Some(top) = self.screens.last_mut() {
+            top.on_exit();
+        }
+        screen.on_enter();
+        self.screens.push(screen);
+        self.redraw = true;
+    }
+
+    /// Takes the shown screen off the stack, showing the one underneath, if
+    /// there is one, and returns it
+    pub fn pop(&mut self) -> Option<Box<dyn Screen>> {
+        let mut popped = self.screens.pop()?;
+        popped.on_exit();
+        if let Some(top) = self.screens.last_mut() {
+            top.on_enter();
+        }
+        self.redraw = true;
+        Some(popped)
+    }
+
+    /// Shows `screen` in place of the one shown now, returning that
+    pub fn replace(&mut self, mut screen: Box<dyn Screen>) -> Option<Box<dyn Screen>> {
+        let mut replaced = self.screens.pop();
+        if let Some(replaced) = &mut replaced {
+            replaced.on_exit();
+        }
+        screen.on_enter();
+        self.screens.push(screen);
+        self.redraw = true;
+        replaced
+    }
+
+    /// Ends every screen, leaving the stack empty
+    pub fn clear(&mut self) {
+        if let Some(top) = self.screens.last_mut() {
+            top.on_exit();
+        }
+        self.screens.clear();
+    }
+
+    /// Changes the stack as `transition` says, and returns whether there are
+    /// still screens on it
+    pub fn apply(&mut self, transition: Transition) -> bool {
+        match transition {
+            Transition::Stay => {}
+            Transition::Push(screen) => self.push(screen),
+            Transition::Replace(screen) => {
+                self.replace(screen);
+            }
+            Transition::Pop => {
+                self.pop();
+            }
+            Transition::Quit => self.clear(),
+        }
+        !self.screens.is_empty()
+    }
+
+    /// Passes a gesture to the shown screen and carries out the transition it
+    /// returns. Returns whether there are still screens on the stack.
+    pub fn handle(&mut self, gesture: Gesture) -> bool {
+        match self.screens.last_mut() {
+            Some(screen) => {
+                let transition = screen.handle(gesture);
+                self.apply(transition)
+            }
+            None => false,
+        }
+    }
+
+    /// Ticks the shown screen, `elapsed` since its last tick, and carries out
+    /// the transition it returns. Returns whether there are still screens on
+    /// the stack.
+    pub fn tick(&mut self, elapsed: Duration) -> bool {
+        match self.screens.last_mut() {
+            Some(screen) => {
+                let transition = screen.tick(elapsed);
+                self.apply(transition)
+            }
+            None => false,
+        }
+    }
+
+    /// Whether the shown screen needs rendering, because it has changed or
+    /// just come to the top
+    pub fn needs_render(&self) -> bool {
+        self.screens
+            .last()
+            .is_some_and(|screen| self.redraw || screen.needs_render())
+    }
+
+    /// Renders the shown screen across the whole display, if it needs it
+    pub fn draw<I: OledInterface>(&mut self, oled: &mut Oled<I>) -> OledResult {
+        if !self.needs_render() {
+            return Ok(());
+        }
+        self.redraw = false;
+        let (width, height) = oled.dimensions();
+        if let Some(screen) = self.screens.last_mut() {
+            oled.draw(|frame| screen.render(frame, Rect::new(0, 0, width, height)))?;
+        }
+        Ok(())
+    }
+
+    /// The shown screen, if there is one
+    pub fn top(&mut self) -> Option<&mut (dyn Screen + 'static)> {
+        self.screens.last_mut().map(|screen| &mut **screen)
+    }
+
+    /// Number of screens on the stack
+    pub fn len(&self) -> usize {
+        self.screens.len()
+    }
+
+    /// Whether the stack has no screens left, once the last has been popped or
+    /// one has quit
+    pub fn is_empty(&self) -> bool {
+        self.screens.is_empty()
+    }
+}
+
+/// Runs a NanoHat application: polls the buttons, passes the gestures they
+/// make and regular ticks to the screen on top of a stack, and renders it when
+/// it changes, until the last screen is popped or one quits.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{
+/// #     App, Buttons, Framebuffer, Gesture, Key, Oled, OledError, Rect, Screen, Transition,
+/// #     Widget,
+/// # };
+/// /// Counts presses of K1 until K3 is pressed
+/// struct Counter(u32);
+///
+/// impl Widget for Counter {
+///     fn render(&mut self, frame: &mut Framebuffer, area: Rect) {
+///         frame.fill_rect(area.x, area.y, area.width, area.height, false);
+///         // ... draw the count
+///     }
+/// }
+///
+/// impl Screen for Counter {
+///     fn handle(&mut self, gesture: Gesture) -> Transition {
+///         match gesture {
+///             Gesture::Press(Key::K1) => self.0 += 1,
+///             Gesture::Press(Key::K3) => return Transition::Quit,
+///             _ => {}
+///         }
+///         Transition::Stay
+///     }
+/// }
+///
+/// # fn main() -> Result<(), OledError> {
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// App::new(oled, Buttons::new()?, Box::new(Counter(0))).run()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct App<I> {
+    /// The display
+    oled: Oled<I>,
+    /// Where gestures come from
+    buttons: Box<dyn Input>,
+    /// What gestures the buttons make
+    gestures: GestureDetector,
+    /// Screens, with the one shown on top
+    screens: ScreenManager,
+    /// How often the shown screen ticks
+    tick_interval: Duration,
+    /// How often the buttons are polled
+    poll_interval: Duration,
+    /// When the shown screen last ticked, once the app has started
+    last_tick: Option<Instant>,
+    /// Which buttons are down, while the press that woke the display from
+    /// auto-off or ended the screensaver is being ignored
+    waking: Option<[bool; 3]>,
+    /// What's shown while the buttons go unused, if anything
+    idle: Option<IdleManager<BoxedScreensaver>>,
+}
+
+impl<I: OledInterface> App<I> {
+    /// Creates an app showing `screen` on `oled`, controlled by `buttons`, or
+    /// any other [`Input`](trait.Input.html), making the gestures of a default
+    /// [`GestureDetector`](struct.GestureDetector.html). Buttons are polled
+    /// every 10 ms, and the screen ticks every 100 ms.
+    pub fn new<B: Input + 'static>(oled: Oled<I>, buttons: B, screen: Box<dyn Screen>) -> Self {
+        App {
+            oled,
+            buttons: Box::new(buttons),
+            gestures: GestureDetector::new(),
+            screens: ScreenManager::new(screen),
+            tick_interval: Duration::from_millis(100),
+            poll_interval: Duration::from_millis(10),
+            last_tick: None,
+            waking: None,
+            idle: None,
+        }
+    }
+
+    /// Recognises gestures with `gestures`, eg to turn on double presses
+    pub fn with_gestures(self, gestures: GestureDetector) -> Self {
+        App { gestures, ..self }
+    }
+
+    /// Ticks the shown screen every `interval`
+    pub fn with_tick_interval(self, interval: Duration) -> Self {
+        App {
+            tick_interval: interval,
+            ..self
+        }
+    }
+
+    /// Polls the buttons every `interval`
+    pub fn with_poll_interval(self, interval: Duration) -> Self {
+        App {
+            poll_interval: interval,
+            ..self
+        }
+    }
+
+    /// Shows a screensaver, or puts the display to sleep, as `idle` says once
+    /// the buttons have gone unused for a while. The press that ends it puts
+    /// the shown screen back, and isn't passed on.
+    pub fn with_idle<S: FnMut(&mut Framebuffer, Duration) + 'static>(
+        self,
+        idle: IdleManager<S>,
+    ) -> Self {
+        App {
+            idle: Some(idle.boxed()),
+            ..self
+        }
+    }
+
+    /// The display, eg to change its contrast
+    pub fn oled(&mut self) -> &mut Oled<I> {
+        &mut self.oled
+    }
+
+    /// Consumes the app, returning the display
+    pub fn into_oled(self) -> Oled<I> {
+        self.oled
+    }
+
+    /// Runs the app until it ends
+    pub fn run(&mut self) -> OledResult {
+        while self.step(Instant::now())? {
+            sleep(self.poll_interval);
+        }
+        Ok(())
+    }
+
+    /// Runs the app once through, at `now`: polls the buttons and passes on
+    /// any gestures, ticks the shown screen if it's due, then renders it if
+    /// need be. Returns whether the app is still running.
+    ///
+    /// With [`Oled::set_auto_off()`](struct.Oled.html#method.set_auto_off),
+    /// button presses count as activity, and once the display has gone to sleep
+    /// the screen isn't rendered until a press wakes it. That press only wakes
+    /// it, and isn't passed on. Likewise, the picture is moved as set by
+    /// [`Oled::set_pixel_shift()`](struct.Oled.html#method.set_pixel_shift),
+    /// and the cursor shown by
+    /// [`Oled::show_cursor()`](struct.Oled.html#method.show_cursor) blinks,
+    /// as the screen ticks.
+    pub fn step(&mut self, now: Instant) -> Result<bool, OledError> {
+        let mut events = self.buttons.poll_events()?;
+        if !events.is_empty() {
+            let woke = self.oled.touch()?;
+            let was_idle = match &mut self.idle {
+                Some(idle) => idle.touch(&mut self.oled)?,
+                None => false,
+            };
+            if woke || was_idle {
+                self.waking = Some([false; 3]);
+            }
+        }
+        // a press that wakes the display only wakes it, until it's let go
+        if let Some(held) = &mut self.waking {
+            for event in events.drain(..) {
+                match event {
+                    ButtonEvent::Press(key) => held[key as usize] = true,
+                    ButtonEvent::Release(key) => held[key as usize] = false,
+                }
+            }
+            if !held.contains(&true) {
+                self.waking = None;
+            }
+        }
+        for gesture in self.gestures.update(events, now) {
+            if !self.screens.handle(gesture) {
+                return Ok(false);
+            }
+        }
+        let last_tick = *self.last_tick.get_or_insert(now);
+        let elapsed = now.saturating_duration_since(last_tick);
+        if elapsed >= self.tick_interval && elapsed > Duration::ZERO {
+            self.last_tick = Some(now);
+            self.oled.tick_auto_off(elapsed)?;
+            self.oled.tick_pixel_shift(elapsed)?;
+            self.oled.tick_cursor(elapsed)?;
+            if let Some(idle) = &mut self.idle {
+                idle.tick(&mut self.oled, elapsed)?;
+            }
+            if !self.screens.tick(elapsed) {
+                return Ok(false);
+            }
+        }
+        if self.screens.is_empty() {
+            return Ok(false);
+        }
+        // rendering would wake a display that has gone to sleep by itself, or
+        // be lost under the screensaver
+        let idle = self.idle.as_ref().is_some_and(|idle| idle.is_idle());
+        if !self.oled.auto_off.asleep && !idle {
+            self.screens.draw(&mut self.oled)?;
+        }
+        Ok(true)
+    }
+
+    /// The stack of screens, eg to push one from outside the app
+    pub fn screens(&mut self) -> &mut ScreenManager {
+        &mut self.screens
+    }
+}
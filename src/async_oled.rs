@@ -0,0 +1,179 @@
+//! Non-blocking variant of the display driver, for use with async executors
+use crate::font::BasicFont;
+use crate::{
+    pack_fn, pack_image, AddressingMode, Command, Framebuffer, Image, OledConfig, OledError,
+    OledResult, EMPTY_SCREEN, OLED_HEIGHT, OLED_RAM_SIZE, OLED_WIDTH, TEXT_COLUMNS, TEXT_ROWS,
+};
+
+/// Commands to move the RAM pointer to the given text cell
+fn text_xy_commands(column: u8, row: u8) -> Result<[u8; 3], OledError> {
+    if column >= TEXT_COLUMNS || row >= TEXT_ROWS {
+        return Err(OledError::OutOfBounds);
+    }
+    Ok([
+        0xb0 + row,                          // set page address
+        (8 * column) & 0x0f,                 // set column low address
+        0x10 + (((8 * column) >> 4) & 0x0f), // set column high address
+    ])
+}
+
+/// An asynchronous transport capable of delivering command and data bytes to the display.
+///
+/// This is the async counterpart of [`OledInterface`](trait.OledInterface.html), and
+/// maps directly onto an `embedded-hal-async` I2C bus.
+#[allow(async_fn_in_trait)]
+pub trait AsyncOledInterface {
+    /// Sends a single byte to the display's command parser
+    async fn send_command(&mut self, command: u8) -> Result<(), OledError>;
+
+    /// Sends bytes to the display RAM at the current RAM pointer
+    async fn send_data(&mut self, data: &[u8]) -> Result<(), OledError>;
+}
+
+/// Represents the NanoHat OLED device, driven asynchronously.
+///
+/// Mirrors the [`Oled`](struct.Oled.html) API, but yields to the executor while
+/// waiting on the bus, so that other tasks can run during a full-frame write.
+pub struct OledAsync<I> {
+    /// Transport used to reach the display
+    interface: I,
+}
+
+impl<I: AsyncOledInterface> OledAsync<I> {
+    /// Wraps an [`AsyncOledInterface`](trait.AsyncOledInterface.html) that is already
+    /// connected to the display.
+    pub fn new(interface: I) -> Self {
+        Self { interface }
+    }
+
+    /// Initial low-level setup for the display, per SSD1306 and NanoHat OLED datasheets.
+    pub async fn init(&mut self) -> OledResult {
+        self.init_with(OledConfig::DEFAULT).await
+    }
+
+    /// Initial low-level setup for the display, with `config`'s settings.
+    /// See [`Oled::init_with()`](struct.Oled.html#method.init_with).
+    ///
+    /// Pictures are sent in one go, relying on horizontal addressing, so only
+    /// the SSD1306 is supported. Returns `InvalidArgument`, without sending
+    /// anything, for the SH1106.
+    pub async fn init_with(&mut self, config: OledConfig) -> OledResult {
+        if !config.controller().has_windows() {
+            return Err(OledError::InvalidArgument(
+                "only the SSD1306 can be driven asynchronously",
+            ));
+        }
+        for command in config.sequence()?.iter() {
+            self.send_command(*command).await?;
+        }
+        self.set_addressing_mode(AddressingMode::Horizontal).await?;
+        self.clear_display().await?;
+        Ok(())
+    }
+
+    /// Sends a command or command argument to the display's command parser
+    pub async fn send_command<B: Into<u8>>(&mut self, byte: B) -> OledResult {
+        self.interface.send_command(byte.into()).await
+    }
+
+    /// Sends a data byte to the display RAM.
+    /// See [`Oled::send_data()`](struct.Oled.html#method.send_data) for details on RAM layout.
+    pub async fn send_data<B: Into<u8>>(&mut self, byte: B) -> OledResult {
+        self.interface.send_data(&[byte.into()]).await
+    }
+
+    /// Sends a set of data all at once into the display RAM.
+    pub async fn send_array_data<'a, B: Into<&'a [u8]>>(&mut self, data: B) -> OledResult {
+        self.interface.send_data(data.into()).await
+    }
+
+    /// Sets the cursor position for writing text to display RAM.
+    pub async fn set_text_xy(&mut self, column: u8, row: u8) -> OledResult {
+        for command in text_xy_commands(column, row)?.iter() {
+            self.send_command(*command).await?;
+        }
+        Ok(())
+    }
+
+    /// Completely clears the display of text and images
+    pub async fn clear_display(&mut self) -> OledResult {
+        self.send_command(Command::DisplayOff).await?;
+        self.set_text_xy(0, 0).await?;
+        self.send_array_data(&EMPTY_SCREEN[..]).await?;
+        self.send_command(Command::DisplayOn).await?;
+        Ok(())
+    }
+
+    /// Writes an image bitmap to the screen.
+    /// See [`Oled::draw_image()`](struct.Oled.html#method.draw_image) for details.
+    pub async fn draw_image(&mut self, image: &Image, threshold: u8) -> OledResult {
+        let mut write_page = [0; OLED_RAM_SIZE];
+        pack_image(image, OLED_WIDTH, OLED_HEIGHT, threshold, &mut write_page)?;
+        self.set_text_xy(0, 0).await?;
+        self.send_array_data(&write_page[..]).await?;
+        Ok(())
+    }
+
+    /// Draws procedurally generated content to the whole screen.
+    /// See [`Oled::draw_fn()`](struct.Oled.html#method.draw_fn) for details.
+    pub async fn draw_fn<F: Fn(u16, u16) -> bool>(&mut self, f: F) -> OledResult {
+        let mut write_page = [0; OLED_RAM_SIZE];
+        pack_fn(f, &mut write_page);
+        self.set_text_xy(0, 0).await?;
+        self.send_array_data(&write_page[..]).await?;
+        Ok(())
+    }
+
+    /// Sends a whole [`Framebuffer`](struct.Framebuffer.html) to the display.
+    /// Unlike [`Oled::flush()`](struct.Oled.html#method.flush), this relies on the display
+    /// being in horizontal addressing mode, as set up by [`init()`](struct.OledAsync.html#method.init).
+    pub async fn flush(&mut self, framebuffer: &Framebuffer) -> OledResult {
+        self.set_text_xy(0, 0).await?;
+        self.send_array_data(&framebuffer.as_bytes()[..]).await?;
+        Ok(())
+    }
+
+    /// Writes a single character to the display at the current X,Y location.
+    /// See [`Oled::put_char()`](struct.Oled.html#method.put_char) for details.
+    pub async fn put_char(&mut self, char: char) -> OledResult {
+        let bitmap = BasicFont::bitmap(char);
+        self.send_array_data(&bitmap[..]).await?;
+        Ok(())
+    }
+
+    /// Writes a string to the display, starting at the current X,Y location.
+    /// See [`Oled::put_string()`](struct.Oled.html#method.put_string) for details.
+    pub async fn put_string(&mut self, string: &str) -> OledResult {
+        for char in string.chars() {
+            self.put_char(char).await?;
+        }
+        Ok(())
+    }
+
+    /// Writes a single character to the display as black on white.
+    /// See [`Oled::put_char_inverted()`](struct.Oled.html#method.put_char_inverted) for details.
+    pub async fn put_char_inverted(&mut self, char: char) -> OledResult {
+        let mut bitmap = BasicFont::bitmap(char);
+        for column in bitmap.iter_mut() {
+            *column = !*column;
+        }
+        self.send_array_data(&bitmap[..]).await?;
+        Ok(())
+    }
+
+    /// Writes a string to the display as black on white.
+    /// See [`Oled::put_string_inverted()`](struct.Oled.html#method.put_string_inverted) for details.
+    pub async fn put_string_inverted(&mut self, string: &str) -> OledResult {
+        for char in string.chars() {
+            self.put_char_inverted(char).await?;
+        }
+        Ok(())
+    }
+
+    /// Sets the addressing mode to the supplied [`AddressingMode`](enum.AddressingMode.html).
+    pub async fn set_addressing_mode(&mut self, mode: AddressingMode) -> OledResult {
+        self.send_command(Command::SetAddressingMode).await?;
+        self.send_command(mode).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,295 @@
+//! Encoding and drawing 1D barcodes, eg to show an asset ID to a scanner
+use crate::{Framebuffer, Oled, OledError, OledInterface, OledResult, Rect, Widget, OLED_WIDTH};
+
+/// Most modules a [`Barcode`](struct.Barcode.html) can have, not counting its
+/// quiet zones: one for each column of the display
+pub const MAX_BARCODE_MODULES: usize = OLED_WIDTH as usize;
+
+/// Widths of the bars and spaces of each Code 128 symbol, from 0 to Start C
+const CODE128: [u32; 106] = [
+    212222, 222122, 222221, 121223, 121322, 131222, 122213, 122312, 132212, 221213, 221312, 231212,
+    112232, 122132, 122231, 113222, 123122, 123221, 223211, 221132, 221231, 213212, 223112, 312131,
+    311222, 321122, 321221, 312212, 322112, 322211, 212123, 212321, 232121, 111323, 131123, 131321,
+    112313, 132113, 132311, 211313, 231113, 231311, 112133, 112331, 132131, 113123, 113321, 133121,
+    313121, 211331, 231131, 213113, 213311, 213131, 311123, 311321, 331121, 312113, 312311, 332111,
+    314111, 221411, 431111, 111224, 111422, 121124, 121421, 141122, 141221, 112214, 112412, 122114,
+    122411, 142112, 142211, 241211, 221114, 413111, 241112, 134111, 111242, 121142, 121241, 114212,
+    124112, 124211, 411212, 421112, 421211, 212141, 214121, 412121, 111143, 111341, 131141, 114113,
+    114311, 411113, 411311, 113141, 114131, 311141, 411131, 211412, 211214, 211232,
+];
+
+/// Widths of the bars and spaces of the Code 128 stop symbol
+const CODE128_STOP: u32 = 2331112;
+
+/// Code 128 symbols that start code sets B and C
+const CODE128_START_B: u32 = 104;
+const CODE128_START_C: u32 = 105;
+
+/// Code 39 characters in groups of ten that share the position of their wide
+/// space, each in the same order of wide bars
+const CODE39: [(&str, usize); 4] = [
+    ("1234567890", 3),
+    ("ABCDEFGHIJ", 5),
+    ("KLMNOPQRST", 7),
+    ("UVWXYZ-. *", 1),
+];
+
+/// Positions of the two wide bars of each character in a Code 39 group
+const CODE39_BARS: [(usize, usize); 10] = [
+    (0, 8),
+    (2, 8),
+    (0, 2),
+    (4, 8),
+    (0, 4),
+    (2, 4),
+    (6, 8),
+    (0, 6),
+    (2, 6),
+    (4, 6),
+];
+
+/// Code 39 characters with three wide spaces and no wide bars, and which
+/// space is narrow
+const CODE39_SPACES: [(char, usize); 4] = [('$', 7), ('/', 5), ('+', 3), ('%', 1)];
+
+/// How a [`Barcode`](struct.Barcode.html) encodes its text
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Symbology {
+    /// Code 128, which encodes printable ASCII, and pairs of digits compactly.
+    /// Text that's all digits, an even number of them, is encoded in code
+    /// set C, and anything else in code set B.
+    Code128,
+    /// Code 39, which encodes digits, capital letters, space and `-.$/+%`,
+    /// less compactly, but is read by nearly every scanner. Wide elements are
+    /// three times the width of narrow ones.
+    Code39,
+}
+
+/// A [`Widget`](trait.Widget.html) showing text as a 1D barcode, as bars
+/// across the whole height of its area. Bars are unlit on a lit background,
+/// with quiet zones at either side, as scanners expect, and the barcode is
+/// centred across its area, with its narrowest bars (modules) as wide as fit
+/// unless set. If it doesn't fit, nothing is drawn.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{Barcode, Oled, OledResult, Symbology};
+/// # fn main() -> OledResult {
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// let asset = Barcode::new("A1234", Symbology::Code128)?.with_quiet_zone(8);
+/// oled.draw_barcode(&asset, 16, 32)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Barcode {
+    /// Whether each module is a bar
+    bars: [bool; MAX_BARCODE_MODULES],
+    /// Number of modules
+    len: usize,
+    /// Width of each module in pixels, or 0 for as wide as fits
+    module_width: u16,
+    /// Width of each quiet zone, in modules
+    quiet_zone: u16,
+}
+
+impl Barcode {
+    /// Encodes `text` in `symbology`, with quiet zones of 10 modules
+    ///
+    /// Returns `InvalidArgument` if `text` has characters the symbology can't
+    /// encode, or is too long for a barcode that fits on the display.
+    pub fn new(text: &str, symbology: Symbology) -> Result<Self, OledError> {
+        let mut barcode = Barcode {
+            bars: [false; MAX_BARCODE_MODULES],
+            len: 0,
+            module_width: 0,
+            quiet_zone: 10,
+        };
+        match symbology {
+            Symbology::Code128 => barcode.encode_code128(text)?,
+            Symbology::Code39 => barcode.encode_code39(text)?,
+        }
+        Ok(barcode)
+    }
+
+    /// Makes each module `pixels` wide, or as wide as fits if 0
+    pub fn with_module_width(self, pixels: u16) -> Self {
+        Barcode {
+            module_width: pixels,
+            ..self
+        }
+    }
+
+    /// Leaves `modules` of space at either side of the bars
+    pub fn with_quiet_zone(self, modules: u16) -> Self {
+        Barcode {
+            quiet_zone: modules,
+            ..self
+        }
+    }
+
+    /// Modules across the barcode, not counting its quiet zones
+    pub fn modules(&self) -> u16 {
+        self.len as u16
+    }
+
+    /// Returns whether the module at `x` is a bar
+    pub fn get_module(&self, x: u16) -> bool {
+        self.bars[..self.len]
+            .get(usize::from(x))
+            .copied()
+            .unwrap_or(false)
+    }
+
+    /// Width of each module when drawn `width` pixels across, with the quiet
+    /// zones, or `None` if it doesn't fit
+    fn module_width_in(&self, width: u16) -> Option<u16> {
+        let modules = u32::from(self.modules()) + 2 * u32::from(self.quiet_zone);
+        let widest = u32::from(width) / modules.max(1);
+        match self.module_width {
+            0 if widest > 0 => Some(widest as u16),
+            0 => None,
+            module_width => Some(module_width).filter(|_| u32::from(module_width) <= widest),
+        }
+    }
+
+    /// Appends a bar or a space `modules` wide
+    fn push(&mut self, bar: bool, modules: u32) -> Result<(), OledError> {
+        for _ in 0..modules {
+            let module = self
+                .bars
+                .get_mut(self.len)
+                .ok_or(OledError::InvalidArgument(
+                    "too much text for a barcode that fits on the display",
+                ))?;
+            *module = bar;
+            self.len += 1;
+        }
+        Ok(())
+    }
+
+    /// Appends alternating bars and spaces, starting with a bar, of the widths
+    /// given by the decimal digits of `widths`
+    fn push_widths(&mut self, widths: u32) -> Result<(), OledError> {
+        let mut place = 1;
+        while place * 10 <= widths {
+            place *= 10;
+        }
+        let mut bar = true;
+        while place > 0 {
+            self.push(bar, widths / place % 10)?;
+            bar = !bar;
+            place /= 10;
+        }
+        Ok(())
+    }
+
+    /// Encodes `text` as Code 128, with its check symbol
+    fn encode_code128(&mut self, text: &str) -> Result<(), OledError> {
+        let digits = text.bytes().all(|byte| byte.is_ascii_digit());
+        let set_c = digits && !text.is_empty() && text.len().is_multiple_of(2);
+        let start = if set_c {
+            CODE128_START_C
+        } else {
+            CODE128_START_B
+        };
+        self.push_widths(CODE128[start as usize])?;
+        let mut checksum = start;
+        let mut push_symbol = |barcode: &mut Self, position: u32, value: u32| {
+            checksum += position * value;
+            barcode.push_widths(CODE128[value as usize])
+        };
+        if set_c {
+            for (position, pair) in text.as_bytes().chunks(2).enumerate() {
+                let value = u32::from(pair[0] - b'0') * 10 + u32::from(pair[1] - b'0');
+                push_symbol(self, position as u32 + 1, value)?;
+            }
+        } else {
+            for (position, byte) in text.bytes().enumerate() {
+                if !(b' '..=b'~').contains(&byte) {
+                    return Err(OledError::InvalidArgument(
+                        "Code 128 only encodes printable ASCII",
+                    ));
+                }
+                push_symbol(self, position as u32 + 1, u32::from(byte - b' '))?;
+            }
+        }
+        self.push_widths(CODE128[(checksum % 103) as usize])?;
+        self.push_widths(CODE128_STOP)
+    }
+
+    /// Encodes `text` as Code 39, between start and stop characters
+    fn encode_code39(&mut self, text: &str) -> Result<(), OledError> {
+        if text.contains('*') {
+            return Err(OledError::InvalidArgument(
+                "Code 39 uses * to start and stop, so can't encode it",
+            ));
+        }
+        let chars = core::iter::once('*').chain(text.chars()).chain(Some('*'));
+        for (index, char) in chars.enumerate() {
+            let mut wide = [false; 9];
+            if let Some((group, space)) = CODE39
+                .iter()
+                .find_map(|(group, space)| group.find(char).map(|i| (i, *space)))
+            {
+                let (first, second) = CODE39_BARS[group];
+                wide[first] = true;
+                wide[second] = true;
+                wide[space] = true;
+            } else if let Some((_, narrow)) = CODE39_SPACES.iter().find(|(c, _)| *c == char) {
+                for space in [1, 3, 5, 7].iter().filter(|space| *space != narrow) {
+                    wide[*space] = true;
+                }
+            } else {
+                return Err(OledError::InvalidArgument(
+                    "Code 39 only encodes digits, capital letters, space and -.$/+%",
+                ));
+            }
+            // a narrow space between characters
+            if index > 0 {
+                self.push(false, 1)?;
+            }
+            for (element, wide) in wide.iter().enumerate() {
+                self.push(element % 2 == 0, if *wide { 3 } else { 1 })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Widget for Barcode {
+    fn render(&mut self, frame: &mut Framebuffer, area: Rect) {
+        frame.fill_rect(area.x, area.y, area.width, area.height, false);
+        let Some(module_width) = self.module_width_in(area.width) else {
+            return;
+        };
+        let width = (self.modules() + 2 * self.quiet_zone) * module_width;
+        let left = area.x + (area.width - width) / 2;
+        frame.fill_rect(left, area.y, width, area.height, true);
+        let bars = left + self.quiet_zone * module_width;
+        for module in 0..self.modules() {
+            if self.get_module(module) {
+                let x = bars + module * module_width;
+                frame.fill_rect(x, area.y, module_width, area.height, false);
+            }
+        }
+    }
+}
+
+impl<I: OledInterface> Oled<I> {
+    /// Draws `barcode` across the display, `height` pixels from `y` down,
+    /// as it renders as a [`Widget`](trait.Widget.html), leaving the rest of
+    /// the display alone
+    ///
+    /// Returns `InvalidArgument` if the barcode doesn't fit across the display.
+    pub fn draw_barcode(&mut self, barcode: &Barcode, y: u16, height: u16) -> OledResult {
+        let (width, _) = self.dimensions();
+        if barcode.module_width_in(width).is_none() {
+            return Err(OledError::InvalidArgument(
+                "barcode doesn't fit across the display",
+            ));
+        }
+        let mut barcode = barcode.clone();
+        self.draw(|frame| barcode.render(frame, Rect::new(0, y, width, height)))
+    }
+}
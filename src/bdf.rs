@@ -0,0 +1,183 @@
+//! Loading fonts in the Glyph Bitmap Distribution Format (BDF), as used by X11
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+use crate::{BitmapFont, OledError};
+
+/// The error for a BDF font that can't be read
+fn invalid(reason: &'static str) -> OledError {
+    OledError::InvalidArgument(reason)
+}
+
+/// Parses whitespace-separated numbers, failing with `reason` if any are missing or invalid
+fn numbers<const N: usize>(
+    fields: &mut core::str::SplitWhitespace<'_>,
+    reason: &'static str,
+) -> Result<[i32; N], OledError> {
+    let mut values = [0; N];
+    for value in values.iter_mut() {
+        *value = fields
+            .next()
+            .and_then(|field| field.parse().ok())
+            .ok_or(invalid(reason))?;
+    }
+    Ok(values)
+}
+
+/// Converts a dimension read from the file, rejecting values that can't be right
+fn dimension(value: i32, reason: &'static str) -> Result<u16, OledError> {
+    u16::try_from(value).map_err(|_| invalid(reason))
+}
+
+/// Converts an offset read from the file, rejecting values that can't be right
+fn offset(value: i32, reason: &'static str) -> Result<i16, OledError> {
+    i16::try_from(value).map_err(|_| invalid(reason))
+}
+
+/// A glyph being read, between `STARTCHAR` and `ENDCHAR`
+#[derive(Default)]
+struct Pending {
+    /// Its Unicode code point, or `None` if it has none
+    char: Option<char>,
+    /// How far the pen moves on, from `DWIDTH`
+    advance: Option<u16>,
+    /// Width, height and offsets of the bitmap from the baseline, from `BBX`
+    bounds: Option<[i32; 4]>,
+    /// Rows of the bitmap read so far, or `None` before `BITMAP`
+    rows: Option<Vec<u8>>,
+}
+
+impl BitmapFont {
+    /// Loads a font from the contents of a BDF file, as used by X11 and many
+    /// bitmap font collections, eg for a 6x12 or 12x16 font.
+    ///
+    /// Glyphs are looked up by their `ENCODING`, taken as a Unicode code point,
+    /// which is right for `ISO10646-1` fonts and, for the characters they share,
+    /// `ISO8859-1` ones. Glyphs without an encoding are skipped. The font's
+    /// `DEFAULT_CHAR`, if given, is drawn in place of missing characters.
+    ///
+    /// Returns `InvalidArgument` if the file isn't a BDF font or can't be read.
+    /// # Example:
+    /// ```no_run
+    /// # use nanohat_oled::{BitmapFont, Oled};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let font = BitmapFont::from_bdf_bytes(&std::fs::read("6x12.bdf")?)?;
+    /// let mut oled = Oled::from_path("/dev/i2c-0")?;
+    /// oled.init()?;
+    /// oled.put_string_font(0, 0, "Hello, world!", &font)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_bdf_bytes(data: &[u8]) -> Result<Self, OledError> {
+        let mut lines = data
+            .split(|byte| *byte == b'\n')
+            // comments aren't always UTF-8, and nothing else is worth reading there
+            .map(|line| core::str::from_utf8(line).unwrap_or("").trim());
+        if !lines
+            .next()
+            .is_some_and(|line| line.starts_with("STARTFONT"))
+        {
+            return Err(invalid("not a BDF font"));
+        }
+        let mut bounding_box = None;
+        let mut ascent = None;
+        let mut descent = None;
+        let mut default_char = None;
+        let mut glyphs = Vec::new();
+        let mut pending: Option<Pending> = None;
+        for line in lines {
+            let mut fields = line.split_whitespace();
+            let Some(keyword) = fields.next() else {
+                continue;
+            };
+            if let Some(glyph) = pending.as_mut() {
+                if let Some(rows) = glyph.rows.as_mut() {
+                    if keyword == "ENDCHAR" {
+                        glyphs.push(pending.take().unwrap_or_default());
+                        continue;
+                    }
+                    let [width, ..] = glyph.bounds.unwrap_or_default();
+                    let stride = (width.max(0) as usize).div_ceil(8);
+                    let hex = keyword.as_bytes();
+                    for pair in 0..stride {
+                        let digits = hex
+                            .get(pair * 2..pair * 2 + 2)
+                            .and_then(|digits| core::str::from_utf8(digits).ok())
+                            .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+                            .ok_or(invalid("BDF bitmap row is invalid"))?;
+                        rows.push(digits);
+                    }
+                    continue;
+                }
+                match keyword {
+                    "ENCODING" => {
+                        let [code] = numbers(&mut fields, "BDF ENCODING is invalid")?;
+                        glyph.char = u32::try_from(code).ok().and_then(char::from_u32);
+                    }
+                    "DWIDTH" => {
+                        let [advance] = numbers(&mut fields, "BDF DWIDTH is invalid")?;
+                        glyph.advance = Some(dimension(advance, "BDF DWIDTH is invalid")?);
+                    }
+                    "BBX" => glyph.bounds = Some(numbers(&mut fields, "BDF BBX is invalid")?),
+                    "BITMAP" => glyph.rows = Some(Vec::new()),
+                    _ => {}
+                }
+                continue;
+            }
+            match keyword {
+                "FONTBOUNDINGBOX" => {
+                    bounding_box =
+                        Some(numbers::<4>(&mut fields, "BDF FONTBOUNDINGBOX is invalid")?)
+                }
+                "FONT_ASCENT" => {
+                    ascent = Some(numbers::<1>(&mut fields, "BDF FONT_ASCENT is invalid")?[0])
+                }
+                "FONT_DESCENT" => {
+                    descent = Some(numbers::<1>(&mut fields, "BDF FONT_DESCENT is invalid")?[0])
+                }
+                "DEFAULT_CHAR" => {
+                    let [code] = numbers(&mut fields, "BDF DEFAULT_CHAR is invalid")?;
+                    default_char = u32::try_from(code).ok().and_then(char::from_u32);
+                }
+                "STARTCHAR" => pending = Some(Pending::default()),
+                "ENDFONT" => break,
+                _ => {}
+            }
+        }
+        // the lines are spaced by the ascent and descent, or failing those, the bounding box
+        let [_, box_height, _, box_bottom] = bounding_box.unwrap_or_default();
+        let ascent = ascent.unwrap_or(box_height + box_bottom);
+        let descent = descent.unwrap_or(-box_bottom);
+        let line_height = dimension(ascent + descent, "BDF font has no height")?;
+        if line_height == 0 {
+            return Err(invalid("BDF font has no height"));
+        }
+        let mut font = BitmapFont::new(line_height);
+        for glyph in glyphs {
+            let Some(char) = glyph.char else {
+                continue;
+            };
+            let [width, height, left, bottom] = glyph
+                .bounds
+                .or(bounding_box)
+                .ok_or(invalid("BDF glyph has no BBX"))?;
+            let size = (
+                dimension(width, "BDF BBX is invalid")?,
+                dimension(height, "BDF BBX is invalid")?,
+            );
+            let rows = glyph.rows.unwrap_or_default();
+            if rows.len() != (size.0 as usize).div_ceil(8) * size.1 as usize {
+                return Err(invalid("BDF bitmap doesn't match its BBX"));
+            }
+            // BDF measures up from the baseline, but glyphs are placed down from the top of the line
+            let top = offset(ascent - bottom - height, "BDF BBX is invalid")?;
+            let left = offset(left, "BDF BBX is invalid")?;
+            let advance = glyph.advance.unwrap_or(size.0);
+            font.insert(char, size, (left, top), advance, &rows);
+        }
+        if let Some(char) = default_char {
+            font.set_default_char(char);
+        }
+        Ok(font)
+    }
+}
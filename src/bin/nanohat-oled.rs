@@ -0,0 +1,226 @@
+//! Drives the NanoHat OLED from the command line, eg from shell scripts or systemd units
+use nanohat_oled::{
+    is_png, Brightness, Command, Daemon, Framebuffer, Oled, OledError, OLED_ADDRESS,
+};
+use std::fs::File;
+use std::io::{self, Read};
+use std::process::exit;
+use std::time::Duration;
+
+/// I2C device used unless `--device` is given
+const DEFAULT_DEVICE: &str = "/dev/i2c-0";
+
+const USAGE: &str = "\
+Usage: nanohat-oled [--device PATH] [--address ADDR] COMMAND
+
+Commands:
+  init                        set up the display after power-on, and clear it
+  text MESSAGE [--x N] [--y N]
+                              write text starting at text column x, row y (default 0, 0);
+                              \\n in MESSAGE starts a new row
+  image FILE                  show a PNG or binary PBM image, from the top left corner;
+                              PNG pixels lighter than mid-gray are lit, and PBM pixels
+                              where they're white
+  clear                       clear the display
+  on                          turn the display on
+  off                         turn the display off
+  sleep                       turn the display and its charge pump off, to save power
+  wake                        turn the charge pump and the display back on
+  contrast N                  set the contrast, from 0 to 255
+  brightness LEVEL            set the contrast, pre-charge and VCOMH for a brightness:
+                              dim, normal or bright
+  invert [off]                show black on white, or go back to white on black
+  daemon [--socket PATH | --fifo PATH]
+                              carry out requests a line at a time, read from a Unix
+                              socket, a named pipe made with mkfifo, or standard input:
+                              TEXT X Y MESSAGE, CLEAR, IMG BASE64 or CONTRAST N
+  self-test                   show each test pattern, then invert the display and light
+                              every pixel, for a second each, and report how the bus held up
+
+Options:
+  --device PATH               I2C device the display is on (default /dev/i2c-0), or auto
+                              to look for it at 0x3c and 0x3d on every /dev/i2c-* bus
+  --address ADDR              I2C address of the display, eg 0x3d (default 0x3c)";
+
+/// Something that went wrong, with the message to show for it
+enum Failure {
+    /// The command line couldn't be understood
+    Usage(String),
+    /// The command was understood, but couldn't be carried out
+    Failed(String),
+}
+
+impl From<OledError> for Failure {
+    fn from(err: OledError) -> Self {
+        Failure::Failed(err.to_string())
+    }
+}
+
+/// Parses a numeric argument, naming it in the error if it isn't valid
+fn number<T: std::str::FromStr>(name: &str, value: Option<String>) -> Result<T, Failure> {
+    let value = value.ok_or_else(|| Failure::Usage(format!("{} needs a value", name)))?;
+    value
+        .parse()
+        .map_err(|_| Failure::Usage(format!("invalid value for {}: {}", name, value)))
+}
+
+/// Parses an I2C address, in hex with a `0x` prefix, or in decimal
+fn address(value: Option<String>) -> Result<u16, Failure> {
+    let value = value.ok_or_else(|| Failure::Usage("--address needs a value".into()))?;
+    let parsed = match value.strip_prefix("0x") {
+        Some(hex) => u16::from_str_radix(hex, 16),
+        None => value.parse(),
+    };
+    parsed
+        .ok()
+        .filter(|address| *address <= 0x7f)
+        .ok_or_else(|| Failure::Usage(format!("invalid value for --address: {}", value)))
+}
+
+/// Loads a PNG or binary PBM image file into a frame, in its top left corner
+fn load_image(path: &str) -> Result<Framebuffer, Failure> {
+    let mut data = Vec::new();
+    File::open(path)
+        .and_then(|mut file| file.read_to_end(&mut data))
+        .map_err(|err| Failure::Failed(format!("couldn't read {}: {}", path, err)))?;
+    let decoded = if is_png(&data) {
+        let mut frame = Framebuffer::new();
+        frame.draw_png(&data, 0, 0).map(|_| frame)
+    } else {
+        Framebuffer::try_from_pbm(&data)
+    };
+    decoded.map_err(|err| Failure::Failed(format!("{}: {}", path, err)))
+}
+
+/// Parses the command line and carries out the command
+fn run(mut args: impl Iterator<Item = String>) -> Result<(), Failure> {
+    let mut device = DEFAULT_DEVICE.to_string();
+    let mut i2c_address = OLED_ADDRESS;
+    let command = loop {
+        match args.next() {
+            Some(arg) if arg == "--device" => {
+                device = args
+                    .next()
+                    .ok_or_else(|| Failure::Usage("--device needs a value".into()))?;
+            }
+            Some(arg) if arg == "--address" => i2c_address = address(args.next())?,
+            Some(arg) if arg == "--help" || arg == "-h" => {
+                println!("{}", USAGE);
+                return Ok(());
+            }
+            Some(arg) => break arg,
+            None => return Err(Failure::Usage("no command given".into())),
+        }
+    };
+    let open = || {
+        if device == "auto" {
+            return Oled::probe().map_err(Failure::from);
+        }
+        Oled::from_path_with_address(&device, i2c_address)
+            .map_err(|err| Failure::Failed(format!("{}: {}", device, err)))
+    };
+    match command.as_str() {
+        "init" => open()?.init()?,
+        "text" => {
+            let message = args
+                .next()
+                .ok_or_else(|| Failure::Usage("text needs a message".into()))?;
+            let (mut x, mut y) = (0u8, 0u8);
+            while let Some(arg) = args.next() {
+                match arg.as_str() {
+                    "--x" => x = number("--x", args.next())?,
+                    "--y" => y = number("--y", args.next())?,
+                    _ => return Err(Failure::Usage(format!("unexpected argument: {}", arg))),
+                }
+            }
+            let message = message.replace("\\n", "\n");
+            let mut oled = open()?;
+            let lines: Vec<&str> = message.split('\n').collect();
+            for (row, line) in lines.iter().enumerate() {
+                oled.set_text_xy(x, y.saturating_add(row as u8))?;
+                oled.put_string(line)?;
+            }
+        }
+        "image" => {
+            let path = args
+                .next()
+                .ok_or_else(|| Failure::Usage("image needs a file".into()))?;
+            if let Some(arg) = args.next() {
+                return Err(Failure::Usage(format!("unexpected argument: {}", arg)));
+            }
+            let frame = load_image(&path)?;
+            open()?.flush_all(&frame)?;
+        }
+        "clear" => open()?.clear_display()?,
+        "on" => open()?.send_command(Command::DisplayOn)?,
+        "off" => open()?.send_command(Command::DisplayOff)?,
+        "sleep" => open()?.sleep()?,
+        "wake" => open()?.wake()?,
+        "contrast" => {
+            let level: u8 = number("contrast", args.next())?;
+            open()?.set_contrast(level)?;
+        }
+        "brightness" => {
+            let brightness = match args.next().as_deref() {
+                Some("dim") => Brightness::Dim,
+                Some("normal") => Brightness::Normal,
+                Some("bright") => Brightness::Bright,
+                Some(arg) => return Err(Failure::Usage(format!("unknown brightness: {}", arg))),
+                None => return Err(Failure::Usage("brightness needs a level".into())),
+            };
+            open()?.set_brightness(brightness)?;
+        }
+        "invert" => match args.next().as_deref() {
+            None => open()?.send_command(Command::InverseDisplay)?,
+            Some("off") => open()?.send_command(Command::NormalDisplay)?,
+            Some(arg) => return Err(Failure::Usage(format!("unexpected argument: {}", arg))),
+        },
+        "daemon" => {
+            let source = match args.next() {
+                Some(flag) if flag == "--socket" || flag == "--fifo" => {
+                    let path = args
+                        .next()
+                        .ok_or_else(|| Failure::Usage(format!("{} needs a path", flag)))?;
+                    Some((flag, path))
+                }
+                Some(arg) => return Err(Failure::Usage(format!("unexpected argument: {}", arg))),
+                None => None,
+            };
+            if let Some(arg) = args.next() {
+                return Err(Failure::Usage(format!("unexpected argument: {}", arg)));
+            }
+            let mut daemon = Daemon::new(open()?);
+            match source {
+                Some((flag, path)) if flag == "--socket" => daemon.serve_socket(path)?,
+                Some((_, path)) => daemon.serve_fifo(path)?,
+                None => daemon.serve(io::stdin().lock())?,
+            }
+        }
+        "self-test" => {
+            let report = open()?.self_test(Duration::from_secs(1));
+            println!(
+                "{} of {} steps failed; slowest frame took {:?}",
+                report.failures, report.steps, report.frame_time
+            );
+            if let Some(err) = report.last_error {
+                return Err(err.into());
+            }
+        }
+        _ => return Err(Failure::Usage(format!("unknown command: {}", command))),
+    }
+    Ok(())
+}
+
+fn main() {
+    match run(std::env::args().skip(1)) {
+        Ok(()) => {}
+        Err(Failure::Usage(message)) => {
+            eprintln!("nanohat-oled: {}\n\n{}", message, USAGE);
+            exit(2);
+        }
+        Err(Failure::Failed(message)) => {
+            eprintln!("nanohat-oled: {}", message);
+            exit(1);
+        }
+    }
+}
@@ -0,0 +1,77 @@
+//! Grayscale images whose dimensions are checked at compile time
+use crate::{Oled, OledInterface, OledResult, OLED_HEIGHT, OLED_WIDTH};
+
+/// An 8-bit grayscale image, `W` pixels wide and `H` pixels high, stored row by row.
+///
+/// Unlike an [`Image`](type.Image.html), the dimensions are part of the type, so
+/// a bitmap of the wrong size for the screen is a compile error rather than an
+/// [`InvalidDimensions`](enum.OledError.html#variant.InvalidDimensions) at runtime.
+/// Bitmaps can be built in a `const` context, eg for splash screens:
+/// ```
+/// use nanohat_oled::Bitmap;
+///
+/// const SPLASH: Bitmap<128, 64> = Bitmap::from_bytes(&[0xff; 128 * 64]);
+/// ```
+/// Data of the wrong length fails to compile:
+/// ```compile_fail
+/// use nanohat_oled::Bitmap;
+///
+/// const SPLASH: Bitmap<128, 64> = Bitmap::from_bytes(&[0xff; 100]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Bitmap<const W: usize, const H: usize>(pub [[u8; W]; H]);
+
+impl<const W: usize, const H: usize> Bitmap<W, H> {
+    /// Creates a bitmap from its rows of pixels
+    pub const fn new(rows: [[u8; W]; H]) -> Self {
+        Bitmap(rows)
+    }
+
+    /// Creates a bitmap from pixel data laid out row by row, as in an
+    /// [`Image`](type.Image.html).
+    ///
+    /// # Panics
+    /// Panics if `data` isn't exactly `W * H` bytes long. When used to initialize a
+    /// `const` or `static`, that becomes a compile error instead.
+    pub const fn from_bytes(data: &[u8]) -> Self {
+        assert!(
+            data.len() == W * H,
+            "bitmap data doesn't match its dimensions"
+        );
+        let mut rows = [[0u8; W]; H];
+        let mut y = 0;
+        while y < H {
+            let mut x = 0;
+            while x < W {
+                rows[y][x] = data[y * W + x];
+                x += 1;
+            }
+            y += 1;
+        }
+        Bitmap(rows)
+    }
+
+    /// The pixel data, row by row
+    pub fn as_bytes(&self) -> &[u8] {
+        self.0.as_flattened()
+    }
+}
+
+impl<I: OledInterface> Oled<I> {
+    /// Writes a full-screen bitmap to the display. Behaves like
+    /// [`draw_image()`](struct.Oled.html#method.draw_image), but since the bitmap's
+    /// size is checked by the compiler, it can only fail on the bus:
+    /// ```compile_fail
+    /// # use nanohat_oled::{Bitmap, Oled};
+    /// # let mut oled = Oled::from_path("/dev/i2c-0").unwrap();
+    /// let icon: Bitmap<32, 32> = Bitmap::new([[0xff; 32]; 32]);
+    /// oled.draw_bitmap(&icon, 0x80);
+    /// ```
+    pub fn draw_bitmap(
+        &mut self,
+        bitmap: &Bitmap<{ OLED_WIDTH as usize }, { OLED_HEIGHT as usize }>,
+        threshold: u8,
+    ) -> OledResult {
+        self.draw_image(bitmap.as_bytes(), threshold)
+    }
+}
@@ -0,0 +1,119 @@
+//! Fonts loaded at runtime, whose glyphs are kept in memory
+use alloc::vec::Vec;
+
+use crate::{Font, Glyph, Sprite};
+
+/// Where a glyph of a [`BitmapFont`](struct.BitmapFont.html) is, and how it sits on the line
+#[derive(Clone, Copy, Debug)]
+struct Entry {
+    /// Character the glyph is for
+    char: char,
+    /// Width of the bitmap in pixels
+    width: u16,
+    /// Height of the bitmap in pixels
+    height: u16,
+    /// Offset of the bitmap from the pen and the top of the line
+    offset: (i16, i16),
+    /// How far the pen moves on after the glyph
+    advance: u16,
+    /// Offset of the bitmap's rows in the font's bitmap data
+    start: usize,
+}
+
+/// A [`Font`](trait.Font.html) loaded from a font file at runtime, eg with
+/// [`from_bdf_bytes()`](#method.from_bdf_bytes) or
+/// [`from_psf_bytes()`](#method.from_psf_bytes), so text isn't limited to the
+/// built-in 8x8 font.
+#[derive(Clone, Debug)]
+pub struct BitmapFont {
+    /// Distance from one line to the next, in pixels
+    line_height: u16,
+    /// Glyphs, sorted by character
+    entries: Vec<Entry>,
+    /// Rows of every glyph's bitmap, one after another
+    bitmaps: Vec<u8>,
+    /// Character drawn in place of ones the font doesn't have, if it names one
+    default_char: Option<char>,
+}
+
+impl BitmapFont {
+    /// Creates an empty font, with lines `line_height` pixels apart
+    pub(crate) fn new(line_height: u16) -> Self {
+        BitmapFont {
+            line_height,
+            entries: Vec::new(),
+            bitmaps: Vec::new(),
+            default_char: None,
+        }
+    }
+
+    /// Adds a glyph, given its rows of bits as in a [`Sprite`](struct.Sprite.html),
+    /// replacing any glyph already added for the same character
+    pub(crate) fn insert(
+        &mut self,
+        char: char,
+        (width, height): (u16, u16),
+        offset: (i16, i16),
+        advance: u16,
+        rows: &[u8],
+    ) {
+        let entry = Entry {
+            char,
+            width,
+            height,
+            offset,
+            advance,
+            start: self.bitmaps.len(),
+        };
+        self.bitmaps.extend_from_slice(rows);
+        match self.entries.binary_search_by_key(&char, |entry| entry.char) {
+            Ok(index) => self.entries[index] = entry,
+            Err(index) => self.entries.insert(index, entry),
+        }
+    }
+
+    /// Sets the character drawn in place of ones the font doesn't have
+    pub(crate) fn set_default_char(&mut self, char: char) {
+        self.default_char = Some(char);
+    }
+
+    /// Number of glyphs in the font
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the font has no glyphs at all
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The characters the font has glyphs for, in order
+    pub fn chars(&self) -> impl Iterator<Item = char> + '_ {
+        self.entries.iter().map(|entry| entry.char)
+    }
+}
+
+impl Font for BitmapFont {
+    fn line_height(&self) -> u16 {
+        self.line_height
+    }
+
+    fn glyph(&self, char: char) -> Option<Glyph<'_>> {
+        let index = self
+            .entries
+            .binary_search_by_key(&char, |entry| entry.char)
+            .ok()?;
+        let entry = &self.entries[index];
+        let len = (entry.width as usize).div_ceil(8) * entry.height as usize;
+        let rows = &self.bitmaps[entry.start..entry.start + len];
+        let sprite = Sprite::new(entry.width, entry.height, rows);
+        Some(Glyph::new(sprite, entry.advance).with_offset(entry.offset.0, entry.offset.1))
+    }
+
+    /// The font's own default character, if it names one, otherwise its `?`
+    fn replacement(&self) -> Option<Glyph<'_>> {
+        self.default_char
+            .and_then(|char| self.glyph(char))
+            .or_else(|| self.glyph('?'))
+    }
+}
@@ -0,0 +1,212 @@
+//! Text and regions that flash, and a blinking cursor
+use core::time::Duration;
+
+use crate::{Framebuffer, Oled, OledError, OledInterface, OledResult, Rect};
+
+/// How long blinking things stay on, and then off, by default
+pub(crate) const BLINK_PERIOD: Duration = Duration::from_millis(500);
+
+/// The shape of the cursor shown by
+/// [`Oled::show_cursor()`](struct.Oled.html#method.show_cursor)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CursorStyle {
+    /// A line along the bottom of the text cell
+    Underline,
+    /// The whole text cell, inverting the character in it
+    Block,
+    /// A line down the left of the text cell
+    Bar,
+}
+
+impl CursorStyle {
+    /// The pixels the cursor covers in the text cell at `column`, `row`
+    fn rect(self, column: u8, row: u8) -> Rect {
+        let (x, y) = (u16::from(column) * 8, u16::from(row) * 8);
+        match self {
+            CursorStyle::Underline => Rect::new(x, y + 7, 8, 1),
+            CursorStyle::Block => Rect::new(x, y, 8, 8),
+            CursorStyle::Bar => Rect::new(x, y, 1, 8),
+        }
+    }
+}
+
+/// Inverts the pixels of `frame` inside `rect`
+fn invert(frame: &mut Framebuffer, rect: Rect) {
+    let (width, height) = frame.dimensions();
+    let right = rect.x.saturating_add(rect.width).min(width);
+    let bottom = rect.y.saturating_add(rect.height).min(height);
+    for y in rect.y..bottom {
+        for x in rect.x..right {
+            frame.set_pixel(x, y, !frame.get_pixel(x, y));
+        }
+    }
+}
+
+/// A region of the display that flashes, eg an alert or a value being edited,
+/// without the application drawing it again each time.
+///
+/// The region is flashed by inverting its pixels, so whatever is drawn in it
+/// shows through, inverted, every other period, and only the region is sent.
+/// As it inverts what's there, anything drawn in the region should be drawn
+/// while it isn't [inverted](#method.is_inverted), eg after
+/// [`stop()`](#method.stop).
+///
+/// Like an [`Animation`](struct.Animation.html), the blink doesn't read a clock
+/// itself: [`tick()`](#method.tick) is told how much time has passed.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{Blink, Oled, OledResult};
+/// # use std::time::Duration;
+/// # fn main() -> OledResult {
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// oled.set_text_xy(0, 4)?;
+/// oled.put_string("LOW BATTERY")?;
+/// let mut alert = Blink::text(0, 4, 11);
+/// loop {
+///     let wait = alert.tick(&mut oled, Duration::from_millis(100))?;
+///     std::thread::sleep(wait.min(Duration::from_millis(100)));
+/// }
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Blink {
+    /// The pixels that flash
+    rect: Rect,
+    /// How long each on and off lasts
+    period: Duration,
+    /// Time since the last change
+    elapsed: Duration,
+    /// Whether the region is currently inverted
+    inverted: bool,
+}
+
+impl Blink {
+    /// Flashes the pixels in `rect`, every half second. The region starts as
+    /// drawn, and is first inverted once a period has passed.
+    pub fn new(rect: Rect) -> Self {
+        Blink {
+            rect,
+            period: BLINK_PERIOD,
+            elapsed: Duration::ZERO,
+            inverted: false,
+        }
+    }
+
+    /// Flashes `len` text cells in the built-in font, starting at `column`, `row`,
+    /// as for [`Oled::set_text_xy()`](struct.Oled.html#method.set_text_xy)
+    pub fn text(column: u8, row: u8, len: u8) -> Self {
+        let (x, y) = (u16::from(column) * 8, u16::from(row) * 8);
+        Self::new(Rect::new(x, y, u16::from(len) * 8, 8))
+    }
+
+    /// Changes how long the region stays inverted, and then as drawn. With a
+    /// period of zero, it never changes.
+    pub fn with_period(self, period: Duration) -> Self {
+        Blink { period, ..self }
+    }
+
+    /// The pixels that flash
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    /// Whether the region is currently shown inverted
+    pub fn is_inverted(&self) -> bool {
+        self.inverted
+    }
+
+    /// Inverts the region on the display, changing whether it's shown inverted
+    fn toggle<I: OledInterface>(&mut self, oled: &mut Oled<I>) -> OledResult {
+        let rect = self.rect;
+        oled.draw(|frame| invert(frame, rect))?;
+        self.inverted = !self.inverted;
+        Ok(())
+    }
+
+    /// Moves the blinking on by `elapsed`, the time since the last tick,
+    /// inverting the region if a period has run out. Returns how long until it
+    /// next changes.
+    pub fn tick<I: OledInterface>(
+        &mut self,
+        oled: &mut Oled<I>,
+        elapsed: Duration,
+    ) -> Result<Duration, OledError> {
+        self.elapsed = self.elapsed.saturating_add(elapsed);
+        if self.period.is_zero() {
+            return Ok(Duration::MAX);
+        }
+        if self.elapsed < self.period {
+            return Ok(self.period - self.elapsed);
+        }
+        let periods = self.elapsed.as_nanos() / self.period.as_nanos();
+        // a whole number of ons and offs leaves it as it was
+        if !periods.is_multiple_of(2) {
+            self.toggle(oled)?;
+        }
+        self.elapsed =
+            Duration::from_nanos((self.elapsed.as_nanos() % self.period.as_nanos()) as u64);
+        Ok(self.period - self.elapsed)
+    }
+
+    /// Stops flashing, putting the region back as drawn if it's inverted. The
+    /// next tick starts again from there.
+    pub fn stop<I: OledInterface>(&mut self, oled: &mut Oled<I>) -> OledResult {
+        if self.inverted {
+            self.toggle(oled)?;
+        }
+        self.elapsed = Duration::ZERO;
+        Ok(())
+    }
+}
+
+impl<I: OledInterface> Oled<I> {
+    /// Shows a blinking cursor in the text cell at `column`, `row`, as for
+    /// [`set_text_xy()`](struct.Oled.html#method.set_text_xy), eg to show where
+    /// input goes in a prompt. Any cursor already shown is taken away first.
+    /// The text cursor, where the next character goes, isn't moved.
+    ///
+    /// The cursor is drawn by inverting the pixels it covers, shown straight
+    /// away and blinking as time passing is counted by
+    /// [`tick_cursor()`](struct.Oled.html#method.tick_cursor), as an
+    /// [`App`](struct.App.html) does. Anything drawn under it should be drawn
+    /// after [`hide_cursor()`](struct.Oled.html#method.hide_cursor), and the
+    /// cursor shown again after.
+    ///
+    /// Returns `OutOfBounds` if the cell is off the display.
+    pub fn show_cursor(&mut self, column: u8, row: u8, style: CursorStyle) -> OledResult {
+        let (width, height) = self.dimensions();
+        if u16::from(column) >= width / 8 || u16::from(row) >= height / 8 {
+            return Err(OledError::OutOfBounds);
+        }
+        self.hide_cursor()?;
+        let mut cursor = Blink::new(style.rect(column, row));
+        cursor.toggle(self)?;
+        self.cursor = Some(cursor);
+        Ok(())
+    }
+
+    /// Takes away the cursor shown by
+    /// [`show_cursor()`](struct.Oled.html#method.show_cursor), if any, putting
+    /// back what's under it
+    pub fn hide_cursor(&mut self) -> OledResult {
+        if let Some(mut cursor) = self.cursor.take() {
+            if let Err(err) = cursor.stop(self) {
+                self.cursor = Some(cursor);
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    /// Counts `elapsed` towards the blinking of the cursor shown by
+    /// [`show_cursor()`](struct.Oled.html#method.show_cursor), if any
+    pub fn tick_cursor(&mut self, elapsed: Duration) -> OledResult {
+        let Some(mut cursor) = self.cursor else {
+            return Ok(());
+        };
+        let ticked = cursor.tick(self, elapsed);
+        self.cursor = Some(cursor);
+        ticked.map(|_| ())
+    }
+}
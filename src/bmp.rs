@@ -0,0 +1,157 @@
+//! Drawing uncompressed BMP files, without an image decoding library
+use crate::{Framebuffer, Oled, OledError, OledInterface, OledResult};
+
+/// Gray level from which a pixel of a BMP image is lit
+const BMP_THRESHOLD: u8 = 0x80;
+
+/// A BMP image's pixels, as found in the file
+struct Bmp<'a> {
+    /// Width in pixels
+    width: usize,
+    /// Height in pixels
+    height: usize,
+    /// Whether the first row in the file is the top, rather than the bottom
+    top_down: bool,
+    /// Bits per pixel, 1 or 8
+    bits: u16,
+    /// Color table, four bytes per color: blue, green, red and an unused byte
+    palette: &'a [u8],
+    /// Pixel data, row by row
+    pixels: &'a [u8],
+    /// Bytes per row, padded to a multiple of four
+    stride: usize,
+}
+
+impl<'a> Bmp<'a> {
+    /// Finds the pixels in a BMP file, checking it's a kind this can draw
+    fn parse(data: &'a [u8]) -> Result<Self, OledError> {
+        let u16_at = |at: usize| {
+            data.get(at..at + 2)
+                .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        };
+        let u32_at = |at: usize| {
+            data.get(at..at + 4)
+                .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        };
+        if !data.starts_with(b"BM") {
+            return Err(OledError::InvalidArgument("not a BMP image"));
+        }
+        let corrupt = OledError::InvalidArgument("BMP image is corrupt");
+        let (Some(offset), Some(header), Some(width), Some(height)) =
+            (u32_at(10), u32_at(14), u32_at(18), u32_at(22))
+        else {
+            return Err(corrupt);
+        };
+        let (Some(bits), Some(compression), Some(colors)) = (u16_at(28), u32_at(30), u32_at(46))
+        else {
+            return Err(corrupt);
+        };
+        // older OS/2 headers are too small to hold the fields read above
+        if header < 40 || compression != 0 || !(bits == 1 || bits == 8) {
+            return Err(OledError::InvalidArgument(
+                "only uncompressed 1- and 8-bit BMP images are supported",
+            ));
+        }
+        let (width, height) = (width as i32, height as i32);
+        if width <= 0 || height == 0 {
+            return Err(OledError::InvalidDimensions);
+        }
+        let colors = match colors {
+            0 => 1 << bits,
+            colors => colors.min(1 << bits) as usize,
+        };
+        let palette_start = 14 + header as usize;
+        let palette = data
+            .get(palette_start..palette_start + colors * 4)
+            .ok_or(corrupt)?;
+        let top_down = height < 0;
+        let (width, height) = (width as usize, height.unsigned_abs() as usize);
+        let stride = (width * usize::from(bits)).div_ceil(32) * 4;
+        let pixels = data
+            .get(offset as usize..)
+            .and_then(|pixels| pixels.get(..stride * height))
+            .ok_or(OledError::InvalidArgument("BMP image is cut short"))?;
+        Ok(Bmp {
+            width,
+            height,
+            top_down,
+            bits,
+            palette,
+            pixels,
+            stride,
+        })
+    }
+
+    /// The gray level of the pixel at `x`, `y`, counting from the top left
+    fn gray(&self, x: usize, y: usize) -> u8 {
+        let row = if self.top_down {
+            y
+        } else {
+            self.height - 1 - y
+        };
+        let row = &self.pixels[row * self.stride..];
+        let index = match self.bits {
+            1 => (row[x / 8] >> (7 - x % 8)) & 1,
+            _ => row[x],
+        };
+        match self
+            .palette
+            .get(usize::from(index) * 4..usize::from(index) * 4 + 3)
+        {
+            Some(&[blue, green, red]) => {
+                let luma = 77 * u32::from(red) + 150 * u32::from(green) + 29 * u32::from(blue);
+                (luma >> 8) as u8
+            }
+            _ => 0,
+        }
+    }
+
+    /// Draws the image into `frame` with its top left corner at `x`, `y`
+    fn draw(&self, frame: &mut Framebuffer, x: u16, y: u16) {
+        let (frame_width, frame_height) = frame.dimensions();
+        let columns = self.width.min(usize::from(frame_width.saturating_sub(x)));
+        let rows = self.height.min(usize::from(frame_height.saturating_sub(y)));
+        for row in 0..rows {
+            for column in 0..columns {
+                let lit = self.gray(column, row) >= BMP_THRESHOLD;
+                frame.set_pixel(x + column as u16, y + row as u16, lit);
+            }
+        }
+    }
+}
+
+impl Framebuffer {
+    /// Draws a BMP image with its top left corner at `x`, `y`, lit where it's
+    /// lighter than mid-gray and clipped at the edges. Only the simplest BMP
+    /// files are read: uncompressed, with 1 or 8 bits per pixel and a color
+    /// table, as most editors save monochrome and grayscale images, so no image
+    /// decoding library is needed.
+    ///
+    /// Returns `InvalidArgument` if the data isn't such a BMP file, in which
+    /// case nothing is drawn.
+    pub fn draw_bmp(&mut self, bmp: &[u8], x: u16, y: u16) -> Result<(), OledError> {
+        Bmp::parse(bmp)?.draw(self, x, y);
+        Ok(())
+    }
+}
+
+impl<I: OledInterface> Oled<I> {
+    /// Draws a BMP image onto the display with its top left corner at `x`, `y`,
+    /// as for [`Framebuffer::draw_bmp()`](struct.Framebuffer.html#method.draw_bmp).
+    /// Only what changes is sent.
+    /// # Example:
+    /// ```no_run
+    /// # use nanohat_oled::{Oled, OledResult};
+    /// # fn main() -> OledResult {
+    /// let mut oled = Oled::from_path("/dev/i2c-0")?;
+    /// oled.init()?;
+    /// let logo = std::fs::read("logo.bmp").expect("logo.bmp");
+    /// oled.draw_bmp(&logo, 32, 0)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn draw_bmp(&mut self, bmp: &[u8], x: u16, y: u16) -> OledResult {
+        let bmp = Bmp::parse(bmp)?;
+        self.draw(|frame| bmp.draw(frame, x, y))
+    }
+}
@@ -0,0 +1,229 @@
+//! The NanoHat's three buttons, read from their GPIOs through sysfs
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::thread::sleep;
+use std::time::Duration;
+
+use crate::OledError;
+
+/// GPIO numbers of the NanoHat's K1, K2 and K3 buttons
+pub const NANOHAT_BUTTON_GPIOS: [u32; 3] = [0, 2, 3];
+
+/// Where sysfs puts GPIOs
+const GPIO_ROOT: &str = "/sys/class/gpio";
+
+/// One of the NanoHat's buttons, from left to right
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Key {
+    /// The left button
+    K1,
+    /// The middle button
+    K2,
+    /// The right button
+    K3,
+}
+
+impl Key {
+    /// All the buttons, from left to right
+    pub const ALL: [Key; 3] = [Key::K1, Key::K2, Key::K3];
+}
+
+/// A change in a button's state
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ButtonEvent {
+    /// The button was pressed
+    Press(Key),
+    /// The button was let go
+    Release(Key),
+}
+
+impl ButtonEvent {
+    /// The button that changed
+    pub fn key(&self) -> Key {
+        match self {
+            ButtonEvent::Press(key) | ButtonEvent::Release(key) => *key,
+        }
+    }
+}
+
+/// Something that presses and lets go of the three keys, such as the NanoHat's
+/// own [`Buttons`](struct.Buttons.html), or a USB keypad or rotary encoder read
+/// through [`EvdevInput`](struct.EvdevInput.html), so that either can drive an
+/// [`App`](struct.App.html) or a [`GestureDetector`](struct.GestureDetector.html)
+pub trait Input {
+    /// Returns a press or release for each key whose state has changed since
+    /// it was last polled, without waiting for one
+    fn poll_events(&mut self) -> Result<Vec<ButtonEvent>, OledError>;
+}
+
+/// The NanoHat's buttons, polled through the `value` files of their GPIOs in
+/// sysfs for presses and releases. By default a GPIO reading 1 is taken to be
+/// a pressed button.
+///
+/// Buttons already held when they're opened aren't reported as pressed until
+/// they're let go and pressed again.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{ButtonEvent, Buttons, Key, OledError};
+/// # use std::time::Duration;
+/// # fn main() -> Result<(), OledError> {
+/// let mut buttons = Buttons::new()?;
+/// for event in buttons.events(Duration::from_millis(20)) {
+///     match event? {
+///         ButtonEvent::Press(Key::K3) => break,
+///         ButtonEvent::Press(key) => println!("{:?} pressed", key),
+///         ButtonEvent::Release(_) => {}
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Buttons {
+    /// Each button's `value` file
+    values: [File; 3],
+    /// Each button's level when last polled
+    levels: [bool; 3],
+    /// Whether a button reads 0 while pressed
+    active_low: bool,
+}
+
+impl Buttons {
+    /// Opens the NanoHat's buttons, on GPIOs 0, 2 and 3
+    pub fn new() -> Result<Self, OledError> {
+        Self::from_gpios(NANOHAT_BUTTON_GPIOS)
+    }
+
+    /// Opens buttons on the GPIOs numbered `gpios`, for K1, K2 and K3, exporting
+    /// each to sysfs as an input if it isn't already
+    pub fn from_gpios(gpios: [u32; 3]) -> Result<Self, OledError> {
+        let mut paths = [PathBuf::new(), PathBuf::new(), PathBuf::new()];
+        for (path, gpio) in paths.iter_mut().zip(&gpios) {
+            let dir = Path::new(GPIO_ROOT).join(format!("gpio{}", gpio));
+            if !dir.exists() {
+                fs::write(Path::new(GPIO_ROOT).join("export"), gpio.to_string())
+                    .map_err(OledError::Gpio)?;
+            }
+            fs::write(dir.join("direction"), "in").map_err(OledError::Gpio)?;
+            *path = dir.join("value");
+        }
+        Self::from_paths(paths)
+    }
+
+    /// Opens buttons read from the GPIO `value` files at `paths`, for K1, K2
+    /// and K3, eg for GPIOs exported already
+    pub fn from_paths<P: AsRef<Path>>(paths: [P; 3]) -> Result<Self, OledError> {
+        let [k1, k2, k3] = paths;
+        let open = |path: P| File::open(path).map_err(OledError::Gpio);
+        let mut buttons = Buttons {
+            values: [open(k1)?, open(k2)?, open(k3)?],
+            levels: [false; 3],
+            active_low: false,
+        };
+        for index in 0..buttons.values.len() {
+            buttons.levels[index] = buttons.read(index)?;
+        }
+        Ok(buttons)
+    }
+
+    /// Takes a button reading 0 to be pressed, or not
+    pub fn with_active_low(self, active_low: bool) -> Self {
+        Buttons { active_low, ..self }
+    }
+
+    /// Returns whether `key` was held down when last polled
+    pub fn is_pressed(&self, key: Key) -> bool {
+        self.levels[key as usize] != self.active_low
+    }
+
+    /// Reads each button, and returns a press or release for each whose
+    /// state has changed since it was last read, from K1 to K3
+    pub fn poll(&mut self) -> Result<impl Iterator<Item = ButtonEvent>, OledError> {
+        let mut events = [None; 3];
+        for (event, key) in events.iter_mut().zip(&Key::ALL) {
+            let index = *key as usize;
+            let level = self.read(index)?;
+            if level != self.levels[index] {
+                self.levels[index] = level;
+                *event = Some(if self.is_pressed(*key) {
+                    ButtonEvent::Press(*key)
+                } else {
+                    ButtonEvent::Release(*key)
+                });
+            }
+        }
+        Ok(IntoIterator::into_iter(events).flatten())
+    }
+
+    /// Returns an iterator that waits for each press or release, polling
+    /// every `interval`. It only ends if reading a button fails.
+    pub fn events(&mut self, interval: Duration) -> ButtonEvents<'_> {
+        ButtonEvents {
+            buttons: self,
+            interval,
+            pending: [None; 3],
+            failed: false,
+        }
+    }
+
+    /// The level of the button at `index`
+    fn read(&mut self, index: usize) -> Result<bool, OledError> {
+        let value = &mut self.values[index];
+        let mut level = [0];
+        value
+            .seek(SeekFrom::Start(0))
+            .and_then(|_| value.read_exact(&mut level))
+            .map_err(OledError::Gpio)?;
+        Ok(level[0] == b'1')
+    }
+}
+
+impl Input for Buttons {
+    fn poll_events(&mut self) -> Result<Vec<ButtonEvent>, OledError> {
+        Ok(self.poll()?.collect())
+    }
+}
+
+/// An iterator over presses and releases of [`Buttons`](struct.Buttons.html),
+/// as returned by [`Buttons::events()`](struct.Buttons.html#method.events)
+#[derive(Debug)]
+pub struct ButtonEvents<'a> {
+    /// Buttons being polled
+    buttons: &'a mut Buttons,
+    /// How long to wait between polls
+    interval: Duration,
+    /// Events from the last poll not yet returned
+    pending: [Option<ButtonEvent>; 3],
+    /// Whether reading the buttons has failed, ending the iterator
+    failed: bool,
+}
+
+impl Iterator for ButtonEvents<'_> {
+    type Item = Result<ButtonEvent, OledError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        loop {
+            if let Some(event) = self.pending.iter_mut().find_map(Option::take) {
+                return Some(Ok(event));
+            }
+            match self.buttons.poll() {
+                Ok(events) => {
+                    for (pending, event) in self.pending.iter_mut().zip(events) {
+                        *pending = Some(event);
+                    }
+                }
+                Err(err) => {
+                    self.failed = true;
+                    return Some(Err(err));
+                }
+            }
+            if self.pending.iter().all(Option::is_none) {
+                sleep(self.interval);
+            }
+        }
+    }
+}
@@ -0,0 +1,250 @@
+//! Clocks showing a time of day, in big digits or with hands
+use crate::graphics::towards;
+use crate::{BuiltinFont, Font, Framebuffer, OledError, Rect, Widget};
+
+/// Largest scale of a [`DigitalClock`](struct.DigitalClock.html)'s digits
+const DIGIT_SCALE: u16 = 3;
+
+/// Length of an [`AnalogClock`](struct.AnalogClock.html)'s hour marks, in pixels
+const MARK_LENGTH: u16 = 2;
+
+/// Returns `InvalidArgument` unless `hours`, `minutes` and `seconds` are a time
+/// on a 24 hour clock
+fn check_time(hours: u8, minutes: u8, seconds: u8) -> Result<(u8, u8, u8), OledError> {
+    if hours >= 24 || minutes >= 60 || seconds >= 60 {
+        return Err(OledError::InvalidArgument(
+            "hours must be below 24, and minutes and seconds below 60",
+        ));
+    }
+    Ok((hours, minutes, seconds))
+}
+
+/// A [`Widget`](trait.Widget.html) showing the time as `HH:MM`, or `HH:MM:SS`
+/// with seconds, in the built-in font at three times its size, or as large as
+/// fits if that doesn't: `HH:MM` fills most of the display's width at three
+/// times, and `HH:MM:SS` fills it at twice. The digits are centred in the
+/// clock's area.
+///
+/// The colon can blink, shown only in even seconds, so a clock without seconds
+/// still shows it's running.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{DigitalClock, Oled, OledResult, Rect};
+/// # fn main() -> OledResult {
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// let mut clock = DigitalClock::new().with_blinking_colon(true);
+/// clock.set_time(18, 30, 0)?;
+/// oled.render_widgets(&mut [(&mut clock, Rect::new(0, 16, 128, 32))])?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DigitalClock {
+    /// Hours, minutes and seconds shown
+    time: (u8, u8, u8),
+    /// Whether the seconds are shown
+    seconds: bool,
+    /// Whether the colon is hidden in odd seconds
+    blink: bool,
+    /// Whether anything has changed since the clock was last rendered
+    changed: bool,
+}
+
+impl Default for DigitalClock {
+    fn default() -> Self {
+        DigitalClock::new()
+    }
+}
+
+impl DigitalClock {
+    /// Creates a clock showing midnight, without seconds
+    pub fn new() -> Self {
+        DigitalClock {
+            time: (0, 0, 0),
+            seconds: false,
+            blink: false,
+            changed: true,
+        }
+    }
+
+    /// Shows the seconds as well, or not
+    pub fn with_seconds(self, seconds: bool) -> Self {
+        DigitalClock { seconds, ..self }
+    }
+
+    /// Hides the colons in odd seconds, or not
+    pub fn with_blinking_colon(self, blink: bool) -> Self {
+        DigitalClock { blink, ..self }
+    }
+
+    /// Shows the time `hours`:`minutes`:`seconds`, on a 24 hour clock
+    ///
+    /// Returns `InvalidArgument` unless `hours` is below 24, and `minutes` and
+    /// `seconds` below 60.
+    pub fn set_time(&mut self, hours: u8, minutes: u8, seconds: u8) -> Result<(), OledError> {
+        let time = check_time(hours, minutes, seconds)?;
+        let (before, len) = self.text();
+        self.time = time;
+        let (after, _) = self.text();
+        self.changed |= after[..len] != before[..len];
+        Ok(())
+    }
+
+    /// The characters shown, and how many of them there are
+    fn text(&self) -> ([u8; 8], usize) {
+        let (hours, minutes, seconds) = self.time;
+        let colon = if self.blink && seconds % 2 == 1 {
+            b' '
+        } else {
+            b':'
+        };
+        let digits = |value: u8| [b'0' + value / 10, b'0' + value % 10];
+        let [h0, h1] = digits(hours);
+        let [m0, m1] = digits(minutes);
+        let [s0, s1] = digits(seconds);
+        let text = [h0, h1, colon, m0, m1, colon, s0, s1];
+        (text, if self.seconds { 8 } else { 5 })
+    }
+}
+
+impl Widget for DigitalClock {
+    fn render(&mut self, frame: &mut Framebuffer, area: Rect) {
+        frame.fill_rect(area.x, area.y, area.width, area.height, false);
+        self.changed = false;
+        let (text, len) = self.text();
+        let font = BuiltinFont::MONOSPACE;
+        let cell = font.line_height();
+        let scale = DIGIT_SCALE
+            .min(area.width / (cell * len as u16))
+            .min(area.height / cell);
+        if scale == 0 {
+            return;
+        }
+        let left = area.x + (area.width - cell * scale * len as u16) / 2;
+        let top = area.y + (area.height - cell * scale) / 2;
+        for (index, char) in text[..len].iter().enumerate() {
+            let Some(glyph) = font.glyph(char::from(*char)) else {
+                continue;
+            };
+            let x = left + index as u16 * cell * scale;
+            for glyph_y in 0..cell {
+                for glyph_x in 0..cell {
+                    if glyph.get_pixel(glyph_x, glyph_y) {
+                        let (px, py) = (x + glyph_x * scale, top + glyph_y * scale);
+                        frame.fill_rect(px, py, scale, scale, true);
+                    }
+                }
+            }
+        }
+    }
+
+    fn needs_render(&self) -> bool {
+        self.changed
+    }
+}
+
+/// A [`Widget`](trait.Widget.html) showing the time as a clock face, a circle
+/// with a mark at each hour and hour and minute hands, and optionally a second
+/// hand. The face is as large as fits in the clock's area, centred in it.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{AnalogClock, Oled, OledResult, Rect};
+/// # fn main() -> OledResult {
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// let mut clock = AnalogClock::new().with_second_hand(true);
+/// clock.set_time(10, 10, 30)?;
+/// oled.render_widgets(&mut [(&mut clock, Rect::new(32, 0, 64, 64))])?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AnalogClock {
+    /// Hours, minutes and seconds shown
+    time: (u8, u8, u8),
+    /// Whether the second hand is shown
+    second_hand: bool,
+    /// Whether anything has changed since the clock was last rendered
+    changed: bool,
+}
+
+impl Default for AnalogClock {
+    fn default() -> Self {
+        AnalogClock::new()
+    }
+}
+
+impl AnalogClock {
+    /// Creates a clock showing midnight, without a second hand
+    pub fn new() -> Self {
+        AnalogClock {
+            time: (0, 0, 0),
+            second_hand: false,
+            changed: true,
+        }
+    }
+
+    /// Shows a second hand, or not
+    pub fn with_second_hand(self, second_hand: bool) -> Self {
+        AnalogClock {
+            second_hand,
+            ..self
+        }
+    }
+
+    /// Shows the time `hours`:`minutes`:`seconds`, on a 24 or 12 hour clock
+    ///
+    /// Returns `InvalidArgument` unless `hours` is below 24, and `minutes` and
+    /// `seconds` below 60.
+    pub fn set_time(&mut self, hours: u8, minutes: u8, seconds: u8) -> Result<(), OledError> {
+        let time = check_time(hours, minutes, seconds)?;
+        let second_hand = self.second_hand;
+        let shown = |(hours, minutes, seconds): (u8, u8, u8)| {
+            (hours % 12, minutes, Some(seconds).filter(|_| second_hand))
+        };
+        self.changed |= shown(time) != shown(self.time);
+        self.time = time;
+        Ok(())
+    }
+}
+
+impl Widget for AnalogClock {
+    fn render(&mut self, frame: &mut Framebuffer, area: Rect) {
+        frame.fill_rect(area.x, area.y, area.width, area.height, false);
+        self.changed = false;
+        let radius = (area.width.min(area.height).saturating_sub(1)) / 2;
+        if radius == 0 {
+            return;
+        }
+        let centre = (area.x + area.width / 2, area.y + area.height / 2);
+        frame.draw_circle(centre.0, centre.1, radius, true);
+        // angles on the face, clockwise from 12, turned into anticlockwise from 3
+        let angle = |clockwise: f32| 90.0 - clockwise;
+        let mut hand = |clockwise: f32, length: u16| {
+            let (x, y) = towards(centre, angle(clockwise), length);
+            frame.draw_line(centre.0, centre.1, x, y, true);
+        };
+        let (hours, minutes, seconds) = self.time;
+        let minutes = f32::from(minutes);
+        hand(f32::from(hours % 12) * 30.0 + minutes / 2.0, radius / 2);
+        hand(minutes * 6.0, radius * 4 / 5);
+        if self.second_hand {
+            hand(
+                f32::from(seconds) * 6.0,
+                radius.saturating_sub(MARK_LENGTH + 1),
+            );
+        }
+        for hour in 0..12u8 {
+            let degrees = angle(f32::from(hour) * 30.0);
+            let (x0, y0) = towards(centre, degrees, radius);
+            let (x1, y1) = towards(centre, degrees, radius.saturating_sub(MARK_LENGTH));
+            frame.draw_line(x0, y0, x1, y1, true);
+        }
+        frame.fill_circle(centre.0, centre.1, 1, true);
+    }
+
+    fn needs_render(&self) -> bool {
+        self.changed
+    }
+}
@@ -0,0 +1,281 @@
+//! Settings for the setup sequence sent by `init()`, for panels other than the NanoHat's
+use crate::{
+    AddressingMode, Command, Controller, Instruction, Oled, OledError, OledInterface, OledResult,
+    MAX_INSTRUCTION_LEN,
+};
+
+/// Length of the setup sequence, in bytes
+pub(crate) const INIT_SEQUENCE_LEN: usize = 25;
+
+/// The size of an SSD1306 panel. The controller's RAM is 128x64 whatever the
+/// panel, and smaller panels show its top left corner, so drawing works the
+/// same on all of them, with what falls outside the panel not shown.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PanelSize {
+    /// 128 pixels wide and 64 high, like the NanoHat OLED. (default)
+    Panel128x64,
+    /// 128 pixels wide and 32 high
+    Panel128x32,
+    /// 96 pixels wide and 16 high
+    Panel96x16,
+}
+
+impl PanelSize {
+    /// The width of the panel, in pixels
+    pub fn width(self) -> u16 {
+        match self {
+            PanelSize::Panel128x64 | PanelSize::Panel128x32 => 128,
+            PanelSize::Panel96x16 => 96,
+        }
+    }
+
+    /// The height of the panel, in pixels
+    pub fn height(self) -> u16 {
+        match self {
+            PanelSize::Panel128x64 => 64,
+            PanelSize::Panel128x32 => 32,
+            PanelSize::Panel96x16 => 16,
+        }
+    }
+
+    /// The number of text columns and rows that fit on the panel with the built-in font
+    pub fn text_cells(self) -> (u8, u8) {
+        ((self.width() / 8) as u8, (self.height() / 8) as u8)
+    }
+}
+
+/// The low-level settings [`Oled::init_with()`](struct.Oled.html#method.init_with)
+/// sets the display up with. The defaults, as used by
+/// [`Oled::init()`](struct.Oled.html#method.init), suit the NanoHat OLED; the
+/// builder methods change them, eg for a module of another make or one with
+/// an external V<sub>CC</sub> supply, without sending raw bytes by hand.
+///
+/// Nothing is checked until the settings are used, when `init_with()` returns
+/// `InvalidArgument` for any that are out of range, before sending anything.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{Oled, OledConfig, OledResult};
+/// # fn main() -> OledResult {
+/// let config = OledConfig::DEFAULT
+///     .with_charge_pump(false)
+///     .with_precharge(2, 2);
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init_with(config)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct OledConfig {
+    /// Chip driving the panel
+    controller: Controller,
+    /// Size of the panel
+    size: PanelSize,
+    /// Number of rows driven
+    multiplex_ratio: u8,
+    /// Row the picture starts on
+    display_offset: u8,
+    /// Clock divide ratio, and oscillator frequency setting
+    clock: (u8, u8),
+    /// Pre-charge phases, in display clocks
+    precharge: (u8, u8),
+    /// Whether the COM pins are in the alternative configuration, and whether
+    /// their left and right halves are swapped
+    com_pins: (bool, bool),
+    /// V<sub>COMH</sub> deselect level
+    vcomh: u8,
+    /// Whether the panel is powered by the controller's own charge pump
+    charge_pump: bool,
+}
+
+impl OledConfig {
+    /// The NanoHat OLED's settings, per the SSD1306 and NanoHat OLED datasheets
+    pub const DEFAULT: OledConfig = OledConfig {
+        controller: Controller::Ssd1306,
+        size: PanelSize::Panel128x64,
+        multiplex_ratio: 64,
+        display_offset: 0,
+        clock: (1, 8),
+        precharge: (1, 15),
+        com_pins: (true, false),
+        vcomh: 0x40,
+        charge_pump: true,
+    };
+
+    /// Talks to the panel as `controller` expects. Default is the SSD1306.
+    pub fn with_controller(self, controller: Controller) -> Self {
+        OledConfig { controller, ..self }
+    }
+
+    /// Sets the display up for a panel of `size`, including the multiplex ratio
+    /// and COM pin configuration it needs, and makes
+    /// [`Oled::dimensions()`](struct.Oled.html#method.dimensions) and the
+    /// bounds of drawing and text follow it. Default is 128x64.
+    pub fn with_size(self, size: PanelSize) -> Self {
+        OledConfig {
+            size,
+            multiplex_ratio: size.height() as u8,
+            com_pins: (size == PanelSize::Panel128x64, false),
+            ..self
+        }
+    }
+
+    /// Drives `rows` rows of the panel, from 16 to 64. Default is 64.
+    pub fn with_multiplex_ratio(self, rows: u8) -> Self {
+        OledConfig {
+            multiplex_ratio: rows,
+            ..self
+        }
+    }
+
+    /// Starts the picture `rows` rows down the panel's COM lines, from 0 to 63,
+    /// for panels whose first row isn't wired to the first line. Default is 0.
+    pub fn with_display_offset(self, rows: u8) -> Self {
+        OledConfig {
+            display_offset: rows,
+            ..self
+        }
+    }
+
+    /// Sets the display clock: the oscillator is divided by `divide`, from 1 to
+    /// 16, and its `frequency` setting goes from 0 (slowest) to 15 (fastest).
+    /// Default is a divide ratio of 1 at frequency setting 8.
+    pub fn with_clock(self, divide: u8, frequency: u8) -> Self {
+        OledConfig {
+            clock: (divide, frequency),
+            ..self
+        }
+    }
+
+    /// Sets the pre-charge period, as for
+    /// [`Oled::set_precharge()`](struct.Oled.html#method.set_precharge).
+    /// Default is 1 clock for phase 1 and 15 for phase 2; panels with an
+    /// external V<sub>CC</sub> usually want 2 and 2, or 2 and 15.
+    pub fn with_precharge(self, phase1: u8, phase2: u8) -> Self {
+        OledConfig {
+            precharge: (phase1, phase2),
+            ..self
+        }
+    }
+
+    /// Sets how the COM pins are wired to the panel's rows: the `alternative`
+    /// configuration interleaves them, as 128x64 panels need, and
+    /// `left_right_remap` swaps the left and right halves. Default is the
+    /// alternative configuration, without remapping.
+    pub fn with_com_pins(self, alternative: bool, left_right_remap: bool) -> Self {
+        OledConfig {
+            com_pins: (alternative, left_right_remap),
+            ..self
+        }
+    }
+
+    /// Sets the V<sub>COMH</sub> deselect level, as for
+    /// [`Oled::set_vcomh()`](struct.Oled.html#method.set_vcomh). Default is `0x40`.
+    pub fn with_vcomh(self, level: u8) -> Self {
+        OledConfig {
+            vcomh: level,
+            ..self
+        }
+    }
+
+    /// Powers the panel from the controller's own charge pump, or the SH1106's
+    /// DC-DC converter, or not, for
+    /// modules with an external V<sub>CC</sub> supply. Default is on.
+    /// [`Oled::wake()`](struct.Oled.html#method.wake) then leaves it off too.
+    pub fn with_charge_pump(self, enabled: bool) -> Self {
+        OledConfig {
+            charge_pump: enabled,
+            ..self
+        }
+    }
+
+    /// The chip driving the panel
+    pub fn controller(&self) -> Controller {
+        self.controller
+    }
+
+    /// The size of the panel
+    pub fn size(&self) -> PanelSize {
+        self.size
+    }
+
+    /// The number of rows driven
+    pub(crate) fn multiplex_ratio(&self) -> u8 {
+        self.multiplex_ratio
+    }
+
+    /// Whether the panel is powered by the controller's own charge pump
+    pub(crate) fn charge_pump(&self) -> bool {
+        self.charge_pump
+    }
+
+    /// The setup sequence for these settings, or `InvalidArgument` if any is
+    /// out of range
+    pub(crate) fn sequence(&self) -> Result<[u8; INIT_SEQUENCE_LEN], OledError> {
+        let (divide, frequency) = self.clock;
+        let (phase1, phase2) = self.precharge;
+        let (alternative, left_right_remap) = self.com_pins;
+        let steps = [
+            Command::DisplayOff.into(),
+            Instruction::SetColumnStart(0),
+            Instruction::SetStartLine(0),
+            Instruction::SetPageStart(0),
+            Instruction::SetContrast(0x7f),
+            Command::SegmentRemapReversed.into(),
+            Command::NormalDisplay.into(),
+            Instruction::SetMultiplex(self.multiplex_ratio),
+            Command::ComScanRemapped.into(),
+            Instruction::SetDisplayOffset(self.display_offset),
+            Instruction::SetClock { divide, frequency },
+            Instruction::SetPrecharge(phase1, phase2),
+            Instruction::SetComPins {
+                alternative,
+                left_right_remap,
+            },
+            Instruction::SetVcomhLevel(self.vcomh),
+            self.controller.supply(self.charge_pump),
+            Command::DisplayOn.into(),
+        ];
+        let mut sequence = [0; INIT_SEQUENCE_LEN];
+        let mut len = 0;
+        for step in steps.iter() {
+            let mut buffer = [0; MAX_INSTRUCTION_LEN];
+            let bytes = step.encode(&mut buffer)?;
+            sequence[len..len + bytes.len()].copy_from_slice(bytes);
+            len += bytes.len();
+        }
+        Ok(sequence)
+    }
+}
+
+impl Default for OledConfig {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl<I: OledInterface> Oled<I> {
+    /// Initial low-level setup for the display, like [`init()`](struct.Oled.html#method.init),
+    /// but with `config`'s settings rather than the NanoHat OLED's.
+    /// Returns `InvalidArgument`, without sending anything, if any are out of range,
+    /// or if the display is turned a quarter turn, which only 128x64 panels can be,
+    /// as for [`set_rotation()`](struct.Oled.html#method.set_rotation).
+    pub fn init_with(&mut self, config: OledConfig) -> OledResult {
+        if self.rotation.is_portrait() && config.size() != PanelSize::Panel128x64 {
+            return Err(OledError::InvalidArgument(
+                "quarter turns are only supported on 128x64 panels",
+            ));
+        }
+        self.send_commands(&config.sequence()?)?;
+        self.config = config;
+        if config.controller().has_windows() {
+            self.set_addressing_mode(AddressingMode::Horizontal)?;
+        }
+        self.clear_display()?;
+        Ok(())
+    }
+
+    /// Returns the size of the panel, as set by [`init_with()`](struct.Oled.html#method.init_with)
+    pub fn panel_size(&self) -> PanelSize {
+        self.config.size()
+    }
+}
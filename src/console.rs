@@ -0,0 +1,423 @@
+//! A scrolling text console on top of the display's text primitives
+use crate::{Oled, OledInterface, OledResult, TEXT_COLUMNS};
+
+/// Number of rows an [`OledConsole`](struct.OledConsole.html) keeps after they
+/// have scrolled off the top of the screen
+pub const SCROLLBACK_ROWS: usize = 32;
+
+/// Most columns on screen, on the largest panel
+const COLUMNS: usize = TEXT_COLUMNS as usize;
+
+/// Most rows a console holds: those on screen, on the largest panel turned a
+/// quarter turn, and its scrollback
+const CONSOLE_ROWS: usize = TEXT_COLUMNS as usize + SCROLLBACK_ROWS;
+
+/// A character cell, and whether it's shown inverted
+type Cell = (char, bool);
+
+/// An empty cell
+const BLANK: Cell = (' ', false);
+
+/// Most parameters kept from a control sequence; any more are ignored
+const MAX_PARAMS: usize = 4;
+
+/// How far through an escape sequence the console has read
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Escape {
+    /// Not in one
+    None,
+    /// Just after an `ESC`
+    Started,
+    /// In a control sequence, after `ESC [`, with the parameters read so far
+    /// and the index of the one being read
+    Control {
+        params: [u16; MAX_PARAMS],
+        index: usize,
+        private: bool,
+    },
+}
+
+/// A text console, like a terminal's output, on the display's character grid:
+/// 16x8 on the NanoHat's panel, 8x16 once it's turned a quarter turn by
+/// [`set_rotation()`](struct.Oled.html#method.set_rotation), and as many cells
+/// as fit on a [smaller one](enum.PanelSize.html).
+///
+/// Text is written at the bottom of what is there so far. Once all text rows
+/// are in use, existing rows scroll up one row to make space. The last
+/// [`SCROLLBACK_ROWS`](constant.SCROLLBACK_ROWS.html) rows to scroll off the
+/// top are kept, and can be brought back with
+/// [`scroll_back()`](#method.scroll_back); older ones are discarded.
+///
+/// Enough of the ANSI escape codes terminals understand are followed for
+/// output meant for a terminal, eg progress bars and small full-screen tools,
+/// to show as meant:
+/// - `\r` goes back to the start of the row, so what follows writes over it
+/// - `ESC [ row ; column H`, and `f`, moves the cursor to a cell of the screen,
+///   counting from 1 at the top left
+/// - `ESC [ K` erases the row from the cursor on, `ESC [ 1 K` up to the
+///   cursor and `ESC [ 2 K` all of it
+/// - `ESC [ J` erases the screen from the cursor on, `ESC [ 1 J` up to the
+///   cursor and `ESC [ 2 J` all of it, leaving the cursor where it is
+/// - `ESC [ 7 m` writes what follows inverted, and `ESC [ 27 m` or
+///   `ESC [ 0 m` goes back to normal
+///
+/// Other escape sequences, and other parts of those, are read and left out.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{Oled, OledConsole, OledResult};
+/// # fn main() -> OledResult {
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// let mut console = OledConsole::new(oled);
+/// console.println("Booting...")?;
+/// console.print("Network ")?;
+/// console.println("up")?;
+/// // a count on the bottom row, inverted, written over as it goes up
+/// for percent in (0..=100).step_by(25) {
+///     console.print(&format!("\x1b[8;1H\x1b[7m{:3}%\x1b[0m\x1b[K", percent))?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct OledConsole<I> {
+    /// The display being written to
+    oled: Oled<I>,
+    /// Number of columns on screen
+    columns: usize,
+    /// Number of rows on screen
+    rows: usize,
+    /// Cells of each row, oldest first, padded with spaces
+    lines: [[Cell; COLUMNS]; CONSOLE_ROWS],
+    /// Number of rows holding text so far
+    used: usize,
+    /// Row the cursor is on
+    row: usize,
+    /// Column the next character goes in on the cursor's row, or `None` if it
+    /// starts the row after
+    column: Option<usize>,
+    /// Number of rows the screen is scrolled back from the latest text
+    view: usize,
+    /// Whether characters are written inverted
+    inverse: bool,
+    /// How far through an escape sequence the text written so far ends
+    escape: Escape,
+    /// First row changed since the display was last updated
+    changed: usize,
+}
+
+impl<I: OledInterface> OledConsole<I> {
+    /// Creates a console over an already-initialized display, sized to the
+    /// display as it's turned at the time.
+    /// The console starts out empty; the next text will be written to the top row.
+    pub fn new(oled: Oled<I>) -> Self {
+        let (columns, rows) = oled.text_grid();
+        Self {
+            oled,
+            columns: usize::from(columns),
+            rows: usize::from(rows),
+            lines: [[BLANK; COLUMNS]; CONSOLE_ROWS],
+            used: 0,
+            row: 0,
+            column: None,
+            view: 0,
+            inverse: false,
+            escape: Escape::None,
+            changed: usize::MAX,
+        }
+    }
+
+    /// Writes text after whatever was written last, scrolling earlier rows up if
+    /// the screen is full. Text running past the end of a row is wrapped onto the
+    /// next, and `\n` starts a new row. If the console is scrolled back, it first
+    /// goes back to showing the latest text.
+    pub fn print(&mut self, text: &str) -> OledResult {
+        self.write(text, false)
+    }
+
+    /// Writes text like [`print()`](#method.print), then ends the row, so
+    /// whatever is written next starts on a new one
+    pub fn println(&mut self, text: &str) -> OledResult {
+        self.write(text, true)
+    }
+
+    /// Shows earlier text, moving the screen `rows` rows further back through the
+    /// scrollback, or as far back as it goes
+    pub fn scroll_back(&mut self, rows: usize) -> OledResult {
+        let view = self
+            .view
+            .saturating_add(rows)
+            .min(self.used.saturating_sub(self.rows));
+        self.set_view(view)
+    }
+
+    /// Shows later text, moving the screen `rows` rows back towards the latest
+    pub fn scroll_forward(&mut self, rows: usize) -> OledResult {
+        self.set_view(self.view.saturating_sub(rows))
+    }
+
+    /// Number of rows the screen is scrolled back from the latest text,
+    /// 0 if it is showing it
+    pub fn scrollback_position(&self) -> usize {
+        self.view
+    }
+
+    /// Clears the console, including its scrollback, and the display
+    pub fn clear(&mut self) -> OledResult {
+        self.lines = [[BLANK; COLUMNS]; CONSOLE_ROWS];
+        self.used = 0;
+        self.row = 0;
+        self.column = None;
+        self.view = 0;
+        self.inverse = false;
+        self.escape = Escape::None;
+        self.oled.clear_display()
+    }
+
+    /// Returns the display, eg for sending commands directly
+    pub fn oled(&mut self) -> &mut Oled<I> {
+        &mut self.oled
+    }
+
+    /// Consumes the console, returning the display
+    pub fn into_oled(self) -> Oled<I> {
+        self.oled
+    }
+
+    /// Writes text into the rows, ending the row afterwards if `end_row` is set,
+    /// then updates the display
+    fn write(&mut self, text: &str, end_row: bool) -> OledResult {
+        let mut scrolled = core::mem::replace(&mut self.view, 0) > 0;
+        for char in text.chars() {
+            scrolled |= self.write_char(char);
+        }
+        if end_row && self.column.take().is_none() {
+            scrolled |= self.next_row();
+        }
+        let top = self.used.saturating_sub(self.rows);
+        let first = core::mem::replace(&mut self.changed, usize::MAX);
+        if scrolled {
+            self.redraw_rows(0)
+        } else {
+            self.redraw_rows(first.saturating_sub(top))
+        }
+    }
+
+    /// Writes one character of text, or of an escape sequence. Returns whether
+    /// the screen scrolled.
+    fn write_char(&mut self, char: char) -> bool {
+        match self.escape {
+            Escape::None => return self.put(char),
+            Escape::Started if char == '[' => {
+                self.escape = Escape::Control {
+                    params: [0; MAX_PARAMS],
+                    index: 0,
+                    private: false,
+                }
+            }
+            // other sequences end at their first character that isn't an
+            // intermediate one, eg `B` in `ESC ( B`
+            Escape::Started if (' '..='/').contains(&char) => {}
+            Escape::Started => self.escape = Escape::None,
+            Escape::Control {
+                mut params,
+                mut index,
+                mut private,
+            } => {
+                match char {
+                    '0'..='9' => {
+                        if let Some(param) = params.get_mut(index) {
+                            let digit = char as u16 - u16::from(b'0');
+                            *param = param.saturating_mul(10).saturating_add(digit);
+                        }
+                    }
+                    ';' => index = index.saturating_add(1),
+                    '<'..='?' => private = true,
+                    '@'..='~' => {
+                        self.escape = Escape::None;
+                        if !private {
+                            let count = index.saturating_add(1).min(MAX_PARAMS);
+                            self.control(char, &params[..count]);
+                        }
+                        return false;
+                    }
+                    // intermediate characters, and anything that shouldn't be there
+                    _ => {}
+                }
+                self.escape = Escape::Control {
+                    params,
+                    index,
+                    private,
+                };
+            }
+        }
+        false
+    }
+
+    /// Writes one character that isn't part of an escape sequence. Returns
+    /// whether the screen scrolled.
+    fn put(&mut self, char: char) -> bool {
+        match char {
+            '\x1b' => self.escape = Escape::Started,
+            '\n' => {
+                if self.column.take().is_none() {
+                    return self.next_row();
+                }
+            }
+            '\r' => {
+                if self.column.is_some() {
+                    self.column = Some(0);
+                }
+            }
+            _ => {
+                let mut scrolled = false;
+                let column = match self.column {
+                    Some(column) if column < self.columns => column,
+                    _ => {
+                        scrolled = self.next_row();
+                        0
+                    }
+                };
+                self.lines[self.row][column] = (char, self.inverse);
+                self.mark(self.row);
+                self.column = Some(column + 1);
+                return scrolled;
+            }
+        }
+        false
+    }
+
+    /// Carries out the control sequence ending in `command`, with `params`
+    fn control(&mut self, command: char, params: &[u16]) {
+        let param = |index: usize| params.get(index).copied().unwrap_or(0);
+        match command {
+            'H' | 'f' => self.move_cursor(param(0), param(1)),
+            'K' => {
+                let (row, column) = self.cursor();
+                match param(0) {
+                    0 => self.erase(row, column, self.columns),
+                    1 => self.erase(row, 0, column + 1),
+                    2 => self.erase(row, 0, self.columns),
+                    _ => {}
+                }
+            }
+            'J' => {
+                let (row, column) = self.cursor();
+                let top = self.used.saturating_sub(self.rows);
+                let (from, to) = match param(0) {
+                    0 => {
+                        self.erase(row, column, self.columns);
+                        (row + 1, self.used)
+                    }
+                    1 => {
+                        self.erase(row, 0, column + 1);
+                        (top, row)
+                    }
+                    2 => (top, self.used),
+                    _ => (0, 0),
+                };
+                for row in from..to {
+                    self.erase(row, 0, self.columns);
+                }
+            }
+            'm' => {
+                for param in params {
+                    match param {
+                        0 | 27 => self.inverse = false,
+                        7 => self.inverse = true,
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Moves the cursor to `row` and `column` of the screen, counting from 1,
+    /// with rows below the text so far filled in as blank ones
+    fn move_cursor(&mut self, row: u16, column: u16) {
+        let row = usize::from(row.clamp(1, self.rows as u16)) - 1;
+        let column = usize::from(column.clamp(1, self.columns as u16)) - 1;
+        let top = self.used.saturating_sub(self.rows);
+        while self.used <= top + row {
+            self.lines[self.used] = [BLANK; COLUMNS];
+            self.mark(self.used);
+            self.used += 1;
+        }
+        self.row = top + row;
+        self.column = Some(column);
+    }
+
+    /// The row and column the next character goes in, which for a row that has
+    /// ended is the start of the row after
+    fn cursor(&self) -> (usize, usize) {
+        match self.column {
+            Some(column) => (self.row, column.min(self.columns - 1)),
+            None if self.used == 0 => (0, 0),
+            None => (self.row + 1, 0),
+        }
+    }
+
+    /// Blanks the cells of `row` from column `from` up to `to`, if it's in use
+    fn erase(&mut self, row: usize, from: usize, to: usize) {
+        if row < self.used {
+            for cell in &mut self.lines[row][from..to] {
+                *cell = BLANK;
+            }
+            self.mark(row);
+        }
+    }
+
+    /// Notes that `row` has changed, so it's redrawn
+    fn mark(&mut self, row: usize) {
+        self.changed = self.changed.min(row);
+    }
+
+    /// Moves the cursor to the start of the row after its own, claiming a blank
+    /// row for new text if it's on the last, and discarding the oldest if none
+    /// are free. Returns whether the screen scrolled.
+    fn next_row(&mut self) -> bool {
+        if self.row + 1 < self.used {
+            self.row += 1;
+            return false;
+        }
+        if self.used == self.rows + SCROLLBACK_ROWS {
+            self.lines.rotate_left(1);
+            self.used -= 1;
+        }
+        self.lines[self.used] = [BLANK; COLUMNS];
+        self.mark(self.used);
+        self.used += 1;
+        self.row = self.used - 1;
+        self.used > self.rows
+    }
+
+    /// Scrolls the screen to `view` rows back from the latest text, redrawing it
+    /// if that moves it
+    fn set_view(&mut self, view: usize) -> OledResult {
+        if view == self.view {
+            return Ok(());
+        }
+        self.view = view;
+        self.redraw_rows(0)
+    }
+
+    /// Writes the screen's rows from `first` to the last used one to the display
+    fn redraw_rows(&mut self, first: usize) -> OledResult {
+        let top = self
+            .used
+            .saturating_sub(self.rows)
+            .saturating_sub(self.view);
+        let shown = self.used.min(self.rows);
+        for row in first.min(shown)..shown {
+            self.oled.set_text_xy(0, row as u8)?;
+            for &(char, inverted) in &self.lines[top + row][..self.columns] {
+                if inverted {
+                    self.oled.put_char_inverted(char)?;
+                } else {
+                    self.oled.put_char(char)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+//! Display contrast, ie brightness
+use crate::{Command, Oled, OledInterface, OledResult};
+
+/// A contrast level for the display, with higher values meaning brighter pixels
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Contrast(pub u8);
+
+impl Contrast {
+    /// The lowest contrast. Lit pixels are still visible.
+    pub const MIN: Contrast = Contrast(0x00);
+    /// The contrast after reset, and as set by [`Oled::init()`](struct.Oled.html#method.init)
+    pub const DEFAULT: Contrast = Contrast(0x7f);
+    /// The highest contrast
+    pub const MAX: Contrast = Contrast(0xff);
+}
+
+impl Default for Contrast {
+    fn default() -> Self {
+        Contrast::DEFAULT
+    }
+}
+
+impl From<u8> for Contrast {
+    fn from(level: u8) -> Self {
+        Contrast(level)
+    }
+}
+
+impl From<Contrast> for u8 {
+    fn from(contrast: Contrast) -> u8 {
+        contrast.0
+    }
+}
+
+/// A ready-made brightness for the display, as set by
+/// [`Oled::set_brightness()`](struct.Oled.html#method.set_brightness), which sets its
+/// pre-charge period and V<sub>COMH</sub> level along with its contrast
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Brightness {
+    /// As dim as the display goes, eg at night. Lowering contrast alone leaves
+    /// lit pixels quite bright, so this also shortens the second pre-charge
+    /// phase and lowers V<sub>COMH</sub>, which dims them evenly.
+    Dim,
+    /// As set by [`Oled::init()`](struct.Oled.html#method.init)
+    Normal,
+    /// The highest contrast, with the pre-charge and V<sub>COMH</sub> of `Normal`
+    Bright,
+}
+
+impl Brightness {
+    /// The contrast, pre-charge phases and V<sub>COMH</sub> level for the brightness
+    fn settings(self) -> (Contrast, (u8, u8), u8) {
+        match self {
+            Brightness::Dim => (Contrast::MIN, (2, 2), 0x00),
+            Brightness::Normal => (Contrast::DEFAULT, (1, 15), 0x40),
+            Brightness::Bright => (Contrast::MAX, (1, 15), 0x40),
+        }
+    }
+}
+
+impl<I: OledInterface> Oled<I> {
+    /// Sets the contrast of the display
+    pub fn set_contrast<C: Into<Contrast>>(&mut self, contrast: C) -> OledResult {
+        self.send_command(Command::SetContrast)?;
+        self.send_command(contrast.into())?;
+        Ok(())
+    }
+
+    /// Sets the display to a ready-made [`Brightness`](enum.Brightness.html), by
+    /// setting its contrast, pre-charge period and V<sub>COMH</sub> level together
+    /// # Example:
+    /// ```no_run
+    /// # use nanohat_oled::{Brightness, Oled, OledResult};
+    /// # fn main() -> OledResult {
+    /// let mut oled = Oled::from_path("/dev/i2c-0")?;
+    /// oled.init()?;
+    /// oled.set_brightness(Brightness::Dim)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_brightness(&mut self, brightness: Brightness) -> OledResult {
+        let (contrast, (phase1, phase2), vcomh) = brightness.settings();
+        self.set_contrast(contrast)?;
+        self.set_precharge(phase1, phase2)?;
+        self.set_vcomh(vcomh)
+    }
+
+    /// Returns the contrast last set on the display.
+    /// The display can't report it, so this is tracked as commands are sent,
+    /// starting from the reset value of [`Contrast::DEFAULT`](struct.Contrast.html#associatedconstant.DEFAULT).
+    pub fn contrast(&self) -> u8 {
+        self.state.contrast
+    }
+
+    /// Raises or lowers the contrast relative to its current value,
+    /// saturating at the lowest and highest levels.
+    pub fn adjust_contrast(&mut self, delta: i16) -> OledResult {
+        let level = i16::from(self.contrast())
+            .saturating_add(delta)
+            .clamp(0, 0xff);
+        self.set_contrast(level as u8)
+    }
+}
@@ -0,0 +1,99 @@
+//! The controller chips the driver can talk to, and where they differ
+use crate::{Instruction, Oled, OledInterface, OledResult, OLED_RAM_SIZE, OLED_WIDTH};
+
+/// Which controller chip drives the panel, as set by
+/// [`OledConfig::with_controller()`](struct.OledConfig.html#method.with_controller)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Controller {
+    /// The SSD1306, as on the NanoHat OLED. (default)
+    Ssd1306,
+    /// The SH1106, as on many 1.3" modules. Its RAM is 132 columns wide, with
+    /// the panel showing the middle 128, and it only has page addressing, so
+    /// pictures are written a page at a time. Its DC-DC converter takes the
+    /// place of the SSD1306's charge pump, and it can't scroll or fade by itself.
+    Sh1106,
+}
+
+impl Controller {
+    /// The RAM column shown at the left edge of the panel
+    fn column_offset(self) -> u8 {
+        match self {
+            Controller::Ssd1306 => 0,
+            Controller::Sh1106 => 2,
+        }
+    }
+
+    /// Whether the controller has horizontal and vertical addressing, and the
+    /// column and page windows they use
+    pub(crate) fn has_windows(self) -> bool {
+        self == Controller::Ssd1306
+    }
+
+    /// The instruction turning the panel's supply on or off: the SSD1306's
+    /// charge pump, or the SH1106's DC-DC converter
+    pub(crate) fn supply(self, on: bool) -> Instruction {
+        match self {
+            Controller::Ssd1306 => Instruction::ChargePump(on),
+            Controller::Sh1106 => Instruction::DcDc(on),
+        }
+    }
+}
+
+/// Commands moving the RAM pointer to `column` of `page`
+fn pointer_commands(column: u8, page: u8) -> [u8; 3] {
+    [0xb0 | page, column & 0x0f, 0x10 | (column >> 4)]
+}
+
+impl<I: OledInterface> Oled<I> {
+    /// Moves the RAM pointer to `column` of `page`, counting columns from the
+    /// left edge of the panel, whatever the controller's RAM is offset by
+    pub(crate) fn move_pointer(&mut self, column: u8, page: u8) -> OledResult {
+        let offset = self.config.controller().column_offset();
+        let sent = pointer_commands(column + offset, page);
+        self.retry("commands", |interface, _| interface.send_commands(&sent))?;
+        for tracked in pointer_commands(column, page).iter() {
+            self.state.command(*tracked);
+        }
+        Ok(())
+    }
+
+    /// Writes the whole of `buffer`, laid out like display RAM, to display RAM,
+    /// leaving the RAM pointer at the top left
+    pub(crate) fn write_ram(&mut self, buffer: &[u8; OLED_RAM_SIZE]) -> OledResult {
+        self.scratch = *buffer;
+        self.write_scratch()
+    }
+
+    /// Writes the whole of the scratch buffer to display RAM, as for
+    /// [`write_ram()`](#method.write_ram), eg once an image is packed into it
+    pub(crate) fn write_scratch(&mut self) -> OledResult {
+        if self.config.controller().has_windows() {
+            self.widen_window()?;
+            self.set_text_xy(0, 0)?;
+            return self.send_scratch(0..OLED_RAM_SIZE);
+        }
+        let width = OLED_WIDTH as usize;
+        for page in 0..OLED_RAM_SIZE / width {
+            self.move_pointer(0, page as u8)?;
+            self.send_scratch(page * width..(page + 1) * width)?;
+        }
+        self.move_pointer(0, 0)
+    }
+
+    /// Writes `columns` of each of `pages` of `buffer` a page at a time, for
+    /// controllers without column and page windows
+    pub(crate) fn write_pages(
+        &mut self,
+        columns: (u8, u8),
+        pages: (u8, u8),
+        buffer: &[u8; OLED_RAM_SIZE],
+    ) -> OledResult {
+        for page in pages.0..=pages.1 {
+            let start = page as usize * OLED_WIDTH as usize;
+            let row = &buffer[start + columns.0 as usize..=start + columns.1 as usize];
+            self.move_pointer(columns.0, page)?;
+            self.send_array_data(row)?;
+        }
+        self.move_pointer(0, 0)
+    }
+}
@@ -0,0 +1,206 @@
+//! Driving the display from other programs, over a named pipe or Unix socket
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader};
+use std::os::unix::fs::FileTypeExt;
+use std::os::unix::net::UnixListener;
+use std::path::Path;
+
+use log::warn;
+
+use crate::{Oled, OledError, OledInterface, OledResult};
+
+/// Gray level from which pixels of a grayscale `IMG` are lit
+const IMG_THRESHOLD: u8 = 0x80;
+
+/// A request, as sent in one line of the daemon protocol
+#[derive(Debug, PartialEq)]
+enum Request<'a> {
+    /// Write text at a text column and row
+    Text(u8, u8, &'a str),
+    /// Clear the display
+    Clear,
+    /// Show a whole-screen image
+    Image(Vec<u8>),
+    /// Set the contrast
+    Contrast(u8),
+}
+
+impl<'a> Request<'a> {
+    /// Parses a line of the protocol, or returns `InvalidArgument` saying what's
+    /// wrong with it
+    fn parse(line: &'a str) -> Result<Self, OledError> {
+        let line = line.trim_end_matches(['\r', '\n']);
+        let (command, args) = line.split_once(' ').unwrap_or((line, ""));
+        let number = |value: Option<&str>, err: &'static str| {
+            value
+                .and_then(|value| value.parse().ok())
+                .ok_or(OledError::InvalidArgument(err))
+        };
+        match command {
+            "TEXT" => {
+                let mut args = args.splitn(3, ' ');
+                let column = number(args.next(), "TEXT needs a column from 0 to 15")?;
+                let row = number(args.next(), "TEXT needs a row from 0 to 7")?;
+                Ok(Request::Text(column, row, args.next().unwrap_or("")))
+            }
+            "CLEAR" => Ok(Request::Clear),
+            "IMG" => decode_base64(args.trim())
+                .map(Request::Image)
+                .ok_or(OledError::InvalidArgument("IMG needs a base64 image")),
+            "CONTRAST" => number(Some(args.trim()), "CONTRAST needs a level from 0 to 255")
+                .map(Request::Contrast),
+            _ => Err(OledError::InvalidArgument("unknown command")),
+        }
+    }
+}
+
+/// Decodes standard base64, with or without padding, or returns `None` if
+/// `text` isn't base64
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let text = text.trim_end_matches('=').as_bytes();
+    let mut bytes = Vec::with_capacity(text.len() * 3 / 4);
+    let (mut bits, mut count) = (0u32, 0);
+    for byte in text {
+        let value = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        };
+        bits = bits << 6 | u32::from(value);
+        count += 6;
+        if count >= 8 {
+            count -= 8;
+            bytes.push((bits >> count) as u8);
+        }
+    }
+    Some(bytes)
+}
+
+/// Runs the display on behalf of other programs, eg shell scripts or programs
+/// in other languages, which send it requests a line at a time over a named
+/// pipe or Unix socket, without linking this library. The `nanohat-oled` tool
+/// runs one with its `daemon` command.
+///
+/// Each line is a command and its arguments, separated by spaces:
+/// - `TEXT 0 3 Hello` writes the rest of the line at text column 0, row 3
+/// - `CLEAR` clears the display
+/// - `IMG <base64>` shows a whole-screen image, packed one bit per pixel by row
+///   as for [`ImageFormat::RowMajor`](enum.ImageFormat.html#variant.RowMajor),
+///   or one byte per pixel, lit from gray level 128 up
+/// - `CONTRAST 128` sets the contrast, from 0 to 255
+///
+/// A line that can't be understood or carried out, eg text off the edge of the
+/// display, is logged as a warning and skipped, so one bad request from a
+/// script doesn't stop the daemon. Only failing to reach the display does.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{Daemon, Oled, OledResult};
+/// # fn main() -> OledResult {
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// // then, eg: echo "TEXT 0 0 Hello" | nc -U /run/nanohat-oled.sock
+/// Daemon::new(oled).serve_socket("/run/nanohat-oled.sock")
+/// # }
+/// ```
+pub struct Daemon<I> {
+    /// The display being driven
+    oled: Oled<I>,
+}
+
+impl<I: OledInterface> Daemon<I> {
+    /// Creates a daemon for an already-initialized display
+    pub fn new(oled: Oled<I>) -> Self {
+        Daemon { oled }
+    }
+
+    /// Carries out one line of the protocol. Returns `InvalidArgument` if it
+    /// isn't a request the daemon understands, or whatever error the display
+    /// gives carrying it out.
+    pub fn handle_line(&mut self, line: &str) -> OledResult {
+        match Request::parse(line)? {
+            Request::Text(column, row, text) => {
+                self.oled.set_text_xy(column, row)?;
+                self.oled.put_string(text)?;
+                Ok(())
+            }
+            Request::Clear => self.oled.clear_display(),
+            Request::Image(image) => self.oled.draw_image_by_len(&image, IMG_THRESHOLD),
+            Request::Contrast(level) => self.oled.set_contrast(level),
+        }
+    }
+
+    /// Carries out each line read from `reader` until it ends, or can't be read
+    /// from any more. Lines that fail, other than by not reaching the display,
+    /// are logged and skipped.
+    pub fn serve<R: BufRead>(&mut self, mut reader: R) -> OledResult {
+        let mut bytes = Vec::new();
+        loop {
+            bytes.clear();
+            match reader.read_until(b'\n', &mut bytes) {
+                Ok(0) => return Ok(()),
+                Ok(_) => {}
+                Err(err) => {
+                    // eg a client hanging up mid-line, which shouldn't stop the daemon
+                    warn!("Stopped reading requests: {}", err);
+                    return Ok(());
+                }
+            }
+            let line = String::from_utf8_lossy(&bytes);
+            if line.trim().is_empty() {
+                continue;
+            }
+            match self.handle_line(&line) {
+                Ok(()) => {}
+                Err(err @ OledError::InvalidArgument(_))
+                | Err(err @ OledError::InvalidDimensions)
+                | Err(err @ OledError::OutOfBounds) => {
+                    warn!("Skipping {:?}: {}", line, err);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// Reads requests from the named pipe at `path`, made beforehand with
+    /// `mkfifo`, eg `echo CLEAR > /run/nanohat-oled`. Whenever the last writer
+    /// closes it, the pipe is opened again for the next, so this only returns on
+    /// an error.
+    pub fn serve_fifo<P: AsRef<Path>>(&mut self, path: P) -> OledResult {
+        loop {
+            // opening blocks until something opens the pipe to write to it
+            let fifo = File::open(path.as_ref())?;
+            self.serve(BufReader::new(fifo))?;
+        }
+    }
+
+    /// Listens on a Unix socket at `path`, replacing any socket left there, and
+    /// reads requests from each program that connects, one connection after
+    /// another. Returns `InvalidArgument` if something other than a socket is
+    /// at `path`, leaving it there, and otherwise only returns on an error.
+    pub fn serve_socket<P: AsRef<Path>>(&mut self, path: P) -> OledResult {
+        match fs::symlink_metadata(path.as_ref()) {
+            Ok(metadata) if metadata.file_type().is_socket() => fs::remove_file(path.as_ref())?,
+            Ok(_) => return Err(OledError::InvalidArgument("not a socket")),
+            Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err.into()),
+            Err(_) => {}
+        }
+        let listener = UnixListener::bind(path)?;
+        for stream in listener.incoming() {
+            self.serve(BufReader::new(stream?))?;
+        }
+        Ok(())
+    }
+
+    /// Returns the display, eg for sending commands directly
+    pub fn oled(&mut self) -> &mut Oled<I> {
+        &mut self.oled
+    }
+
+    /// Consumes the daemon, returning the display
+    pub fn into_oled(self) -> Oled<I> {
+        self.oled
+    }
+}
@@ -0,0 +1,281 @@
+//! A ready-made screen of the system's vital signs, like the NanoPi's stock demo
+use std::fs;
+use std::net::{IpAddr, UdpSocket};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+use crate::{BuiltinFont, Font, Framebuffer, Rect, TextRegion, Widget};
+#[cfg(feature = "buttons")]
+use crate::{Gesture, Screen, Transition};
+
+/// Bytes in a mebibyte
+const MIB: f32 = 1024.0 * 1024.0;
+
+/// Bytes in a gibibyte
+const GIB: f32 = MIB * 1024.0;
+
+/// A snapshot of the system's state, as shown by a
+/// [`SystemDashboard`](struct.SystemDashboard.html). Anything that couldn't be
+/// read is `None`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SystemStats {
+    /// The host's name
+    pub hostname: Option<String>,
+    /// The address the host reaches the rest of the network from
+    pub ip: Option<IpAddr>,
+    /// How busy the CPUs have been, from 0 to 100 percent, since the last
+    /// reading, or since boot for the first
+    pub cpu: Option<f32>,
+    /// Bytes of memory in use, and in all
+    pub memory: Option<(u64, u64)>,
+    /// Bytes of the disk in use, and in all
+    pub disk: Option<(u64, u64)>,
+    /// The CPU's temperature, in degrees Celsius
+    pub temperature: Option<f32>,
+}
+
+/// A [`Widget`](trait.Widget.html) showing the host's name and IP address, and
+/// how busy its CPUs, memory and disk are and how hot it's running, a line of
+/// each in the proportional built-in font, as the NanoPi's stock Python demo
+/// does. Stats are read from `/proc` and `/sys`, and `df` for the disk.
+///
+/// It's also a [`Screen`](trait.Screen.html), with the `buttons` feature,
+/// which reads the stats again every second or so as it ticks, and ignores the
+/// buttons.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{Oled, OledResult, Rect, SystemDashboard};
+/// # use std::{thread::sleep, time::Duration};
+/// # fn main() -> OledResult {
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// let mut dashboard = SystemDashboard::new();
+/// loop {
+///     dashboard.refresh();
+///     oled.render_widgets(&mut [(&mut dashboard, Rect::new(0, 0, 128, 64))])?;
+///     sleep(Duration::from_secs(1));
+/// }
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct SystemDashboard {
+    /// Where `/proc` and `/sys` are found
+    root: PathBuf,
+    /// A path on the disk shown
+    disk: PathBuf,
+    /// How often stats are read again as a screen
+    interval: Duration,
+    /// Time ticked since stats were last read
+    since_refresh: Duration,
+    /// Busy and total CPU time, when last read
+    cpu_times: Option<(u64, u64)>,
+    /// Stats when last read
+    stats: SystemStats,
+    /// Whether the stats have been read yet
+    refreshed: bool,
+    /// Whether anything has changed since the dashboard was last rendered
+    changed: bool,
+}
+
+impl Default for SystemDashboard {
+    fn default() -> Self {
+        SystemDashboard::new()
+    }
+}
+
+impl SystemDashboard {
+    /// Creates a dashboard for the root filesystem, refreshed every second as a
+    /// screen. Its stats are first read when it's first rendered, unless they're
+    /// [refreshed](#method.refresh) before then.
+    pub fn new() -> Self {
+        SystemDashboard {
+            root: PathBuf::from("/"),
+            disk: PathBuf::from("/"),
+            interval: Duration::from_secs(1),
+            since_refresh: Duration::ZERO,
+            cpu_times: None,
+            stats: SystemStats::default(),
+            refreshed: false,
+            changed: true,
+        }
+    }
+
+    /// Reads `/proc` and `/sys` from under `root` instead, eg in a container
+    /// with the host's mounted elsewhere
+    pub fn with_root<P: AsRef<Path>>(self, root: P) -> Self {
+        SystemDashboard {
+            root: root.as_ref().to_path_buf(),
+            ..self
+        }
+    }
+
+    /// Shows the usage of the disk holding `path`
+    pub fn with_disk<P: AsRef<Path>>(self, path: P) -> Self {
+        SystemDashboard {
+            disk: path.as_ref().to_path_buf(),
+            ..self
+        }
+    }
+
+    /// Reads the stats again every `interval` as a screen
+    pub fn with_interval(self, interval: Duration) -> Self {
+        SystemDashboard { interval, ..self }
+    }
+
+    /// The stats shown, as last read, or all `None` until they're first read
+    pub fn stats(&self) -> &SystemStats {
+        &self.stats
+    }
+
+    /// Reads the stats again
+    pub fn refresh(&mut self) {
+        let read = |path: &str| fs::read_to_string(self.root.join(path)).ok();
+        let cpu_times = read("proc/stat").and_then(|stat| cpu_times(&stat));
+        // with no CPU time passed since the last reading, it still stands
+        let cpu = cpu_times.and_then(|(busy, total)| {
+            let (last_busy, last_total) = self.cpu_times.unwrap_or_default();
+            match total.saturating_sub(last_total) {
+                0 => self.stats.cpu,
+                total => Some(busy.saturating_sub(last_busy) as f32 * 100.0 / total as f32),
+            }
+        });
+        let stats = SystemStats {
+            hostname: read("proc/sys/kernel/hostname").map(|name| name.trim().to_string()),
+            ip: local_ip(),
+            cpu,
+            memory: read("proc/meminfo").and_then(|meminfo| memory(&meminfo)),
+            disk: disk(&self.disk),
+            temperature: read("sys/class/thermal/thermal_zone0/temp")
+                .and_then(|temp| temp.trim().parse::<f32>().ok())
+                .map(|millidegrees| millidegrees / 1000.0),
+        };
+        self.cpu_times = cpu_times;
+        self.refreshed = true;
+        self.since_refresh = Duration::ZERO;
+        self.changed |= stats != self.stats;
+        self.stats = stats;
+    }
+
+    /// The lines shown
+    fn lines(&self) -> [String; 6] {
+        let stats = &self.stats;
+        let or_dash = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+        [
+            or_dash(stats.hostname.clone()),
+            format!("IP {}", or_dash(stats.ip.map(|ip| ip.to_string()))),
+            format!(
+                "CPU {}",
+                or_dash(stats.cpu.map(|cpu| format!("{:.0}%", cpu)))
+            ),
+            format!(
+                "Mem {}",
+                or_dash(stats.memory.map(|(used, total)| {
+                    format!("{:.0}/{:.0}MB", used as f32 / MIB, total as f32 / MIB)
+                }))
+            ),
+            format!(
+                "Disk {}",
+                or_dash(stats.disk.map(|(used, total)| {
+                    format!("{:.1}/{:.1}GB", used as f32 / GIB, total as f32 / GIB)
+                }))
+            ),
+            format!(
+                "Temp {}",
+                or_dash(stats.temperature.map(|temp| format!("{:.1}C", temp)))
+            ),
+        ]
+    }
+}
+
+/// Busy and total time of all the CPUs, from the first line of `/proc/stat`
+fn cpu_times(stat: &str) -> Option<(u64, u64)> {
+    let mut fields = stat.lines().next()?.split_whitespace();
+    if fields.next() != Some("cpu") {
+        return None;
+    }
+    // user, nice, system, idle, iowait, irq, softirq and steal
+    let times: Vec<u64> = fields
+        .take(8)
+        .map(|field| field.parse().ok())
+        .collect::<Option<_>>()?;
+    let total: u64 = times.iter().sum();
+    let idle = times.get(3)? + times.get(4).unwrap_or(&0);
+    Some((total - idle, total))
+}
+
+/// Bytes of memory used and in all, from `/proc/meminfo`
+fn memory(meminfo: &str) -> Option<(u64, u64)> {
+    let field = |name: &str| {
+        meminfo.lines().find_map(|line| {
+            let value = line.strip_prefix(name)?.strip_prefix(':')?;
+            value
+                .trim()
+                .trim_end_matches("kB")
+                .trim()
+                .parse::<u64>()
+                .ok()
+        })
+    };
+    let (total, available) = (field("MemTotal")?, field("MemAvailable")?);
+    Some((total.saturating_sub(available) * 1024, total * 1024))
+}
+
+/// Bytes used and in all on the disk holding `path`, as `df` reports them
+fn disk(path: &Path) -> Option<(u64, u64)> {
+    let output = Command::new("df").arg("-Pk").arg(path).output().ok()?;
+    let output = String::from_utf8(output.stdout).ok()?;
+    // the filesystem, then its size, used and available space in kilobytes
+    let mut fields = output.lines().nth(1)?.split_whitespace().skip(1);
+    let total: u64 = fields.next()?.parse().ok()?;
+    let used: u64 = fields.next()?.parse().ok()?;
+    Some((used * 1024, total * 1024))
+}
+
+/// The address the host would reach the internet from. Connecting a UDP
+/// socket picks a route without sending anything.
+fn local_ip() -> Option<IpAddr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("8.8.8.8:80").ok()?;
+    Some(socket.local_addr().ok()?.ip())
+}
+
+impl Widget for SystemDashboard {
+    fn render(&mut self, frame: &mut Framebuffer, area: Rect) {
+        if !self.refreshed {
+            self.refresh();
+        }
+        frame.fill_rect(area.x, area.y, area.width, area.height, false);
+        let font = BuiltinFont::PROPORTIONAL;
+        let clip = TextRegion::new(area.x, area.y, area.width, area.height);
+        let mut rest = area;
+        for line in self.lines().iter() {
+            let (row, below) = rest.split_top(font.line_height());
+            if row.height < font.line_height() {
+                break;
+            }
+            frame.draw_text_clipped(i32::from(row.x), row.y, line, &font, &clip);
+            rest = below;
+        }
+        self.changed = false;
+    }
+
+    fn needs_render(&self) -> bool {
+        self.changed
+    }
+}
+
+#[cfg(feature = "buttons")]
+impl Screen for SystemDashboard {
+    fn handle(&mut self, _gesture: Gesture) -> Transition {
+        Transition::Stay
+    }
+
+    fn tick(&mut self, elapsed: Duration) -> Transition {
+        self.since_refresh += elapsed;
+        if self.since_refresh >= self.interval {
+            self.refresh();
+        }
+        Transition::Stay
+    }
+}
@@ -0,0 +1,814 @@
+//! Offering the display to other programs as a D-Bus service
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::os::unix::net::UnixStream;
+#[cfg(feature = "buttons")]
+use std::time::Duration;
+
+use log::warn;
+
+use crate::{is_png, Oled, OledError, OledInterface, OledResult};
+#[cfg(feature = "buttons")]
+use crate::{ButtonEvent, Input};
+
+/// Bus name the service asks for unless told otherwise, and its interface's name
+pub const DBUS_NAME: &str = "io.github.squidpickles.NanoHatOled";
+
+/// Object path the display is found at
+pub const DBUS_PATH: &str = "/io/github/squidpickles/NanoHatOled";
+
+/// The system bus, when `DBUS_SYSTEM_BUS_ADDRESS` doesn't say otherwise
+const SYSTEM_BUS: &str = "unix:path=/var/run/dbus/system_bus_socket";
+
+/// Gray level from which pixels of a grayscale image are lit
+const IMAGE_THRESHOLD: u8 = 0x80;
+
+/// Largest message taken from the bus, plenty for a grayscale image
+const MAX_MESSAGE_LEN: usize = 1 << 20;
+
+/// How often the buttons are polled while waiting for calls
+#[cfg(feature = "buttons")]
+const BUTTON_POLL: Duration = Duration::from_millis(20);
+
+/// Message types
+const METHOD_CALL: u8 = 1;
+const METHOD_RETURN: u8 = 2;
+const ERROR: u8 = 3;
+#[cfg(feature = "buttons")]
+const SIGNAL: u8 = 4;
+
+/// Header field codes
+const PATH: u8 = 1;
+const INTERFACE: u8 = 2;
+const MEMBER: u8 = 3;
+const ERROR_NAME: u8 = 4;
+const REPLY_SERIAL: u8 = 5;
+const DESTINATION: u8 = 6;
+const SENDER: u8 = 7;
+const SIGNATURE: u8 = 8;
+
+/// Flag on a method call whose caller doesn't want a reply
+const NO_REPLY_EXPECTED: u8 = 0x01;
+
+/// What the service's object offers, for `Introspect`
+const INTROSPECTION: &str = concat!(
+    r#"<!DOCTYPE node PUBLIC "-//freedesktop//DTD D-BUS Object Introspection 1.0//EN" "#,
+    r#""http://www.freedesktop.org/standards/dbus/1.0/introspect.dtd">
+<node>
+ <interface name="io.github.squidpickles.NanoHatOled">
+  <method name="ShowText">
+   <arg name="column" type="y" direction="in"/>
+   <arg name="row" type="y" direction="in"/>
+   <arg name="text" type="s" direction="in"/>
+  </method>
+  <method name="ShowImage">
+   <arg name="image" type="ay" direction="in"/>
+  </method>
+  <method name="Clear"/>
+  <method name="SetContrast">
+   <arg name="level" type="y" direction="in"/>
+  </method>
+  <signal name="ButtonPressed">
+   <arg name="key" type="s"/>
+  </signal>
+  <signal name="ButtonReleased">
+   <arg name="key" type="s"/>
+  </signal>
+ </interface>
+ <interface name="org.freedesktop.DBus.Introspectable">
+  <method name="Introspect">
+   <arg name="xml" type="s" direction="out"/>
+  </method>
+ </interface>
+ <interface name="org.freedesktop.DBus.Peer">
+  <method name="Ping"/>
+ </interface>
+</node>
+"#
+);
+
+/// Values marshalled in D-Bus's wire format, little-endian, aligned from the
+/// start of the buffer
+#[derive(Default)]
+struct Marshal(Vec<u8>);
+
+impl Marshal {
+    /// Pads to a multiple of `alignment`
+    fn align(&mut self, alignment: usize) -> &mut Self {
+        let len = self.0.len().div_ceil(alignment) * alignment;
+        self.0.resize(len, 0);
+        self
+    }
+
+    /// Appends a byte
+    fn u8(&mut self, value: u8) -> &mut Self {
+        self.0.push(value);
+        self
+    }
+
+    /// Appends a 32-bit number
+    fn u32(&mut self, value: u32) -> &mut Self {
+        self.align(4).0.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    /// Appends a string or object path
+    fn string(&mut self, value: &str) -> &mut Self {
+        self.u32(value.len() as u32)
+            .0
+            .extend_from_slice(value.as_bytes());
+        self.u8(0)
+    }
+
+    /// Appends a type signature
+    fn signature(&mut self, value: &str) -> &mut Self {
+        self.u8(value.len() as u8)
+            .0
+            .extend_from_slice(value.as_bytes());
+        self.u8(0)
+    }
+
+    /// Appends a header field holding a string-like value of type `kind`
+    fn field(&mut self, code: u8, kind: &str, value: &str) -> &mut Self {
+        self.align(8).u8(code).signature(kind);
+        match kind {
+            "g" => self.signature(value),
+            _ => self.string(value),
+        }
+    }
+}
+
+/// Puts together a message of type `kind`, with the header fields written by
+/// `fields` and a body of type `signature`
+fn message(
+    kind: u8,
+    flags: u8,
+    serial: u32,
+    fields: impl FnOnce(&mut Marshal),
+    signature: &str,
+    body: &[u8],
+) -> Vec<u8> {
+    let mut header = Marshal::default();
+    header.u8(b'l').u8(kind).u8(flags).u8(1);
+    header.u32(body.len() as u32).u32(serial);
+    // the fields' array, whose length is filled in once they're written
+    header.u32(0);
+    let start = header.0.len().div_ceil(8) * 8;
+    fields(&mut header);
+    if !signature.is_empty() {
+        header.field(SIGNATURE, "g", signature);
+    }
+    let len = (header.0.len() - start) as u32;
+    header.0[12..16].copy_from_slice(&len.to_le_bytes());
+    header.align(8).0.extend_from_slice(body);
+    header.0
+}
+
+/// Reads values in D-Bus's wire format, aligned from the start of the message
+struct Unmarshal<'a> {
+    /// The whole message
+    data: &'a [u8],
+    /// Next byte to read
+    at: usize,
+    /// Whether numbers are big-endian
+    big_endian: bool,
+}
+
+impl<'a> Unmarshal<'a> {
+    /// Skips padding to a multiple of `alignment`
+    fn align(&mut self, alignment: usize) -> Option<()> {
+        self.at = self.at.div_ceil(alignment) * alignment;
+        (self.at <= self.data.len()).then_some(())
+    }
+
+    /// Reads `len` bytes
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self.data.get(self.at..self.at.checked_add(len)?)?;
+        self.at += len;
+        Some(bytes)
+    }
+
+    /// Reads a byte
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.bytes(1)?[0])
+    }
+
+    /// Reads a 32-bit number
+    fn u32(&mut self) -> Option<u32> {
+        self.align(4)?;
+        let bytes = self.bytes(4)?;
+        let bytes = [bytes[0], bytes[1], bytes[2], bytes[3]];
+        Some(match self.big_endian {
+            true => u32::from_be_bytes(bytes),
+            false => u32::from_le_bytes(bytes),
+        })
+    }
+
+    /// Reads a string or object path
+    fn string(&mut self) -> Option<&'a str> {
+        let len = self.u32()? as usize;
+        let string = core::str::from_utf8(self.bytes(len)?).ok()?;
+        self.u8()?;
+        Some(string)
+    }
+
+    /// Reads a type signature
+    fn signature(&mut self) -> Option<&'a str> {
+        let len = usize::from(self.u8()?);
+        let signature = core::str::from_utf8(self.bytes(len)?).ok()?;
+        self.u8()?;
+        Some(signature)
+    }
+
+    /// Skips a value of a single basic type, as in a header field this doesn't use
+    fn skip(&mut self, kind: &str) -> Option<()> {
+        match kind {
+            "y" => self.bytes(1).map(drop),
+            "n" | "q" => self.align(2).and_then(|_| self.bytes(2)).map(drop),
+            "b" | "i" | "u" | "h" => self.u32().map(drop),
+            "x" | "t" | "d" => self.align(8).and_then(|_| self.bytes(8)).map(drop),
+            "s" | "o" => self.string().map(drop),
+            "g" => self.signature().map(drop),
+            _ => None,
+        }
+    }
+}
+
+/// A message from the bus
+#[derive(Debug, Default)]
+struct Message {
+    /// Message type
+    kind: u8,
+    /// Flags, eg `NO_REPLY_EXPECTED`
+    flags: u8,
+    /// Serial number, for replies to refer to
+    serial: u32,
+    /// Object called
+    path: String,
+    /// Interface called
+    interface: String,
+    /// Method called
+    member: String,
+    /// Name of the error, for errors
+    error_name: String,
+    /// Serial number of the message this replies to
+    reply_serial: u32,
+    /// Where this came from, for replies to go back to
+    sender: String,
+    /// Type of the body
+    signature: String,
+    /// Whether numbers in the body are big-endian
+    big_endian: bool,
+    /// The body
+    body: Vec<u8>,
+}
+
+impl Message {
+    /// Reads a message from `reader`, or returns `None` if the bus has gone.
+    /// `first` is the message's first byte, already read.
+    fn read<R: Read>(reader: &mut R, first: u8) -> io::Result<Self> {
+        let malformed = || io::Error::new(io::ErrorKind::InvalidData, "malformed D-Bus message");
+        let mut data = vec![first; 16];
+        reader.read_exact(&mut data[1..])?;
+        let big_endian = match first {
+            b'l' => false,
+            b'B' => true,
+            _ => return Err(malformed()),
+        };
+        let mut fixed = Unmarshal {
+            data: &data,
+            at: 4,
+            big_endian,
+        };
+        let (body_len, _, fields_len) = (
+            fixed.u32().ok_or_else(malformed)? as usize,
+            fixed.u32().ok_or_else(malformed)?,
+            fixed.u32().ok_or_else(malformed)? as usize,
+        );
+        let header_len = (16 + fields_len).div_ceil(8) * 8;
+        if header_len.saturating_add(body_len) > MAX_MESSAGE_LEN {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "D-Bus message too long",
+            ));
+        }
+        data.resize(header_len + body_len, 0);
+        reader.read_exact(&mut data[16..])?;
+        Message::parse(&data, fields_len, header_len, big_endian).ok_or_else(malformed)
+    }
+
+    /// Reads the header fields and body of a whole message
+    fn parse(data: &[u8], fields_len: usize, header_len: usize, big_endian: bool) -> Option<Self> {
+        let mut message = Message {
+            kind: data[1],
+            flags: data[2],
+            big_endian,
+            body: data[header_len..].to_vec(),
+            ..Message::default()
+        };
+        let mut fields = Unmarshal {
+            data,
+            at: 8,
+            big_endian,
+        };
+        message.serial = fields.u32()?;
+        fields.at = 16;
+        while fields.at < 16 + fields_len {
+            fields.align(8)?;
+            let code = fields.u8()?;
+            let kind = fields.signature()?;
+            match (code, kind) {
+                (PATH, "o") => message.path = fields.string()?.into(),
+                (INTERFACE, "s") => message.interface = fields.string()?.into(),
+                (MEMBER, "s") => message.member = fields.string()?.into(),
+                (ERROR_NAME, "s") => message.error_name = fields.string()?.into(),
+                (REPLY_SERIAL, "u") => message.reply_serial = fields.u32()?,
+                (SENDER, "s") => message.sender = fields.string()?.into(),
+                (SIGNATURE, "g") => message.signature = fields.signature()?.into(),
+                _ => fields.skip(kind)?,
+            }
+        }
+        Some(message)
+    }
+
+    /// Reads the body from its start, as a message's body is aligned from there
+    fn body(&self) -> Unmarshal<'_> {
+        Unmarshal {
+            data: &self.body,
+            at: 0,
+            big_endian: self.big_endian,
+        }
+    }
+}
+
+/// Why a method call wasn't carried out
+enum Refusal {
+    /// The call itself was wrong, with the D-Bus error name and message to reply with
+    Call(&'static str, String),
+    /// The display failed
+    Display(OledError),
+}
+
+impl From<OledError> for Refusal {
+    fn from(err: OledError) -> Self {
+        match err {
+            OledError::InvalidArgument(_)
+            | OledError::InvalidDimensions
+            | OledError::OutOfBounds => {
+                Refusal::Call("org.freedesktop.DBus.Error.InvalidArgs", err.to_string())
+            }
+            err => Refusal::Display(err),
+        }
+    }
+}
+
+/// Connects to the bus at a D-Bus address, eg `unix:path=/run/dbus/system_bus_socket`
+fn connect(address: &str) -> Result<UnixStream, OledError> {
+    for address in address.split(';') {
+        let Some(params) = address.strip_prefix("unix:") else {
+            continue;
+        };
+        for (key, value) in params.split(',').filter_map(|param| param.split_once('=')) {
+            let value = unescape(value);
+            match key {
+                "path" => return Ok(UnixStream::connect(value)?),
+                #[cfg(target_os = "linux")]
+                "abstract" => {
+                    use std::os::linux::net::SocketAddrExt;
+                    let addr =
+                        std::os::unix::net::SocketAddr::from_abstract_name(value.as_bytes())?;
+                    return Ok(UnixStream::connect_addr(&addr)?);
+                }
+                _ => {}
+            }
+        }
+    }
+    Err(OledError::InvalidArgument(
+        "D-Bus address has no Unix socket path this can connect to",
+    ))
+}
+
+/// Undoes the `%` escapes in a value in a D-Bus address
+fn unescape(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut unescaped = Vec::with_capacity(bytes.len());
+    let mut at = 0;
+    while at < bytes.len() {
+        let hex = bytes
+            .get(at + 1..at + 3)
+            .and_then(|hex| core::str::from_utf8(hex).ok());
+        match (
+            bytes[at],
+            hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()),
+        ) {
+            (b'%', Some(byte)) => {
+                unescaped.push(byte);
+                at += 3;
+            }
+            (byte, _) => {
+                unescaped.push(byte);
+                at += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&unescaped).into_owned()
+}
+
+/// Whether a read gave up because nothing came before its timeout
+#[cfg(feature = "buttons")]
+fn is_timeout(err: &io::Error) -> bool {
+    matches!(
+        err.kind(),
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+    )
+}
+
+/// The display, offered to other programs on the system or session D-Bus, as
+/// is usual for Linux desktops and services. The object at
+/// [`DBUS_PATH`](constant.DBUS_PATH.html) has an interface named
+/// [`DBUS_NAME`](constant.DBUS_NAME.html), with the methods:
+/// - `ShowText(y column, y row, s text)` writes text from a text column and row
+/// - `ShowImage(ay image)` shows a PNG image, drawn at the top left as for
+///   [`Oled::draw_png()`](struct.Oled.html#method.draw_png), or a whole-screen
+///   image, packed one bit per pixel by row as for
+///   [`ImageFormat::RowMajor`](enum.ImageFormat.html#variant.RowMajor), or one
+///   byte per pixel, lit from gray level 128 up
+/// - `Clear()` clears the display
+/// - `SetContrast(y level)` sets the contrast
+///
+/// With the `buttons` feature, and buttons given with
+/// [`with_buttons()`](#method.with_buttons), the `ButtonPressed(s key)` and
+/// `ButtonReleased(s key)` signals say when `K1`, `K2` or `K3` changes.
+///
+/// The service speaks the D-Bus protocol itself, so needs no libraries. Calls
+/// that can't be carried out get an `InvalidArgs` error back; only failing to
+/// reach the display, or losing the bus, stop it.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{DbusService, Oled, OledResult};
+/// # fn main() -> OledResult {
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// // then, eg: busctl call io.github.squidpickles.NanoHatOled \
+/// //     /io/github/squidpickles/NanoHatOled io.github.squidpickles.NanoHatOled \
+/// //     ShowText yys 0 0 Hello
+/// DbusService::new(oled).serve_system()
+/// # }
+/// ```
+pub struct DbusService<I> {
+    /// The display being offered
+    oled: Oled<I>,
+    /// Bus name asked for
+    name: String,
+    /// Buttons whose presses are signalled
+    #[cfg(feature = "buttons")]
+    buttons: Option<Box<dyn Input>>,
+    /// Serial number of the last message sent
+    serial: u32,
+}
+
+impl<I: OledInterface> DbusService<I> {
+    /// Offers an already-initialized display under the bus name
+    /// [`DBUS_NAME`](constant.DBUS_NAME.html)
+    pub fn new(oled: Oled<I>) -> Self {
+        DbusService {
+            oled,
+            name: DBUS_NAME.into(),
+            #[cfg(feature = "buttons")]
+            buttons: None,
+            serial: 0,
+        }
+    }
+
+    /// Asks for the bus name `name` instead, eg to offer two displays. The
+    /// object's path and interface stay the same.
+    pub fn with_name(self, name: &str) -> Self {
+        DbusService {
+            name: name.into(),
+            ..self
+        }
+    }
+
+    /// Signals presses and releases of `buttons`, or any other
+    /// [`Input`](trait.Input.html)
+    #[cfg(feature = "buttons")]
+    pub fn with_buttons<B: Input + 'static>(self, buttons: B) -> Self {
+        DbusService {
+            buttons: Some(Box::new(buttons)),
+            ..self
+        }
+    }
+
+    /// Offers the display on the system bus, which needs a policy file in
+    /// `/etc/dbus-1/system.d` allowing the name to be owned. Only returns on an
+    /// error.
+    pub fn serve_system(&mut self) -> OledResult {
+        let address =
+            std::env::var("DBUS_SYSTEM_BUS_ADDRESS").unwrap_or_else(|_| SYSTEM_BUS.into());
+        self.serve_address(&address)
+    }
+
+    /// Offers the display on the session bus of the user running the program.
+    /// Only returns on an error.
+    pub fn serve_session(&mut self) -> OledResult {
+        let address = std::env::var("DBUS_SESSION_BUS_ADDRESS")
+            .map_err(|_| OledError::InvalidArgument("DBUS_SESSION_BUS_ADDRESS isn't set"))?;
+        self.serve_address(&address)
+    }
+
+    /// Offers the display on the bus at `address`, as D-Bus writes them, eg
+    /// `unix:path=/run/user/1000/bus`. Returns once the bus closes the
+    /// connection, or on an error, with `I2c` for connection problems as for
+    /// other I/O.
+    pub fn serve_address(&mut self, address: &str) -> OledResult {
+        let mut stream = connect(address)?;
+        authenticate(&mut stream)?;
+        self.call_bus(&mut stream, "Hello", "", &[])?;
+        let mut body = Marshal::default();
+        // don't queue for the name if something else has it
+        body.string(&self.name).u32(4);
+        let reply = self.call_bus(&mut stream, "RequestName", "su", &body.0)?;
+        if !matches!(reply.body().u32(), Some(1) | Some(4)) {
+            return Err(io::Error::new(
+                io::ErrorKind::AddrInUse,
+                format!("D-Bus name {} is already taken", self.name),
+            )
+            .into());
+        }
+        while let Some(message) = self.next_message(&mut stream)? {
+            if message.kind == METHOD_CALL {
+                self.answer(&mut stream, &message)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Calls a method of the bus itself, returning its reply
+    fn call_bus(
+        &mut self,
+        stream: &mut UnixStream,
+        member: &str,
+        signature: &str,
+        body: &[u8],
+    ) -> Result<Message, OledError> {
+        let serial = self.next_serial();
+        let call = message(
+            METHOD_CALL,
+            0,
+            serial,
+            |fields| {
+                fields
+                    .field(PATH, "o", "/org/freedesktop/DBus")
+                    .field(INTERFACE, "s", "org.freedesktop.DBus")
+                    .field(MEMBER, "s", member)
+                    .field(DESTINATION, "s", "org.freedesktop.DBus");
+            },
+            signature,
+            body,
+        );
+        stream.write_all(&call)?;
+        // signals, such as NameAcquired, may come before the reply
+        loop {
+            let mut first = [0];
+            if stream.read(&mut first)? == 0 {
+                return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+            }
+            let reply = Message::read(stream, first[0])?;
+            match reply.kind {
+                METHOD_RETURN if reply.reply_serial == serial => return Ok(reply),
+                ERROR if reply.reply_serial == serial => {
+                    let message = format!("D-Bus {} failed: {}", member, reply.error_name);
+                    return Err(io::Error::new(io::ErrorKind::PermissionDenied, message).into());
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Waits for the next message from the bus, signalling button presses
+    /// meanwhile. Returns `None` once the bus has closed the connection.
+    fn next_message(&mut self, stream: &mut UnixStream) -> Result<Option<Message>, OledError> {
+        let mut first = [0];
+        if self.read_first(stream, &mut first)? == 0 {
+            return Ok(None);
+        }
+        Ok(Some(Message::read(stream, first[0])?))
+    }
+
+    /// Reads the first byte of the next message
+    #[cfg(not(feature = "buttons"))]
+    fn read_first(
+        &mut self,
+        stream: &mut UnixStream,
+        first: &mut [u8],
+    ) -> Result<usize, OledError> {
+        Ok(stream.read(first)?)
+    }
+
+    /// Reads the first byte of the next message, polling the buttons while
+    /// waiting for it
+    #[cfg(feature = "buttons")]
+    fn read_first(
+        &mut self,
+        stream: &mut UnixStream,
+        first: &mut [u8],
+    ) -> Result<usize, OledError> {
+        stream.set_read_timeout(self.buttons.as_ref().map(|_| BUTTON_POLL))?;
+        let read = loop {
+            match stream.read(first) {
+                Err(err) if is_timeout(&err) => self.signal_buttons(stream)?,
+                read => break read?,
+            }
+        };
+        // the rest of the message follows straight on
+        stream.set_read_timeout(None)?;
+        Ok(read)
+    }
+
+    /// Sends a signal for each button pressed or let go since last polled
+    #[cfg(feature = "buttons")]
+    fn signal_buttons(&mut self, stream: &mut UnixStream) -> OledResult {
+        let events: Vec<ButtonEvent> = match self.buttons.as_mut() {
+            Some(buttons) => buttons.poll_events()?,
+            None => return Ok(()),
+        };
+        for event in events {
+            let member = match event {
+                ButtonEvent::Press(_) => "ButtonPressed",
+                ButtonEvent::Release(_) => "ButtonReleased",
+            };
+            let mut body = Marshal::default();
+            body.string(&format!("{:?}", event.key()));
+            let serial = self.next_serial();
+            let signal = message(
+                SIGNAL,
+                0,
+                serial,
+                |fields| {
+                    fields
+                        .field(PATH, "o", DBUS_PATH)
+                        .field(INTERFACE, "s", DBUS_NAME)
+                        .field(MEMBER, "s", member);
+                },
+                "s",
+                &body.0,
+            );
+            stream.write_all(&signal)?;
+        }
+        Ok(())
+    }
+
+    /// Carries out a method call and replies to it. Returns an error only if
+    /// the display fails, having replied with a `Failed` error.
+    fn answer(&mut self, stream: &mut UnixStream, call: &Message) -> OledResult {
+        let (reply, failure) = match self.carry_out(call) {
+            Ok(xml) => (Ok(xml), None),
+            Err(Refusal::Call(name, text)) => {
+                warn!("Refusing D-Bus call to {}: {}", call.member, text);
+                (Err((name, text)), None)
+            }
+            Err(Refusal::Display(err)) => (
+                Err(("org.freedesktop.DBus.Error.Failed", err.to_string())),
+                Some(err),
+            ),
+        };
+        if call.flags & NO_REPLY_EXPECTED == 0 {
+            let mut body = Marshal::default();
+            let (kind, error_name, signature) = match &reply {
+                Ok(None) => (METHOD_RETURN, None, ""),
+                Ok(Some(xml)) => {
+                    body.string(xml);
+                    (METHOD_RETURN, None, "s")
+                }
+                Err((name, text)) => {
+                    body.string(text);
+                    (ERROR, Some(*name), "s")
+                }
+            };
+            let fields = |fields: &mut Marshal| {
+                if let Some(name) = error_name {
+                    fields.field(ERROR_NAME, "s", name);
+                }
+                fields
+                    .align(8)
+                    .u8(REPLY_SERIAL)
+                    .signature("u")
+                    .u32(call.serial);
+                if !call.sender.is_empty() {
+                    fields.field(DESTINATION, "s", &call.sender);
+                }
+            };
+            let serial = self.next_serial();
+            stream.write_all(&message(kind, 0, serial, fields, signature, &body.0))?;
+        }
+        failure.map_or(Ok(()), Err)
+    }
+
+    /// Carries out a method call, returning the introspection data if that's
+    /// what was asked for
+    fn carry_out(&mut self, call: &Message) -> Result<Option<&'static str>, Refusal> {
+        let invalid = || {
+            let message = format!("wrong arguments for {}", call.member);
+            Refusal::Call("org.freedesktop.DBus.Error.InvalidArgs", message)
+        };
+        if call.path != DBUS_PATH {
+            let message = format!("no object at {}", call.path);
+            return Err(Refusal::Call(
+                "org.freedesktop.DBus.Error.UnknownObject",
+                message,
+            ));
+        }
+        let mut args = call.body();
+        // the interface can be left out, as the members' names don't clash
+        let interface = match call.interface.as_str() {
+            "" => None,
+            interface => Some(interface),
+        };
+        let ours = matches!(interface, None | Some(DBUS_NAME));
+        match (ours, call.member.as_str(), call.signature.as_str()) {
+            (true, "ShowText", "yys") => {
+                let (column, row) = (
+                    args.u8().ok_or_else(invalid)?,
+                    args.u8().ok_or_else(invalid)?,
+                );
+                let text = args.string().ok_or_else(invalid)?;
+                self.oled.set_text_xy(column, row)?;
+                self.oled.put_string(text)?;
+            }
+            (true, "ShowImage", "ay") => {
+                let len = args.u32().ok_or_else(invalid)? as usize;
+                let image = args.bytes(len).ok_or_else(invalid)?;
+                if is_png(image) {
+                    self.oled.draw_png(image, 0, 0)?;
+                } else {
+                    self.oled.draw_image_by_len(image, IMAGE_THRESHOLD)?;
+                }
+            }
+            (true, "Clear", "") => self.oled.clear_display()?,
+            (true, "SetContrast", "y") => self.oled.set_contrast(args.u8().ok_or_else(invalid)?)?,
+            (true, "ShowText", _)
+            | (true, "ShowImage", _)
+            | (true, "Clear", _)
+            | (true, "SetContrast", _) => return Err(invalid()),
+            (_, "Introspect", "")
+                if matches!(
+                    interface,
+                    None | Some("org.freedesktop.DBus.Introspectable")
+                ) =>
+            {
+                return Ok(Some(INTROSPECTION))
+            }
+            (_, "Ping", "") if matches!(interface, None | Some("org.freedesktop.DBus.Peer")) => {}
+            _ => {
+                let message = format!("no method {} taking '{}'", call.member, call.signature);
+                return Err(Refusal::Call(
+                    "org.freedesktop.DBus.Error.UnknownMethod",
+                    message,
+                ));
+            }
+        }
+        Ok(None)
+    }
+
+    /// The serial number for the next message sent
+    fn next_serial(&mut self) -> u32 {
+        self.serial = self.serial.wrapping_add(1).max(1);
+        self.serial
+    }
+
+    /// Returns the display, eg for sending commands directly
+    pub fn oled(&mut self) -> &mut Oled<I> {
+        &mut self.oled
+    }
+
+    /// Consumes the service, returning the display
+    pub fn into_oled(self) -> Oled<I> {
+        self.oled
+    }
+}
+
+/// Sends the bus its authentication, as the user the program runs as, which
+/// the bus finds from the socket itself
+fn authenticate(stream: &mut UnixStream) -> OledResult {
+    stream.write_all(b"\0AUTH EXTERNAL\r\n")?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.by_ref().take(1024).read_line(&mut line)? == 0 {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
+        if line.starts_with("DATA") {
+            // no identity of its own, so the one the socket gives is used
+            stream.write_all(b"DATA\r\n")?;
+        } else if line.starts_with("OK ") {
+            stream.write_all(b"BEGIN\r\n")?;
+            return Ok(());
+        } else {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "D-Bus refused the connection",
+            )
+            .into());
+        }
+    }
+}
@@ -0,0 +1,191 @@
+//! Reducing grayscale images to the display's one bit per pixel
+use crate::{Image, Oled, OledError, OledInterface, OledResult, OLED_HEIGHT, OLED_WIDTH};
+
+/// Number of pixels in a full-screen image
+pub(crate) const IMAGE_SIZE: usize = (OLED_WIDTH * OLED_HEIGHT) as usize;
+
+/// 4x4 Bayer matrix, giving the order pixels in each tile light up as gray increases
+pub(crate) const BAYER_4X4: [[u8; 4]; 4] =
+    [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// How a grayscale image is turned into lit and unlit pixels,
+/// as used by [`Oled::draw_image_dithered()`](struct.Oled.html#method.draw_image_dithered)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Dither {
+    /// Pixels greater than or equal to the threshold are lit, as in
+    /// [`Oled::draw_image()`](struct.Oled.html#method.draw_image).
+    /// Best for line art and text.
+    Threshold(u8),
+    /// As for `Threshold`, with the threshold picked from the image itself by
+    /// [`Threshold::Auto`](enum.Threshold.html#variant.Auto)
+    AutoThreshold,
+    /// Floyd–Steinberg error diffusion: the difference between each pixel and what
+    /// is shown is spread over its neighbours. Best for photos.
+    FloydSteinberg,
+    /// Ordered dithering with a 4x4 Bayer matrix, giving a regular cross-hatch
+    /// pattern. Unlike error diffusion, changing one part of an image doesn't
+    /// disturb the rest, which suits animations.
+    Bayer,
+}
+
+/// The gray level from which pixels are lit when an image is reduced to one bit
+/// per pixel. [`level()`](#method.level) gives the level to pass to
+/// [`Oled::draw_image()`](struct.Oled.html#method.draw_image) and the like.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{Oled, OledResult, Threshold};
+/// # fn main() -> OledResult {
+/// # let scan = [0u8; 128 * 64];
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// oled.draw_image(&scan, Threshold::Auto.level(&scan))?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Threshold {
+    /// Pixels at this gray level or above are lit
+    Fixed(u8),
+    /// The level is picked from the image's histogram by Otsu's method, splitting
+    /// its pixels into the two groups that are each as uniform as can be, eg the
+    /// ink and the paper of a scanned logo, whatever their shades. An image of a
+    /// single gray level is split at mid-gray.
+    Auto,
+}
+
+impl Threshold {
+    /// The gray level from which pixels of `image` are lit
+    pub fn level(self, image: &Image) -> u8 {
+        match self {
+            Threshold::Fixed(level) => level,
+            Threshold::Auto => otsu_level(image),
+        }
+    }
+}
+
+impl Default for Threshold {
+    fn default() -> Self {
+        Threshold::Fixed(0x80)
+    }
+}
+
+/// Picks the level splitting `image` into dark and light pixels with the most
+/// variance between the two groups, by Otsu's method
+fn otsu_level(image: &Image) -> u8 {
+    let mut histogram = [0u64; 256];
+    for pixel in image {
+        histogram[usize::from(*pixel)] += 1;
+    }
+    let total = image.len() as u64;
+    let sum: u64 = histogram
+        .iter()
+        .enumerate()
+        .map(|(level, count)| level as u64 * count)
+        .sum();
+    let (mut dark, mut dark_sum) = (0u64, 0u64);
+    let (mut best, mut best_variance) = (None, 0.0f64);
+    for (level, count) in histogram.iter().enumerate() {
+        dark += count;
+        dark_sum += level as u64 * count;
+        let light = total - dark;
+        if dark == 0 {
+            continue;
+        }
+        if light == 0 {
+            break;
+        }
+        let dark_mean = dark_sum as f64 / dark as f64;
+        let light_mean = (sum - dark_sum) as f64 / light as f64;
+        let difference = dark_mean - light_mean;
+        let variance = dark as f64 * light as f64 * difference * difference;
+        if variance > best_variance {
+            best = Some(level);
+            best_variance = variance;
+        }
+    }
+    // the light group starts just above the darkest level of it
+    best.map_or(0x80, |level| level as u8 + 1)
+}
+
+/// Dithers an image `width` pixels wide, with no more pixels than the display,
+/// into one where every pixel is either 0 or 255, at the start of the array
+/// returned
+pub(crate) fn dither(
+    image: &Image,
+    width: usize,
+    dither: Dither,
+) -> Result<[u8; IMAGE_SIZE], OledError> {
+    if width == 0 || image.len() > IMAGE_SIZE || !image.len().is_multiple_of(width) {
+        return Err(OledError::InvalidDimensions);
+    }
+    let mut output = [0u8; IMAGE_SIZE];
+    match dither {
+        Dither::Threshold(_) | Dither::AutoThreshold => {
+            let threshold = match dither {
+                Dither::Threshold(threshold) => threshold,
+                _ => Threshold::Auto.level(image),
+            };
+            for (out, pixel) in output.iter_mut().zip(image) {
+                *out = if *pixel >= threshold { 255 } else { 0 };
+            }
+        }
+        Dither::Bayer => {
+            for (index, (out, pixel)) in output.iter_mut().zip(image).enumerate() {
+                let (x, y) = (index % width, index / width);
+                let threshold = u16::from(BAYER_4X4[y % 4][x % 4]) * 16 + 8;
+                *out = if u16::from(*pixel) >= threshold {
+                    255
+                } else {
+                    0
+                };
+            }
+        }
+        Dither::FloydSteinberg => {
+            let mut levels = [0i16; IMAGE_SIZE];
+            for (level, pixel) in levels.iter_mut().zip(image) {
+                *level = i16::from(*pixel);
+            }
+            let height = image.len() / width;
+            for y in 0..height {
+                for x in 0..width {
+                    let index = y * width + x;
+                    let shown = if levels[index] >= 128 { 255 } else { 0 };
+                    let error = levels[index] - shown;
+                    output[index] = shown as u8;
+                    let mut spread = |dx: isize, dy: usize, sixteenths: i16| {
+                        let nx = x as isize + dx;
+                        if nx >= 0 && (nx as usize) < width && y + dy < height {
+                            levels[(y + dy) * width + nx as usize] += error * sixteenths / 16;
+                        }
+                    };
+                    spread(1, 0, 7);
+                    spread(-1, 1, 3);
+                    spread(0, 1, 5);
+                    spread(1, 1, 1);
+                }
+            }
+        }
+    }
+    Ok(output)
+}
+
+impl<I: OledInterface> Oled<I> {
+    /// Writes a grayscale image to the screen, like
+    /// [`draw_image()`](struct.Oled.html#method.draw_image), but using `dither`
+    /// to choose which pixels are lit, so that photos keep their shading.
+    /// # Example:
+    /// ```no_run
+    /// # use nanohat_oled::{Dither, Oled, OledResult};
+    /// # fn main() -> OledResult {
+    /// # let photo = [0u8; 128 * 64];
+    /// let mut oled = Oled::from_path("/dev/i2c-0")?;
+    /// oled.init()?;
+    /// oled.draw_image_dithered(&photo, Dither::FloydSteinberg)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn draw_image_dithered(&mut self, image: &Image, dither_mode: Dither) -> OledResult {
+        let dithered = dither(image, self.dimensions().0 as usize, dither_mode)?;
+        self.draw_image(&dithered[..image.len()], 0x80)
+    }
+}
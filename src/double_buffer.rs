@@ -0,0 +1,106 @@
+//! Drawing a whole frame off-screen, then showing it all at once
+use crate::{Framebuffer, Oled, OledInterface, OledResult};
+
+/// A display drawn on through a back buffer, so that a frame put together from
+/// many drawing calls is never seen half-drawn. Drawing only touches the back
+/// buffer; [`present()`](#method.present) sends it, making it what the display
+/// shows.
+///
+/// By default only what differs from what the display shows is sent, as
+/// [`Oled::flush()`](struct.Oled.html#method.flush) does, and the back buffer
+/// keeps the frame just presented, to draw the next one on top of.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{DoubleBuffer, Oled, OledResult};
+/// # fn main() -> OledResult {
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// let mut screen = DoubleBuffer::new(oled).with_clear(true);
+/// for x in 0..100 {
+///     let frame = screen.back();
+///     frame.draw_rect(0, 0, 128, 64, true);
+///     frame.fill_circle(x + 14, 32, 10, true);
+///     screen.present()?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct DoubleBuffer<I> {
+    /// The display, holding what it shows
+    oled: Oled<I>,
+    /// The frame being drawn
+    back: Framebuffer,
+    /// Whether only what changed is sent
+    diff: bool,
+    /// Whether the back buffer is cleared after presenting
+    clear: bool,
+}
+
+impl<I: OledInterface> DoubleBuffer<I> {
+    /// Draws on `oled` through a back buffer, starting with what it currently shows
+    pub fn new(oled: Oled<I>) -> Self {
+        let back = Framebuffer::with_layout(*oled.framebuffer(), oled.rotation().is_portrait());
+        DoubleBuffer {
+            oled,
+            back,
+            diff: true,
+            clear: false,
+        }
+    }
+
+    /// Sends only what has changed when presenting, if `diff`, or the whole
+    /// frame otherwise, eg after the display may have been reset. Default is on.
+    pub fn with_diff(self, diff: bool) -> Self {
+        DoubleBuffer { diff, ..self }
+    }
+
+    /// Starts each frame blank after presenting, if `clear`, for drawing every
+    /// frame from scratch. Default is off, keeping the frame just presented.
+    pub fn with_clear(self, clear: bool) -> Self {
+        DoubleBuffer { clear, ..self }
+    }
+
+    /// The frame being drawn, which takes the display's orientation as it was
+    /// when the buffer was created or last presented
+    pub fn back(&mut self) -> &mut Framebuffer {
+        &mut self.back
+    }
+
+    /// Calls `f` with the frame being drawn; nothing is sent until
+    /// [`present()`](#method.present)
+    pub fn draw<F: FnOnce(&mut Framebuffer)>(&mut self, f: F) {
+        f(&mut self.back);
+    }
+
+    /// Sends the frame being drawn to the display, making it what the display
+    /// shows. If the display has been turned since, the frame is dropped, and the
+    /// next starts from what the display shows, the new way round, without
+    /// sending anything.
+    pub fn present(&mut self) -> OledResult {
+        let portrait = self.oled.rotation().is_portrait();
+        if self.back.is_portrait() == portrait {
+            if self.diff {
+                self.oled.flush(&self.back)?;
+            } else {
+                self.oled.flush_all(&self.back)?;
+            }
+        }
+        self.back = Framebuffer::with_layout(*self.oled.framebuffer(), portrait);
+        if self.clear {
+            self.back.clear();
+        }
+        Ok(())
+    }
+
+    /// The display, eg to change the contrast. Anything drawn on it directly is
+    /// overwritten by the next [`present()`](#method.present).
+    pub fn oled(&mut self) -> &mut Oled<I> {
+        &mut self.oled
+    }
+
+    /// Consumes the buffer, returning the display. Anything drawn but not
+    /// presented isn't sent.
+    pub fn into_inner(self) -> Oled<I> {
+        self.oled
+    }
+}
@@ -0,0 +1,103 @@
+//! Errors returned by display operations
+use core::fmt;
+
+use crate::{OLED_HEIGHT, OLED_WIDTH};
+
+/// An error that occurred while driving the display
+#[derive(Debug)]
+pub enum OledError {
+    /// The I2C bus reported an error
+    #[cfg(feature = "std")]
+    I2c(std::io::Error),
+    /// Reading the buttons' GPIOs or an input device, or setting the SPI
+    /// interface's D/C GPIO, failed
+    #[cfg(any(feature = "buttons", feature = "spi"))]
+    Gpio(std::io::Error),
+    /// The SPI device reported an error
+    #[cfg(feature = "spi")]
+    Spi(std::io::Error),
+    /// The underlying interface failed to deliver bytes to the display.
+    /// Used by interfaces that have no richer error to report.
+    Interface,
+    /// Image data did not match the dimensions of the display
+    InvalidDimensions,
+    /// A value passed to a command was outside the range the display accepts
+    InvalidArgument(&'static str),
+    /// A position was outside the display, eg a text cell past the last column
+    OutOfBounds,
+    /// The built-in font has no glyph for a character
+    UnsupportedGlyph(char),
+}
+
+impl OledError {
+    /// Whether the error may go away if the same bytes are sent again, as with
+    /// a glitch on the bus, rather than coming from what was asked for or a
+    /// device that isn't there. [`Oled`](struct.Oled.html) only retries these.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            #[cfg(feature = "std")]
+            OledError::I2c(err) => is_transient_io(err),
+            #[cfg(any(feature = "buttons", feature = "spi"))]
+            OledError::Gpio(err) => is_transient_io(err),
+            #[cfg(feature = "spi")]
+            OledError::Spi(err) => is_transient_io(err),
+            OledError::Interface => true,
+            _ => false,
+        }
+    }
+}
+
+/// Whether an I/O error may be down to a glitch, rather than a missing or
+/// unsuitable device, or a lack of permission to use it
+#[cfg(feature = "std")]
+fn is_transient_io(err: &std::io::Error) -> bool {
+    use std::io::ErrorKind;
+    !matches!(
+        err.kind(),
+        ErrorKind::NotFound
+            | ErrorKind::PermissionDenied
+            | ErrorKind::InvalidInput
+            | ErrorKind::Unsupported
+    )
+}
+
+impl fmt::Display for OledError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            #[cfg(feature = "std")]
+            OledError::I2c(err) => write!(f, "I2C error: {}", err),
+            #[cfg(any(feature = "buttons", feature = "spi"))]
+            OledError::Gpio(err) => write!(f, "GPIO error: {}", err),
+            #[cfg(feature = "spi")]
+            OledError::Spi(err) => write!(f, "SPI error: {}", err),
+            OledError::Interface => write!(f, "display interface error"),
+            OledError::InvalidDimensions => {
+                write!(f, "Image dimensions must be {}x{}", OLED_WIDTH, OLED_HEIGHT)
+            }
+            OledError::InvalidArgument(reason) => write!(f, "invalid argument: {}", reason),
+            OledError::OutOfBounds => write!(f, "position is outside the display"),
+            OledError::UnsupportedGlyph(char) => write!(f, "no glyph for character {:?}", char),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for OledError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            OledError::I2c(err) => Some(err),
+            #[cfg(any(feature = "buttons", feature = "spi"))]
+            OledError::Gpio(err) => Some(err),
+            #[cfg(feature = "spi")]
+            OledError::Spi(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for OledError {
+    fn from(err: std::io::Error) -> Self {
+        OledError::I2c(err)
+    }
+}
@@ -0,0 +1,180 @@
+//! USB keypads and rotary encoders, read through Linux's evdev interface
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::Path;
+
+use crate::{ButtonEvent, Input, Key, OledError};
+
+/// Length of a `struct input_event`: its time, as two `long`s, then its type,
+/// code and value
+const EVENT_LEN: usize = 2 * core::mem::size_of::<usize>() + 8;
+
+/// Event types, from `linux/input-event-codes.h`
+const EV_KEY: u16 = 0x01;
+const EV_REL: u16 = 0x02;
+
+/// Key codes mapped unless told otherwise: up and left, down and right, and
+/// enter, select and OK
+const DEFAULT_KEYS: [(u16, Key); 8] = [
+    (103, Key::K1),
+    (105, Key::K1),
+    (108, Key::K2),
+    (106, Key::K2),
+    (28, Key::K3),
+    (96, Key::K3),
+    (353, Key::K3),
+    (352, Key::K3),
+];
+
+/// Relative axes mapped unless told otherwise, as turned by rotary encoders
+/// and scroll wheels: the dial, turning clockwise for down, and the wheel,
+/// scrolling up for up
+const DEFAULT_AXES: [(u16, Key, Key); 2] = [(0x07, Key::K1, Key::K2), (0x08, Key::K2, Key::K1)];
+
+/// Flag for opening a file so reads don't wait, which differs between architectures
+#[cfg(any(target_arch = "mips", target_arch = "mips64"))]
+const O_NONBLOCK: i32 = 0x80;
+#[cfg(target_arch = "sparc64")]
+const O_NONBLOCK: i32 = 0x4000;
+#[cfg(not(any(target_arch = "mips", target_arch = "mips64", target_arch = "sparc64")))]
+const O_NONBLOCK: i32 = 0o4000;
+
+/// A keypad, rotary encoder or other input device read through its
+/// `/dev/input/event*` file, standing in for the NanoHat's three buttons
+/// wherever an [`Input`](trait.Input.html) is taken, eg by
+/// [`App`](struct.App.html).
+///
+/// Keys are mapped onto `K1`, `K2` and `K3`, which by the NanoHat's custom
+/// are up, down and select: by default the up and left arrow keys press `K1`,
+/// down and right press `K2`, and enter, select and OK press `K3`. A step of a
+/// dial or wheel, as rotary encoders usually report, presses and lets go of
+/// `K1` one way and `K2` the other. Other keys, eg escape for going back, can
+/// be mapped with [`with_key()`](#method.with_key) onto whichever key the
+/// app's screens go back with.
+///
+/// Other programs reading the device see its events too. Auto-repeats from the
+/// device are dropped, as a [`GestureDetector`](struct.GestureDetector.html)
+/// makes repeats of its own.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{EvdevInput, GestureDetector, Key, OledError};
+/// # use std::{thread::sleep, time::Duration};
+/// # fn main() -> Result<(), OledError> {
+/// // escape as K1 too, for screens that go back on it
+/// let mut keypad =
+///     EvdevInput::new("/dev/input/by-id/usb-keypad-event-kbd")?.with_key(1, Key::K1);
+/// let mut gestures = GestureDetector::new();
+/// loop {
+///     for gesture in gestures.poll(&mut keypad)? {
+///         println!("{:?}", gesture);
+///     }
+///     sleep(Duration::from_millis(10));
+/// }
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct EvdevInput {
+    /// The device's event file, opened not to wait for events
+    device: File,
+    /// Which key each key code presses
+    keys: Vec<(u16, Key)>,
+    /// Which keys each relative axis presses as it decreases, and increases
+    axes: Vec<(u16, Key, Key)>,
+    /// The start of an event not yet read in full
+    partial: Vec<u8>,
+}
+
+impl EvdevInput {
+    /// Opens the input device at `path`, eg `/dev/input/event0`, or one of the
+    /// stabler names under `/dev/input/by-id`, with the default mapping. This
+    /// usually needs membership of the `input` group.
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<Self, OledError> {
+        let device = OpenOptions::new()
+            .read(true)
+            .custom_flags(O_NONBLOCK)
+            .open(path)
+            .map_err(OledError::Gpio)?;
+        Ok(EvdevInput {
+            device,
+            keys: DEFAULT_KEYS.to_vec(),
+            axes: DEFAULT_AXES.to_vec(),
+            partial: Vec::new(),
+        })
+    }
+
+    /// Maps the key with code `code`, as in `linux/input-event-codes.h`, eg 1
+    /// for escape, onto `key`, in place of what it was mapped onto before
+    pub fn with_key(mut self, code: u16, key: Key) -> Self {
+        self.keys.retain(|(mapped, _)| *mapped != code);
+        self.keys.push((code, key));
+        self
+    }
+
+    /// Maps the relative axis with code `code`, eg 0 for the X axis some rotary
+    /// encoders report, onto pressing `decrease` for each step it decreases,
+    /// eg turning anticlockwise, and `increase` for each step it increases
+    pub fn with_axis(mut self, code: u16, decrease: Key, increase: Key) -> Self {
+        self.axes.retain(|(mapped, _, _)| *mapped != code);
+        self.axes.push((code, decrease, increase));
+        self
+    }
+
+    /// Turns one event into the presses and releases it makes
+    fn translate(&self, kind: u16, code: u16, value: i32, events: &mut Vec<ButtonEvent>) {
+        match kind {
+            EV_KEY => {
+                let key = self.keys.iter().find(|(mapped, _)| *mapped == code);
+                match (key, value) {
+                    (Some((_, key)), 1) => events.push(ButtonEvent::Press(*key)),
+                    (Some((_, key)), 0) => events.push(ButtonEvent::Release(*key)),
+                    // repeats while held, and keys not mapped
+                    _ => {}
+                }
+            }
+            EV_REL => {
+                let axis = self.axes.iter().find(|(mapped, _, _)| *mapped == code);
+                if let Some((_, decrease, increase)) = axis {
+                    let key = if value < 0 { *decrease } else { *increase };
+                    for _ in 0..value.unsigned_abs() {
+                        events.push(ButtonEvent::Press(key));
+                        events.push(ButtonEvent::Release(key));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Input for EvdevInput {
+    fn poll_events(&mut self) -> Result<Vec<ButtonEvent>, OledError> {
+        let mut data = std::mem::take(&mut self.partial);
+        let mut buffer = [0; 64 * EVENT_LEN];
+        loop {
+            match self.device.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(len) => data.extend_from_slice(&buffer[..len]),
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => break,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(OledError::Gpio(err)),
+            }
+        }
+        let mut events = Vec::new();
+        let mut records = data.chunks_exact(EVENT_LEN);
+        for record in &mut records {
+            let at = EVENT_LEN - 8;
+            let kind = u16::from_ne_bytes([record[at], record[at + 1]]);
+            let code = u16::from_ne_bytes([record[at + 2], record[at + 3]]);
+            let value = i32::from_ne_bytes([
+                record[at + 4],
+                record[at + 5],
+                record[at + 6],
+                record[at + 7],
+            ]);
+            self.translate(kind, code, value, &mut events);
+        }
+        self.partial = records.remainder().to_vec();
+        Ok(events)
+    }
+}
@@ -0,0 +1,244 @@
+//! Fading the display in and out by ramping its contrast, or by the controller itself
+use core::time::Duration;
+
+use crate::{Command, Oled, OledError, OledInterface, OledResult};
+
+/// Number of contrast changes a fade is made of
+const FADE_STEPS: u32 = 32;
+
+/// A fade of the display from its contrast down to darkness, then turned off,
+/// or from darkness, turned on, back up to its contrast, by ramping it in steps.
+///
+/// Since the contrast is all that changes, what's on the display is untouched.
+/// [`Oled::contrast()`](struct.Oled.html#method.contrast) is the same before
+/// and after either fade: a fade out sets it back once the display is off, and
+/// a fade in ramps up to it, so fading out and in again returns to where it was.
+///
+/// Like an [`Animation`](struct.Animation.html), a fade doesn't read a clock
+/// itself: [`tick()`](#method.tick) is told how much time has passed, so it
+/// can be driven from any event loop, including on `no_std`. With `std`,
+/// [`Oled::fade_out()`](struct.Oled.html#method.fade_out) and
+/// [`fade_in()`](struct.Oled.html#method.fade_in) do the waiting too.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{Fade, Oled, OledResult};
+/// # use std::{thread::sleep, time::Duration};
+/// # fn main() -> OledResult {
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// let mut fade = Fade::fade_out(Duration::from_secs(1));
+/// let mut elapsed = Duration::ZERO;
+/// while let Some(wait) = fade.tick(&mut oled, elapsed)? {
+///     // do other work in the meantime
+///     sleep(wait);
+///     elapsed = wait;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Fade {
+    /// Whether the display fades out, rather than in
+    out: bool,
+    /// How long the fade takes
+    duration: Duration,
+    /// Time since the fade started
+    elapsed: Duration,
+    /// Contrast faded from or to, read from the display on the first tick
+    level: Option<u8>,
+    /// Step last shown, if any
+    step: Option<u32>,
+}
+
+impl Fade {
+    /// Creates a fade from the display's contrast down to darkness over
+    /// `duration`, after which the display is turned off
+    pub fn fade_out(duration: Duration) -> Self {
+        Fade::new(true, duration)
+    }
+
+    /// Creates a fade that turns the display on in darkness, then brings it up
+    /// to its contrast over `duration`
+    pub fn fade_in(duration: Duration) -> Self {
+        Fade::new(false, duration)
+    }
+
+    /// Creates a fade in either direction
+    fn new(out: bool, duration: Duration) -> Self {
+        Fade {
+            out,
+            duration,
+            elapsed: Duration::ZERO,
+            level: None,
+            step: None,
+        }
+    }
+
+    /// Whether the fade has run its course
+    pub fn is_finished(&self) -> bool {
+        self.step == Some(FADE_STEPS)
+    }
+
+    /// Moves the fade on by `elapsed`, the time since the last tick, and sets
+    /// the contrast that is then due, if it isn't already set. The first tick
+    /// starts the fade, so it can be passed `Duration::ZERO`.
+    ///
+    /// Returns how long until the contrast next changes, or `None` once the
+    /// fade has finished.
+    pub fn tick<I: OledInterface>(
+        &mut self,
+        oled: &mut Oled<I>,
+        elapsed: Duration,
+    ) -> Result<Option<Duration>, OledError> {
+        if self.is_finished() {
+            return Ok(None);
+        }
+        let level = *self.level.get_or_insert(oled.contrast());
+        self.elapsed += elapsed;
+        let step = if self.elapsed >= self.duration {
+            FADE_STEPS
+        } else {
+            (self.elapsed.as_nanos() * u128::from(FADE_STEPS) / self.duration.as_nanos()) as u32
+        };
+        if self.step != Some(step) {
+            let lit = if self.out { FADE_STEPS - step } else { step };
+            let contrast = u32::from(level) * lit / FADE_STEPS;
+            oled.set_contrast(contrast as u8)?;
+            if !self.out && self.step.is_none() {
+                oled.send_command(Command::DisplayOn)?;
+            }
+            if self.out && step == FADE_STEPS {
+                oled.send_command(Command::DisplayOff)?;
+                oled.set_contrast(level)?;
+            }
+            self.step = Some(step);
+        }
+        if self.is_finished() {
+            Ok(None)
+        } else {
+            Ok(Some(self.duration * (step + 1) / FADE_STEPS - self.elapsed))
+        }
+    }
+}
+
+/// The time each step of the controller's own fading takes, in frames, as used
+/// by [`FadeMode`](enum.FadeMode.html). There are 16 steps from full contrast
+/// to dark. The frame rate depends on the clock divide setting; with the
+/// settings in [`Oled::init()`](struct.Oled.html#method.init), it is around 100 Hz.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FadeInterval {
+    /// 8 frames per step
+    Frames8,
+    /// 16 frames per step
+    Frames16,
+    /// 24 frames per step
+    Frames24,
+    /// 32 frames per step
+    Frames32,
+    /// 40 frames per step
+    Frames40,
+    /// 48 frames per step
+    Frames48,
+    /// 56 frames per step
+    Frames56,
+    /// 64 frames per step
+    Frames64,
+    /// 72 frames per step
+    Frames72,
+    /// 80 frames per step
+    Frames80,
+    /// 88 frames per step
+    Frames88,
+    /// 96 frames per step
+    Frames96,
+    /// 104 frames per step
+    Frames104,
+    /// 112 frames per step
+    Frames112,
+    /// 120 frames per step
+    Frames120,
+    /// 128 frames per step
+    Frames128,
+}
+
+impl From<FadeInterval> for u8 {
+    fn from(interval: FadeInterval) -> u8 {
+        interval as u8
+    }
+}
+
+/// What the controller does to the display's contrast by itself, without a
+/// software timer, as set by [`Oled::set_fade_mode()`](struct.Oled.html#method.set_fade_mode)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FadeMode {
+    /// The display is shown at its contrast. (default)
+    Off,
+    /// The contrast falls step by step until the display is dark, and stays dark
+    FadeOut(FadeInterval),
+    /// The contrast falls step by step until the display is dark, then rises
+    /// back, over and over, eg to draw attention to an alert
+    Blink(FadeInterval),
+}
+
+impl From<FadeMode> for u8 {
+    fn from(mode: FadeMode) -> u8 {
+        match mode {
+            FadeMode::Off => 0x00,
+            FadeMode::FadeOut(interval) => 0x20 | u8::from(interval),
+            FadeMode::Blink(interval) => 0x30 | u8::from(interval),
+        }
+    }
+}
+
+impl<I: OledInterface> Oled<I> {
+    /// Sets the controller fading out or blinking the display by itself, or
+    /// stops it and goes back to showing the display at its contrast. RAM and
+    /// [`contrast()`](struct.Oled.html#method.contrast) are left as they are.
+    /// # Example:
+    /// ```no_run
+    /// # use nanohat_oled::{FadeInterval, FadeMode, Oled, OledResult};
+    /// # fn main() -> OledResult {
+    /// let mut oled = Oled::from_path("/dev/i2c-0")?;
+    /// oled.init()?;
+    /// oled.put_string("Disk full!")?;
+    /// oled.set_fade_mode(FadeMode::Blink(FadeInterval::Frames8))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_fade_mode(&mut self, mode: FadeMode) -> OledResult {
+        self.send_command(Command::SetFadeMode)?;
+        self.send_command(mode)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I: OledInterface> Oled<I> {
+    /// Fades the display from its contrast down to darkness over `duration`,
+    /// then turns it off, blocking until done. Its contrast is set back once
+    /// it's off, ready for [`fade_in()`](struct.Oled.html#method.fade_in).
+    /// See [`Fade`](struct.Fade.html) to fade without blocking.
+    pub fn fade_out(&mut self, duration: Duration) -> OledResult {
+        self.fade(Fade::fade_out(duration))
+    }
+
+    /// Turns the display on in darkness, then brings it up to its contrast over
+    /// `duration`, blocking until done.
+    /// See [`Fade`](struct.Fade.html) to fade without blocking.
+    pub fn fade_in(&mut self, duration: Duration) -> OledResult {
+        self.fade(Fade::fade_in(duration))
+    }
+
+    /// Runs a fade to the end, sleeping between steps
+    fn fade(&mut self, mut fade: Fade) -> OledResult {
+        let mut last = std::time::Instant::now();
+        let mut elapsed = Duration::ZERO;
+        while let Some(wait) = fade.tick(self, elapsed)? {
+            std::thread::sleep(wait);
+            let now = std::time::Instant::now();
+            elapsed = now - last;
+            last = now;
+        }
+        Ok(())
+    }
+}
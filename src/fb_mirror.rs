@@ -0,0 +1,231 @@
+//! Mirroring the Linux framebuffer console or desktop onto the display
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::thread::sleep;
+use std::time::Duration;
+
+use crate::{Dither, Oled, OledError, OledInterface, OledResult};
+
+/// Where Linux describes each framebuffer device
+const GRAPHICS_ROOT: &str = "/sys/class/graphics";
+
+/// Size and layout of a framebuffer's memory
+#[derive(Clone, Copy, Debug)]
+struct Geometry {
+    /// Width in pixels
+    width: usize,
+    /// Height in pixels
+    height: usize,
+    /// Bits per pixel: 8, 16, 24 or 32
+    bits_per_pixel: usize,
+    /// Bytes from the start of one row to the next
+    stride: usize,
+}
+
+impl Geometry {
+    /// Reads the geometry of framebuffer `device`, eg `/dev/fb0`, from sysfs
+    fn read(device: &Path) -> Result<Self, OledError> {
+        let name = device
+            .file_name()
+            .ok_or(OledError::InvalidArgument("not a framebuffer device"))?;
+        let dir = Path::new(GRAPHICS_ROOT).join(name);
+        let read = |file: &str| fs::read_to_string(dir.join(file));
+        let number = |value: &str| {
+            value
+                .trim()
+                .parse()
+                .map_err(|_| OledError::InvalidArgument("unreadable framebuffer geometry"))
+        };
+        let size = read("virtual_size")?;
+        let (width, height) = size.split_once(',').ok_or(OledError::InvalidArgument(
+            "unreadable framebuffer geometry",
+        ))?;
+        let (width, height) = (number(width)?, number(height)?);
+        let bits_per_pixel = number(&read("bits_per_pixel")?)?;
+        let stride = match read("stride") {
+            Ok(stride) => number(&stride)?,
+            // older kernels leave it out, for rows with no padding
+            Err(_) => width * bits_per_pixel / 8,
+        };
+        Ok(Geometry {
+            width,
+            height,
+            bits_per_pixel,
+            stride,
+        })
+    }
+}
+
+/// The gray level of a pixel's bytes, in the layouts Linux uses on little-endian
+/// machines: blue, green, red and unused for 32 bits, and RGB565 for 16
+fn gray(pixel: &[u8]) -> u8 {
+    let luma = |red: u32, green: u32, blue: u32| ((77 * red + 150 * green + 29 * blue) >> 8) as u8;
+    match *pixel {
+        [blue, green, red, _] | [blue, green, red] => {
+            luma(u32::from(red), u32::from(green), u32::from(blue))
+        }
+        [low, high] => {
+            let rgb = u32::from(u16::from_le_bytes([low, high]));
+            luma(
+                (rgb >> 11) * 255 / 31,
+                (rgb >> 5 & 0x3f) * 255 / 63,
+                (rgb & 0x1f) * 255 / 31,
+            )
+        }
+        [gray] => gray,
+        _ => 0,
+    }
+}
+
+/// Copies a region of a Linux framebuffer device, such as `/dev/fb0`, onto the
+/// display every so often, scaled down to fit and dithered. Whatever draws to
+/// the framebuffer, be it the text console, a kiosk browser or a game, then
+/// also shows on the OLED, as a second screen.
+///
+/// The framebuffer's size and depth are read from `/sys/class/graphics`, or can
+/// be given with [`with_geometry()`](#method.with_geometry). Pixels of 8, 16
+/// (RGB565), 24 and 32 bits are understood. Reading the device usually needs
+/// membership of the `video` group.
+/// # Example:
+/// ```no_run
+/// # use std::time::Duration;
+/// # use nanohat_oled::{Dither, FbMirror, Oled, OledResult};
+/// # fn main() -> OledResult {
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// // the top left quarter of a 1920x1080 desktop
+/// let mut mirror = FbMirror::new(oled)
+///     .with_region(0, 0, 960, 540)
+///     .with_dither(Dither::FloydSteinberg);
+/// mirror.run(Duration::from_millis(200))
+/// # }
+/// ```
+pub struct FbMirror<I> {
+    /// The display mirrored to
+    oled: Oled<I>,
+    /// The framebuffer device mirrored
+    device: PathBuf,
+    /// The framebuffer's geometry, if given rather than read from sysfs
+    geometry: Option<Geometry>,
+    /// The region mirrored, as left, top, width and height, or all of it if `None`
+    region: Option<(usize, usize, usize, usize)>,
+    /// How gray levels are turned into lit pixels
+    dither: Dither,
+}
+
+impl<I: OledInterface> FbMirror<I> {
+    /// Mirrors all of `/dev/fb0` onto an already-initialized display, with
+    /// ordered dithering, which keeps still parts still while others change
+    pub fn new(oled: Oled<I>) -> Self {
+        FbMirror {
+            oled,
+            device: PathBuf::from("/dev/fb0"),
+            geometry: None,
+            region: None,
+            dither: Dither::Bayer,
+        }
+    }
+
+    /// Mirrors another framebuffer device, eg `/dev/fb1`
+    pub fn with_device<P: AsRef<Path>>(self, device: P) -> Self {
+        FbMirror {
+            device: device.as_ref().to_path_buf(),
+            ..self
+        }
+    }
+
+    /// Takes the framebuffer to be `width` by `height` pixels of `bits_per_pixel`
+    /// bits, with `stride` bytes from one row to the next, rather than reading
+    /// that from sysfs, eg for a copy of one saved to a file
+    pub fn with_geometry(
+        self,
+        width: usize,
+        height: usize,
+        bits_per_pixel: usize,
+        stride: usize,
+    ) -> Self {
+        let geometry = Geometry {
+            width,
+            height,
+            bits_per_pixel,
+            stride,
+        };
+        FbMirror {
+            geometry: Some(geometry),
+            ..self
+        }
+    }
+
+    /// Mirrors only the `width` by `height` pixels with their top left corner at
+    /// `x`, `y`, eg one window of a desktop, or the visible part of a
+    /// framebuffer kept twice the screen's height for page flipping
+    pub fn with_region(self, x: usize, y: usize, width: usize, height: usize) -> Self {
+        FbMirror {
+            region: Some((x, y, width, height)),
+            ..self
+        }
+    }
+
+    /// Turns gray levels into lit pixels with `dither`, rather than with
+    /// ordered dithering
+    pub fn with_dither(self, dither: Dither) -> Self {
+        FbMirror { dither, ..self }
+    }
+
+    /// Copies the region onto the display once. Returns `OutOfBounds` if the
+    /// region isn't all within the framebuffer, `InvalidDimensions` if it's
+    /// empty, and `InvalidArgument` for a depth that isn't understood.
+    pub fn mirror(&mut self) -> OledResult {
+        let geometry = match self.geometry {
+            Some(geometry) => geometry,
+            None => Geometry::read(&self.device)?,
+        };
+        let bytes = match geometry.bits_per_pixel {
+            8 | 16 | 24 | 32 => geometry.bits_per_pixel / 8,
+            _ => {
+                return Err(OledError::InvalidArgument(
+                    "framebuffer depth isn't 8, 16, 24 or 32 bits",
+                ))
+            }
+        };
+        let (x, y, width, height) = self
+            .region
+            .unwrap_or((0, 0, geometry.width, geometry.height));
+        if width == 0 || height == 0 {
+            return Err(OledError::InvalidDimensions);
+        }
+        if x + width > geometry.width || y + height > geometry.height {
+            return Err(OledError::OutOfBounds);
+        }
+        let mut device = File::open(&self.device)?;
+        let mut row = vec![0; width * bytes];
+        let mut pixels = Vec::with_capacity(width * height);
+        for line in y..y + height {
+            device.seek(SeekFrom::Start((line * geometry.stride + x * bytes) as u64))?;
+            device.read_exact(&mut row)?;
+            pixels.extend(row.chunks_exact(bytes).map(gray));
+        }
+        self.oled
+            .draw_image_fit(&pixels, width, height, self.dither)
+    }
+
+    /// Copies the region onto the display every `interval`. Only returns on an
+    /// error.
+    pub fn run(&mut self, interval: Duration) -> OledResult {
+        loop {
+            self.mirror()?;
+            sleep(interval);
+        }
+    }
+
+    /// Returns the display, eg for sending commands directly
+    pub fn oled(&mut self) -> &mut Oled<I> {
+        &mut self.oled
+    }
+
+    /// Consumes the mirror, returning the display
+    pub fn into_oled(self) -> Oled<I> {
+        self.oled
+    }
+}
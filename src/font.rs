@@ -1,110 +1,286 @@
-//! A simple built-in font that handles printable ASCII
-/// A simple built-in font that handles printable ASCII
+//! A simple built-in font that handles printable ASCII and the rest of code page 437
+/// A simple built-in font that handles printable ASCII, and the rest of the IBM PC's
+/// code page 437, eg accented letters, box drawing characters, arrows and block
+/// elements, looked up by their Unicode code points
 pub struct BasicFont;
 
 impl BasicFont {
+    /// The empty box drawn for characters without a bitmap of their own
+    pub const MISSING: [u8; 8] = [0xff, 0x81, 0x81, 0x81, 0x81, 0x81, 0x81, 0xff];
+
     /// Returns an 8x8 bitmap in graphics RAM format. If the character
-    /// does not have a bitmap (usually true for those outside printable
-    /// ASCII), it will return an empty box.
+    /// does not have a bitmap (true for those outside printable ASCII
+    /// and code page 437), it will return an empty box.
     pub fn bitmap(char: char) -> [u8; 8] {
+        Self::glyph(char).unwrap_or(Self::MISSING)
+    }
+
+    /// Returns an 8x8 bitmap in graphics RAM format, or `None` if the
+    /// character does not have one.
+    pub fn glyph(char: char) -> Option<[u8; 8]> {
         match char {
-            ' ' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
-            '!' => [0x00, 0x00, 0x5F, 0x00, 0x00, 0x00, 0x00, 0x00],
-            '"' => [0x00, 0x00, 0x07, 0x00, 0x07, 0x00, 0x00, 0x00],
-            '#' => [0x00, 0x14, 0x7F, 0x14, 0x7F, 0x14, 0x00, 0x00],
-            '$' => [0x00, 0x24, 0x2A, 0x7F, 0x2A, 0x12, 0x00, 0x00],
-            '%' => [0x00, 0x23, 0x13, 0x08, 0x64, 0x62, 0x00, 0x00],
-            '&' => [0x00, 0x36, 0x49, 0x55, 0x22, 0x50, 0x00, 0x00],
-            '\'' => [0x00, 0x00, 0x05, 0x03, 0x00, 0x00, 0x00, 0x00],
-            '(' => [0x00, 0x1C, 0x22, 0x41, 0x00, 0x00, 0x00, 0x00],
-            ')' => [0x00, 0x41, 0x22, 0x1C, 0x00, 0x00, 0x00, 0x00],
-            '*' => [0x00, 0x08, 0x2A, 0x1C, 0x2A, 0x08, 0x00, 0x00],
-            '+' => [0x00, 0x08, 0x08, 0x3E, 0x08, 0x08, 0x00, 0x00],
-            ',' => [0x00, 0xA0, 0x60, 0x00, 0x00, 0x00, 0x00, 0x00],
-            '-' => [0x00, 0x08, 0x08, 0x08, 0x08, 0x08, 0x00, 0x00],
-            '.' => [0x00, 0x60, 0x60, 0x00, 0x00, 0x00, 0x00, 0x00],
-            '/' => [0x00, 0x20, 0x10, 0x08, 0x04, 0x02, 0x00, 0x00],
-            '0' => [0x00, 0x3E, 0x51, 0x49, 0x45, 0x3E, 0x00, 0x00],
-            '1' => [0x00, 0x00, 0x42, 0x7F, 0x40, 0x00, 0x00, 0x00],
-            '2' => [0x00, 0x62, 0x51, 0x49, 0x49, 0x46, 0x00, 0x00],
-            '3' => [0x00, 0x22, 0x41, 0x49, 0x49, 0x36, 0x00, 0x00],
-            '4' => [0x00, 0x18, 0x14, 0x12, 0x7F, 0x10, 0x00, 0x00],
-            '5' => [0x00, 0x27, 0x45, 0x45, 0x45, 0x39, 0x00, 0x00],
-            '6' => [0x00, 0x3C, 0x4A, 0x49, 0x49, 0x30, 0x00, 0x00],
-            '7' => [0x00, 0x01, 0x71, 0x09, 0x05, 0x03, 0x00, 0x00],
-            '8' => [0x00, 0x36, 0x49, 0x49, 0x49, 0x36, 0x00, 0x00],
-            '9' => [0x00, 0x06, 0x49, 0x49, 0x29, 0x1E, 0x00, 0x00],
-            ':' => [0x00, 0x00, 0x36, 0x36, 0x00, 0x00, 0x00, 0x00],
-            ';' => [0x00, 0x00, 0xAC, 0x6C, 0x00, 0x00, 0x00, 0x00],
-            '<' => [0x00, 0x08, 0x14, 0x22, 0x41, 0x00, 0x00, 0x00],
-            '=' => [0x00, 0x14, 0x14, 0x14, 0x14, 0x14, 0x00, 0x00],
-            '>' => [0x00, 0x41, 0x22, 0x14, 0x08, 0x00, 0x00, 0x00],
-            '?' => [0x00, 0x02, 0x01, 0x51, 0x09, 0x06, 0x00, 0x00],
-            '@' => [0x00, 0x32, 0x49, 0x79, 0x41, 0x3E, 0x00, 0x00],
-            'A' => [0x00, 0x7E, 0x09, 0x09, 0x09, 0x7E, 0x00, 0x00],
-            'B' => [0x00, 0x7F, 0x49, 0x49, 0x49, 0x36, 0x00, 0x00],
-            'C' => [0x00, 0x3E, 0x41, 0x41, 0x41, 0x22, 0x00, 0x00],
-            'D' => [0x00, 0x7F, 0x41, 0x41, 0x22, 0x1C, 0x00, 0x00],
-            'E' => [0x00, 0x7F, 0x49, 0x49, 0x49, 0x41, 0x00, 0x00],
-            'F' => [0x00, 0x7F, 0x09, 0x09, 0x09, 0x01, 0x00, 0x00],
-            'G' => [0x00, 0x3E, 0x41, 0x41, 0x51, 0x72, 0x00, 0x00],
-            'H' => [0x00, 0x7F, 0x08, 0x08, 0x08, 0x7F, 0x00, 0x00],
-            'I' => [0x00, 0x41, 0x7F, 0x41, 0x00, 0x00, 0x00, 0x00],
-            'J' => [0x00, 0x20, 0x40, 0x41, 0x3F, 0x01, 0x00, 0x00],
-            'K' => [0x00, 0x7F, 0x08, 0x14, 0x22, 0x41, 0x00, 0x00],
-            'L' => [0x00, 0x7F, 0x40, 0x40, 0x40, 0x40, 0x00, 0x00],
-            'M' => [0x00, 0x7F, 0x02, 0x0C, 0x02, 0x7F, 0x00, 0x00],
-            'N' => [0x00, 0x7F, 0x04, 0x08, 0x10, 0x7F, 0x00, 0x00],
-            'O' => [0x00, 0x3E, 0x41, 0x41, 0x41, 0x3E, 0x00, 0x00],
-            'P' => [0x00, 0x7F, 0x09, 0x09, 0x09, 0x06, 0x00, 0x00],
-            'Q' => [0x00, 0x3E, 0x41, 0x51, 0x21, 0x5E, 0x00, 0x00],
-            'R' => [0x00, 0x7F, 0x09, 0x19, 0x29, 0x46, 0x00, 0x00],
-            'S' => [0x00, 0x26, 0x49, 0x49, 0x49, 0x32, 0x00, 0x00],
-            'T' => [0x00, 0x01, 0x01, 0x7F, 0x01, 0x01, 0x00, 0x00],
-            'U' => [0x00, 0x3F, 0x40, 0x40, 0x40, 0x3F, 0x00, 0x00],
-            'V' => [0x00, 0x1F, 0x20, 0x40, 0x20, 0x1F, 0x00, 0x00],
-            'W' => [0x00, 0x3F, 0x40, 0x38, 0x40, 0x3F, 0x00, 0x00],
-            'X' => [0x00, 0x63, 0x14, 0x08, 0x14, 0x63, 0x00, 0x00],
-            'Y' => [0x00, 0x03, 0x04, 0x78, 0x04, 0x03, 0x00, 0x00],
-            'Z' => [0x00, 0x61, 0x51, 0x49, 0x45, 0x43, 0x00, 0x00],
-            '[' => [0x00, 0x7F, 0x41, 0x41, 0x00, 0x00, 0x00, 0x00],
-            '\\' => [0x00, 0x02, 0x04, 0x08, 0x10, 0x20, 0x00, 0x00],
-            ']' => [0x00, 0x41, 0x41, 0x7F, 0x00, 0x00, 0x00, 0x00],
-            '^' => [0x00, 0x04, 0x02, 0x01, 0x02, 0x04, 0x00, 0x00],
-            '_' => [0x00, 0x80, 0x80, 0x80, 0x80, 0x80, 0x00, 0x00],
-            '`' => [0x00, 0x01, 0x02, 0x04, 0x00, 0x00, 0x00, 0x00],
-            'a' => [0x00, 0x20, 0x54, 0x54, 0x54, 0x78, 0x00, 0x00],
-            'b' => [0x00, 0x7F, 0x48, 0x44, 0x44, 0x38, 0x00, 0x00],
-            'c' => [0x00, 0x38, 0x44, 0x44, 0x28, 0x00, 0x00, 0x00],
-            'd' => [0x00, 0x38, 0x44, 0x44, 0x48, 0x7F, 0x00, 0x00],
-            'e' => [0x00, 0x38, 0x54, 0x54, 0x54, 0x18, 0x00, 0x00],
-            'f' => [0x00, 0x08, 0x7E, 0x09, 0x02, 0x00, 0x00, 0x00],
-            'g' => [0x00, 0x18, 0xA4, 0xA4, 0xA4, 0x7C, 0x00, 0x00],
-            'h' => [0x00, 0x7F, 0x08, 0x04, 0x04, 0x78, 0x00, 0x00],
-            'i' => [0x00, 0x00, 0x7D, 0x00, 0x00, 0x00, 0x00, 0x00],
-            'j' => [0x00, 0x80, 0x84, 0x7D, 0x00, 0x00, 0x00, 0x00],
-            'k' => [0x00, 0x7F, 0x10, 0x28, 0x44, 0x00, 0x00, 0x00],
-            'l' => [0x00, 0x41, 0x7F, 0x40, 0x00, 0x00, 0x00, 0x00],
-            'm' => [0x00, 0x7C, 0x04, 0x18, 0x04, 0x78, 0x00, 0x00],
-            'n' => [0x00, 0x7C, 0x08, 0x04, 0x7C, 0x00, 0x00, 0x00],
-            'o' => [0x00, 0x38, 0x44, 0x44, 0x38, 0x00, 0x00, 0x00],
-            'p' => [0x00, 0xFC, 0x24, 0x24, 0x18, 0x00, 0x00, 0x00],
-            'q' => [0x00, 0x18, 0x24, 0x24, 0xFC, 0x00, 0x00, 0x00],
-            'r' => [0x00, 0x00, 0x7C, 0x08, 0x04, 0x00, 0x00, 0x00],
-            's' => [0x00, 0x48, 0x54, 0x54, 0x24, 0x00, 0x00, 0x00],
-            't' => [0x00, 0x04, 0x7F, 0x44, 0x00, 0x00, 0x00, 0x00],
-            'u' => [0x00, 0x3C, 0x40, 0x40, 0x7C, 0x00, 0x00, 0x00],
-            'v' => [0x00, 0x1C, 0x20, 0x40, 0x20, 0x1C, 0x00, 0x00],
-            'w' => [0x00, 0x3C, 0x40, 0x30, 0x40, 0x3C, 0x00, 0x00],
-            'x' => [0x00, 0x44, 0x28, 0x10, 0x28, 0x44, 0x00, 0x00],
-            'y' => [0x00, 0x1C, 0xA0, 0xA0, 0x7C, 0x00, 0x00, 0x00],
-            'z' => [0x00, 0x44, 0x64, 0x54, 0x4C, 0x44, 0x00, 0x00],
-            '{' => [0x00, 0x08, 0x36, 0x41, 0x00, 0x00, 0x00, 0x00],
-            '|' => [0x00, 0x00, 0x7F, 0x00, 0x00, 0x00, 0x00, 0x00],
-            '}' => [0x00, 0x41, 0x36, 0x08, 0x00, 0x00, 0x00, 0x00],
-            '~' => [0x00, 0x02, 0x01, 0x01, 0x02, 0x01, 0x00, 0x00],
-            '˚' => [0x00, 0x02, 0x05, 0x05, 0x02, 0x00, 0x00, 0x00],
-            _ => [0xff, 0x81, 0x81, 0x81, 0x81, 0x81, 0x81, 0xff],
+            ' ' => Some([0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]),
+            '!' => Some([0x00, 0x00, 0x5F, 0x00, 0x00, 0x00, 0x00, 0x00]),
+            '"' => Some([0x00, 0x00, 0x07, 0x00, 0x07, 0x00, 0x00, 0x00]),
+            '#' => Some([0x00, 0x14, 0x7F, 0x14, 0x7F, 0x14, 0x00, 0x00]),
+            '$' => Some([0x00, 0x24, 0x2A, 0x7F, 0x2A, 0x12, 0x00, 0x00]),
+            '%' => Some([0x00, 0x23, 0x13, 0x08, 0x64, 0x62, 0x00, 0x00]),
+            '&' => Some([0x00, 0x36, 0x49, 0x55, 0x22, 0x50, 0x00, 0x00]),
+            '\'' => Some([0x00, 0x00, 0x05, 0x03, 0x00, 0x00, 0x00, 0x00]),
+            '(' => Some([0x00, 0x1C, 0x22, 0x41, 0x00, 0x00, 0x00, 0x00]),
+            ')' => Some([0x00, 0x41, 0x22, 0x1C, 0x00, 0x00, 0x00, 0x00]),
+            '*' => Some([0x00, 0x08, 0x2A, 0x1C, 0x2A, 0x08, 0x00, 0x00]),
+            '+' => Some([0x00, 0x08, 0x08, 0x3E, 0x08, 0x08, 0x00, 0x00]),
+            ',' => Some([0x00, 0xA0, 0x60, 0x00, 0x00, 0x00, 0x00, 0x00]),
+            '-' => Some([0x00, 0x08, 0x08, 0x08, 0x08, 0x08, 0x00, 0x00]),
+            '.' => Some([0x00, 0x60, 0x60, 0x00, 0x00, 0x00, 0x00, 0x00]),
+            '/' => Some([0x00, 0x20, 0x10, 0x08, 0x04, 0x02, 0x00, 0x00]),
+            '0' => Some([0x00, 0x3E, 0x51, 0x49, 0x45, 0x3E, 0x00, 0x00]),
+            '1' => Some([0x00, 0x00, 0x42, 0x7F, 0x40, 0x00, 0x00, 0x00]),
+            '2' => Some([0x00, 0x62, 0x51, 0x49, 0x49, 0x46, 0x00, 0x00]),
+            '3' => Some([0x00, 0x22, 0x41, 0x49, 0x49, 0x36, 0x00, 0x00]),
+            '4' => Some([0x00, 0x18, 0x14, 0x12, 0x7F, 0x10, 0x00, 0x00]),
+            '5' => Some([0x00, 0x27, 0x45, 0x45, 0x45, 0x39, 0x00, 0x00]),
+            '6' => Some([0x00, 0x3C, 0x4A, 0x49, 0x49, 0x30, 0x00, 0x00]),
+            '7' => Some([0x00, 0x01, 0x71, 0x09, 0x05, 0x03, 0x00, 0x00]),
+            '8' => Some([0x00, 0x36, 0x49, 0x49, 0x49, 0x36, 0x00, 0x00]),
+            '9' => Some([0x00, 0x06, 0x49, 0x49, 0x29, 0x1E, 0x00, 0x00]),
+            ':' => Some([0x00, 0x00, 0x36, 0x36, 0x00, 0x00, 0x00, 0x00]),
+            ';' => Some([0x00, 0x00, 0xAC, 0x6C, 0x00, 0x00, 0x00, 0x00]),
+            '<' => Some([0x00, 0x08, 0x14, 0x22, 0x41, 0x00, 0x00, 0x00]),
+            '=' => Some([0x00, 0x14, 0x14, 0x14, 0x14, 0x14, 0x00, 0x00]),
+            '>' => Some([0x00, 0x41, 0x22, 0x14, 0x08, 0x00, 0x00, 0x00]),
+            '?' => Some([0x00, 0x02, 0x01, 0x51, 0x09, 0x06, 0x00, 0x00]),
+            '@' => Some([0x00, 0x32, 0x49, 0x79, 0x41, 0x3E, 0x00, 0x00]),
+            'A' => Some([0x00, 0x7E, 0x09, 0x09, 0x09, 0x7E, 0x00, 0x00]),
+            'B' => Some([0x00, 0x7F, 0x49, 0x49, 0x49, 0x36, 0x00, 0x00]),
+            'C' => Some([0x00, 0x3E, 0x41, 0x41, 0x41, 0x22, 0x00, 0x00]),
+            'D' => Some([0x00, 0x7F, 0x41, 0x41, 0x22, 0x1C, 0x00, 0x00]),
+            'E' => Some([0x00, 0x7F, 0x49, 0x49, 0x49, 0x41, 0x00, 0x00]),
+            'F' => Some([0x00, 0x7F, 0x09, 0x09, 0x09, 0x01, 0x00, 0x00]),
+            'G' => Some([0x00, 0x3E, 0x41, 0x41, 0x51, 0x72, 0x00, 0x00]),
+            'H' => Some([0x00, 0x7F, 0x08, 0x08, 0x08, 0x7F, 0x00, 0x00]),
+            'I' => Some([0x00, 0x41, 0x7F, 0x41, 0x00, 0x00, 0x00, 0x00]),
+            'J' => Some([0x00, 0x20, 0x40, 0x41, 0x3F, 0x01, 0x00, 0x00]),
+            'K' => Some([0x00, 0x7F, 0x08, 0x14, 0x22, 0x41, 0x00, 0x00]),
+            'L' => Some([0x00, 0x7F, 0x40, 0x40, 0x40, 0x40, 0x00, 0x00]),
+            'M' => Some([0x00, 0x7F, 0x02, 0x0C, 0x02, 0x7F, 0x00, 0x00]),
+            'N' => Some([0x00, 0x7F, 0x04, 0x08, 0x10, 0x7F, 0x00, 0x00]),
+            'O' => Some([0x00, 0x3E, 0x41, 0x41, 0x41, 0x3E, 0x00, 0x00]),
+            'P' => Some([0x00, 0x7F, 0x09, 0x09, 0x09, 0x06, 0x00, 0x00]),
+            'Q' => Some([0x00, 0x3E, 0x41, 0x51, 0x21, 0x5E, 0x00, 0x00]),
+            'R' => Some([0x00, 0x7F, 0x09, 0x19, 0x29, 0x46, 0x00, 0x00]),
+            'S' => Some([0x00, 0x26, 0x49, 0x49, 0x49, 0x32, 0x00, 0x00]),
+            'T' => Some([0x00, 0x01, 0x01, 0x7F, 0x01, 0x01, 0x00, 0x00]),
+            'U' => Some([0x00, 0x3F, 0x40, 0x40, 0x40, 0x3F, 0x00, 0x00]),
+            'V' => Some([0x00, 0x1F, 0x20, 0x40, 0x20, 0x1F, 0x00, 0x00]),
+            'W' => Some([0x00, 0x3F, 0x40, 0x38, 0x40, 0x3F, 0x00, 0x00]),
+            'X' => Some([0x00, 0x63, 0x14, 0x08, 0x14, 0x63, 0x00, 0x00]),
+            'Y' => Some([0x00, 0x03, 0x04, 0x78, 0x04, 0x03, 0x00, 0x00]),
+            'Z' => Some([0x00, 0x61, 0x51, 0x49, 0x45, 0x43, 0x00, 0x00]),
+            '[' => Some([0x00, 0x7F, 0x41, 0x41, 0x00, 0x00, 0x00, 0x00]),
+            '\\' => Some([0x00, 0x02, 0x04, 0x08, 0x10, 0x20, 0x00, 0x00]),
+            ']' => Some([0x00, 0x41, 0x41, 0x7F, 0x00, 0x00, 0x00, 0x00]),
+            '^' => Some([0x00, 0x04, 0x02, 0x01, 0x02, 0x04, 0x00, 0x00]),
+            '_' => Some([0x00, 0x80, 0x80, 0x80, 0x80, 0x80, 0x00, 0x00]),
+            '`' => Some([0x00, 0x01, 0x02, 0x04, 0x00, 0x00, 0x00, 0x00]),
+            'a' => Some([0x00, 0x20, 0x54, 0x54, 0x54, 0x78, 0x00, 0x00]),
+            'b' => Some([0x00, 0x7F, 0x48, 0x44, 0x44, 0x38, 0x00, 0x00]),
+            'c' => Some([0x00, 0x38, 0x44, 0x44, 0x28, 0x00, 0x00, 0x00]),
+            'd' => Some([0x00, 0x38, 0x44, 0x44, 0x48, 0x7F, 0x00, 0x00]),
+            'e' => Some([0x00, 0x38, 0x54, 0x54, 0x54, 0x18, 0x00, 0x00]),
+            'f' => Some([0x00, 0x08, 0x7E, 0x09, 0x02, 0x00, 0x00, 0x00]),
+            'g' => Some([0x00, 0x18, 0xA4, 0xA4, 0xA4, 0x7C, 0x00, 0x00]),
+            'h' => Some([0x00, 0x7F, 0x08, 0x04, 0x04, 0x78, 0x00, 0x00]),
+            'i' => Some([0x00, 0x00, 0x7D, 0x00, 0x00, 0x00, 0x00, 0x00]),
+            'j' => Some([0x00, 0x80, 0x84, 0x7D, 0x00, 0x00, 0x00, 0x00]),
+            'k' => Some([0x00, 0x7F, 0x10, 0x28, 0x44, 0x00, 0x00, 0x00]),
+            'l' => Some([0x00, 0x41, 0x7F, 0x40, 0x00, 0x00, 0x00, 0x00]),
+            'm' => Some([0x00, 0x7C, 0x04, 0x18, 0x04, 0x78, 0x00, 0x00]),
+            'n' => Some([0x00, 0x7C, 0x08, 0x04, 0x7C, 0x00, 0x00, 0x00]),
+            'o' => Some([0x00, 0x38, 0x44, 0x44, 0x38, 0x00, 0x00, 0x00]),
+            'p' => Some([0x00, 0xFC, 0x24, 0x24, 0x18, 0x00, 0x00, 0x00]),
+            'q' => Some([0x00, 0x18, 0x24, 0x24, 0xFC, 0x00, 0x00, 0x00]),
+            'r' => Some([0x00, 0x00, 0x7C, 0x08, 0x04, 0x00, 0x00, 0x00]),
+            's' => Some([0x00, 0x48, 0x54, 0x54, 0x24, 0x00, 0x00, 0x00]),
+            't' => Some([0x00, 0x04, 0x7F, 0x44, 0x00, 0x00, 0x00, 0x00]),
+            'u' => Some([0x00, 0x3C, 0x40, 0x40, 0x7C, 0x00, 0x00, 0x00]),
+            'v' => Some([0x00, 0x1C, 0x20, 0x40, 0x20, 0x1C, 0x00, 0x00]),
+            'w' => Some([0x00, 0x3C, 0x40, 0x30, 0x40, 0x3C, 0x00, 0x00]),
+            'x' => Some([0x00, 0x44, 0x28, 0x10, 0x28, 0x44, 0x00, 0x00]),
+            'y' => Some([0x00, 0x1C, 0xA0, 0xA0, 0x7C, 0x00, 0x00, 0x00]),
+            'z' => Some([0x00, 0x44, 0x64, 0x54, 0x4C, 0x44, 0x00, 0x00]),
+            '{' => Some([0x00, 0x08, 0x36, 0x41, 0x00, 0x00, 0x00, 0x00]),
+            '|' => Some([0x00, 0x00, 0x7F, 0x00, 0x00, 0x00, 0x00, 0x00]),
+            '}' => Some([0x00, 0x41, 0x36, 0x08, 0x00, 0x00, 0x00, 0x00]),
+            '~' => Some([0x00, 0x02, 0x01, 0x01, 0x02, 0x01, 0x00, 0x00]),
+            // code page 437 graphics for the control codes, and DEL
+            '☺' => Some([0x7E, 0x81, 0x95, 0xA1, 0xA1, 0x95, 0x81, 0x7E]),
+            '☻' => Some([0x7E, 0xFF, 0xEB, 0xDF, 0xDF, 0xEB, 0xFF, 0x7E]),
+            '♥' => Some([0x0E, 0x1F, 0x3F, 0x7E, 0x3F, 0x1F, 0x0E, 0x00]),
+            '♦' => Some([0x08, 0x1C, 0x3E, 0x7F, 0x3E, 0x1C, 0x08, 0x00]),
+            '♣' => Some([0x1C, 0x1C, 0x4F, 0x7F, 0x4F, 0x1C, 0x1C, 0x00]),
+            '♠' => Some([0x18, 0x1C, 0x5E, 0x7F, 0x5E, 0x1C, 0x18, 0x00]),
+            '•' => Some([0x00, 0x00, 0x18, 0x3C, 0x3C, 0x18, 0x00, 0x00]),
+            '◘' => Some([0xFF, 0xFF, 0xE7, 0xC3, 0xC3, 0xE7, 0xFF, 0xFF]),
+            '○' => Some([0x00, 0x3C, 0x42, 0x42, 0x42, 0x42, 0x3C, 0x00]),
+            '◙' => Some([0xFF, 0xC3, 0xBD, 0xBD, 0xBD, 0xBD, 0xC3, 0xFF]),
+            '♂' => Some([0x00, 0x38, 0x44, 0x44, 0x44, 0x39, 0x07, 0x03]),
+            '♀' => Some([0x00, 0x4E, 0x51, 0xF1, 0x51, 0x4E, 0x00, 0x00]),
+            '♪' => Some([0x40, 0xE0, 0xE0, 0x7F, 0x01, 0x02, 0x04, 0x00]),
+            '♫' => Some([0x70, 0x70, 0x3F, 0x01, 0x01, 0x71, 0x71, 0x3F]),
+            '☼' => Some([0x2A, 0x1C, 0x1C, 0x77, 0x1C, 0x1C, 0x2A, 0x00]),
+            '►' => Some([0x7F, 0x3E, 0x3E, 0x1C, 0x1C, 0x08, 0x08, 0x00]),
+            '◄' => Some([0x08, 0x08, 0x1C, 0x1C, 0x3E, 0x3E, 0x7F, 0x00]),
+            '↕' => Some([0x00, 0x14, 0x22, 0x7F, 0x22, 0x14, 0x00, 0x00]),
+            '‼' => Some([0x00, 0x5F, 0x00, 0x00, 0x5F, 0x00, 0x00, 0x00]),
+            '¶' => Some([0x00, 0x06, 0x0F, 0x7F, 0x01, 0x7F, 0x00, 0x00]),
+            '§' => Some([0x00, 0x0A, 0x55, 0x55, 0x69, 0x00, 0x00, 0x00]),
+            '▬' => Some([0x70, 0x70, 0x70, 0x70, 0x70, 0x70, 0x70, 0x00]),
+            '↨' => Some([0x00, 0x94, 0xA2, 0xFF, 0xA2, 0x94, 0x00, 0x00]),
+            '↑' => Some([0x00, 0x04, 0x02, 0x7F, 0x02, 0x04, 0x00, 0x00]),
+            '↓' => Some([0x00, 0x10, 0x20, 0x7F, 0x20, 0x10, 0x00, 0x00]),
+            '→' => Some([0x08, 0x08, 0x08, 0x08, 0x2A, 0x1C, 0x08, 0x00]),
+            '←' => Some([0x08, 0x1C, 0x2A, 0x08, 0x08, 0x08, 0x08, 0x00]),
+            '∟' => Some([0x00, 0x3C, 0x20, 0x20, 0x20, 0x20, 0x00, 0x00]),
+            '↔' => Some([0x1C, 0x3E, 0x08, 0x08, 0x08, 0x3E, 0x1C, 0x00]),
+            '▲' => Some([0x20, 0x30, 0x38, 0x3C, 0x38, 0x30, 0x20, 0x00]),
+            '▼' => Some([0x04, 0x0C, 0x1C, 0x3C, 0x1C, 0x0C, 0x04, 0x00]),
+            '⌂' => Some([0x70, 0x48, 0x44, 0x42, 0x44, 0x48, 0x70, 0x00]),
+            // code page 437 accented letters, currency and punctuation
+            'Ç' => Some([0x00, 0x3E, 0x41, 0xC1, 0x41, 0x22, 0x00, 0x00]),
+            'ü' => Some([0x00, 0x3D, 0x40, 0x40, 0x7D, 0x00, 0x00, 0x00]),
+            'é' => Some([0x00, 0x38, 0x54, 0x56, 0x55, 0x18, 0x00, 0x00]),
+            'â' => Some([0x00, 0x20, 0x56, 0x55, 0x56, 0x78, 0x00, 0x00]),
+            'ä' => Some([0x00, 0x20, 0x55, 0x54, 0x55, 0x78, 0x00, 0x00]),
+            'à' => Some([0x00, 0x20, 0x55, 0x56, 0x54, 0x78, 0x00, 0x00]),
+            'å' => Some([0x00, 0x20, 0x57, 0x55, 0x57, 0x78, 0x00, 0x00]),
+            'ç' => Some([0x00, 0x38, 0xC4, 0x44, 0x28, 0x00, 0x00, 0x00]),
+            'ê' => Some([0x00, 0x38, 0x56, 0x55, 0x56, 0x18, 0x00, 0x00]),
+            'ë' => Some([0x00, 0x38, 0x55, 0x54, 0x55, 0x18, 0x00, 0x00]),
+            'è' => Some([0x00, 0x38, 0x55, 0x56, 0x54, 0x18, 0x00, 0x00]),
+            'ï' => Some([0x00, 0x01, 0x7C, 0x01, 0x00, 0x00, 0x00, 0x00]),
+            'î' => Some([0x00, 0x02, 0x7D, 0x02, 0x00, 0x00, 0x00, 0x00]),
+            'ì' => Some([0x00, 0x01, 0x7E, 0x00, 0x00, 0x00, 0x00, 0x00]),
+            'Ä' => Some([0x00, 0x79, 0x14, 0x14, 0x14, 0x79, 0x00, 0x00]),
+            'Å' => Some([0x00, 0x78, 0x17, 0x15, 0x17, 0x78, 0x00, 0x00]),
+            'É' => Some([0x00, 0x7C, 0x54, 0x56, 0x55, 0x44, 0x00, 0x00]),
+            'æ' => Some([0x20, 0x54, 0x54, 0x38, 0x54, 0x54, 0x58, 0x00]),
+            'Æ' => Some([0x7C, 0x0A, 0x09, 0x7F, 0x49, 0x49, 0x41, 0x00]),
+            'ô' => Some([0x00, 0x3A, 0x45, 0x45, 0x3A, 0x00, 0x00, 0x00]),
+            'ö' => Some([0x00, 0x39, 0x44, 0x44, 0x39, 0x00, 0x00, 0x00]),
+            'ò' => Some([0x00, 0x39, 0x46, 0x44, 0x38, 0x00, 0x00, 0x00]),
+            'û' => Some([0x00, 0x3E, 0x41, 0x41, 0x7E, 0x00, 0x00, 0x00]),
+            'ù' => Some([0x00, 0x3D, 0x42, 0x40, 0x7C, 0x00, 0x00, 0x00]),
+            'ÿ' => Some([0x00, 0x1D, 0xA0, 0xA0, 0x7D, 0x00, 0x00, 0x00]),
+            'Ö' => Some([0x00, 0x39, 0x44, 0x44, 0x44, 0x39, 0x00, 0x00]),
+            'Ü' => Some([0x00, 0x3D, 0x40, 0x40, 0x40, 0x3D, 0x00, 0x00]),
+            '¢' => Some([0x00, 0x1C, 0x22, 0x7F, 0x22, 0x14, 0x00, 0x00]),
+            '£' => Some([0x48, 0x7E, 0x49, 0x49, 0x42, 0x60, 0x00, 0x00]),
+            '¥' => Some([0x00, 0x15, 0x16, 0x7C, 0x16, 0x15, 0x00, 0x00]),
+            '₧' => Some([0x7F, 0x05, 0x02, 0x00, 0x04, 0x3E, 0x44, 0x20]),
+            'ƒ' => Some([0x00, 0x80, 0x84, 0x7E, 0x05, 0x01, 0x00, 0x00]),
+            'á' => Some([0x00, 0x20, 0x54, 0x56, 0x55, 0x78, 0x00, 0x00]),
+            'í' => Some([0x00, 0x00, 0x7E, 0x01, 0x00, 0x00, 0x00, 0x00]),
+            'ó' => Some([0x00, 0x38, 0x46, 0x45, 0x38, 0x00, 0x00, 0x00]),
+            'ú' => Some([0x00, 0x3C, 0x42, 0x41, 0x7C, 0x00, 0x00, 0x00]),
+            'ñ' => Some([0x00, 0x7E, 0x09, 0x06, 0x7D, 0x00, 0x00, 0x00]),
+            'Ñ' => Some([0x00, 0x7C, 0x0A, 0x11, 0x22, 0x7D, 0x00, 0x00]),
+            'ª' => Some([0x00, 0x48, 0x55, 0x55, 0x5E, 0x00, 0x00, 0x00]),
+            'º' => Some([0x00, 0x4E, 0x51, 0x51, 0x4E, 0x00, 0x00, 0x00]),
+            '¿' => Some([0x00, 0x30, 0x48, 0x45, 0x40, 0x20, 0x00, 0x00]),
+            '⌐' => Some([0x00, 0x38, 0x08, 0x08, 0x08, 0x08, 0x00, 0x00]),
+            '¬' => Some([0x00, 0x08, 0x08, 0x08, 0x08, 0x38, 0x00, 0x00]),
+            '½' => Some([0x42, 0x27, 0x10, 0x08, 0x94, 0xCA, 0xA9, 0x90]),
+            '¼' => Some([0x42, 0x27, 0x10, 0x08, 0x64, 0x52, 0xF9, 0x40]),
+            '¡' => Some([0x00, 0x00, 0x7D, 0x00, 0x00, 0x00, 0x00, 0x00]),
+            '«' => Some([0x00, 0x08, 0x14, 0x22, 0x08, 0x14, 0x22, 0x00]),
+            '»' => Some([0x00, 0x22, 0x14, 0x08, 0x22, 0x14, 0x08, 0x00]),
+            // code page 437 shades, blocks and box drawing
+            '░' => Some([0x55, 0x00, 0xAA, 0x00, 0x55, 0x00, 0xAA, 0x00]),
+            '▒' => Some([0x55, 0xAA, 0x55, 0xAA, 0x55, 0xAA, 0x55, 0xAA]),
+            '▓' => Some([0xAA, 0xFF, 0x55, 0xFF, 0xAA, 0xFF, 0x55, 0xFF]),
+            '│' => Some([0x00, 0x00, 0x00, 0xFF, 0x00, 0x00, 0x00, 0x00]),
+            '┤' => Some([0x08, 0x08, 0x08, 0xFF, 0x00, 0x00, 0x00, 0x00]),
+            '╡' => Some([0x14, 0x14, 0x14, 0xFF, 0x00, 0x00, 0x00, 0x00]),
+            '╢' => Some([0x08, 0x08, 0xFF, 0x00, 0xFF, 0x00, 0x00, 0x00]),
+            '╖' => Some([0x08, 0x08, 0xF8, 0x08, 0xF8, 0x00, 0x00, 0x00]),
+            '╕' => Some([0x14, 0x14, 0x14, 0xFC, 0x00, 0x00, 0x00, 0x00]),
+            '╣' => Some([0x14, 0x14, 0xF7, 0x00, 0xFF, 0x00, 0x00, 0x00]),
+            '║' => Some([0x00, 0x00, 0xFF, 0x00, 0xFF, 0x00, 0x00, 0x00]),
+            '╗' => Some([0x14, 0x14, 0xF4, 0x04, 0xFC, 0x00, 0x00, 0x00]),
+            '╝' => Some([0x14, 0x14, 0x17, 0x10, 0x1F, 0x00, 0x00, 0x00]),
+            '╜' => Some([0x08, 0x08, 0x0F, 0x08, 0x0F, 0x00, 0x00, 0x00]),
+            '╛' => Some([0x14, 0x14, 0x14, 0x1F, 0x00, 0x00, 0x00, 0x00]),
+            '┐' => Some([0x08, 0x08, 0x08, 0xF8, 0x00, 0x00, 0x00, 0x00]),
+            '└' => Some([0x00, 0x00, 0x00, 0x0F, 0x08, 0x08, 0x08, 0x08]),
+            '┴' => Some([0x08, 0x08, 0x08, 0x0F, 0x08, 0x08, 0x08, 0x08]),
+            '┬' => Some([0x08, 0x08, 0x08, 0xF8, 0x08, 0x08, 0x08, 0x08]),
+            '├' => Some([0x00, 0x00, 0x00, 0xFF, 0x08, 0x08, 0x08, 0x08]),
+            '─' => Some([0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08]),
+            '┼' => Some([0x08, 0x08, 0x08, 0xFF, 0x08, 0x08, 0x08, 0x08]),
+            '╞' => Some([0x00, 0x00, 0x00, 0xFF, 0x14, 0x14, 0x14, 0x14]),
+            '╟' => Some([0x00, 0x00, 0xFF, 0x00, 0xFF, 0x08, 0x08, 0x08]),
+            '╚' => Some([0x00, 0x00, 0x1F, 0x10, 0x17, 0x14, 0x14, 0x14]),
+            '╔' => Some([0x00, 0x00, 0xFC, 0x04, 0xF4, 0x14, 0x14, 0x14]),
+            '╩' => Some([0x14, 0x14, 0x17, 0x10, 0x17, 0x14, 0x14, 0x14]),
+            '╦' => Some([0x14, 0x14, 0xF4, 0x04, 0xF4, 0x14, 0x14, 0x14]),
+            '╠' => Some([0x00, 0x00, 0xFF, 0x00, 0xF7, 0x14, 0x14, 0x14]),
+            '═' => Some([0x14, 0x14, 0x14, 0x14, 0x14, 0x14, 0x14, 0x14]),
+            '╬' => Some([0x14, 0x14, 0xF7, 0x00, 0xF7, 0x14, 0x14, 0x14]),
+            '╧' => Some([0x14, 0x14, 0x14, 0x17, 0x14, 0x14, 0x14, 0x14]),
+            '╨' => Some([0x08, 0x08, 0x0F, 0x08, 0x0F, 0x08, 0x08, 0x08]),
+            '╤' => Some([0x14, 0x14, 0x14, 0xF4, 0x14, 0x14, 0x14, 0x14]),
+            '╥' => Some([0x08, 0x08, 0xF8, 0x08, 0xF8, 0x08, 0x08, 0x08]),
+            '╙' => Some([0x00, 0x00, 0x0F, 0x08, 0x0F, 0x08, 0x08, 0x08]),
+            '╘' => Some([0x00, 0x00, 0x00, 0x1F, 0x14, 0x14, 0x14, 0x14]),
+            '╒' => Some([0x00, 0x00, 0x00, 0xFC, 0x14, 0x14, 0x14, 0x14]),
+            '╓' => Some([0x00, 0x00, 0xF8, 0x08, 0xF8, 0x08, 0x08, 0x08]),
+            '╫' => Some([0x08, 0x08, 0xFF, 0x08, 0xFF, 0x08, 0x08, 0x08]),
+            '╪' => Some([0x14, 0x14, 0x14, 0xFF, 0x14, 0x14, 0x14, 0x14]),
+            '┘' => Some([0x08, 0x08, 0x08, 0x0F, 0x00, 0x00, 0x00, 0x00]),
+            '┌' => Some([0x00, 0x00, 0x00, 0xF8, 0x08, 0x08, 0x08, 0x08]),
+            '█' => Some([0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]),
+            '▄' => Some([0xF0, 0xF0, 0xF0, 0xF0, 0xF0, 0xF0, 0xF0, 0xF0]),
+            '▌' => Some([0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00]),
+            '▐' => Some([0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF]),
+            '▀' => Some([0x0F, 0x0F, 0x0F, 0x0F, 0x0F, 0x0F, 0x0F, 0x0F]),
+            // code page 437 Greek letters and mathematical symbols
+            'α' => Some([0x00, 0x38, 0x44, 0x44, 0x38, 0x44, 0x00, 0x00]),
+            'ß' | 'β' => Some([0x00, 0xFE, 0x49, 0x49, 0x36, 0x00, 0x00, 0x00]),
+            'Γ' => Some([0x00, 0x7F, 0x01, 0x01, 0x01, 0x01, 0x00, 0x00]),
+            'π' => Some([0x00, 0x04, 0x7C, 0x04, 0x7C, 0x04, 0x00, 0x00]),
+            'Σ' => Some([0x00, 0x63, 0x55, 0x49, 0x41, 0x41, 0x00, 0x00]),
+            'σ' => Some([0x00, 0x38, 0x44, 0x44, 0x3C, 0x04, 0x00, 0x00]),
+            'µ' | 'μ' => Some([0x00, 0xFC, 0x40, 0x40, 0x3C, 0x40, 0x00, 0x00]),
+            'τ' => Some([0x00, 0x04, 0x04, 0x3C, 0x44, 0x44, 0x00, 0x00]),
+            'Φ' => Some([0x00, 0x1C, 0x22, 0x7F, 0x22, 0x1C, 0x00, 0x00]),
+            'Θ' => Some([0x00, 0x3E, 0x49, 0x49, 0x49, 0x3E, 0x00, 0x00]),
+            'Ω' => Some([0x00, 0x4E, 0x71, 0x01, 0x71, 0x4E, 0x00, 0x00]),
+            'δ' => Some([0x00, 0x3A, 0x45, 0x45, 0x39, 0x00, 0x00, 0x00]),
+            '∞' => Some([0x10, 0x28, 0x28, 0x10, 0x28, 0x28, 0x10, 0x00]),
+            'φ' => Some([0x00, 0x30, 0x48, 0xFC, 0x48, 0x30, 0x00, 0x00]),
+            'ε' => Some([0x00, 0x38, 0x54, 0x54, 0x44, 0x00, 0x00, 0x00]),
+            '∩' => Some([0x00, 0x7C, 0x02, 0x02, 0x02, 0x7C, 0x00, 0x00]),
+            '≡' => Some([0x00, 0x2A, 0x2A, 0x2A, 0x2A, 0x2A, 0x00, 0x00]),
+            '±' => Some([0x00, 0x44, 0x44, 0x5F, 0x44, 0x44, 0x00, 0x00]),
+            '≥' => Some([0x00, 0x51, 0x51, 0x4A, 0x4A, 0x44, 0x00, 0x00]),
+            '≤' => Some([0x00, 0x44, 0x4A, 0x4A, 0x51, 0x51, 0x00, 0x00]),
+            '⌠' => Some([0x00, 0x00, 0x00, 0xFE, 0x01, 0x01, 0x02, 0x00]),
+            '⌡' => Some([0x40, 0x80, 0x80, 0x7F, 0x00, 0x00, 0x00, 0x00]),
+            '÷' => Some([0x00, 0x08, 0x08, 0x2A, 0x08, 0x08, 0x00, 0x00]),
+            '≈' => Some([0x00, 0x24, 0x12, 0x12, 0x24, 0x24, 0x12, 0x00]),
+            '°' | '˚' => Some([0x00, 0x02, 0x05, 0x05, 0x02, 0x00, 0x00, 0x00]),
+            '∙' => Some([0x00, 0x00, 0x00, 0x18, 0x18, 0x00, 0x00, 0x00]),
+            '·' => Some([0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00, 0x00]),
+            '√' => Some([0x08, 0x10, 0x20, 0x40, 0x30, 0x0F, 0x01, 0x01]),
+            'ⁿ' => Some([0x00, 0x0F, 0x02, 0x01, 0x0E, 0x00, 0x00, 0x00]),
+            '²' => Some([0x00, 0x09, 0x0D, 0x0A, 0x00, 0x00, 0x00, 0x00]),
+            '■' => Some([0x00, 0x00, 0x3C, 0x3C, 0x3C, 0x3C, 0x00, 0x00]),
+            '\u{a0}' => Some([0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]),
+            // beyond code page 437, for text cut short
+            '…' => Some([0x00, 0x40, 0x00, 0x40, 0x00, 0x40, 0x00, 0x00]),
+            _ => None,
         }
     }
 }
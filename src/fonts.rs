@@ -0,0 +1,468 @@
+//! Fonts with glyphs of any size, and drawing and measuring text set in them
+#[cfg(feature = "alloc")]
+use alloc::{borrow::Cow, string::String};
+
+use crate::dither::BAYER_4X4;
+use crate::font::BasicFont;
+use crate::{Framebuffer, Oled, OledInterface, OledResult, Rect, Sprite, TextRegion};
+
+/// Width of the fade at the end of text cut short by
+/// [`Truncation::Fade`](enum.Truncation.html#variant.Fade), in pixels
+const FADE_WIDTH: i32 = 8;
+
+/// Pixels of a glyph, in whichever layout its font keeps them
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum GlyphBitmap<'a> {
+    /// Rows of bits, as in a sprite
+    Rows(Sprite<'a>),
+    /// Columns of the built-in font, bit 0 at the top, starting from column `first`
+    Columns {
+        columns: [u8; 8],
+        first: u8,
+        width: u8,
+    },
+}
+
+/// The shape of one character in a [`Font`](trait.Font.html), and how far it moves
+/// the pen along.
+///
+/// The bitmap is drawn `left` pixels to the right of the pen and `top` pixels
+/// below the top of the line, either of which may be negative, eg for glyphs that
+/// hang below the baseline in fonts that keep their bitmaps tightly cropped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Glyph<'a> {
+    /// The glyph's pixels
+    bitmap: GlyphBitmap<'a>,
+    /// Horizontal offset of the bitmap from the pen
+    left: i16,
+    /// Vertical offset of the bitmap from the top of the line
+    top: i16,
+    /// How far the pen moves on after the glyph
+    advance: u16,
+}
+
+impl<'a> Glyph<'a> {
+    /// Creates a glyph from its bitmap, drawn at the pen position and the top of
+    /// the line, that moves the pen on by `advance` pixels
+    pub const fn new(bitmap: Sprite<'a>, advance: u16) -> Self {
+        Glyph {
+            bitmap: GlyphBitmap::Rows(bitmap),
+            left: 0,
+            top: 0,
+            advance,
+        }
+    }
+
+    /// Moves the bitmap `left` pixels right of the pen and `top` pixels down
+    /// from the top of the line
+    pub const fn with_offset(self, left: i16, top: i16) -> Self {
+        Glyph { left, top, ..self }
+    }
+
+    /// Creates a glyph from the built-in font's columns, keeping only `width`
+    /// of them from `first` on
+    fn from_columns(columns: [u8; 8], first: u8, width: u8, advance: u16) -> Self {
+        Glyph {
+            bitmap: GlyphBitmap::Columns {
+                columns,
+                first,
+                width,
+            },
+            left: 0,
+            top: 0,
+            advance,
+        }
+    }
+
+    /// Width and height of the bitmap in pixels
+    pub fn dimensions(&self) -> (u16, u16) {
+        match self.bitmap {
+            GlyphBitmap::Rows(sprite) => sprite.dimensions(),
+            GlyphBitmap::Columns { width, .. } => (u16::from(width), 8),
+        }
+    }
+
+    /// Offset of the bitmap from the pen and the top of the line
+    pub fn offset(&self) -> (i16, i16) {
+        (self.left, self.top)
+    }
+
+    /// How far the pen moves on after the glyph, in pixels
+    pub fn advance(&self) -> u16 {
+        self.advance
+    }
+
+    /// Returns whether the pixel at `x`, `y` of the bitmap is lit.
+    /// Pixels outside the bitmap are never lit.
+    pub fn get_pixel(&self, x: u16, y: u16) -> bool {
+        match self.bitmap {
+            GlyphBitmap::Rows(sprite) => sprite.get_pixel(x, y) == Some(true),
+            GlyphBitmap::Columns {
+                columns,
+                first,
+                width,
+            } => {
+                x < u16::from(width)
+                    && y < 8
+                    && columns[(u16::from(first) + x) as usize] & (1 << y) != 0
+            }
+        }
+    }
+}
+
+/// A source of glyphs for drawing text, eg the built-in [`BuiltinFont`](struct.BuiltinFont.html).
+///
+/// Glyphs may be of any width, so besides drawing, this is what works out how
+/// much room text takes up, eg to centre or right-align it.
+pub trait Font {
+    /// Distance from the top of one line of text to the top of the next, in pixels
+    fn line_height(&self) -> u16;
+
+    /// The glyph for `char`, or `None` if the font doesn't have one
+    fn glyph(&self, char: char) -> Option<Glyph<'_>>;
+
+    /// The glyph drawn in place of characters the font doesn't have.
+    /// By default, this is the font's `?`.
+    fn replacement(&self) -> Option<Glyph<'_>> {
+        self.glyph('?')
+    }
+
+    /// How far `char` moves the pen on, in pixels, including any spacing after it.
+    /// Characters with no glyph or replacement take up no room.
+    fn char_advance(&self, char: char) -> u16 {
+        self.glyph(char)
+            .or_else(|| self.replacement())
+            .map_or(0, |glyph| glyph.advance())
+    }
+
+    /// Width of a line of text in pixels, as drawn by
+    /// [`Framebuffer::draw_text()`](struct.Framebuffer.html#method.draw_text)
+    fn text_width(&self, text: &str) -> u16 {
+        text.chars().fold(0, |width: u16, char| {
+            width.saturating_add(self.char_advance(char))
+        })
+    }
+}
+
+/// The built-in font, either in its 8x8 cells or proportionally spaced.
+/// # Example:
+/// ```
+/// use nanohat_oled::{BuiltinFont, Font};
+///
+/// assert_eq!(BuiltinFont::MONOSPACE.text_width("il"), 16);
+/// assert_eq!(BuiltinFont::PROPORTIONAL.text_width("il"), 6);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BuiltinFont {
+    /// Whether blank columns are trimmed from around each glyph
+    proportional: bool,
+}
+
+impl BuiltinFont {
+    /// Every character takes up a full 8x8 cell, as with
+    /// [`Oled::put_string()`](struct.Oled.html#method.put_string)
+    pub const MONOSPACE: BuiltinFont = BuiltinFont {
+        proportional: false,
+    };
+
+    /// Each character is only as wide as its glyph, plus a column of spacing,
+    /// so narrow characters like `i` and `.` take up less room
+    pub const PROPORTIONAL: BuiltinFont = BuiltinFont { proportional: true };
+
+    /// Width of a space in the proportional font
+    const SPACE_WIDTH: u16 = 3;
+
+    /// Makes a glyph from the built-in font's columns
+    fn make_glyph(&self, columns: [u8; 8]) -> Glyph<'static> {
+        if !self.proportional {
+            return Glyph::from_columns(columns, 0, 8, 8);
+        }
+        let first = columns.iter().position(|column| *column != 0);
+        let last = columns.iter().rposition(|column| *column != 0);
+        match (first, last) {
+            (Some(first), Some(last)) => {
+                let width = (last - first + 1) as u8;
+                Glyph::from_columns(columns, first as u8, width, u16::from(width) + 1)
+            }
+            _ => Glyph::from_columns(columns, 0, 0, Self::SPACE_WIDTH),
+        }
+    }
+}
+
+impl Font for BuiltinFont {
+    fn line_height(&self) -> u16 {
+        8
+    }
+
+    fn glyph(&self, char: char) -> Option<Glyph<'_>> {
+        BasicFont::glyph(char).map(|columns| self.make_glyph(columns))
+    }
+
+    /// The same box as [`Oled::put_char()`](struct.Oled.html#method.put_char) draws
+    fn replacement(&self) -> Option<Glyph<'_>> {
+        Some(self.make_glyph(BasicFont::MISSING))
+    }
+}
+
+/// How a line of text too wide for the room it has is cut short, so that it
+/// stays on its own line rather than wrapping onto the next
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Truncation {
+    /// The text is cut off at the edge, mid-character if need be
+    Clip,
+    /// As many whole characters as fit are kept, followed by an ellipsis: the
+    /// font's `…`, or `...` if it has none
+    Ellipsis,
+    /// The text is cut off at the edge, fading out over its last few pixels
+    Fade,
+}
+
+/// The ellipsis drawn after text cut short in `font`
+fn ellipsis<F: Font + ?Sized>(font: &F) -> &'static str {
+    if font.glyph('…').is_some() {
+        "…"
+    } else {
+        "..."
+    }
+}
+
+/// The start of `text` that fits in `max_width` pixels of `font` along with an
+/// ellipsis, or `None` if all of `text` fits without one
+fn fit_prefix<'t, F: Font + ?Sized>(text: &'t str, max_width: u16, font: &F) -> Option<&'t str> {
+    if font.text_width(text) <= max_width {
+        return None;
+    }
+    let room = max_width.saturating_sub(font.text_width(ellipsis(font)));
+    let mut width = 0u16;
+    for (index, char) in text.char_indices() {
+        width = width.saturating_add(font.char_advance(char));
+        if width > room {
+            return Some(&text[..index]);
+        }
+    }
+    Some(text)
+}
+
+/// Returns `text` if it fits in `max_width` pixels of `font`, or otherwise as
+/// much of its start as fits followed by an ellipsis, as
+/// [`Truncation::Ellipsis`](enum.Truncation.html#variant.Ellipsis) draws it.
+/// If not even the ellipsis fits, it's returned alone.
+/// # Example:
+/// ```
+/// use nanohat_oled::{fit_text, BuiltinFont};
+///
+/// let font = BuiltinFont::MONOSPACE;
+/// assert_eq!(fit_text("wlan0", 64, &font), "wlan0");
+/// assert_eq!(fit_text("enx00e04c680001", 64, &font), "enx00e0…");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn fit_text<'t, F: Font + ?Sized>(text: &'t str, max_width: u16, font: &F) -> Cow<'t, str> {
+    match fit_prefix(text, max_width, font) {
+        None => Cow::Borrowed(text),
+        Some(prefix) => {
+            let mut fitted = String::from(prefix);
+            fitted.push_str(ellipsis(font));
+            Cow::Owned(fitted)
+        }
+    }
+}
+
+impl Framebuffer {
+    /// Draws a line of text in `font`, with the top left of the line at `x`, `y`,
+    /// and returns the `x` coordinate just past its end.
+    ///
+    /// Each character's cell, from the pen to its advance and down the line
+    /// height, is cleared before its glyph is drawn, so new text can be drawn over
+    /// old. Anything off the display is clipped. `\n` isn't treated specially.
+    pub fn draw_text<F: Font + ?Sized>(&mut self, x: u16, y: u16, text: &str, font: &F) -> u16 {
+        self.draw_text_clipped(
+            i32::from(x),
+            y,
+            text,
+            font,
+            &TextRegion::new(0, 0, u16::MAX, u16::MAX),
+        )
+    }
+
+    /// Draws a line of text like [`draw_text()`](struct.Framebuffer.html#method.draw_text),
+    /// leaving alone any pixels outside `clip`. The line may start left of the
+    /// display, with `x` negative.
+    pub(crate) fn draw_text_clipped<F: Font + ?Sized>(
+        &mut self,
+        x: i32,
+        y: u16,
+        text: &str,
+        font: &F,
+        clip: &TextRegion,
+    ) -> u16 {
+        let mut plot = |x: i32, y: i32, on: bool| {
+            if clip.contains(x, y) {
+                self.plot(x, y, on);
+            }
+        };
+        let mut pen = x;
+        let line_height = i32::from(font.line_height());
+        for char in text.chars() {
+            let Some(glyph) = font.glyph(char).or_else(|| font.replacement()) else {
+                continue;
+            };
+            let advance = i32::from(glyph.advance());
+            for cell_y in 0..line_height {
+                for cell_x in 0..advance {
+                    plot(pen + cell_x, i32::from(y) + cell_y, false);
+                }
+            }
+            let (width, height) = glyph.dimensions();
+            let (left, top) = glyph.offset();
+            for glyph_y in 0..height {
+                for glyph_x in 0..width {
+                    if glyph.get_pixel(glyph_x, glyph_y) {
+                        plot(
+                            pen + i32::from(left) + i32::from(glyph_x),
+                            i32::from(y) + i32::from(top) + i32::from(glyph_y),
+                            true,
+                        );
+                    }
+                }
+            }
+            pen += advance;
+        }
+        pen.clamp(0, i32::from(u16::MAX)) as u16
+    }
+
+    /// Draws a line of text in `font` with the top left of the line at `x`, `y`,
+    /// like a label `width` pixels wide, cutting it short by `truncation` if it
+    /// doesn't fit. The label's whole line is cleared first, and nothing is
+    /// drawn beyond it.
+    /// # Example:
+    /// ```
+    /// use nanohat_oled::{BuiltinFont, Framebuffer, Truncation};
+    ///
+    /// let mut frame = Framebuffer::new();
+    /// let font = BuiltinFont::MONOSPACE;
+    /// frame.draw_text_fit(0, 0, 64, "enx00e04c680001", &font, Truncation::Ellipsis);
+    /// ```
+    pub fn draw_text_fit<F: Font + ?Sized>(
+        &mut self,
+        x: u16,
+        y: u16,
+        width: u16,
+        text: &str,
+        font: &F,
+        truncation: Truncation,
+    ) {
+        let area = Rect::new(x, y, width, font.line_height());
+        self.fill_rect(area.x, area.y, area.width, area.height, false);
+        self.flip_text_fit(i32::from(x), i32::from(y), text, font, area, truncation);
+    }
+
+    /// Flips the pixels of a line of `text` like [`flip_text()`](#method.flip_text),
+    /// cutting it short by `truncation` if it runs past the right edge of `clip`
+    pub(crate) fn flip_text_fit<F: Font + ?Sized>(
+        &mut self,
+        x: i32,
+        y: i32,
+        text: &str,
+        font: &F,
+        clip: Rect,
+        truncation: Truncation,
+    ) {
+        let right = i32::from(clip.x) + i32::from(clip.width);
+        let room = (right - x).clamp(0, i32::from(u16::MAX)) as u16;
+        match truncation {
+            Truncation::Ellipsis => match fit_prefix(text, room, font) {
+                Some(prefix) => {
+                    self.flip_text(x, y, prefix, font, clip);
+                    let after = x + i32::from(font.text_width(prefix));
+                    self.flip_text(after, y, ellipsis(font), font, clip);
+                }
+                None => self.flip_text(x, y, text, font, clip),
+            },
+            Truncation::Fade if font.text_width(text) > room => {
+                // fewer of the pixels are kept the nearer they are to the edge
+                self.flip_text_where(x, y, text, font, clip, |x, y| {
+                    let distance = right - x;
+                    let order = i32::from(BAYER_4X4[(y & 3) as usize][(x & 3) as usize]);
+                    distance > FADE_WIDTH || order * FADE_WIDTH < 16 * (distance - 1)
+                })
+            }
+            _ => self.flip_text(x, y, text, font, clip),
+        }
+    }
+
+    /// Flips each pixel inside `clip` that is lit in a line of `text` with its
+    /// top left at `x`, `y`, so the text shows over both lit and unlit pixels
+    pub(crate) fn flip_text<F: Font + ?Sized>(
+        &mut self,
+        x: i32,
+        y: i32,
+        text: &str,
+        font: &F,
+        clip: Rect,
+    ) {
+        self.flip_text_where(x, y, text, font, clip, |_, _| true)
+    }
+
+    /// Flips the pixels of a line of `text` like [`flip_text()`](#method.flip_text),
+    /// only where `keep` returns true
+    fn flip_text_where<F: Font + ?Sized>(
+        &mut self,
+        x: i32,
+        y: i32,
+        text: &str,
+        font: &F,
+        clip: Rect,
+        keep: impl Fn(i32, i32) -> bool,
+    ) {
+        let mut pen = x;
+        for char in text.chars() {
+            let Some(glyph) = font.glyph(char).or_else(|| font.replacement()) else {
+                continue;
+            };
+            let (width, height) = glyph.dimensions();
+            let (left, top) = glyph.offset();
+            for glyph_y in 0..height {
+                for glyph_x in 0..width {
+                    let px = pen + i32::from(left) + i32::from(glyph_x);
+                    let py = y + i32::from(top) + i32::from(glyph_y);
+                    if glyph.get_pixel(glyph_x, glyph_y) && clip.contains(px, py) && keep(px, py) {
+                        let (px, py) = (px as u16, py as u16);
+                        let lit = self.get_pixel(px, py);
+                        self.set_pixel(px, py, !lit);
+                    }
+                }
+            }
+            pen += i32::from(glyph.advance());
+        }
+    }
+}
+
+impl<I: OledInterface> Oled<I> {
+    /// Writes a line of text in `font` with the top left of the line at `x`, `y`,
+    /// as [`Framebuffer::draw_text()`](struct.Framebuffer.html#method.draw_text)
+    /// does, sending only the part of the display that changes.
+    /// # Example:
+    /// ```no_run
+    /// # use nanohat_oled::{BuiltinFont, Font, Oled, OledResult};
+    /// # fn main() -> OledResult {
+    /// let mut oled = Oled::from_path("/dev/i2c-0")?;
+    /// oled.init()?;
+    /// // right-aligned against the edge of the display
+    /// let font = BuiltinFont::PROPORTIONAL;
+    /// let text = "21.5C";
+    /// oled.put_string_font(128 - font.text_width(text), 0, text, &font)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn put_string_font<F: Font + ?Sized>(
+        &mut self,
+        x: u16,
+        y: u16,
+        text: &str,
+        font: &F,
+    ) -> OledResult {
+        self.draw(|frame| {
+            frame.draw_text(x, y, text, font);
+        })
+    }
+}
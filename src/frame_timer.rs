@@ -0,0 +1,111 @@
+//! Keeping animations to a steady frame rate, however long each frame takes to draw
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use crate::{DoubleBuffer, OledError, OledInterface};
+
+/// Paces a drawing loop to a steady number of frames a second, in place of
+/// guessing at a `thread::sleep` after each frame.
+///
+/// [`wait()`](#method.wait) sleeps until the next frame is due, counting from
+/// when the timer started rather than from the last frame, so time spent drawing
+/// doesn't slow the rate down. A frame that takes too long to draw is shown as
+/// soon as it's ready, with the frames it held up counted as dropped, and the
+/// next is then due on the beat again.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{DoubleBuffer, FrameTimer, Oled, OledResult};
+/// # fn main() -> OledResult {
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// let mut screen = DoubleBuffer::new(oled).with_clear(true);
+/// let mut timer = FrameTimer::new(30);
+/// for x in 0..128 {
+///     screen.back().fill_circle(x, 32, 8, true);
+///     screen.present_at(&mut timer)?;
+/// }
+/// println!("dropped {} frames", timer.dropped());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct FrameTimer {
+    /// Time between frames
+    period: Duration,
+    /// When the next frame is due
+    due: Instant,
+    /// Frames waited for so far
+    frames: u64,
+    /// Frames dropped so far
+    dropped: u64,
+}
+
+impl FrameTimer {
+    /// A timer for `fps` frames a second, with the first frame due one frame
+    /// from now. An `fps` of 0 is taken as 1.
+    pub fn new(fps: u32) -> Self {
+        Self::with_period(Duration::from_secs(1) / fps.max(1))
+    }
+
+    /// A timer for a frame every `period`, with the first due one period from now
+    pub fn with_period(period: Duration) -> Self {
+        FrameTimer {
+            period,
+            due: Instant::now() + period,
+            frames: 0,
+            dropped: 0,
+        }
+    }
+
+    /// The time between frames
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    /// Sleeps until the next frame is due, or returns straight away if it's
+    /// overdue. Returns the number of frames dropped because of the delay: 0 when
+    /// on time.
+    pub fn wait(&mut self) -> u32 {
+        self.frames += 1;
+        let now = Instant::now();
+        if now <= self.due {
+            sleep(self.due - now);
+            self.due += self.period;
+            return 0;
+        }
+        let late = (now - self.due).as_nanos() / self.period.as_nanos().max(1);
+        let dropped = late.min(u128::from(u32::MAX)) as u32;
+        self.dropped += u64::from(dropped);
+        self.due += self.period * (dropped.saturating_add(1));
+        dropped
+    }
+
+    /// The number of frames waited for so far
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    /// The number of frames dropped so far, because drawing or sending took
+    /// longer than a frame
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Starts again, with the next frame due one period from now and the counts
+    /// at zero, eg after a pause
+    pub fn reset(&mut self) {
+        *self = Self::with_period(self.period);
+    }
+}
+
+impl<I: OledInterface> DoubleBuffer<I> {
+    /// Waits for the next frame of `timer`, then
+    /// [presents](struct.DoubleBuffer.html#method.present) the frame drawn.
+    /// Returns the number of frames dropped, as for
+    /// [`FrameTimer::wait()`](struct.FrameTimer.html#method.wait).
+    pub fn present_at(&mut self, timer: &mut FrameTimer) -> Result<u32, OledError> {
+        let dropped = timer.wait();
+        self.present()?;
+        Ok(dropped)
+    }
+}
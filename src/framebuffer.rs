@@ -0,0 +1,245 @@
+//! An off-screen copy of display RAM, for composing frames pixel by pixel
+use crate::rotation::portrait_to_physical;
+use crate::{
+    Oled, OledError, OledInterface, OledResult, OLED_HEIGHT, OLED_PAGE_HEIGHT, OLED_RAM_SIZE,
+    OLED_WIDTH,
+};
+
+/// A 128x64 monochrome frame, held in memory until it is sent to the display with
+/// [`Oled::flush()`](struct.Oled.html#method.flush).
+///
+/// Drawing into a framebuffer doesn't touch the bus, so a frame can be built up
+/// from many small changes and then shown all at once, without the display
+/// showing it half-drawn.
+///
+/// On a smaller [`PanelSize`](enum.PanelSize.html), only the top left corner
+/// the panel shows is drawn on it.
+///
+/// For a display turned to [`Rotation::Rotate90`](enum.Rotation.html#variant.Rotate90)
+/// or [`Rotate270`](enum.Rotation.html#variant.Rotate270), use
+/// [`portrait()`](struct.Framebuffer.html#method.portrait) instead of `new()`.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{Framebuffer, Oled, OledResult};
+/// # fn main() -> OledResult {
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// let mut frame = Framebuffer::new();
+/// for x in 0..128 {
+///     frame.set_pixel(x, 32, true);
+/// }
+/// oled.flush(&frame)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Framebuffer {
+    /// Pixels in display RAM layout, one byte per column of each page
+    buffer: [u8; OLED_RAM_SIZE],
+    /// Whether pixel coordinates are for the display turned on its side
+    portrait: bool,
+    /// For a frame holding part of a wider canvas, as a
+    /// [`DisplayGroup`](struct.DisplayGroup.html) draws on, the canvas column
+    /// the frame starts at, and the canvas width
+    window: Option<(u16, u16)>,
+}
+
+impl Framebuffer {
+    /// Creates a 128x64 framebuffer with every pixel off
+    pub const fn new() -> Self {
+        Self::from_bytes([0u8; OLED_RAM_SIZE])
+    }
+
+    /// Creates a 64x128 framebuffer with every pixel off, for a display turned
+    /// a quarter turn with [`Oled::set_rotation()`](struct.Oled.html#method.set_rotation).
+    /// The pixels are still stored in display RAM layout, the right way round for
+    /// the panel, so only the coordinates passed to
+    /// [`set_pixel()`](struct.Framebuffer.html#method.set_pixel) and
+    /// [`get_pixel()`](struct.Framebuffer.html#method.get_pixel) differ.
+    pub const fn portrait() -> Self {
+        Self::with_layout([0u8; OLED_RAM_SIZE], true)
+    }
+
+    /// Creates a 128x64 framebuffer from bytes laid out like display RAM, eg a copy of
+    /// [`Oled::framebuffer()`](struct.Oled.html#method.framebuffer) to draw on top of
+    /// what is currently shown.
+    pub const fn from_bytes(buffer: [u8; OLED_RAM_SIZE]) -> Self {
+        Self::with_layout(buffer, false)
+    }
+
+    /// Creates a framebuffer from bytes laid out like display RAM, in either orientation
+    pub(crate) const fn with_layout(buffer: [u8; OLED_RAM_SIZE], portrait: bool) -> Self {
+        Self {
+            buffer,
+            portrait,
+            window: None,
+        }
+    }
+
+    /// Creates a framebuffer holding the 128 columns of a `width` wide canvas
+    /// from column `first`, from bytes laid out like display RAM. Coordinates
+    /// are the canvas's, and pixels outside the frame's columns are ignored.
+    pub(crate) const fn window(buffer: [u8; OLED_RAM_SIZE], first: u16, width: u16) -> Self {
+        Self {
+            buffer,
+            portrait: false,
+            window: Some((first, width)),
+        }
+    }
+
+    /// The pixels, laid out like display RAM: see
+    /// [`Oled::send_data()`](struct.Oled.html#method.send_data)
+    pub fn as_bytes(&self) -> &[u8; OLED_RAM_SIZE] {
+        &self.buffer
+    }
+
+    /// Whether this is a 64x128 framebuffer, as created by
+    /// [`portrait()`](struct.Framebuffer.html#method.portrait)
+    pub fn is_portrait(&self) -> bool {
+        self.portrait
+    }
+
+    /// The width and height of the framebuffer, in pixels
+    pub fn dimensions(&self) -> (u16, u16) {
+        match (self.portrait, self.window) {
+            (true, _) => (OLED_HEIGHT, OLED_WIDTH),
+            (false, Some((_, width))) => (width, OLED_HEIGHT),
+            (false, None) => (OLED_WIDTH, OLED_HEIGHT),
+        }
+    }
+
+    /// Turns the pixel at `x`, `y` on or off.
+    /// Pixels outside the display are ignored.
+    pub fn set_pixel(&mut self, x: u16, y: u16, on: bool) {
+        if let Some((offset, bit)) = self.locate(x, y) {
+            if on {
+                self.buffer[offset] |= bit;
+            } else {
+                self.buffer[offset] &= !bit;
+            }
+        }
+    }
+
+    /// Returns whether the pixel at `x`, `y` is on.
+    /// Pixels outside the display are always off.
+    pub fn get_pixel(&self, x: u16, y: u16) -> bool {
+        self.locate(x, y)
+            .is_some_and(|(offset, bit)| self.buffer[offset] & bit != 0)
+    }
+
+    /// Turns every pixel off
+    pub fn clear(&mut self) {
+        self.buffer = [0u8; OLED_RAM_SIZE];
+    }
+
+    /// Finds the byte offset and bit mask holding a pixel, if it is on the display
+    fn locate(&self, x: u16, y: u16) -> Option<(usize, u8)> {
+        let (width, height) = self.dimensions();
+        if x >= width || y >= height {
+            return None;
+        }
+        let x = match self.window {
+            Some((first, _)) => x.checked_sub(first).filter(|x| *x < OLED_WIDTH)?,
+            None => x,
+        };
+        let (x, y) = if self.portrait {
+            portrait_to_physical(x, y)
+        } else {
+            (x, y)
+        };
+        let offset = (y / OLED_PAGE_HEIGHT) as usize * OLED_WIDTH as usize + x as usize;
+        Some((offset, 1 << (y % OLED_PAGE_HEIGHT)))
+    }
+}
+
+impl Default for Framebuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Finds the first and last columns of a page that differ between two buffers
+fn changed_columns(
+    old: &[u8; OLED_RAM_SIZE],
+    new: &[u8; OLED_RAM_SIZE],
+    page: u8,
+) -> Option<(u8, u8)> {
+    let start = page as usize * OLED_WIDTH as usize;
+    let differs = |column: &usize| old[start + column] != new[start + column];
+    let first = (0..OLED_WIDTH as usize).find(differs)?;
+    let last = (0..OLED_WIDTH as usize).rev().find(differs)?;
+    Some((first as u8, last as u8))
+}
+
+impl<I: OledInterface> Oled<I> {
+    /// Sends a [`Framebuffer`](struct.Framebuffer.html) to the display, so that it
+    /// shows exactly what the framebuffer holds.
+    ///
+    /// Only the parts that differ from the current
+    /// [`framebuffer()`](struct.Oled.html#method.framebuffer) are sent: each run of
+    /// changed pages goes as one window, covering the changed columns, so redrawing
+    /// a clock digit costs a few dozen bytes rather than a whole frame. On a
+    /// [`PanelSize`](enum.PanelSize.html) smaller than 128x64, only the top left
+    /// corner the panel shows is sent. Use
+    /// [`flush_all()`](struct.Oled.html#method.flush_all) where the display RAM may
+    /// not match, eg after scrolling.
+    ///
+    /// This works whatever the current [`AddressingMode`](enum.AddressingMode.html).
+    /// If anything is sent, the display is left in horizontal mode with the text
+    /// cursor at the top left.
+    ///
+    /// The framebuffer must match the display's orientation: a
+    /// [`portrait()`](struct.Framebuffer.html#method.portrait) framebuffer when it is
+    /// turned a quarter turn by [`set_rotation()`](struct.Oled.html#method.set_rotation),
+    /// and an ordinary one otherwise. If not, `InvalidDimensions` is returned.
+    pub fn flush(&mut self, framebuffer: &Framebuffer) -> OledResult {
+        if framebuffer.is_portrait() != self.rotation.is_portrait() {
+            return Err(OledError::InvalidDimensions);
+        }
+        let new = framebuffer.as_bytes();
+        let (last_column, last_page) = self.panel_window();
+        let mut run: Option<((u8, u8), (u8, u8))> = None;
+        for page in 0..=last_page {
+            // only what the panel shows is sent
+            let changed = changed_columns(self.framebuffer(), new, page)
+                .filter(|(first, _)| *first <= last_column)
+                .map(|(first, last)| (first, last.min(last_column)));
+            run = match (run, changed) {
+                (Some((columns, pages)), Some(changed)) => Some((
+                    (columns.0.min(changed.0), columns.1.max(changed.1)),
+                    (pages.0, page),
+                )),
+                (Some((columns, pages)), None) => {
+                    self.write_window(columns, pages, new)?;
+                    None
+                }
+                (None, Some(changed)) => Some((changed, (page, page))),
+                (None, None) => None,
+            };
+        }
+        match run {
+            Some((columns, pages)) => self.write_window(columns, pages, new),
+            None => Ok(()),
+        }
+    }
+
+    /// Sends the whole of a [`Framebuffer`](struct.Framebuffer.html) to the display,
+    /// whether or not it differs from what was last written.
+    /// Otherwise behaves like [`flush()`](struct.Oled.html#method.flush).
+    pub fn flush_all(&mut self, framebuffer: &Framebuffer) -> OledResult {
+        if framebuffer.is_portrait() != self.rotation.is_portrait() {
+            return Err(OledError::InvalidDimensions);
+        }
+        let (last_column, last_page) = self.panel_window();
+        self.write_window((0, last_column), (0, last_page), framebuffer.as_bytes())
+    }
+
+    /// The last column and page of display RAM the panel shows
+    fn panel_window(&self) -> (u8, u8) {
+        let size = self.panel_size();
+        (
+            (size.width() - 1) as u8,
+            (size.height() / OLED_PAGE_HEIGHT - 1) as u8,
+        )
+    }
+}
@@ -0,0 +1,130 @@
+//! A dial with a needle pointing to a value, like a car's temperature gauge
+use crate::graphics::towards;
+use crate::widgets::Bounds;
+use crate::{BuiltinFont, Font, Framebuffer, OledError, Rect, TextRegion, Widget};
+
+/// Length of the tick marks, in pixels
+const TICK_LENGTH: u16 = 3;
+
+/// A [`Widget`](trait.Widget.html) showing a value as a needle on a semicircular
+/// dial, eg for CPU temperature or fan speed on a dashboard. The needle points
+/// left at the minimum and right at the maximum, and the dial has evenly spaced
+/// tick marks, with optional labels under its two ends.
+///
+/// The dial is as large as fits in its area, centred across it, with its open
+/// side at the bottom, or just above the labels if there are any.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{Gauge, Oled, OledResult, Rect};
+/// # fn main() -> OledResult {
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// let mut temperature = Gauge::new(20.0, 90.0)?
+///     .with_ticks(7)
+///     .with_labels("20C", "90C");
+/// temperature.set_value(47.5);
+/// oled.render_widgets(&mut [(&mut temperature, Rect::new(0, 0, 64, 40))])?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Gauge<'a> {
+    /// Values at the two ends of the dial
+    bounds: Bounds,
+    /// Value the needle points to, within its bounds
+    value: f32,
+    /// Number of spaces between tick marks, or 0 for none
+    ticks: u16,
+    /// Text under the two ends of the dial
+    labels: Option<(&'a str, &'a str)>,
+    /// Whether anything has changed since the gauge was last rendered
+    changed: bool,
+}
+
+impl<'a> Gauge<'a> {
+    /// Creates a gauge whose needle swings from left to right as its value goes
+    /// from `min` to `max`, starting at `min`, with a tick mark at each end and
+    /// three in between
+    ///
+    /// Returns `InvalidArgument` unless `max` is more than `min`.
+    pub fn new(min: f32, max: f32) -> Result<Self, OledError> {
+        let bounds = Bounds::new(min, max, "gauge maximum must be more than its minimum")?;
+        Ok(Gauge {
+            bounds,
+            value: min,
+            ticks: 4,
+            labels: None,
+            changed: true,
+        })
+    }
+
+    /// Divides the dial into `spaces` equal parts with tick marks, or leaves
+    /// them off if 0
+    pub fn with_ticks(self, spaces: u16) -> Self {
+        Gauge {
+            ticks: spaces,
+            ..self
+        }
+    }
+
+    /// Writes `min` under the left end of the dial and `max` under the right,
+    /// in the proportional built-in font
+    pub fn with_labels(self, min: &'a str, max: &'a str) -> Self {
+        Gauge {
+            labels: Some((min, max)),
+            ..self
+        }
+    }
+
+    /// Sets the value the needle points to. Values outside the gauge's range
+    /// are taken to be the nearest end of it, and `NaN` as the minimum.
+    pub fn set_value(&mut self, value: f32) {
+        let value = self.bounds.clamp(value);
+        self.changed |= value != self.value;
+        self.value = value;
+    }
+
+    /// The value the needle points to, as last set
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+}
+
+impl Widget for Gauge<'_> {
+    fn render(&mut self, frame: &mut Framebuffer, area: Rect) {
+        frame.fill_rect(area.x, area.y, area.width, area.height, false);
+        let font = BuiltinFont::PROPORTIONAL;
+        let label_height = match self.labels {
+            Some(_) => font.line_height(),
+            None => 0,
+        };
+        // a row below the centre is left for the needle's hub
+        let radius =
+            (area.width.saturating_sub(1) / 2).min(area.height.saturating_sub(label_height + 2));
+        let centre = (area.x + area.width / 2, area.y + radius);
+        frame.draw_arc(centre.0, centre.1, radius, 0.0, 180.0, true);
+        for tick in (0..=self.ticks).filter(|_| self.ticks > 0) {
+            let degrees = 180.0 - 180.0 * f32::from(tick) / f32::from(self.ticks);
+            let (x0, y0) = towards(centre, degrees, radius);
+            let (x1, y1) = towards(centre, degrees, radius.saturating_sub(TICK_LENGTH));
+            frame.draw_line(x0, y0, x1, y1, true);
+        }
+        let degrees = 180.0 - 180.0 * self.bounds.fraction(self.value);
+        let (x, y) = towards(centre, degrees, radius.saturating_sub(TICK_LENGTH + 1));
+        frame.draw_line(centre.0, centre.1, x, y, true);
+        frame.fill_circle(centre.0, centre.1, 1, true);
+        if let Some((min, max)) = self.labels {
+            let clip = TextRegion::new(area.x, area.y, area.width, area.height);
+            let y = centre.1 + 2;
+            let left = i32::from(centre.0 - radius);
+            let right = i32::from(centre.0 + radius) + 2 - i32::from(font.text_width(max));
+            frame.draw_text_clipped(left, y, min, &font, &clip);
+            frame.draw_text_clipped(right, y, max, &font, &clip);
+        }
+        self.changed = false;
+    }
+
+    fn needs_render(&self) -> bool {
+        self.changed
+    }
+}
@@ -0,0 +1,236 @@
+//! Telling short, long, double and held presses of the buttons apart
+use std::time::{Duration, Instant};
+
+use crate::{ButtonEvent, Input, Key, OledError};
+
+/// A way of pressing one of the buttons, as recognised by a
+/// [`GestureDetector`](struct.GestureDetector.html)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Gesture {
+    /// The button was pressed and let go quickly
+    Press(Key),
+    /// The button has been held down for a while
+    LongPress(Key),
+    /// The button was pressed twice in quick succession
+    DoublePress(Key),
+    /// The button is still held down after a long press
+    Repeat(Key),
+}
+
+impl Gesture {
+    /// The button pressed
+    pub fn key(&self) -> Key {
+        match self {
+            Gesture::Press(key)
+            | Gesture::LongPress(key)
+            | Gesture::DoublePress(key)
+            | Gesture::Repeat(key) => *key,
+        }
+    }
+}
+
+/// What is known about one button
+#[derive(Clone, Copy, Debug, Default)]
+struct KeyState {
+    /// Whether it's down, as last read
+    raw: bool,
+    /// Whether it's down, once debounced
+    pressed: bool,
+    /// When `pressed` last changed
+    changed_at: Option<Instant>,
+    /// When a long press or repeat is next due, while held
+    hold_due: Option<Instant>,
+    /// Whether this press has been a long press
+    long: bool,
+    /// Whether this press is the second of a double press
+    second: bool,
+    /// When a short press was let go, while waiting to see if it's the first
+    /// of a double press
+    released_at: Option<Instant>,
+}
+
+/// Turns raw presses and releases of [`Buttons`](struct.Buttons.html) into
+/// [`Gesture`](enum.Gesture.html)s, so each of the three buttons can do more
+/// than one thing, eg a short press of K2 to move down a menu, a long press to
+/// go back, and repeats while held to move quickly.
+///
+/// Presses are debounced, ignoring changes for 20 ms after each, and a press
+/// held for 600 ms is a long press. A short press is reported when it's let go,
+/// unless double presses are recognised, in which case it's reported once the
+/// window for a second press has passed; a long press is reported while it's
+/// still held. Double presses and repeats are only recognised if turned on.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{Buttons, Gesture, GestureDetector, Key, OledError};
+/// # use std::{thread::sleep, time::Duration};
+/// # fn main() -> Result<(), OledError> {
+/// let mut buttons = Buttons::new()?;
+/// let mut gestures = GestureDetector::new()
+///     .with_double_press(Some(Duration::from_millis(300)))
+///     .with_repeat(Some(Duration::from_millis(150)));
+/// loop {
+///     for gesture in gestures.poll(&mut buttons)? {
+///         match gesture {
+///             Gesture::Press(Key::K2) | Gesture::Repeat(Key::K2) => println!("down"),
+///             Gesture::DoublePress(Key::K2) => println!("select"),
+///             Gesture::LongPress(Key::K1) => return Ok(()),
+///             _ => {}
+///         }
+///     }
+///     sleep(Duration::from_millis(10));
+/// }
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct GestureDetector {
+    /// How long changes are ignored for after each
+    debounce: Duration,
+    /// How long a button is held for a long press
+    long_press: Duration,
+    /// Longest wait between the presses of a double press, if recognised
+    double_press: Option<Duration>,
+    /// Time between repeats while held after a long press, if repeating
+    repeat: Option<Duration>,
+    /// State of each button
+    keys: [KeyState; 3],
+}
+
+impl Default for GestureDetector {
+    fn default() -> Self {
+        GestureDetector::new()
+    }
+}
+
+impl GestureDetector {
+    /// Creates a detector recognising short and long presses
+    pub fn new() -> Self {
+        GestureDetector {
+            debounce: Duration::from_millis(20),
+            long_press: Duration::from_millis(600),
+            double_press: None,
+            repeat: None,
+            keys: [KeyState::default(); 3],
+        }
+    }
+
+    /// Ignores changes for `debounce` after each, while a button's contacts settle
+    pub fn with_debounce(self, debounce: Duration) -> Self {
+        GestureDetector { debounce, ..self }
+    }
+
+    /// Takes a button held for `long_press` to be a long press
+    pub fn with_long_press(self, long_press: Duration) -> Self {
+        GestureDetector { long_press, ..self }
+    }
+
+    /// Recognises two presses less than `window` apart as a double press, or
+    /// doesn't if `None`
+    pub fn with_double_press(self, window: Option<Duration>) -> Self {
+        GestureDetector {
+            double_press: window,
+            ..self
+        }
+    }
+
+    /// Repeats every `interval` while a button is held after a long press, or
+    /// doesn't if `None`
+    pub fn with_repeat(self, interval: Option<Duration>) -> Self {
+        GestureDetector {
+            repeat: interval,
+            ..self
+        }
+    }
+
+    /// Polls `buttons`, or any other [`Input`](trait.Input.html), and returns
+    /// the gestures they make
+    pub fn poll<B: Input + ?Sized>(
+        &mut self,
+        buttons: &mut B,
+    ) -> Result<impl Iterator<Item = Gesture>, OledError> {
+        let events = buttons.poll_events()?;
+        Ok(self.update(events, Instant::now()))
+    }
+
+    /// Takes in `events` that happened at `now`, and returns the gestures made
+    /// by them and by time passing since the last update. This should be called
+    /// regularly, every 10 ms or so, even when there are no events.
+    pub fn update<E: IntoIterator<Item = ButtonEvent>>(
+        &mut self,
+        events: E,
+        now: Instant,
+    ) -> impl Iterator<Item = Gesture> {
+        let mut gestures = Vec::new();
+        for event in events {
+            let (key, down) = match event {
+                ButtonEvent::Press(key) => (key, true),
+                ButtonEvent::Release(key) => (key, false),
+            };
+            self.keys[key as usize].raw = down;
+            self.settle(key, now, &mut gestures);
+        }
+        for key in Key::ALL.iter().copied() {
+            self.settle(key, now, &mut gestures);
+            self.time_passes(key, now, &mut gestures);
+        }
+        gestures.into_iter()
+    }
+
+    /// Changes whether `key` is pressed to match its last reading, unless it's
+    /// still bouncing
+    fn settle(&mut self, key: Key, now: Instant, gestures: &mut Vec<Gesture>) {
+        let (debounce, double_press) = (self.debounce, self.double_press);
+        let state = &mut self.keys[key as usize];
+        let bouncing = state
+            .changed_at
+            .is_some_and(|changed_at| now.saturating_duration_since(changed_at) < debounce);
+        if state.raw == state.pressed || bouncing {
+            return;
+        }
+        state.pressed = state.raw;
+        state.changed_at = Some(now);
+        if state.pressed {
+            state.long = false;
+            state.hold_due = Some(now + self.long_press);
+            let window = double_press.unwrap_or_default();
+            state.second = state
+                .released_at
+                .take()
+                .is_some_and(|released_at| now.saturating_duration_since(released_at) <= window);
+            if state.second {
+                state.hold_due = None;
+                gestures.push(Gesture::DoublePress(key));
+            }
+        } else {
+            state.hold_due = None;
+            if state.long || state.second {
+                return;
+            }
+            match double_press {
+                Some(_) => state.released_at = Some(now),
+                None => gestures.push(Gesture::Press(key)),
+            }
+        }
+    }
+
+    /// Reports long presses and repeats of `key` that have come due, and short
+    /// presses that weren't followed by a second in time
+    fn time_passes(&mut self, key: Key, now: Instant, gestures: &mut Vec<Gesture>) {
+        let state = &mut self.keys[key as usize];
+        if state.hold_due.is_some_and(|due| due <= now) {
+            if state.long {
+                gestures.push(Gesture::Repeat(key));
+            } else {
+                state.long = true;
+                gestures.push(Gesture::LongPress(key));
+            }
+            state.hold_due = self.repeat.map(|interval| now + interval);
+        }
+        let window = self.double_press.unwrap_or_default();
+        if let Some(released_at) = state.released_at {
+            if now.saturating_duration_since(released_at) > window {
+                state.released_at = None;
+                gestures.push(Gesture::Press(key));
+            }
+        }
+    }
+}
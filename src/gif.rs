@@ -0,0 +1,422 @@
+//! Playing animated GIFs, with a small decoder of its own
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use crate::dither::dither;
+use crate::scale::{letterbox, MAX_PIXELS};
+use crate::{Dither, Framebuffer, Oled, OledError, OledInterface, OledResult};
+
+/// Largest number of codes an LZW table can hold in a GIF
+const MAX_CODES: usize = 4096;
+
+/// Delay GIF decoders conventionally give frames that ask for 0 or 10ms
+const DEFAULT_DELAY: Duration = Duration::from_millis(100);
+
+/// How [`Oled::play_gif()`](struct.Oled.html#method.play_gif) shows an animation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PlayOptions {
+    /// How frames are reduced to lit and unlit pixels
+    pub dither: Dither,
+    /// Number of times to play the animation, or `None` to follow the file, which
+    /// may ask for it to be played once, a set number of times, or forever
+    pub loops: Option<u32>,
+}
+
+impl PlayOptions {
+    /// Frames are dithered with Floyd–Steinberg, and looped as the file asks
+    pub const DEFAULT: PlayOptions = PlayOptions {
+        dither: Dither::FloydSteinberg,
+        loops: None,
+    };
+}
+
+impl Default for PlayOptions {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// How the area a frame covers is treated before the next frame is drawn
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Disposal {
+    /// Left as it is
+    Keep,
+    /// Cleared to black
+    Background,
+    /// Restored to what was there before the frame was drawn
+    Previous,
+}
+
+/// Settings from a graphic control extension, which apply to the next image
+#[derive(Clone, Copy)]
+struct Control {
+    /// What happens to the image once it has been shown
+    disposal: Disposal,
+    /// Palette index of pixels that leave the canvas unchanged, if any
+    transparent: Option<u8>,
+    /// How long the image is shown
+    delay: Duration,
+}
+
+impl Default for Control {
+    fn default() -> Self {
+        Control {
+            disposal: Disposal::Keep,
+            transparent: None,
+            delay: DEFAULT_DELAY,
+        }
+    }
+}
+
+/// The error for a GIF that ends before it should
+fn truncated() -> OledError {
+    OledError::InvalidArgument("GIF data is truncated")
+}
+
+/// Reads the parts of a GIF file in order
+struct Reader<'a> {
+    /// The whole file
+    data: &'a [u8],
+    /// Offset of the next byte to read
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    /// Whether the whole file has been read
+    fn at_end(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    /// Reads `len` bytes
+    fn take(&mut self, len: usize) -> Result<&'a [u8], OledError> {
+        let bytes = self
+            .data
+            .get(self.pos..self.pos + len)
+            .ok_or_else(truncated)?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    /// Reads a byte
+    fn byte(&mut self) -> Result<u8, OledError> {
+        Ok(self.take(1)?[0])
+    }
+
+    /// Reads a little-endian 16-bit number
+    fn u16(&mut self) -> Result<u16, OledError> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    /// Reads a run of data sub-blocks, up to the empty one that ends it,
+    /// returning their contents joined together
+    fn sub_blocks(&mut self) -> Result<Vec<u8>, OledError> {
+        let mut data = Vec::new();
+        loop {
+            let len = self.byte()? as usize;
+            if len == 0 {
+                return Ok(data);
+            }
+            data.extend_from_slice(self.take(len)?);
+        }
+    }
+
+    /// Reads a color table if `flags` says there is one, returning the gray level
+    /// of each of its colors
+    fn palette(&mut self, flags: u8) -> Result<Option<Vec<u8>>, OledError> {
+        if flags & 0x80 == 0 {
+            return Ok(None);
+        }
+        let colors = 2 << (flags & 0x07);
+        let table = self.take(colors * 3)?;
+        Ok(Some(
+            table
+                .chunks_exact(3)
+                .map(|rgb| {
+                    let luma =
+                        77 * u32::from(rgb[0]) + 150 * u32::from(rgb[1]) + 29 * u32::from(rgb[2]);
+                    (luma >> 8) as u8
+                })
+                .collect(),
+        ))
+    }
+}
+
+/// Decompresses an image's LZW data into `len` palette indices. Data that stops
+/// short leaves the rest of the image as index 0, as most decoders do.
+fn decompress(min_code_size: u8, data: &[u8], len: usize) -> Result<Vec<u8>, OledError> {
+    let corrupt = || OledError::InvalidArgument("GIF image data is corrupt");
+    if !(1..=11).contains(&min_code_size) {
+        return Err(corrupt());
+    }
+    let clear = 1usize << min_code_size;
+    let end = clear + 1;
+    // each code is a string: the string of its prefix code, plus its suffix
+    let mut prefix = [0u16; MAX_CODES];
+    let mut suffix = [0u8; MAX_CODES];
+    let mut first = [0u8; MAX_CODES];
+    for code in 0..clear {
+        suffix[code] = code as u8;
+        first[code] = code as u8;
+    }
+    let mut next = end + 1;
+    let mut code_size = min_code_size + 1;
+    let mut previous: Option<usize> = None;
+    let mut output = Vec::with_capacity(len);
+    let mut string = Vec::new();
+    let (mut bits, mut bit_count) = (0u32, 0u8);
+    let mut bytes = data.iter();
+    while output.len() < len {
+        while bit_count < code_size {
+            match bytes.next() {
+                Some(byte) => {
+                    bits |= u32::from(*byte) << bit_count;
+                    bit_count += 8;
+                }
+                None => break,
+            }
+        }
+        if bit_count < code_size {
+            break;
+        }
+        let code = (bits & ((1 << code_size) - 1)) as usize;
+        bits >>= code_size;
+        bit_count -= code_size;
+        if code == clear {
+            next = end + 1;
+            code_size = min_code_size + 1;
+            previous = None;
+            continue;
+        }
+        if code == end {
+            break;
+        }
+        let Some(prev) = previous else {
+            if code > clear {
+                return Err(corrupt());
+            }
+            output.push(code as u8);
+            previous = Some(code);
+            continue;
+        };
+        // a code one past the end of the table is the previous string plus its own first byte
+        let (known, extra) = match code {
+            code if code < next => (code, None),
+            code if code == next => (prev, Some(first[prev])),
+            _ => return Err(corrupt()),
+        };
+        string.clear();
+        let mut link = known;
+        while link > end {
+            string.push(suffix[link]);
+            link = prefix[link] as usize;
+        }
+        string.push(suffix[link]);
+        output.extend(string.iter().rev());
+        output.extend(extra);
+        if next < MAX_CODES {
+            prefix[next] = prev as u16;
+            suffix[next] = first[known];
+            first[next] = first[prev];
+            next += 1;
+            if next == 1 << code_size && code_size < 12 {
+                code_size += 1;
+            }
+        }
+        previous = Some(code);
+    }
+    output.resize(len, 0);
+    Ok(output)
+}
+
+/// Returns the row of an interlaced image stored at position `row`
+fn deinterlace(row: usize, height: usize) -> usize {
+    let mut start = 0;
+    for (first, step) in [(0, 8), (4, 8), (2, 4), (1, 2)] {
+        let rows = (height + step - 1 - first) / step;
+        if row < start + rows {
+            return first + (row - start) * step;
+        }
+        start += rows;
+    }
+    row
+}
+
+/// Decodes a GIF file, passing each frame to `frame` as the whole canvas once
+/// the frame is drawn on it, with the canvas's width and height and how long the
+/// frame is shown. Returns the number of times the file asks to be played, or
+/// `None` for forever, and `InvalidDimensions` if the canvas or a frame has more
+/// than `MAX_PIXELS`.
+fn decode<F>(data: &[u8], mut frame: F) -> Result<Option<u32>, OledError>
+where
+    F: FnMut(&[u8], usize, usize, Duration) -> OledResult,
+{
+    let mut reader = Reader { data, pos: 0 };
+    let signature = reader.take(6).ok();
+    if signature != Some(b"GIF87a") && signature != Some(b"GIF89a") {
+        return Err(OledError::InvalidArgument("not a GIF image"));
+    }
+    let width = reader.u16()? as usize;
+    let height = reader.u16()? as usize;
+    let flags = reader.byte()?;
+    // the background color and pixel aspect ratio are ignored: cleared areas are black
+    reader.take(2)?;
+    if width == 0 || height == 0 || width * height > MAX_PIXELS {
+        return Err(OledError::InvalidDimensions);
+    }
+    let global_palette = reader.palette(flags)?;
+    let mut canvas = vec![0u8; width * height];
+    let mut frames = 0;
+    let mut loops = Some(1);
+    let mut control = Control::default();
+    // many encoders leave off the trailer, so running out of data between blocks is fine
+    while !reader.at_end() {
+        match reader.byte()? {
+            0x21 => {
+                let label = reader.byte()?;
+                let block = reader.sub_blocks()?;
+                match label {
+                    0xf9 if block.len() >= 4 => {
+                        let hundredths = u16::from_le_bytes([block[1], block[2]]);
+                        control = Control {
+                            disposal: match (block[0] >> 2) & 0x07 {
+                                2 => Disposal::Background,
+                                3 => Disposal::Previous,
+                                _ => Disposal::Keep,
+                            },
+                            transparent: Some(block[3]).filter(|_| block[0] & 0x01 != 0),
+                            delay: if hundredths > 1 {
+                                Duration::from_millis(u64::from(hundredths) * 10)
+                            } else {
+                                DEFAULT_DELAY
+                            },
+                        };
+                    }
+                    0xff if block.len() >= 14
+                        && (block.starts_with(b"NETSCAPE2.0")
+                            || block.starts_with(b"ANIMEXTS1.0"))
+                        && block[11] == 1 =>
+                    {
+                        // the count is of repeats after the first play, and 0 is forever
+                        let repeats = u16::from_le_bytes([block[12], block[13]]);
+                        loops = match repeats {
+                            0 => None,
+                            repeats => Some(u32::from(repeats) + 1),
+                        };
+                    }
+                    _ => {}
+                }
+            }
+            0x2c => {
+                let left = reader.u16()? as usize;
+                let top = reader.u16()? as usize;
+                let image_width = reader.u16()? as usize;
+                let image_height = reader.u16()? as usize;
+                if image_width * image_height > MAX_PIXELS {
+                    return Err(OledError::InvalidDimensions);
+                }
+                let flags = reader.byte()?;
+                let palette = reader
+                    .palette(flags)?
+                    .or_else(|| global_palette.clone())
+                    .ok_or(OledError::InvalidArgument("GIF has no color table"))?;
+                let min_code_size = reader.byte()?;
+                let compressed = reader.sub_blocks()?;
+                let indices = decompress(min_code_size, &compressed, image_width * image_height)?;
+                let previous = (control.disposal == Disposal::Previous).then(|| canvas.clone());
+                for (row, pixels) in indices.chunks(image_width.max(1)).enumerate() {
+                    let row = if flags & 0x40 != 0 {
+                        deinterlace(row, image_height)
+                    } else {
+                        row
+                    };
+                    let y = top + row;
+                    for (column, index) in pixels.iter().enumerate() {
+                        let x = left + column;
+                        if x < width && y < height && Some(*index) != control.transparent {
+                            canvas[y * width + x] =
+                                palette.get(*index as usize).copied().unwrap_or(0);
+                        }
+                    }
+                }
+                frame(&canvas, width, height, control.delay)?;
+                frames += 1;
+                match (control.disposal, previous) {
+                    (Disposal::Previous, Some(previous)) => canvas = previous,
+                    (Disposal::Background, _) => {
+                        for y in top..(top + image_height).min(height) {
+                            for x in left..(left + image_width).min(width) {
+                                canvas[y * width + x] = 0;
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+                control = Control::default();
+            }
+            0x3b => break,
+            _ => return Err(OledError::InvalidArgument("GIF data is corrupt")),
+        }
+    }
+    if frames == 0 {
+        return Err(OledError::InvalidArgument("GIF has no frames"));
+    }
+    Ok(loops)
+}
+
+impl<I: OledInterface> Oled<I> {
+    /// Plays an animated GIF, eg a boot animation, and returns once it has finished.
+    /// Each frame is scaled to fit the display as
+    /// [`draw_image_fit()`](struct.Oled.html#method.draw_image_fit) does, dithered,
+    /// and shown for as long as the file asks; only the parts of the display that
+    /// change between frames are sent. A still GIF is simply drawn.
+    ///
+    /// Unless `options` says otherwise, the animation loops as many times as the
+    /// file asks, which may be forever, in which case this only returns if drawing
+    /// fails. Returns `InvalidArgument` if `gif` can't be decoded.
+    /// # Example:
+    /// ```no_run
+    /// # use nanohat_oled::{Oled, PlayOptions};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let gif = std::fs::read("boot.gif")?;
+    /// let mut oled = Oled::from_path("/dev/i2c-0")?;
+    /// oled.init()?;
+    /// let options = PlayOptions {
+    ///     loops: Some(3),
+    ///     ..PlayOptions::DEFAULT
+    /// };
+    /// oled.play_gif(&gif, options)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn play_gif(&mut self, gif: &[u8], options: PlayOptions) -> OledResult {
+        let (width, height) = self.dimensions();
+        let portrait = self.rotation.is_portrait();
+        let mut frames = Vec::new();
+        let loops = decode(gif, |pixels, gif_width, gif_height, delay| {
+            let fitted = letterbox(
+                pixels,
+                gif_width,
+                gif_height,
+                width as usize,
+                height as usize,
+            )?;
+            let dithered = dither(&fitted, width as usize, options.dither)?;
+            self.pack_oriented(&dithered, 0x80)?;
+            frames.push((Framebuffer::with_layout(self.scratch, portrait), delay));
+            Ok(())
+        })?;
+        let loops = options.loops.or(loops);
+        let mut deadline = Instant::now();
+        let mut played = 0;
+        while loops.is_none_or(|loops| played < loops) {
+            for (frame, delay) in &frames {
+                self.flush(frame)?;
+                deadline += *delay;
+                sleep(deadline.saturating_duration_since(Instant::now()));
+            }
+            played += 1;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,104 @@
+//! Glyphs registered by applications, drawn by the text functions alongside the built-in font
+use crate::font::BasicFont;
+use crate::{Oled, OledError, OledInterface, OledResult};
+
+/// Number of glyphs that can be registered at once
+pub const MAX_CUSTOM_GLYPHS: usize = 32;
+
+/// Glyphs registered with [`Oled::register_glyph()`](struct.Oled.html#method.register_glyph)
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct CustomGlyphs {
+    /// Registered characters and their bitmaps; only the first `len` are in use
+    glyphs: [(char, [u8; 8]); MAX_CUSTOM_GLYPHS],
+    /// Number of glyphs registered
+    len: usize,
+}
+
+impl Default for CustomGlyphs {
+    fn default() -> Self {
+        CustomGlyphs {
+            glyphs: [('\0', [0; 8]); MAX_CUSTOM_GLYPHS],
+            len: 0,
+        }
+    }
+}
+
+impl CustomGlyphs {
+    /// The registered glyphs
+    fn registered(&self) -> &[(char, [u8; 8])] {
+        &self.glyphs[..self.len]
+    }
+
+    /// The bitmap registered for `char`, if any
+    fn get(&self, char: char) -> Option<[u8; 8]> {
+        self.registered()
+            .iter()
+            .find(|(registered, _)| *registered == char)
+            .map(|(_, bitmap)| *bitmap)
+    }
+
+    /// The bitmap drawn for `char`: its registered glyph, or the built-in font's,
+    /// or `None` if neither has one
+    pub(crate) fn glyph(&self, char: char) -> Option<[u8; 8]> {
+        self.get(char).or_else(|| BasicFont::glyph(char))
+    }
+
+    /// The bitmap drawn for `char`, falling back to the built-in font's
+    pub(crate) fn bitmap(&self, char: char) -> [u8; 8] {
+        self.get(char).unwrap_or_else(|| BasicFont::bitmap(char))
+    }
+}
+
+impl<I: OledInterface> Oled<I> {
+    /// Registers an 8x8 `bitmap` to be drawn for `char` by
+    /// [`put_char()`](struct.Oled.html#method.put_char) and the other functions that
+    /// write text in the built-in font, so symbols like battery levels can be
+    /// printed inline with it. The bitmap holds the glyph's columns from left to
+    /// right, with the top pixel in bit 0, as in display RAM. Registering a
+    /// character again replaces its bitmap, and registered glyphs take precedence
+    /// over the built-in font; characters in Unicode's Private Use Area, from
+    /// `'\u{e000}'`, are a good choice for symbols of your own.
+    ///
+    /// Returns `InvalidArgument` if [`MAX_CUSTOM_GLYPHS`](constant.MAX_CUSTOM_GLYPHS.html)
+    /// other characters are registered already.
+    /// # Example:
+    /// ```no_run
+    /// # use nanohat_oled::{Oled, OledResult};
+    /// # fn main() -> OledResult {
+    /// const BATTERY_HALF: char = '\u{e000}';
+    ///
+    /// let mut oled = Oled::from_path("/dev/i2c-0")?;
+    /// oled.init()?;
+    /// oled.register_glyph(BATTERY_HALF, [0x00, 0x3e, 0x3e, 0x3e, 0x22, 0x22, 0x3e, 0x1c])?;
+    /// oled.put_string("Battery \u{e000} 50%")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn register_glyph(&mut self, char: char, bitmap: [u8; 8]) -> OledResult {
+        let glyphs = &mut self.custom_glyphs;
+        if let Some(index) = glyphs.registered().iter().position(|(c, _)| *c == char) {
+            glyphs.glyphs[index].1 = bitmap;
+            return Ok(());
+        }
+        if glyphs.len == MAX_CUSTOM_GLYPHS {
+            return Err(OledError::InvalidArgument("too many custom glyphs"));
+        }
+        glyphs.glyphs[glyphs.len] = (char, bitmap);
+        glyphs.len += 1;
+        Ok(())
+    }
+
+    /// Removes the glyph registered for `char`, so the built-in font is used for
+    /// it again. Returns whether there was one.
+    pub fn unregister_glyph(&mut self, char: char) -> bool {
+        let glyphs = &mut self.custom_glyphs;
+        match glyphs.registered().iter().position(|(c, _)| *c == char) {
+            Some(index) => {
+                glyphs.glyphs.copy_within(index + 1..glyphs.len, index);
+                glyphs.len -= 1;
+                true
+            }
+            None => false,
+        }
+    }
+}
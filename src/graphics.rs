@@ -0,0 +1,618 @@
+//! Lines, rectangles, circles, ellipses and polygons, drawn into a [`Framebuffer`](struct.Framebuffer.html)
+use crate::{Framebuffer, Oled, OledInterface, OledResult, OLED_RAM_SIZE, OLED_WIDTH};
+
+impl Framebuffer {
+    /// Sets a pixel given signed coordinates, ignoring anything off the display
+    pub(crate) fn plot(&mut self, x: i32, y: i32, on: bool) {
+        if x >= 0 && y >= 0 && x <= i32::from(u16::MAX) && y <= i32::from(u16::MAX) {
+            self.set_pixel(x as u16, y as u16, on);
+        }
+    }
+
+    /// Draws a horizontal run of pixels from `x0` to `x1` inclusive
+    fn span(&mut self, x0: i32, x1: i32, y: i32, on: bool) {
+        for x in x0.min(x1)..=x0.max(x1) {
+            self.plot(x, y, on);
+        }
+    }
+
+    /// Draws a straight line between two points, including both ends, using
+    /// Bresenham's algorithm. Parts of the line off the display are clipped.
+    pub fn draw_line(&mut self, x0: u16, y0: u16, x1: u16, y1: u16, on: bool) {
+        let (from, to) = (
+            (i32::from(x0), i32::from(y0)),
+            (i32::from(x1), i32::from(y1)),
+        );
+        for_each_line_point(from, to, |x, y| self.plot(x, y, on));
+    }
+
+    /// Draws a line between two points given signed coordinates, cut down to
+    /// the part on the display first, so far-off points don't take long to draw
+    fn clipped_line(&mut self, from: (i32, i32), to: (i32, i32), on: bool) {
+        if let Some((from, to)) = clip_line(self.dimensions(), from, to) {
+            for_each_line_point(from, to, |x, y| self.plot(x, y, on));
+        }
+    }
+
+    /// Draws the outline of a rectangle `width` by `height` pixels,
+    /// with its top left corner at `x`, `y`. Nothing is drawn if either is 0.
+    pub fn draw_rect(&mut self, x: u16, y: u16, width: u16, height: u16, on: bool) {
+        if width == 0 || height == 0 {
+            return;
+        }
+        let (left, top) = (i32::from(x), i32::from(y));
+        let right = left + i32::from(width) - 1;
+        let bottom = top + i32::from(height) - 1;
+        self.span(left, right, top, on);
+        self.span(left, right, bottom, on);
+        for row in top..=bottom {
+            self.plot(left, row, on);
+            self.plot(right, row, on);
+        }
+    }
+
+    /// Fills a rectangle `width` by `height` pixels, with its top left corner at `x`, `y`
+    pub fn fill_rect(&mut self, x: u16, y: u16, width: u16, height: u16, on: bool) {
+        let (left, top) = (i32::from(x), i32::from(y));
+        for row in top..top + i32::from(height) {
+            for column in left..left + i32::from(width) {
+                self.plot(column, row, on);
+            }
+        }
+    }
+
+    /// Draws the outline of a circle centred on `x`, `y`, using the midpoint circle
+    /// algorithm. A radius of 0 draws a single pixel. Parts off the display are clipped.
+    pub fn draw_circle(&mut self, x: u16, y: u16, radius: u16, on: bool) {
+        let (cx, cy) = (i32::from(x), i32::from(y));
+        for_each_octant_point(radius, |dx, dy| {
+            for (px, py) in [
+                (dx, dy),
+                (dy, dx),
+                (-dx, dy),
+                (-dy, dx),
+                (dx, -dy),
+                (dy, -dx),
+                (-dx, -dy),
+                (-dy, -dx),
+            ] {
+                self.plot(cx + px, cy + py, on);
+            }
+        });
+    }
+
+    /// Fills a circle centred on `x`, `y`, covering the same pixels as
+    /// [`draw_circle()`](struct.Framebuffer.html#method.draw_circle) and everything inside.
+    pub fn fill_circle(&mut self, x: u16, y: u16, radius: u16, on: bool) {
+        let (cx, cy) = (i32::from(x), i32::from(y));
+        for_each_octant_point(radius, |dx, dy| {
+            self.span(cx - dx, cx + dx, cy + dy, on);
+            self.span(cx - dx, cx + dx, cy - dy, on);
+            self.span(cx - dy, cx + dy, cy + dx, on);
+            self.span(cx - dy, cx + dy, cy - dx, on);
+        });
+    }
+
+    /// Draws the part of a circle's outline centred on `x`, `y` that runs
+    /// anticlockwise from `start` to `end`, in degrees anticlockwise from the
+    /// right, so 0 to 180 is the top half. It covers the same pixels as that part
+    /// of [`draw_circle()`](struct.Framebuffer.html#method.draw_circle). Parts off
+    /// the display are clipped.
+    pub fn draw_arc(&mut self, x: u16, y: u16, radius: u16, start: f32, end: f32, on: bool) {
+        let (cx, cy) = (i32::from(x), i32::from(y));
+        let inside = arc_sweep(start, end);
+        for_each_octant_point(radius, |dx, dy| {
+            for (px, py) in [
+                (dx, dy),
+                (dy, dx),
+                (-dx, dy),
+                (-dy, dx),
+                (dx, -dy),
+                (dy, -dx),
+                (-dx, -dy),
+                (-dy, -dx),
+            ] {
+                if inside(px, py) {
+                    self.plot(cx + px, cy + py, on);
+                }
+            }
+        });
+    }
+
+    /// Fills the slice of a circle centred on `x`, `y` between `start` and `end`,
+    /// as for [`draw_arc()`](struct.Framebuffer.html#method.draw_arc), eg a
+    /// segment of a pie chart. It covers the same pixels as that part of
+    /// [`fill_circle()`](struct.Framebuffer.html#method.fill_circle).
+    pub fn fill_arc(&mut self, x: u16, y: u16, radius: u16, start: f32, end: f32, on: bool) {
+        let (cx, cy) = (i32::from(x), i32::from(y));
+        let inside = arc_sweep(start, end);
+        let mut span = |dx: i32, dy: i32| {
+            for px in -dx..=dx {
+                if inside(px, dy) {
+                    self.plot(cx + px, cy + dy, on);
+                }
+            }
+        };
+        for_each_octant_point(radius, |dx, dy| {
+            span(dx, dy);
+            span(dx, -dy);
+            span(dy, dx);
+            span(dy, -dx);
+        });
+    }
+
+    /// Draws the outline of an ellipse centred on `x`, `y`, reaching `radius_x`
+    /// to either side and `radius_y` above and below, using the midpoint ellipse
+    /// algorithm. Parts off the display are clipped.
+    pub fn draw_ellipse(&mut self, x: u16, y: u16, radius_x: u16, radius_y: u16, on: bool) {
+        let (cx, cy) = (i32::from(x), i32::from(y));
+        for_each_quadrant_point(radius_x, radius_y, |dx, dy| {
+            for (px, py) in [(dx, dy), (-dx, dy), (dx, -dy), (-dx, -dy)] {
+                self.plot(cx + px, cy + py, on);
+            }
+        });
+    }
+
+    /// Fills an ellipse centred on `x`, `y`, covering the same pixels as
+    /// [`draw_ellipse()`](struct.Framebuffer.html#method.draw_ellipse) and everything inside.
+    pub fn fill_ellipse(&mut self, x: u16, y: u16, radius_x: u16, radius_y: u16, on: bool) {
+        let (cx, cy) = (i32::from(x), i32::from(y));
+        for_each_quadrant_point(radius_x, radius_y, |dx, dy| {
+            self.span(cx - dx, cx + dx, cy + dy, on);
+            self.span(cx - dx, cx + dx, cy - dy, on);
+        });
+    }
+
+    /// Draws the outline of a rectangle like
+    /// [`draw_rect()`](struct.Framebuffer.html#method.draw_rect), with its corners
+    /// rounded off as quarters of a circle of `radius`, eg for a button or a
+    /// frame. The radius is limited to what fits; 0 gives square corners.
+    pub fn draw_round_rect(
+        &mut self,
+        x: u16,
+        y: u16,
+        width: u16,
+        height: u16,
+        radius: u16,
+        on: bool,
+    ) {
+        let Some(corners) = Corners::new(x, y, width, height, radius) else {
+            return;
+        };
+        let Corners {
+            left,
+            top,
+            right,
+            bottom,
+            radius,
+        } = corners;
+        self.span(left + radius, right - radius, top, on);
+        self.span(left + radius, right - radius, bottom, on);
+        for row in top + radius..=bottom - radius {
+            self.plot(left, row, on);
+            self.plot(right, row, on);
+        }
+        for_each_octant_point(radius as u16, |dx, dy| {
+            for (px, py) in [(dx, dy), (dy, dx)] {
+                self.plot(left + radius - px, top + radius - py, on);
+                self.plot(right - radius + px, top + radius - py, on);
+                self.plot(left + radius - px, bottom - radius + py, on);
+                self.plot(right - radius + px, bottom - radius + py, on);
+            }
+        });
+    }
+
+    /// Fills a rectangle with rounded corners, covering the same pixels as
+    /// [`draw_round_rect()`](struct.Framebuffer.html#method.draw_round_rect) and
+    /// everything inside
+    pub fn fill_round_rect(
+        &mut self,
+        x: u16,
+        y: u16,
+        width: u16,
+        height: u16,
+        radius: u16,
+        on: bool,
+    ) {
+        let Some(corners) = Corners::new(x, y, width, height, radius) else {
+            return;
+        };
+        let Corners {
+            left,
+            top,
+            right,
+            bottom,
+            radius,
+        } = corners;
+        for row in top + radius..=bottom - radius {
+            self.span(left, right, row, on);
+        }
+        for_each_octant_point(radius as u16, |dx, dy| {
+            for (px, py) in [(dx, dy), (dy, dx)] {
+                self.span(
+                    left + radius - px,
+                    right - radius + px,
+                    top + radius - py,
+                    on,
+                );
+                self.span(
+                    left + radius - px,
+                    right - radius + px,
+                    bottom - radius + py,
+                    on,
+                );
+            }
+        });
+    }
+
+    /// Draws lines joining each of `points` to the next, eg the trace of a chart.
+    /// Points can be off the display, even to the left of or above it, and the
+    /// lines are clipped at its edges.
+    pub fn draw_polyline(&mut self, points: &[(i32, i32)], on: bool) {
+        if let [point] = points {
+            self.clipped_line(*point, *point, on);
+        }
+        for pair in points.windows(2) {
+            self.clipped_line(pair[0], pair[1], on);
+        }
+    }
+
+    /// Draws the outline of a polygon, as for
+    /// [`draw_polyline()`](struct.Framebuffer.html#method.draw_polyline) with a
+    /// line from the last of `points` back to the first
+    pub fn draw_polygon(&mut self, points: &[(i32, i32)], on: bool) {
+        self.draw_polyline(points, on);
+        if let (Some(&first), Some(&last)) = (points.first(), points.last()) {
+            self.clipped_line(last, first, on);
+        }
+    }
+
+    /// Fills a polygon with corners at `points`, eg an arrow or a gauge's needle,
+    /// covering the same pixels as
+    /// [`draw_polygon()`](struct.Framebuffer.html#method.draw_polygon) and
+    /// everything inside. The polygon can be any shape; where its edges cross,
+    /// a pixel is inside if a line from it crosses an odd number of edges.
+    /// Corners can be off the display, and what's off it is clipped.
+    pub fn fill_polygon(&mut self, points: &[(i32, i32)], on: bool) {
+        let (width, height) = self.dimensions();
+        for y in 0..height {
+            // each edge crossing the middle of the row flips whether the pixels
+            // from there on are inside
+            let mut flips = [false; OLED_WIDTH as usize];
+            for (index, &(x0, y0)) in points.iter().enumerate() {
+                let (x1, y1) = points[(index + 1) % points.len()];
+                let y = i32::from(y);
+                if y < y0.min(y1) || y >= y0.max(y1) {
+                    continue;
+                }
+                let (x0, y0, x1, y1) = (x0 as f32, y0 as f32, x1 as f32, y1 as f32);
+                let crossing = x0 + (y as f32 + 0.5 - y0) * (x1 - x0) / (y1 - y0);
+                // the pixel nearest the crossing, as the outline rounds to
+                let first = floor(crossing + 0.5);
+                if first < f32::from(width) {
+                    let first = first.max(0.0) as usize;
+                    flips[first] = !flips[first];
+                }
+            }
+            let mut inside = false;
+            for x in 0..width {
+                inside ^= flips[x as usize];
+                if inside {
+                    self.set_pixel(x, y, on);
+                }
+            }
+        }
+        self.draw_polygon(points, on);
+    }
+
+    /// Fills the area around `x`, `y` with `on`: the pixel there, and every pixel
+    /// joined to it, above, below or to the side, that was set the same way, so
+    /// a shape outlined in lit pixels can be filled from a point inside it.
+    /// Nothing is drawn if `x`, `y` is off the display or already `on`.
+    pub fn flood_fill(&mut self, x: u16, y: u16, on: bool) {
+        let (width, height) = self.dimensions();
+        if x >= width || y >= height || self.get_pixel(x, y) == on {
+            return;
+        }
+        // pixels reached so far, kept apart from those already `on`
+        let mut filled = Framebuffer::with_layout([0; OLED_RAM_SIZE], self.is_portrait());
+        filled.set_pixel(x, y, true);
+        let reaches = |filled: &Framebuffer, frame: &Framebuffer, x: u16, y: u16| {
+            if filled.get_pixel(x, y) || frame.get_pixel(x, y) == on {
+                return false;
+            }
+            (x > 0 && filled.get_pixel(x - 1, y))
+                || (x + 1 < width && filled.get_pixel(x + 1, y))
+                || (y > 0 && filled.get_pixel(x, y - 1))
+                || (y + 1 < height && filled.get_pixel(x, y + 1))
+        };
+        // sweep forwards then backwards until nothing more is reached; each
+        // sweep follows the area as far as it runs in that direction
+        let mut spreading = true;
+        while spreading {
+            spreading = false;
+            for y in 0..height {
+                for x in 0..width {
+                    if reaches(&filled, self, x, y) {
+                        filled.set_pixel(x, y, true);
+                        spreading = true;
+                    }
+                }
+            }
+            for y in (0..height).rev() {
+                for x in (0..width).rev() {
+                    if reaches(&filled, self, x, y) {
+                        filled.set_pixel(x, y, true);
+                        spreading = true;
+                    }
+                }
+            }
+        }
+        for y in 0..height {
+            for x in 0..width {
+                if filled.get_pixel(x, y) {
+                    self.set_pixel(x, y, on);
+                }
+            }
+        }
+    }
+}
+
+/// The largest whole number no greater than `value`, which `core` doesn't provide
+pub(crate) fn floor(value: f32) -> f32 {
+    let truncated = value as i64 as f32;
+    if truncated > value {
+        truncated - 1.0
+    } else {
+        truncated
+    }
+}
+
+/// Sine and cosine of an angle in degrees, close enough for drawing with,
+/// which `core` doesn't provide either
+pub(crate) fn sin_cos(degrees: f32) -> (f32, f32) {
+    // the nearest multiple of 90 degrees, and a Taylor series for the rest
+    let quarters = floor(degrees / 90.0 + 0.5);
+    let x = (degrees - quarters * 90.0).to_radians();
+    let x2 = x * x;
+    let sin = x * (1.0 - x2 / 6.0 * (1.0 - x2 / 20.0 * (1.0 - x2 / 42.0)));
+    let cos = 1.0 - x2 / 2.0 * (1.0 - x2 / 12.0 * (1.0 - x2 / 30.0 * (1.0 - x2 / 56.0)));
+    match (quarters as i64).rem_euclid(4) {
+        0 => (sin, cos),
+        1 => (cos, -sin),
+        2 => (-sin, -cos),
+        _ => (-cos, sin),
+    }
+}
+
+/// The point `length` pixels from `x`, `y` at `degrees` anticlockwise from the right
+pub(crate) fn towards((x, y): (u16, u16), degrees: f32, length: u16) -> (u16, u16) {
+    let (sin, cos) = sin_cos(degrees);
+    let length = f32::from(length);
+    let to = |centre: u16, offset: f32| (f32::from(centre) + offset + 0.5).max(0.0) as u16;
+    (to(x, cos * length), to(y, -sin * length))
+}
+
+/// Calls `f` with each point on a straight line between two points, including
+/// both ends, in order from the first, using Bresenham's algorithm
+pub(crate) fn for_each_line_point<F: FnMut(i32, i32)>(
+    (mut x, mut y): (i32, i32),
+    (x1, y1): (i32, i32),
+    mut f: F,
+) {
+    let dx = (x1 - x).abs();
+    let dy = -(y1 - y).abs();
+    let step_x = if x < x1 { 1 } else { -1 };
+    let step_y = if y < y1 { 1 } else { -1 };
+    let mut error = dx + dy;
+    loop {
+        f(x, y);
+        if x == x1 && y == y1 {
+            break;
+        }
+        let doubled = 2 * error;
+        if doubled >= dy {
+            error += dy;
+            x += step_x;
+        }
+        if doubled <= dx {
+            error += dx;
+            y += step_y;
+        }
+    }
+}
+
+/// The part of the line between two points that's on a display `width` by
+/// `height` pixels, using the Liang-Barsky algorithm, or `None` if none of it is.
+/// A line already on the display is returned as is.
+fn clip_line(
+    (width, height): (u16, u16),
+    (x0, y0): (i32, i32),
+    (x1, y1): (i32, i32),
+) -> Option<((i32, i32), (i32, i32))> {
+    let (right, bottom) = (i32::from(width) - 1, i32::from(height) - 1);
+    let on_display = |x: i32, y: i32| (0..=right).contains(&x) && (0..=bottom).contains(&y);
+    if on_display(x0, y0) && on_display(x1, y1) {
+        return Some(((x0, y0), (x1, y1)));
+    }
+    let (dx, dy) = ((x1 - x0) as f32, (y1 - y0) as f32);
+    let (mut enter, mut leave) = (0.0f32, 1.0f32);
+    // for each edge, how fast the line heads out through it, and how far
+    // inside it the line starts
+    for (outwards, inside) in [
+        (-dx, x0 as f32),
+        (dx, (right - x0) as f32),
+        (-dy, y0 as f32),
+        (dy, (bottom - y0) as f32),
+    ] {
+        if outwards == 0.0 {
+            if inside < 0.0 {
+                return None;
+            }
+            continue;
+        }
+        let crossing = inside / outwards;
+        if outwards < 0.0 {
+            enter = enter.max(crossing);
+        } else {
+            leave = leave.min(crossing);
+        }
+        if enter > leave {
+            return None;
+        }
+    }
+    let point = |along: f32| {
+        let x = floor(x0 as f32 + along * dx + 0.5) as i32;
+        let y = floor(y0 as f32 + along * dy + 0.5) as i32;
+        (x.clamp(0, right), y.clamp(0, bottom))
+    };
+    Some((point(enter), point(leave)))
+}
+
+/// The edges of a rectangle with rounded corners, and the radius of its
+/// corners, limited so that they don't overlap
+struct Corners {
+    left: i32,
+    top: i32,
+    right: i32,
+    bottom: i32,
+    radius: i32,
+}
+
+impl Corners {
+    /// The corners of a `width` by `height` rectangle at `x`, `y`, or `None` if it's empty
+    fn new(x: u16, y: u16, width: u16, height: u16, radius: u16) -> Option<Self> {
+        if width == 0 || height == 0 {
+            return None;
+        }
+        let (left, top) = (i32::from(x), i32::from(y));
+        let (right, bottom) = (left + i32::from(width) - 1, top + i32::from(height) - 1);
+        let radius = i32::from(radius)
+            .min((right - left) / 2)
+            .min((bottom - top) / 2);
+        Some(Corners {
+            left,
+            top,
+            right,
+            bottom,
+            radius,
+        })
+    }
+}
+
+/// Returns whether the direction to a point `dx`, `dy` from the centre of a
+/// circle is within the part running anticlockwise from `start` to `end` degrees,
+/// as for [`Framebuffer::draw_arc()`](struct.Framebuffer.html#method.draw_arc)
+fn arc_sweep(start: f32, end: f32) -> impl Fn(i32, i32) -> bool {
+    let sweep = end - start;
+    let whole = sweep.abs() >= 360.0;
+    let wrap = sweep - 360.0 * floor(sweep / 360.0);
+    let (start_sin, start_cos) = sin_cos(start);
+    let (end_sin, end_cos) = sin_cos(start + wrap);
+    // whether the direction to a point is anticlockwise of one angle and
+    // clockwise of the other, with y pointing down the display
+    move |dx: i32, dy: i32| {
+        let (dx, dy) = (dx as f32, -dy as f32);
+        let after_start = start_cos * dy - start_sin * dx >= 0.0;
+        let before_end = dx * end_sin - dy * end_cos >= 0.0;
+        if whole {
+            true
+        } else if wrap <= 180.0 {
+            after_start && before_end
+        } else {
+            after_start || before_end
+        }
+    }
+}
+
+/// Calls `f` with the offsets from the centre of each point on a quarter of the
+/// outline of an ellipse, from the top round to the right, using the midpoint
+/// ellipse algorithm. The rest of the ellipse is its reflections.
+fn for_each_quadrant_point<F: FnMut(i32, i32)>(radius_x: u16, radius_y: u16, mut f: F) {
+    if radius_y == 0 {
+        for dx in 0..=i32::from(radius_x) {
+            f(dx, 0);
+        }
+        return;
+    }
+    let (a2, b2) = (i64::from(radius_x).pow(2), i64::from(radius_y).pow(2));
+    let (mut x, mut y) = (0i64, i64::from(radius_y));
+    let (mut step_x, mut step_y) = (0, 2 * a2 * y);
+    // decisions are kept four times over, to stay in whole numbers
+    let mut decision = 4 * b2 - 4 * a2 * y + a2;
+    // where the outline is flatter than 45 degrees, step across
+    while step_x < step_y {
+        f(x as i32, y as i32);
+        x += 1;
+        step_x += 2 * b2;
+        if decision < 0 {
+            decision += 4 * (step_x + b2);
+        } else {
+            y -= 1;
+            step_y -= 2 * a2;
+            decision += 4 * (step_x - step_y + b2);
+        }
+    }
+    // then step down
+    let mut decision = b2 * (2 * x + 1).pow(2) + 4 * a2 * (y - 1).pow(2) - 4 * a2 * b2;
+    while y >= 0 {
+        f(x as i32, y as i32);
+        y -= 1;
+        step_y -= 2 * a2;
+        if decision > 0 {
+            decision += 4 * (a2 - step_y);
+        } else {
+            x += 1;
+            step_x += 2 * b2;
+            decision += 4 * (step_x - step_y + a2);
+        }
+    }
+}
+
+/// Calls `f` with the offsets of each point on one octant of a circle, from
+/// `(radius, 0)` round to the diagonal. The other octants are reflections of these.
+fn for_each_octant_point<F: FnMut(i32, i32)>(radius: u16, mut f: F) {
+    let mut dx = i32::from(radius);
+    let mut dy = 0;
+    let mut error = 1 - dx;
+    while dx >= dy {
+        f(dx, dy);
+        dy += 1;
+        if error < 0 {
+            error += 2 * dy + 1;
+        } else {
+            dx -= 1;
+            error += 2 * (dy - dx) + 1;
+        }
+    }
+}
+
+impl<I: OledInterface> Oled<I> {
+    /// Draws straight onto the display, starting from what is currently shown.
+    ///
+    /// `f` is handed a [`Framebuffer`](struct.Framebuffer.html) holding a copy of
+    /// the current [`framebuffer()`](struct.Oled.html#method.framebuffer), which is
+    /// then sent with [`flush()`](struct.Oled.html#method.flush). The framebuffer is
+    /// in the display's current orientation, as set by
+    /// [`set_rotation()`](struct.Oled.html#method.set_rotation).
+    /// # Example:
+    /// ```no_run
+    /// # use nanohat_oled::{Oled, OledResult};
+    /// # fn main() -> OledResult {
+    /// let mut oled = Oled::from_path("/dev/i2c-0")?;
+    /// oled.init()?;
+    /// oled.draw(|frame| {
+    ///     frame.draw_rect(0, 0, 128, 64, true);
+    ///     frame.draw_line(0, 0, 127, 63, true);
+    ///     frame.fill_circle(64, 32, 10, true);
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn draw<F: FnOnce(&mut Framebuffer)>(&mut self, f: F) -> OledResult {
+        let mut framebuffer =
+            Framebuffer::with_layout(*self.framebuffer(), self.rotation.is_portrait());
+        f(&mut framebuffer);
+        self.flush(&framebuffer)
+    }
+}
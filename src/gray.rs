@@ -0,0 +1,136 @@
+//! Shades of gray, shown by lighting pixels in only some of a run of frames
+use std::time::{Duration, Instant};
+
+use crate::dither::BAYER_4X4;
+use crate::{Framebuffer, Image, Oled, OledError, OledInterface, OledResult};
+
+/// The most shades of gray between black and white
+const MAX_GRAYS: u8 = 4;
+
+/// Shows grayscale images in a few shades of gray, by lighting each pixel in
+/// as many of a short cycle of frames as its shade needs, so that showing them
+/// one after another quickly enough blends them, as with PWM dimming. Photos
+/// and text drawn with anti-aliasing keep more of their shading than when each
+/// pixel is simply lit or not.
+///
+/// **This is experimental.** How well the shades blend depends on how fast
+/// frames reach the display: over I2C, changes fast enough not to flicker
+/// need a fast bus, and a fair amount of an image in gray. A camera sees the
+/// frames flicker even when the eye doesn't.
+///
+/// With `n` shades of gray between black and white, there are `n + 1` frames
+/// in a cycle. Pixels of the same shade are lit in different frames of it,
+/// offset in a 4x4 pattern, so that a patch of gray shimmers rather than
+/// blinking all at once, and each frame sends roughly the same amount.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{Oled, OledResult, TemporalGray};
+/// # use std::time::Duration;
+/// # fn main() -> OledResult {
+/// # let photo = [0u8; 128 * 64];
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// let mut gray = TemporalGray::new(oled).with_grays(2);
+/// gray.set_image(&photo)?;
+/// gray.show_for(Duration::from_secs(10))?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct TemporalGray<I> {
+    /// The display shown on
+    oled: Oled<I>,
+    /// Shades of gray between black and white
+    grays: u8,
+    /// The frames of the cycle, of which the first `grays + 1` are used
+    frames: [Framebuffer; MAX_GRAYS as usize + 1],
+    /// Index of the frame shown next
+    next: usize,
+}
+
+impl<I: OledInterface> TemporalGray<I> {
+    /// Shows shades of gray on an already-initialized display, two of them
+    /// between black and white, and nothing until an image is set
+    pub fn new(oled: Oled<I>) -> Self {
+        let portrait = oled.rotation.is_portrait();
+        TemporalGray {
+            oled,
+            grays: 2,
+            frames: core::array::from_fn(|_| {
+                if portrait {
+                    Framebuffer::portrait()
+                } else {
+                    Framebuffer::new()
+                }
+            }),
+            next: 0,
+        }
+    }
+
+    /// Shows `grays` shades of gray between black and white, from 1 to 4, rather
+    /// than 2. More shades need more frames, so flicker more. Set this before
+    /// the image.
+    pub fn with_grays(self, grays: u8) -> Self {
+        TemporalGray {
+            grays: grays.clamp(1, MAX_GRAYS),
+            next: 0,
+            ..self
+        }
+    }
+
+    /// The number of frames in a cycle
+    pub fn cycle_len(&self) -> usize {
+        usize::from(self.grays) + 1
+    }
+
+    /// Sets the grayscale image shown, the display's size, as given by
+    /// [`Oled::dimensions()`](struct.Oled.html#method.dimensions), with each
+    /// pixel's level rounded to the nearest shade. Returns `InvalidDimensions`
+    /// if it's another size. Nothing is sent until the next
+    /// [`step()`](#method.step).
+    pub fn set_image(&mut self, image: &Image) -> OledResult {
+        let (width, height) = self.oled.dimensions();
+        if image.len() != usize::from(width) * usize::from(height) {
+            return Err(OledError::InvalidDimensions);
+        }
+        let cycle = self.cycle_len();
+        for (index, pixel) in image.iter().enumerate() {
+            let (x, y) = (index % usize::from(width), index / usize::from(width));
+            let shade = (usize::from(*pixel) * cycle + 127) / 255;
+            let offset = usize::from(BAYER_4X4[y % 4][x % 4]);
+            for (frame_index, frame) in self.frames[..cycle].iter_mut().enumerate() {
+                let lit = (frame_index + offset) % cycle < shade;
+                frame.set_pixel(x as u16, y as u16, lit);
+            }
+        }
+        Ok(())
+    }
+
+    /// Sends the next frame of the cycle, or rather the parts of it that
+    /// differ from the one before
+    pub fn step(&mut self) -> OledResult {
+        let frame = &self.frames[self.next % self.cycle_len()];
+        self.oled.flush(frame)?;
+        self.next = (self.next + 1) % self.cycle_len();
+        Ok(())
+    }
+
+    /// Sends frame after frame, as fast as the display takes them, for
+    /// `duration`, and then finishes the cycle it's in
+    pub fn show_for(&mut self, duration: Duration) -> OledResult {
+        let start = Instant::now();
+        while start.elapsed() < duration || self.next != 0 {
+            self.step()?;
+        }
+        Ok(())
+    }
+
+    /// Returns the display, eg for sending commands directly
+    pub fn oled(&mut self) -> &mut Oled<I> {
+        &mut self.oled
+    }
+
+    /// Consumes the player, returning the display
+    pub fn into_oled(self) -> Oled<I> {
+        self.oled
+    }
+}
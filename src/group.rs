@@ -0,0 +1,131 @@
+//! Driving two displays together, showing the same picture or sharing a wider one
+use crate::{Framebuffer, Oled, OledInterface, OledResult};
+
+/// How a [`DisplayGroup`](struct.DisplayGroup.html) shares what's drawn between its displays
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GroupLayout {
+    /// Both displays show the same picture
+    Mirrored,
+    /// The displays sit side by side, the first on the left, showing halves of
+    /// a canvas as wide as both together, eg 256x64 for two NanoHat-sized panels
+    SideBySide,
+}
+
+/// Two displays driven as one, eg two modules on the same I2C bus, one at
+/// [`OLED_ADDRESS`](constant.OLED_ADDRESS.html) and the other at
+/// [`OLED_ALT_ADDRESS`](constant.OLED_ALT_ADDRESS.html), as
+/// [`DisplayGroup::from_path()`](struct.DisplayGroup.html#method.from_path) opens.
+///
+/// [`draw()`](#method.draw) draws on the group as a whole, following its
+/// [`GroupLayout`](enum.GroupLayout.html): side by side, the framebuffer
+/// handed over takes coordinates across both displays, so anything that draws
+/// into a framebuffer, from lines to text and widgets, can span the join.
+/// Everything else, such as setting the contrast or writing text in the
+/// built-in font, is done on each display with [`for_each()`](#method.for_each).
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{DisplayGroup, GroupLayout, OledResult};
+/// # fn main() -> OledResult {
+/// let mut group = DisplayGroup::from_path("/dev/i2c-0", GroupLayout::SideBySide)?;
+/// group.for_each(|oled| oled.init())?;
+/// group.draw(|frame| {
+///     // a frame around both displays
+///     frame.draw_rect(0, 0, 256, 64, true);
+/// })?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct DisplayGroup<I> {
+    /// The displays, the left one first when side by side
+    displays: [Oled<I>; 2],
+    /// How what's drawn is shared between them
+    layout: GroupLayout,
+}
+
+impl<I: OledInterface> DisplayGroup<I> {
+    /// Groups two displays showing the same picture
+    pub fn mirrored(first: Oled<I>, second: Oled<I>) -> Self {
+        Self::new([first, second], GroupLayout::Mirrored)
+    }
+
+    /// Groups two displays side by side, `left` on the left, sharing a canvas
+    /// as wide as both together
+    pub fn side_by_side(left: Oled<I>, right: Oled<I>) -> Self {
+        Self::new([left, right], GroupLayout::SideBySide)
+    }
+
+    /// Groups two displays with the given `layout`
+    pub fn new(displays: [Oled<I>; 2], layout: GroupLayout) -> Self {
+        DisplayGroup { displays, layout }
+    }
+
+    /// How what's drawn is shared between the displays
+    pub fn layout(&self) -> GroupLayout {
+        self.layout
+    }
+
+    /// The width and height of the picture drawn by [`draw()`](#method.draw): that
+    /// of the first display when mirrored, or both together when side by side
+    pub fn dimensions(&self) -> (u16, u16) {
+        let (width, height) = self.displays[0].dimensions();
+        match self.layout {
+            GroupLayout::Mirrored => (width, height),
+            GroupLayout::SideBySide => {
+                let (right_width, right_height) = self.displays[1].dimensions();
+                (width + right_width, height.max(right_height))
+            }
+        }
+    }
+
+    /// Calls `f` on each display in turn, the first first, stopping at the
+    /// first error, eg to set both up with [`init()`](struct.Oled.html#method.init)
+    pub fn for_each<F: FnMut(&mut Oled<I>) -> OledResult>(&mut self, mut f: F) -> OledResult {
+        for oled in self.displays.iter_mut() {
+            f(oled)?;
+        }
+        Ok(())
+    }
+
+    /// Draws straight onto the displays, as
+    /// [`Oled::draw()`](struct.Oled.html#method.draw) does for one.
+    ///
+    /// Mirrored, `f` is handed a copy of what the first display shows, and the
+    /// result is sent to both, in the first display's orientation. Side by side,
+    /// `f` is called once for each display, each time with a framebuffer holding
+    /// what that display shows, that takes coordinates across the whole canvas
+    /// and keeps the pixels falling on that display. `f` should draw the same
+    /// each time. Side by side only works with both displays unrotated, returning
+    /// `InvalidDimensions` otherwise.
+    pub fn draw<F: FnMut(&mut Framebuffer)>(&mut self, mut f: F) -> OledResult {
+        match self.layout {
+            GroupLayout::Mirrored => {
+                let first = &self.displays[0];
+                let mut frame =
+                    Framebuffer::with_layout(*first.framebuffer(), first.rotation().is_portrait());
+                f(&mut frame);
+                self.for_each(|oled| oled.flush(&frame))
+            }
+            GroupLayout::SideBySide => {
+                let (width, _) = self.dimensions();
+                let mut first = 0;
+                for oled in self.displays.iter_mut() {
+                    let mut frame = Framebuffer::window(*oled.framebuffer(), first, width);
+                    f(&mut frame);
+                    oled.flush(&frame)?;
+                    first += oled.dimensions().0;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// The displays, the left one first when side by side
+    pub fn displays(&mut self) -> &mut [Oled<I>; 2] {
+        &mut self.displays
+    }
+
+    /// Consumes the group, returning the displays
+    pub fn into_displays(self) -> [Oled<I>; 2] {
+        self.displays
+    }
+}
@@ -0,0 +1,266 @@
+//! Driving the display from web services on the same device, over HTTP
+use std::convert::TryFrom;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, TcpListener};
+use std::str::FromStr;
+use std::time::Duration;
+
+use log::warn;
+
+use crate::{Oled, OledError, OledInterface, OledResult};
+
+/// Largest request body taken, in bytes, plenty for a PNG the size of the display
+const MAX_BODY: usize = 1 << 20;
+
+/// Longest line taken in a request's head, in bytes
+const MAX_LINE: u64 = 8 * 1024;
+
+/// How long a client has to send its request, so one that stalls doesn't hold
+/// up the others
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Why a request wasn't carried out
+enum Refusal {
+    /// The request itself was wrong, with the status and message to reply with
+    Client(&'static str, String),
+    /// The display failed
+    Display(OledError),
+}
+
+impl From<OledError> for Refusal {
+    fn from(err: OledError) -> Self {
+        match err {
+            OledError::InvalidArgument(_)
+            | OledError::InvalidDimensions
+            | OledError::OutOfBounds => Refusal::Client("400 Bad Request", err.to_string()),
+            err => Refusal::Display(err),
+        }
+    }
+}
+
+/// A request's method, path, query string and body
+struct Request {
+    /// Method, eg `POST`
+    method: String,
+    /// Path, without the query string
+    path: String,
+    /// Query string, after the `?`
+    query: String,
+    /// Body
+    body: Vec<u8>,
+}
+
+impl Request {
+    /// Reads a request, or returns the status and message to refuse it with
+    fn read<R: BufRead>(reader: &mut R) -> Result<Self, Refusal> {
+        let bad = |message: &str| Refusal::Client("400 Bad Request", message.into());
+        let mut line = String::new();
+        let mut read_line = |line: &mut String| {
+            line.clear();
+            match reader.by_ref().take(MAX_LINE).read_line(line) {
+                Ok(0) => Err(bad("request ended early")),
+                Ok(_) if !line.ends_with('\n') => Err(bad("request line too long")),
+                Ok(_) => Ok(()),
+                Err(err) => Err(bad(&err.to_string())),
+            }
+        };
+        read_line(&mut line)?;
+        let mut parts = line.split_whitespace();
+        let (method, target) = match (parts.next(), parts.next()) {
+            (Some(method), Some(target)) => (method.to_string(), target.to_string()),
+            _ => return Err(bad("malformed request line")),
+        };
+        let mut len = 0;
+        loop {
+            read_line(&mut line)?;
+            let header = line.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    len = value
+                        .trim()
+                        .parse()
+                        .map_err(|_| bad("bad Content-Length"))?;
+                } else if name.eq_ignore_ascii_case("transfer-encoding") {
+                    let refusal = "a Content-Length is needed, rather than a Transfer-Encoding";
+                    return Err(Refusal::Client("411 Length Required", refusal.into()));
+                }
+            }
+        }
+        if len > MAX_BODY {
+            let refusal = format!("bodies are limited to {} bytes", MAX_BODY);
+            return Err(Refusal::Client("413 Payload Too Large", refusal));
+        }
+        let mut body = vec![0; len];
+        reader
+            .read_exact(&mut body)
+            .map_err(|_| bad("body shorter than its Content-Length"))?;
+        let (path, query) = target.split_once('?').unwrap_or((&target, ""));
+        Ok(Request {
+            method,
+            path: path.into(),
+            query: query.into(),
+            body,
+        })
+    }
+
+    /// The number given for `name` in the query string, or `default` if it's
+    /// not there
+    fn number<T: FromStr>(&self, name: &str, default: T) -> Result<T, Refusal> {
+        let value = self
+            .query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(key, _)| *key == name);
+        match value {
+            None => Ok(default),
+            Some((_, value)) => value.parse().map_err(|_| {
+                Refusal::Client(
+                    "400 Bad Request",
+                    format!("invalid value for {}: {}", name, value),
+                )
+            }),
+        }
+    }
+}
+
+/// Writes a response with `status`, and `message` as its body if there is one
+fn respond<W: Write>(writer: &mut W, status: &str, message: &str) -> io::Result<()> {
+    let body = if message.is_empty() {
+        String::new()
+    } else {
+        format!("{}\n", message)
+    };
+    write!(
+        writer,
+        "HTTP/1.1 {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )?;
+    writer.flush()
+}
+
+/// A small HTTP server for the display, so that web services on the same
+/// device can update it without an IPC layer of their own. It answers three
+/// requests, each replying `204 No Content` once done:
+/// - `POST /text?x=0&y=0` writes the body, as UTF-8, from text column `x` and
+///   row `y`, 0 if not given, with each line of it on the next row
+/// - `POST /image?x=0&y=0` draws the body, a PNG image, with its top left corner
+///   at pixel `x`, `y`, as [`Oled::draw_png()`](struct.Oled.html#method.draw_png)
+///   does
+/// - `POST /clear` clears the display
+///
+/// A request that can't be carried out, eg text off the edge of the display,
+/// gets a `400 Bad Request` saying why. As with [`Daemon`](struct.Daemon.html),
+/// only failing to reach the display stops the server. Requests are answered
+/// one at a time, which suits a display that only shows one thing at once.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{HttpDisplay, Oled, OledResult};
+/// # fn main() -> OledResult {
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// // then, eg: curl --data-binary @status.png localhost:8128/image
+/// HttpDisplay::new(oled).serve(8128)
+/// # }
+/// ```
+pub struct HttpDisplay<I> {
+    /// The display being driven
+    oled: Oled<I>,
+}
+
+impl<I: OledInterface> HttpDisplay<I> {
+    /// Creates a server for an already-initialized display
+    pub fn new(oled: Oled<I>) -> Self {
+        HttpDisplay { oled }
+    }
+
+    /// Listens on `port` of localhost only, so that only programs on the device
+    /// can reach it, and answers requests until the display fails
+    pub fn serve(&mut self, port: u16) -> OledResult {
+        self.serve_listener(TcpListener::bind((Ipv4Addr::LOCALHOST, port))?)
+    }
+
+    /// Answers requests made to `listener`, eg one bound to another address on
+    /// purpose, until the display fails
+    pub fn serve_listener(&mut self, listener: TcpListener) -> OledResult {
+        for stream in listener.incoming() {
+            // a connection that can't be taken up, eg for running out of file
+            // descriptors, is given up on, not the server
+            let stream = match stream.and_then(|stream| {
+                stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+                Ok(stream)
+            }) {
+                Ok(stream) => stream,
+                Err(err) => {
+                    warn!("Couldn't accept an HTTP connection: {}", err);
+                    continue;
+                }
+            };
+            self.handle_connection(stream)?;
+        }
+        Ok(())
+    }
+
+    /// Reads one request from `stream`, carries it out, and replies to it.
+    /// Returns an error only if the display fails, having replied
+    /// `500 Internal Server Error`.
+    pub fn handle_connection<S: Read + Write>(&mut self, mut stream: S) -> OledResult {
+        let result = Request::read(&mut BufReader::new(&mut stream))
+            .and_then(|request| self.carry_out(&request));
+        let (status, message, failure) = match result {
+            Ok(()) => ("204 No Content", String::new(), None),
+            Err(Refusal::Client(status, message)) => (status, message, None),
+            Err(Refusal::Display(err)) => ("500 Internal Server Error", err.to_string(), Some(err)),
+        };
+        // the client going away before it's answered doesn't matter to the display
+        if let Err(err) = respond(&mut stream, status, &message) {
+            warn!("Couldn't reply to an HTTP request: {}", err);
+        }
+        failure.map_or(Ok(()), Err)
+    }
+
+    /// Carries out a request
+    fn carry_out(&mut self, request: &Request) -> Result<(), Refusal> {
+        match (request.method.as_str(), request.path.as_str()) {
+            ("POST", "/text") => {
+                let column: u8 = request.number("x", 0)?;
+                let row: u8 = request.number("y", 0)?;
+                let text = String::from_utf8_lossy(&request.body);
+                for (line, text) in text.lines().enumerate() {
+                    let row = u8::try_from(line)
+                        .ok()
+                        .and_then(|line| row.checked_add(line))
+                        .ok_or(OledError::OutOfBounds)?;
+                    self.oled.set_text_xy(column, row)?;
+                    self.oled.put_string(text)?;
+                }
+                Ok(())
+            }
+            ("POST", "/image") => {
+                let x = request.number("x", 0)?;
+                let y = request.number("y", 0)?;
+                Ok(self.oled.draw_png(&request.body, x, y)?)
+            }
+            ("POST", "/clear") => Ok(self.oled.clear_display()?),
+            (_, "/text") | (_, "/image") | (_, "/clear") => Err(Refusal::Client(
+                "405 Method Not Allowed",
+                "only POST is supported".into(),
+            )),
+            _ => Err(Refusal::Client("404 Not Found", "no such endpoint".into())),
+        }
+    }
+
+    /// Returns the display, eg for sending commands directly
+    pub fn oled(&mut self) -> &mut Oled<I> {
+        &mut self.oled
+    }
+
+    /// Consumes the server, returning the display
+    pub fn into_oled(self) -> Oled<I> {
+        self.oled
+    }
+}
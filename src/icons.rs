@@ -0,0 +1,749 @@
+//! A small set of built-in icons, eg for a status bar, in two sizes
+use crate::{BlitOp, Framebuffer, Oled, OledInterface, OledResult, Sprite};
+
+/// Number of built-in icons, counting each level separately
+const ICONS: usize = 20;
+
+/// One of the built-in icons, drawn as an opaque square
+/// [`Sprite`](struct.Sprite.html) of [either size](enum.IconSize.html), with its
+/// lines lit on an unlit background
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{Icon, IconSize, Oled, OledResult};
+/// # fn main() -> OledResult {
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// oled.draw_icon(Icon::Wifi(2), IconSize::Small, 0, 0)?;
+/// oled.draw_icon(Icon::Battery(3), IconSize::Small, 120, 0)?;
+/// oled.draw_icon(Icon::Warning, IconSize::Large, 56, 24)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Icon {
+    /// Wi-Fi signal strength, from 0, just a dot, to
+    /// [`MAX_WIFI_LEVEL`](#associatedconstant.MAX_WIFI_LEVEL), three arcs
+    Wifi(u8),
+    /// Battery charge, from 0, empty, to
+    /// [`MAX_BATTERY_LEVEL`](#associatedconstant.MAX_BATTERY_LEVEL), full
+    Battery(u8),
+    /// An arrow pointing up
+    ArrowUp,
+    /// An arrow pointing down
+    ArrowDown,
+    /// An arrow pointing left
+    ArrowLeft,
+    /// An arrow pointing right
+    ArrowRight,
+    /// A triangle pointing right, for playing
+    Play,
+    /// Two bars, for pausing
+    Pause,
+    /// A square, for stopping
+    Stop,
+    /// An exclamation mark in a triangle
+    Warning,
+    /// Two links of a chain, eg for a network connection
+    Link,
+    /// A tick
+    Check,
+    /// A cross
+    Cross,
+}
+
+/// How big an [`Icon`](enum.Icon.html) is drawn
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum IconSize {
+    /// 8 pixels square, the height of a page or a line of the built-in font
+    Small,
+    /// 16 pixels square
+    Large,
+}
+
+impl IconSize {
+    /// Width and height in pixels
+    pub fn pixels(self) -> u16 {
+        match self {
+            IconSize::Small => 8,
+            IconSize::Large => 16,
+        }
+    }
+}
+
+impl Icon {
+    /// Highest level of [`Icon::Wifi`](#variant.Wifi)
+    pub const MAX_WIFI_LEVEL: u8 = 3;
+    /// Highest level of [`Icon::Battery`](#variant.Battery)
+    pub const MAX_BATTERY_LEVEL: u8 = 4;
+
+    /// The icon's pixels at `size`. Levels higher than the highest are shown
+    /// as the highest.
+    pub fn sprite(self, size: IconSize) -> Sprite<'static> {
+        let index = self.index();
+        match size {
+            IconSize::Small => Sprite::new(8, 8, &SMALL[index]),
+            IconSize::Large => Sprite::new(16, 16, &LARGE[index]),
+        }
+    }
+
+    /// Where the icon is in the tables
+    fn index(self) -> usize {
+        match self {
+            Icon::Wifi(level) => usize::from(level.min(Self::MAX_WIFI_LEVEL)),
+            Icon::Battery(level) => 4 + usize::from(level.min(Self::MAX_BATTERY_LEVEL)),
+            Icon::ArrowUp => 9,
+            Icon::ArrowDown => 10,
+            Icon::ArrowLeft => 11,
+            Icon::ArrowRight => 12,
+            Icon::Play => 13,
+            Icon::Pause => 14,
+            Icon::Stop => 15,
+            Icon::Warning => 16,
+            Icon::Link => 17,
+            Icon::Check => 18,
+            Icon::Cross => 19,
+        }
+    }
+}
+
+/// Splits 16-pixel rows into the two bytes of each, the most significant first,
+/// as a [`Sprite`](struct.Sprite.html) holds them
+const fn bytes(icons: [[u16; 16]; ICONS]) -> [[u8; 32]; ICONS] {
+    let mut bytes = [[0; 32]; ICONS];
+    let mut icon = 0;
+    while icon < ICONS {
+        let mut row = 0;
+        while row < 16 {
+            let [high, low] = icons[icon][row].to_be_bytes();
+            bytes[icon][row * 2] = high;
+            bytes[icon][row * 2 + 1] = low;
+            row += 1;
+        }
+        icon += 1;
+    }
+    bytes
+}
+
+impl Framebuffer {
+    /// Draws `icon` at `size` with its top left corner at `x`, `y`, replacing
+    /// what's beneath it. Parts of it off the display are clipped.
+    pub fn draw_icon(&mut self, icon: Icon, size: IconSize, x: u16, y: u16) {
+        self.blit_sprite(&icon.sprite(size), x, y, BlitOp::Copy);
+    }
+}
+
+impl<I: OledInterface> Oled<I> {
+    /// Draws an icon, as [`Framebuffer::draw_icon()`](struct.Framebuffer.html#method.draw_icon)
+    /// does, sending only the part of the display that changes.
+    pub fn draw_icon(&mut self, icon: Icon, size: IconSize, x: u16, y: u16) -> OledResult {
+        self.draw(|frame| frame.draw_icon(icon, size, x, y))
+    }
+}
+
+/// 8x8 icons, in the order of [`Icon::index()`](enum.Icon.html), a byte per row
+static SMALL: [[u8; 8]; ICONS] = [
+    // Wi-Fi, level 0
+    [
+        0b0000_0000,
+        0b0000_0000,
+        0b0000_0000,
+        0b0000_0000,
+        0b0000_0000,
+        0b0000_0000,
+        0b0000_0000,
+        0b0001_1000,
+    ],
+    // Wi-Fi, level 1
+    [
+        0b0000_0000,
+        0b0000_0000,
+        0b0000_0000,
+        0b0000_0000,
+        0b0001_1000,
+        0b0010_0100,
+        0b0000_0000,
+        0b0001_1000,
+    ],
+    // Wi-Fi, level 2
+    [
+        0b0000_0000,
+        0b0000_0000,
+        0b0011_1100,
+        0b0100_0010,
+        0b0001_1000,
+        0b0010_0100,
+        0b0000_0000,
+        0b0001_1000,
+    ],
+    // Wi-Fi, level 3
+    [
+        0b0111_1110,
+        0b1000_0001,
+        0b0011_1100,
+        0b0100_0010,
+        0b0001_1000,
+        0b0010_0100,
+        0b0000_0000,
+        0b0001_1000,
+    ],
+    // battery, level 0
+    [
+        0b0000_0000,
+        0b1111_1100,
+        0b1000_0100,
+        0b1000_0110,
+        0b1000_0110,
+        0b1000_0100,
+        0b1111_1100,
+        0b0000_0000,
+    ],
+    // battery, level 1
+    [
+        0b0000_0000,
+        0b1111_1100,
+        0b1100_0100,
+        0b1100_0110,
+        0b1100_0110,
+        0b1100_0100,
+        0b1111_1100,
+        0b0000_0000,
+    ],
+    // battery, level 2
+    [
+        0b0000_0000,
+        0b1111_1100,
+        0b1110_0100,
+        0b1110_0110,
+        0b1110_0110,
+        0b1110_0100,
+        0b1111_1100,
+        0b0000_0000,
+    ],
+    // battery, level 3
+    [
+        0b0000_0000,
+        0b1111_1100,
+        0b1111_0100,
+        0b1111_0110,
+        0b1111_0110,
+        0b1111_0100,
+        0b1111_1100,
+        0b0000_0000,
+    ],
+    // battery, level 4
+    [
+        0b0000_0000,
+        0b1111_1100,
+        0b1111_1100,
+        0b1111_1110,
+        0b1111_1110,
+        0b1111_1100,
+        0b1111_1100,
+        0b0000_0000,
+    ],
+    // arrow up
+    [
+        0b0001_1000,
+        0b0011_1100,
+        0b0111_1110,
+        0b1111_1111,
+        0b0001_1000,
+        0b0001_1000,
+        0b0001_1000,
+        0b0001_1000,
+    ],
+    // arrow down
+    [
+        0b0001_1000,
+        0b0001_1000,
+        0b0001_1000,
+        0b0001_1000,
+        0b1111_1111,
+        0b0111_1110,
+        0b0011_1100,
+        0b0001_1000,
+    ],
+    // arrow left
+    [
+        0b0001_0000,
+        0b0011_0000,
+        0b0111_0000,
+        0b1111_1111,
+        0b1111_1111,
+        0b0111_0000,
+        0b0011_0000,
+        0b0001_0000,
+    ],
+    // arrow right
+    [
+        0b0000_1000,
+        0b0000_1100,
+        0b0000_1110,
+        0b1111_1111,
+        0b1111_1111,
+        0b0000_1110,
+        0b0000_1100,
+        0b0000_1000,
+    ],
+    // play
+    [
+        0b0100_0000,
+        0b0110_0000,
+        0b0111_0000,
+        0b0111_1000,
+        0b0111_1000,
+        0b0111_0000,
+        0b0110_0000,
+        0b0100_0000,
+    ],
+    // pause
+    [
+        0b0000_0000,
+        0b0110_0110,
+        0b0110_0110,
+        0b0110_0110,
+        0b0110_0110,
+        0b0110_0110,
+        0b0110_0110,
+        0b0000_0000,
+    ],
+    // stop
+    [
+        0b0000_0000,
+        0b0111_1110,
+        0b0111_1110,
+        0b0111_1110,
+        0b0111_1110,
+        0b0111_1110,
+        0b0111_1110,
+        0b0000_0000,
+    ],
+    // warning
+    [
+        0b0001_1000,
+        0b0011_1100,
+        0b0010_0100,
+        0b0110_0110,
+        0b0110_0110,
+        0b1111_1111,
+        0b1110_0111,
+        0b1111_1111,
+    ],
+    // link
+    [
+        0b0000_0000,
+        0b0111_0000,
+        0b1000_1000,
+        0b1000_1110,
+        0b0111_0001,
+        0b0001_0001,
+        0b0000_1110,
+        0b0000_0000,
+    ],
+    // check mark
+    [
+        0b0000_0000,
+        0b0000_0001,
+        0b0000_0011,
+        0b1000_0110,
+        0b1100_1100,
+        0b0111_1000,
+        0b0011_0000,
+        0b0000_0000,
+    ],
+    // cross
+    [
+        0b1100_0011,
+        0b1110_0111,
+        0b0111_1110,
+        0b0011_1100,
+        0b0011_1100,
+        0b0111_1110,
+        0b1110_0111,
+        0b1100_0011,
+    ],
+];
+
+/// 16x16 icons, in the same order, a `u16` per row
+static LARGE: [[u8; 32]; ICONS] = bytes([
+    // Wi-Fi, level 0
+    [
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0000_0001_1000_0000,
+        0b0000_0011_1100_0000,
+        0b0000_0001_1000_0000,
+        0b0000_0000_0000_0000,
+    ],
+    // Wi-Fi, level 1
+    [
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0000_0011_1100_0000,
+        0b0000_0111_1110_0000,
+        0b0000_1100_0011_0000,
+        0b0000_0000_0000_0000,
+        0b0000_0001_1000_0000,
+        0b0000_0011_1100_0000,
+        0b0000_0001_1000_0000,
+        0b0000_0000_0000_0000,
+    ],
+    // Wi-Fi, level 2
+    [
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0000_0011_1100_0000,
+        0b0000_1111_1111_0000,
+        0b0001_1100_0011_1000,
+        0b0011_0000_0000_1100,
+        0b0000_0011_1100_0000,
+        0b0000_0111_1110_0000,
+        0b0000_1100_0011_0000,
+        0b0000_0000_0000_0000,
+        0b0000_0001_1000_0000,
+        0b0000_0011_1100_0000,
+        0b0000_0001_1000_0000,
+        0b0000_0000_0000_0000,
+    ],
+    // Wi-Fi, level 3
+    [
+        0b0000_0000_0000_0000,
+        0b0000_0111_1110_0000,
+        0b0001_1111_1111_1000,
+        0b0111_0000_0000_1110,
+        0b1100_0011_1100_0011,
+        0b1000_1111_1111_0001,
+        0b0001_1100_0011_1000,
+        0b0011_0000_0000_1100,
+        0b0000_0011_1100_0000,
+        0b0000_0111_1110_0000,
+        0b0000_1100_0011_0000,
+        0b0000_0000_0000_0000,
+        0b0000_0001_1000_0000,
+        0b0000_0011_1100_0000,
+        0b0000_0001_1000_0000,
+        0b0000_0000_0000_0000,
+    ],
+    // battery, level 0
+    [
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b1111_1111_1111_1110,
+        0b1000_0000_0000_0010,
+        0b1000_0000_0000_0010,
+        0b1000_0000_0000_0011,
+        0b1000_0000_0000_0011,
+        0b1000_0000_0000_0011,
+        0b1000_0000_0000_0011,
+        0b1000_0000_0000_0010,
+        0b1000_0000_0000_0010,
+        0b1111_1111_1111_1110,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+    ],
+    // battery, level 1
+    [
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b1111_1111_1111_1110,
+        0b1000_0000_0000_0010,
+        0b1011_0000_0000_0010,
+        0b1011_0000_0000_0011,
+        0b1011_0000_0000_0011,
+        0b1011_0000_0000_0011,
+        0b1011_0000_0000_0011,
+        0b1011_0000_0000_0010,
+        0b1000_0000_0000_0010,
+        0b1111_1111_1111_1110,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+    ],
+    // battery, level 2
+    [
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b1111_1111_1111_1110,
+        0b1000_0000_0000_0010,
+        0b1011_0110_0000_0010,
+        0b1011_0110_0000_0011,
+        0b1011_0110_0000_0011,
+        0b1011_0110_0000_0011,
+        0b1011_0110_0000_0011,
+        0b1011_0110_0000_0010,
+        0b1000_0000_0000_0010,
+        0b1111_1111_1111_1110,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+    ],
+    // battery, level 3
+    [
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b1111_1111_1111_1110,
+        0b1000_0000_0000_0010,
+        0b1011_0110_1100_0010,
+        0b1011_0110_1100_0011,
+        0b1011_0110_1100_0011,
+        0b1011_0110_1100_0011,
+        0b1011_0110_1100_0011,
+        0b1011_0110_1100_0010,
+        0b1000_0000_0000_0010,
+        0b1111_1111_1111_1110,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+    ],
+    // battery, level 4
+    [
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b1111_1111_1111_1110,
+        0b1000_0000_0000_0010,
+        0b1011_0110_1101_1010,
+        0b1011_0110_1101_1011,
+        0b1011_0110_1101_1011,
+        0b1011_0110_1101_1011,
+        0b1011_0110_1101_1011,
+        0b1011_0110_1101_1010,
+        0b1000_0000_0000_0010,
+        0b1111_1111_1111_1110,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+    ],
+    // arrow up
+    [
+        0b0000_0000_0000_0000,
+        0b0000_0001_1000_0000,
+        0b0000_0011_1100_0000,
+        0b0000_0111_1110_0000,
+        0b0000_1111_1111_0000,
+        0b0001_1111_1111_1000,
+        0b0011_1111_1111_1100,
+        0b0111_1111_1111_1110,
+        0b0000_0011_1100_0000,
+        0b0000_0011_1100_0000,
+        0b0000_0011_1100_0000,
+        0b0000_0011_1100_0000,
+        0b0000_0011_1100_0000,
+        0b0000_0011_1100_0000,
+        0b0000_0011_1100_0000,
+        0b0000_0000_0000_0000,
+    ],
+    // arrow down
+    [
+        0b0000_0000_0000_0000,
+        0b0000_0011_1100_0000,
+        0b0000_0011_1100_0000,
+        0b0000_0011_1100_0000,
+        0b0000_0011_1100_0000,
+        0b0000_0011_1100_0000,
+        0b0000_0011_1100_0000,
+        0b0000_0011_1100_0000,
+        0b0111_1111_1111_1110,
+        0b0011_1111_1111_1100,
+        0b0001_1111_1111_1000,
+        0b0000_1111_1111_0000,
+        0b0000_0111_1110_0000,
+        0b0000_0011_1100_0000,
+        0b0000_0001_1000_0000,
+        0b0000_0000_0000_0000,
+    ],
+    // arrow left
+    [
+        0b0000_0000_0000_0000,
+        0b0000_0001_0000_0000,
+        0b0000_0011_0000_0000,
+        0b0000_0111_0000_0000,
+        0b0000_1111_0000_0000,
+        0b0001_1111_0000_0000,
+        0b0011_1111_1111_1110,
+        0b0111_1111_1111_1110,
+        0b0111_1111_1111_1110,
+        0b0011_1111_1111_1110,
+        0b0001_1111_0000_0000,
+        0b0000_1111_0000_0000,
+        0b0000_0111_0000_0000,
+        0b0000_0011_0000_0000,
+        0b0000_0001_0000_0000,
+        0b0000_0000_0000_0000,
+    ],
+    // arrow right
+    [
+        0b0000_0000_0000_0000,
+        0b0000_0000_1000_0000,
+        0b0000_0000_1100_0000,
+        0b0000_0000_1110_0000,
+        0b0000_0000_1111_0000,
+        0b0000_0000_1111_1000,
+        0b0111_1111_1111_1100,
+        0b0111_1111_1111_1110,
+        0b0111_1111_1111_1110,
+        0b0111_1111_1111_1100,
+        0b0000_0000_1111_1000,
+        0b0000_0000_1111_0000,
+        0b0000_0000_1110_0000,
+        0b0000_0000_1100_0000,
+        0b0000_0000_1000_0000,
+        0b0000_0000_0000_0000,
+    ],
+    // play
+    [
+        0b0000_0000_0000_0000,
+        0b0001_1000_0000_0000,
+        0b0001_1110_0000_0000,
+        0b0001_1111_1000_0000,
+        0b0001_1111_1110_0000,
+        0b0001_1111_1111_1000,
+        0b0001_1111_1111_1110,
+        0b0001_1111_1111_1110,
+        0b0001_1111_1111_1110,
+        0b0001_1111_1111_1110,
+        0b0001_1111_1111_1000,
+        0b0001_1111_1110_0000,
+        0b0001_1111_1000_0000,
+        0b0001_1110_0000_0000,
+        0b0001_1000_0000_0000,
+        0b0000_0000_0000_0000,
+    ],
+    // pause
+    [
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0001_1110_0111_1000,
+        0b0001_1110_0111_1000,
+        0b0001_1110_0111_1000,
+        0b0001_1110_0111_1000,
+        0b0001_1110_0111_1000,
+        0b0001_1110_0111_1000,
+        0b0001_1110_0111_1000,
+        0b0001_1110_0111_1000,
+        0b0001_1110_0111_1000,
+        0b0001_1110_0111_1000,
+        0b0001_1110_0111_1000,
+        0b0001_1110_0111_1000,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+    ],
+    // stop
+    [
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0001_1111_1111_1000,
+        0b0001_1111_1111_1000,
+        0b0001_1111_1111_1000,
+        0b0001_1111_1111_1000,
+        0b0001_1111_1111_1000,
+        0b0001_1111_1111_1000,
+        0b0001_1111_1111_1000,
+        0b0001_1111_1111_1000,
+        0b0001_1111_1111_1000,
+        0b0001_1111_1111_1000,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+    ],
+    // warning
+    [
+        0b0000_0000_0000_0000,
+        0b0000_0001_1000_0000,
+        0b0000_0001_1000_0000,
+        0b0000_0011_1100_0000,
+        0b0000_0011_1100_0000,
+        0b0000_0110_0110_0000,
+        0b0000_0110_0110_0000,
+        0b0000_1110_0111_0000,
+        0b0000_1110_0111_0000,
+        0b0001_1110_0111_1000,
+        0b0001_1111_1111_1000,
+        0b0011_1110_0111_1100,
+        0b0011_1110_0111_1100,
+        0b0111_1111_1111_1110,
+        0b1111_1111_1111_1111,
+        0b0000_0000_0000_0000,
+    ],
+    // link
+    [
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0111_1111_1000_0000,
+        0b1111_1111_1100_0000,
+        0b1100_0000_1100_0000,
+        0b1100_0000_1100_0000,
+        0b1100_0000_1100_0000,
+        0b1111_1111_1111_1110,
+        0b0111_1111_1111_1111,
+        0b0000_0011_0000_0011,
+        0b0000_0011_0000_0011,
+        0b0000_0011_0000_0011,
+        0b0000_0011_1111_1111,
+        0b0000_0001_1111_1110,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+    ],
+    // check mark
+    [
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0011,
+        0b0000_0000_0000_0111,
+        0b0000_0000_0000_1110,
+        0b0000_0000_0001_1100,
+        0b0000_0000_0011_1000,
+        0b0110_0000_0111_0000,
+        0b0111_0000_1110_0000,
+        0b0011_1001_1100_0000,
+        0b0001_1111_1000_0000,
+        0b0000_1111_0000_0000,
+        0b0000_0110_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+    ],
+    // cross
+    [
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+        0b0011_0000_0000_1100,
+        0b0011_1000_0001_1100,
+        0b0001_1100_0011_1000,
+        0b0000_1110_0111_0000,
+        0b0000_0111_1110_0000,
+        0b0000_0011_1100_0000,
+        0b0000_0011_1100_0000,
+        0b0000_0111_1110_0000,
+        0b0000_1110_0111_0000,
+        0b0001_1100_0011_1000,
+        0b0011_1000_0001_1100,
+        0b0011_0000_0000_1100,
+        0b0000_0000_0000_0000,
+        0b0000_0000_0000_0000,
+    ],
+]);
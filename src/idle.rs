@@ -0,0 +1,174 @@
+//! Showing a screensaver, or nothing, while no one is using the display
+use core::time::Duration;
+
+use crate::{Framebuffer, Oled, OledError, OledInterface, OledResult, Screensaver, OLED_RAM_SIZE};
+
+/// What an [`IdleManager`](struct.IdleManager.html) without a screensaver has;
+/// it puts the display to sleep instead
+pub type NoScreensaver = fn(&mut Framebuffer, Duration);
+
+/// A screensaver of any kind, as an [`App`](struct.App.html) keeps it
+#[cfg(feature = "buttons")]
+pub(crate) type BoxedScreensaver = Box<dyn FnMut(&mut Framebuffer, Duration)>;
+
+/// Keeps track of activity, eg button presses, and once there has been none for
+/// a while, swaps what's on the display for a screensaver, or puts the display
+/// to [`sleep()`](struct.Oled.html#method.sleep). On the next activity,
+/// what was on the display before is put back.
+///
+/// The screensaver is a function drawing into a framebuffer, given how long it
+/// has been showing, called on every [`tick()`](#method.tick) while idle. The
+/// first time, the framebuffer holds what was on the display, so it can be
+/// cleared, or drawn over. Whatever else is drawn while idle is lost when the
+/// previous frame is put back, so drawing should wait until
+/// [`is_idle()`](#method.is_idle) is false, as an [`App`](struct.App.html)
+/// given [`with_idle()`](struct.App.html#method.with_idle) does.
+///
+/// Like a [`Fade`](struct.Fade.html), it doesn't read a clock itself, so it
+/// works on `no_std` too.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{IdleManager, Oled, OledResult};
+/// # use std::{thread::sleep, time::Duration};
+/// # fn main() -> OledResult {
+/// # let pressed = || false;
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// oled.put_string("Up and running")?;
+/// let mut idle = IdleManager::new(Duration::from_secs(60)).with_screensaver(|frame, shown| {
+///     // a dot wandering along the top edge
+///     frame.clear();
+///     frame.set_pixel((shown.as_secs() % 128) as u16, 0, true);
+/// });
+/// loop {
+///     if pressed() {
+///         idle.touch(&mut oled)?;
+///     }
+///     idle.tick(&mut oled, Duration::from_secs(1))?;
+///     sleep(Duration::from_secs(1));
+/// }
+/// # }
+/// ```
+pub struct IdleManager<S = NoScreensaver> {
+    /// Time without activity after which the display is idle
+    timeout: Duration,
+    /// Time since the last activity
+    idle: Duration,
+    /// What's drawn while idle, if anything
+    screensaver: Option<S>,
+    /// What was on the display before it went idle, while it is
+    saved: Option<[u8; OLED_RAM_SIZE]>,
+}
+
+impl IdleManager {
+    /// Creates a manager that puts the display to sleep after `timeout`
+    /// without activity
+    pub fn new(timeout: Duration) -> Self {
+        IdleManager {
+            timeout,
+            idle: Duration::ZERO,
+            screensaver: None,
+            saved: None,
+        }
+    }
+}
+
+impl<S: FnMut(&mut Framebuffer, Duration)> IdleManager<S> {
+    /// Shows `screensaver` while idle, instead of putting the display to sleep
+    pub fn with_screensaver<T: FnMut(&mut Framebuffer, Duration)>(
+        self,
+        screensaver: T,
+    ) -> IdleManager<T> {
+        IdleManager {
+            timeout: self.timeout,
+            idle: self.idle,
+            screensaver: Some(screensaver),
+            saved: None,
+        }
+    }
+
+    /// Shows `animation` while idle, instead of putting the display to sleep,
+    /// drawing its next frame on every tick, eg one of the ready-made
+    /// [`Screensaver`](trait.Screensaver.html)s
+    /// # Example:
+    /// ```no_run
+    /// # use nanohat_oled::{GameOfLife, IdleManager, Oled, OledResult};
+    /// # use std::{thread::sleep, time::Duration};
+    /// # fn main() -> OledResult {
+    /// let mut oled = Oled::from_path("/dev/i2c-0")?;
+    /// oled.init()?;
+    /// let mut idle = IdleManager::new(Duration::from_secs(60)).with_animation(GameOfLife::new());
+    /// loop {
+    ///     idle.tick(&mut oled, Duration::from_millis(100))?;
+    ///     sleep(Duration::from_millis(100));
+    /// }
+    /// # }
+    /// ```
+    pub fn with_animation<A: Screensaver>(
+        self,
+        mut animation: A,
+    ) -> IdleManager<impl FnMut(&mut Framebuffer, Duration)> {
+        self.with_screensaver(move |frame: &mut Framebuffer, _| animation.step(frame))
+    }
+
+    /// Whether the timeout has passed, so the screensaver is showing or the
+    /// display is asleep
+    pub fn is_idle(&self) -> bool {
+        self.saved.is_some()
+    }
+
+    /// Counts `elapsed` towards the timeout, going idle if it has run out,
+    /// and draws the screensaver if idle
+    pub fn tick<I: OledInterface>(&mut self, oled: &mut Oled<I>, elapsed: Duration) -> OledResult {
+        self.idle += elapsed;
+        if self.idle < self.timeout {
+            return Ok(());
+        }
+        if self.saved.is_none() {
+            self.saved = Some(*oled.framebuffer());
+            if self.screensaver.is_none() {
+                oled.sleep()?;
+            }
+        }
+        if let Some(screensaver) = &mut self.screensaver {
+            let shown = self.idle - self.timeout;
+            oled.draw(|frame| screensaver(frame, shown))?;
+        }
+        Ok(())
+    }
+
+    /// Counts as activity, starting the timeout again. If idle, puts back what
+    /// the display showed before, and wakes it if it was asleep. Returns
+    /// whether it was idle, eg so the button press that ends it can be ignored.
+    pub fn touch<I: OledInterface>(&mut self, oled: &mut Oled<I>) -> Result<bool, OledError> {
+        self.idle = Duration::ZERO;
+        let Some(saved) = self.saved else {
+            return Ok(false);
+        };
+        oled.flush(&Framebuffer::with_layout(
+            saved,
+            oled.rotation().is_portrait(),
+        ))?;
+        if self.screensaver.is_none() {
+            oled.wake()?;
+        }
+        self.saved = None;
+        Ok(true)
+    }
+
+    /// Boxes the screensaver, so that managers with different ones have the same type
+    #[cfg(feature = "buttons")]
+    pub(crate) fn boxed(self) -> IdleManager<BoxedScreensaver>
+    where
+        S: 'static,
+    {
+        IdleManager {
+            timeout: self.timeout,
+            idle: self.idle,
+            screensaver: self
+                .screensaver
+                .map(|screensaver| Box::new(screensaver) as BoxedScreensaver),
+            saved: self.saved,
+        }
+    }
+}
@@ -0,0 +1,112 @@
+//! Whole-screen images in other layouts than one byte per pixel
+use crate::{
+    Framebuffer, Oled, OledError, OledInterface, OledResult, OLED_PAGE_HEIGHT, OLED_RAM_SIZE,
+    OLED_WIDTH,
+};
+
+/// How the pixels of an image passed to
+/// [`Oled::draw_image_as()`](struct.Oled.html#method.draw_image_as) are laid out
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ImageFormat {
+    /// One byte per pixel, row by row, lit from the given gray level up, as
+    /// for [`draw_image()`](struct.Oled.html#method.draw_image)
+    Gray(u8),
+    /// One bit per pixel, row by row, most significant bit first, with each row
+    /// padded to a whole byte, as in PBM files and a
+    /// [`Sprite`](struct.Sprite.html). A set bit is a lit pixel.
+    RowMajor,
+    /// One byte per column of eight rows, least significant bit at the top, one
+    /// page of eight rows after another, as the display's RAM is laid out and
+    /// [`framebuffer()`](struct.Oled.html#method.framebuffer) returns it
+    PageMajor,
+}
+
+impl ImageFormat {
+    /// The number of bytes in an image `width` by `height` pixels
+    pub fn image_len(self, width: u16, height: u16) -> usize {
+        let (width, height) = (usize::from(width), usize::from(height));
+        match self {
+            ImageFormat::Gray(_) => width * height,
+            ImageFormat::RowMajor => width.div_ceil(8) * height,
+            ImageFormat::PageMajor => width * height.div_ceil(OLED_PAGE_HEIGHT as usize),
+        }
+    }
+
+    /// Whether the pixel at `x`, `y` of `image`, `width` pixels wide, is lit
+    fn is_lit(self, image: &[u8], width: u16, x: u16, y: u16) -> bool {
+        let (width, x, y) = (usize::from(width), usize::from(x), usize::from(y));
+        match self {
+            ImageFormat::Gray(threshold) => image[y * width + x] >= threshold,
+            ImageFormat::RowMajor => image[y * width.div_ceil(8) + x / 8] & (0x80 >> (x % 8)) != 0,
+            ImageFormat::PageMajor => {
+                let page = y / OLED_PAGE_HEIGHT as usize;
+                image[page * width + x] & (1 << (y % OLED_PAGE_HEIGHT as usize)) != 0
+            }
+        }
+    }
+}
+
+impl<I: OledInterface> Oled<I> {
+    /// Writes a whole-screen image laid out as `format` says, eg one already
+    /// packed one bit per pixel by another tool, which takes an eighth of the
+    /// memory of the one byte per pixel that
+    /// [`draw_image()`](struct.Oled.html#method.draw_image) takes. The image
+    /// must be the [dimensions](struct.Oled.html#method.dimensions) of the
+    /// display, the way it's turned, or `InvalidDimensions` is returned.
+    ///
+    /// A [`PageMajor`](enum.ImageFormat.html#variant.PageMajor) image the full
+    /// width of the display, in landscape, is already laid out as its RAM, and
+    /// is sent as it is.
+    /// # Example:
+    /// ```no_run
+    /// # use nanohat_oled::{ImageFormat, Oled, OledResult};
+    /// # fn main() -> OledResult {
+    /// let mut oled = Oled::from_path("/dev/i2c-0")?;
+    /// oled.init()?;
+    /// // eg from a tool writing SSD1306 frames
+    /// let frame = [0x55u8; 128 * 64 / 8];
+    /// oled.draw_image_as(&frame, ImageFormat::PageMajor)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn draw_image_as(&mut self, image: &[u8], format: ImageFormat) -> OledResult {
+        let (width, height) = self.dimensions();
+        if image.len() != format.image_len(width, height) {
+            return Err(OledError::InvalidDimensions);
+        }
+        let portrait = self.rotation.is_portrait();
+        match format {
+            ImageFormat::Gray(threshold) => self.draw_image(image, threshold),
+            ImageFormat::PageMajor if !portrait && width == OLED_WIDTH => {
+                let mut buffer = [0; OLED_RAM_SIZE];
+                buffer[..image.len()].copy_from_slice(image);
+                self.write_ram(&buffer)
+            }
+            _ => {
+                let mut frame = Framebuffer::with_layout([0; OLED_RAM_SIZE], portrait);
+                for y in 0..height {
+                    for x in 0..width {
+                        if format.is_lit(image, width, x, y) {
+                            frame.set_pixel(x, y, true);
+                        }
+                    }
+                }
+                self.write_ram(frame.as_bytes())
+            }
+        }
+    }
+
+    /// Writes a whole-screen image sent without saying how it's laid out, eg to
+    /// a [`Daemon`](struct.Daemon.html): packed a bit per pixel by row if it's
+    /// the size of one, or otherwise one byte per pixel, lit from `threshold` up
+    #[cfg(any(all(feature = "std", unix), feature = "mqtt"))]
+    pub(crate) fn draw_image_by_len(&mut self, image: &[u8], threshold: u8) -> OledResult {
+        let (width, height) = self.dimensions();
+        let format = if image.len() == ImageFormat::RowMajor.image_len(width, height) {
+            ImageFormat::RowMajor
+        } else {
+            ImageFormat::Gray(threshold)
+        };
+        self.draw_image_as(image, format)
+    }
+}
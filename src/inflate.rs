@@ -0,0 +1,235 @@
+//! Decompressing zlib streams, as PNG images hold their pixels in
+use alloc::vec::Vec;
+
+/// Most bits in a Huffman code
+const MAX_BITS: usize = 15;
+
+/// Base lengths of the length codes 257 to 285, and their extra bits
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+
+/// Base distances of the distance codes, and their extra bits
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+/// The order code length code lengths come in, in a dynamic block's header
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+/// Reads a deflate stream a bit at a time, least significant bit first
+struct Bits<'a> {
+    /// The stream
+    data: &'a [u8],
+    /// Next byte to read
+    at: usize,
+    /// Bits read from the stream but not yet used
+    buffer: u32,
+    /// Number of bits in `buffer`
+    count: u32,
+}
+
+impl<'a> Bits<'a> {
+    /// Takes the next `n` bits, up to 16, or `None` at the end of the stream
+    fn take(&mut self, n: u32) -> Option<u32> {
+        while self.count < n {
+            self.buffer |= u32::from(*self.data.get(self.at)?) << self.count;
+            self.at += 1;
+            self.count += 8;
+        }
+        let bits = self.buffer & ((1 << n) - 1);
+        self.buffer >>= n;
+        self.count -= n;
+        Some(bits)
+    }
+
+    /// Drops what's left of the current byte, as before a stored block
+    fn align(&mut self) {
+        self.buffer = 0;
+        self.count = 0;
+    }
+}
+
+/// A canonical Huffman code, as the number of codes of each length and the
+/// symbols in code order
+struct Huffman {
+    /// Number of codes of each length, from 0 bits to 15
+    counts: [u16; MAX_BITS + 1],
+    /// Symbols, shortest code first
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    /// Builds the code from each symbol's code length, 0 for symbols not used.
+    /// Returns `None` if the lengths give more codes than there's room for.
+    fn new(lengths: &[u8]) -> Option<Self> {
+        let mut counts = [0u16; MAX_BITS + 1];
+        for length in lengths {
+            counts[usize::from(*length)] += 1;
+        }
+        let mut left = 1i32;
+        for count in &counts[1..] {
+            left = left * 2 - i32::from(*count);
+            if left < 0 {
+                return None;
+            }
+        }
+        let mut offsets = [0u16; MAX_BITS + 2];
+        for length in 1..=MAX_BITS {
+            offsets[length + 1] = offsets[length] + counts[length];
+        }
+        let mut symbols = alloc::vec![0; usize::from(offsets[MAX_BITS + 1])];
+        for (symbol, length) in lengths.iter().enumerate() {
+            if *length > 0 {
+                let offset = &mut offsets[usize::from(*length)];
+                symbols[usize::from(*offset)] = symbol as u16;
+                *offset += 1;
+            }
+        }
+        Some(Huffman { counts, symbols })
+    }
+
+    /// Reads one symbol from `bits`
+    fn decode(&self, bits: &mut Bits) -> Option<u16> {
+        // codes of each length follow on from the last of the length before
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+        for count in &self.counts[1..] {
+            code |= bits.take(1)? as i32;
+            let count = i32::from(*count);
+            if code - first < count {
+                return self.symbols.get((index + code - first) as usize).copied();
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        None
+    }
+}
+
+/// Reads the code lengths of a dynamic block, returning its literal and length
+/// code, and distance code
+fn dynamic_codes(bits: &mut Bits) -> Option<(Huffman, Huffman)> {
+    let literals = bits.take(5)? as usize + 257;
+    let distances = bits.take(5)? as usize + 1;
+    let code_lengths = bits.take(4)? as usize + 4;
+    let mut lengths = [0u8; 19];
+    for index in &CODE_LENGTH_ORDER[..code_lengths] {
+        lengths[*index] = bits.take(3)? as u8;
+    }
+    let lengths_code = Huffman::new(&lengths)?;
+    let mut lengths = alloc::vec![0u8; literals + distances];
+    let mut at = 0;
+    while at < lengths.len() {
+        let symbol = lengths_code.decode(bits)?;
+        let (length, repeat) = match symbol {
+            0..=15 => (symbol as u8, 1),
+            16 => (*lengths.get(at.checked_sub(1)?)?, 3 + bits.take(2)?),
+            17 => (0, 3 + bits.take(3)?),
+            _ => (0, 11 + bits.take(7)?),
+        };
+        for _ in 0..repeat {
+            *lengths.get_mut(at)? = length;
+            at += 1;
+        }
+    }
+    Some((
+        Huffman::new(&lengths[..literals])?,
+        Huffman::new(&lengths[literals..])?,
+    ))
+}
+
+/// The codes of a block compressed with the fixed Huffman codes
+fn fixed_codes() -> (Huffman, Huffman) {
+    let mut lengths = [8u8; 288];
+    lengths[144..256].fill(9);
+    lengths[256..280].fill(7);
+    let literals = Huffman::new(&lengths).expect("fixed literal code");
+    let distances = Huffman::new(&[5; 30]).expect("fixed distance code");
+    (literals, distances)
+}
+
+/// Decompresses a zlib stream, or returns `None` if it's corrupt, or
+/// decompresses to more than `limit` bytes
+pub(crate) fn inflate_zlib(data: &[u8], limit: usize) -> Option<Vec<u8>> {
+    let (method, flags) = (*data.first()?, *data.get(1)?);
+    // deflate, with no preset dictionary
+    if method & 0x0f != 8
+        || (u16::from(method) << 8 | u16::from(flags)) % 31 != 0
+        || flags & 0x20 != 0
+    {
+        return None;
+    }
+    let mut bits = Bits {
+        data: &data[2..],
+        at: 0,
+        buffer: 0,
+        count: 0,
+    };
+    let mut out = Vec::new();
+    loop {
+        let last = bits.take(1)? == 1;
+        let (literals, distances) = match bits.take(2)? {
+            0 => {
+                bits.align();
+                let header = bits.data.get(bits.at..bits.at + 4)?;
+                let len = usize::from(u16::from_le_bytes([header[0], header[1]]));
+                if len != usize::from(!u16::from_le_bytes([header[2], header[3]]))
+                    || out.len() + len > limit
+                {
+                    return None;
+                }
+                out.extend_from_slice(bits.data.get(bits.at + 4..bits.at + 4 + len)?);
+                bits.at += 4 + len;
+                if last {
+                    return Some(out);
+                }
+                continue;
+            }
+            1 => fixed_codes(),
+            2 => dynamic_codes(&mut bits)?,
+            _ => return None,
+        };
+        loop {
+            let symbol = usize::from(literals.decode(&mut bits)?);
+            if symbol < 256 {
+                if out.len() == limit {
+                    return None;
+                }
+                out.push(symbol as u8);
+                continue;
+            }
+            if symbol == 256 {
+                break;
+            }
+            let code = symbol - 257;
+            let len = usize::from(*LENGTH_BASE.get(code)?)
+                + bits.take(u32::from(LENGTH_EXTRA[code]))? as usize;
+            let code = usize::from(distances.decode(&mut bits)?);
+            let distance = usize::from(*DISTANCE_BASE.get(code)?)
+                + bits.take(u32::from(DISTANCE_EXTRA[code]))? as usize;
+            if distance > out.len() || out.len() + len > limit {
+                return None;
+            }
+            // copied a byte at a time, as the copy can overlap what it's making
+            let start = out.len() - distance;
+            for index in start..start + len {
+                out.push(out[index]);
+            }
+        }
+        if last {
+            return Some(out);
+        }
+    }
+}
@@ -0,0 +1,207 @@
+//! Commands that take arguments, checked and encoded as the bytes the controller expects
+use crate::{AddressingMode, Command, Oled, OledError, OledInterface, OledResult};
+
+/// The most bytes an [`Instruction`](enum.Instruction.html) encodes to
+pub const MAX_INSTRUCTION_LEN: usize = 3;
+
+/// A command together with its arguments, for the settings that
+/// [`Command`](enum.Command.html) only has the first byte of. Sent with
+/// [`Oled::send_instruction()`](struct.Oled.html#method.send_instruction),
+/// or encoded with [`encode()`](#method.encode), which checks the arguments
+/// are in range.
+/// # Example:
+/// ```
+/// # use nanohat_oled::{Instruction, MAX_INSTRUCTION_LEN};
+/// let mut buffer = [0; MAX_INSTRUCTION_LEN];
+/// let bytes = Instruction::SetMultiplex(32).encode(&mut buffer).unwrap();
+/// assert_eq!(bytes, [0xa8, 0x1f]);
+/// assert!(Instruction::SetMultiplex(8).encode(&mut buffer).is_err());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Instruction {
+    /// A command on its own, for those that take no arguments
+    Command(Command),
+    /// Sets the contrast, from 0 to 255. Default is 0x7f.
+    SetContrast(u8),
+    /// Sets the [`AddressingMode`](enum.AddressingMode.html)
+    SetAddressingMode(AddressingMode),
+    /// Sets the first and last columns, 0 to 127, written in horizontal and
+    /// vertical addressing modes
+    SetColumnAddress(u8, u8),
+    /// Sets the first and last pages, 0 to 7, written in horizontal and
+    /// vertical addressing modes
+    SetPageAddress(u8, u8),
+    /// Moves the column pointer, in page addressing mode
+    SetColumnStart(u8),
+    /// Moves the page pointer, 0 to 7, in page addressing mode
+    SetPageStart(u8),
+    /// Sets the RAM row, 0 to 63, shown at the top of the panel
+    SetStartLine(u8),
+    /// Sets the number of rows driven, from 16 to 64
+    SetMultiplex(u8),
+    /// Shifts the rows shown up by 0 to 63 rows, wrapping around
+    SetDisplayOffset(u8),
+    /// Sets the display clock's divide ratio, 1 to 16, and oscillator
+    /// frequency, 0 to 15
+    SetClock {
+        /// Display clocks per oscillator cycle
+        divide: u8,
+        /// The oscillator frequency setting
+        frequency: u8,
+    },
+    /// Sets how the COM pins are wired to the panel's rows
+    SetComPins {
+        /// Alternative, rather than sequential, pin configuration
+        alternative: bool,
+        /// Swaps the left and right halves of the pins
+        left_right_remap: bool,
+    },
+    /// Sets the two pre-charge phases, each 1 to 15 display clocks
+    SetPrecharge(u8, u8),
+    /// Sets the V<sub>COMH</sub> deselect level, which may only use bits 4 through 6
+    SetVcomhLevel(u8),
+    /// Turns the SSD1306's charge pump on or off
+    ChargePump(bool),
+    /// Turns the SH1106's DC-DC converter on or off
+    DcDc(bool),
+}
+
+impl Instruction {
+    /// Writes the bytes for the instruction, the command first, into `buffer`,
+    /// returning those written. Returns `InvalidArgument` if an argument is out
+    /// of range.
+    pub fn encode(self, buffer: &mut [u8; MAX_INSTRUCTION_LEN]) -> Result<&[u8], OledError> {
+        let mut len = 0;
+        let mut push = |byte: u8| {
+            buffer[len] = byte;
+            len += 1;
+        };
+        match self {
+            Instruction::Command(command) => push(command.into()),
+            Instruction::SetContrast(contrast) => {
+                push(Command::SetContrast.into());
+                push(contrast);
+            }
+            Instruction::SetAddressingMode(mode) => {
+                push(Command::SetAddressingMode.into());
+                push(mode.into());
+            }
+            Instruction::SetColumnAddress(start, end) => {
+                if start > end || end > 127 {
+                    return Err(OledError::InvalidArgument(
+                        "column addresses must be between 0 and 127, the first no greater than the last",
+                    ));
+                }
+                push(Command::SetColumnAddress.into());
+                push(start);
+                push(end);
+            }
+            Instruction::SetPageAddress(start, end) => {
+                if start > end || end > 7 {
+                    return Err(OledError::InvalidArgument(
+                        "page addresses must be between 0 and 7, the first no greater than the last",
+                    ));
+                }
+                push(Command::SetPageAddress.into());
+                push(start);
+                push(end);
+            }
+            Instruction::SetColumnStart(column) => {
+                push(column & 0x0f);
+                push(0x10 | (column >> 4));
+            }
+            Instruction::SetPageStart(page) => {
+                if page > 7 {
+                    return Err(OledError::InvalidArgument("page must be between 0 and 7"));
+                }
+                push(0xb0 | page);
+            }
+            Instruction::SetStartLine(line) => {
+                if line > 63 {
+                    return Err(OledError::InvalidArgument(
+                        "start line must be between 0 and 63",
+                    ));
+                }
+                push(0x40 | line);
+            }
+            Instruction::SetMultiplex(rows) => {
+                if !(16..=64).contains(&rows) {
+                    return Err(OledError::InvalidArgument(
+                        "multiplex ratio must be between 16 and 64 rows",
+                    ));
+                }
+                push(0xa8);
+                push(rows - 1);
+            }
+            Instruction::SetDisplayOffset(rows) => {
+                if rows > 63 {
+                    return Err(OledError::InvalidArgument(
+                        "display offset must be between 0 and 63 rows",
+                    ));
+                }
+                push(0xd3);
+                push(rows);
+            }
+            Instruction::SetClock { divide, frequency } => {
+                if !(1..=16).contains(&divide) || frequency > 15 {
+                    return Err(OledError::InvalidArgument(
+                        "clock divide ratio must be between 1 and 16, and frequency between 0 and 15",
+                    ));
+                }
+                push(0xd5);
+                push((frequency << 4) | (divide - 1));
+            }
+            Instruction::SetComPins {
+                alternative,
+                left_right_remap,
+            } => {
+                push(0xda);
+                push(0x02 | (u8::from(alternative) << 4) | (u8::from(left_right_remap) << 5));
+            }
+            Instruction::SetPrecharge(phase1, phase2) => {
+                if !(1..=15).contains(&phase1) || !(1..=15).contains(&phase2) {
+                    return Err(OledError::InvalidArgument(
+                        "pre-charge phases must be between 1 and 15 clocks",
+                    ));
+                }
+                push(Command::SetPrechargePeriod.into());
+                push((phase2 << 4) | phase1);
+            }
+            Instruction::SetVcomhLevel(level) => {
+                if level & !0x70 != 0 {
+                    return Err(OledError::InvalidArgument(
+                        "VCOMH level may only use bits 4 through 6",
+                    ));
+                }
+                push(Command::SetVcomhDeselectLevel.into());
+                push(level);
+            }
+            Instruction::ChargePump(on) => {
+                push(0x8d);
+                push(if on { 0x14 } else { 0x10 });
+            }
+            Instruction::DcDc(on) => {
+                push(0xad);
+                push(if on { 0x8b } else { 0x8a });
+            }
+        }
+        Ok(&buffer[..len])
+    }
+}
+
+impl From<Command> for Instruction {
+    fn from(command: Command) -> Instruction {
+        Instruction::Command(command)
+    }
+}
+
+impl<I: OledInterface> Oled<I> {
+    /// Sends `instruction` and its arguments, in one transaction where the
+    /// interface allows. Returns `InvalidArgument`, without sending anything,
+    /// if an argument is out of range.
+    pub fn send_instruction(&mut self, instruction: Instruction) -> OledResult {
+        let mut buffer = [0; MAX_INSTRUCTION_LEN];
+        let bytes = instruction.encode(&mut buffer)?;
+        self.send_commands(bytes)
+    }
+}
@@ -0,0 +1,87 @@
+//! The transport used to get commands and data to the display
+use crate::error::OledError;
+
+/// A transport capable of delivering command and data bytes to the display.
+///
+/// The SSD1306 distinguishes between bytes destined for its command parser and
+/// bytes destined for graphics RAM; an interface is responsible for framing
+/// each appropriately for its bus (over I2C, with a [`COMMAND_MODE`](constant.COMMAND_MODE.html)
+/// or [`DATA_MODE`](constant.DATA_MODE.html) control byte).
+///
+/// On Linux, this is implemented by `I2cInterface`, wrapping an `i2c_linux::I2c`,
+/// when the `linux` feature is enabled. On other platforms, implement it over
+/// whatever bus driver is available. For example, with an `embedded-hal` I2C bus:
+/// ```ignore
+/// use embedded_hal::i2c::I2c;
+/// use nanohat_oled::{OledError, OledInterface, COMMAND_MODE, DATA_MODE, OLED_ADDRESS};
+///
+/// struct HalInterface<B>(B);
+///
+/// impl<B: I2c> OledInterface for HalInterface<B> {
+///     fn send_command(&mut self, command: u8) -> Result<(), OledError> {
+///         self.0
+///             .write(OLED_ADDRESS as u8, &[COMMAND_MODE, command])
+///             .map_err(|_| OledError::Interface)
+///     }
+///
+///     fn send_data(&mut self, data: &[u8]) -> Result<(), OledError> {
+///         let mut buf = [DATA_MODE; 33];
+///         for chunk in data.chunks(32) {
+///             buf[1..=chunk.len()].copy_from_slice(chunk);
+///             self.0
+///                 .write(OLED_ADDRESS as u8, &buf[..=chunk.len()])
+///                 .map_err(|_| OledError::Interface)?;
+///         }
+///         Ok(())
+///     }
+///
+///     fn max_transfer_len(&self) -> usize {
+///         32
+///     }
+/// }
+/// ```
+pub trait OledInterface {
+    /// Sends a single byte to the display's command parser
+    fn send_command(&mut self, command: u8) -> Result<(), OledError>;
+
+    /// Sends bytes to the display RAM at the current RAM pointer
+    fn send_data(&mut self, data: &[u8]) -> Result<(), OledError>;
+
+    /// Sends several bytes to the display's command parser, eg a command and its
+    /// arguments. Interfaces that can frame them as a single transaction, such as
+    /// one control byte followed by all the commands over I2C, override this to
+    /// save round trips; by default, each goes through
+    /// [`send_command()`](trait.OledInterface.html#tymethod.send_command) in turn.
+    fn send_commands(&mut self, commands: &[u8]) -> Result<(), OledError> {
+        for command in commands {
+            self.send_command(*command)?;
+        }
+        Ok(())
+    }
+
+    /// The largest number of data bytes delivered in a single bus transaction.
+    /// [`Oled`](struct.Oled.html) splits RAM writes into transfers of this size,
+    /// so that a transfer that fails can be retried on its own.
+    /// Defaults to no limit.
+    fn max_transfer_len(&self) -> usize {
+        usize::MAX
+    }
+}
+
+impl<T: OledInterface + ?Sized> OledInterface for &mut T {
+    fn send_command(&mut self, command: u8) -> Result<(), OledError> {
+        (**self).send_command(command)
+    }
+
+    fn send_data(&mut self, data: &[u8]) -> Result<(), OledError> {
+        (**self).send_data(data)
+    }
+
+    fn send_commands(&mut self, commands: &[u8]) -> Result<(), OledError> {
+        (**self).send_commands(commands)
+    }
+
+    fn max_transfer_len(&self) -> usize {
+        (**self).max_transfer_len()
+    }
+}
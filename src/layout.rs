@@ -0,0 +1,117 @@
+//! Splitting the display into rows and columns for widgets
+use crate::Rect;
+
+/// How much of a [`Layout`](struct.Layout.html) one of its parts takes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Size {
+    /// Exactly this many pixels, or as many as are left if fewer
+    Fixed(u16),
+    /// A share of the pixels the fixed parts leave, in proportion to the
+    /// other weights, eg `Weight(2)` takes twice as much as `Weight(1)`
+    Weight(u16),
+}
+
+/// Splits a [`Rect`](struct.Rect.html) into `N` rows, or `N` columns, of
+/// fixed or weighted sizes, so that a screen's widgets are placed by what they
+/// need rather than by pixel offsets worked out by hand, and the rest move
+/// when one of them changes.
+///
+/// Fixed parts get their pixels first, in order, and weighted parts share what
+/// is left, with rounding spread so the parts always fill the whole area.
+/// Layouts nest, by splitting one of the parts another layout returns.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{Layout, Oled, OledResult, Rect, Size};
+/// # fn main() -> OledResult {
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// // a status bar across the top, and a list beside a gauge half its width
+/// let [bar, body] = Layout::rows([Size::Fixed(10), Size::Weight(1)]).split(Rect::new(0, 0, 128, 64));
+/// let [list, gauge] = Layout::columns([Size::Weight(2), Size::Weight(1)])
+///     .with_gap(2)
+///     .split(body);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Layout<const N: usize> {
+    /// Size of each part, from the top or the left
+    sizes: [Size; N],
+    /// Whether the parts are rows, rather than columns
+    rows: bool,
+    /// Pixels left empty between each part and the next
+    gap: u16,
+}
+
+impl<const N: usize> Layout<N> {
+    /// Splits an area into rows, from the top, of `sizes`
+    pub const fn rows(sizes: [Size; N]) -> Self {
+        Layout {
+            sizes,
+            rows: true,
+            gap: 0,
+        }
+    }
+
+    /// Splits an area into columns, from the left, of `sizes`
+    pub const fn columns(sizes: [Size; N]) -> Self {
+        Layout {
+            sizes,
+            rows: false,
+            gap: 0,
+        }
+    }
+
+    /// Leaves `gap` pixels empty between each part and the next
+    pub const fn with_gap(self, gap: u16) -> Self {
+        Layout { gap, ..self }
+    }
+
+    /// Splits `area` into its parts, in order. Parts that don't fit are empty,
+    /// at the far edge of the area.
+    pub fn split(&self, area: Rect) -> [Rect; N] {
+        let total = if self.rows { area.height } else { area.width };
+        let gaps = self.gap.saturating_mul(N.saturating_sub(1) as u16);
+        let mut left = total.saturating_sub(gaps);
+        // fixed parts first, then the weighted ones share the rest
+        let mut lengths = [0u16; N];
+        for (length, size) in lengths.iter_mut().zip(&self.sizes) {
+            if let Size::Fixed(pixels) = size {
+                *length = (*pixels).min(left);
+                left -= *length;
+            }
+        }
+        let weights: u32 = self
+            .sizes
+            .iter()
+            .map(|size| match size {
+                Size::Weight(weight) => u32::from(*weight),
+                Size::Fixed(_) => 0,
+            })
+            .sum();
+        let mut weight_so_far = 0;
+        let mut given = 0;
+        for (length, size) in lengths.iter_mut().zip(&self.sizes) {
+            if let Size::Weight(weight) = size {
+                weight_so_far += u32::from(*weight);
+                let upto = (u32::from(left) * weight_so_far)
+                    .checked_div(weights)
+                    .unwrap_or(0) as u16;
+                *length = upto - given;
+                given = upto;
+            }
+        }
+        let mut parts = [Rect::default(); N];
+        let mut at = 0u16;
+        for (part, length) in parts.iter_mut().zip(lengths) {
+            let start = at.min(total);
+            *part = if self.rows {
+                Rect::new(area.x, area.y + start, area.width, length)
+            } else {
+                Rect::new(area.x + start, area.y, length, area.height)
+            };
+            at = start.saturating_add(length).saturating_add(self.gap);
+        }
+        parts
+    }
+}
@@ -0,0 +1,193 @@
+//! Level meters with falling bars and peak markers
+use core::time::Duration;
+
+use crate::widgets::Bounds;
+use crate::{Framebuffer, OledError, Rect, Widget};
+
+/// A [`Widget`](trait.Widget.html) showing `N` levels as vertical meters side
+/// by side, eg the channels of a VU meter or the traffic on each network
+/// interface.
+///
+/// Like a hardware meter, each bar jumps straight up to a louder level but falls
+/// back gradually, and a marker holds at its peak for a while before falling
+/// too, so that short bursts can be seen. The falling happens as time passing is
+/// counted by [`tick()`](#method.tick), eg twenty or thirty times a second;
+/// rendered with [`Oled::render_widgets()`](struct.Oled.html#method.render_widgets),
+/// only what changed is sent, which keeps up at those rates.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{LevelBars, Oled, OledResult, Rect};
+/// # use std::time::Duration;
+/// # fn main() -> OledResult {
+/// # fn read_channels() -> [f32; 2] { [0.0; 2] }
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// let mut meter: LevelBars<2> = LevelBars::new(0.0, 1.0)?.with_gap(4);
+/// let frame = Duration::from_millis(40);
+/// loop {
+///     meter.set_levels(&read_channels());
+///     meter.tick(frame);
+///     oled.render_widgets(&mut [(&mut meter, Rect::new(0, 0, 128, 64))])?;
+///     std::thread::sleep(frame);
+/// }
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LevelBars<const N: usize> {
+    /// Values at which the bars are empty and full
+    bounds: Bounds,
+    /// Level each bar shows
+    levels: [f32; N],
+    /// Level last set for each bar, which it falls to
+    targets: [f32; N],
+    /// Where each peak marker is
+    peaks: [f32; N],
+    /// Time since each peak was reached
+    held: [Duration; N],
+    /// How long peaks stay put before falling
+    hold: Duration,
+    /// How far bars and peaks fall each second, as a fraction of the range
+    decay: f32,
+    /// Space between bars
+    gap: u16,
+    /// Whether anything has changed since the bars were last rendered
+    changed: bool,
+}
+
+impl<const N: usize> LevelBars<N> {
+    /// Creates `N` empty bars, from `min` to `max`, a pixel apart. Bars fall the
+    /// whole range in a second and a half, and peaks hold for a second.
+    ///
+    /// Returns `InvalidArgument` unless `max` is more than `min`.
+    pub fn new(min: f32, max: f32) -> Result<Self, OledError> {
+        let bounds = Bounds::new(min, max, "level maximum must be more than its minimum")?;
+        Ok(LevelBars {
+            bounds,
+            levels: [min; N],
+            targets: [min; N],
+            peaks: [min; N],
+            held: [Duration::ZERO; N],
+            hold: Duration::from_secs(1),
+            decay: 1.0 / 1.5,
+            gap: 1,
+            changed: true,
+        })
+    }
+
+    /// Leaves `gap` pixels between bars
+    pub fn with_gap(self, gap: u16) -> Self {
+        LevelBars { gap, ..self }
+    }
+
+    /// Keeps peak markers where they are for `hold` before they start to fall,
+    /// or, for zero, leaves them off
+    pub fn with_peak_hold(self, hold: Duration) -> Self {
+        LevelBars { hold, ..self }
+    }
+
+    /// Makes bars and peaks fall by `decay` of the whole range each second, eg
+    /// 2.0 to fall from full to empty in half a second. Negative values are
+    /// taken as 0, which makes bars drop straight away and peaks stay once held.
+    pub fn with_decay(self, decay: f32) -> Self {
+        LevelBars {
+            decay: decay.max(0.0),
+            ..self
+        }
+    }
+
+    /// Sets the level of bar `index`, if there is one. A louder level shows
+    /// straight away; a quieter one is fallen to by [`tick()`](#method.tick).
+    /// Values outside the bounds are taken to be the nearest of them, and `NaN`
+    /// as the minimum.
+    pub fn set_level(&mut self, index: usize, value: f32) {
+        if index >= N {
+            return;
+        }
+        let value = self.bounds.clamp(value);
+        self.targets[index] = value;
+        if value > self.levels[index] || self.decay == 0.0 {
+            self.changed |= value != self.levels[index];
+            self.levels[index] = value;
+        }
+        if value >= self.peaks[index] {
+            self.changed |= value != self.peaks[index];
+            self.peaks[index] = value;
+            self.held[index] = Duration::ZERO;
+        }
+    }
+
+    /// Sets the levels of the bars from the left, as for
+    /// [`set_level()`](#method.set_level). Any bars past the end of `values` are
+    /// left as they are.
+    pub fn set_levels(&mut self, values: &[f32]) {
+        for (index, value) in values.iter().enumerate() {
+            self.set_level(index, *value);
+        }
+    }
+
+    /// The level each bar shows, from the left
+    pub fn levels(&self) -> &[f32; N] {
+        &self.levels
+    }
+
+    /// Where each peak marker is, from the left
+    pub fn peaks(&self) -> &[f32; N] {
+        &self.peaks
+    }
+
+    /// Lets bars and peaks fall by `elapsed`, the time since the last tick. A bar
+    /// falls no further than the level last set, so bars set every tick follow
+    /// their level down smoothly.
+    pub fn tick(&mut self, elapsed: Duration) {
+        let span = self.bounds.span();
+        let fall = span * self.decay * elapsed.as_secs_f32();
+        for index in 0..N {
+            if self.levels[index] > self.targets[index] && fall > 0.0 {
+                self.levels[index] = (self.levels[index] - fall).max(self.targets[index]);
+                self.changed = true;
+            }
+            self.held[index] = self.held[index].saturating_add(elapsed);
+            if self.held[index] <= self.hold || self.peaks[index] <= self.levels[index] {
+                continue;
+            }
+            // falling starts once the hold runs out, not from when the peak was set
+            let falling = (self.held[index] - self.hold).min(elapsed);
+            let fall = span * self.decay * falling.as_secs_f32();
+            if fall > 0.0 {
+                self.peaks[index] = (self.peaks[index] - fall).max(self.levels[index]);
+                self.changed = true;
+            }
+        }
+    }
+}
+
+impl<const N: usize> Widget for LevelBars<N> {
+    fn render(&mut self, frame: &mut Framebuffer, area: Rect) {
+        frame.fill_rect(area.x, area.y, area.width, area.height, false);
+        self.changed = false;
+        if N == 0 || area.height == 0 {
+            return;
+        }
+        let gaps = self.gap.saturating_mul(N as u16 - 1);
+        let width = area.width.saturating_sub(gaps) / N as u16;
+        if width == 0 {
+            return;
+        }
+        let bottom = area.y + area.height;
+        // rows lit for `value`, from the bottom
+        let rows = |value: f32| (f32::from(area.height) * self.bounds.fraction(value) + 0.5) as u16;
+        for index in 0..N {
+            let x = area.x + index as u16 * (width + self.gap);
+            let level = rows(self.levels[index]);
+            frame.fill_rect(x, bottom - level, width, level, true);
+            let peak = rows(self.peaks[index]);
+            if !self.hold.is_zero() && peak > level {
+                frame.fill_rect(x, bottom - peak, width, 1, true);
+            }
+        }
+    }
+
+    fn needs_render(&self) -> bool {
+        self.changed
+    }
+}
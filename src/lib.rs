@@ -1,32 +1,271 @@
 #![doc = include_str!("../README.md")]
 #![warn(missing_docs)]
-use i2c_linux::I2c;
+#![cfg_attr(not(feature = "std"), no_std)]
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+use core::ops::Range;
 use log::*;
-use std::fs::File;
-use std::io::{Error, ErrorKind, Result};
-use std::path::Path;
+#[cfg(feature = "std")]
+use std::time::Duration;
 
+mod animation;
+#[cfg(feature = "buttons")]
+mod app;
+#[cfg(feature = "async")]
+mod async_oled;
+mod barcode;
+#[cfg(feature = "alloc")]
+mod bdf;
+mod bitmap;
+#[cfg(feature = "alloc")]
+mod bitmap_font;
+mod blink;
+mod bmp;
+#[cfg(feature = "buttons")]
+mod buttons;
+mod clock;
+mod config;
+mod console;
+mod contrast;
+mod controller;
+#[cfg(all(feature = "std", unix))]
+mod daemon;
+#[cfg(feature = "sysinfo")]
+mod dashboard;
+#[cfg(all(feature = "dbus", unix))]
+mod dbus;
+mod dither;
+mod double_buffer;
+mod error;
+#[cfg(all(feature = "evdev", target_os = "linux"))]
+mod evdev;
+mod fade;
+#[cfg(feature = "fbdev")]
+mod fb_mirror;
 mod font;
-use crate::font::BasicFont;
+mod fonts;
+#[cfg(feature = "std")]
+mod frame_timer;
+mod framebuffer;
+mod gauge;
+#[cfg(feature = "buttons")]
+mod gestures;
+#[cfg(feature = "gif")]
+mod gif;
+mod glyphs;
+mod graphics;
+#[cfg(feature = "std")]
+mod gray;
+mod group;
+#[cfg(feature = "http")]
+mod http;
+mod icons;
+mod idle;
+mod image_format;
+#[cfg(feature = "png")]
+mod inflate;
+mod instruction;
+mod interface;
+mod layout;
+mod level_bars;
+#[cfg(feature = "linux")]
+mod linux;
+#[cfg(feature = "std")]
+mod log_tail;
+#[cfg(feature = "logger")]
+mod logger;
+mod marquee;
+mod menu;
+#[cfg(feature = "alloc")]
+mod mock;
+#[cfg(feature = "mqtt")]
+mod mqtt;
+#[cfg(all(feature = "async", feature = "std"))]
+mod offload;
+mod pixel_shift;
+mod plot;
+#[cfg(feature = "png")]
+mod png_decode;
+mod power;
+mod progress_bar;
+#[cfg(feature = "alloc")]
+mod psf;
+#[cfg(feature = "qr")]
+mod qr;
+mod recover;
+mod region;
+mod rotation;
+mod scale;
+mod screensavers;
+#[cfg(feature = "alloc")]
+mod screenshot;
+mod scroll;
+#[cfg(feature = "std")]
+mod shared;
+#[cfg(feature = "simulator")]
+mod simulator;
+#[cfg(feature = "spi")]
+mod spi;
+mod splash;
+mod sprite;
+mod state;
+mod status_bar;
+#[cfg(feature = "std")]
+mod stream;
+mod stroke;
+mod table;
+#[cfg(feature = "std")]
+mod terminal;
+mod test_pattern;
+mod text;
+#[cfg(feature = "trace")]
+mod trace;
+mod transition;
+#[cfg(feature = "ttf")]
+mod ttf;
+mod typewriter;
+mod widgets;
+mod window;
+mod xbm;
+pub use crate::animation::Animation;
+#[cfg(feature = "buttons")]
+pub use crate::app::{App, Screen, ScreenManager, Transition};
+#[cfg(feature = "async")]
+pub use crate::async_oled::{AsyncOledInterface, OledAsync};
+pub use crate::barcode::{Barcode, Symbology, MAX_BARCODE_MODULES};
+pub use crate::bitmap::Bitmap;
+#[cfg(feature = "alloc")]
+pub use crate::bitmap_font::BitmapFont;
+pub use crate::blink::{Blink, CursorStyle};
+#[cfg(feature = "buttons")]
+pub use crate::buttons::{ButtonEvent, ButtonEvents, Buttons, Input, Key, NANOHAT_BUTTON_GPIOS};
+pub use crate::clock::{AnalogClock, DigitalClock};
+pub use crate::config::{OledConfig, PanelSize};
+pub use crate::console::{OledConsole, SCROLLBACK_ROWS};
+pub use crate::contrast::{Brightness, Contrast};
+pub use crate::controller::Controller;
+#[cfg(all(feature = "std", unix))]
+pub use crate::daemon::Daemon;
+#[cfg(feature = "sysinfo")]
+pub use crate::dashboard::{SystemDashboard, SystemStats};
+#[cfg(all(feature = "dbus", unix))]
+pub use crate::dbus::{DbusService, DBUS_NAME, DBUS_PATH};
+pub use crate::dither::{Dither, Threshold};
+pub use crate::double_buffer::DoubleBuffer;
+pub use crate::error::OledError;
+#[cfg(all(feature = "evdev", target_os = "linux"))]
+pub use crate::evdev::EvdevInput;
+pub use crate::fade::{Fade, FadeInterval, FadeMode};
+#[cfg(feature = "fbdev")]
+pub use crate::fb_mirror::FbMirror;
+#[cfg(feature = "alloc")]
+pub use crate::fonts::fit_text;
+pub use crate::fonts::{BuiltinFont, Font, Glyph, Truncation};
+#[cfg(feature = "std")]
+pub use crate::frame_timer::FrameTimer;
+pub use crate::framebuffer::Framebuffer;
+pub use crate::gauge::Gauge;
+#[cfg(feature = "buttons")]
+pub use crate::gestures::{Gesture, GestureDetector};
+#[cfg(feature = "gif")]
+pub use crate::gif::PlayOptions;
+use crate::glyphs::CustomGlyphs;
+pub use crate::glyphs::MAX_CUSTOM_GLYPHS;
+#[cfg(feature = "std")]
+pub use crate::gray::TemporalGray;
+pub use crate::group::{DisplayGroup, GroupLayout};
+#[cfg(feature = "http")]
+pub use crate::http::HttpDisplay;
+pub use crate::icons::{Icon, IconSize};
+pub use crate::idle::{IdleManager, NoScreensaver};
+pub use crate::image_format::ImageFormat;
+pub use crate::instruction::{Instruction, MAX_INSTRUCTION_LEN};
+pub use crate::interface::OledInterface;
+pub use crate::layout::{Layout, Size};
+pub use crate::level_bars::LevelBars;
+#[cfg(feature = "linux")]
+pub use crate::linux::I2cInterface;
+#[cfg(feature = "std")]
+pub use crate::log_tail::LogTail;
+#[cfg(feature = "logger")]
+pub use crate::logger::OledLogger;
+pub use crate::marquee::Marquee;
+pub use crate::menu::Menu;
+#[cfg(feature = "alloc")]
+pub use crate::mock::{CaptureDevice, MockInterface, Sent};
+#[cfg(feature = "mqtt")]
+pub use crate::mqtt::MqttDisplay;
+#[cfg(all(feature = "async", feature = "std"))]
+pub use crate::offload::Offload;
+use crate::pixel_shift::PixelShift;
+pub use crate::plot::{BarChart, LineChart, Scale, Sparkline, MAX_SPARKLINE_SAMPLES};
+#[cfg(feature = "png")]
+pub use crate::png_decode::is_png;
+use crate::power::AutoOff;
+pub use crate::progress_bar::ProgressBar;
+#[cfg(feature = "qr")]
+pub use crate::qr::{QrCode, QrEcc, QR_QUIET_ZONE};
+pub use crate::region::{Align, TextRegion};
+pub use crate::rotation::Rotation;
+use crate::rotation::{physical_to_portrait, portrait_to_physical, rotate_glyph};
+pub use crate::screensavers::{Bouncing, GameOfLife, Screensaver, Starfield};
+pub use crate::scroll::{ScrollDirection, ScrollInterval, VerticalScrollArea};
+#[cfg(feature = "std")]
+pub use crate::shared::{Flusher, SharedOled};
+#[cfg(feature = "simulator")]
+pub use crate::simulator::WindowSimulator;
+#[cfg(feature = "spi")]
+pub use crate::spi::SpiInterface;
+pub use crate::sprite::Sprite;
+use crate::state::DisplayState;
+pub use crate::status_bar::StatusBar;
+#[cfg(feature = "std")]
+pub use crate::stream::{FrameFormat, FrameStream};
+pub use crate::stroke::Stroke;
+pub use crate::table::{Table, TableColumn};
+#[cfg(feature = "std")]
+pub use crate::terminal::TerminalSimulator;
+pub use crate::test_pattern::Pattern;
+#[cfg(feature = "std")]
+pub use crate::test_pattern::SelfTest;
+pub use crate::text::{Overflow, TextCursor, TextLines, TextStyle};
+#[cfg(feature = "trace")]
+pub use crate::trace::TraceInterface;
+pub use crate::transition::{Effect, ScreenTransition};
+#[cfg(feature = "ttf")]
+pub use crate::ttf::TtfFont;
+pub use crate::typewriter::Typewriter;
+pub use crate::widgets::{Rect, Widget};
 
 /// The width of the display, in pixels
 pub const OLED_WIDTH: u16 = 128;
 /// The height of the display, in pixels
 pub const OLED_HEIGHT: u16 = 64;
-/// The I2C slave address of the display
+/// The number of text columns that fit across the display with the built-in font
+pub const TEXT_COLUMNS: u8 = 16;
+/// The number of text rows that fit down the display with the built-in font
+pub const TEXT_ROWS: u8 = 8;
+/// The I2C slave address of the display, as on the NanoHat OLED
 pub const OLED_ADDRESS: u16 = 0x3c;
+/// The other I2C slave address an SSD1306 can be strapped to, as many modules are
+pub const OLED_ALT_ADDRESS: u16 = 0x3d;
 /// The height of a single memory page
 const OLED_PAGE_HEIGHT: u16 = 8;
-/// Prefix for sending a command
-const COMMAND_MODE: u8 = 0x00;
-/// Prefix for sending bitmap data
-const DATA_MODE: u8 = 0x40;
+/// Control byte prefix for sending a command
+pub const COMMAND_MODE: u8 = 0x00;
+/// Control byte prefix for sending bitmap data
+pub const DATA_MODE: u8 = 0x40;
+/// The index of the last memory page
+const OLED_LAST_PAGE: u8 = (OLED_HEIGHT / OLED_PAGE_HEIGHT - 1) as u8;
+/// Size of the display RAM, in bytes (one bit per pixel)
+const OLED_RAM_SIZE: usize = (OLED_WIDTH * OLED_HEIGHT / OLED_PAGE_HEIGHT) as usize;
 /// Empty array for clearing screen
 const EMPTY_SCREEN: [u8; (OLED_WIDTH * OLED_HEIGHT) as usize] =
     [0u8; (OLED_WIDTH * OLED_HEIGHT) as usize];
 
 /// Returned for commands and data sent to the OLED display.
-pub type OledResult = Result<()>;
+pub type OledResult = Result<(), OledError>;
 
 /// For now, images are represented as byte arrays representing 8-bit grayscale bitmaps.
 /// Note that images must be exactly the size of the display
@@ -34,6 +273,7 @@ pub type Image = [u8];
 
 /// Different addressing modes available for the display.
 /// They affect how pointers are advanced after data is written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum AddressingMode {
     /// Each byte (column) written advances the column pointer by one.
     /// When it reaches the end of the page, the page pointer is advanced
@@ -59,7 +299,36 @@ impl From<AddressingMode> for u8 {
     }
 }
 
+/// How an image is combined with what is already on the display,
+/// as used by [`Oled::blit()`](struct.Oled.html#method.blit) and
+/// [`Framebuffer::blit_sprite()`](struct.Framebuffer.html#method.blit_sprite)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlitOp {
+    /// The image replaces the existing contents
+    Copy,
+    /// Pixels lit in either the image or the existing contents are lit
+    Or,
+    /// Only pixels lit in both the image and the existing contents stay lit
+    And,
+    /// Pixels lit in the image toggle the existing contents.
+    /// Doing the same blit twice restores the original display.
+    Xor,
+}
+
+impl BlitOp {
+    /// Combines a byte of existing display RAM with a byte of new image data
+    fn apply(self, existing: u8, new: u8) -> u8 {
+        match self {
+            BlitOp::Copy => new,
+            BlitOp::Or => existing | new,
+            BlitOp::And => existing & new,
+            BlitOp::Xor => existing ^ new,
+        }
+    }
+}
+
 /// A command that can be sent to the OLED display
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Command {
     /// Sets contrast level of display, with higher number meaning higher contrast. Default is 0x7f.
     SetContrast,
@@ -78,6 +347,42 @@ pub enum Command {
     NormalDisplay,
     /// Display is black on white, ie a 1 denotes black, 0 denotes white.
     InverseDisplay,
+    /// Sets the duration of the two pre-charge phases, in display clocks.
+    /// Default is 0x22.
+    SetPrechargePeriod,
+    /// Sets the V<sub>COMH</sub> regulator output level. Default is 0x20.
+    SetVcomhDeselectLevel,
+    /// Sets the first and last columns written in horizontal and vertical addressing modes
+    SetColumnAddress,
+    /// Sets the first and last pages written in horizontal and vertical addressing modes
+    SetPageAddress,
+    /// Sets the rows that take part in vertical scrolling
+    SetVerticalScrollArea,
+    /// Sets up a continuous rightward horizontal scroll
+    RightHorizontalScroll,
+    /// Sets up a continuous leftward horizontal scroll
+    LeftHorizontalScroll,
+    /// Sets up a continuous vertical and rightward horizontal scroll
+    VerticalRightHorizontalScroll,
+    /// Sets up a continuous vertical and leftward horizontal scroll
+    VerticalLeftHorizontalScroll,
+    /// Sets up the controller's own fading out or blinking, as one of the
+    /// [`FadeMode`](enum.FadeMode.html) values
+    SetFadeMode,
+    /// Stops scrolling
+    DeactivateScroll,
+    /// Starts scrolling, as set up by the last scroll setup command
+    ActivateScroll,
+    /// Column address 0 is mapped to the leftmost segment. (default)
+    /// Only affects data written afterwards.
+    SegmentRemapNormal,
+    /// Column address 127 is mapped to the leftmost segment.
+    /// Only affects data written afterwards.
+    SegmentRemapReversed,
+    /// Rows are scanned from the top COM pin down. (default)
+    ComScanNormal,
+    /// Rows are scanned from the bottom COM pin up
+    ComScanRemapped,
 }
 
 impl From<Command> for u8 {
@@ -91,65 +396,223 @@ impl From<Command> for u8 {
             Command::NormalDisplay => 0xa6,
             Command::InverseDisplay => 0xa7,
             Command::SetContrast => 0x81,
+            Command::SetPrechargePeriod => 0xd9,
+            Command::SetVcomhDeselectLevel => 0xdb,
+            Command::SetColumnAddress => 0x21,
+            Command::SetPageAddress => 0x22,
+            Command::SetVerticalScrollArea => 0xa3,
+            Command::RightHorizontalScroll => 0x26,
+            Command::LeftHorizontalScroll => 0x27,
+            Command::VerticalRightHorizontalScroll => 0x29,
+            Command::VerticalLeftHorizontalScroll => 0x2a,
+            Command::SetFadeMode => 0x23,
+            Command::DeactivateScroll => 0x2e,
+            Command::ActivateScroll => 0x2f,
+            Command::SegmentRemapNormal => 0xa0,
+            Command::SegmentRemapReversed => 0xa1,
+            Command::ComScanNormal => 0xc0,
+            Command::ComScanRemapped => 0xc8,
+        }
+    }
+}
+
+/// Packs a grayscale image `width` by `height` pixels into the top left of
+/// `write_page`, in display RAM layout, one bit per pixel, clearing the rest.
+/// Anything greater than or equal to the `threshold` becomes a `1` pixel.
+fn pack_image(
+    image: &Image,
+    width: u16,
+    height: u16,
+    threshold: u8,
+    write_page: &mut [u8; OLED_RAM_SIZE],
+) -> OledResult {
+    if image.len() != (height * width) as usize {
+        return Err(OledError::InvalidDimensions);
+    }
+    write_page.fill(0);
+    for (page_bytes, page_rows) in write_page
+        .chunks_exact_mut(OLED_WIDTH as usize)
+        .zip(image.chunks((width * OLED_PAGE_HEIGHT) as usize))
+    {
+        // each row sets one bit of every byte in the page, LSB at the top
+        for (row, row_pixels) in page_rows.chunks_exact(width as usize).enumerate() {
+            for (byte, pixel) in page_bytes.iter_mut().zip(row_pixels) {
+                *byte |= u8::from(*pixel >= threshold) << row;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Packs procedurally generated content into `write_page`, in display RAM layout.
+/// `f` is called with the `x` and `y` coordinate of every pixel.
+fn pack_fn<F: Fn(u16, u16) -> bool>(f: F, write_page: &mut [u8; OLED_RAM_SIZE]) {
+    for (offset, byte) in write_page.iter_mut().enumerate() {
+        let column = offset as u16 % OLED_WIDTH;
+        let page = offset as u16 / OLED_WIDTH;
+        *byte = 0;
+        for row in 0..OLED_PAGE_HEIGHT {
+            if f(column, page * OLED_PAGE_HEIGHT + row) {
+                *byte |= 1 << row;
+            }
         }
     }
 }
 
 /// Represents the NanoHat OLED device
-pub struct Oled {
-    /// Device's I2C slave address
-    device: I2c<File>,
+pub struct Oled<I> {
+    /// Transport used to reach the display
+    interface: I,
+    /// Number of times a failed transfer is retried before giving up
+    retries: u8,
+    /// Time waited before the first retry, doubling with each one after
+    #[cfg(feature = "std")]
+    backoff: Duration,
+    /// Copy of the display's RAM and settings, as written so far
+    state: DisplayState,
+    /// Orientation the picture is drawn in
+    rotation: Rotation,
+    /// Text cell the next character goes in, in portrait orientations
+    text_cursor: (u8, u8),
+    /// Glyphs registered by the application
+    custom_glyphs: CustomGlyphs,
+    /// How text in the built-in font is drawn
+    text_style: TextStyle,
+    /// Blinking cursor shown in a text cell, if any
+    cursor: Option<Blink>,
+    /// Whether and when the display goes to sleep for want of activity
+    auto_off: AutoOff,
+    /// When the picture is moved against burn-in, and where to
+    pixel_shift: PixelShift,
+    /// Settings the display was set up with
+    config: OledConfig,
+    /// Display RAM being put together before it's sent, eg a packed image or
+    /// the changed part of a framebuffer, kept so that each write reuses it
+    scratch: [u8; OLED_RAM_SIZE],
 }
 
-impl Oled {
-    /// Opens the device from its entry in the dev filesystem.
-    /// # Example:
-    /// ```
-    /// # use nanohat_oled::Oled;
-    /// let mut oled = Oled::from_path("/dev/i2c-0");
-    /// ```
-    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let mut i2c = I2c::from_path(path)?;
-        i2c.smbus_set_slave_address(OLED_ADDRESS, false)?;
-        Ok(Self { device: i2c })
+impl<I: OledInterface> Oled<I> {
+    /// Wraps an [`OledInterface`](trait.OledInterface.html) that is already
+    /// connected to the display. On Linux, see also `from_path`.
+    pub fn new(interface: I) -> Self {
+        Self {
+            interface,
+            retries: 0,
+            #[cfg(feature = "std")]
+            backoff: Duration::ZERO,
+            state: DisplayState::default(),
+            rotation: Rotation::Rotate0,
+            text_cursor: (0, 0),
+            custom_glyphs: CustomGlyphs::default(),
+            text_style: TextStyle::Normal,
+            cursor: None,
+            auto_off: AutoOff::default(),
+            pixel_shift: PixelShift::default(),
+            config: OledConfig::DEFAULT,
+            scratch: [0; OLED_RAM_SIZE],
+        }
+    }
+
+    /// Consumes the display, returning the interface it was using
+    pub fn into_interface(self) -> I {
+        self.interface
+    }
+
+    /// Returns the contents of display RAM, in the layout described for
+    /// [`send_data()`](struct.Oled.html#method.send_data): one byte per column,
+    /// one page after another.
+    ///
+    /// The display RAM can't be read over I2C, so this is a copy kept up to date
+    /// with every command and data byte sent through this `Oled`, starting from the
+    /// controller's reset state. Changes made by anything else sharing the bus
+    /// are not reflected.
+    pub fn framebuffer(&self) -> &[u8; OLED_RAM_SIZE] {
+        &self.state.buffer
+    }
+
+    /// Expands the current [`framebuffer()`](struct.Oled.html#method.framebuffer)
+    /// into an 8-bit grayscale [`Image`](type.Image.html), using `on` for lit pixels
+    /// and `off` for dark ones.
+    /// Passing the result to [`draw_image()`](struct.Oled.html#method.draw_image) recreates
+    /// the current display, provided `on` meets the threshold and `off` doesn't.
+    #[cfg(feature = "alloc")]
+    pub fn to_image(&self, on: u8, off: u8) -> alloc::vec::Vec<u8> {
+        let mut image = alloc::vec![off; (OLED_WIDTH * OLED_HEIGHT) as usize];
+        for (offset, byte) in self.state.buffer.iter().enumerate() {
+            let column = offset % OLED_WIDTH as usize;
+            let page = offset / OLED_WIDTH as usize;
+            for row in 0..OLED_PAGE_HEIGHT as usize {
+                if byte & (1 << row) != 0 {
+                    let y = page * OLED_PAGE_HEIGHT as usize + row;
+                    image[y * OLED_WIDTH as usize + column] = on;
+                }
+            }
+        }
+        image
+    }
+
+    /// Sets how many times a command byte or transfer to display RAM that fails is
+    /// retried before the error is returned. Retries happen per transfer (see
+    /// [`send_array_data()`](struct.Oled.html#method.send_array_data)), so data that
+    /// was already delivered is not sent again, and only for errors that
+    /// [`is_transient()`](enum.OledError.html#method.is_transient).
+    /// Default is 0, ie no retries.
+    pub fn set_retries(&mut self, retries: u8) {
+        self.retries = retries;
+    }
+
+    /// Sets how long to wait before retrying a failed transfer, doubling with
+    /// each retry after the first, so that a bus upset by interference or a
+    /// device being plugged in has time to settle. Default is not to wait.
+    #[cfg(feature = "std")]
+    pub fn set_retry_backoff(&mut self, initial: Duration) {
+        self.backoff = initial;
+    }
+
+    /// Calls `send` with the interface and the scratch buffer, retrying on
+    /// transient errors as set by [`set_retries()`](struct.Oled.html#method.set_retries)
+    fn retry<F>(&mut self, what: &str, mut send: F) -> OledResult
+    where
+        F: FnMut(&mut I, &[u8; OLED_RAM_SIZE]) -> OledResult,
+    {
+        let mut attempt = 0;
+        loop {
+            match send(&mut self.interface, &self.scratch) {
+                Err(err) if attempt < self.retries && err.is_transient() => {
+                    warn!("Retrying {} (attempt {}): {}", what, attempt + 1, err);
+                    #[cfg(feature = "std")]
+                    std::thread::sleep(self.backoff * (1u32 << attempt.min(16)));
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
     }
 
     /// Initial low-level setup for the display, per SSD1306 and NanoHat OLED datasheets.
+    /// See [`init_with()`](struct.Oled.html#method.init_with) for other panels.
     pub fn init(&mut self) -> OledResult {
-        self.send_command(Command::DisplayOff)?;
-        self.send_command(0x00)?; // Set lower column address
-        self.send_command(0x10)?; // Set higher column address
-        self.send_command(0x40)?; // Set display start line
-        self.send_command(0xB0)?; // Set page address
-        self.send_command(0x81)?; // contrast control
-        self.send_command(0x7f)?; // default contrast is 0x7f
-        self.send_command(0xa1)?; // Set segment remap
-        self.send_command(Command::NormalDisplay)?;
-        self.send_command(0xa8)?; // Multiplex ratio
-        self.send_command(0x3f)?; // Duty = 1/64
-        self.send_command(0xc8)?; // Use remapped COM scan direction
-        self.send_command(0xd3)?; // Set display offset
-        self.send_command(0x00)?; // No offset
-        self.send_command(0xd5)?; // Set display clock division
-        self.send_command(0x80)?; // divide ratio
-        self.send_command(0xd9)?; // Set pre-charge period
-        self.send_command(0xf1)?;
-        self.send_command(0xda)?; // Set COM pins
-        self.send_command(0x12)?;
-        self.send_command(0xdb)?; // Set vcomh deselect level
-        self.send_command(0x40)?;
-        self.send_command(0x8d)?; // Set charge pump state
-        self.send_command(0x14)?; // charge pump enabled
-        self.send_command(Command::DisplayOn)?;
-        self.set_addressing_mode(AddressingMode::Horizontal)?;
-        self.clear_display()?;
-        Ok(())
+        self.init_with(OledConfig::DEFAULT)
     }
 
     /// Sends a command or command argument to the display's command parser
     pub fn send_command<B: Into<u8>>(&mut self, byte: B) -> OledResult {
-        self.device
-            .i2c_write_block_data(COMMAND_MODE, &[byte.into()])?;
+        let byte = byte.into();
+        self.retry("command", |interface, _| interface.send_command(byte))?;
+        self.state.command(byte);
+        Ok(())
+    }
+
+    /// Sends several bytes to the display's command parser, eg a command and its
+    /// arguments, in as few bus transactions as the interface allows: see
+    /// [`OledInterface::send_commands()`](trait.OledInterface.html#method.send_commands).
+    /// They are retried together, according to
+    /// [`set_retries()`](struct.Oled.html#method.set_retries).
+    pub fn send_commands(&mut self, commands: &[u8]) -> OledResult {
+        self.retry("commands", |interface, _| interface.send_commands(commands))?;
+        for command in commands {
+            self.state.command(*command);
+        }
         Ok(())
     }
 
@@ -162,102 +625,480 @@ impl Oled {
     /// be written to the bottom row. Once the byte is written, pointers will advance,
     /// depending on the [`AddressingMode`](enum.AddressingMode.html).
     pub fn send_data<B: Into<u8>>(&mut self, byte: B) -> OledResult {
-        self.device
-            .i2c_write_block_data(DATA_MODE, &[byte.into()])?;
-        Ok(())
+        self.send_transfer(&[byte.into()])
     }
 
     /// Sends a set of data all at once into the display RAM.
-    /// Data is always written in chunks of 31 bytes (plus a byte to set data mode).
     /// See [`send_data()`](struct.Oled.html#method.send_data) for more details on RAM layout
+    ///
+    /// Data is split into transfers of at most
+    /// [`OledInterface::max_transfer_len()`](trait.OledInterface.html#method.max_transfer_len)
+    /// bytes, each retried according to [`set_retries()`](struct.Oled.html#method.set_retries).
+    /// If a transfer still fails, its error is returned immediately and the remaining
+    /// transfers are not sent. The transfers before it will already have been written,
+    /// so the display may show a partial update, and the RAM pointer is left wherever
+    /// the delivered data advanced it to. Callers should rewrite the whole region to recover.
     pub fn send_array_data<'a, B: Into<&'a [u8]>>(&mut self, data: B) -> OledResult {
-        for chunk in data.into().chunks(31) {
-            self.device.i2c_write_block_data(DATA_MODE, chunk)?;
+        let transfer_len = self.interface.max_transfer_len().max(1);
+        for transfer in data.into().chunks(transfer_len) {
+            self.send_transfer(transfer)?;
+        }
+        Ok(())
+    }
+
+    /// Sends a single transfer of RAM data, retrying it if it fails
+    fn send_transfer(&mut self, data: &[u8]) -> OledResult {
+        if self.auto_off.asleep {
+            self.touch()?;
+        }
+        self.retry("data transfer", |interface, _| interface.send_data(data))?;
+        self.state.write(data);
+        Ok(())
+    }
+
+    /// Sends `range` of the scratch buffer to display RAM, in transfers as for
+    /// [`send_array_data()`](struct.Oled.html#method.send_array_data)
+    fn send_scratch(&mut self, range: Range<usize>) -> OledResult {
+        let transfer_len = self.interface.max_transfer_len().max(1);
+        let mut start = range.start;
+        while start < range.end {
+            let end = range.end.min(start.saturating_add(transfer_len));
+            if self.auto_off.asleep {
+                self.touch()?;
+            }
+            self.retry("data transfer", |interface, scratch| {
+                interface.send_data(&scratch[start..end])
+            })?;
+            self.state.write(&self.scratch[start..end]);
+            start = end;
         }
         Ok(())
     }
 
     /// Sets the cursor position for writing text to display RAM.
+    ///
+    /// When turned a quarter turn by [`set_rotation()`](struct.Oled.html#method.set_rotation),
+    /// there are 8 columns and 16 rows, and the cursor is kept by the driver rather
+    /// than the display.
+    ///
+    /// Returns `OutOfBounds`, without moving the cursor, if the cell is off the display.
     pub fn set_text_xy(&mut self, column: u8, row: u8) -> OledResult {
-        self.send_command(0xb0 + row)?; // set page address
-        self.send_command((8 * column) & 0x0f)?; // set column low address
-        self.send_command(0x10 + (((8 * column) >> 4) & 0x0f))?; // set column high address
-        Ok(())
+        if self.rotation.is_portrait() {
+            if column >= TEXT_ROWS || row >= TEXT_COLUMNS {
+                return Err(OledError::OutOfBounds);
+            }
+            self.text_cursor = (column, row);
+            return Ok(());
+        }
+        let (columns, rows) = self.config.size().text_cells();
+        if column >= columns || row >= rows {
+            return Err(OledError::OutOfBounds);
+        }
+        self.move_pointer(8 * column, row)
+    }
+
+    /// Moves the RAM pointer to pixel column `x` of `page`, for text and
+    /// graphics placed to the pixel across the display rather than in 8-pixel
+    /// cells, eg `put_string()` starting part way into a cell. Pages are 8 rows
+    /// high, counting from the top. Positions are in the display's own layout,
+    /// whatever the [rotation](struct.Oled.html#method.set_rotation).
+    ///
+    /// Returns `OutOfBounds`, without moving the pointer, if the position is off the panel.
+    /// # Example:
+    /// ```no_run
+    /// # use nanohat_oled::{Oled, OledResult};
+    /// # fn main() -> OledResult {
+    /// let mut oled = Oled::from_path("/dev/i2c-0")?;
+    /// oled.init()?;
+    /// // centred on the second text row
+    /// oled.set_position(44, 1)?;
+    /// oled.put_string("Hello")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_position(&mut self, x: u8, page: u8) -> OledResult {
+        let size = self.config.size();
+        if u16::from(x) >= size.width() || u16::from(page) >= size.height() / OLED_PAGE_HEIGHT {
+            return Err(OledError::OutOfBounds);
+        }
+        self.move_pointer(x, page)
+    }
+
+    /// Moves the RAM pointer to pixel column `x`, staying on the same page, as for
+    /// [`set_position()`](struct.Oled.html#method.set_position)
+    pub fn set_column_px(&mut self, x: u8) -> OledResult {
+        let (_, page) = self.state.pointer();
+        self.set_position(x, page)
+    }
+
+    /// Writes a rectangular part of `buffer`, which is laid out like display RAM,
+    /// to the same place in display RAM. `columns` and `pages` are inclusive ranges.
+    /// Afterwards, the column and page window is reset to the whole display, and the
+    /// RAM pointer is left at the top left.
+    pub(crate) fn write_window(
+        &mut self,
+        columns: (u8, u8),
+        pages: (u8, u8),
+        buffer: &[u8; OLED_RAM_SIZE],
+    ) -> OledResult {
+        if !self.config.controller().has_windows() {
+            return self.write_pages(columns, pages, buffer);
+        }
+        let mut len = 0;
+        for page in pages.0..=pages.1 {
+            let start = page as usize * OLED_WIDTH as usize;
+            let row = &buffer[start + columns.0 as usize..=start + columns.1 as usize];
+            self.scratch[len..len + row.len()].copy_from_slice(row);
+            len += row.len();
+        }
+        if self.state.mode != AddressingMode::Horizontal {
+            self.set_addressing_mode(AddressingMode::Horizontal)?;
+        }
+        self.set_draw_window(columns.0, columns.1, pages.0, pages.1)?;
+        self.send_scratch(0..len)?;
+        self.reset_draw_window()
     }
 
     /// Completely clears the display of text and images
     pub fn clear_display(&mut self) -> OledResult {
         self.send_command(Command::DisplayOff)?;
-        self.set_text_xy(0, 0)?;
-        self.send_array_data(&EMPTY_SCREEN[..])?;
+        if self.config.controller().has_windows() {
+            self.widen_window()?;
+            self.set_text_xy(0, 0)?;
+            self.send_array_data(&EMPTY_SCREEN[..])?;
+        } else {
+            self.scratch.fill(0);
+            self.write_scratch()?;
+        }
         self.send_command(Command::DisplayOn)?;
         Ok(())
     }
 
     /// Writes an image bitmap to the screen.
-    /// The bitmap must be the same dimensions as the display.
+    /// The bitmap must be the same dimensions as the display, as given by
+    /// [`dimensions()`](struct.Oled.html#method.dimensions).
     /// Anything greater than or equal to the `threshold` will
     /// be interpreted as a `1` pixel; anything under will be
     /// interpreted as a `0`.
     pub fn draw_image(&mut self, image: &Image, threshold: u8) -> OledResult {
-        if image.len() != (OLED_HEIGHT * OLED_WIDTH) as usize {
-            return Err(Error::new(
-                ErrorKind::InvalidData,
-                format!("Image dimensions must be {}x{}", OLED_WIDTH, OLED_HEIGHT),
-            ));
+        self.pack_oriented(image, threshold)?;
+        self.write_scratch()
+    }
+
+    /// Combines an image bitmap with the current contents of the screen using
+    /// a raster operation, eg [`BlitOp::Xor`](enum.BlitOp.html#variant.Xor) to draw
+    /// a cursor that can be erased by drawing it again.
+    /// The bitmap and `threshold` are as for [`draw_image()`](struct.Oled.html#method.draw_image),
+    /// and the current contents are taken from [`framebuffer()`](struct.Oled.html#method.framebuffer).
+    pub fn blit(&mut self, image: &Image, threshold: u8, op: BlitOp) -> OledResult {
+        self.pack_oriented(image, threshold)?;
+        for (new, existing) in self.scratch.iter_mut().zip(self.state.buffer.iter()) {
+            *new = op.apply(*existing, *new);
         }
-        let mut write_page = [0u8; (OLED_WIDTH * OLED_HEIGHT / OLED_PAGE_HEIGHT) as usize];
-        for (page, page_data) in image
-            .chunks((OLED_WIDTH * OLED_PAGE_HEIGHT) as usize)
-            .enumerate()
-        {
-            for (row, row_data) in page_data.chunks(OLED_WIDTH as usize).enumerate() {
-                for (column, pixel) in row_data.iter().enumerate() {
-                    let pixel = if *pixel >= threshold { 1 } else { 0 };
-                    debug!(
-                        "page: {}, row: {}, column: {}, write offset: {}",
-                        page,
-                        row,
-                        column,
-                        (page * OLED_WIDTH as usize) + column
-                    );
-                    write_page[(page * OLED_WIDTH as usize) + column] |= pixel << row;
+        self.write_scratch()
+    }
+
+    /// Writes an image `width` by `height` pixels to the screen with its top left
+    /// corner at `x`, `y`, leaving the rest of the screen as it is, eg to show an
+    /// icon in a corner. Any part of the image beyond the right or bottom edge is
+    /// clipped, and only the pages and columns it covers are sent.
+    /// `threshold` is as for [`draw_image()`](struct.Oled.html#method.draw_image).
+    /// Returns `InvalidDimensions` if `image` isn't `width * height` bytes long.
+    /// # Example:
+    /// ```no_run
+    /// # use nanohat_oled::{Oled, OledResult};
+    /// # fn main() -> OledResult {
+    /// # let icon = [0xffu8; 32 * 32];
+    /// let mut oled = Oled::from_path("/dev/i2c-0")?;
+    /// oled.init()?;
+    /// oled.draw_image_at(&icon, 32, 32, 96, 0, 0x80)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn draw_image_at(
+        &mut self,
+        image: &Image,
+        width: u16,
+        height: u16,
+        x: u16,
+        y: u16,
+        threshold: u8,
+    ) -> OledResult {
+        if image.len() != width as usize * height as usize {
+            return Err(OledError::InvalidDimensions);
+        }
+        let (screen_width, screen_height) = self.dimensions();
+        if x >= screen_width || y >= screen_height || width == 0 || height == 0 {
+            return Ok(());
+        }
+        let last_x = x + (width - 1).min(screen_width - 1 - x);
+        let last_y = y + (height - 1).min(screen_height - 1 - y);
+        let portrait = self.rotation.is_portrait();
+        let mut frame = Framebuffer::with_layout(*self.framebuffer(), portrait);
+        for (row, pixels) in image.chunks_exact(width as usize).enumerate() {
+            for (column, pixel) in pixels.iter().enumerate() {
+                let (px, py) = (x + column as u16, y + row as u16);
+                if px <= last_x && py <= last_y {
+                    frame.set_pixel(px, py, *pixel >= threshold);
                 }
             }
         }
-        self.set_text_xy(0, 0)?;
-        self.send_array_data(&write_page[..])?;
-        Ok(())
+        let (columns, rows) = if portrait {
+            let (left, bottom) = portrait_to_physical(x, y);
+            let (right, top) = portrait_to_physical(last_x, last_y);
+            ((left, right), (top, bottom))
+        } else {
+            ((x, last_x), (y, last_y))
+        };
+        self.write_window(
+            (columns.0 as u8, columns.1 as u8),
+            (
+                (rows.0 / OLED_PAGE_HEIGHT) as u8,
+                (rows.1 / OLED_PAGE_HEIGHT) as u8,
+            ),
+            frame.as_bytes(),
+        )
+    }
+
+    /// Draws procedurally generated content to the whole screen, without
+    /// needing an intermediate [`Image`](type.Image.html).
+    /// `f` is called once for each pixel with its `x` and `y` coordinates
+    /// (`0..width` and `0..height`, as given by
+    /// [`dimensions()`](struct.Oled.html#method.dimensions)), and returns whether it is lit.
+    /// # Example:
+    /// ```no_run
+    /// # use nanohat_oled::{Oled, OledResult};
+    /// # fn main() -> OledResult {
+    /// let mut oled = Oled::from_path("/dev/i2c-0")?;
+    /// oled.init()?;
+    /// // checkerboard of 8x8 squares
+    /// oled.draw_fn(|x, y| (x / 8 + y / 8) % 2 == 0)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn draw_fn<F: Fn(u16, u16) -> bool>(&mut self, f: F) -> OledResult {
+        if self.rotation.is_portrait() {
+            pack_fn(
+                |x, y| {
+                    let (x, y) = physical_to_portrait(x, y);
+                    f(x, y)
+                },
+                &mut self.scratch,
+            );
+        } else {
+            let size = self.config.size();
+            pack_fn(
+                |x, y| x < size.width() && y < size.height() && f(x, y),
+                &mut self.scratch,
+            );
+        }
+        self.write_scratch()
     }
 
     /// Writes a single character to the display at the current
     /// X,Y location (as set by [`set_text_xy()`](struct.Oled.html#method.set_text_xy)
     /// and incremented by the [`AddressingMode`](enum.AddressingMode.html)).
-    /// Note: only printable ASCII is supported. Other characters will output as
-    /// an empty square.
-    pub fn put_char(&mut self, char: char) -> OledResult {
-        let bitmap = BasicFont::bitmap(char);
-        self.send_array_data(&bitmap[..])?;
-        Ok(())
+    /// Note: only printable ASCII, the rest of code page 437 and glyphs added with
+    /// [`register_glyph()`](struct.Oled.html#method.register_glyph) are supported.
+    /// Other characters will output as an empty square. The character is drawn in
+    /// the style set by [`set_text_style()`](struct.Oled.html#method.set_text_style).
+    ///
+    /// Returns where the text cursor is left, as [`cursor()`](struct.Oled.html#method.cursor)
+    /// would, and whether writing the character ran off the end of its row.
+    pub fn put_char(&mut self, char: char) -> Result<TextCursor, OledError> {
+        self.put_glyph(self.text_style.apply(self.custom_glyphs.bitmap(char)))
     }
 
     /// Writes a string to the display, starting at the current
     /// X, Y location (as set by `set_text_xy` and incremented by
     /// the [`AddressingMode`](enum.AddressingMode.html)).
-    /// None: only printable ASCII is supported
-    pub fn put_string(&mut self, string: &str) -> OledResult {
+    /// Note: only printable ASCII, the rest of code page 437 and registered glyphs
+    /// are supported
+    ///
+    /// Returns where the text cursor is left, and how many times the string ran
+    /// off the end of a row, eg to tell whether it fitted on the one it started on.
+    /// # Example:
+    /// ```no_run
+    /// # use nanohat_oled::{Oled, OledResult};
+    /// # fn main() -> OledResult {
+    /// let mut oled = Oled::from_path("/dev/i2c-0")?;
+    /// oled.init()?;
+    /// // too long for the first row, so it carries on onto the next
+    /// let written = oled.put_string("Address: 192.168.1.20")?;
+    /// assert_eq!((written.column, written.row, written.wraps), (5, 1, 1));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn put_string(&mut self, string: &str) -> Result<TextCursor, OledError> {
+        self.put_chars(string, |oled, char| oled.put_char(char))
+    }
+
+    /// Writes each character of `string` with `put`, adding up the wraps
+    fn put_chars(
+        &mut self,
+        string: &str,
+        put: impl Fn(&mut Self, char) -> Result<TextCursor, OledError>,
+    ) -> Result<TextCursor, OledError> {
+        let (column, row) = self.cursor();
+        let mut written = TextCursor {
+            column,
+            row,
+            wraps: 0,
+        };
         for char in string.chars() {
-            self.put_char(char)?;
+            let cursor = put(self, char)?;
+            written = TextCursor {
+                wraps: written.wraps.saturating_add(cursor.wraps),
+                ..cursor
+            };
+        }
+        Ok(written)
+    }
+
+    /// Writes a string like [`put_string()`](struct.Oled.html#method.put_string), but
+    /// returns `UnsupportedGlyph` for the first character that neither the built-in
+    /// font nor a registered glyph can show, instead of drawing a box. Nothing is written in that case.
+    pub fn try_put_string(&mut self, string: &str) -> Result<TextCursor, OledError> {
+        if let Some(char) = string
+            .chars()
+            .find(|char| self.custom_glyphs.glyph(*char).is_none())
+        {
+            return Err(OledError::UnsupportedGlyph(char));
+        }
+        self.put_string(string)
+    }
+
+    /// Writes a single character to the display as black on white,
+    /// filling its whole 8x8 cell, eg to highlight a selected menu item.
+    /// The rest of the display is unaffected, whatever the
+    /// [`text_style()`](struct.Oled.html#method.text_style).
+    /// Otherwise behaves like [`put_char()`](struct.Oled.html#method.put_char).
+    pub fn put_char_inverted(&mut self, char: char) -> Result<TextCursor, OledError> {
+        self.put_glyph(TextStyle::Inverted.apply(self.custom_glyphs.bitmap(char)))
+    }
+
+    /// Writes a glyph at the text cursor, turning it to suit the orientation,
+    /// and returns where that leaves the cursor
+    fn put_glyph(&mut self, glyph: [u8; 8]) -> Result<TextCursor, OledError> {
+        let before = self.cursor();
+        if self.rotation.is_portrait() {
+            let (column, row) = self.text_cursor;
+            // portrait text columns run up the display's pages, and rows across its columns
+            self.move_pointer(8 * row, OLED_LAST_PAGE - column)?;
+            self.send_array_data(&rotate_glyph(glyph)[..])?;
+            self.text_cursor = if column + 1 < TEXT_ROWS {
+                (column + 1, row)
+            } else {
+                (0, (row + 1) % TEXT_COLUMNS)
+            };
+        } else {
+            self.send_array_data(&glyph[..])?;
+        }
+        let (column, row) = self.cursor();
+        Ok(TextCursor {
+            column,
+            row,
+            wraps: u16::from((column, row) != (before.0 + 1, before.1)),
+        })
+    }
+
+    /// Packs an image into the scratch buffer for display RAM, laid out for the
+    /// current orientation
+    fn pack_oriented(&mut self, image: &Image, threshold: u8) -> OledResult {
+        if !self.rotation.is_portrait() {
+            let size = self.config.size();
+            return pack_image(
+                image,
+                size.width(),
+                size.height(),
+                threshold,
+                &mut self.scratch,
+            );
+        }
+        if image.len() != (OLED_HEIGHT * OLED_WIDTH) as usize {
+            return Err(OledError::InvalidDimensions);
         }
+        pack_fn(
+            |x, y| {
+                let (x, y) = physical_to_portrait(x, y);
+                image[(y * OLED_HEIGHT + x) as usize] >= threshold
+            },
+            &mut self.scratch,
+        );
         Ok(())
     }
 
+    /// Writes a string to the display as black on white, starting at the current
+    /// X, Y location. See [`put_char_inverted()`](struct.Oled.html#method.put_char_inverted).
+    pub fn put_string_inverted(&mut self, string: &str) -> Result<TextCursor, OledError> {
+        self.put_chars(string, |oled, char| oled.put_char_inverted(char))
+    }
+
+    /// Lights every pixel of the display when `enabled`, regardless of RAM contents,
+    /// eg to check the panel is alive or look for burnt-in pixels.
+    /// When disabled, the display goes back to showing RAM, which is left untouched
+    /// in the meantime. Contrast and inversion settings still apply while enabled.
+    pub fn all_on(&mut self, enabled: bool) -> OledResult {
+        if enabled {
+            self.send_command(Command::EntireDisplayOn)
+        } else {
+            self.send_command(Command::ContentFollowsRam)
+        }
+    }
+
+    /// Returns whether the display is currently inverted, ie showing black on white
+    /// (see [`Command::InverseDisplay`](enum.Command.html#variant.InverseDisplay)).
+    pub fn is_inverted(&self) -> bool {
+        self.state.inverted
+    }
+
+    /// Flashes the whole display `times` times, eg to draw attention to an alert,
+    /// by toggling between normal and inverse display every `interval`.
+    /// Blocks until done, and always leaves the display in the state it started in,
+    /// even if it was inverted to begin with or a command fails partway through.
+    #[cfg(feature = "std")]
+    pub fn flash(&mut self, times: u32, interval: Duration) -> OledResult {
+        let original = self.state.inverted;
+        let (flashed, restored) = if original {
+            (Command::NormalDisplay, Command::InverseDisplay)
+        } else {
+            (Command::InverseDisplay, Command::NormalDisplay)
+        };
+        let result = (0..times).try_for_each(|flash| {
+            if flash > 0 {
+                std::thread::sleep(interval);
+            }
+            self.send_command(flashed)?;
+            std::thread::sleep(interval);
+            self.send_command(restored)
+        });
+        if result.is_err() && self.state.inverted != original {
+            // best effort: the original error is the one worth reporting
+            let _ = self.send_command(restored);
+        }
+        result
+    }
+
+    /// Sets the pre-charge period, which can reduce ghosting and flicker on some panels.
+    /// `phase1` and `phase2` are each a number of display clocks, from 1 to 15.
+    /// The display default is 2 clocks for each; `init()` sets phase 1 to 1 and phase 2 to 15.
+    pub fn set_precharge(&mut self, phase1: u8, phase2: u8) -> OledResult {
+        self.send_instruction(Instruction::SetPrecharge(phase1, phase2))
+    }
+
+    /// Sets the V<sub>COMH</sub> deselect level, which affects contrast at low brightness.
+    /// The level occupies bits 4 through 6; the datasheet documents `0x00` (~0.65 x V<sub>CC</sub>),
+    /// `0x20` (~0.77 x V<sub>CC</sub>, the display default) and `0x30` (~0.83 x V<sub>CC</sub>).
+    /// `init()` uses `0x40`, per the NanoHat OLED sample code.
+    pub fn set_vcomh(&mut self, level: u8) -> OledResult {
+        self.send_instruction(Instruction::SetVcomhLevel(level))
+    }
+
     /// Sets the addressing mode to the supplied [`AddressingMode`](enum.AddressingMode.html).
     /// See [`AddressingMode`](enum.AddressingMode.html) for more details.
     /// Default is [`AddressingMode::Horizontal`](enum.AddressingMode.html#variant.Horizontal).
+    /// The [`Controller::Sh1106`](enum.Controller.html#variant.Sh1106) only has page addressing.
     pub fn set_addressing_mode(&mut self, mode: AddressingMode) -> OledResult {
-        self.send_command(Command::SetAddressingMode)?;
-        self.send_command(mode)?;
-        Ok(())
+        self.send_instruction(Instruction::SetAddressingMode(mode))
     }
 }
@@ -0,0 +1,371 @@
+//! Support for the display on Linux, via the `/dev/i2c-*` interface
+use i2c_linux::{Functionality, I2c, Message, ReadWrite, WriteFlags};
+use std::fs::{self, File};
+use std::io;
+use std::os::unix::io::{AsRawFd, OwnedFd};
+use std::path::{Path, PathBuf};
+
+use crate::error::OledError;
+use crate::interface::OledInterface;
+use crate::{
+    DisplayGroup, GroupLayout, Oled, COMMAND_MODE, DATA_MODE, OLED_ADDRESS, OLED_ALT_ADDRESS,
+    OLED_RAM_SIZE,
+};
+#[cfg(feature = "async")]
+use crate::{Offload, OledAsync};
+
+/// The largest payload an SMBus block write can carry, minus the control byte
+const BLOCK_DATA_SIZE: usize = 31;
+
+/// The largest payload sent in one plain I2C write: a whole frame
+const BULK_DATA_SIZE: usize = OLED_RAM_SIZE;
+
+/// Where the kernel puts I2C bus devices
+const DEV_ROOT: &str = "/dev";
+
+/// The I2C bus devices present, in order of bus number
+fn buses() -> io::Result<Vec<PathBuf>> {
+    let mut buses: Vec<(u32, PathBuf)> = fs::read_dir(DEV_ROOT)?
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let number = entry
+                .file_name()
+                .to_str()?
+                .strip_prefix("i2c-")?
+                .parse()
+                .ok()?;
+            Some((number, entry.path()))
+        })
+        .collect();
+    buses.sort();
+    Ok(buses.into_iter().map(|(_, path)| path).collect())
+}
+
+/// Whether a device answers at the bus's slave address, checked as `i2cdetect`
+/// does, with a quick write where the adapter can do one, which touches nothing
+/// on the device, or a byte read otherwise
+fn responds<D: AsRawFd>(i2c: &mut I2c<D>) -> bool {
+    let functionality = match i2c.i2c_functionality() {
+        Ok(functionality) => functionality,
+        Err(_) => return false,
+    };
+    if functionality.contains(Functionality::SMBUS_QUICK) {
+        i2c.smbus_write_quick(ReadWrite::Write).is_ok()
+    } else if functionality.contains(Functionality::SMBUS_READ_BYTE) {
+        i2c.smbus_read_byte().is_ok()
+    } else {
+        false
+    }
+}
+
+/// Whether the adapter can do plain I2C writes, rather than just SMBus commands
+fn supports_bulk<D: AsRawFd>(i2c: &I2c<D>) -> bool {
+    i2c.i2c_functionality()
+        .is_ok_and(|functionality| functionality.contains(Functionality::I2C))
+}
+
+/// An [`OledInterface`](trait.OledInterface.html) over a Linux I2C bus, talking
+/// to the display at a given slave address
+pub struct I2cInterface<D = File> {
+    /// The bus
+    i2c: I2c<D>,
+    /// The display's slave address on the bus
+    address: u16,
+    /// Whether every transaction is addressed to the display, rather than
+    /// relying on the bus's slave address
+    shared: bool,
+    /// Whether the adapter can do plain I2C writes, asked once rather than
+    /// before every transaction
+    bulk: bool,
+    /// A plain I2C write being put together: its control byte, then up to a
+    /// whole frame, kept so that each write reuses it
+    buffer: [u8; BULK_DATA_SIZE + 1],
+}
+
+impl<D: AsRawFd> I2cInterface<D> {
+    /// Wraps an I2C bus that has already been opened, setting its slave address
+    /// to `address`
+    pub fn new(mut i2c: I2c<D>, address: u16) -> Result<Self, OledError> {
+        i2c.smbus_set_slave_address(address, false)?;
+        Ok(I2cInterface::with_parts(i2c, address, false))
+    }
+
+    /// Wraps an I2C bus that other code also talks to other devices on, eg
+    /// sensors. Rather than setting the bus's slave address once, every
+    /// transaction is addressed to the display at `address`: as a plain I2C
+    /// transfer where the adapter supports it, or by setting the slave address
+    /// again before each SMBus write otherwise.
+    pub fn shared(i2c: I2c<D>, address: u16) -> Self {
+        I2cInterface::with_parts(i2c, address, true)
+    }
+
+    /// Wraps `i2c`, finding out what the adapter can do
+    fn with_parts(i2c: I2c<D>, address: u16, shared: bool) -> Self {
+        let bulk = supports_bulk(&i2c);
+        I2cInterface {
+            i2c,
+            address,
+            shared,
+            bulk,
+            buffer: [0; BULK_DATA_SIZE + 1],
+        }
+    }
+
+    /// The display's slave address
+    pub fn address(&self) -> u16 {
+        self.address
+    }
+
+    /// Consumes the interface, returning the I2C bus
+    pub fn into_i2c(self) -> I2c<D> {
+        self.i2c
+    }
+}
+
+impl<D: AsRawFd> I2cInterface<D> {
+    /// Sends `bytes` in a single plain I2C write, addressed to the display
+    fn transfer(&mut self, bytes: &[u8]) -> Result<(), OledError> {
+        self.i2c.i2c_transfer(&mut [Message::Write {
+            address: self.address,
+            data: bytes,
+            flags: WriteFlags::default(),
+        }])?;
+        Ok(())
+    }
+
+    /// Sends `mode`, then `bytes`, in a single plain I2C write, as for
+    /// [`transfer()`](#method.transfer), put together in the interface's buffer
+    fn transfer_mode(&mut self, mode: u8, bytes: &[u8]) -> Result<(), OledError> {
+        self.buffer[0] = mode;
+        self.buffer[1..=bytes.len()].copy_from_slice(bytes);
+        self.i2c.i2c_transfer(&mut [Message::Write {
+            address: self.address,
+            data: &self.buffer[..=bytes.len()],
+            flags: WriteFlags::default(),
+        }])?;
+        Ok(())
+    }
+
+    /// Points the bus at the display before SMBus writes, if other code may
+    /// have pointed it elsewhere
+    fn claim(&mut self) -> Result<(), OledError> {
+        if self.shared {
+            self.i2c.smbus_set_slave_address(self.address, false)?;
+        }
+        Ok(())
+    }
+}
+
+impl<D: AsRawFd> OledInterface for I2cInterface<D> {
+    fn send_command(&mut self, command: u8) -> Result<(), OledError> {
+        if self.shared && self.bulk {
+            return self.transfer(&[COMMAND_MODE, command]);
+        }
+        self.claim()?;
+        self.i2c.i2c_write_block_data(COMMAND_MODE, &[command])?;
+        Ok(())
+    }
+
+    /// Where the adapter supports plain I2C, the commands go in a single transfer,
+    /// after one byte to set command mode. Otherwise, they go in SMBus block
+    /// writes of up to 31 each.
+    fn send_commands(&mut self, commands: &[u8]) -> Result<(), OledError> {
+        if !self.bulk {
+            self.claim()?;
+            for chunk in commands.chunks(BLOCK_DATA_SIZE) {
+                self.i2c.i2c_write_block_data(COMMAND_MODE, chunk)?;
+            }
+            return Ok(());
+        }
+        for chunk in commands.chunks(BULK_DATA_SIZE) {
+            self.transfer_mode(COMMAND_MODE, chunk)?;
+        }
+        Ok(())
+    }
+
+    /// Where the adapter supports plain I2C, data is written in a single transfer of
+    /// up to a whole frame (plus a byte to set data mode), addressed to the
+    /// display's slave address. Otherwise, it falls back to SMBus block writes of
+    /// 31 bytes each.
+    fn send_data(&mut self, data: &[u8]) -> Result<(), OledError> {
+        if !self.bulk {
+            self.claim()?;
+            for chunk in data.chunks(BLOCK_DATA_SIZE) {
+                self.i2c.i2c_write_block_data(DATA_MODE, chunk)?;
+            }
+            return Ok(());
+        }
+        for chunk in data.chunks(BULK_DATA_SIZE) {
+            self.transfer_mode(DATA_MODE, chunk)?;
+        }
+        Ok(())
+    }
+
+    fn max_transfer_len(&self) -> usize {
+        if self.bulk {
+            BULK_DATA_SIZE
+        } else {
+            BLOCK_DATA_SIZE
+        }
+    }
+}
+
+impl Oled<I2cInterface> {
+    /// Opens the device from its entry in the dev filesystem, at the NanoHat
+    /// OLED's address, [`OLED_ADDRESS`](constant.OLED_ADDRESS.html).
+    /// # Example:
+    /// ```
+    /// # use nanohat_oled::Oled;
+    /// let mut oled = Oled::from_path("/dev/i2c-0");
+    /// ```
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, OledError> {
+        Self::from_path_with_address(path, OLED_ADDRESS)
+    }
+
+    /// Opens the device from its entry in the dev filesystem, at the slave
+    /// address `address`, eg [`OLED_ALT_ADDRESS`](constant.OLED_ALT_ADDRESS.html)
+    /// for modules strapped to `0x3d`.
+    /// # Example:
+    /// ```
+    /// # use nanohat_oled::{Oled, OLED_ALT_ADDRESS};
+    /// let mut oled = Oled::from_path_with_address("/dev/i2c-0", OLED_ALT_ADDRESS);
+    /// ```
+    pub fn from_path_with_address<P: AsRef<Path>>(
+        path: P,
+        address: u16,
+    ) -> Result<Self, OledError> {
+        Ok(Self::new(I2cInterface::new(
+            I2c::from_path(path)?,
+            address,
+        )?))
+    }
+
+    /// Looks for the display on each of the `/dev/i2c-*` buses in turn, in order
+    /// of bus number, at [`OLED_ADDRESS`](constant.OLED_ADDRESS.html) and then
+    /// [`OLED_ALT_ADDRESS`](constant.OLED_ALT_ADDRESS.html), and opens the first
+    /// that answers, for systems where the bus it's on varies. Returns `I2c`
+    /// with an error of kind `NotFound` if none does.
+    ///
+    /// Only whether something answers at those addresses is checked, so any
+    /// other device there is taken for the display; buses that can't be opened,
+    /// and addresses claimed by a kernel driver, are skipped.
+    /// # Example:
+    /// ```no_run
+    /// # use nanohat_oled::{Oled, OledResult};
+    /// # fn main() -> OledResult {
+    /// let mut oled = Oled::probe()?;
+    /// oled.init()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn probe() -> Result<Self, OledError> {
+        for bus in buses()? {
+            let mut i2c = match I2c::from_path(&bus) {
+                Ok(i2c) => i2c,
+                Err(_) => continue,
+            };
+            for &address in &[OLED_ADDRESS, OLED_ALT_ADDRESS] {
+                if i2c.smbus_set_slave_address(address, false).is_ok() && responds(&mut i2c) {
+                    return Ok(Self::new(I2cInterface::with_parts(i2c, address, false)));
+                }
+            }
+        }
+        Err(OledError::I2c(io::Error::new(
+            io::ErrorKind::NotFound,
+            "no display found on any I2C bus",
+        )))
+    }
+
+    /// Wraps an I2C bus that has already been opened, eg one shared with other
+    /// devices on the same adapter, or configured with custom retries or timeouts.
+    /// Unlike [`from_path()`](struct.Oled.html#method.from_path), this leaves the
+    /// slave address alone: it must already be set to the display's
+    /// ([`OLED_ADDRESS`](constant.OLED_ADDRESS.html)). For another address, wrap
+    /// the bus in an [`I2cInterface`](struct.I2cInterface.html) and pass that
+    /// to [`new()`](struct.Oled.html#method.new).
+    /// # Example:
+    /// ```no_run
+    /// # use i2c_linux::I2c;
+    /// # use nanohat_oled::{Oled, OLED_ADDRESS};
+    /// # use std::time::Duration;
+    /// # fn main() -> std::io::Result<()> {
+    /// let mut i2c = I2c::from_path("/dev/i2c-0")?;
+    /// i2c.i2c_set_timeout(Duration::from_millis(100))?;
+    /// i2c.smbus_set_slave_address(OLED_ADDRESS, false)?;
+    /// let mut oled = Oled::with_i2c(i2c);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_i2c(device: I2c<File>) -> Self {
+        Self::new(I2cInterface::with_parts(device, OLED_ADDRESS, false))
+    }
+
+    /// Wraps an I2C bus that has already been opened and is shared with other
+    /// devices, eg sensors read elsewhere in the program, talking to the display
+    /// at [`OLED_ADDRESS`](constant.OLED_ADDRESS.html) whatever the bus's slave
+    /// address has been set to since, as
+    /// [`I2cInterface::shared()`](struct.I2cInterface.html#method.shared) describes.
+    /// # Example:
+    /// ```no_run
+    /// # use i2c_linux::I2c;
+    /// # use nanohat_oled::{Oled, OledResult};
+    /// # fn main() -> OledResult {
+    /// let i2c = I2c::from_path("/dev/i2c-0")?;
+    /// let mut oled = Oled::from_i2c(i2c);
+    /// oled.init()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_i2c(device: I2c<File>) -> Self {
+        Self::new(I2cInterface::shared(device, OLED_ADDRESS))
+    }
+
+    /// Wraps an open file descriptor for an I2C bus, eg one inherited from a
+    /// parent process or passed over a socket, as
+    /// [`from_i2c()`](struct.Oled.html#method.from_i2c) does
+    pub fn from_fd(fd: OwnedFd) -> Self {
+        Self::from_i2c(I2c::new(File::from(fd)))
+    }
+
+    /// The display's I2C slave address
+    pub fn address(&self) -> u16 {
+        self.interface.address()
+    }
+
+    /// Consumes the display, returning the I2C bus it was using
+    pub fn into_i2c(self) -> I2c<File> {
+        self.into_interface().into_i2c()
+    }
+}
+
+impl DisplayGroup<I2cInterface> {
+    /// Opens two displays on the same bus, from its entry in the dev filesystem:
+    /// the first, or left, at [`OLED_ADDRESS`](constant.OLED_ADDRESS.html), and
+    /// the second at [`OLED_ALT_ADDRESS`](constant.OLED_ALT_ADDRESS.html)
+    pub fn from_path<P: AsRef<Path>>(path: P, layout: GroupLayout) -> Result<Self, OledError> {
+        let first = Oled::from_path_with_address(&path, OLED_ADDRESS)?;
+        let second = Oled::from_path_with_address(&path, OLED_ALT_ADDRESS)?;
+        Ok(Self::new([first, second], layout))
+    }
+}
+
+#[cfg(feature = "async")]
+impl OledAsync<Offload> {
+    /// Opens the device from its entry in the dev filesystem, as
+    /// [`Oled::from_path()`](struct.Oled.html#method.from_path) does, and moves it
+    /// onto a thread of its own so that writes don't block the executor.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, OledError> {
+        Self::from_path_with_address(path, OLED_ADDRESS)
+    }
+
+    /// Opens the device at the slave address `address`, as
+    /// [`Oled::from_path_with_address()`](struct.Oled.html#method.from_path_with_address)
+    /// does, and moves it onto a thread of its own.
+    pub fn from_path_with_address<P: AsRef<Path>>(
+        path: P,
+        address: u16,
+    ) -> Result<Self, OledError> {
+        let interface = I2cInterface::new(I2c::from_path(path)?, address)?;
+        Ok(Self::new(Offload::new(interface)))
+    }
+}
@@ -0,0 +1,242 @@
+//! Following a log file on the display, like `tail -f`
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::thread::sleep;
+use std::time::Duration;
+
+use crate::{Oled, OledError, OledInterface, OledResult};
+
+/// An [`OledConsole`](struct.OledConsole.html)-like view of a log file, which
+/// follows the file as it grows, as `tail -f` does, eg to show an installer's
+/// or boot script's progress on a headless device.
+///
+/// The last lines of the file are kept, 100 by default, and shown wrapped onto
+/// the display's character grid, 16x8 on the NanoHat's panel or 8x16 turned a
+/// quarter turn, with the latest at the bottom.
+/// [`poll()`](#method.poll) reads whatever has been added since it last looked,
+/// and [`follow()`](#method.follow) keeps doing so. While scrolled back with
+/// [`scroll_back()`](#method.scroll_back), the screen stays on the text being
+/// read as new lines come in.
+///
+/// A file that doesn't exist yet shows nothing until it's created, and one that
+/// shrinks, eg when the log is truncated, is read again from the start. As for
+/// [`SystemDashboard`](struct.SystemDashboard.html), a file that can't be read
+/// is taken as having nothing new.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{LogTail, Oled, OledResult};
+/// # use std::time::Duration;
+/// # fn main() -> OledResult {
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// LogTail::new(oled, "/var/log/install.log")
+///     .with_lines(50)
+///     .follow(Duration::from_millis(250))
+/// # }
+/// ```
+pub struct LogTail<I> {
+    /// The display being written to
+    oled: Oled<I>,
+    /// The file followed
+    path: PathBuf,
+    /// How far through the file has been read
+    position: u64,
+    /// The start of a line still being written, not yet ended with `\n`
+    partial: Vec<u8>,
+    /// The last lines read, oldest first
+    lines: VecDeque<String>,
+    /// Most lines kept
+    max_lines: usize,
+    /// Number of rows the screen is scrolled back from the latest text
+    view: usize,
+    /// Number of text columns on screen
+    columns: usize,
+    /// Number of text rows on screen
+    screen_rows: usize,
+}
+
+impl<I: OledInterface> LogTail<I> {
+    /// Follows the file at `path` on an already-initialized display. Nothing is
+    /// read until the first [`poll()`](#method.poll).
+    pub fn new<P: AsRef<Path>>(oled: Oled<I>, path: P) -> Self {
+        let (columns, screen_rows) = oled.text_grid();
+        LogTail {
+            oled,
+            path: path.as_ref().to_path_buf(),
+            position: 0,
+            partial: Vec::new(),
+            lines: VecDeque::new(),
+            max_lines: 100,
+            view: 0,
+            columns: usize::from(columns),
+            screen_rows: usize::from(screen_rows),
+        }
+    }
+
+    /// Keeps the last `lines` lines, and no fewer than one
+    pub fn with_lines(self, lines: usize) -> Self {
+        LogTail {
+            max_lines: lines.max(1),
+            ..self
+        }
+    }
+
+    /// The lines kept, oldest first
+    pub fn lines(&self) -> impl Iterator<Item = &str> {
+        self.lines.iter().map(String::as_str)
+    }
+
+    /// Reads what has been added to the file since it was last read, and shows
+    /// it. A line is only shown once it has been ended with `\n`.
+    ///
+    /// Returns whether any new lines were read.
+    pub fn poll(&mut self) -> Result<bool, OledError> {
+        let added = match self.read_added() {
+            Some(added) => added,
+            None => return Ok(false),
+        };
+        let mut read = false;
+        for byte in added {
+            if byte != b'\n' {
+                self.partial.push(byte);
+                continue;
+            }
+            let line = String::from_utf8_lossy(&self.partial).into_owned();
+            self.partial.clear();
+            self.push_line(line);
+            read = true;
+        }
+        if read {
+            self.redraw()?;
+        }
+        Ok(read)
+    }
+
+    /// Polls the file every `interval`, showing each line as it's added. Only
+    /// returns if writing to the display fails.
+    pub fn follow(mut self, interval: Duration) -> OledResult {
+        self.redraw()?;
+        loop {
+            self.poll()?;
+            sleep(interval);
+        }
+    }
+
+    /// Shows earlier lines, moving the screen `rows` rows further back, or as far
+    /// back as the lines kept go
+    pub fn scroll_back(&mut self, rows: usize) -> OledResult {
+        let view = self
+            .view
+            .saturating_add(rows)
+            .min(self.rows().saturating_sub(self.screen_rows));
+        self.set_view(view)
+    }
+
+    /// Shows later lines, moving the screen `rows` rows back towards the latest
+    pub fn scroll_forward(&mut self, rows: usize) -> OledResult {
+        self.set_view(self.view.saturating_sub(rows))
+    }
+
+    /// Number of rows the screen is scrolled back from the latest text,
+    /// 0 if it is showing it
+    pub fn scrollback_position(&self) -> usize {
+        self.view
+    }
+
+    /// Returns the display, eg for sending commands directly
+    pub fn oled(&mut self) -> &mut Oled<I> {
+        &mut self.oled
+    }
+
+    /// Consumes the tail, returning the display
+    pub fn into_oled(self) -> Oled<I> {
+        self.oled
+    }
+
+    /// Reads the bytes added to the file since it was last read, starting over
+    /// if it has shrunk. Returns `None` if it can't be read.
+    fn read_added(&mut self) -> Option<Vec<u8>> {
+        let mut file = File::open(&self.path).ok()?;
+        let len = file.metadata().ok()?.len();
+        if len < self.position {
+            self.position = 0;
+            self.partial.clear();
+        }
+        file.seek(SeekFrom::Start(self.position)).ok()?;
+        let mut added = Vec::new();
+        file.read_to_end(&mut added).ok()?;
+        self.position += added.len() as u64;
+        Some(added)
+    }
+
+    /// Keeps `line`, dropping the oldest if there are too many, and keeps the
+    /// screen where it is if scrolled back
+    fn push_line(&mut self, line: String) {
+        // tabs and carriage returns would show as boxes
+        let line = line.trim_end_matches('\r').replace('\t', " ");
+        if self.view > 0 {
+            self.view += wrapped_rows(&line, self.columns);
+        }
+        self.lines.push_back(line);
+        if self.lines.len() > self.max_lines {
+            self.lines.pop_front();
+        }
+        self.view = self.view.min(self.rows().saturating_sub(self.screen_rows));
+    }
+
+    /// Number of rows the lines kept take up, wrapped
+    fn rows(&self) -> usize {
+        self.lines
+            .iter()
+            .map(|line| wrapped_rows(line, self.columns))
+            .sum()
+    }
+
+    /// Scrolls the screen to `view` rows back from the latest text, redrawing it
+    /// if that moves it
+    fn set_view(&mut self, view: usize) -> OledResult {
+        if view == self.view {
+            return Ok(());
+        }
+        self.view = view;
+        self.redraw()
+    }
+
+    /// Writes every row of the screen to the display
+    fn redraw(&mut self) -> OledResult {
+        let columns = self.columns;
+        let chars: Vec<Vec<char>> = self
+            .lines
+            .iter()
+            .map(|line| line.chars().collect())
+            .collect();
+        let mut rows: Vec<&[char]> = Vec::new();
+        for line in &chars {
+            if line.is_empty() {
+                rows.push(&[]);
+            }
+            rows.extend(line.chunks(columns));
+        }
+        let end = rows.len() - self.view.min(rows.len());
+        let start = end.saturating_sub(self.screen_rows);
+        let shown = rows[start..end]
+            .iter()
+            .copied()
+            .chain(core::iter::repeat(&[][..]));
+        for (row, text) in shown.take(self.screen_rows).enumerate() {
+            self.oled.set_text_xy(0, row as u8)?;
+            for column in 0..columns {
+                self.oled
+                    .put_char(text.get(column).copied().unwrap_or(' '))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Number of rows `line` takes up, wrapped onto a character grid `columns` wide
+fn wrapped_rows(line: &str, columns: usize) -> usize {
+    line.chars().count().div_ceil(columns).max(1)
+}
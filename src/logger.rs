@@ -0,0 +1,114 @@
+//! A `log` backend that shows the latest log records on the display
+use std::sync::Mutex;
+
+use log::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
+
+use crate::{Oled, OledConsole, OledInterface};
+
+/// A [`log`](https://docs.rs/log) backend that writes each record to an
+/// [`OledConsole`](struct.OledConsole.html), so the display shows the latest
+/// ones, eg on a headless box with nothing else to show them on.
+///
+/// Each record is written on a row of its own, after the first letter of its
+/// level, as in `W disk 91% full`, and long records are wrapped. Older records
+/// scroll up and off the top. Errors writing to the display are ignored, since
+/// there is nowhere to report them. Records from this crate itself, such as the
+/// warnings about retried transfers, aren't shown, since showing them would
+/// mean logging more of them.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{Oled, OledLogger};
+/// # use log::LevelFilter;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// OledLogger::new(oled).with_level(LevelFilter::Warn).init()?;
+/// log::warn!("battery low");
+/// # Ok(())
+/// # }
+/// ```
+pub struct OledLogger<I> {
+    /// The console records are written to
+    console: Mutex<OledConsole<I>>,
+    /// The least severe level shown
+    level: LevelFilter,
+}
+
+impl<I: OledInterface> OledLogger<I> {
+    /// Creates a logger writing to an already-initialized display, showing
+    /// records of `Info` level and more severe
+    pub fn new(oled: Oled<I>) -> Self {
+        Self::from_console(OledConsole::new(oled))
+    }
+
+    /// Creates a logger writing to `console`, after whatever it already shows
+    pub fn from_console(console: OledConsole<I>) -> Self {
+        OledLogger {
+            console: Mutex::new(console),
+            level: LevelFilter::Info,
+        }
+    }
+
+    /// Shows records of `level` and more severe, instead
+    pub fn with_level(self, level: LevelFilter) -> Self {
+        OledLogger { level, ..self }
+    }
+
+    /// Consumes the logger, returning its console
+    pub fn into_console(self) -> OledConsole<I> {
+        self.console
+            .into_inner()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Installs the logger as the one the `log` macros go to, and sets the
+    /// maximum level they log at to match it.
+    ///
+    /// Returns an error if a logger has already been installed.
+    pub fn init(self) -> Result<(), SetLoggerError>
+    where
+        I: Send + 'static,
+    {
+        let level = self.level;
+        log::set_boxed_logger(Box::new(self))?;
+        log::set_max_level(level);
+        Ok(())
+    }
+}
+
+/// Returns whether a record logged to `target` comes from this crate
+fn is_own(target: &str) -> bool {
+    let name = env!("CARGO_CRATE_NAME");
+    target
+        .strip_prefix(name)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+}
+
+/// The letter each record is marked with, for its level
+fn level_letter(level: Level) -> char {
+    match level {
+        Level::Error => 'E',
+        Level::Warn => 'W',
+        Level::Info => 'I',
+        Level::Debug => 'D',
+        Level::Trace => 'T',
+    }
+}
+
+impl<I: OledInterface + Send> Log for OledLogger<I> {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level && !is_own(metadata.target())
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let text = format!("{} {}", level_letter(record.level()), record.args());
+        if let Ok(mut console) = self.console.lock() {
+            let _ = console.println(&text);
+        }
+    }
+
+    fn flush(&self) {}
+}
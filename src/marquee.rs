@@ -0,0 +1,210 @@
+//! Text too long for its space, scrolled sideways through it
+use core::time::Duration;
+
+use crate::{Font, Oled, OledError, OledInterface, TextRegion, OLED_WIDTH};
+
+/// A line of text scrolled from right to left through a
+/// [`TextRegion`](struct.TextRegion.html), for strings too long to show whole.
+/// The text moves a pixel at a time and wraps round, with a gap between its
+/// end and its start coming round again. Text that fits in the region is
+/// drawn still instead, placed as the region's alignment says.
+///
+/// Like an [`Animation`](struct.Animation.html), the marquee doesn't read a
+/// clock itself: [`tick()`](#method.tick) is told how much time has passed.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{BuiltinFont, Marquee, Oled, OledResult, TextRegion};
+/// # use std::time::Duration;
+/// # fn main() -> OledResult {
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// let font = BuiltinFont::MONOSPACE;
+/// let mut title = Marquee::new("Now playing: a song with a very long name", &font)
+///     .with_region(TextRegion::new(0, 24, 128, 8))
+///     .with_speed(40);
+/// loop {
+///     let wait = title.tick(&mut oled, Duration::from_millis(25))?;
+///     std::thread::sleep(wait.unwrap_or(Duration::from_millis(25)));
+/// }
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Marquee<'a, F: ?Sized> {
+    /// The text being scrolled
+    text: &'a str,
+    /// Font the text is drawn in
+    font: &'a F,
+    /// Where the text is drawn, and what it is clipped to
+    region: TextRegion,
+    /// Pixels the text moves each second
+    speed: u16,
+    /// Blank pixels between the end of the text and its start coming round again
+    gap: u16,
+    /// Number of times to scroll all the way through, or `None` to repeat forever
+    loops: Option<u32>,
+    /// How far the text has moved left, in pixels
+    offset: u32,
+    /// Time since the text last moved
+    elapsed: Duration,
+    /// Times scrolled all the way through so far
+    completed: u32,
+    /// Offset last drawn, if any
+    drawn: Option<u32>,
+}
+
+impl<'a, F: Font + ?Sized> Marquee<'a, F> {
+    /// Creates a marquee for `text` in `font`, across the top of the display,
+    /// moving 32 pixels a second with a 16 pixel gap, and repeating forever
+    pub fn new(text: &'a str, font: &'a F) -> Self {
+        Marquee {
+            text,
+            font,
+            region: TextRegion::new(0, 0, OLED_WIDTH, font.line_height()),
+            speed: 32,
+            gap: 16,
+            loops: None,
+            offset: 0,
+            elapsed: Duration::ZERO,
+            completed: 0,
+            drawn: None,
+        }
+    }
+
+    /// Scrolls the text through `region` instead, with its top at the top of the
+    /// region. Nothing is drawn outside it.
+    pub fn with_region(self, region: TextRegion) -> Self {
+        Marquee { region, ..self }
+    }
+
+    /// Moves the text `pixels_per_second` pixels a second; 0 holds it still
+    pub fn with_speed(self, pixels_per_second: u16) -> Self {
+        Marquee {
+            speed: pixels_per_second,
+            ..self
+        }
+    }
+
+    /// Leaves `pixels` blank pixels between the end of the text and its start
+    /// coming round again
+    pub fn with_gap(self, pixels: u16) -> Self {
+        Marquee {
+            gap: pixels,
+            ..self
+        }
+    }
+
+    /// Scrolls all the way through the text `loops` times, then stops with its
+    /// start showing again, or repeats forever if `None`
+    pub fn with_loops(self, loops: Option<u32>) -> Self {
+        Marquee { loops, ..self }
+    }
+
+    /// Whether the text is too wide for the region, and so scrolls
+    pub fn scrolls(&self) -> bool {
+        self.font.text_width(self.text) > self.region.width
+    }
+
+    /// Whether every loop has been scrolled through
+    pub fn is_finished(&self) -> bool {
+        self.loops.is_some_and(|loops| self.completed >= loops)
+    }
+
+    /// Goes back to the start of the text, so the next tick draws it and starts over
+    pub fn reset(&mut self) {
+        self.offset = 0;
+        self.elapsed = Duration::ZERO;
+        self.completed = 0;
+        self.drawn = None;
+    }
+
+    /// Whether the text is still moving
+    fn is_moving(&self) -> bool {
+        self.speed > 0 && self.scrolls() && !self.is_finished()
+    }
+
+    /// Pixels the text moves before it is back where it started
+    fn cycle(&self) -> u32 {
+        u32::from(self.font.text_width(self.text)) + u32::from(self.gap)
+    }
+
+    /// Time the text takes to move one pixel
+    fn step(&self) -> Duration {
+        Duration::from_secs(1) / u32::from(self.speed.max(1))
+    }
+
+    /// Moves the text on by `elapsed`, the time since the last tick, and redraws
+    /// it if it has moved. The first tick always draws the text, so it can be
+    /// passed `Duration::ZERO`.
+    ///
+    /// Returns how long until the text next moves, or `None` if it won't, because
+    /// it fits in the region, its speed is 0 or every loop has been scrolled.
+    pub fn tick<I: OledInterface>(
+        &mut self,
+        oled: &mut Oled<I>,
+        elapsed: Duration,
+    ) -> Result<Option<Duration>, OledError> {
+        if self.is_moving() {
+            self.elapsed += elapsed;
+            let step = self.step().as_nanos();
+            let steps = self.elapsed.as_nanos() / step;
+            self.elapsed = Duration::from_nanos((self.elapsed.as_nanos() % step) as u64);
+            let position = u128::from(self.offset) + steps;
+            let cycle = u128::from(self.cycle());
+            self.offset = (position % cycle) as u32;
+            self.completed = self
+                .completed
+                .saturating_add((position / cycle).min(u128::from(u32::MAX)) as u32);
+            if self.is_finished() {
+                self.offset = 0;
+                self.elapsed = Duration::ZERO;
+            }
+        }
+        if self.drawn != Some(self.offset) {
+            self.draw(oled)?;
+            self.drawn = Some(self.offset);
+        }
+        if self.is_moving() {
+            Ok(Some(self.step() - self.elapsed))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Draws the text at its current offset
+    fn draw<I: OledInterface>(&self, oled: &mut Oled<I>) -> Result<(), OledError> {
+        let region = self.region;
+        let (text, font) = (self.text, self.font);
+        let scrolls = self.scrolls();
+        let x = if scrolls {
+            i32::from(region.x) - self.offset as i32
+        } else {
+            i32::from(region.line_start(font.text_width(text)))
+        };
+        let cycle = self.cycle() as i32;
+        oled.draw(|frame| {
+            region.clear(frame);
+            frame.draw_text_clipped(x, region.y, text, font, &region);
+            if scrolls {
+                // the start of the text coming round again, after the gap
+                frame.draw_text_clipped(x + cycle, region.y, text, font, &region);
+            }
+        })
+    }
+
+    /// Scrolls the text from the start, sleeping between steps, and returns once
+    /// every loop has been scrolled, or straight away if the text doesn't move.
+    /// A marquee set to repeat forever only returns if drawing fails.
+    #[cfg(feature = "std")]
+    pub fn play<I: OledInterface>(&mut self, oled: &mut Oled<I>) -> Result<(), OledError> {
+        self.reset();
+        let mut last = std::time::Instant::now();
+        let mut elapsed = Duration::ZERO;
+        while let Some(wait) = self.tick(oled, elapsed)? {
+            std::thread::sleep(wait);
+            let now = std::time::Instant::now();
+            elapsed = now - last;
+            last = now;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,139 @@
+//! A list of items to choose from, with a cursor moved by buttons
+use crate::{BuiltinFont, Font, Framebuffer, Rect, Truncation, Widget};
+
+/// A [`Widget`](trait.Widget.html) listing items one to a row in the built-in
+/// font, with the selected one highlighted in inverted text, for the buttons on
+/// the NanoHat to move through and choose from. Items can be anything that
+/// gives a label, eg `&str`, or an enum of actions.
+///
+/// Labels too wide for the menu are cut short with an ellipsis, or as set by
+/// [`with_truncation()`](#method.with_truncation).
+///
+/// If there are more items than rows fit in the menu's area (8 on the whole
+/// display), they're shown a page at a time, as the selection moves onto it.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{Menu, Oled, OledResult, Rect};
+/// # enum Button { Up, Down, Select }
+/// # fn wait_for_button() -> Button { Button::Select }
+/// # fn main() -> OledResult {
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// let mut menu = Menu::new(&["Network", "Restart", "Shut down"]);
+/// loop {
+///     oled.render_widgets(&mut [(&mut menu, Rect::new(0, 0, 128, 64))])?;
+///     match wait_for_button() {
+///         Button::Up => menu.up(),
+///         Button::Down => menu.down(),
+///         Button::Select => {
+///             if menu.select() == Some(&"Shut down") {
+///                 break;
+///             }
+///         }
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Menu<'a, T> {
+    /// Items, from top to bottom
+    items: &'a [T],
+    /// Index of the selected item
+    selected: usize,
+    /// How labels too wide for the menu are cut short
+    truncation: Truncation,
+    /// Whether anything has changed since the menu was last rendered
+    changed: bool,
+}
+
+impl<'a, T: AsRef<str>> Menu<'a, T> {
+    /// Creates a menu of `items`, from top to bottom, with the first selected
+    pub fn new(items: &'a [T]) -> Self {
+        Menu {
+            items,
+            selected: 0,
+            truncation: Truncation::Ellipsis,
+            changed: true,
+        }
+    }
+
+    /// Selects the item at `index`, or the last if there aren't that many
+    pub fn with_selected(mut self, index: usize) -> Self {
+        self.set_selected(index);
+        self
+    }
+
+    /// Cuts labels too wide for the menu short by `truncation`, rather than
+    /// with an ellipsis
+    pub fn with_truncation(self, truncation: Truncation) -> Self {
+        Menu { truncation, ..self }
+    }
+
+    /// Moves the selection up an item, or round to the last from the first
+    pub fn up(&mut self) {
+        let index = match self.selected {
+            0 => self.items.len().saturating_sub(1),
+            index => index - 1,
+        };
+        self.set_selected(index);
+    }
+
+    /// Moves the selection down an item, or round to the first from the last
+    pub fn down(&mut self) {
+        let index = match self.selected + 1 {
+            next if next >= self.items.len() => 0,
+            next => next,
+        };
+        self.set_selected(index);
+    }
+
+    /// The selected item, to act on as chosen, or `None` if the menu is empty
+    pub fn select(&self) -> Option<&'a T> {
+        self.items.get(self.selected)
+    }
+
+    /// Index of the selected item
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Selects the item at `index`, or the last if there aren't that many
+    pub fn set_selected(&mut self, index: usize) {
+        let index = index.min(self.items.len().saturating_sub(1));
+        self.changed |= index != self.selected;
+        self.selected = index;
+    }
+
+    /// Changes the items listed, keeping the selection where it is if there
+    /// are still enough of them
+    pub fn set_items(&mut self, items: &'a [T]) {
+        self.items = items;
+        self.changed = true;
+        self.set_selected(self.selected);
+    }
+}
+
+impl<T: AsRef<str>> Widget for Menu<'_, T> {
+    fn render(&mut self, frame: &mut Framebuffer, area: Rect) {
+        frame.fill_rect(area.x, area.y, area.width, area.height, false);
+        let font = BuiltinFont::MONOSPACE;
+        let row_height = font.line_height();
+        let rows = usize::from(area.height / row_height).max(1);
+        let first = self.selected / rows * rows;
+        let mut rest = area;
+        for (index, item) in self.items.iter().enumerate().skip(first).take(rows) {
+            let (row, below) = rest.split_top(row_height);
+            rest = below;
+            let selected = index == self.selected;
+            frame.fill_rect(row.x, row.y, row.width, row.height, selected);
+            let (x, y) = (i32::from(row.x), i32::from(row.y));
+            frame.flip_text_fit(x, y, item.as_ref(), &font, row, self.truncation);
+        }
+        self.changed = false;
+    }
+
+    fn needs_render(&self) -> bool {
+        self.changed
+    }
+}
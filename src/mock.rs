@@ -0,0 +1,237 @@
+//! Interfaces that record what is sent to them, for testing code that draws to the display
+use alloc::vec::Vec;
+
+use crate::state::DisplayState;
+use crate::{AddressingMode, Framebuffer, OledError, OledInterface, OLED_RAM_SIZE};
+
+/// A byte sent to a [`MockInterface`](struct.MockInterface.html), tagged with its destination
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sent {
+    /// A byte for the command parser
+    Command(u8),
+    /// A byte for display RAM
+    Data(u8),
+}
+
+/// An [`OledInterface`](trait.OledInterface.html) with no display behind it, which
+/// records every byte sent to it, so drawing code can be tested off the device.
+///
+/// Combined with [`Oled::framebuffer()`](struct.Oled.html#method.framebuffer), which
+/// shows what would be on the display, this covers both what was drawn and how
+/// it was sent.
+/// # Example:
+/// ```
+/// use nanohat_oled::{MockInterface, Oled, Sent};
+///
+/// let mut mock = MockInterface::new();
+/// let mut oled = Oled::new(&mut mock);
+/// oled.set_text_xy(2, 1).unwrap();
+/// oled.put_char('-').unwrap();
+/// assert_eq!(mock.commands(), [0xb1, 0x00, 0x11]);
+/// assert_eq!(mock.data(), [0x00, 0x08, 0x08, 0x08, 0x08, 0x08, 0x00, 0x00]);
+/// assert_eq!(mock.sent()[0], Sent::Command(0xb1));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MockInterface {
+    /// Every byte sent so far, in order
+    sent: Vec<Sent>,
+    /// Largest transfer reported to the driver
+    max_transfer_len: Option<usize>,
+}
+
+impl MockInterface {
+    /// Creates a mock that has recorded nothing, with no limit on transfer length
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the transfer length reported by
+    /// [`max_transfer_len()`](trait.OledInterface.html#method.max_transfer_len),
+    /// eg 31 to match the Linux I2C interface on an SMBus-only adapter
+    pub fn set_max_transfer_len(&mut self, len: usize) {
+        self.max_transfer_len = Some(len);
+    }
+
+    /// Every byte sent so far, in order
+    pub fn sent(&self) -> &[Sent] {
+        &self.sent
+    }
+
+    /// Command bytes sent so far, in order
+    pub fn commands(&self) -> Vec<u8> {
+        self.sent
+            .iter()
+            .filter_map(|sent| match sent {
+                Sent::Command(byte) => Some(*byte),
+                Sent::Data(_) => None,
+            })
+            .collect()
+    }
+
+    /// Data bytes sent so far, in order
+    pub fn data(&self) -> Vec<u8> {
+        self.sent
+            .iter()
+            .filter_map(|sent| match sent {
+                Sent::Data(byte) => Some(*byte),
+                Sent::Command(_) => None,
+            })
+            .collect()
+    }
+
+    /// Forgets everything sent so far
+    pub fn clear(&mut self) {
+        self.sent.clear();
+    }
+}
+
+impl OledInterface for MockInterface {
+    fn send_command(&mut self, command: u8) -> Result<(), OledError> {
+        self.sent.push(Sent::Command(command));
+        Ok(())
+    }
+
+    fn send_data(&mut self, data: &[u8]) -> Result<(), OledError> {
+        self.sent.extend(data.iter().map(|byte| Sent::Data(*byte)));
+        Ok(())
+    }
+
+    fn max_transfer_len(&self) -> usize {
+        self.max_transfer_len.unwrap_or(usize::MAX)
+    }
+}
+
+/// An [`OledInterface`](trait.OledInterface.html) with no display behind it,
+/// which records every byte sent to it like a
+/// [`MockInterface`](struct.MockInterface.html), and also decodes them as the
+/// controller would, into a virtual copy of its RAM and settings.
+///
+/// Where [`Oled::framebuffer()`](struct.Oled.html#method.framebuffer) is the
+/// driver's own idea of what it has drawn, this is worked out from nothing but
+/// the bytes on the bus, so tests can check the protocol gets pixels where
+/// they're meant to be.
+/// # Example:
+/// ```
+/// use nanohat_oled::{CaptureDevice, Oled};
+///
+/// let mut device = CaptureDevice::new();
+/// let mut oled = Oled::new(&mut device);
+/// oled.put_string("Hi").unwrap();
+/// // the uprights of the H, and the dot of the i
+/// assert!(device.get_pixel(1, 0) && device.get_pixel(5, 6));
+/// assert!(device.get_pixel(10, 0) && !device.get_pixel(10, 1));
+/// // the display stays dark until it's turned on
+/// assert!(!device.is_lit(1, 0));
+/// ```
+#[derive(Clone, Debug)]
+pub struct CaptureDevice {
+    /// Every byte sent so far
+    mock: MockInterface,
+    /// The controller, as commanded so far
+    state: DisplayState,
+}
+
+impl Default for CaptureDevice {
+    fn default() -> Self {
+        CaptureDevice::new()
+    }
+}
+
+impl CaptureDevice {
+    /// Creates a device that has recorded nothing, in the controller's reset
+    /// state, with no limit on transfer length
+    pub fn new() -> Self {
+        CaptureDevice {
+            mock: MockInterface::new(),
+            state: DisplayState::default(),
+        }
+    }
+
+    /// Sets the transfer length reported by
+    /// [`max_transfer_len()`](trait.OledInterface.html#method.max_transfer_len),
+    /// as for [`MockInterface`](struct.MockInterface.html#method.set_max_transfer_len)
+    pub fn set_max_transfer_len(&mut self, len: usize) {
+        self.mock.set_max_transfer_len(len);
+    }
+
+    /// Every byte sent so far, in order
+    pub fn sent(&self) -> &[Sent] {
+        self.mock.sent()
+    }
+
+    /// Command bytes sent so far, in order
+    pub fn commands(&self) -> Vec<u8> {
+        self.mock.commands()
+    }
+
+    /// Data bytes sent so far, in order
+    pub fn data(&self) -> Vec<u8> {
+        self.mock.data()
+    }
+
+    /// Forgets every byte sent so far, leaving the RAM and settings as they are
+    pub fn clear(&mut self) {
+        self.mock.clear();
+    }
+
+    /// Puts the virtual controller back in its reset state, with RAM cleared, as
+    /// a real one is after losing power, eg to test recovering from that
+    pub fn reset(&mut self) {
+        self.state = DisplayState::default();
+    }
+
+    /// The virtual display RAM, in the layout described for
+    /// [`Oled::send_data()`](struct.Oled.html#method.send_data)
+    pub fn ram(&self) -> &[u8; OLED_RAM_SIZE] {
+        &self.state.buffer
+    }
+
+    /// The virtual display RAM as a framebuffer, eg to compare with one drawn
+    /// in a test, or save with [`to_pbm()`](struct.Framebuffer.html#method.to_pbm)
+    pub fn framebuffer(&self) -> Framebuffer {
+        Framebuffer::from_bytes(self.state.buffer)
+    }
+
+    /// Whether the pixel at `x`, `y` is set in RAM, as
+    /// [`Framebuffer::get_pixel()`](struct.Framebuffer.html#method.get_pixel)
+    /// would say of it. Pixels outside the display are always off.
+    pub fn get_pixel(&self, x: u16, y: u16) -> bool {
+        self.framebuffer().get_pixel(x, y)
+    }
+
+    /// Whether the panel lights the pixel at `x`, `y`, counting from the top
+    /// left of the NanoHat the right way up. Unlike
+    /// [`get_pixel()`](#method.get_pixel), this follows the commands that
+    /// change how RAM is shown, so nothing is lit until the display is turned
+    /// on, everything is with [`Oled::all_on()`](struct.Oled.html#method.all_on),
+    /// and inverting and turning the display a half turn show.
+    pub fn is_lit(&self, x: u16, y: u16) -> bool {
+        self.state.lit(x, y)
+    }
+
+    /// The column and page the RAM pointer is on, where the next data byte goes
+    pub fn pointer(&self) -> (u8, u8) {
+        self.state.pointer()
+    }
+
+    /// The addressing mode the controller is in
+    pub fn addressing_mode(&self) -> AddressingMode {
+        self.state.mode
+    }
+}
+
+impl OledInterface for CaptureDevice {
+    fn send_command(&mut self, command: u8) -> Result<(), OledError> {
+        self.state.command(command);
+        self.mock.send_command(command)
+    }
+
+    fn send_data(&mut self, data: &[u8]) -> Result<(), OledError> {
+        self.state.write(data);
+        self.mock.send_data(data)
+    }
+
+    fn max_transfer_len(&self) -> usize {
+        self.mock.max_transfer_len()
+    }
+}
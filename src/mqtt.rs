@@ -0,0 +1,312 @@
+//! Showing what an MQTT broker publishes, eg from Home Assistant
+use std::io::{self, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+use log::warn;
+
+use crate::{Oled, OledError, OledInterface, OledResult};
+
+/// Gray level from which pixels of a grayscale image are lit
+const IMAGE_THRESHOLD: u8 = 0x80;
+
+/// Largest packet taken from the broker, enough for a grayscale image
+const MAX_PACKET_LEN: usize = 64 * 1024;
+
+/// MQTT control packet types, in the top four bits of a packet's first byte
+const CONNECT: u8 = 0x10;
+const CONNACK: u8 = 0x20;
+const PUBLISH: u8 = 0x30;
+const PUBACK: u8 = 0x40;
+const SUBSCRIBE: u8 = 0x82;
+const PINGREQ: u8 = 0xc0;
+
+/// An MQTT 3.1.1 packet being put together
+struct Packet(Vec<u8>);
+
+impl Packet {
+    /// Appends a string, prefixed with its length
+    fn string(mut self, string: &str) -> Self {
+        self.0
+            .extend_from_slice(&(string.len() as u16).to_be_bytes());
+        self.0.extend_from_slice(string.as_bytes());
+        self
+    }
+
+    /// Appends a two-byte number
+    fn u16(mut self, value: u16) -> Self {
+        self.0.extend_from_slice(&value.to_be_bytes());
+        self
+    }
+
+    /// Appends a byte
+    fn u8(mut self, value: u8) -> Self {
+        self.0.push(value);
+        self
+    }
+
+    /// Sends the packet, with a fixed header of `kind` and its length
+    fn send<W: Write>(self, writer: &mut W, kind: u8) -> io::Result<()> {
+        let mut header = vec![kind];
+        let mut len = self.0.len();
+        loop {
+            let byte = (len % 128) as u8;
+            len /= 128;
+            header.push(if len > 0 { byte | 0x80 } else { byte });
+            if len == 0 {
+                break;
+            }
+        }
+        writer.write_all(&header)?;
+        writer.write_all(&self.0)
+    }
+}
+
+/// Reads the rest of a packet after its first byte, returning its body
+fn read_body<R: Read>(reader: &mut R) -> io::Result<Vec<u8>> {
+    let (mut len, mut shift) = (0usize, 0);
+    loop {
+        let mut byte = [0];
+        reader.read_exact(&mut byte)?;
+        len |= usize::from(byte[0] & 0x7f) << shift;
+        shift += 7;
+        if byte[0] & 0x80 == 0 {
+            break;
+        }
+        if shift > 21 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "malformed MQTT packet",
+            ));
+        }
+    }
+    if len > MAX_PACKET_LEN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "MQTT packet too long",
+        ));
+    }
+    let mut body = vec![0; len];
+    reader.read_exact(&mut body)?;
+    Ok(body)
+}
+
+/// Whether a read gave up because nothing came before its timeout
+fn is_timeout(err: &io::Error) -> bool {
+    matches!(
+        err.kind(),
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+    )
+}
+
+/// Splits a PUBLISH packet's body into its topic, packet identifier if it needs
+/// acknowledging, and payload
+fn parse_publish(flags: u8, body: &[u8]) -> Option<(&str, Option<u16>, &[u8])> {
+    let topic_len = usize::from(u16::from_be_bytes([*body.first()?, *body.get(1)?]));
+    let topic = core::str::from_utf8(body.get(2..2 + topic_len)?).ok()?;
+    let rest = &body[2 + topic_len..];
+    if flags & 0x06 == 0 {
+        return Some((topic, None, rest));
+    }
+    let id = u16::from_be_bytes([*rest.first()?, *rest.get(1)?]);
+    Some((topic, Some(id), &rest[2..]))
+}
+
+/// A subscriber to an MQTT broker that shows what's published under a topic
+/// prefix, `display` by default, so that Home Assistant, Node-RED or a script
+/// with `mosquitto_pub` can push content to the display:
+/// - `display/line/0` to `display/line/7` write their text on that text row,
+///   replacing what was there, or up to `display/line/15` turned a quarter
+///   turn and `display/line/3` on a 128x32 panel
+/// - `display/image` shows a whole-screen image, packed one bit per pixel by row
+///   as for [`ImageFormat::RowMajor`](enum.ImageFormat.html#variant.RowMajor),
+///   or one byte per pixel, lit from gray level 128 up
+/// - `display/contrast` sets the contrast, from 0 to 255
+/// - `display/clear` clears the display, whatever is published
+///
+/// Messages under other topics, or that can't be shown, eg a line with no
+/// number, are logged as warnings and skipped. The subscriber speaks just enough
+/// MQTT 3.1.1 to subscribe at QoS 0, without TLS or a password, as on a broker
+/// on the local network.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{MqttDisplay, Oled, OledResult};
+/// # fn main() -> OledResult {
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// let mut display = MqttDisplay::new(oled).with_prefix("nanopi/oled");
+/// // then, eg: mosquitto_pub -t nanopi/oled/line/0 -m "Door open"
+/// loop {
+///     display.run("homeassistant.local:1883")?;
+///     std::thread::sleep(std::time::Duration::from_secs(5));
+/// }
+/// # }
+/// ```
+pub struct MqttDisplay<I> {
+    /// The display being driven
+    oled: Oled<I>,
+    /// What topics start with, without a trailing `/`
+    prefix: String,
+    /// Client identifier given to the broker
+    client_id: String,
+    /// Longest the display goes without sending before it pings the broker
+    keep_alive: Duration,
+}
+
+impl<I: OledInterface> MqttDisplay<I> {
+    /// Creates a subscriber for an already-initialized display, listening under
+    /// `display` as client `nanohat-oled`, and pinging the broker each minute
+    pub fn new(oled: Oled<I>) -> Self {
+        MqttDisplay {
+            oled,
+            prefix: "display".into(),
+            client_id: "nanohat-oled".into(),
+            keep_alive: Duration::from_secs(60),
+        }
+    }
+
+    /// Listens to topics under `prefix` rather than under `display`
+    pub fn with_prefix(self, prefix: &str) -> Self {
+        MqttDisplay {
+            prefix: prefix.trim_end_matches('/').into(),
+            ..self
+        }
+    }
+
+    /// Identifies to the broker as `client_id`, eg to run several displays
+    /// against the same broker, which only allows one connection for each
+    pub fn with_client_id(self, client_id: &str) -> Self {
+        MqttDisplay {
+            client_id: client_id.into(),
+            ..self
+        }
+    }
+
+    /// Pings the broker when the display hasn't sent it anything for
+    /// `keep_alive`, rounded down to whole seconds, so it knows the display is
+    /// still there. Zero turns pinging off.
+    pub fn with_keep_alive(self, keep_alive: Duration) -> Self {
+        MqttDisplay { keep_alive, ..self }
+    }
+
+    /// Shows a message published to `topic`. Returns `InvalidArgument` if the
+    /// topic isn't one shown, or the payload can't be, or whatever error the
+    /// display gives.
+    pub fn handle_message(&mut self, topic: &str, payload: &[u8]) -> OledResult {
+        let element = topic
+            .strip_prefix(self.prefix.as_str())
+            .and_then(|topic| topic.strip_prefix('/'))
+            .ok_or(OledError::InvalidArgument("topic isn't under the prefix"))?;
+        let text = String::from_utf8_lossy(payload);
+        match element {
+            "image" => self.oled.draw_image_by_len(payload, IMAGE_THRESHOLD),
+            "contrast" => {
+                let level: u8 = text
+                    .trim()
+                    .parse()
+                    .map_err(|_| OledError::InvalidArgument("contrast must be from 0 to 255"))?;
+                self.oled.set_contrast(level)
+            }
+            "clear" => self.oled.clear_display(),
+            _ => {
+                let (columns, rows) = self.oled.text_grid();
+                let row = element
+                    .strip_prefix("line/")
+                    .and_then(|row| row.parse::<u8>().ok())
+                    .filter(|row| *row < rows)
+                    .ok_or(OledError::InvalidArgument("no screen element for topic"))?;
+                self.oled.set_text_xy(0, row)?;
+                let padded = text
+                    .chars()
+                    .chain(core::iter::repeat(' '))
+                    .take(usize::from(columns));
+                for char in padded {
+                    self.oled.put_char(char)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Connects to the broker at `broker`, subscribes to the topics shown, and
+    /// shows each message published to them. Returns once the broker closes the
+    /// connection, eg to be called again to reconnect, or on an error, with
+    /// `I2c` for connection problems as for other I/O.
+    pub fn run<A: ToSocketAddrs>(&mut self, broker: A) -> OledResult {
+        let mut stream = TcpStream::connect(broker)?;
+        let keep_alive = self.keep_alive.as_secs().min(u64::from(u16::MAX)) as u16;
+        // a clean session, as the display only shows what's current
+        Packet(Vec::new())
+            .string("MQTT")
+            .u8(4)
+            .u8(0x02)
+            .u16(keep_alive)
+            .string(&self.client_id)
+            .send(&mut stream, CONNECT)?;
+        let mut kind = [0];
+        stream.read_exact(&mut kind)?;
+        let connack = read_body(&mut stream)?;
+        if kind[0] != CONNACK || connack.get(1) != Some(&0) {
+            return Err(io::Error::new(
+                io::ErrorKind::ConnectionRefused,
+                "MQTT broker refused the connection",
+            )
+            .into());
+        }
+        Packet(Vec::new())
+            .u16(1)
+            .string(&format!("{}/#", self.prefix))
+            .u8(0)
+            .send(&mut stream, SUBSCRIBE)?;
+        let keep_alive = Duration::from_secs(u64::from(keep_alive));
+        // the broker needs to hear from the display within each keep-alive period
+        let mut last_sent = Instant::now();
+        loop {
+            if !keep_alive.is_zero() {
+                if last_sent.elapsed() >= keep_alive {
+                    Packet(Vec::new()).send(&mut stream, PINGREQ)?;
+                    last_sent = Instant::now();
+                }
+                let wait = keep_alive.saturating_sub(last_sent.elapsed());
+                stream.set_read_timeout(Some(wait.max(Duration::from_millis(1))))?;
+            }
+            match stream.read(&mut kind) {
+                Ok(0) => return Ok(()),
+                Ok(_) => {}
+                Err(err) if is_timeout(&err) => continue,
+                Err(err) => return Err(err.into()),
+            }
+            let body = read_body(&mut stream)?;
+            if kind[0] & 0xf0 != PUBLISH {
+                // acknowledgements of the subscription and pings
+                continue;
+            }
+            let (topic, id, payload) = parse_publish(kind[0], &body).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, "malformed MQTT message")
+            })?;
+            match self.handle_message(topic, payload) {
+                Ok(()) => {}
+                Err(err @ OledError::InvalidArgument(_))
+                | Err(err @ OledError::InvalidDimensions)
+                | Err(err @ OledError::OutOfBounds) => {
+                    warn!("Skipping message on {}: {}", topic, err);
+                }
+                Err(err) => return Err(err),
+            }
+            if let Some(id) = id {
+                Packet(Vec::new()).u16(id).send(&mut stream, PUBACK)?;
+                last_sent = Instant::now();
+            }
+        }
+    }
+
+    /// Returns the display, eg for sending commands directly
+    pub fn oled(&mut self) -> &mut Oled<I> {
+        &mut self.oled
+    }
+
+    /// Consumes the subscriber, returning the display
+    pub fn into_oled(self) -> Oled<I> {
+        self.oled
+    }
+}
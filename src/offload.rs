@@ -0,0 +1,141 @@
+//! Runs a blocking interface on its own thread, for use with async executors
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::mpsc::{channel, Sender};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+
+use crate::{AsyncOledInterface, OledError, OledInterface, OledResult};
+
+/// Bytes for the worker thread to send
+enum Request {
+    /// A byte for the command parser
+    Command(u8),
+    /// Bytes for display RAM
+    Data(Vec<u8>),
+}
+
+/// Progress of a request, shared between the worker thread and the waiting task
+#[derive(Default)]
+struct ReplyState {
+    /// The result, once the request has been sent
+    result: Option<OledResult>,
+    /// The task to wake when the result arrives
+    waker: Option<Waker>,
+}
+
+/// Where the worker thread leaves the outcome of a request
+#[derive(Default)]
+struct Reply {
+    /// Progress so far
+    state: Mutex<ReplyState>,
+}
+
+impl Reply {
+    /// Records the result of a request, and wakes the task waiting on it
+    fn complete(&self, result: OledResult) {
+        let mut state = self.state.lock().unwrap_or_else(|err| err.into_inner());
+        state.result = Some(result);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// The worker thread's side of a reply. If the interface panics before the
+/// request completes, the waiting task gets `Interface` rather than waiting forever.
+struct Responder(Option<Arc<Reply>>);
+
+impl Responder {
+    /// Records the result of the request
+    fn finish(mut self, result: OledResult) {
+        if let Some(reply) = self.0.take() {
+            reply.complete(result);
+        }
+    }
+}
+
+impl Drop for Responder {
+    fn drop(&mut self) {
+        if let Some(reply) = self.0.take() {
+            reply.complete(Err(OledError::Interface));
+        }
+    }
+}
+
+/// Waits for the worker thread to finish a request
+struct ReplyFuture(Arc<Reply>);
+
+impl Future for ReplyFuture {
+    type Output = OledResult;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.0.state.lock().unwrap_or_else(|err| err.into_inner());
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// An [`AsyncOledInterface`](trait.AsyncOledInterface.html) that hands every transfer
+/// to a blocking [`OledInterface`](trait.OledInterface.html) running on a thread of
+/// its own, so that slow I2C writes don't hold up the executor.
+///
+/// This works with any executor, since the thread wakes waiting tasks itself.
+/// The thread exits once the `Offload` is dropped and any transfers already
+/// handed to it are sent.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{OledAsync, OledResult};
+/// # async fn run() -> OledResult {
+/// let mut oled = OledAsync::from_path("/dev/i2c-0")?;
+/// oled.init().await?;
+/// oled.put_string("Hello, world!").await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct Offload {
+    /// Requests for the worker thread, each with its reply
+    requests: Sender<(Request, Arc<Reply>)>,
+}
+
+impl Offload {
+    /// Moves `interface` onto a new thread, and returns a handle to send through it
+    pub fn new<I: OledInterface + Send + 'static>(mut interface: I) -> Self {
+        let (requests, received) = channel::<(Request, Arc<Reply>)>();
+        thread::spawn(move || {
+            for (request, reply) in received {
+                let responder = Responder(Some(reply));
+                responder.finish(match request {
+                    Request::Command(command) => interface.send_command(command),
+                    Request::Data(data) => interface.send_data(&data),
+                });
+            }
+        });
+        Self { requests }
+    }
+
+    /// Hands a request to the worker thread, and waits for it to be sent
+    async fn send(&self, request: Request) -> OledResult {
+        let reply = Arc::new(Reply::default());
+        self.requests
+            .send((request, reply.clone()))
+            .map_err(|_| OledError::Interface)?;
+        ReplyFuture(reply).await
+    }
+}
+
+impl AsyncOledInterface for Offload {
+    async fn send_command(&mut self, command: u8) -> OledResult {
+        self.send(Request::Command(command)).await
+    }
+
+    async fn send_data(&mut self, data: &[u8]) -> OledResult {
+        self.send(Request::Data(data.to_vec())).await
+    }
+}
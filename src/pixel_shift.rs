@@ -0,0 +1,76 @@
+//! Protecting the panel from burn-in by moving the picture a pixel now and then
+use core::time::Duration;
+
+use crate::{Oled, OledInterface, OledResult, OLED_HEIGHT};
+
+/// Command to set the RAM row shown at the top of the display, ORed with the row
+const SET_START_LINE: u8 = 0x40;
+
+/// Rows the picture is moved down by, in turn. Each edge of what's drawn spends
+/// half the time on its own row, and a quarter on each of its neighbours.
+const ORBIT: [i8; 4] = [0, 1, 0, -1];
+
+/// When the picture next moves, and where it is
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct PixelShift {
+    /// Time between moves, if the picture moves at all
+    interval: Option<Duration>,
+    /// Time since the last move
+    elapsed: Duration,
+    /// Position in the orbit
+    step: usize,
+}
+
+impl<I: OledInterface> Oled<I> {
+    /// Moves the whole picture a pixel up or down every `interval`, a few
+    /// minutes being plenty, so that a dashboard shown all day doesn't etch
+    /// its lines into the panel. Pass `None` to stop, which puts the picture
+    /// back where it was drawn.
+    ///
+    /// The moving is done by the controller, by changing the RAM row it shows
+    /// at the top of the display, so nothing has to be drawn again and drawing
+    /// is unaffected: coordinates, text rows and
+    /// [`framebuffer()`](struct.Oled.html#method.framebuffer) all stay as drawn.
+    /// The row moved off one edge of the panel shows at the other, so the
+    /// outermost rows are best left blank.
+    ///
+    /// The time passing is counted by
+    /// [`tick_pixel_shift()`](struct.Oled.html#method.tick_pixel_shift), as an
+    /// [`App`](struct.App.html) does.
+    pub fn set_pixel_shift(&mut self, interval: Option<Duration>) -> OledResult {
+        self.pixel_shift = PixelShift {
+            interval,
+            ..PixelShift::default()
+        };
+        self.send_command(SET_START_LINE)
+    }
+
+    /// Counts `elapsed` towards the interval set by
+    /// [`set_pixel_shift()`](struct.Oled.html#method.set_pixel_shift), and moves
+    /// the picture on if it has run out
+    pub fn tick_pixel_shift(&mut self, elapsed: Duration) -> OledResult {
+        let Some(interval) = self.pixel_shift.interval else {
+            return Ok(());
+        };
+        self.pixel_shift.elapsed += elapsed;
+        if self.pixel_shift.elapsed < interval {
+            return Ok(());
+        }
+        self.pixel_shift.elapsed = Duration::ZERO;
+        self.pixel_shift.step = (self.pixel_shift.step + 1) % ORBIT.len();
+        self.send_start_line()
+    }
+
+    /// Sends the start line for where the picture currently is
+    pub(crate) fn send_start_line(&mut self) -> OledResult {
+        // showing a later row at the top moves the picture up
+        let line = (OLED_HEIGHT as i16 - i16::from(self.pixel_shift())) % OLED_HEIGHT as i16;
+        self.send_command(SET_START_LINE | line as u8)
+    }
+
+    /// Returns how many rows the picture is currently moved down by, as set by
+    /// [`set_pixel_shift()`](struct.Oled.html#method.set_pixel_shift), from -1 to 1
+    pub fn pixel_shift(&self) -> i8 {
+        ORBIT[self.pixel_shift.step]
+    }
+}
@@ -0,0 +1,388 @@
+//! Graphs of series of values, eg CPU load over time
+use core::convert::TryFrom;
+use core::iter::once;
+
+use crate::widgets::Bounds;
+use crate::{Framebuffer, OledError, Rect, Widget, OLED_WIDTH};
+
+/// Most samples a [`Sparkline`](struct.Sparkline.html) can keep, one for each
+/// column of the display
+pub const MAX_SPARKLINE_SAMPLES: usize = OLED_WIDTH as usize;
+
+/// How the values of a chart map to its height
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Scale(Option<Bounds>);
+
+impl Scale {
+    /// Fits the chart to the values shown, with the lowest at the bottom and
+    /// the highest at the top
+    pub const AUTO: Scale = Scale(None);
+
+    /// Puts `min` at the bottom of the chart and `max` at the top. Values
+    /// outside them are drawn at the nearest edge.
+    ///
+    /// Returns `InvalidArgument` unless `max` is more than `min`.
+    pub fn fixed(min: f32, max: f32) -> Result<Self, OledError> {
+        let bounds = Bounds::new(min, max, "chart maximum must be more than its minimum")?;
+        Ok(Scale(Some(bounds)))
+    }
+
+    /// Bounds to draw `values` within, or `None` if there is nothing to draw
+    fn bounds<V: Iterator<Item = f32>>(&self, values: V) -> Option<Bounds> {
+        if let Some(bounds) = self.0 {
+            return Some(bounds);
+        }
+        let (min, max) = values.filter(|value| value.is_finite()).fold(
+            None,
+            |range: Option<(f32, f32)>, value| match range {
+                Some((min, max)) => Some((min.min(value), max.max(value))),
+                None => Some((value, value)),
+            },
+        )?;
+        if min < max {
+            Bounds::new(min, max, "").ok()
+        } else {
+            // a flat line, across the middle
+            let spread = min.abs().max(1.0);
+            Bounds::new(min - spread, max + spread, "").ok()
+        }
+    }
+}
+
+impl Default for Scale {
+    fn default() -> Self {
+        Scale::AUTO
+    }
+}
+
+/// The row of `area` that `value` is drawn at, with `bounds` from its bottom to
+/// its top. `area` mustn't be empty.
+fn row(area: Rect, bounds: Bounds, value: f32) -> u16 {
+    let rows = area.height - 1;
+    area.y + rows - (bounds.fraction(value) * f32::from(rows) + 0.5) as u16
+}
+
+/// Draws `points`, each a column and a value, as a line through `area`, with
+/// `bounds` from its bottom to its top. `NaN` values leave gaps.
+fn draw_series<P: Iterator<Item = (u16, f32)>>(
+    frame: &mut Framebuffer,
+    area: Rect,
+    points: P,
+    bounds: Bounds,
+) {
+    if area.height == 0 {
+        return;
+    }
+    let mut last = None;
+    for (x, value) in points {
+        if value.is_nan() {
+            last = None;
+            continue;
+        }
+        let y = row(area, bounds, value);
+        let (x0, y0) = last.unwrap_or((x, y));
+        frame.draw_line(x0, y0, x, y, true);
+        last = Some((x, y));
+    }
+}
+
+/// A [`Widget`](trait.Widget.html) showing how a value has changed recently,
+/// as a line graph of its last few samples with the latest at the right, eg
+/// for CPU load or network throughput. It keeps a rolling window of samples,
+/// and shows one for each column of its area, so as many as fit.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{Oled, OledResult, Rect, Sparkline};
+/// # use std::{thread::sleep, time::Duration};
+/// # fn cpu_load() -> f32 { 0.5 }
+/// # fn main() -> OledResult {
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// let mut load = Sparkline::new(128)?;
+/// loop {
+///     load.push(cpu_load());
+///     oled.render_widgets(&mut [(&mut load, Rect::new(0, 32, 128, 32))])?;
+///     sleep(Duration::from_secs(1));
+/// }
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sparkline {
+    /// Samples in the window, wrapping around from `start`
+    samples: [f32; MAX_SPARKLINE_SAMPLES],
+    /// Index of the oldest sample
+    start: usize,
+    /// Number of samples in the window so far
+    len: usize,
+    /// Most samples kept
+    window: usize,
+    /// How samples map to the height of the graph
+    scale: Scale,
+    /// Whether anything has changed since the graph was last rendered
+    changed: bool,
+}
+
+impl Sparkline {
+    /// Creates an empty graph, which keeps the last `window` samples pushed
+    /// to it, scaled to fit
+    ///
+    /// Returns `InvalidArgument` unless `window` is between 1 and
+    /// [`MAX_SPARKLINE_SAMPLES`](constant.MAX_SPARKLINE_SAMPLES.html).
+    pub fn new(window: usize) -> Result<Self, OledError> {
+        if window == 0 || window > MAX_SPARKLINE_SAMPLES {
+            return Err(OledError::InvalidArgument(
+                "sparkline window must be between 1 and 128 samples",
+            ));
+        }
+        Ok(Sparkline {
+            samples: [0.0; MAX_SPARKLINE_SAMPLES],
+            start: 0,
+            len: 0,
+            window,
+            scale: Scale::AUTO,
+            changed: true,
+        })
+    }
+
+    /// Maps samples to the height of the graph by `scale`, rather than fitting
+    /// it to them
+    pub fn with_scale(self, scale: Scale) -> Self {
+        Sparkline { scale, ..self }
+    }
+
+    /// Adds a sample at the right of the graph, dropping the oldest once the
+    /// window is full. `NaN` leaves a gap.
+    pub fn push(&mut self, value: f32) {
+        if self.len < self.window {
+            self.samples[(self.start + self.len) % self.window] = value;
+            self.len += 1;
+        } else {
+            self.samples[self.start] = value;
+            self.start = (self.start + 1) % self.window;
+        }
+        self.changed = true;
+    }
+
+    /// Drops all the samples
+    pub fn clear(&mut self) {
+        self.changed |= self.len > 0;
+        self.start = 0;
+        self.len = 0;
+    }
+
+    /// The samples in the window, oldest first
+    pub fn samples(&self) -> impl Iterator<Item = f32> + '_ {
+        (0..self.len).map(move |index| self.samples[(self.start + index) % self.window])
+    }
+}
+
+impl Widget for Sparkline {
+    fn render(&mut self, frame: &mut Framebuffer, area: Rect) {
+        frame.fill_rect(area.x, area.y, area.width, area.height, false);
+        let shown = self.len.min(usize::from(area.width));
+        let skip = self.len - shown;
+        if let Some(bounds) = self.scale.bounds(self.samples().skip(skip)) {
+            let left = area.x + area.width - shown as u16;
+            let points = (left..).zip(self.samples().skip(skip));
+            draw_series(frame, area, points, bounds);
+        }
+        self.changed = false;
+    }
+
+    fn needs_render(&self) -> bool {
+        self.changed
+    }
+}
+
+/// A [`Widget`](trait.Widget.html) showing a series of values as a line graph
+/// stretched across its area, with optional axes along its left and bottom
+/// edges.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{LineChart, Oled, OledResult, Rect, Scale};
+/// # fn main() -> OledResult {
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// let throughput = [1.5, 3.0, 2.25, 8.0, 6.5, 7.0];
+/// let mut chart = LineChart::new(&throughput)
+///     .with_scale(Scale::fixed(0.0, 10.0)?)
+///     .with_axes(true);
+/// oled.render_widgets(&mut [(&mut chart, Rect::new(0, 0, 128, 64))])?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LineChart<'a> {
+    /// Values, from left to right
+    values: &'a [f32],
+    /// How values map to the height of the chart
+    scale: Scale,
+    /// Whether axes are drawn
+    axes: bool,
+}
+
+impl<'a> LineChart<'a> {
+    /// Creates a chart of `values`, from left to right, scaled to fit
+    pub fn new(values: &'a [f32]) -> Self {
+        LineChart {
+            values,
+            scale: Scale::AUTO,
+            axes: false,
+        }
+    }
+
+    /// Maps values to the height of the chart by `scale`, rather than fitting
+    /// it to them
+    pub fn with_scale(self, scale: Scale) -> Self {
+        LineChart { scale, ..self }
+    }
+
+    /// Draws axes along the left and bottom edges of the chart, or not
+    pub fn with_axes(self, axes: bool) -> Self {
+        LineChart { axes, ..self }
+    }
+
+    /// Changes the values shown
+    pub fn set_values(&mut self, values: &'a [f32]) {
+        self.values = values;
+    }
+}
+
+impl Widget for LineChart<'_> {
+    fn render(&mut self, frame: &mut Framebuffer, area: Rect) {
+        frame.fill_rect(area.x, area.y, area.width, area.height, false);
+        let mut graph = area;
+        if self.axes && area.width > 0 && area.height > 0 {
+            let bottom = area.y + area.height - 1;
+            frame.draw_line(area.x, area.y, area.x, bottom, true);
+            frame.draw_line(area.x, bottom, area.x + area.width - 1, bottom, true);
+            // a pixel of space between the axes and the line
+            let (_, right) = area.split_left(2);
+            (graph, _) = right.split_top(area.height.saturating_sub(2));
+        }
+        if graph.width == 0 {
+            return;
+        }
+        let Some(bounds) = self.scale.bounds(self.values.iter().copied()) else {
+            return;
+        };
+        let spaces = (self.values.len() as u32).saturating_sub(1).max(1);
+        let columns = u32::from(graph.width - 1);
+        let points = self.values.iter().enumerate().map(|(index, value)| {
+            let x = graph.x + (index as u32 * columns / spaces) as u16;
+            (x, *value)
+        });
+        draw_series(frame, graph, points, bounds);
+    }
+}
+
+/// A [`Widget`](trait.Widget.html) showing a series of values as vertical
+/// bars from left to right, eg for the load on each CPU core or an audio
+/// spectrum. Bars grow up from a baseline, or down for values below it, and
+/// any that don't fit across the area are left off.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{BarChart, Oled, OledResult, Rect, Scale};
+/// # fn main() -> OledResult {
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// let cores = [0.25, 0.9, 0.5, 0.05];
+/// let mut chart = BarChart::new(&cores)
+///     .with_scale(Scale::fixed(0.0, 1.0)?)
+///     .with_bar_width(12)
+///     .with_gap(4);
+/// oled.render_widgets(&mut [(&mut chart, Rect::new(64, 0, 64, 64))])?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BarChart<'a> {
+    /// Values, from left to right
+    values: &'a [f32],
+    /// How values map to the height of the chart
+    scale: Scale,
+    /// Width of each bar, or 0 to share the width of the area between them
+    bar_width: u16,
+    /// Space between bars
+    gap: u16,
+    /// Value the bars grow from
+    baseline: f32,
+}
+
+impl<'a> BarChart<'a> {
+    /// Creates a chart of `values`, from left to right, with bars sharing the
+    /// width of the chart a pixel apart, growing up from 0, scaled to fit
+    pub fn new(values: &'a [f32]) -> Self {
+        BarChart {
+            values,
+            scale: Scale::AUTO,
+            bar_width: 0,
+            gap: 1,
+            baseline: 0.0,
+        }
+    }
+
+    /// Maps values to the height of the chart by `scale`, rather than fitting
+    /// it to them and the baseline
+    pub fn with_scale(self, scale: Scale) -> Self {
+        BarChart { scale, ..self }
+    }
+
+    /// Makes each bar `width` pixels wide, or shares the width of the chart
+    /// between them if 0
+    pub fn with_bar_width(self, bar_width: u16) -> Self {
+        BarChart { bar_width, ..self }
+    }
+
+    /// Leaves `gap` pixels between bars
+    pub fn with_gap(self, gap: u16) -> Self {
+        BarChart { gap, ..self }
+    }
+
+    /// Grows bars from `baseline`, so values below it are drawn downwards
+    pub fn with_baseline(self, baseline: f32) -> Self {
+        BarChart { baseline, ..self }
+    }
+
+    /// Changes the values shown
+    pub fn set_values(&mut self, values: &'a [f32]) {
+        self.values = values;
+    }
+}
+
+impl Widget for BarChart<'_> {
+    fn render(&mut self, frame: &mut Framebuffer, area: Rect) {
+        frame.fill_rect(area.x, area.y, area.width, area.height, false);
+        if area.width == 0 || area.height == 0 || self.values.is_empty() {
+            return;
+        }
+        let values = self.values.iter().copied().chain(once(self.baseline));
+        let Some(bounds) = self.scale.bounds(values) else {
+            return;
+        };
+        let bar_width = match self.bar_width {
+            0 => {
+                let count = u16::try_from(self.values.len()).unwrap_or(u16::MAX);
+                let gaps = self.gap.saturating_mul(count - 1);
+                (area.width.saturating_sub(gaps) / count).max(1)
+            }
+            width => width,
+        };
+        let base = row(area, bounds, self.baseline);
+        let right = u32::from(area.x) + u32::from(area.width);
+        let step = u32::from(bar_width) + u32::from(self.gap);
+        for (index, value) in self.values.iter().enumerate() {
+            let x = u32::from(area.x) + index as u32 * step;
+            if x >= right {
+                break;
+            }
+            if value.is_nan() {
+                continue;
+            }
+            let width = (right - x).min(u32::from(bar_width)) as u16;
+            let top = row(area, bounds, *value);
+            let (top, bottom) = (top.min(base), top.max(base));
+            frame.fill_rect(x as u16, top, width, bottom - top + 1, true);
+        }
+    }
+}
@@ -0,0 +1,265 @@
+//! Drawing PNG files, without an image decoding library
+use alloc::vec::Vec;
+
+use crate::inflate::inflate_zlib;
+use crate::scale::MAX_PIXELS;
+use crate::{Framebuffer, Oled, OledError, OledInterface, OledResult};
+
+/// Gray level from which a pixel of a PNG image is lit
+const PNG_THRESHOLD: u8 = 0x80;
+
+/// PNG files start with these bytes
+pub(crate) const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+/// Whether `data` starts like a PNG file, eg to tell it from images in other
+/// formats before calling [`Oled::draw_png()`](struct.Oled.html#method.draw_png)
+pub fn is_png(data: &[u8]) -> bool {
+    data.starts_with(&PNG_SIGNATURE)
+}
+
+/// A PNG image, decompressed and unfiltered
+struct Png {
+    /// Width in pixels
+    width: usize,
+    /// Height in pixels
+    height: usize,
+    /// Color type: 0 gray, 2 RGB, 3 palette, 4 gray and alpha, 6 RGBA
+    color: u8,
+    /// Bits per sample
+    depth: u8,
+    /// Palette colors, as red, green, blue and alpha
+    palette: Vec<[u8; 4]>,
+    /// Pixel data, row by row, without filter bytes
+    pixels: Vec<u8>,
+    /// Bytes per row
+    stride: usize,
+}
+
+/// The Paeth predictor, from the pixels left, above, and above left
+fn paeth(left: u8, up: u8, up_left: u8) -> u8 {
+    let estimate = i16::from(left) + i16::from(up) - i16::from(up_left);
+    let (to_left, to_up, to_up_left) = (
+        (estimate - i16::from(left)).abs(),
+        (estimate - i16::from(up)).abs(),
+        (estimate - i16::from(up_left)).abs(),
+    );
+    if to_left <= to_up && to_left <= to_up_left {
+        left
+    } else if to_up <= to_up_left {
+        up
+    } else {
+        up_left
+    }
+}
+
+impl Png {
+    /// Decodes a PNG file, checking it's a kind this can draw
+    fn parse(data: &[u8]) -> Result<Self, OledError> {
+        if !is_png(data) {
+            return Err(OledError::InvalidArgument("not a PNG image"));
+        }
+        let corrupt = || OledError::InvalidArgument("PNG image is corrupt");
+        let u32_at = |chunk: &[u8], at: usize| {
+            chunk
+                .get(at..at + 4)
+                .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]) as usize)
+        };
+        let mut header = None;
+        let mut palette = Vec::new();
+        let mut compressed = Vec::new();
+        let mut at = PNG_SIGNATURE.len();
+        loop {
+            let len =
+                u32_at(data, at).ok_or(OledError::InvalidArgument("PNG image is cut short"))?;
+            let kind = data.get(at + 4..at + 8).ok_or_else(corrupt)?;
+            let body = data
+                .get(at + 8..at + 8 + len)
+                .ok_or(OledError::InvalidArgument("PNG image is cut short"))?;
+            match kind {
+                b"IHDR" if body.len() == 13 => header = Some(body),
+                b"PLTE" => {
+                    palette = body
+                        .chunks_exact(3)
+                        .map(|rgb| [rgb[0], rgb[1], rgb[2], 0xff])
+                        .collect()
+                }
+                // alpha for palette colors
+                b"tRNS" => {
+                    for (color, alpha) in palette.iter_mut().zip(body) {
+                        color[3] = *alpha;
+                    }
+                }
+                b"IDAT" => compressed.extend_from_slice(body),
+                b"IEND" => break,
+                _ => {}
+            }
+            // the chunk's length, type and body, then its CRC
+            at += 12 + len;
+        }
+        let header = header.ok_or_else(corrupt)?;
+        let (width, height) = (
+            u32_at(header, 0).ok_or_else(corrupt)?,
+            u32_at(header, 4).ok_or_else(corrupt)?,
+        );
+        let (depth, color, interlace) = (header[8], header[9], header[12]);
+        let channels = match (color, depth) {
+            (0, 1 | 2 | 4 | 8 | 16) => 1,
+            (3, 1 | 2 | 4 | 8) => 1,
+            (4, 8 | 16) => 2,
+            (2, 8 | 16) => 3,
+            (6, 8 | 16) => 4,
+            _ => return Err(corrupt()),
+        };
+        if interlace != 0 {
+            return Err(OledError::InvalidArgument(
+                "interlaced PNG images aren't supported",
+            ));
+        }
+        if width == 0 || height == 0 || width.saturating_mul(height) > MAX_PIXELS {
+            return Err(OledError::InvalidDimensions);
+        }
+        if color == 3 && palette.is_empty() {
+            return Err(corrupt());
+        }
+        let bits = channels * usize::from(depth);
+        let stride = (width * bits).div_ceil(8);
+        let filtered = inflate_zlib(&compressed, height * (stride + 1)).ok_or_else(corrupt)?;
+        if filtered.len() != height * (stride + 1) {
+            return Err(corrupt());
+        }
+        // filters work on whole pixels, or bytes for pixels smaller than one
+        let pixel = bits.div_ceil(8);
+        let mut pixels = alloc::vec![0u8; height * stride];
+        for (y, row) in filtered.chunks_exact(stride + 1).enumerate() {
+            let (filter, row) = (row[0], &row[1..]);
+            let (done, rest) = pixels.split_at_mut(y * stride);
+            let previous = done
+                .get(done.len().saturating_sub(stride)..)
+                .filter(|_| y > 0);
+            let current = &mut rest[..stride];
+            for x in 0..stride {
+                let left = if x >= pixel { current[x - pixel] } else { 0 };
+                let up = previous.map_or(0, |previous| previous[x]);
+                let up_left = match previous {
+                    Some(previous) if x >= pixel => previous[x - pixel],
+                    _ => 0,
+                };
+                let predicted = match filter {
+                    0 => 0,
+                    1 => left,
+                    2 => up,
+                    3 => ((u16::from(left) + u16::from(up)) / 2) as u8,
+                    4 => paeth(left, up, up_left),
+                    _ => return Err(corrupt()),
+                };
+                current[x] = row[x].wrapping_add(predicted);
+            }
+        }
+        Ok(Png {
+            width,
+            height,
+            color,
+            depth,
+            palette,
+            pixels,
+            stride,
+        })
+    }
+
+    /// Sample `index` of the row starting at `row`, scaled to 8 bits, or left
+    /// as it is for palette indexes
+    fn sample(&self, row: &[u8], index: usize) -> u8 {
+        match self.depth {
+            16 => row[index * 2],
+            8 => row[index],
+            depth => {
+                let bit = index * usize::from(depth);
+                let max = (1u16 << depth) - 1;
+                let value = u16::from(row[bit / 8] >> (8 - usize::from(depth) - bit % 8)) & max;
+                match self.color {
+                    3 => value as u8,
+                    _ => (value * 255 / max) as u8,
+                }
+            }
+        }
+    }
+
+    /// The gray level of the pixel at `x`, `y`, counting from the top left, as
+    /// if over black where it's transparent
+    fn gray(&self, x: usize, y: usize) -> u8 {
+        let row = &self.pixels[y * self.stride..];
+        let luma = |red: u8, green: u8, blue: u8| {
+            ((77 * u32::from(red) + 150 * u32::from(green) + 29 * u32::from(blue)) >> 8) as u8
+        };
+        let over_black = |gray: u8, alpha: u8| (u16::from(gray) * u16::from(alpha) / 255) as u8;
+        match self.color {
+            0 => self.sample(row, x),
+            2 => luma(
+                self.sample(row, x * 3),
+                self.sample(row, x * 3 + 1),
+                self.sample(row, x * 3 + 2),
+            ),
+            3 => match self.palette.get(usize::from(self.sample(row, x))) {
+                Some(&[red, green, blue, alpha]) => over_black(luma(red, green, blue), alpha),
+                None => 0,
+            },
+            4 => over_black(self.sample(row, x * 2), self.sample(row, x * 2 + 1)),
+            _ => over_black(
+                luma(
+                    self.sample(row, x * 4),
+                    self.sample(row, x * 4 + 1),
+                    self.sample(row, x * 4 + 2),
+                ),
+                self.sample(row, x * 4 + 3),
+            ),
+        }
+    }
+
+    /// Draws the image into `frame` with its top left corner at `x`, `y`
+    fn draw(&self, frame: &mut Framebuffer, x: u16, y: u16) {
+        let (frame_width, frame_height) = frame.dimensions();
+        let columns = self.width.min(usize::from(frame_width.saturating_sub(x)));
+        let rows = self.height.min(usize::from(frame_height.saturating_sub(y)));
+        for row in 0..rows {
+            for column in 0..columns {
+                let lit = self.gray(column, row) >= PNG_THRESHOLD;
+                frame.set_pixel(x + column as u16, y + row as u16, lit);
+            }
+        }
+    }
+}
+
+impl Framebuffer {
+    /// Draws a PNG image with its top left corner at `x`, `y`, lit where it's
+    /// lighter than mid-gray and clipped at the edges. Transparent parts are taken
+    /// as black. Any non-interlaced PNG file can be drawn, in gray or color, so no
+    /// image decoding library is needed.
+    ///
+    /// Returns `InvalidArgument` if the data isn't such a PNG file, in which
+    /// case nothing is drawn.
+    pub fn draw_png(&mut self, png: &[u8], x: u16, y: u16) -> Result<(), OledError> {
+        Png::parse(png)?.draw(self, x, y);
+        Ok(())
+    }
+}
+
+impl<I: OledInterface> Oled<I> {
+    /// Draws a PNG image onto the display with its top left corner at `x`, `y`,
+    /// as for [`Framebuffer::draw_png()`](struct.Framebuffer.html#method.draw_png).
+    /// Only what changes is sent.
+    /// # Example:
+    /// ```no_run
+    /// # use nanohat_oled::{Oled, OledResult};
+    /// # fn main() -> OledResult {
+    /// let mut oled = Oled::from_path("/dev/i2c-0")?;
+    /// oled.init()?;
+    /// let logo = std::fs::read("logo.png").expect("logo.png");
+    /// oled.draw_png(&logo, 0, 0)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn draw_png(&mut self, png: &[u8], x: u16, y: u16) -> OledResult {
+        let png = Png::parse(png)?;
+        self.draw(|frame| png.draw(frame, x, y))
+    }
+}
@@ -0,0 +1,89 @@
+//! Putting the display to sleep, by hand or after a time without activity
+use core::time::Duration;
+
+use crate::{Command, Oled, OledError, OledInterface, OledResult};
+
+/// When the display turns itself off, and whether it has
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct AutoOff {
+    /// Time without activity after which the display goes to sleep, if it does
+    timeout: Option<Duration>,
+    /// Time since the last activity
+    idle: Duration,
+    /// Whether the display was put to sleep for want of activity
+    pub(crate) asleep: bool,
+}
+
+impl<I: OledInterface> Oled<I> {
+    /// Puts the display to sleep: turns it off, and its charge pump too, so it
+    /// draws next to no power. RAM is kept, and can still be written, eg to have
+    /// the next picture ready for [`wake()`](struct.Oled.html#method.wake).
+    pub fn sleep(&mut self) -> OledResult {
+        self.send_command(Command::DisplayOff)?;
+        self.send_instruction(self.config.controller().supply(false))
+    }
+
+    /// Wakes the display from [`sleep()`](struct.Oled.html#method.sleep): turns
+    /// its charge pump back on, unless [`init_with()`](struct.Oled.html#method.init_with)
+    /// was told the panel has its own supply, then the display, showing what's in RAM
+    pub fn wake(&mut self) -> OledResult {
+        if self.config.charge_pump() {
+            self.send_instruction(self.config.controller().supply(true))?;
+        }
+        self.send_command(Command::DisplayOn)?;
+        Ok(())
+    }
+
+    /// Returns whether the display is off, whether asleep or only turned off.
+    /// This is tracked as commands are sent, starting from the controller's
+    /// reset state, in which it's off until [`init()`](struct.Oled.html#method.init).
+    pub fn is_asleep(&self) -> bool {
+        !self.state.on
+    }
+
+    /// Puts the display to [`sleep()`](struct.Oled.html#method.sleep) once
+    /// `timeout` has passed without activity, or stops doing so if `None`,
+    /// eg so a box that's on all the time doesn't burn in its dashboard.
+    ///
+    /// Activity is a call to [`touch()`](struct.Oled.html#method.touch), eg
+    /// for a button press, as an [`App`](struct.App.html) makes, and the time
+    /// passing is counted by [`tick_auto_off()`](struct.Oled.html#method.tick_auto_off).
+    /// Drawing while the display is awake doesn't count, so a dashboard that
+    /// redraws itself still goes to sleep, but drawing while it's asleep wakes
+    /// it again.
+    pub fn set_auto_off(&mut self, timeout: Option<Duration>) {
+        self.auto_off.timeout = timeout;
+        self.auto_off.idle = Duration::ZERO;
+    }
+
+    /// Counts `elapsed` towards the timeout set by
+    /// [`set_auto_off()`](struct.Oled.html#method.set_auto_off), and puts the
+    /// display to sleep if it has run out
+    pub fn tick_auto_off(&mut self, elapsed: Duration) -> OledResult {
+        let Some(timeout) = self.auto_off.timeout else {
+            return Ok(());
+        };
+        if self.auto_off.asleep {
+            return Ok(());
+        }
+        self.auto_off.idle += elapsed;
+        if self.auto_off.idle >= timeout {
+            self.sleep()?;
+            self.auto_off.asleep = true;
+        }
+        Ok(())
+    }
+
+    /// Counts as activity for [`set_auto_off()`](struct.Oled.html#method.set_auto_off),
+    /// starting its timeout again, and waking the display if it went to sleep
+    /// for want of activity. Returns whether it woke it.
+    pub fn touch(&mut self) -> Result<bool, OledError> {
+        self.auto_off.idle = Duration::ZERO;
+        if !self.auto_off.asleep {
+            return Ok(false);
+        }
+        self.wake()?;
+        self.auto_off.asleep = false;
+        Ok(true)
+    }
+}
@@ -0,0 +1,117 @@
+//! A bar filling up to show how far something has got
+use crate::widgets::Bounds;
+use crate::{BuiltinFont, Font, Framebuffer, OledError, Rect, Widget};
+
+/// A [`Widget`](trait.Widget.html) showing progress as an outlined bar, filled
+/// in proportion to a value between two bounds, eg for a download, an update or
+/// booting. The bar is drawn to fill its whole area, and can have the
+/// percentage written across its middle, in pixels that flip between lit and
+/// unlit over the filled part so they show on either.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{Oled, OledResult, ProgressBar};
+/// # fn main() -> OledResult {
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// let total = 4096.0;
+/// let mut bar = ProgressBar::new(0.0, total)?.with_label(true);
+/// for received in (0..=4096).step_by(512) {
+///     bar.set_value(received as f32);
+///     // along the bottom two pages of the display
+///     oled.render_to_pages(&mut bar, 6, 7)?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ProgressBar {
+    /// Values at which the bar is empty and full
+    bounds: Bounds,
+    /// Current value, within its bounds
+    value: f32,
+    /// Whether the percentage is written across the bar
+    label: bool,
+    /// Whether anything has changed since the bar was last rendered
+    changed: bool,
+}
+
+impl ProgressBar {
+    /// Creates an empty bar, which fills up as its value goes from `min` to `max`
+    ///
+    /// Returns `InvalidArgument` unless `max` is more than `min`.
+    pub fn new(min: f32, max: f32) -> Result<Self, OledError> {
+        let bounds = Bounds::new(
+            min,
+            max,
+            "progress bar maximum must be more than its minimum",
+        )?;
+        Ok(ProgressBar {
+            bounds,
+            value: min,
+            label: false,
+            changed: true,
+        })
+    }
+
+    /// Writes the percentage across the middle of the bar, or not
+    pub fn with_label(self, label: bool) -> Self {
+        ProgressBar { label, ..self }
+    }
+
+    /// Sets how far along the bar is. Values outside its bounds are taken to be
+    /// the nearest of them, and `NaN` as the minimum.
+    pub fn set_value(&mut self, value: f32) {
+        let value = self.bounds.clamp(value);
+        self.changed |= value != self.value;
+        self.value = value;
+    }
+
+    /// How far along the bar is, as last set
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    /// How far along the bar is, from 0 to 100
+    pub fn percentage(&self) -> u8 {
+        (self.bounds.fraction(self.value) * 100.0 + 0.5) as u8
+    }
+}
+
+impl Widget for ProgressBar {
+    fn render(&mut self, frame: &mut Framebuffer, area: Rect) {
+        frame.fill_rect(area.x, area.y, area.width, area.height, false);
+        frame.draw_rect(area.x, area.y, area.width, area.height, true);
+        // a pixel of space between the outline and the fill
+        let inner = area.inset(2);
+        let filled = (f32::from(inner.width) * self.bounds.fraction(self.value) + 0.5) as u16;
+        frame.fill_rect(inner.x, inner.y, filled, inner.height, true);
+        if self.label {
+            let percentage = self.percentage();
+            let digits = [
+                b'0' + percentage / 100,
+                b'0' + percentage / 10 % 10,
+                b'0' + percentage % 10,
+                b'%',
+            ];
+            // without leading zeros
+            let skip = match percentage {
+                100.. => 0,
+                10..=99 => 1,
+                _ => 2,
+            };
+            let text = core::str::from_utf8(&digits[skip..]).unwrap_or("");
+            let font = BuiltinFont::PROPORTIONAL;
+            // the trailing column of spacing doesn't count towards centring
+            let width = i32::from(font.text_width(text)) - 1;
+            let x = i32::from(area.x) + (i32::from(area.width) - width) / 2;
+            // digits sit in the top 7 rows of their cells
+            let y = i32::from(area.y) + (i32::from(area.height) - 7) / 2;
+            frame.flip_text(x, y, text, &font, inner);
+        }
+        self.changed = false;
+    }
+
+    fn needs_render(&self) -> bool {
+        self.changed
+    }
+}
@@ -0,0 +1,206 @@
+//! Loading Linux console fonts in the PC Screen Font (PSF) formats
+use core::convert::TryFrom;
+
+use crate::{BitmapFont, Font, OledError};
+
+/// Magic number at the start of a PSF1 font
+const PSF1_MAGIC: [u8; 2] = [0x36, 0x04];
+/// PSF1 mode flag for a font of 512 glyphs, rather than 256
+const PSF1_MODE512: u8 = 0x01;
+/// PSF1 mode flags for a font with a Unicode table
+const PSF1_MODEHASTAB: u8 = 0x02 | 0x04;
+/// Ends a glyph's entries in a PSF1 Unicode table
+const PSF1_SEPARATOR: u16 = 0xffff;
+/// Starts a sequence of code points in a PSF1 Unicode table
+const PSF1_STARTSEQ: u16 = 0xfffe;
+
+/// Magic number at the start of a PSF2 font
+const PSF2_MAGIC: [u8; 4] = [0x72, 0xb5, 0x4a, 0x86];
+/// PSF2 flag for a font with a Unicode table
+const PSF2_HAS_UNICODE_TABLE: u32 = 0x01;
+/// Ends a glyph's entries in a PSF2 Unicode table
+const PSF2_SEPARATOR: u8 = 0xff;
+/// Starts a sequence of characters in a PSF2 Unicode table
+const PSF2_STARTSEQ: u8 = 0xfe;
+
+/// The error for a PSF font that can't be read
+fn invalid(reason: &'static str) -> OledError {
+    OledError::InvalidArgument(reason)
+}
+
+/// Reads the little-endian `u32` at `offset`
+fn u32_at(data: &[u8], offset: usize) -> Result<u32, OledError> {
+    data.get(offset..offset + 4)
+        .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        .ok_or(invalid("PSF header is truncated"))
+}
+
+/// Converts a size read from the header, rejecting values that can't be right
+fn size(value: u32) -> Result<usize, OledError> {
+    usize::try_from(value).map_err(|_| invalid("PSF header is invalid"))
+}
+
+/// Layout of a font's glyphs, whichever version of PSF it came from
+struct Layout {
+    /// Number of glyphs
+    count: usize,
+    /// Width and height of every glyph, in pixels
+    dimensions: (u16, u16),
+    /// Offset of the first glyph
+    start: usize,
+    /// Whether a Unicode table follows the glyphs
+    has_table: bool,
+}
+
+impl Layout {
+    /// Reads a PSF1 header
+    fn psf1(data: &[u8]) -> Result<Self, OledError> {
+        let (mode, height) = match data {
+            [_, _, mode, height, ..] => (*mode, *height),
+            _ => return Err(invalid("PSF header is truncated")),
+        };
+        Ok(Layout {
+            count: if mode & PSF1_MODE512 != 0 { 512 } else { 256 },
+            dimensions: (8, u16::from(height)),
+            start: 4,
+            has_table: mode & PSF1_MODEHASTAB != 0,
+        })
+    }
+
+    /// Reads a PSF2 header
+    fn psf2(data: &[u8]) -> Result<Self, OledError> {
+        let header_size = size(u32_at(data, 8)?)?;
+        let flags = u32_at(data, 12)?;
+        let count = size(u32_at(data, 16)?)?;
+        let glyph_size = size(u32_at(data, 20)?)?;
+        let height =
+            u16::try_from(u32_at(data, 24)?).map_err(|_| invalid("PSF glyphs are too big"))?;
+        let width =
+            u16::try_from(u32_at(data, 28)?).map_err(|_| invalid("PSF glyphs are too big"))?;
+        if glyph_size != (width as usize).div_ceil(8) * height as usize {
+            return Err(invalid("PSF glyph size doesn't match its dimensions"));
+        }
+        Ok(Layout {
+            count,
+            dimensions: (width, height),
+            start: header_size,
+            has_table: flags & PSF2_HAS_UNICODE_TABLE != 0,
+        })
+    }
+
+    /// Number of bytes in each glyph's bitmap
+    fn glyph_size(&self) -> usize {
+        (self.dimensions.0 as usize).div_ceil(8) * self.dimensions.1 as usize
+    }
+}
+
+/// Calls `map` with each glyph index and a character it is drawn for, from a
+/// PSF1 Unicode table. Sequences of combining characters are skipped, as are
+/// glyphs past the end of a truncated table.
+fn psf1_table(table: &[u8], count: usize, mut map: impl FnMut(usize, char)) {
+    let mut entries = table
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]));
+    for index in 0..count {
+        let mut in_sequence = false;
+        loop {
+            match entries.next() {
+                None => return,
+                Some(PSF1_SEPARATOR) => break,
+                Some(PSF1_STARTSEQ) => in_sequence = true,
+                Some(code) if !in_sequence => {
+                    if let Some(char) = char::from_u32(u32::from(code)) {
+                        map(index, char);
+                    }
+                }
+                Some(_) => {}
+            }
+        }
+    }
+}
+
+/// Calls `map` with each glyph index and a character it is drawn for, from a
+/// PSF2 Unicode table. Sequences of combining characters are skipped, as are
+/// glyphs past the end of a truncated table.
+fn psf2_table(
+    table: &[u8],
+    count: usize,
+    mut map: impl FnMut(usize, char),
+) -> Result<(), OledError> {
+    let entries = table.split(|byte| *byte == PSF2_SEPARATOR);
+    for (index, entry) in entries.take(count).enumerate() {
+        // only the characters before the first sequence stand on their own
+        let singles = entry
+            .split(|byte| *byte == PSF2_STARTSEQ)
+            .next()
+            .unwrap_or(&[]);
+        let singles =
+            core::str::from_utf8(singles).map_err(|_| invalid("PSF Unicode table is invalid"))?;
+        for char in singles.chars() {
+            map(index, char);
+        }
+    }
+    Ok(())
+}
+
+impl BitmapFont {
+    /// Loads a font from the contents of a PSF1 or PSF2 file, the formats of the
+    /// Linux console fonts, eg those under `/usr/share/consolefonts`. Those are
+    /// usually gzipped, and need decompressing first.
+    ///
+    /// Characters are looked up through the font's Unicode table, if it has one;
+    /// otherwise each glyph is taken to be the character with the same code point
+    /// as its index, which is right at least for ASCII. Glyphs are drawn in cells
+    /// of the font's width and height, with no extra spacing.
+    ///
+    /// Returns `InvalidArgument` if the file isn't a PSF font or can't be read.
+    /// # Example:
+    /// ```no_run
+    /// # use nanohat_oled::{BitmapFont, Oled};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let font = BitmapFont::from_psf_bytes(&std::fs::read("Lat2-Terminus12x6.psf")?)?;
+    /// let mut oled = Oled::from_path("/dev/i2c-0")?;
+    /// oled.init()?;
+    /// oled.put_string_font(0, 0, "25\u{b0}C", &font)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_psf_bytes(data: &[u8]) -> Result<Self, OledError> {
+        let layout = if data.starts_with(&PSF2_MAGIC) {
+            Layout::psf2(data)?
+        } else if data.starts_with(&PSF1_MAGIC) {
+            Layout::psf1(data)?
+        } else {
+            return Err(invalid("not a PSF font"));
+        };
+        if layout.dimensions.1 == 0 {
+            return Err(invalid("PSF font has no height"));
+        }
+        let glyph_size = layout.glyph_size();
+        let end = layout
+            .count
+            .checked_mul(glyph_size)
+            .and_then(|len| len.checked_add(layout.start))
+            .ok_or(invalid("PSF header is invalid"))?;
+        let glyphs = data
+            .get(layout.start..end)
+            .ok_or(invalid("PSF glyphs are truncated"))?;
+        let mut font = BitmapFont::new(layout.dimensions.1);
+        let mut insert = |index: usize, char: char| {
+            let rows = &glyphs[index * glyph_size..(index + 1) * glyph_size];
+            font.insert(char, layout.dimensions, (0, 0), layout.dimensions.0, rows);
+        };
+        let table = &data[end..];
+        match (layout.has_table, data.starts_with(&PSF2_MAGIC)) {
+            (false, _) => (0..layout.count)
+                .filter_map(|index| Some((index, char::from_u32(u32::try_from(index).ok()?)?)))
+                .for_each(|(index, char)| insert(index, char)),
+            (true, false) => psf1_table(table, layout.count, insert),
+            (true, true) => psf2_table(table, layout.count, insert)?,
+        }
+        if font.glyph('\u{fffd}').is_some() {
+            font.set_default_char('\u{fffd}');
+        }
+        Ok(font)
+    }
+}
@@ -0,0 +1,545 @@
+//! Encoding and drawing QR codes, eg to show a device's address for provisioning
+use crate::{Align, Framebuffer, Oled, OledError, OledInterface, OledResult};
+
+/// Largest QR code version that fits on the display, with a quiet zone
+const MAX_VERSION: usize = 9;
+
+/// Modules across the largest QR code that fits on the display
+const MAX_SIZE: usize = MAX_VERSION * 4 + 17;
+
+/// Modules of light space needed around a QR code for it to scan
+pub const QR_QUIET_ZONE: u16 = 4;
+
+/// Error correction codewords in each block, by level and version
+const ECC_PER_BLOCK: [[u8; MAX_VERSION + 1]; 4] = [
+    [0, 7, 10, 15, 20, 26, 18, 20, 24, 30],
+    [0, 10, 16, 26, 18, 24, 16, 18, 22, 22],
+    [0, 13, 22, 18, 26, 18, 24, 18, 22, 20],
+    [0, 17, 28, 22, 16, 22, 28, 26, 26, 24],
+];
+
+/// Error correction blocks, by level and version
+const ECC_BLOCKS: [[u8; MAX_VERSION + 1]; 4] = [
+    [0, 1, 1, 1, 1, 1, 2, 2, 2, 2],
+    [0, 1, 1, 1, 2, 2, 4, 4, 4, 5],
+    [0, 1, 1, 2, 2, 4, 4, 6, 6, 8],
+    [0, 1, 1, 2, 4, 4, 4, 5, 6, 8],
+];
+
+/// How much of a [`QrCode`](struct.QrCode.html) can be damaged or hidden
+/// while it still scans. More error correction makes the code denser.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum QrEcc {
+    /// About 7% can be lost
+    Low,
+    /// About 15% can be lost
+    Medium,
+    /// About 25% can be lost
+    Quartile,
+    /// About 30% can be lost
+    High,
+}
+
+impl QrEcc {
+    /// Row of the error correction tables for this level
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    /// The level's two bits in the format information
+    fn format_bits(self) -> u16 {
+        match self {
+            QrEcc::Low => 1,
+            QrEcc::Medium => 0,
+            QrEcc::Quartile => 3,
+            QrEcc::High => 2,
+        }
+    }
+}
+
+/// A QR code encoding some bytes, in the smallest version that holds them,
+/// up to version 9 (53 modules across) so it fits on the display with its
+/// quiet zone. That's up to 230 bytes with [`QrEcc::Low`](enum.QrEcc.html),
+/// down to 98 with `High`.
+/// # Example:
+/// ```
+/// use nanohat_oled::{QrCode, QrEcc};
+///
+/// let code = QrCode::new(b"https://example.com", QrEcc::Medium).unwrap();
+/// assert_eq!(code.size(), 25);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QrCode {
+    /// Modules across and down
+    size: usize,
+    /// Whether each module is dark, by row
+    modules: [[bool; MAX_SIZE]; MAX_SIZE],
+    /// Whether each module is part of a pattern rather than data, by row
+    function: [[bool; MAX_SIZE]; MAX_SIZE],
+}
+
+impl QrCode {
+    /// Encodes `data` with error correction `ecc`
+    ///
+    /// Returns `InvalidArgument` if there's too much data for a code that fits
+    /// on the display.
+    pub fn new(data: &[u8], ecc: QrEcc) -> Result<Self, OledError> {
+        let version = (1..=MAX_VERSION)
+            .find(|version| 4 + 8 + data.len() * 8 <= data_codewords(*version, ecc) * 8)
+            .ok_or(OledError::InvalidArgument(
+                "too much data for a QR code that fits on the display",
+            ))?;
+        let mut codewords = [0; raw_codewords(MAX_VERSION)];
+        let capacity = data_codewords(version, ecc);
+        let mut bits = BitWriter {
+            bytes: &mut codewords[..capacity],
+            len: 0,
+        };
+        // byte mode, then the length and the data
+        bits.push(0b0100, 4);
+        bits.push(data.len() as u32, 8);
+        for byte in data {
+            bits.push(u32::from(*byte), 8);
+        }
+        let terminator = (capacity * 8 - bits.len).min(4);
+        bits.push(0, terminator);
+        bits.push(0, (8 - bits.len % 8) % 8);
+        for pad in [0xec, 0x11].iter().cycle().take(capacity - bits.len / 8) {
+            bits.push(*pad, 8);
+        }
+        let mut interleaved = [0; raw_codewords(MAX_VERSION)];
+        let total = interleave(&codewords[..capacity], version, ecc, &mut interleaved);
+
+        let size = version * 4 + 17;
+        let mut code = QrCode {
+            size,
+            modules: [[false; MAX_SIZE]; MAX_SIZE],
+            function: [[false; MAX_SIZE]; MAX_SIZE],
+        };
+        code.draw_function_patterns(version);
+        code.draw_codewords(&interleaved[..total]);
+        let mut best = (u32::MAX, 0);
+        for mask in 0..8 {
+            code.apply_mask(mask);
+            code.draw_format(ecc, mask);
+            let penalty = code.penalty();
+            if penalty < best.0 {
+                best = (penalty, mask);
+            }
+            code.apply_mask(mask);
+        }
+        code.apply_mask(best.1);
+        code.draw_format(ecc, best.1);
+        Ok(code)
+    }
+
+    /// Modules across and down the code, not counting its quiet zone
+    pub fn size(&self) -> u16 {
+        self.size as u16
+    }
+
+    /// Returns whether the module at `x`, `y` is dark. Modules outside the
+    /// code, in its quiet zone, are light.
+    pub fn get_module(&self, x: u16, y: u16) -> bool {
+        let (x, y) = (usize::from(x), usize::from(y));
+        x < self.size && y < self.size && self.modules[y][x]
+    }
+
+    /// Sets a module that's part of a pattern
+    fn set_function(&mut self, x: usize, y: usize, dark: bool) {
+        self.modules[y][x] = dark;
+        self.function[y][x] = true;
+    }
+
+    /// Draws the finder, timing and alignment patterns and the version
+    /// information, and reserves room for the format information
+    fn draw_function_patterns(&mut self, version: usize) {
+        let size = self.size;
+        for i in 0..size {
+            self.set_function(6, i, i % 2 == 0);
+            self.set_function(i, 6, i % 2 == 0);
+        }
+        for (x, y) in [(3, 3), (size - 4, 3), (3, size - 4)].iter().copied() {
+            for dy in -4i32..=4 {
+                for dx in -4i32..=4 {
+                    let (px, py) = (x as i32 + dx, y as i32 + dy);
+                    if px >= 0 && py >= 0 && (px as usize) < size && (py as usize) < size {
+                        let distance = dx.abs().max(dy.abs());
+                        self.set_function(px as usize, py as usize, distance != 2 && distance != 4);
+                    }
+                }
+            }
+        }
+        let (positions, count) = alignment_positions(version);
+        for (i, x) in positions[..count].iter().enumerate() {
+            for (j, y) in positions[..count].iter().enumerate() {
+                // except where the finder patterns are
+                let corner = (i == 0 || j == 0) && (i + j == 0 || i + j == count - 1);
+                if corner {
+                    continue;
+                }
+                for dy in -2i32..=2 {
+                    for dx in -2i32..=2 {
+                        let (px, py) = ((*x as i32 + dx) as usize, (*y as i32 + dy) as usize);
+                        self.set_function(px, py, dx.abs().max(dy.abs()) != 1);
+                    }
+                }
+            }
+        }
+        if version >= 7 {
+            let mut remainder = version as u32;
+            for _ in 0..12 {
+                remainder = (remainder << 1) ^ ((remainder >> 11) * 0x1f25);
+            }
+            let bits = (version as u32) << 12 | remainder;
+            for i in 0..18 {
+                let dark = bits >> i & 1 != 0;
+                let (a, b) = (size - 11 + i % 3, i / 3);
+                self.set_function(a, b, dark);
+                self.set_function(b, a, dark);
+            }
+        }
+        self.draw_format(QrEcc::Low, 0);
+    }
+
+    /// Draws both copies of the format information, for `ecc` and `mask`
+    fn draw_format(&mut self, ecc: QrEcc, mask: u8) {
+        let data = ecc.format_bits() << 3 | u16::from(mask);
+        let mut remainder = data;
+        for _ in 0..10 {
+            remainder = (remainder << 1) ^ ((remainder >> 9) * 0x537);
+        }
+        let bits = (data << 10 | remainder) ^ 0x5412;
+        let bit = |i: usize| bits >> i & 1 != 0;
+        let size = self.size;
+        for i in 0..6 {
+            self.set_function(8, i, bit(i));
+        }
+        self.set_function(8, 7, bit(6));
+        self.set_function(8, 8, bit(7));
+        self.set_function(7, 8, bit(8));
+        for i in 9..15 {
+            self.set_function(14 - i, 8, bit(i));
+        }
+        for i in 0..8 {
+            self.set_function(size - 1 - i, 8, bit(i));
+        }
+        for i in 8..15 {
+            self.set_function(8, size - 15 + i, bit(i));
+        }
+        // always dark
+        self.set_function(8, size - 8, true);
+    }
+
+    /// Fills the modules not in patterns with `codewords`, zigzagging up and
+    /// down pairs of columns from the right
+    fn draw_codewords(&mut self, codewords: &[u8]) {
+        let size = self.size;
+        let mut bit = 0;
+        let mut right = size - 1;
+        loop {
+            // the vertical timing pattern is skipped over
+            if right == 6 {
+                right = 5;
+            }
+            for vertical in 0..size {
+                for j in 0..2 {
+                    let x = right - j;
+                    let upward = (right + 1) & 2 == 0;
+                    let y = if upward {
+                        size - 1 - vertical
+                    } else {
+                        vertical
+                    };
+                    if !self.function[y][x] && bit < codewords.len() * 8 {
+                        self.modules[y][x] = codewords[bit / 8] >> (7 - bit % 8) & 1 != 0;
+                        bit += 1;
+                    }
+                }
+            }
+            if right < 2 {
+                break;
+            }
+            right -= 2;
+        }
+    }
+
+    /// Flips the data modules picked out by `mask`; applying it twice undoes it
+    fn apply_mask(&mut self, mask: u8) {
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let flip = match mask {
+                    0 => (x + y) % 2 == 0,
+                    1 => y % 2 == 0,
+                    2 => x % 3 == 0,
+                    3 => (x + y) % 3 == 0,
+                    4 => (x / 3 + y / 2) % 2 == 0,
+                    5 => x * y % 2 + x * y % 3 == 0,
+                    6 => (x * y % 2 + x * y % 3) % 2 == 0,
+                    _ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+                };
+                if flip && !self.function[y][x] {
+                    self.modules[y][x] = !self.modules[y][x];
+                }
+            }
+        }
+    }
+
+    /// How hard the code is likely to be to scan, by the standard's rules for
+    /// choosing a mask: long runs, blocks and finder-like patterns, and an
+    /// imbalance of dark and light
+    fn penalty(&self) -> u32 {
+        let size = self.size;
+        let at = |x: usize, y: usize, transpose: bool| {
+            if transpose {
+                self.modules[x][y]
+            } else {
+                self.modules[y][x]
+            }
+        };
+        let mut penalty = 0;
+        for transpose in [false, true].iter().copied() {
+            for y in 0..size {
+                let mut run = 0;
+                for x in 0..size {
+                    if x > 0 && at(x, y, transpose) == at(x - 1, y, transpose) {
+                        run += 1;
+                    } else {
+                        run = 1;
+                    }
+                    if run == 5 {
+                        penalty += 3;
+                    } else if run > 5 {
+                        penalty += 1;
+                    }
+                }
+                let pattern = [true, false, true, true, true, false, true];
+                for x in 0..size.saturating_sub(6) {
+                    let finder = (0..7).all(|i| at(x + i, y, transpose) == pattern[i]);
+                    let light =
+                        |from: usize| (from..from + 4).all(|i| i >= size || !at(i, y, transpose));
+                    if finder && ((x >= 4 && light(x - 4)) || light(x + 7)) {
+                        penalty += 40;
+                    }
+                }
+            }
+        }
+        let mut dark = 0;
+        for y in 0..size {
+            for x in 0..size {
+                dark += u32::from(self.modules[y][x]);
+                if x + 1 < size && y + 1 < size {
+                    let colour = self.modules[y][x];
+                    if self.modules[y][x + 1] == colour
+                        && self.modules[y + 1][x] == colour
+                        && self.modules[y + 1][x + 1] == colour
+                    {
+                        penalty += 3;
+                    }
+                }
+            }
+        }
+        let total = (size * size) as u32;
+        let imbalance = (dark * 20).max(total * 10) - (dark * 20).min(total * 10);
+        penalty + imbalance.div_ceil(total).saturating_sub(1) * 10
+    }
+}
+
+/// Appends bits to a buffer of codewords, most significant first
+struct BitWriter<'a> {
+    /// Codewords, zeroed to begin with
+    bytes: &'a mut [u8],
+    /// Bits written so far
+    len: usize,
+}
+
+impl BitWriter<'_> {
+    /// Appends the lowest `count` bits of `value`
+    fn push(&mut self, value: u32, count: usize) {
+        for i in (0..count).rev() {
+            if value >> i & 1 != 0 {
+                self.bytes[self.len / 8] |= 0x80 >> (self.len % 8);
+            }
+            self.len += 1;
+        }
+    }
+}
+
+/// Modules of a code of `version` that hold data and error correction
+const fn raw_modules(version: usize) -> usize {
+    let mut modules = (16 * version + 128) * version + 64;
+    if version >= 2 {
+        let alignments = version / 7 + 2;
+        modules -= (25 * alignments - 10) * alignments - 55;
+        if version >= 7 {
+            modules -= 36;
+        }
+    }
+    modules
+}
+
+/// Codewords of data and error correction in a code of `version`
+const fn raw_codewords(version: usize) -> usize {
+    raw_modules(version) / 8
+}
+
+/// Codewords of data in a code of `version` with error correction `ecc`
+fn data_codewords(version: usize, ecc: QrEcc) -> usize {
+    let blocks = usize::from(ECC_BLOCKS[ecc.index()][version]);
+    let per_block = usize::from(ECC_PER_BLOCK[ecc.index()][version]);
+    raw_codewords(version) - blocks * per_block
+}
+
+/// Centres of the alignment patterns across (and down) a code of `version`,
+/// and how many there are
+fn alignment_positions(version: usize) -> ([usize; 7], usize) {
+    let mut positions = [0; 7];
+    if version == 1 {
+        return (positions, 0);
+    }
+    let count = version / 7 + 2;
+    let step = (version * 4 + count * 2 + 1) / (count * 2 - 2) * 2;
+    positions[0] = 6;
+    let mut position = version * 4 + 10;
+    for i in (1..count).rev() {
+        positions[i] = position;
+        position -= step;
+    }
+    (positions, count)
+}
+
+/// Splits `data` into blocks, adds error correction to each, and interleaves
+/// them into `out`, returning how many codewords there are
+fn interleave(data: &[u8], version: usize, ecc: QrEcc, out: &mut [u8]) -> usize {
+    let blocks = usize::from(ECC_BLOCKS[ecc.index()][version]);
+    let ecc_len = usize::from(ECC_PER_BLOCK[ecc.index()][version]);
+    let raw = raw_codewords(version);
+    let short_blocks = blocks - raw % blocks;
+    let short_len = raw / blocks - ecc_len;
+    let divisor = rs_divisor(ecc_len);
+    let mut start = 0;
+    for block in 0..blocks {
+        let len = short_len + usize::from(block >= short_blocks);
+        let block_data = &data[start..start + len];
+        start += len;
+        for (i, byte) in block_data.iter().enumerate() {
+            // only the long blocks have a last codeword
+            let index = if i < short_len {
+                i * blocks + block
+            } else {
+                short_len * blocks + block - short_blocks
+            };
+            out[index] = *byte;
+        }
+        let remainder = rs_remainder(block_data, &divisor[..ecc_len]);
+        for (i, byte) in remainder[..ecc_len].iter().enumerate() {
+            out[data.len() + i * blocks + block] = *byte;
+        }
+    }
+    raw
+}
+
+/// Multiplies two elements of GF(2^8), modulo the QR code polynomial
+fn gf_multiply(x: u8, y: u8) -> u8 {
+    let mut product: u16 = 0;
+    for i in (0..8).rev() {
+        product = (product << 1) ^ ((product >> 7) * 0x11d);
+        product ^= u16::from(y >> i & 1) * u16::from(x);
+    }
+    product as u8
+}
+
+/// The Reed-Solomon generator polynomial of `degree`, highest term first,
+/// without its leading 1
+fn rs_divisor(degree: usize) -> [u8; 32] {
+    let mut divisor = [0; 32];
+    divisor[degree - 1] = 1;
+    let mut root = 1;
+    for _ in 0..degree {
+        for j in 0..degree {
+            divisor[j] = gf_multiply(divisor[j], root);
+            if j + 1 < degree {
+                divisor[j] ^= divisor[j + 1];
+            }
+        }
+        root = gf_multiply(root, 0x02);
+    }
+    divisor
+}
+
+/// The Reed-Solomon error correction codewords for `data`
+fn rs_remainder(data: &[u8], divisor: &[u8]) -> [u8; 32] {
+    let mut remainder = [0; 32];
+    let degree = divisor.len();
+    for byte in data {
+        let factor = byte ^ remainder[0];
+        remainder.copy_within(1..degree, 0);
+        remainder[degree - 1] = 0;
+        for (r, d) in remainder[..degree].iter_mut().zip(divisor) {
+            *r ^= gf_multiply(*d, factor);
+        }
+    }
+    remainder
+}
+
+impl Framebuffer {
+    /// Draws `code` with its top left corner, including its quiet zone, at
+    /// `x`, `y`, each module `scale` pixels square. Dark modules are unlit
+    /// and light ones, and the quiet zone, are lit, as scanners expect.
+    /// Anything off the display is clipped.
+    pub fn draw_qr(&mut self, code: &QrCode, x: u16, y: u16, scale: u16) {
+        let side = code.size() + QR_QUIET_ZONE * 2;
+        for row in 0..side {
+            for column in 0..side {
+                let dark = column >= QR_QUIET_ZONE
+                    && row >= QR_QUIET_ZONE
+                    && code.get_module(column - QR_QUIET_ZONE, row - QR_QUIET_ZONE);
+                self.fill_rect(
+                    x.saturating_add(column * scale),
+                    y.saturating_add(row * scale),
+                    scale,
+                    scale,
+                    !dark,
+                );
+            }
+        }
+    }
+}
+
+impl<I: OledInterface> Oled<I> {
+    /// Draws a QR code of `data` with error correction `ecc`, as large as fits
+    /// down (or, turned a quarter turn, across) the display, centred the
+    /// other way and placed along the display by `position`. The rest of the
+    /// display is left alone, eg for a caption beside the code.
+    ///
+    /// Returns `InvalidArgument` if there's too much data for a code that fits.
+    /// # Example:
+    /// ```no_run
+    /// # use nanohat_oled::{Align, Oled, OledResult, QrEcc};
+    /// # fn main() -> OledResult {
+    /// let mut oled = Oled::from_path("/dev/i2c-0")?;
+    /// oled.init()?;
+    /// oled.clear_display()?;
+    /// oled.draw_qr("http://192.168.1.20:8080/setup", QrEcc::Medium, Align::Right)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn draw_qr(&mut self, data: &str, ecc: QrEcc, position: Align) -> OledResult {
+        let code = QrCode::new(data.as_bytes(), ecc)?;
+        let (width, height) = self.dimensions();
+        let side = code.size() + QR_QUIET_ZONE * 2;
+        let scale = width.min(height) / side;
+        let length = side * scale;
+        let along = |extent: u16| match position {
+            Align::Left => 0,
+            Align::Center => (extent - length) / 2,
+            Align::Right => extent - length,
+        };
+        let (x, y) = if width >= height {
+            (along(width), (height - length) / 2)
+        } else {
+            ((width - length) / 2, along(height))
+        };
+        self.draw(|frame| frame.draw_qr(&code, x, y, scale))
+    }
+}
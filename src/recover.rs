@@ -0,0 +1,58 @@
+//! Getting the display going again after it has lost its settings
+use crate::{AddressingMode, Command, Oled, OledInterface, OledResult};
+
+impl<I: OledInterface> Oled<I> {
+    /// Sets the display up again after a glitch on the bus, or the module being
+    /// unplugged and plugged back in, has left it reset or showing garbage, so
+    /// the program can carry on rather than restart.
+    ///
+    /// The setup sequence last sent by [`init_with()`](struct.Oled.html#method.init_with)
+    /// is sent again, and then the orientation, contrast, inversion,
+    /// [pixel shift](struct.Oled.html#method.set_pixel_shift) and picture are
+    /// put back as this `Oled` last sent them, as
+    /// [`framebuffer()`](struct.Oled.html#method.framebuffer) shows, with the
+    /// text cursor where it was. A display that was off, or asleep, is left so.
+    /// Scrolling, fades and settings sent as raw commands aren't restored.
+    /// # Example:
+    /// ```no_run
+    /// # use nanohat_oled::{Oled, OledResult};
+    /// # use std::time::Duration;
+    /// # fn main() -> OledResult {
+    /// let mut oled = Oled::from_path("/dev/i2c-0")?;
+    /// oled.init()?;
+    /// oled.set_retries(3);
+    /// oled.set_retry_backoff(Duration::from_millis(10));
+    /// if oled.put_string("Still here").is_err() {
+    ///     oled.recover()?;
+    ///     oled.put_string("Still here")?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn recover(&mut self) -> OledResult {
+        let saved = self.state.clone();
+        let asleep = self.auto_off.asleep;
+        // so that putting the picture back doesn't wake the display
+        self.auto_off.asleep = false;
+        self.send_commands(&self.config.sequence()?)?;
+        if self.config.controller().has_windows() {
+            self.set_addressing_mode(AddressingMode::Horizontal)?;
+        }
+        self.send_orientation()?;
+        self.send_commands(&[Command::SetContrast.into(), saved.contrast])?;
+        if saved.inverted {
+            self.send_command(Command::InverseDisplay)?;
+        }
+        self.send_start_line()?;
+        self.write_ram(&saved.buffer)?;
+        let (column, page) = saved.pointer();
+        self.move_pointer(column, page)?;
+        if asleep {
+            self.sleep()?;
+            self.auto_off.asleep = true;
+        } else if !saved.on {
+            self.send_command(Command::DisplayOff)?;
+        }
+        Ok(())
+    }
+}
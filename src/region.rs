@@ -0,0 +1,180 @@
+//! Text laid out in a rectangle of the display, wrapped, aligned and clipped to it
+use crate::{Font, Framebuffer, Oled, OledInterface, OledResult};
+
+/// Where each line of text goes across a [`TextRegion`](struct.TextRegion.html)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Align {
+    /// Lines start at the left edge of the region
+    Left,
+    /// Lines are centred in the region, rounding towards the left
+    Center,
+    /// Lines end at the right edge of the region
+    Right,
+}
+
+/// A rectangle of the display to lay text out in, as used by
+/// [`Framebuffer::draw_text_in()`](struct.Framebuffer.html#method.draw_text_in) and
+/// [`Oled::put_string_in()`](struct.Oled.html#method.put_string_in).
+///
+/// Text is wrapped to the region's width, each line is placed across it according
+/// to `align`, and nothing is drawn outside it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TextRegion {
+    /// Left edge of the region, in pixels
+    pub x: u16,
+    /// Top edge of the region, in pixels
+    pub y: u16,
+    /// Width of the region, in pixels
+    pub width: u16,
+    /// Height of the region, in pixels
+    pub height: u16,
+    /// Where each line goes across the region
+    pub align: Align,
+}
+
+impl TextRegion {
+    /// A region with its top left corner at `x`, `y`, with text aligned left
+    pub const fn new(x: u16, y: u16, width: u16, height: u16) -> Self {
+        TextRegion {
+            x,
+            y,
+            width,
+            height,
+            align: Align::Left,
+        }
+    }
+
+    /// Places lines across the region according to `align` instead
+    pub const fn with_align(self, align: Align) -> Self {
+        TextRegion { align, ..self }
+    }
+
+    /// Returns whether the pixel at `x`, `y` is inside the region
+    pub(crate) fn contains(&self, x: i32, y: i32) -> bool {
+        let (left, top) = (i32::from(self.x), i32::from(self.y));
+        x >= left
+            && y >= top
+            && x < left + i32::from(self.width)
+            && y < top + i32::from(self.height)
+    }
+
+    /// Turns off every pixel of the region that is on the display
+    pub(crate) fn clear(&self, frame: &mut Framebuffer) {
+        let (frame_width, frame_height) = frame.dimensions();
+        frame.fill_rect(
+            self.x,
+            self.y,
+            self.width.min(frame_width.saturating_sub(self.x)),
+            self.height.min(frame_height.saturating_sub(self.y)),
+            false,
+        );
+    }
+
+    /// The `x` coordinate a line `width` pixels wide starts at. Lines too wide
+    /// for the region start at its left edge, whatever the alignment.
+    pub(crate) fn line_start(&self, width: u16) -> u16 {
+        let space = self.width.saturating_sub(width);
+        match self.align {
+            Align::Left => self.x,
+            Align::Center => self.x.saturating_add(space / 2),
+            Align::Right => self.x.saturating_add(space),
+        }
+    }
+}
+
+/// Splits off the first line of `text` as wrapped to `width` pixels in `font`,
+/// returning it and the text after it, if any.
+///
+/// Each `\n` ends a line. Otherwise lines are broken at the last space that
+/// fits, which is dropped, or mid-word if a word is too long for a whole line.
+/// Every line has at least one character, however narrow the region.
+fn next_line<'a, F: Font + ?Sized>(
+    text: &'a str,
+    width: u16,
+    font: &F,
+) -> (&'a str, Option<&'a str>) {
+    let mut pen = 0u16;
+    let mut last_space = None;
+    for (index, char) in text.char_indices() {
+        if char == '\n' {
+            return (&text[..index], Some(&text[index + 1..]));
+        }
+        let advance = font.char_advance(char);
+        if index > 0 && pen.saturating_add(advance) > width {
+            return match (char, last_space) {
+                (' ', _) => (&text[..index], Some(&text[index + 1..])),
+                (_, Some(space)) => (&text[..space], Some(&text[space + 1..])),
+                _ => (&text[..index], Some(&text[index..])),
+            };
+        }
+        if char == ' ' {
+            last_space = Some(index);
+        }
+        pen = pen.saturating_add(advance);
+    }
+    (text, None)
+}
+
+impl Framebuffer {
+    /// Clears `region` and draws text in `font` inside it, wrapped to its width
+    /// and with each line aligned as the region says. Lines that don't fit in
+    /// its height are cut off, and nothing outside the region is touched.
+    ///
+    /// Returns whether all of the text fit.
+    pub fn draw_text_in<F: Font + ?Sized>(
+        &mut self,
+        region: &TextRegion,
+        text: &str,
+        font: &F,
+    ) -> bool {
+        region.clear(self);
+        let (_, frame_height) = self.dimensions();
+        let line_height = font.line_height();
+        // lines past the bottom of the display can't be seen, so don't fit either
+        let bottom = (u32::from(region.y) + u32::from(region.height)).min(u32::from(frame_height));
+        let mut top = u32::from(region.y);
+        let mut remaining = Some(text);
+        let mut fit = true;
+        while let Some(text) = remaining {
+            if top >= bottom {
+                return false;
+            }
+            let (line, rest) = next_line(text, region.width, font);
+            let width = font.text_width(line);
+            fit &= width <= region.width && top + u32::from(line_height) <= bottom;
+            let x = i32::from(region.line_start(width));
+            self.draw_text_clipped(x, top as u16, line, font, region);
+            top += u32::from(line_height);
+            remaining = rest;
+        }
+        fit
+    }
+}
+
+impl<I: OledInterface> Oled<I> {
+    /// Writes text in `font` inside `region`, wrapped, aligned and clipped to it,
+    /// as [`Framebuffer::draw_text_in()`](struct.Framebuffer.html#method.draw_text_in)
+    /// does, sending only the part of the display that changes.
+    /// # Example:
+    /// ```no_run
+    /// # use nanohat_oled::{Align, BuiltinFont, Oled, OledResult, TextRegion};
+    /// # fn main() -> OledResult {
+    /// let mut oled = Oled::from_path("/dev/i2c-0")?;
+    /// oled.init()?;
+    /// // a centred message across the middle of the display, whatever its length
+    /// let message = TextRegion::new(0, 24, 128, 16).with_align(Align::Center);
+    /// oled.put_string_in(&message, "Update complete", &BuiltinFont::PROPORTIONAL)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn put_string_in<F: Font + ?Sized>(
+        &mut self,
+        region: &TextRegion,
+        text: &str,
+        font: &F,
+    ) -> OledResult {
+        self.draw(|frame| {
+            frame.draw_text_in(region, text, font);
+        })
+    }
+}
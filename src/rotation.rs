@@ -0,0 +1,130 @@
+//! Orientation of the picture on the panel.
+//!
+//! Turning the picture upside down is done by the controller, by reversing the
+//! segment and COM scan order. Quarter turns are done in software: everything
+//! drawn is laid out for a 64x128 display, then turned a quarter turn clockwise
+//! into display RAM. [`Rotation::Rotate270`](enum.Rotation.html#variant.Rotate270)
+//! combines the two.
+use crate::{
+    Command, Oled, OledError, OledInterface, OledResult, PanelSize, OLED_HEIGHT, OLED_LAST_PAGE,
+    OLED_WIDTH,
+};
+
+/// Which way up the picture is drawn on the panel
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rotation {
+    /// The orientation set up by [`Oled::init()`](struct.Oled.html#method.init),
+    /// for a NanoHat in its usual position. (default)
+    Rotate0,
+    /// A quarter turn clockwise, for a portrait enclosure. The display is treated as
+    /// 64 pixels wide and 128 high, or 8 text columns by 16 rows.
+    Rotate90,
+    /// Upside down, for a NanoHat mounted the other way up
+    Rotate180,
+    /// A quarter turn anticlockwise. Otherwise like
+    /// [`Rotate90`](enum.Rotation.html#variant.Rotate90).
+    Rotate270,
+}
+
+impl Rotation {
+    /// Whether the display is on its side, ie 64 pixels wide and 128 high
+    pub fn is_portrait(self) -> bool {
+        matches!(self, Rotation::Rotate90 | Rotation::Rotate270)
+    }
+
+    /// Whether the controller needs to turn the picture upside down
+    fn is_flipped(self) -> bool {
+        matches!(self, Rotation::Rotate180 | Rotation::Rotate270)
+    }
+}
+
+/// Converts portrait coordinates (`x` across 64 pixels, `y` down 128) to the
+/// position of the same pixel in display RAM
+pub(crate) fn portrait_to_physical(x: u16, y: u16) -> (u16, u16) {
+    (y, OLED_HEIGHT - 1 - x)
+}
+
+/// Converts the position of a pixel in display RAM to portrait coordinates.
+/// The reverse of [`portrait_to_physical()`](fn.portrait_to_physical.html).
+pub(crate) fn physical_to_portrait(x: u16, y: u16) -> (u16, u16) {
+    (OLED_HEIGHT - 1 - y, x)
+}
+
+/// Turns a glyph a quarter turn clockwise, so that it reads the right way
+/// round in portrait. Both are 8 columns of 8 rows, LSB at the top.
+pub(crate) fn rotate_glyph(glyph: [u8; 8]) -> [u8; 8] {
+    let mut rotated = [0u8; 8];
+    for (x, column) in glyph.iter().enumerate() {
+        for (y, byte) in rotated.iter_mut().enumerate() {
+            if column & (1 << y) != 0 {
+                *byte |= 0x80 >> x;
+            }
+        }
+    }
+    rotated
+}
+
+impl<I: OledInterface> Oled<I> {
+    /// Turns the picture to suit how the display is mounted, so that text and
+    /// images come out the right way up with no other changes.
+    ///
+    /// Half turns use the controller's segment remap and COM scan direction settings.
+    /// Segment remap only applies to data written after it is changed, so the current
+    /// [`framebuffer()`](struct.Oled.html#method.framebuffer) is written again
+    /// to show it in the new orientation. This leaves the display in horizontal
+    /// addressing mode, with the text cursor at the top left.
+    ///
+    /// After a quarter turn, [`set_text_xy()`](struct.Oled.html#method.set_text_xy),
+    /// [`put_char()`](struct.Oled.html#method.put_char),
+    /// [`draw_image()`](struct.Oled.html#method.draw_image), [`blit()`](struct.Oled.html#method.blit),
+    /// [`draw_fn()`](struct.Oled.html#method.draw_fn) and [`flush()`](struct.Oled.html#method.flush)
+    /// all work in portrait coordinates, with images 64 pixels wide and 128 high.
+    /// What is already on the display isn't turned along with it, so it should
+    /// be redrawn. Other methods, including
+    /// [`framebuffer()`](struct.Oled.html#method.framebuffer), still use the
+    /// panel's own layout.
+    ///
+    /// Quarter turns are only for 128x64 panels; on others, they return
+    /// `InvalidArgument`, without changing anything.
+    pub fn set_rotation(&mut self, rotation: Rotation) -> OledResult {
+        if rotation.is_portrait() && self.config.size() != PanelSize::Panel128x64 {
+            return Err(OledError::InvalidArgument(
+                "quarter turns are only supported on 128x64 panels",
+            ));
+        }
+        self.rotation = rotation;
+        self.send_orientation()?;
+        self.text_cursor = (0, 0);
+        let buffer = *self.framebuffer();
+        self.write_window((0, (OLED_WIDTH - 1) as u8), (0, OLED_LAST_PAGE), &buffer)
+    }
+
+    /// Sends the segment remap and COM scan direction for the current rotation
+    pub(crate) fn send_orientation(&mut self) -> OledResult {
+        let (segments, scan) = if self.rotation.is_flipped() {
+            (Command::SegmentRemapNormal, Command::ComScanNormal)
+        } else {
+            (Command::SegmentRemapReversed, Command::ComScanRemapped)
+        };
+        self.send_command(segments)?;
+        self.send_command(scan)
+    }
+}
+
+impl<I> Oled<I> {
+    /// Returns the orientation set by [`set_rotation()`](struct.Oled.html#method.set_rotation)
+    pub fn rotation(&self) -> Rotation {
+        self.rotation
+    }
+
+    /// Returns the width and height of the display in its current orientation,
+    /// in pixels, for the panel size set by [`init_with()`](struct.Oled.html#method.init_with)
+    pub fn dimensions(&self) -> (u16, u16) {
+        let size = self.config.size();
+        if self.rotation.is_portrait() {
+            (size.height(), size.width())
+        } else {
+            (size.width(), size.height())
+        }
+    }
+}
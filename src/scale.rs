@@ -0,0 +1,89 @@
+//! Fitting grayscale images of any size to the display
+use crate::dither::IMAGE_SIZE;
+use crate::{Dither, Oled, OledError, OledInterface, OledResult};
+
+/// Most pixels in an image that's decoded, well beyond the display's, to keep
+/// a corrupt or hostile file from using up memory
+#[cfg(any(feature = "gif", feature = "png", feature = "ttf"))]
+pub(crate) const MAX_PIXELS: usize = 1 << 20;
+
+/// Scales a grayscale image `width` by `height` pixels to fit within `out_width` by
+/// `out_height`, keeping its aspect ratio, and centres it on a black background.
+/// Each output pixel is the average of the source pixels it covers.
+pub(crate) fn letterbox(
+    pixels: &[u8],
+    width: usize,
+    height: usize,
+    out_width: usize,
+    out_height: usize,
+) -> Result<[u8; IMAGE_SIZE], OledError> {
+    if width == 0 || height == 0 || pixels.len() != width * height {
+        return Err(OledError::InvalidDimensions);
+    }
+    let (fit_width, fit_height) = if width * out_height <= height * out_width {
+        ((width * out_height / height).max(1), out_height)
+    } else {
+        (out_width, (height * out_width / width).max(1))
+    };
+    let left = (out_width - fit_width) / 2;
+    let top = (out_height - fit_height) / 2;
+    let mut output = [0u8; IMAGE_SIZE];
+    for y in 0..fit_height {
+        let first_row = y * height / fit_height;
+        let last_row = ((y + 1) * height / fit_height).max(first_row + 1);
+        for x in 0..fit_width {
+            let first_column = x * width / fit_width;
+            let last_column = ((x + 1) * width / fit_width).max(first_column + 1);
+            let mut total = 0u32;
+            for row in first_row..last_row {
+                let row = &pixels[row * width..(row + 1) * width];
+                total += row[first_column..last_column]
+                    .iter()
+                    .map(|pixel| u32::from(*pixel))
+                    .sum::<u32>();
+            }
+            let count = ((last_row - first_row) * (last_column - first_column)) as u32;
+            output[(top + y) * out_width + left + x] = (total / count) as u8;
+        }
+    }
+    Ok(output)
+}
+
+impl<I: OledInterface> Oled<I> {
+    /// Draws a grayscale image of any size, scaled to fill as much of the display as
+    /// it can without changing its aspect ratio. Any space left over at the sides or
+    /// top and bottom is black.
+    ///
+    /// `pixels` holds `width` by `height` 8-bit gray levels, row by row, as produced
+    /// by most image decoders (eg `image::DynamicImage::to_luma8()`), and is turned
+    /// into lit pixels using `dither`.
+    /// Returns `InvalidDimensions` if `pixels` is the wrong length for `width` and `height`.
+    /// # Example:
+    /// ```no_run
+    /// # use nanohat_oled::{Dither, Oled, OledResult};
+    /// # fn main() -> OledResult {
+    /// # let photo = vec![0u8; 640 * 480];
+    /// let mut oled = Oled::from_path("/dev/i2c-0")?;
+    /// oled.init()?;
+    /// oled.draw_image_fit(&photo, 640, 480, Dither::FloydSteinberg)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn draw_image_fit(
+        &mut self,
+        pixels: &[u8],
+        width: usize,
+        height: usize,
+        dither: Dither,
+    ) -> OledResult {
+        let (out_width, out_height) = self.dimensions();
+        let image = letterbox(
+            pixels,
+            width,
+            height,
+            out_width as usize,
+            out_height as usize,
+        )?;
+        self.draw_image_dithered(&image, dither)
+    }
+}
@@ -0,0 +1,382 @@
+//! Ready-made animations to show while the display is idle
+use crate::{BlitOp, Framebuffer, Sprite};
+
+/// An animation shown while no one is using the display, drawn a frame at a
+/// time, eg by an [`IdleManager`](struct.IdleManager.html) given it with
+/// [`with_animation()`](struct.IdleManager.html#method.with_animation), which
+/// steps it on every tick.
+///
+/// [`Bouncing`](struct.Bouncing.html), [`Starfield`](struct.Starfield.html) and
+/// [`GameOfLife`](struct.GameOfLife.html) are ready-made, and as they redraw
+/// much of the display on every frame, they also make good tests of how fast
+/// frames get to it.
+/// # Example:
+/// ```
+/// use nanohat_oled::{Framebuffer, Screensaver};
+///
+/// /// A line sweeping down the display
+/// struct Sweep(u16);
+///
+/// impl Screensaver for Sweep {
+///     fn step(&mut self, frame: &mut Framebuffer) {
+///         let (width, height) = frame.dimensions();
+///         frame.clear();
+///         frame.draw_line(0, self.0, width - 1, self.0, true);
+///         self.0 = (self.0 + 1) % height;
+///     }
+/// }
+/// ```
+pub trait Screensaver {
+    /// Draws the next frame of the animation into `frame`. The first time, it
+    /// holds what was on the display, and after that, the frame before.
+    fn step(&mut self, frame: &mut Framebuffer);
+}
+
+/// Seed the animations that look random start from, unless given another
+const DEFAULT_SEED: u32 = 0x2545_f491;
+
+/// A small xorshift generator, as the animations only need to look random
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Rng(u32);
+
+impl Rng {
+    /// A generator starting from `seed`, which may be anything, 0 included
+    fn new(seed: u32) -> Self {
+        Rng(if seed == 0 { DEFAULT_SEED } else { seed })
+    }
+
+    /// The next number
+    fn next(&mut self) -> u32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0
+    }
+
+    /// The next number from `low` up to, but not including, `high`
+    fn range(&mut self, low: i32, high: i32) -> i32 {
+        low + (self.next() % (high - low) as u32) as i32
+    }
+}
+
+/// A [`Screensaver`](trait.Screensaver.html) of a sprite, eg a logo, moving
+/// diagonally and bouncing off the edges of the display, a pixel each way per
+/// step unless given another speed.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{Bouncing, IdleManager, Oled, OledResult, Sprite};
+/// # use std::time::Duration;
+/// # fn main() -> OledResult {
+/// const LOGO: Sprite = Sprite::new(8, 2, &[0xff, 0x81]);
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// let mut idle = IdleManager::new(Duration::from_secs(120)).with_animation(Bouncing::new(LOGO));
+/// idle.tick(&mut oled, Duration::from_millis(50))?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bouncing<'a> {
+    /// What bounces
+    sprite: Sprite<'a>,
+    /// Left edge of the sprite
+    x: i32,
+    /// Top edge of the sprite
+    y: i32,
+    /// Pixels moved right each step, or left if negative
+    dx: i32,
+    /// Pixels moved down each step, or up if negative
+    dy: i32,
+}
+
+impl<'a> Bouncing<'a> {
+    /// Bounces `sprite` around the display, starting from the top left corner
+    pub const fn new(sprite: Sprite<'a>) -> Self {
+        Bouncing {
+            sprite,
+            x: 0,
+            y: 0,
+            dx: 1,
+            dy: 1,
+        }
+    }
+
+    /// Moves the sprite `dx` pixels across and `dy` pixels down each step, to
+    /// start with, rather than one each
+    pub const fn with_speed(self, dx: i16, dy: i16) -> Self {
+        Bouncing {
+            dx: dx as i32,
+            dy: dy as i32,
+            ..self
+        }
+    }
+
+    /// Where the sprite's top left corner is
+    pub fn position(&self) -> (u16, u16) {
+        (self.x as u16, self.y as u16)
+    }
+}
+
+/// Moves `position` on by `speed`, turning back off either end of `0..=max`
+fn bounce(position: &mut i32, speed: &mut i32, max: i32) {
+    *position += *speed;
+    if *position < 0 || *position > max {
+        *speed = -*speed;
+        *position = (*position).clamp(0, max);
+    }
+}
+
+impl Screensaver for Bouncing<'_> {
+    fn step(&mut self, frame: &mut Framebuffer) {
+        let (width, height) = frame.dimensions();
+        let (sprite_width, sprite_height) = self.sprite.dimensions();
+        let max_x = i32::from(width.saturating_sub(sprite_width));
+        let max_y = i32::from(height.saturating_sub(sprite_height));
+        bounce(&mut self.x, &mut self.dx, max_x);
+        bounce(&mut self.y, &mut self.dy, max_y);
+        frame.clear();
+        frame.blit_sprite(&self.sprite, self.x as u16, self.y as u16, BlitOp::Copy);
+    }
+}
+
+/// Number of stars in a [`Starfield`](struct.Starfield.html)
+const STARS: usize = 64;
+
+/// How far away stars appear
+const FARTHEST: i32 = 256;
+
+/// How far a star at a distance of 1 is from the centre of the display for
+/// each unit it is off to the side
+const FOCAL_LENGTH: i32 = 32;
+
+/// A [`Screensaver`](trait.Screensaver.html) of stars streaming out from the
+/// middle of the display, as if flying through them, with nearer stars drawn
+/// bigger.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Starfield {
+    /// Each star's offset across, up and away from the viewer
+    stars: [(i32, i32, i32); STARS],
+    /// Distance each star comes nearer each step
+    speed: i32,
+    /// Where new stars are placed from
+    rng: Rng,
+}
+
+impl Starfield {
+    /// Creates a starfield of stars scattered all the way into the distance
+    pub fn new() -> Self {
+        Self::with_seed(DEFAULT_SEED)
+    }
+
+    /// Creates a starfield with stars scattered starting from `seed`, so
+    /// differently seeded ones look different
+    pub fn with_seed(seed: u32) -> Self {
+        let mut starfield = Starfield {
+            stars: [(0, 0, 0); STARS],
+            speed: 4,
+            rng: Rng::new(seed),
+        };
+        for index in 0..STARS {
+            let depth = starfield.rng.range(1, FARTHEST + 1);
+            starfield.stars[index] = starfield.new_star(depth);
+        }
+        starfield
+    }
+
+    /// Brings the stars `speed` nearer each step, rather than 4, out of their
+    /// farthest of 256
+    pub fn with_speed(self, speed: u8) -> Self {
+        Starfield {
+            speed: i32::from(speed.max(1)),
+            ..self
+        }
+    }
+
+    /// A star `depth` away, somewhere within sight
+    fn new_star(&mut self, depth: i32) -> (i32, i32, i32) {
+        let reach = 64 * FARTHEST / FOCAL_LENGTH;
+        (
+            self.rng.range(-reach, reach),
+            self.rng.range(-reach, reach),
+            depth,
+        )
+    }
+}
+
+impl Default for Starfield {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Screensaver for Starfield {
+    fn step(&mut self, frame: &mut Framebuffer) {
+        let (width, height) = frame.dimensions();
+        let (centre_x, centre_y) = (i32::from(width) / 2, i32::from(height) / 2);
+        frame.clear();
+        for index in 0..STARS {
+            let (x, y, mut depth) = self.stars[index];
+            depth -= self.speed;
+            let screen_x = centre_x + x * FOCAL_LENGTH / depth.max(1);
+            let screen_y = centre_y + y * FOCAL_LENGTH / depth.max(1);
+            let gone = screen_x < 0
+                || screen_y < 0
+                || screen_x >= i32::from(width)
+                || screen_y >= i32::from(height);
+            if depth <= 0 || gone {
+                self.stars[index] = self.new_star(FARTHEST);
+                continue;
+            }
+            self.stars[index].2 = depth;
+            let size = if depth < FARTHEST / 4 { 2 } else { 1 };
+            for dy in 0..size {
+                for dx in 0..size {
+                    frame.plot(screen_x + dx, screen_y + dy, true);
+                }
+            }
+        }
+    }
+}
+
+/// Most cells a [`GameOfLife`](struct.GameOfLife.html) has across and down,
+/// enough for the display either way round
+const LIFE_SIZE: usize = 128;
+
+/// A [`Screensaver`](trait.Screensaver.html) of Conway's Game of Life, one
+/// generation per step, with a cell for each pixel and the edges of the display
+/// wrapping round. Cells are scattered at random to start with, and again once
+/// the pattern settles into one that repeats, or after 1000 generations.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GameOfLife {
+    /// Each row's cells, a bit each, from the least significant
+    cells: [u128; LIFE_SIZE],
+    /// Width and height of the display the cells were scattered for
+    size: (u16, u16),
+    /// Generations since the cells were last scattered
+    generation: u32,
+    /// Generations after which the cells are scattered again
+    limit: u32,
+    /// Hashes of the last two generations, to tell when the pattern repeats
+    history: [u64; 2],
+    /// Where cells are scattered from
+    rng: Rng,
+}
+
+impl GameOfLife {
+    /// Creates a game, with cells scattered when it's first stepped
+    pub fn new() -> Self {
+        Self::with_seed(DEFAULT_SEED)
+    }
+
+    /// Creates a game with cells scattered starting from `seed`, so
+    /// differently seeded games look different
+    pub fn with_seed(seed: u32) -> Self {
+        GameOfLife {
+            cells: [0; LIFE_SIZE],
+            size: (0, 0),
+            generation: 0,
+            limit: 1000,
+            history: [0; 2],
+            rng: Rng::new(seed),
+        }
+    }
+
+    /// Scatters the cells again after `limit` generations, rather than 1000,
+    /// even if the pattern keeps changing
+    pub fn with_limit(self, limit: u32) -> Self {
+        GameOfLife {
+            limit: limit.max(1),
+            ..self
+        }
+    }
+
+    /// Number of generations since the cells were last scattered
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Scatters live cells over a display `width` by `height`, about a quarter of them
+    fn scatter(&mut self, width: u16, height: u16) {
+        let mask = u128::MAX >> (LIFE_SIZE - usize::from(width));
+        for row in 0..LIFE_SIZE {
+            let bits = (0..4).fold(0u128, |bits, _| bits << 32 | u128::from(self.rng.next()));
+            let more = (0..4).fold(0u128, |bits, _| bits << 32 | u128::from(self.rng.next()));
+            self.cells[row] = if row < usize::from(height) {
+                bits & more & mask
+            } else {
+                0
+            };
+        }
+        self.size = (width, height);
+        self.generation = 0;
+        self.history = [0; 2];
+    }
+
+    /// Works out the next generation, on a `width` by `height` board
+    fn advance(&mut self, width: usize, height: usize) {
+        let alive = |x: usize, y: usize| self.cells[y % height] >> (x % width) & 1 == 1;
+        let mut next = [0u128; LIFE_SIZE];
+        for (y, row) in next.iter_mut().enumerate().take(height) {
+            for x in 0..width {
+                // a step back is one all the way round the other way
+                let mut neighbours = 0;
+                for dy in [height - 1, 0, 1] {
+                    for dx in [width - 1, 0, 1] {
+                        if (dx, dy) != (0, 0) && alive(x + dx, y + dy) {
+                            neighbours += 1;
+                        }
+                    }
+                }
+                if neighbours == 3 || (neighbours == 2 && alive(x, y)) {
+                    *row |= 1 << x;
+                }
+            }
+        }
+        self.cells = next;
+        self.generation += 1;
+    }
+
+    /// A hash of the cells, to tell one generation from another
+    fn hash(&self) -> u64 {
+        self.cells.iter().fold(0xcbf2_9ce4_8422_2325, |hash, row| {
+            (hash ^ *row as u64 ^ (*row >> 64) as u64).wrapping_mul(0x0100_0000_01b3)
+        })
+    }
+}
+
+impl Default for GameOfLife {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Screensaver for GameOfLife {
+    fn step(&mut self, frame: &mut Framebuffer) {
+        let (width, height) = frame.dimensions();
+        let (width, height) = (width.min(LIFE_SIZE as u16), height.min(LIFE_SIZE as u16));
+        if width == 0 || height == 0 {
+            return;
+        }
+        if self.size != (width, height) || self.generation >= self.limit {
+            self.scatter(width, height);
+        } else {
+            self.advance(usize::from(width), usize::from(height));
+            let hash = self.hash();
+            // still, or blinking between two patterns
+            if self.history.contains(&hash) {
+                self.scatter(width, height);
+            } else {
+                self.history = [self.history[1], hash];
+            }
+        }
+        frame.clear();
+        for y in 0..height {
+            let row = self.cells[usize::from(y)];
+            for x in 0..width {
+                if row >> x & 1 == 1 {
+                    frame.set_pixel(x, y, true);
+                }
+            }
+        }
+    }
+}
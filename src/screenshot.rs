@@ -0,0 +1,274 @@
+//! Saving a framebuffer as an image, for screenshots and golden-image tests
+use alloc::{format, string::String, vec, vec::Vec};
+use core::fmt::Write;
+#[cfg(feature = "png")]
+use std::{fs, io, path::Path};
+
+#[cfg(feature = "png")]
+use crate::png_decode::PNG_SIGNATURE;
+use crate::{Framebuffer, OledError, OLED_HEIGHT, OLED_WIDTH};
+
+/// Characters for a lit and an unlit pixel in a text snapshot
+const SNAPSHOT_PIXELS: [char; 2] = ['.', '#'];
+
+/// Checks that two framebuffers show the same picture, and panics otherwise,
+/// listing the rows that differ and then the whole of the first as a
+/// snapshot, as [`Framebuffer::diff()`](struct.Framebuffer.html#method.diff)
+/// describes them, so that if the change is what was wanted, the new snapshot
+/// can be copied from the test's output.
+///
+/// Along with [`Framebuffer::from_snapshot()`](struct.Framebuffer.html#method.from_snapshot),
+/// this checks rendered screens against snapshots committed alongside the
+/// tests, without hardware, eg in CI.
+/// # Example:
+/// ```ignore
+/// use nanohat_oled::{assert_frame_eq, Framebuffer};
+///
+/// let mut frame = Framebuffer::new();
+/// menu.render(&mut frame, Rect::new(0, 0, 128, 64));
+/// let expected = Framebuffer::from_snapshot(include_str!("snapshots/menu.txt")).unwrap();
+/// assert_frame_eq!(frame, expected);
+/// ```
+#[macro_export]
+macro_rules! assert_frame_eq {
+    ($actual:expr, $expected:expr $(,)?) => {
+        if let Some(diff) = $crate::Framebuffer::diff(&$actual, &$expected) {
+            panic!("frames differ\n{}", diff);
+        }
+    };
+}
+
+impl Framebuffer {
+    /// The rows of pixels, packed eight to a byte, most significant bit first,
+    /// with set bits for `lit` pixels and clear bits for the rest
+    fn packed_rows(&self, lit: bool) -> impl Iterator<Item = Vec<u8>> + '_ {
+        let (width, height) = self.dimensions();
+        (0..height).map(move |y| {
+            let mut row = vec![0u8; usize::from(width).div_ceil(8)];
+            for x in 0..width {
+                if self.get_pixel(x, y) == lit {
+                    row[usize::from(x / 8)] |= 0x80 >> (x % 8);
+                }
+            }
+            row
+        })
+    }
+
+    /// The framebuffer as a binary PBM (`P4`) image, the way round it's drawn,
+    /// with lit pixels white and unlit ones black, like the display shows them.
+    ///
+    /// Since the display's RAM can't be read back over I2C, this is how to see
+    /// exactly what's shown, eg copied from
+    /// [`Oled::framebuffer()`](struct.Oled.html#method.framebuffer) with
+    /// [`from_bytes()`](struct.Framebuffer.html#method.from_bytes).
+    /// # Example:
+    /// ```
+    /// use nanohat_oled::Framebuffer;
+    ///
+    /// let mut frame = Framebuffer::new();
+    /// frame.set_pixel(0, 0, true);
+    /// let pbm = frame.to_pbm();
+    /// assert!(pbm.starts_with(b"P4\n128 64\n"));
+    /// // in PBM, set bits are black
+    /// assert_eq!(pbm[10], 0x7f);
+    /// ```
+    pub fn to_pbm(&self) -> Vec<u8> {
+        let (width, height) = self.dimensions();
+        let mut pbm = format!("P4\n{} {}\n", width, height).into_bytes();
+        for row in self.packed_rows(false) {
+            pbm.extend(row);
+        }
+        pbm
+    }
+
+    /// The framebuffer as text, the way round it's drawn, a line for each row
+    /// of pixels with `#` for each lit pixel and `.` for each unlit one, eg to
+    /// commit as a snapshot that tests compare rendered screens against, where
+    /// a change to one shows up in a diff as the rows it changed.
+    /// # Example:
+    /// ```
+    /// use nanohat_oled::Framebuffer;
+    ///
+    /// let mut frame = Framebuffer::new();
+    /// frame.fill_rect(1, 0, 2, 1, true);
+    /// let snapshot = frame.to_snapshot();
+    /// assert!(snapshot.starts_with(".##....."));
+    /// assert_eq!(snapshot.lines().count(), 64);
+    /// assert_eq!(Framebuffer::from_snapshot(&snapshot).unwrap(), frame);
+    /// ```
+    pub fn to_snapshot(&self) -> String {
+        let (width, height) = self.dimensions();
+        let mut snapshot = String::with_capacity((usize::from(width) + 1) * usize::from(height));
+        for y in 0..height {
+            snapshot.extend((0..width).map(|x| SNAPSHOT_PIXELS[usize::from(self.get_pixel(x, y))]));
+            snapshot.push('\n');
+        }
+        snapshot
+    }
+
+    /// Creates a framebuffer from a snapshot as written by
+    /// [`to_snapshot()`](struct.Framebuffer.html#method.to_snapshot): 64 lines
+    /// of 128 pixels, or 128 lines of 64 for a
+    /// [`portrait()`](struct.Framebuffer.html#method.portrait) one. Blank lines
+    /// before and after it, and spaces at the ends of lines, are left out.
+    ///
+    /// Returns `InvalidDimensions` if the snapshot isn't one of these sizes,
+    /// and `InvalidArgument` if it holds anything but `#` and `.`.
+    pub fn from_snapshot(snapshot: &str) -> Result<Self, OledError> {
+        let lines: Vec<&str> = snapshot.trim().lines().map(str::trim_end).collect();
+        let width = lines.first().map_or(0, |line| line.chars().count());
+        let mut frame = match (width, lines.len()) {
+            (width, height)
+                if width == usize::from(OLED_WIDTH) && height == usize::from(OLED_HEIGHT) =>
+            {
+                Framebuffer::new()
+            }
+            (width, height)
+                if width == usize::from(OLED_HEIGHT) && height == usize::from(OLED_WIDTH) =>
+            {
+                Framebuffer::portrait()
+            }
+            _ => return Err(OledError::InvalidDimensions),
+        };
+        for (y, line) in lines.iter().enumerate() {
+            if line.chars().count() != width {
+                return Err(OledError::InvalidDimensions);
+            }
+            for (x, pixel) in line.chars().enumerate() {
+                match SNAPSHOT_PIXELS.iter().position(|lit| *lit == pixel) {
+                    Some(lit) => frame.set_pixel(x as u16, y as u16, lit == 1),
+                    None => {
+                        return Err(OledError::InvalidArgument(
+                            "snapshots may only hold '#' for lit pixels and '.' for unlit ones",
+                        ))
+                    }
+                }
+            }
+        }
+        Ok(frame)
+    }
+
+    /// Describes how the framebuffer differs from `expected`, or returns `None`
+    /// if they show the same picture: each row that differs, as snapshot text,
+    /// with the columns that differ marked under it, and then the whole of this
+    /// framebuffer as a [snapshot](struct.Framebuffer.html#method.to_snapshot).
+    /// See [`assert_frame_eq!`](macro.assert_frame_eq.html).
+    pub fn diff(&self, expected: &Framebuffer) -> Option<String> {
+        let (width, height) = self.dimensions();
+        let mut diff = String::new();
+        if expected.dimensions() != (width, height) {
+            let (expected_width, expected_height) = expected.dimensions();
+            let _ = writeln!(
+                diff,
+                "frame is {}x{}, but {}x{} was expected",
+                width, height, expected_width, expected_height
+            );
+        } else {
+            let row = |frame: &Framebuffer, y| -> String {
+                (0..width)
+                    .map(|x| SNAPSHOT_PIXELS[usize::from(frame.get_pixel(x, y))])
+                    .collect()
+            };
+            for y in 0..height {
+                let (actual_row, expected_row) = (row(self, y), row(expected, y));
+                if actual_row == expected_row {
+                    continue;
+                }
+                let marks: String = actual_row
+                    .chars()
+                    .zip(expected_row.chars())
+                    .map(|(actual, expected)| if actual == expected { ' ' } else { '^' })
+                    .collect();
+                let _ = writeln!(
+                    diff,
+                    "row {}:\n  frame:    {}\n  expected: {}\n            {}",
+                    y,
+                    actual_row,
+                    expected_row,
+                    marks.trim_end()
+                );
+            }
+            if diff.is_empty() {
+                return None;
+            }
+        }
+        let _ = write!(diff, "frame:\n{}", self.to_snapshot());
+        Some(diff)
+    }
+
+    /// Saves the framebuffer as a 1-bit grayscale PNG image at `path`, the way
+    /// round it's drawn, with lit pixels white and unlit ones black, as
+    /// [`to_pbm()`](struct.Framebuffer.html#method.to_pbm) does.
+    ///
+    /// The image data is stored uncompressed, which at this size costs about a
+    /// kilobyte.
+    #[cfg(feature = "png")]
+    pub fn to_png<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let (width, height) = self.dimensions();
+        let mut header = Vec::with_capacity(13);
+        header.extend(u32::from(width).to_be_bytes());
+        header.extend(u32::from(height).to_be_bytes());
+        // 1-bit grayscale, deflate compression, filtering and no interlacing
+        header.extend([1, 0, 0, 0, 0]);
+        // each row starts with its filter type, none
+        let mut pixels = Vec::new();
+        for row in self.packed_rows(true) {
+            pixels.push(0);
+            pixels.extend(row);
+        }
+        let mut png = PNG_SIGNATURE.to_vec();
+        write_chunk(&mut png, b"IHDR", &header);
+        write_chunk(&mut png, b"IDAT", &zlib_stored(&pixels));
+        write_chunk(&mut png, b"IEND", &[]);
+        fs::write(path, png)
+    }
+}
+
+/// Appends a PNG chunk of `kind` holding `data`, with its length and checksum
+#[cfg(feature = "png")]
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend((data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend(kind);
+    png.extend(data);
+    let crc = crc32(&png[start..]);
+    png.extend(crc.to_be_bytes());
+}
+
+/// `data` in a zlib stream of uncompressed deflate blocks
+#[cfg(feature = "png")]
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    // deflate with a 32K window, and no preset dictionary
+    let mut zlib = vec![0x78, 0x01];
+    let blocks = data.chunks(usize::from(u16::MAX));
+    let count = blocks.len();
+    for (index, block) in blocks.enumerate() {
+        zlib.push(u8::from(index + 1 == count));
+        let len = block.len() as u16;
+        zlib.extend(len.to_le_bytes());
+        zlib.extend((!len).to_le_bytes());
+        zlib.extend(block);
+    }
+    zlib.extend(adler32(data).to_be_bytes());
+    zlib
+}
+
+/// The CRC-32 of `data`, as PNG chunks are checked with
+#[cfg(feature = "png")]
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, byte| {
+        (0..8).fold(crc ^ u32::from(*byte), |crc, _| {
+            (crc >> 1) ^ (0xedb8_8320 & (crc & 1).wrapping_neg())
+        })
+    })
+}
+
+/// The Adler-32 checksum of `data`, as zlib streams end with
+#[cfg(feature = "png")]
+fn adler32(data: &[u8]) -> u32 {
+    let (a, b) = data.iter().fold((1u32, 0u32), |(a, b), byte| {
+        let a = (a + u32::from(*byte)) % 65521;
+        (a, (b + a) % 65521)
+    });
+    b << 16 | a
+}
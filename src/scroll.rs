@@ -0,0 +1,212 @@
+//! Continuous scrolling, performed by the display controller itself
+use crate::{Command, Oled, OledError, OledInterface, OledResult, OLED_HEIGHT, OLED_LAST_PAGE};
+
+/// The direction content moves in while scrolling horizontally
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScrollDirection {
+    /// Content moves towards the right edge
+    Right,
+    /// Content moves towards the left edge
+    Left,
+}
+
+/// The time between scroll steps, in frames.
+/// The frame rate depends on the clock divide setting;
+/// with the settings in [`Oled::init()`](struct.Oled.html#method.init), it is around 100 Hz.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScrollInterval {
+    /// 2 frames between steps
+    Frames2,
+    /// 3 frames between steps
+    Frames3,
+    /// 4 frames between steps
+    Frames4,
+    /// 5 frames between steps
+    Frames5,
+    /// 25 frames between steps
+    Frames25,
+    /// 64 frames between steps
+    Frames64,
+    /// 128 frames between steps
+    Frames128,
+    /// 256 frames between steps
+    Frames256,
+}
+
+impl From<ScrollInterval> for u8 {
+    fn from(interval: ScrollInterval) -> u8 {
+        match interval {
+            ScrollInterval::Frames2 => 0x07,
+            ScrollInterval::Frames3 => 0x04,
+            ScrollInterval::Frames4 => 0x05,
+            ScrollInterval::Frames5 => 0x00,
+            ScrollInterval::Frames25 => 0x06,
+            ScrollInterval::Frames64 => 0x01,
+            ScrollInterval::Frames128 => 0x02,
+            ScrollInterval::Frames256 => 0x03,
+        }
+    }
+}
+
+/// The rows that take part in vertical scrolling.
+///
+/// The top `fixed_rows` rows stay still, the next `scroll_rows` rows scroll, and
+/// any rows below those also stay still. The two must not add up to more than the
+/// multiplex ratio, which is the 64 rows of the display as set up by
+/// [`Oled::init()`](struct.Oled.html#method.init), and the panel's height, or
+/// the ratio chosen, as set up by
+/// [`Oled::init_with()`](struct.Oled.html#method.init_with).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VerticalScrollArea {
+    /// Number of rows at the top of the display that don't scroll
+    pub fixed_rows: u8,
+    /// Number of rows, below the fixed ones, that scroll
+    pub scroll_rows: u8,
+}
+
+impl VerticalScrollArea {
+    /// The whole of a 128x64 display scrolls. Smaller panels need an area of
+    /// no more rows than they have.
+    pub const FULL_SCREEN: VerticalScrollArea = VerticalScrollArea {
+        fixed_rows: 0,
+        scroll_rows: OLED_HEIGHT as u8,
+    };
+}
+
+/// Checks that a range of pages is valid for a scroll command
+fn check_pages(start_page: u8, end_page: u8) -> OledResult {
+    if start_page > OLED_LAST_PAGE || end_page > OLED_LAST_PAGE {
+        return Err(OledError::InvalidArgument(
+            "scroll pages must be between 0 and 7",
+        ));
+    }
+    if start_page > end_page {
+        return Err(OledError::InvalidArgument(
+            "scroll start page must not be after end page",
+        ));
+    }
+    Ok(())
+}
+
+impl<I: OledInterface> Oled<I> {
+    /// Starts continuously scrolling pages `start_page` to `end_page` (inclusive)
+    /// horizontally, one column each step, in `direction`. Content scrolling off one
+    /// edge reappears at the other.
+    ///
+    /// Any scroll already in progress is stopped first. Avoid writing to display RAM
+    /// while scrolling; call [`stop_scroll()`](struct.Oled.html#method.stop_scroll) first.
+    /// # Example:
+    /// ```no_run
+    /// # use nanohat_oled::{Oled, OledResult, ScrollDirection, ScrollInterval};
+    /// # fn main() -> OledResult {
+    /// let mut oled = Oled::from_path("/dev/i2c-0")?;
+    /// oled.init()?;
+    /// oled.put_string("Now playing: ...")?;
+    /// // ticker along the top text row
+    /// oled.start_horizontal_scroll(ScrollDirection::Left, 0, 0, ScrollInterval::Frames5)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn start_horizontal_scroll(
+        &mut self,
+        direction: ScrollDirection,
+        start_page: u8,
+        end_page: u8,
+        interval: ScrollInterval,
+    ) -> OledResult {
+        check_pages(start_page, end_page)?;
+        self.stop_scroll()?;
+        self.send_command(match direction {
+            ScrollDirection::Right => Command::RightHorizontalScroll,
+            ScrollDirection::Left => Command::LeftHorizontalScroll,
+        })?;
+        self.send_command(0x00)?; // dummy byte
+        self.send_command(start_page)?;
+        self.send_command(interval)?;
+        self.send_command(end_page)?;
+        self.send_command(0x00)?; // dummy byte
+        self.send_command(0xff)?; // dummy byte
+        self.send_command(Command::ActivateScroll)?;
+        Ok(())
+    }
+
+    /// Starts scrolling the whole display diagonally: up by `vertical_offset` rows and
+    /// one column in `direction` each step, with pages `start_page` to `end_page` taking
+    /// part in the horizontal movement. This is
+    /// [`start_vertical_horizontal_scroll()`](struct.Oled.html#method.start_vertical_horizontal_scroll)
+    /// with a [`VerticalScrollArea::FULL_SCREEN`](struct.VerticalScrollArea.html#associatedconstant.FULL_SCREEN)
+    /// area.
+    pub fn start_diagonal_scroll(
+        &mut self,
+        direction: ScrollDirection,
+        start_page: u8,
+        end_page: u8,
+        interval: ScrollInterval,
+        vertical_offset: u8,
+    ) -> OledResult {
+        self.start_vertical_horizontal_scroll(
+            VerticalScrollArea::FULL_SCREEN,
+            direction,
+            start_page,
+            end_page,
+            interval,
+            vertical_offset,
+        )
+    }
+
+    /// Starts continuously scrolling content both vertically and horizontally,
+    /// for a diagonal ticker effect.
+    ///
+    /// `area` sets which rows scroll vertically, moving up by `vertical_offset` rows each
+    /// step. Pages `start_page` to `end_page` (inclusive) also scroll horizontally by one
+    /// column each step, in `direction`. `vertical_offset` must be less than
+    /// `area.scroll_rows`.
+    ///
+    /// Any scroll already in progress is stopped first. Avoid writing to display RAM
+    /// while scrolling; call [`stop_scroll()`](struct.Oled.html#method.stop_scroll) first.
+    pub fn start_vertical_horizontal_scroll(
+        &mut self,
+        area: VerticalScrollArea,
+        direction: ScrollDirection,
+        start_page: u8,
+        end_page: u8,
+        interval: ScrollInterval,
+        vertical_offset: u8,
+    ) -> OledResult {
+        check_pages(start_page, end_page)?;
+        let multiplex_ratio = u16::from(self.config.multiplex_ratio());
+        if u16::from(area.fixed_rows) + u16::from(area.scroll_rows) > multiplex_ratio {
+            return Err(OledError::InvalidArgument(
+                "vertical scroll area must fit within the multiplex ratio",
+            ));
+        }
+        if vertical_offset >= area.scroll_rows {
+            return Err(OledError::InvalidArgument(
+                "vertical scroll offset must be less than the scrolling rows",
+            ));
+        }
+        self.stop_scroll()?;
+        self.send_command(Command::SetVerticalScrollArea)?;
+        self.send_command(area.fixed_rows)?;
+        self.send_command(area.scroll_rows)?;
+        self.send_command(match direction {
+            ScrollDirection::Right => Command::VerticalRightHorizontalScroll,
+            ScrollDirection::Left => Command::VerticalLeftHorizontalScroll,
+        })?;
+        self.send_command(0x00)?; // dummy byte
+        self.send_command(start_page)?;
+        self.send_command(interval)?;
+        self.send_command(end_page)?;
+        self.send_command(vertical_offset)?;
+        self.send_command(Command::ActivateScroll)?;
+        Ok(())
+    }
+
+    /// Stops any scrolling in progress. The display RAM needs to be rewritten
+    /// afterwards, since scrolling leaves it in an unspecified state; the copy in
+    /// [`framebuffer()`](struct.Oled.html#method.framebuffer) still holds what was
+    /// last written, so it can be sent again.
+    pub fn stop_scroll(&mut self) -> OledResult {
+        self.send_command(Command::DeactivateScroll)
+    }
+}
@@ -0,0 +1,169 @@
+//! Sharing a display between threads, with drawing sent on in batches
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::{Framebuffer, Oled, OledError, OledInterface, OledResult};
+
+/// The display, and what has been drawn for it but not yet sent
+struct Inner<I> {
+    /// The display
+    oled: Oled<I>,
+    /// What the display is to show, once drawn on since the last flush
+    pending: Option<Framebuffer>,
+}
+
+impl<I: OledInterface> Inner<I> {
+    /// Sends what has been drawn since the last flush, if anything
+    fn flush(&mut self) -> OledResult {
+        match self.pending.take() {
+            Some(frame) => self.oled.flush(&frame),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A handle to a display that any number of threads can draw on, eg a sensor
+/// poller, a button handler and a logger, without wrapping the
+/// [`Oled`](struct.Oled.html) in a mutex of their own. Cloning it gives another
+/// handle to the same display.
+///
+/// [`draw()`](#method.draw) doesn't touch the bus: it draws into a frame held
+/// until the next [`flush()`](#method.flush), so that many small updates from
+/// different threads go out as one. Flushing can be left to a thread started
+/// with [`spawn_flusher()`](#method.spawn_flusher), which sends whatever has
+/// changed at a steady rate.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{Oled, OledResult, SharedOled};
+/// # use std::{thread, time::Duration};
+/// # fn main() -> OledResult {
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// let shared = SharedOled::new(oled);
+/// let flusher = shared.spawn_flusher(Duration::from_millis(100));
+/// let poller = shared.clone();
+/// thread::spawn(move || loop {
+///     poller.draw(|frame| frame.fill_rect(0, 0, 4, 4, true));
+///     thread::sleep(Duration::from_secs(1));
+/// });
+/// shared.draw(|frame| frame.draw_rect(0, 0, 128, 64, true));
+/// thread::sleep(Duration::from_secs(10));
+/// flusher.stop()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct SharedOled<I> {
+    /// The display, shared by every handle
+    inner: Arc<Mutex<Inner<I>>>,
+}
+
+impl<I> Clone for SharedOled<I> {
+    fn clone(&self) -> Self {
+        SharedOled {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<I: OledInterface> SharedOled<I> {
+    /// Shares `oled`, which should already be set up with
+    /// [`init()`](struct.Oled.html#method.init)
+    pub fn new(oled: Oled<I>) -> Self {
+        SharedOled {
+            inner: Arc::new(Mutex::new(Inner {
+                oled,
+                pending: None,
+            })),
+        }
+    }
+
+    /// Locks the display, carrying on if another thread panicked holding it
+    fn lock(&self) -> MutexGuard<'_, Inner<I>> {
+        self.inner.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Draws on the frame to be sent at the next flush, as
+    /// [`Oled::draw()`](struct.Oled.html#method.draw) does, without sending
+    /// anything. `f` is handed what the display will show once everything
+    /// drawn so far is sent, in its current orientation.
+    pub fn draw<F: FnOnce(&mut Framebuffer)>(&self, f: F) {
+        let mut inner = self.lock();
+        let Inner { oled, pending } = &mut *inner;
+        let frame = pending.get_or_insert_with(|| {
+            Framebuffer::with_layout(*oled.framebuffer(), oled.rotation().is_portrait())
+        });
+        f(frame);
+    }
+
+    /// Sends whatever has been drawn since the last flush, if anything
+    pub fn flush(&self) -> OledResult {
+        self.lock().flush()
+    }
+
+    /// Calls `f` with the display itself, eg to set the contrast or write text in
+    /// the built-in font, after sending whatever has been drawn, so that `f`
+    /// sees it on the display. Other threads wait until `f` returns.
+    pub fn with<R, F: FnOnce(&mut Oled<I>) -> R>(&self, f: F) -> Result<R, OledError> {
+        let mut inner = self.lock();
+        inner.flush()?;
+        Ok(f(&mut inner.oled))
+    }
+
+    /// Returns the display, if this is the last handle to it and no flusher
+    /// is running, or the handle otherwise. Anything drawn but not yet
+    /// flushed isn't sent.
+    pub fn try_unwrap(self) -> Result<Oled<I>, Self> {
+        match Arc::try_unwrap(self.inner) {
+            Ok(inner) => Ok(inner
+                .into_inner()
+                .unwrap_or_else(|err| err.into_inner())
+                .oled),
+            Err(inner) => Err(SharedOled { inner }),
+        }
+    }
+}
+
+impl<I: OledInterface + Send + 'static> SharedOled<I> {
+    /// Starts a thread that flushes the display every `interval`, so that what
+    /// is drawn shows up within that time, however many draws it took. The
+    /// thread runs until the returned [`Flusher`](struct.Flusher.html) is
+    /// stopped or dropped, or a flush fails.
+    pub fn spawn_flusher(&self, interval: Duration) -> Flusher {
+        let (stop, stopped) = channel::<()>();
+        let shared = self.clone();
+        let thread = thread::spawn(move || loop {
+            let last = matches!(
+                stopped.recv_timeout(interval),
+                Err(RecvTimeoutError::Disconnected)
+            );
+            shared.flush()?;
+            if last {
+                return Ok(());
+            }
+        });
+        Flusher { stop, thread }
+    }
+}
+
+/// A thread flushing a [`SharedOled`](struct.SharedOled.html), as started by
+/// [`spawn_flusher()`](struct.SharedOled.html#method.spawn_flusher). Dropping it
+/// stops the thread, after a last flush, without waiting for it.
+pub struct Flusher {
+    /// Hung up to stop the thread
+    stop: Sender<()>,
+    /// The thread
+    thread: JoinHandle<OledResult>,
+}
+
+impl Flusher {
+    /// Stops the thread after a last flush, so everything drawn so far is
+    /// sent, and waits for it. Returns the error it stopped on, if a flush
+    /// failed, or `Interface` if it panicked.
+    pub fn stop(self) -> OledResult {
+        let Flusher { stop, thread } = self;
+        drop(stop);
+        thread.join().unwrap_or(Err(OledError::Interface))
+    }
+}
@@ -0,0 +1,422 @@
+//! An interface that shows the display in a desktop window, over the X11 protocol
+use std::env;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+
+use crate::state::DisplayState;
+use crate::{OledError, OledInterface, OLED_HEIGHT, OLED_WIDTH};
+
+/// The only X11 authorization scheme supported
+const MIT_MAGIC_COOKIE: &[u8] = b"MIT-MAGIC-COOKIE-1";
+
+/// The predefined `WM_NAME` atom, for the window's title
+const WM_NAME: u32 = 39;
+
+/// The predefined `STRING` atom
+const STRING: u32 = 31;
+
+/// The window's title
+const TITLE: &[u8] = b"NanoHat OLED";
+
+/// Length of the fixed part of a `PutImage` request, in bytes
+const PUT_IMAGE_HEADER: usize = 24;
+
+/// What's needed from the X server's reply to a new connection
+struct Setup {
+    /// First of the resource IDs the connection can allocate
+    id_base: u32,
+    /// Longest request the server accepts, in bytes
+    max_request_len: usize,
+    /// Whether the server wants the most significant byte of each bitmap unit first
+    msb_bytes: bool,
+    /// Whether the server wants the leftmost pixel of a bitmap unit in its
+    /// most significant bit
+    msb_bits: bool,
+    /// Bits in each bitmap unit
+    scanline_unit: usize,
+    /// Bits each bitmap row is padded to
+    scanline_pad: usize,
+    /// The first screen's root window
+    root: u32,
+    /// The first screen's depth
+    root_depth: u8,
+    /// Pixel values for white and black on the first screen
+    white: u32,
+    black: u32,
+}
+
+impl Setup {
+    /// The byte of a bitmap row, and the bit of that byte, holding pixel `x`
+    fn bit_position(&self, x: usize) -> (usize, u8) {
+        let unit = self.scanline_unit;
+        let bit = if self.msb_bits {
+            unit - 1 - x % unit
+        } else {
+            x % unit
+        };
+        let byte = if self.msb_bytes {
+            unit / 8 - 1 - bit / 8
+        } else {
+            bit / 8
+        };
+        (x / unit * unit / 8 + byte, (bit % 8) as u8)
+    }
+}
+
+/// An [`OledInterface`](trait.OledInterface.html) with no display behind it,
+/// which instead shows what the display would, pixel for pixel, in a desktop
+/// window `scale` times its size. Like
+/// [`TerminalSimulator`](struct.TerminalSimulator.html), it follows the commands
+/// sent, so turning the display on and off, inverting and rotating it all show,
+/// and the same `Oled` code can be developed and demonstrated on a laptop.
+///
+/// The window is drawn with the X11 protocol, so needs an X server or XWayland,
+/// found from the `DISPLAY` environment variable, along with its
+/// `MIT-MAGIC-COOKIE-1` from `XAUTHORITY` or `~/.Xauthority`, if any. Closing
+/// the window ends the connection, so anything sent afterwards fails.
+/// # Example:
+/// ```no_run
+/// use nanohat_oled::{Oled, OledResult, WindowSimulator};
+///
+/// fn main() -> OledResult {
+///     let mut oled = Oled::new(WindowSimulator::open(4)?);
+///     oled.init()?;
+///     oled.put_string("Hello, world!")?;
+///     Ok(())
+/// }
+/// ```
+pub struct WindowSimulator {
+    /// Connection to the X server
+    connection: Box<dyn Write + Send>,
+    /// What's needed from the server's setup
+    setup: Setup,
+    /// The window IDs: the window, the pixmap holding its picture, and the
+    /// graphics context the picture is drawn with
+    ids: (u32, u32, u32),
+    /// Size of each pixel of the display, in pixels of the window
+    scale: u16,
+    /// The controller, as commanded so far
+    state: DisplayState,
+    /// Which pixels were lit when the picture was last drawn, a row at a time
+    shown: Option<Vec<bool>>,
+}
+
+impl WindowSimulator {
+    /// Opens a window `scale` times the size of the display, eg 4 for 512 by 256
+    /// pixels, on the X server named by the `DISPLAY` environment variable.
+    ///
+    /// Returns `InvalidArgument` if `DISPLAY` isn't set or `scale` isn't from 1
+    /// to 511, and `I2c` if the server can't be reached or refuses the connection.
+    pub fn open(scale: u16) -> Result<Self, OledError> {
+        let display = env::var("DISPLAY")
+            .map_err(|_| OledError::InvalidArgument("DISPLAY isn't set to an X server"))?;
+        WindowSimulator::open_display(&display, scale)
+    }
+
+    /// Opens a window `scale` times the size of the display on the X server
+    /// `display`, as `DISPLAY` names it: `:0` for a local server, or eg
+    /// `localhost:10` for one on TCP port 6010, as forwarded by `ssh -X`.
+    pub fn open_display(display: &str, scale: u16) -> Result<Self, OledError> {
+        let (host, number) = match display.rsplit_once(':') {
+            Some((host, number)) => (host, number.split('.').next().unwrap_or_default()),
+            None => return Err(OledError::InvalidArgument("X display must be host:number")),
+        };
+        let number: u16 = number
+            .parse()
+            .map_err(|_| OledError::InvalidArgument("X display must be host:number"))?;
+        if scale == 0 || OLED_WIDTH.checked_mul(scale).is_none() {
+            return Err(OledError::InvalidArgument("scale must be from 1 to 511"));
+        }
+        let (mut reader, mut writer): (Box<dyn Read>, Box<dyn Write + Send>) =
+            match host.strip_prefix("unix").unwrap_or(host) {
+                #[cfg(unix)]
+                "" => {
+                    let stream = UnixStream::connect(format!("/tmp/.X11-unix/X{}", number))?;
+                    (Box::new(stream.try_clone()?), Box::new(stream))
+                }
+                host => {
+                    let stream = TcpStream::connect((host, 6000 + number))?;
+                    stream.set_nodelay(true)?;
+                    (Box::new(stream.try_clone()?), Box::new(stream))
+                }
+            };
+        let setup = connect(&mut reader, &mut writer, cookie(number))?;
+        let mut simulator = WindowSimulator {
+            connection: writer,
+            setup,
+            ids: (0, 0, 0),
+            scale,
+            state: DisplayState::default(),
+            shown: None,
+        };
+        simulator.create_window()?;
+        simulator.redraw()?;
+        Ok(simulator)
+    }
+
+    /// Creates and shows the window, and the pixmap and graphics context its
+    /// picture is drawn with
+    fn create_window(&mut self) -> Result<(), OledError> {
+        let setup = &self.setup;
+        let (window, pixmap, gc) = (setup.id_base | 1, setup.id_base | 2, setup.id_base | 3);
+        let (width, height) = (OLED_WIDTH * self.scale, OLED_HEIGHT * self.scale);
+        let mut requests = Request::new(1, 0)
+            .u32(window)
+            .u32(setup.root)
+            .u16(0)
+            .u16(0)
+            .u16(width)
+            .u16(height)
+            // no border, an input and output window, with the parent's visual
+            .u16(0)
+            .u16(1)
+            .u32(0)
+            // with a black background
+            .u32(0x02)
+            .u32(setup.black)
+            .finish();
+        requests.extend(
+            Request::new(18, 0)
+                .u32(window)
+                .u32(WM_NAME)
+                .u32(STRING)
+                .u32(8)
+                .u32(TITLE.len() as u32)
+                .bytes(TITLE)
+                .finish(),
+        );
+        requests.extend(
+            Request::new(53, setup.root_depth)
+                .u32(pixmap)
+                .u32(window)
+                .u16(width)
+                .u16(height)
+                .finish(),
+        );
+        // with white for lit pixels, and black for the rest
+        requests.extend(
+            Request::new(55, 0)
+                .u32(gc)
+                .u32(pixmap)
+                .u32(0x04 | 0x08)
+                .u32(setup.white)
+                .u32(setup.black)
+                .finish(),
+        );
+        requests.extend(Request::new(8, 0).u32(window).finish());
+        self.connection.write_all(&requests)?;
+        self.ids = (window, pixmap, gc);
+        Ok(())
+    }
+
+    /// Draws the picture again, if it has changed since it was last drawn
+    fn redraw(&mut self) -> Result<(), OledError> {
+        let lit: Vec<bool> = (0..OLED_HEIGHT)
+            .flat_map(|y| (0..OLED_WIDTH).map(move |x| (x, y)))
+            .map(|(x, y)| self.state.lit(x, y))
+            .collect();
+        if self.shown.as_ref() == Some(&lit) {
+            return Ok(());
+        }
+        let (window, pixmap, gc) = self.ids;
+        let scale = usize::from(self.scale);
+        let width = usize::from(OLED_WIDTH) * scale;
+        let row_len = width.div_ceil(self.setup.scanline_pad) * self.setup.scanline_pad / 8;
+        let rows_per_request =
+            ((self.setup.max_request_len - PUT_IMAGE_HEADER) / row_len).clamp(1, u16::MAX.into());
+        let mut bitmap = Vec::with_capacity(row_len * usize::from(OLED_HEIGHT) * scale);
+        for y in 0..usize::from(OLED_HEIGHT) * scale {
+            let mut row = vec![0u8; row_len];
+            for x in 0..width {
+                if lit[y / scale * usize::from(OLED_WIDTH) + x / scale] {
+                    let (byte, bit) = self.setup.bit_position(x);
+                    row[byte] |= 1 << bit;
+                }
+            }
+            bitmap.extend(row);
+        }
+        let mut requests = Vec::new();
+        for (strip, rows) in bitmap.chunks(row_len * rows_per_request).enumerate() {
+            // a bitmap, drawn in the foreground and background colours
+            requests.extend(
+                Request::new(72, 0)
+                    .u32(pixmap)
+                    .u32(gc)
+                    .u16(width as u16)
+                    .u16((rows.len() / row_len) as u16)
+                    .u16(0)
+                    .u16((strip * rows_per_request) as u16)
+                    .u16(1 << 8)
+                    .u16(0)
+                    .bytes(rows)
+                    .finish(),
+            );
+        }
+        // the server may have copied the pixmap when it became the background,
+        // so it's set again, and the window cleared to it
+        requests.extend(
+            Request::new(2, 0)
+                .u32(window)
+                .u32(0x01)
+                .u32(pixmap)
+                .finish(),
+        );
+        requests.extend(Request::new(61, 0).u32(window).u32(0).u32(0).finish());
+        self.connection.write_all(&requests)?;
+        self.connection.flush()?;
+        self.shown = Some(lit);
+        Ok(())
+    }
+}
+
+impl OledInterface for WindowSimulator {
+    fn send_command(&mut self, command: u8) -> Result<(), OledError> {
+        self.state.command(command);
+        self.redraw()
+    }
+
+    fn send_data(&mut self, data: &[u8]) -> Result<(), OledError> {
+        self.state.write(data);
+        self.redraw()
+    }
+}
+
+/// Builds an X11 request, in little-endian byte order
+struct Request(Vec<u8>);
+
+impl Request {
+    /// Starts a request with `opcode`, and `data` in its second byte
+    fn new(opcode: u8, data: u8) -> Self {
+        Request(vec![opcode, data, 0, 0])
+    }
+
+    /// Appends a 16 bit value
+    fn u16(mut self, value: u16) -> Self {
+        self.0.extend(value.to_le_bytes());
+        self
+    }
+
+    /// Appends a 32 bit value
+    fn u32(mut self, value: u32) -> Self {
+        self.0.extend(value.to_le_bytes());
+        self
+    }
+
+    /// Appends `bytes`, padded to a multiple of four
+    fn bytes(mut self, bytes: &[u8]) -> Self {
+        self.0.extend(bytes);
+        self.0.resize(self.0.len().div_ceil(4) * 4, 0);
+        self
+    }
+
+    /// The request, with its length filled in
+    fn finish(mut self) -> Vec<u8> {
+        let len = (self.0.len() / 4) as u16;
+        self.0[2..4].copy_from_slice(&len.to_le_bytes());
+        self.0
+    }
+}
+
+/// The `MIT-MAGIC-COOKIE-1` for display `number` in the X authority file, if any
+fn cookie(number: u16) -> Option<Vec<u8>> {
+    let path = env::var_os("XAUTHORITY")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".Xauthority")))?;
+    let file = fs::read(path).ok()?;
+    let number = number.to_string();
+    // each entry is a family, then an address, display number, scheme and
+    // cookie, each with its big-endian length first
+    let field = |rest: &mut &[u8]| -> Option<Vec<u8>> {
+        let len = usize::from(u16::from_be_bytes([*rest.first()?, *rest.get(1)?]));
+        let value = rest.get(2..2 + len)?.to_vec();
+        *rest = &rest[2 + len..];
+        Some(value)
+    };
+    let mut rest = &file[..];
+    while rest.len() > 2 {
+        rest = &rest[2..];
+        let _address = field(&mut rest)?;
+        let entry_number = field(&mut rest)?;
+        let scheme = field(&mut rest)?;
+        let cookie = field(&mut rest)?;
+        if scheme == MIT_MAGIC_COOKIE
+            && (entry_number.is_empty() || entry_number == number.as_bytes())
+        {
+            return Some(cookie);
+        }
+    }
+    None
+}
+
+/// Sends the connection setup to the X server, with `cookie` if there's one,
+/// and reads what's needed from its reply
+fn connect<R: Read, W: Write + ?Sized>(
+    reader: &mut R,
+    writer: &mut W,
+    cookie: Option<Vec<u8>>,
+) -> Result<Setup, OledError> {
+    let (scheme, cookie) = match &cookie {
+        Some(cookie) => (MIT_MAGIC_COOKIE, &cookie[..]),
+        None => (&[][..], &[][..]),
+    };
+    // little-endian, protocol version 11.0
+    let setup = Request(vec![b'l', 0])
+        .u16(11)
+        .u16(0)
+        .u16(scheme.len() as u16)
+        .u16(cookie.len() as u16)
+        .u16(0)
+        .bytes(scheme)
+        .bytes(cookie)
+        .0;
+    writer.write_all(&setup)?;
+    writer.flush()?;
+    let mut header = [0u8; 8];
+    reader.read_exact(&mut header)?;
+    let mut reply = vec![0u8; usize::from(u16::from_le_bytes([header[6], header[7]])) * 4];
+    reader.read_exact(&mut reply)?;
+    match header[0] {
+        1 => parse_setup(&reply)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "malformed X setup").into()),
+        status => {
+            // refused with a reason, or asked for authentication that isn't supported
+            let reason = match status {
+                0 => reply.get(..usize::from(header[1])).unwrap_or_default(),
+                _ => &reply[..],
+            };
+            let reason = String::from_utf8_lossy(reason);
+            let message = format!("X server refused the connection: {}", reason.trim_end());
+            Err(io::Error::new(io::ErrorKind::ConnectionRefused, message).into())
+        }
+    }
+}
+
+/// What's needed from the X server's reply to a successful connection setup,
+/// after its first eight bytes
+fn parse_setup(reply: &[u8]) -> Option<Setup> {
+    let u16_at = |at: usize| Some(u16::from_le_bytes([*reply.get(at)?, *reply.get(at + 1)?]));
+    let u32_at = |at: usize| {
+        let bytes = reply.get(at..at + 4)?;
+        Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    };
+    let vendor_len = usize::from(u16_at(16)?);
+    let formats = usize::from(*reply.get(21)?);
+    // the first screen follows the vendor's name and the pixmap formats
+    let screen = 32 + vendor_len.div_ceil(4) * 4 + formats * 8;
+    Some(Setup {
+        id_base: u32_at(4)?,
+        max_request_len: usize::from(u16_at(18)?) * 4,
+        msb_bytes: *reply.get(22)? == 1,
+        msb_bits: *reply.get(23)? == 1,
+        scanline_unit: usize::from(*reply.get(24)?).max(8),
+        scanline_pad: usize::from(*reply.get(25)?).max(8),
+        root: u32_at(screen)?,
+        white: u32_at(screen + 8)?,
+        black: u32_at(screen + 12)?,
+        root_depth: *reply.get(screen + 38)?,
+    })
+}
@@ -0,0 +1,138 @@
+//! Support for displays on an SPI bus, via Linux's `/dev/spidev*` and a sysfs GPIO for D/C
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+use crate::{Oled, OledError, OledInterface};
+
+/// Where the kernel exposes GPIOs
+const GPIO_ROOT: &str = "/sys/class/gpio";
+
+/// The most spidev sends in one transfer, unless its `bufsiz` parameter is raised
+const TRANSFER_SIZE: usize = 4096;
+
+/// An [`OledInterface`](trait.OledInterface.html) for modules wired to SPI,
+/// such as larger SSD1306 and SSD1309 panels that have no I2C. Over SPI, a
+/// separate D/C (data/command) line says whether bytes are for the command
+/// parser or display RAM, rather than a control byte, so the interface drives
+/// that line from a GPIO, only changing it when it needs to.
+///
+/// Bytes are written to the `spidev` device as they are, in its current mode
+/// and at its current speed, as set by the kernel or device tree overlay. The
+/// module's reset line, if it has one, is left alone.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{Oled, OledResult};
+/// # fn main() -> OledResult {
+/// // D/C on GPIO 201
+/// let mut oled = Oled::from_spi("/dev/spidev0.0", 201)?;
+/// oled.init()?;
+/// oled.put_string("Hello, SPI!")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct SpiInterface<W = File> {
+    /// The SPI device
+    spi: W,
+    /// The D/C GPIO's `value` file
+    dc: W,
+    /// Whether the D/C line is set for data, rather than commands, once set
+    data_mode: Option<bool>,
+}
+
+impl SpiInterface {
+    /// Opens the SPI device at `spi`, with D/C on the GPIO numbered `dc_gpio`,
+    /// exporting it to sysfs as an output if it isn't already
+    pub fn open<P: AsRef<Path>>(spi: P, dc_gpio: u32) -> Result<Self, OledError> {
+        let dir = Path::new(GPIO_ROOT).join(format!("gpio{}", dc_gpio));
+        if !dir.exists() {
+            fs::write(Path::new(GPIO_ROOT).join("export"), dc_gpio.to_string())
+                .map_err(OledError::Gpio)?;
+        }
+        fs::write(dir.join("direction"), "out").map_err(OledError::Gpio)?;
+        Self::from_paths(spi, dir.join("value"))
+    }
+
+    /// Opens the SPI device at `spi`, with D/C driven through the GPIO `value`
+    /// file at `dc`, eg for a GPIO exported already
+    pub fn from_paths<P: AsRef<Path>, Q: AsRef<Path>>(spi: P, dc: Q) -> Result<Self, OledError> {
+        let open = |path: &Path| OpenOptions::new().write(true).open(path);
+        let spi = open(spi.as_ref()).map_err(OledError::Spi)?;
+        let dc = open(dc.as_ref()).map_err(OledError::Gpio)?;
+        Ok(Self::from_writers(spi, dc))
+    }
+}
+
+impl<W: Write> SpiInterface<W> {
+    /// Wraps an SPI device and D/C `value` file that are already open, or
+    /// anything standing in for them, eg a `Vec<u8>` in tests
+    pub fn from_writers(spi: W, dc: W) -> Self {
+        SpiInterface {
+            spi,
+            dc,
+            data_mode: None,
+        }
+    }
+
+    /// Consumes the interface, returning the SPI device and the D/C `value` file
+    pub fn into_writers(self) -> (W, W) {
+        (self.spi, self.dc)
+    }
+
+    /// Sets the D/C line for data or commands, if it isn't already
+    fn set_data_mode(&mut self, data: bool) -> Result<(), OledError> {
+        if self.data_mode != Some(data) {
+            let level: &[u8] = if data { b"1" } else { b"0" };
+            self.dc
+                .write_all(level)
+                .and_then(|_| self.dc.flush())
+                .map_err(OledError::Gpio)?;
+            self.data_mode = Some(data);
+        }
+        Ok(())
+    }
+
+    /// Sends bytes over SPI, in a single transfer
+    fn transfer(&mut self, bytes: &[u8]) -> Result<(), OledError> {
+        self.spi
+            .write_all(bytes)
+            .and_then(|_| self.spi.flush())
+            .map_err(OledError::Spi)
+    }
+}
+
+impl<W: Write> OledInterface for SpiInterface<W> {
+    fn send_command(&mut self, command: u8) -> Result<(), OledError> {
+        self.set_data_mode(false)?;
+        self.transfer(&[command])
+    }
+
+    fn send_commands(&mut self, commands: &[u8]) -> Result<(), OledError> {
+        self.set_data_mode(false)?;
+        for chunk in commands.chunks(TRANSFER_SIZE) {
+            self.transfer(chunk)?;
+        }
+        Ok(())
+    }
+
+    fn send_data(&mut self, data: &[u8]) -> Result<(), OledError> {
+        self.set_data_mode(true)?;
+        for chunk in data.chunks(TRANSFER_SIZE) {
+            self.transfer(chunk)?;
+        }
+        Ok(())
+    }
+
+    fn max_transfer_len(&self) -> usize {
+        TRANSFER_SIZE
+    }
+}
+
+impl Oled<SpiInterface> {
+    /// Opens a display wired to SPI, as [`SpiInterface::open()`](struct.SpiInterface.html#method.open)
+    /// does
+    pub fn from_spi<P: AsRef<Path>>(spi: P, dc_gpio: u32) -> Result<Self, OledError> {
+        Ok(Self::new(SpiInterface::open(spi, dc_gpio)?))
+    }
+}
@@ -0,0 +1,181 @@
+//! Boot logos, converted from image files when the program is compiled
+#[cfg(feature = "std")]
+use std::{thread::sleep, time::Duration};
+
+use crate::{Framebuffer, OledError, OLED_HEIGHT, OLED_PAGE_HEIGHT, OLED_RAM_SIZE, OLED_WIDTH};
+#[cfg(feature = "std")]
+use crate::{Oled, OledInterface, OledResult};
+
+/// Includes a binary PBM (`P4`) image, as a [`Framebuffer`](struct.Framebuffer.html)
+/// converted when the program is compiled, so a logo can be kept as an image file
+/// rather than a byte array. The path is relative to the file the macro is used in,
+/// as for `include_bytes!`, and the image is as for
+/// [`Framebuffer::from_pbm()`](struct.Framebuffer.html#method.from_pbm): an image
+/// that isn't a PBM, or is larger than the display, fails to compile.
+///
+/// Most image editors can save PBM, and ImageMagick converts other formats with
+/// `convert logo.png -monochrome logo.pbm`.
+/// # Example:
+/// ```ignore
+/// use nanohat_oled::{include_image, Framebuffer};
+///
+/// const SPLASH: Framebuffer = include_image!("logo.pbm");
+/// ```
+#[macro_export]
+macro_rules! include_image {
+    ($path:expr) => {{
+        const IMAGE: $crate::Framebuffer = $crate::Framebuffer::from_pbm(include_bytes!($path));
+        IMAGE
+    }};
+}
+
+/// Returns whether `byte` separates fields in a PBM header
+const fn is_pbm_space(byte: u8) -> bool {
+    matches!(byte, b' ' | b'\t' | b'\n' | b'\r' | 0x0b | 0x0c)
+}
+
+/// Why a PBM image can't be read, as the message for a compile error, or for
+/// an `OledError`
+#[derive(Clone, Copy)]
+enum PbmError {
+    /// Something other than a PBM image, or one cut short
+    Invalid(&'static str),
+    /// An image larger than the display
+    TooLarge,
+}
+
+/// Reads the number in a PBM header after `at`, skipping whitespace and comments
+/// before it, and returns it with the position just past it
+const fn pbm_number(pbm: &[u8], mut at: usize) -> Result<(usize, usize), PbmError> {
+    while at < pbm.len() && (is_pbm_space(pbm[at]) || pbm[at] == b'#') {
+        if pbm[at] == b'#' {
+            while at < pbm.len() && pbm[at] != b'\n' {
+                at += 1;
+            }
+        } else {
+            at += 1;
+        }
+    }
+    let start = at;
+    let mut number: usize = 0;
+    while at < pbm.len() && pbm[at].is_ascii_digit() {
+        number = number
+            .saturating_mul(10)
+            .saturating_add((pbm[at] - b'0') as usize);
+        at += 1;
+    }
+    if at == start {
+        return Err(PbmError::Invalid(
+            "PBM header is missing its width or height",
+        ));
+    }
+    Ok((number, at))
+}
+
+/// Converts a binary PBM (`P4`) image, as for
+/// [`Framebuffer::from_pbm()`](struct.Framebuffer.html#method.from_pbm)
+const fn parse_pbm(pbm: &[u8]) -> Result<Framebuffer, PbmError> {
+    if pbm.len() < 2 || pbm[0] != b'P' || pbm[1] != b'4' {
+        return Err(PbmError::Invalid("not a binary PBM (P4) image"));
+    }
+    let (width, at) = match pbm_number(pbm, 2) {
+        Ok(number) => number,
+        Err(err) => return Err(err),
+    };
+    let (height, at) = match pbm_number(pbm, at) {
+        Ok(number) => number,
+        Err(err) => return Err(err),
+    };
+    if width > OLED_WIDTH as usize || height > OLED_HEIGHT as usize {
+        return Err(PbmError::TooLarge);
+    }
+    // one whitespace character separates the header from the pixels
+    let start = at + 1;
+    let row_len = width.div_ceil(8);
+    if pbm.len() < start + row_len * height {
+        return Err(PbmError::Invalid("PBM image is cut short"));
+    }
+    let mut buffer = [0u8; OLED_RAM_SIZE];
+    let mut y = 0;
+    while y < height {
+        let mut x = 0;
+        while x < width {
+            // in PBM, set bits are black
+            if pbm[start + y * row_len + x / 8] & (0x80 >> (x % 8)) == 0 {
+                let page = y / OLED_PAGE_HEIGHT as usize;
+                buffer[page * OLED_WIDTH as usize + x] |= 1 << (y % OLED_PAGE_HEIGHT as usize);
+            }
+            x += 1;
+        }
+        y += 1;
+    }
+    Ok(Framebuffer::from_bytes(buffer))
+}
+
+impl Framebuffer {
+    /// Creates a 128x64 framebuffer from a binary PBM (`P4`) image, lit where
+    /// the image is white, as [`to_pbm()`](struct.Framebuffer.html#method.to_pbm)
+    /// writes it, so a logo is drawn white on black. An image smaller than the
+    /// display goes in its top left corner. As a `const fn`, this can convert an
+    /// image when the program is compiled; see
+    /// [`include_image!`](macro.include_image.html).
+    ///
+    /// # Panics
+    /// Panics if `pbm` isn't a binary PBM image, is cut short, or is more than
+    /// 128x64 pixels. When used to initialize a `const` or `static`, that becomes
+    /// a compile error instead.
+    /// # Example:
+    /// ```
+    /// use nanohat_oled::Framebuffer;
+    ///
+    /// // a 2x1 image, black then white
+    /// const FRAME: Framebuffer = Framebuffer::from_pbm(b"P4\n2 1\n\x80");
+    /// assert!(!FRAME.get_pixel(0, 0));
+    /// assert!(FRAME.get_pixel(1, 0));
+    /// ```
+    pub const fn from_pbm(pbm: &[u8]) -> Self {
+        match parse_pbm(pbm) {
+            Ok(frame) => frame,
+            Err(PbmError::Invalid(message)) => panic!("{}", message),
+            Err(PbmError::TooLarge) => panic!("image is larger than the display"),
+        }
+    }
+
+    /// Creates a framebuffer from a binary PBM (`P4`) image, as
+    /// [`from_pbm()`](struct.Framebuffer.html#method.from_pbm) does, but for an
+    /// image read while the program runs, eg from a file given on the command
+    /// line. Returns `InvalidDimensions` if it's larger than the display, and
+    /// `InvalidArgument` if it isn't a binary PBM image or is cut short.
+    pub fn try_from_pbm(pbm: &[u8]) -> Result<Self, OledError> {
+        parse_pbm(pbm).map_err(|err| match err {
+            PbmError::Invalid(message) => OledError::InvalidArgument(message),
+            PbmError::TooLarge => OledError::InvalidDimensions,
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I: OledInterface> Oled<I> {
+    /// Shows `splash`, eg a boot logo from [`include_image!`](macro.include_image.html),
+    /// for `duration`, then clears the display for the program to draw on.
+    /// Blocks until done. As for [`flush_all()`](struct.Oled.html#method.flush_all),
+    /// `InvalidDimensions` is returned if the display is turned a quarter turn.
+    /// # Example:
+    /// ```ignore
+    /// use nanohat_oled::{include_image, Framebuffer, Oled, OledResult};
+    /// use std::time::Duration;
+    ///
+    /// const SPLASH: Framebuffer = include_image!("logo.pbm");
+    ///
+    /// fn main() -> OledResult {
+    ///     let mut oled = Oled::from_path("/dev/i2c-0")?;
+    ///     oled.init()?;
+    ///     oled.show_splash(&SPLASH, Duration::from_secs(2))
+    /// }
+    /// ```
+    pub fn show_splash(&mut self, splash: &Framebuffer, duration: Duration) -> OledResult {
+        self.flush_all(splash)?;
+        sleep(duration);
+        self.clear_display()
+    }
+}
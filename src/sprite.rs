@@ -0,0 +1,147 @@
+//! One-bit images with optional transparency, combined into a
+//! [`Framebuffer`](struct.Framebuffer.html) using raster operations
+use crate::{BlitOp, Framebuffer, Oled, OledInterface, OledResult};
+
+/// A one-bit-per-pixel image `width` by `height` pixels, eg an icon or cursor.
+///
+/// Pixels are stored row by row, most significant bit first, with each row padded
+/// to a whole number of bytes, as in XBM and PBM files. A set bit is a lit pixel.
+/// An optional mask, laid out the same way, marks which pixels belong to the
+/// sprite: where its bit is clear, the framebuffer is left alone whatever the
+/// raster operation, so sprites needn't be rectangular.
+/// # Example:
+/// ```
+/// use nanohat_oled::{BlitOp, Framebuffer, Sprite};
+///
+/// // a 3x3 plus sign, drawn only where the mask is set
+/// const PLUS: Sprite = Sprite::new(3, 3, &[0b0100_0000, 0b1110_0000, 0b0100_0000])
+///     .with_mask(&[0b0100_0000, 0b1110_0000, 0b0100_0000]);
+///
+/// let mut frame = Framebuffer::new();
+/// frame.fill_rect(0, 0, 3, 3, true);
+/// frame.blit_sprite(&PLUS, 0, 0, BlitOp::Xor);
+/// assert!(frame.get_pixel(0, 0));
+/// assert!(!frame.get_pixel(1, 1));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Sprite<'a> {
+    /// Width in pixels
+    width: u16,
+    /// Height in pixels
+    height: u16,
+    /// Pixel bits, row by row
+    data: &'a [u8],
+    /// Bits marking the pixels that are drawn, if not all of them
+    mask: Option<&'a [u8]>,
+}
+
+/// Number of bytes holding one row of a sprite `width` pixels wide
+const fn stride(width: u16) -> usize {
+    (width as usize).div_ceil(8)
+}
+
+/// Returns whether bit `x` of row `y` is set in sprite data with rows `stride` bytes long
+fn bit(data: &[u8], stride: usize, x: u16, y: u16) -> bool {
+    data[y as usize * stride + x as usize / 8] & (0x80 >> (x % 8)) != 0
+}
+
+impl<'a> Sprite<'a> {
+    /// Creates an opaque sprite from its pixel bits.
+    ///
+    /// # Panics
+    /// Panics if `data` isn't exactly `height` rows of `width` bits, each padded to
+    /// a whole byte. When used to initialize a `const` or `static`, that becomes
+    /// a compile error instead.
+    pub const fn new(width: u16, height: u16, data: &'a [u8]) -> Self {
+        assert!(
+            data.len() == stride(width) * height as usize,
+            "sprite data doesn't match its dimensions"
+        );
+        Sprite {
+            width,
+            height,
+            data,
+            mask: None,
+        }
+    }
+
+    /// Adds a mask, giving the pixels that are drawn.
+    ///
+    /// # Panics
+    /// Panics if `mask` isn't the same size as the sprite's data.
+    pub const fn with_mask(self, mask: &'a [u8]) -> Self {
+        assert!(
+            mask.len() == self.data.len(),
+            "sprite mask doesn't match its dimensions"
+        );
+        Sprite {
+            mask: Some(mask),
+            ..self
+        }
+    }
+
+    /// The `count` rows starting at row `first`, as a sprite of their own
+    pub(crate) fn rows(&self, first: u16, count: u16) -> Sprite<'a> {
+        let stride = stride(self.width);
+        let bytes = first as usize * stride..(first + count) as usize * stride;
+        Sprite {
+            width: self.width,
+            height: count,
+            data: &self.data[bytes.clone()],
+            mask: self.mask.map(|mask| &mask[bytes]),
+        }
+    }
+
+    /// Width and height in pixels
+    pub fn dimensions(&self) -> (u16, u16) {
+        (self.width, self.height)
+    }
+
+    /// Returns whether the pixel at `x`, `y` is lit,
+    /// or `None` if it is masked out or outside the sprite
+    pub fn get_pixel(&self, x: u16, y: u16) -> Option<bool> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let stride = stride(self.width);
+        match self.mask {
+            Some(mask) if !bit(mask, stride, x, y) => None,
+            _ => Some(bit(self.data, stride, x, y)),
+        }
+    }
+}
+
+impl Framebuffer {
+    /// Combines a sprite with the framebuffer, with its top left corner at `x`, `y`.
+    /// Each pixel the sprite's mask lets through is combined with the one beneath it
+    /// using `op`, eg [`BlitOp::Xor`](enum.BlitOp.html#variant.Xor) for a cursor that
+    /// is erased by drawing it again. Parts of the sprite off the display are clipped.
+    pub fn blit_sprite(&mut self, sprite: &Sprite, x: u16, y: u16, op: BlitOp) {
+        let (width, height) = self.dimensions();
+        for row in 0..sprite.height {
+            let py = y.saturating_add(row);
+            if py >= height {
+                break;
+            }
+            for column in 0..sprite.width {
+                let px = x.saturating_add(column);
+                if px >= width {
+                    break;
+                }
+                if let Some(lit) = sprite.get_pixel(column, row) {
+                    let existing = u8::from(self.get_pixel(px, py));
+                    self.set_pixel(px, py, op.apply(existing, u8::from(lit)) != 0);
+                }
+            }
+        }
+    }
+}
+
+impl<I: OledInterface> Oled<I> {
+    /// Combines a sprite with what is on the display, as
+    /// [`Framebuffer::blit_sprite()`](struct.Framebuffer.html#method.blit_sprite)
+    /// does, sending only the part of the display that changes.
+    pub fn blit_sprite(&mut self, sprite: &Sprite, x: u16, y: u16, op: BlitOp) -> OledResult {
+        self.draw(|frame| frame.blit_sprite(sprite, x, y, op))
+    }
+}
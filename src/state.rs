@@ -0,0 +1,221 @@
+//! A model of the display controller's state, kept in step with what is sent to it.
+//!
+//! The SSD1306 can't be read back over I2C, so instead every command and
+//! data byte is replayed against this model, which tracks the RAM pointer the
+//! same way the controller does.
+use crate::{AddressingMode, OLED_LAST_PAGE, OLED_RAM_SIZE, OLED_WIDTH};
+#[cfg(feature = "alloc")]
+use crate::{OLED_HEIGHT, OLED_PAGE_HEIGHT};
+
+/// The last column address
+const LAST_COLUMN: u8 = (OLED_WIDTH - 1) as u8;
+
+/// Display RAM contents, plus the controller settings that determine where
+/// data bytes land and how they are shown
+#[derive(Clone, Debug)]
+pub(crate) struct DisplayState {
+    /// RAM contents, one byte per column of each page
+    pub(crate) buffer: [u8; OLED_RAM_SIZE],
+    /// How the pointer advances after each data byte
+    pub(crate) mode: AddressingMode,
+    /// Page the pointer is on
+    page: u8,
+    /// Column the pointer is on
+    column: u8,
+    /// First and last columns of the horizontal/vertical mode window
+    pub(crate) columns: (u8, u8),
+    /// First and last pages of the horizontal/vertical mode window
+    pub(crate) pages: (u8, u8),
+    /// Whether the display is showing black on white
+    pub(crate) inverted: bool,
+    /// Contrast level
+    pub(crate) contrast: u8,
+    /// Whether the display is on, rather than asleep
+    pub(crate) on: bool,
+    /// Whether every pixel is lit, whatever the RAM holds
+    entire_on: bool,
+    /// Whether column 127 is mapped to the leftmost segment
+    segments_reversed: bool,
+    /// Whether rows are scanned from the bottom COM pin up
+    com_remapped: bool,
+    /// RAM row shown on the first COM row
+    start_line: u8,
+    /// COM row the start line is shown on
+    offset: u8,
+    /// A command still waiting on arguments, the arguments so far, and how many there are
+    pending: Option<(u8, [u8; 6], usize)>,
+}
+
+impl Default for DisplayState {
+    /// State of the controller after reset
+    fn default() -> Self {
+        Self {
+            buffer: [0u8; OLED_RAM_SIZE],
+            mode: AddressingMode::Page,
+            page: 0,
+            column: 0,
+            columns: (0, LAST_COLUMN),
+            pages: (0, OLED_LAST_PAGE),
+            inverted: false,
+            contrast: 0x7f,
+            on: false,
+            entire_on: false,
+            segments_reversed: false,
+            com_remapped: false,
+            start_line: 0,
+            offset: 0,
+            pending: None,
+        }
+    }
+}
+
+/// Number of argument bytes that follow a command byte
+pub(crate) fn argument_count(command: u8) -> usize {
+    match command {
+        0x26 | 0x27 => 6,
+        0x29 | 0x2a => 5,
+        0x21 | 0x22 | 0xa3 => 2,
+        0x20 | 0x23 | 0x81 | 0x8d | 0xa8 | 0xad | 0xd3 | 0xd5 | 0xd6 | 0xd9 | 0xda | 0xdb => 1,
+        _ => 0,
+    }
+}
+
+impl DisplayState {
+    /// Applies a byte sent to the command parser. Commands that aren't modelled
+    /// are skipped over, along with their arguments.
+    pub(crate) fn command(&mut self, byte: u8) {
+        let (command, mut args, received) = match self.pending.take() {
+            Some((command, mut args, received)) => {
+                args[received] = byte;
+                (command, args, received + 1)
+            }
+            None => (byte, [0u8; 6], 0),
+        };
+        if received < argument_count(command) {
+            self.pending = Some((command, args, received));
+            return;
+        }
+        match command {
+            0x00..=0x0f => self.column = (self.column & 0xf0) | command,
+            0x10..=0x17 => self.column = (self.column & 0x0f) | ((command & 0x07) << 4),
+            0x40..=0x7f => self.start_line = command & 0x3f,
+            0xb0..=0xb7 => self.page = command & 0x07,
+            0xa0 => self.segments_reversed = false,
+            0xa1 => self.segments_reversed = true,
+            0xa4 => self.entire_on = false,
+            0xa5 => self.entire_on = true,
+            0xa6 => self.inverted = false,
+            0xa7 => self.inverted = true,
+            0x81 => self.contrast = args[0],
+            0xae => self.on = false,
+            0xaf => self.on = true,
+            0xc0 => self.com_remapped = false,
+            0xc8 => self.com_remapped = true,
+            0xd3 => self.offset = args[0] & 0x3f,
+            0x20 => {
+                self.mode = match args[0] & 0x03 {
+                    0x00 => AddressingMode::Horizontal,
+                    0x01 => AddressingMode::Vertical,
+                    0x02 => AddressingMode::Page,
+                    _ => self.mode,
+                }
+            }
+            0x21 => {
+                args[0] &= LAST_COLUMN;
+                args[1] &= LAST_COLUMN;
+                self.columns = (args[0], args[1]);
+                self.column = args[0];
+            }
+            0x22 => {
+                args[0] &= OLED_LAST_PAGE;
+                args[1] &= OLED_LAST_PAGE;
+                self.pages = (args[0], args[1]);
+                self.page = args[0];
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether the pixel at `x`, `y` on the panel is lit, counting from the top
+    /// left of the NanoHat the right way up, as set by
+    /// [`Oled::init()`](struct.Oled.html#method.init).
+    ///
+    /// Segment remap is taken to apply to the whole of RAM, rather than only
+    /// data written after it's changed, which is the same whenever RAM is
+    /// rewritten after changing it, as `set_rotation()` does.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn lit(&self, x: u16, y: u16) -> bool {
+        if !self.on {
+            return false;
+        }
+        if self.entire_on {
+            return true;
+        }
+        let column = if self.segments_reversed {
+            x
+        } else {
+            OLED_WIDTH - 1 - x
+        };
+        let com = if self.com_remapped {
+            y
+        } else {
+            OLED_HEIGHT - 1 - y
+        };
+        let row =
+            (com + OLED_HEIGHT + u16::from(self.start_line) - u16::from(self.offset)) % OLED_HEIGHT;
+        let byte =
+            self.buffer[(row / OLED_PAGE_HEIGHT) as usize * OLED_WIDTH as usize + column as usize];
+        (byte >> (row % OLED_PAGE_HEIGHT) & 1 == 1) != self.inverted
+    }
+
+    /// Column and page the pointer is on
+    pub(crate) fn pointer(&self) -> (u8, u8) {
+        (self.column, self.page)
+    }
+
+    /// Applies bytes written to display RAM, advancing the pointer after each
+    pub(crate) fn write(&mut self, data: &[u8]) {
+        for byte in data {
+            let offset = self.page as usize * OLED_WIDTH as usize + self.column as usize;
+            self.buffer[offset] = *byte;
+            self.advance();
+        }
+    }
+
+    /// Moves the pointer on by one byte, per the addressing mode
+    fn advance(&mut self) {
+        match self.mode {
+            AddressingMode::Page => {
+                self.column = if self.column >= LAST_COLUMN {
+                    0
+                } else {
+                    self.column + 1
+                };
+            }
+            AddressingMode::Horizontal => {
+                if self.column >= self.columns.1 {
+                    self.column = self.columns.0;
+                    self.page = if self.page >= self.pages.1 {
+                        self.pages.0
+                    } else {
+                        self.page + 1
+                    };
+                } else {
+                    self.column += 1;
+                }
+            }
+            AddressingMode::Vertical => {
+                if self.page >= self.pages.1 {
+                    self.page = self.pages.0;
+                    self.column = if self.column >= self.columns.1 {
+                        self.columns.0
+                    } else {
+                        self.column + 1
+                    };
+                } else {
+                    self.page += 1;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,135 @@
+//! A row of indicators and a clock along the top of the display
+use crate::{
+    BuiltinFont, Font, Framebuffer, Icon, IconSize, OledError, Rect, Widget, OLED_PAGE_HEIGHT,
+};
+
+/// Gap between indicators, in pixels
+const ICON_GAP: u16 = 2;
+
+/// A [`Widget`](trait.Widget.html) a page high showing which of the Wi-Fi,
+/// link and alert indicators are on, as small [`Icon`](enum.Icon.html)s from
+/// the left, and the time as `HH:MM` on the right. Indicators that are off,
+/// and the clock until it's set, leave gaps.
+///
+/// It's meant for page 0, across the top of the display, where it can be
+/// updated on its own with [`Oled::render_to_pages()`](struct.Oled.html#method.render_to_pages).
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{Oled, OledResult, StatusBar};
+/// # fn main() -> OledResult {
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// let mut status = StatusBar::new();
+/// status.set_wifi(Some(2));
+/// status.set_time(9, 41)?;
+/// oled.render_to_pages(&mut status, 0, 0)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct StatusBar {
+    /// Wi-Fi signal level, if shown
+    wifi: Option<u8>,
+    /// Whether the link indicator is on
+    link: bool,
+    /// Whether the alert indicator is on
+    alert: bool,
+    /// Hours and minutes shown, if any
+    time: Option<(u8, u8)>,
+    /// Whether anything has changed since the bar was last rendered
+    changed: bool,
+}
+
+impl StatusBar {
+    /// Height of the bar, in pixels: one page
+    pub const HEIGHT: u16 = OLED_PAGE_HEIGHT;
+
+    /// Creates a status bar with every indicator off and no time shown
+    pub fn new() -> Self {
+        StatusBar {
+            changed: true,
+            ..StatusBar::default()
+        }
+    }
+
+    /// Shows Wi-Fi signal at `level`, from 0 to
+    /// [`Icon::MAX_WIFI_LEVEL`](enum.Icon.html#associatedconstant.MAX_WIFI_LEVEL),
+    /// or hides it if `None`, eg when disconnected
+    pub fn set_wifi(&mut self, level: Option<u8>) {
+        let level = level.map(|level| level.min(Icon::MAX_WIFI_LEVEL));
+        self.changed |= level != self.wifi;
+        self.wifi = level;
+    }
+
+    /// Turns the link indicator on or off, eg for a wired or VPN connection
+    pub fn set_link(&mut self, on: bool) {
+        self.changed |= on != self.link;
+        self.link = on;
+    }
+
+    /// Turns the alert indicator on or off
+    pub fn set_alert(&mut self, on: bool) {
+        self.changed |= on != self.alert;
+        self.alert = on;
+    }
+
+    /// Shows the time as `hours`:`minutes`, on a 24 hour clock
+    ///
+    /// Returns `InvalidArgument` unless `hours` is below 24 and `minutes` below 60.
+    pub fn set_time(&mut self, hours: u8, minutes: u8) -> Result<(), OledError> {
+        if hours >= 24 || minutes >= 60 {
+            return Err(OledError::InvalidArgument(
+                "hours must be below 24 and minutes below 60",
+            ));
+        }
+        let time = Some((hours, minutes));
+        self.changed |= time != self.time;
+        self.time = time;
+        Ok(())
+    }
+
+    /// Stops showing the time
+    pub fn clear_time(&mut self) {
+        self.changed |= self.time.is_some();
+        self.time = None;
+    }
+}
+
+impl Widget for StatusBar {
+    fn render(&mut self, frame: &mut Framebuffer, area: Rect) {
+        frame.fill_rect(area.x, area.y, area.width, area.height, false);
+        let indicators = [
+            self.wifi.map(Icon::Wifi),
+            Some(Icon::Link).filter(|_| self.link),
+            Some(Icon::Warning).filter(|_| self.alert),
+        ];
+        let side = IconSize::Small.pixels();
+        let mut rest = area;
+        for icon in IntoIterator::into_iter(indicators).flatten() {
+            let (cell, after) = rest.split_left(side);
+            if cell.width < side || cell.height < side {
+                break;
+            }
+            frame.draw_icon(icon, IconSize::Small, cell.x, cell.y);
+            rest = after.split_left(ICON_GAP).1;
+        }
+        if let Some((hours, minutes)) = self.time {
+            let digits = [
+                b'0' + hours / 10,
+                b'0' + hours % 10,
+                b':',
+                b'0' + minutes / 10,
+                b'0' + minutes % 10,
+            ];
+            let text = core::str::from_utf8(&digits).unwrap_or_default();
+            let font = BuiltinFont::MONOSPACE;
+            let x = i32::from(area.x + area.width) - i32::from(font.text_width(text));
+            frame.flip_text(x, i32::from(area.y), text, &font, area);
+        }
+        self.changed = false;
+    }
+
+    fn needs_render(&self) -> bool {
+        self.changed
+    }
+}
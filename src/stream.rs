@@ -0,0 +1,159 @@
+//! Playing a stream of raw frames, eg video piped from ffmpeg
+use std::io::{ErrorKind, Read};
+use std::mem;
+
+use crate::dither::dither;
+use crate::{Dither, FrameTimer, Framebuffer, Oled, OledError, OledInterface, OledResult};
+
+/// How the frames of a [`FrameStream`](struct.FrameStream.html) are laid out.
+/// Either way, a frame is the display's size, as given by
+/// [`Oled::dimensions()`](struct.Oled.html#method.dimensions), one row after
+/// another from the top, with nothing between frames.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrameFormat {
+    /// One bit per pixel, eight to a byte, the leftmost in the most significant
+    /// bit, lit where it's set, as ffmpeg's `monob` pixel format
+    Mono,
+    /// One byte per pixel, from black at 0 to white at 255, as ffmpeg's `gray`
+    /// pixel format, turned into lit and unlit pixels with the `Dither` given
+    Gray(Dither),
+}
+
+impl FrameFormat {
+    /// The number of bytes in a `width` by `height` frame
+    fn frame_len(self, width: usize, height: usize) -> usize {
+        match self {
+            FrameFormat::Mono => width.div_ceil(8) * height,
+            FrameFormat::Gray(_) => width * height,
+        }
+    }
+}
+
+/// Fills `frame` from `reader`, returning `false` if the stream ends first
+fn read_frame<R: Read>(reader: &mut R, frame: &mut [u8]) -> Result<bool, OledError> {
+    let mut filled = 0;
+    while filled < frame.len() {
+        match reader.read(&mut frame[filled..]) {
+            Ok(0) => return Ok(false),
+            Ok(len) => filled += len,
+            Err(err) if err.kind() == ErrorKind::Interrupted => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+    Ok(true)
+}
+
+/// Plays a stream of raw frames on the display at a steady rate, eg video
+/// converted on the fly by ffmpeg and piped in on stdin, or frames sent over a
+/// socket by a program drawing them elsewhere.
+///
+/// Frames are paced by a [`FrameTimer`](struct.FrameTimer.html). When one
+/// can't be shown on time, because sending the last took too long or the
+/// stream was slow to deliver it, the frames it held up are read and dropped
+/// rather than shown late, so the picture keeps up with the stream, eg with
+/// its sound. Only the parts of the display that change between frames are
+/// sent, so a low frame rate plays smoothly over I2C.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{Dither, FrameFormat, FrameStream, Oled, OledResult};
+/// # fn main() -> OledResult {
+/// // ffmpeg -i video.mp4 -vf scale=128:64 -r 15 -pix_fmt gray -f rawvideo - | player
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// let mut stream = FrameStream::new(oled);
+/// stream.play(std::io::stdin().lock(), FrameFormat::Gray(Dither::Bayer), 15)?;
+/// println!("showed {}, dropped {}", stream.shown(), stream.dropped());
+/// # Ok(())
+/// # }
+/// ```
+pub struct FrameStream<I> {
+    /// The display played on
+    oled: Oled<I>,
+    /// Frames shown so far
+    shown: u64,
+    /// Frames read but not shown, for being late, so far
+    dropped: u64,
+}
+
+impl<I: OledInterface> FrameStream<I> {
+    /// Plays streams on an already-initialized display
+    pub fn new(oled: Oled<I>) -> Self {
+        FrameStream {
+            oled,
+            shown: 0,
+            dropped: 0,
+        }
+    }
+
+    /// Reads frames in `format` from `reader` and shows them, `fps` a second,
+    /// until the stream ends. A frame cut short at the end of the stream is
+    /// left out, and the last whole one is shown even if it's late. Returns the
+    /// first error reading the stream or writing to the display.
+    pub fn play<R: Read>(&mut self, mut reader: R, format: FrameFormat, fps: u32) -> OledResult {
+        let (width, height) = self.oled.dimensions();
+        let (width, height) = (usize::from(width), usize::from(height));
+        let mut raw = vec![0; format.frame_len(width, height)];
+        let mut next = raw.clone();
+        let mut gray = match format {
+            FrameFormat::Mono => vec![0; width * height],
+            FrameFormat::Gray(_) => Vec::new(),
+        };
+        let mut timer = FrameTimer::new(fps);
+        while read_frame(&mut reader, &mut raw)? {
+            // skip to the frame due now, or the last there is
+            for _ in 0..timer.wait() {
+                if !read_frame(&mut reader, &mut next)? {
+                    break;
+                }
+                mem::swap(&mut raw, &mut next);
+                self.dropped += 1;
+            }
+            let (image, dither_mode) = match format {
+                FrameFormat::Mono => {
+                    let stride = width.div_ceil(8);
+                    for (index, pixel) in gray.iter_mut().enumerate() {
+                        let (x, y) = (index % width, index / width);
+                        let lit = raw[y * stride + x / 8] & (0x80 >> (x % 8)) != 0;
+                        *pixel = if lit { 255 } else { 0 };
+                    }
+                    (&gray, Dither::Threshold(0x80))
+                }
+                FrameFormat::Gray(dither_mode) => (&raw, dither_mode),
+            };
+            self.show(image, width, dither_mode)?;
+        }
+        Ok(())
+    }
+
+    /// Dithers a `width` wide grayscale frame and sends what has changed
+    fn show(&mut self, gray: &[u8], width: usize, dither_mode: Dither) -> OledResult {
+        let dithered = dither(gray, width, dither_mode)?;
+        self.oled.pack_oriented(&dithered[..gray.len()], 0x80)?;
+        let portrait = self.oled.rotation.is_portrait();
+        let frame = Framebuffer::with_layout(self.oled.scratch, portrait);
+        self.oled.flush(&frame)?;
+        self.shown += 1;
+        Ok(())
+    }
+
+    /// The number of frames shown so far
+    pub fn shown(&self) -> u64 {
+        self.shown
+    }
+
+    /// The number of frames read but dropped so far, because they couldn't
+    /// be shown on time
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Returns the display, eg for sending commands directly
+    pub fn oled(&mut self) -> &mut Oled<I> {
+        &mut self.oled
+    }
+
+    /// Consumes the player, returning the display
+    pub fn into_oled(self) -> Oled<I> {
+        self.oled
+    }
+}
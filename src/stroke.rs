@@ -0,0 +1,96 @@
+//! Thick, dashed and dotted lines
+use crate::graphics::for_each_line_point;
+use crate::Framebuffer;
+
+/// How a line is drawn by
+/// [`Framebuffer::draw_line_styled()`](struct.Framebuffer.html#method.draw_line_styled):
+/// how thick it is, and which of its pixels are drawn, eg for a dotted axis or a
+/// needle thick enough to see across the room.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Stroke {
+    /// Thickness, in pixels, across the line. 0 draws nothing.
+    pub width: u16,
+    /// Which pixels along the line are drawn, lowest bit first: a set bit draws
+    /// the pixel, a clear one leaves a gap, repeating every 16 pixels
+    pub pattern: u16,
+}
+
+impl Stroke {
+    /// An unbroken line a pixel thick, as drawn by
+    /// [`draw_line()`](struct.Framebuffer.html#method.draw_line)
+    pub const SOLID: Stroke = Stroke::new(1, 0xffff);
+    /// Four pixels on, then four off
+    pub const DASHED: Stroke = Stroke::new(1, 0x0f0f);
+    /// Every other pixel
+    pub const DOTTED: Stroke = Stroke::new(1, 0x5555);
+
+    /// A stroke `width` pixels thick, drawing the pixels set in `pattern`
+    pub const fn new(width: u16, pattern: u16) -> Self {
+        Stroke { width, pattern }
+    }
+
+    /// The same stroke, `width` pixels thick
+    pub const fn with_width(self, width: u16) -> Self {
+        Stroke { width, ..self }
+    }
+
+    /// Whether the pixel `index` steps along a line is drawn
+    fn draws(self, index: usize) -> bool {
+        self.pattern & (1 << (index % 16)) != 0
+    }
+}
+
+impl Default for Stroke {
+    fn default() -> Self {
+        Stroke::SOLID
+    }
+}
+
+impl Framebuffer {
+    /// Draws a straight line between two points, like
+    /// [`draw_line()`](struct.Framebuffer.html#method.draw_line), with `stroke`.
+    /// A thick line is centred on the thin one, widened up and down if it runs
+    /// more across than down, or to the sides otherwise, so its ends stay square.
+    /// The pattern starts afresh at the first point. Parts off the display are
+    /// clipped.
+    /// # Example:
+    /// ```
+    /// use nanohat_oled::{Framebuffer, Stroke};
+    ///
+    /// let mut frame = Framebuffer::new();
+    /// frame.draw_line_styled(0, 10, 127, 10, Stroke::DOTTED, true);
+    /// frame.draw_line_styled(64, 63, 100, 20, Stroke::SOLID.with_width(3), true);
+    /// assert!(frame.get_pixel(0, 10) && !frame.get_pixel(1, 10));
+    /// ```
+    pub fn draw_line_styled(
+        &mut self,
+        x0: u16,
+        y0: u16,
+        x1: u16,
+        y1: u16,
+        stroke: Stroke,
+        on: bool,
+    ) {
+        if stroke.width == 0 {
+            return;
+        }
+        let across = x0.abs_diff(x1) >= y0.abs_diff(y1);
+        let width = i32::from(stroke.width);
+        let mut index = 0;
+        let (from, to) = (
+            (i32::from(x0), i32::from(y0)),
+            (i32::from(x1), i32::from(y1)),
+        );
+        for_each_line_point(from, to, |x, y| {
+            if stroke.draws(index) {
+                for offset in -(width - 1) / 2..=width / 2 {
+                    match across {
+                        true => self.plot(x, y + offset, on),
+                        false => self.plot(x + offset, y, on),
+                    }
+                }
+            }
+            index += 1;
+        });
+    }
+}
@@ -0,0 +1,186 @@
+//! Rows of text in columns, with an optional header
+use crate::{Align, BuiltinFont, Font, Framebuffer, Layout, Rect, Size, Truncation, Widget};
+
+/// One column of a [`Table`](struct.Table.html): its title, how wide it is,
+/// and where its text goes across it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TableColumn<'a> {
+    /// Title shown in the header row
+    title: &'a str,
+    /// Width, as a part of the table's width
+    width: Size,
+    /// Where each cell's text goes across the column
+    align: Align,
+}
+
+impl<'a> TableColumn<'a> {
+    /// A column titled `title`, `width` wide, eg `Size::Fixed(40)` for five
+    /// characters of the monospace font, or `Size::Weight(1)` for a share of
+    /// what the fixed columns leave, with its text aligned left
+    pub const fn new(title: &'a str, width: Size) -> Self {
+        TableColumn {
+            title,
+            width,
+            align: Align::Left,
+        }
+    }
+
+    /// Aligns the column's text, eg right for numbers
+    pub const fn with_align(self, align: Align) -> Self {
+        TableColumn { align, ..self }
+    }
+}
+
+/// A [`Widget`](trait.Widget.html) showing rows of text in `N` columns, one
+/// row to a line, under an optional header in inverted text, eg network
+/// interfaces with their addresses, or processes with their CPU use.
+///
+/// Each cell's text is aligned within its column, and cut short with an
+/// ellipsis if it's too wide, or as set by
+/// [`with_truncation()`](#method.with_truncation), so a long value never spills
+/// into the next column or onto the next row. Text that's cut short starts at
+/// the column's left edge whatever the alignment, keeping its start readable.
+/// Columns are separated by a gap, of 2 pixels by default.
+///
+/// Rows that don't fit in the table's area are left out, unless scrolled to
+/// with [`set_first_row()`](#method.set_first_row).
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{Align, Oled, OledResult, Rect, Size, Table, TableColumn};
+/// # fn main() -> OledResult {
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// let rows = [["eth0", "192.168.1.20"], ["wlan0", "10.0.0.5"]];
+/// let mut table = Table::new(
+///     [
+///         TableColumn::new("If", Size::Fixed(40)),
+///         TableColumn::new("Address", Size::Weight(1)).with_align(Align::Right),
+///     ],
+///     &rows,
+/// );
+/// oled.render_widgets(&mut [(&mut table, Rect::new(0, 0, 128, 64))])?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Table<'a, T, const N: usize> {
+    /// Columns, from left to right
+    columns: [TableColumn<'a>; N],
+    /// Rows, from top to bottom, each a cell for each column
+    rows: &'a [[T; N]],
+    /// Index of the row shown first
+    first: usize,
+    /// Whether the header row is shown
+    header: bool,
+    /// Font the text is drawn in
+    font: BuiltinFont,
+    /// Pixels between each column and the next
+    gap: u16,
+    /// How text too wide for its column is cut short
+    truncation: Truncation,
+    /// Whether anything has changed since the table was last rendered
+    changed: bool,
+}
+
+impl<'a, T: AsRef<str>, const N: usize> Table<'a, T, N> {
+    /// Creates a table of `rows` in `columns`, in the monospace built-in font,
+    /// with a header
+    pub fn new(columns: [TableColumn<'a>; N], rows: &'a [[T; N]]) -> Self {
+        Table {
+            columns,
+            rows,
+            first: 0,
+            header: true,
+            font: BuiltinFont::MONOSPACE,
+            gap: 2,
+            truncation: Truncation::Ellipsis,
+            changed: true,
+        }
+    }
+
+    /// Shows the header row with the columns' titles, or not
+    pub fn with_header(self, header: bool) -> Self {
+        Table { header, ..self }
+    }
+
+    /// Draws the text in `font`, eg the proportional built-in font, which fits
+    /// more into narrow columns
+    pub fn with_font(self, font: BuiltinFont) -> Self {
+        Table { font, ..self }
+    }
+
+    /// Leaves `gap` pixels between each column and the next
+    pub fn with_gap(self, gap: u16) -> Self {
+        Table { gap, ..self }
+    }
+
+    /// Cuts text too wide for its column short by `truncation`, rather than
+    /// with an ellipsis
+    pub fn with_truncation(self, truncation: Truncation) -> Self {
+        Table { truncation, ..self }
+    }
+
+    /// Changes the rows shown, eg when the values in them are updated
+    pub fn set_rows(&mut self, rows: &'a [[T; N]]) {
+        self.rows = rows;
+        self.changed = true;
+    }
+
+    /// Shows the rows from the one at `index`, below the header, or from the
+    /// last if there aren't that many
+    pub fn set_first_row(&mut self, index: usize) {
+        let index = index.min(self.rows.len().saturating_sub(1));
+        self.changed |= index != self.first;
+        self.first = index;
+    }
+
+    /// Index of the row shown first
+    pub fn first_row(&self) -> usize {
+        self.first
+    }
+
+    /// Draws `text` in `cell`, aligned by `align` unless it's too wide
+    fn draw_cell(&self, frame: &mut Framebuffer, cell: Rect, text: &str, align: Align) {
+        let width = self.font.text_width(text);
+        let spare = cell.width.saturating_sub(width);
+        let x = match align {
+            Align::Left => cell.x,
+            Align::Center => cell.x + spare / 2,
+            Align::Right => cell.x + spare,
+        };
+        let (x, y) = (i32::from(x), i32::from(cell.y));
+        frame.flip_text_fit(x, y, text, &self.font, cell, self.truncation);
+    }
+}
+
+impl<T: AsRef<str>, const N: usize> Widget for Table<'_, T, N> {
+    fn render(&mut self, frame: &mut Framebuffer, area: Rect) {
+        frame.fill_rect(area.x, area.y, area.width, area.height, false);
+        let line_height = self.font.line_height();
+        let layout = Layout::columns(self.columns.map(|column| column.width)).with_gap(self.gap);
+        let mut rest = area;
+        if self.header {
+            let (row, below) = rest.split_top(line_height);
+            rest = below;
+            frame.fill_rect(row.x, row.y, row.width, row.height, true);
+            for (column, cell) in self.columns.iter().zip(layout.split(row)) {
+                self.draw_cell(frame, cell, column.title, column.align);
+            }
+        }
+        for cells in self.rows.iter().skip(self.first) {
+            let (row, below) = rest.split_top(line_height);
+            if row.height < line_height {
+                break;
+            }
+            rest = below;
+            for ((column, cell), text) in self.columns.iter().zip(layout.split(row)).zip(cells) {
+                self.draw_cell(frame, cell, text.as_ref(), column.align);
+            }
+        }
+        self.changed = false;
+    }
+
+    fn needs_render(&self) -> bool {
+        self.changed
+    }
+}
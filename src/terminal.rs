@@ -0,0 +1,137 @@
+//! An interface that shows the display in a terminal, for developing without the device
+use std::io::{self, Stdout, Write};
+
+use crate::state::DisplayState;
+use crate::{OledError, OledInterface, OLED_HEIGHT, OLED_WIDTH};
+
+/// Moves the cursor to the top left of the terminal
+const CURSOR_HOME: &str = "\x1b[H";
+
+/// Clears the terminal
+const CLEAR_SCREEN: &str = "\x1b[2J";
+
+/// An [`OledInterface`](trait.OledInterface.html) with no display behind it,
+/// which instead draws what the display would show in a terminal, two rows of
+/// pixels to a line of half-block characters, inside a border the size of the
+/// panel. The picture is redrawn in place whenever what's shown changes, eg
+/// after each [`flush()`](struct.Oled.html#method.flush), so UI code can be
+/// developed over SSH without the device.
+///
+/// Commands are followed as the controller would follow them, so the picture
+/// stays blank until the display is turned on, eg by
+/// [`init()`](struct.Oled.html#method.init), and inverting, rotating and
+/// sleeping the display all show. The terminal needs to be at least 130
+/// characters wide and 34 lines high.
+/// # Example:
+/// ```no_run
+/// use nanohat_oled::{Oled, OledResult, TerminalSimulator};
+///
+/// fn main() -> OledResult {
+///     let mut oled = Oled::new(TerminalSimulator::new());
+///     oled.init()?;
+///     oled.put_string("Hello, world!")?;
+///     Ok(())
+/// }
+/// ```
+pub struct TerminalSimulator<W = Stdout> {
+    /// Where the picture is drawn
+    out: W,
+    /// The controller, as commanded so far
+    state: DisplayState,
+    /// The picture last drawn, without escape codes
+    frame: String,
+    /// Number of times the picture has been drawn
+    frames: usize,
+}
+
+impl TerminalSimulator {
+    /// Creates a simulator that draws to standard output
+    pub fn new() -> Self {
+        TerminalSimulator::from_writer(io::stdout())
+    }
+}
+
+impl Default for TerminalSimulator {
+    fn default() -> Self {
+        TerminalSimulator::new()
+    }
+}
+
+impl<W: Write> TerminalSimulator<W> {
+    /// Creates a simulator that draws to `out`, eg a file watched with `tail -f`,
+    /// or a `Vec<u8>` in tests
+    pub fn from_writer(out: W) -> Self {
+        TerminalSimulator {
+            out,
+            state: DisplayState::default(),
+            frame: String::new(),
+            frames: 0,
+        }
+    }
+
+    /// The picture last drawn, a line of text for each two rows of pixels,
+    /// with its border but without the escape codes drawing it in place.
+    /// Empty until the first picture is drawn.
+    pub fn frame(&self) -> &str {
+        &self.frame
+    }
+
+    /// Number of times the picture has been drawn
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    /// Consumes the simulator, returning what it was drawing to
+    pub fn into_writer(self) -> W {
+        self.out
+    }
+
+    /// The picture the display shows, in half-block characters
+    fn render(&self) -> String {
+        let border = "─".repeat(usize::from(OLED_WIDTH));
+        let mut frame = format!("┌{}┐\n", border);
+        for y in (0..OLED_HEIGHT).step_by(2) {
+            frame.push('│');
+            for x in 0..OLED_WIDTH {
+                frame.push(match (self.state.lit(x, y), self.state.lit(x, y + 1)) {
+                    (true, true) => '█',
+                    (true, false) => '▀',
+                    (false, true) => '▄',
+                    (false, false) => ' ',
+                });
+            }
+            frame.push_str("│\n");
+        }
+        frame.push_str(&format!("└{}┘\n", border));
+        frame
+    }
+
+    /// Draws the picture again, if it has changed since it was last drawn
+    fn redraw(&mut self) -> Result<(), OledError> {
+        let frame = self.render();
+        if frame == self.frame {
+            return Ok(());
+        }
+        if self.frames == 0 {
+            self.out.write_all(CLEAR_SCREEN.as_bytes())?;
+        }
+        self.out.write_all(CURSOR_HOME.as_bytes())?;
+        self.out.write_all(frame.as_bytes())?;
+        self.out.flush()?;
+        self.frame = frame;
+        self.frames += 1;
+        Ok(())
+    }
+}
+
+impl<W: Write> OledInterface for TerminalSimulator<W> {
+    fn send_command(&mut self, command: u8) -> Result<(), OledError> {
+        self.state.command(command);
+        self.redraw()
+    }
+
+    fn send_data(&mut self, data: &[u8]) -> Result<(), OledError> {
+        self.state.write(data);
+        self.redraw()
+    }
+}
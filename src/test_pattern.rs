@@ -0,0 +1,153 @@
+//! Test patterns and a self-test, for bringing up a display and telling wiring faults from bugs
+#[cfg(feature = "std")]
+use std::{
+    thread::sleep,
+    time::{Duration, Instant},
+};
+
+use crate::dither::BAYER_4X4;
+#[cfg(feature = "std")]
+use crate::{Command, OledError};
+use crate::{Oled, OledInterface, OledResult};
+
+/// A picture for checking a display, as shown by
+/// [`Oled::show_test_pattern()`](struct.Oled.html#method.show_test_pattern)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Pattern {
+    /// 8x8 squares, one text cell each, showing up pages or columns out of place
+    Checkerboard,
+    /// Alternate columns lit, showing up columns stuck together or missing
+    VStripes,
+    /// Alternate rows lit, showing up rows stuck together or missing
+    HStripes,
+    /// A frame around the edge, showing whether the whole panel is in view
+    Border,
+    /// Dithered from unlit at the left to lit at the right
+    Gradient,
+}
+
+impl Pattern {
+    /// Every pattern, in the order [`Oled::self_test()`](struct.Oled.html#method.self_test)
+    /// shows them
+    pub const ALL: [Pattern; 5] = [
+        Pattern::Checkerboard,
+        Pattern::VStripes,
+        Pattern::HStripes,
+        Pattern::Border,
+        Pattern::Gradient,
+    ];
+
+    /// Whether the pixel at `x`, `y` is lit on a display `width` by `height` pixels
+    pub fn is_lit(self, x: u16, y: u16, width: u16, height: u16) -> bool {
+        match self {
+            Pattern::Checkerboard => (x / 8 + y / 8).is_multiple_of(2),
+            Pattern::VStripes => x.is_multiple_of(2),
+            Pattern::HStripes => y.is_multiple_of(2),
+            Pattern::Border => x == 0 || y == 0 || x + 1 == width || y + 1 == height,
+            Pattern::Gradient => {
+                let gray = u32::from(x) * 256 / u32::from(width.max(1));
+                let threshold =
+                    u32::from(BAYER_4X4[usize::from(y % 4)][usize::from(x % 4)]) * 16 + 8;
+                gray >= threshold
+            }
+        }
+    }
+}
+
+/// What [`Oled::self_test()`](struct.Oled.html#method.self_test) found
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct SelfTest {
+    /// The number of steps carried out, from showing each pattern to putting
+    /// back what was shown
+    pub steps: u32,
+    /// The number of steps that failed on the bus
+    pub failures: u32,
+    /// The error the last failed step returned
+    pub last_error: Option<OledError>,
+    /// The longest a pattern took to send, a full frame of data, which on a
+    /// healthy 400kHz I2C bus is around 25ms
+    pub frame_time: Duration,
+}
+
+#[cfg(feature = "std")]
+impl SelfTest {
+    /// Whether every step got through to the display
+    pub fn passed(&self) -> bool {
+        self.failures == 0
+    }
+}
+
+impl<I: OledInterface> Oled<I> {
+    /// Fills the display with a test `pattern`, the way round it's currently turned
+    /// # Example:
+    /// ```no_run
+    /// # use nanohat_oled::{Oled, OledResult, Pattern};
+    /// # fn main() -> OledResult {
+    /// let mut oled = Oled::from_path("/dev/i2c-0")?;
+    /// oled.init()?;
+    /// oled.show_test_pattern(Pattern::Border)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn show_test_pattern(&mut self, pattern: Pattern) -> OledResult {
+        let (width, height) = self.dimensions();
+        self.draw_fn(|x, y| pattern.is_lit(x, y, width, height))
+    }
+
+    /// Runs through every [`Pattern`](enum.Pattern.html), then inverts the display,
+    /// and lights every pixel with [`all_on()`](struct.Oled.html#method.all_on),
+    /// holding each for `interval` so they can be checked by eye. Steps that fail
+    /// on the bus are counted rather than stopping the test, so a flaky connection
+    /// shows up as some failures, and a missing or miswired display as all of them.
+    /// Blocks until done, and afterwards puts back what was shown, as it was.
+    /// # Example:
+    /// ```no_run
+    /// # use nanohat_oled::{Oled, OledResult};
+    /// # use std::time::Duration;
+    /// # fn main() -> OledResult {
+    /// let mut oled = Oled::from_path("/dev/i2c-0")?;
+    /// oled.init()?;
+    /// let report = oled.self_test(Duration::from_secs(1));
+    /// println!("{}/{} steps failed, frames took {:?}", report.failures, report.steps, report.frame_time);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn self_test(&mut self, interval: Duration) -> SelfTest {
+        let mut report = SelfTest {
+            steps: 0,
+            failures: 0,
+            last_error: None,
+            frame_time: Duration::ZERO,
+        };
+        let record = |report: &mut SelfTest, result: OledResult| {
+            report.steps += 1;
+            if let Err(err) = result {
+                report.failures += 1;
+                report.last_error = Some(err);
+            }
+        };
+        let saved = *self.framebuffer();
+        for pattern in Pattern::ALL.iter() {
+            let started = Instant::now();
+            let result = self.show_test_pattern(*pattern);
+            report.frame_time = report.frame_time.max(started.elapsed());
+            record(&mut report, result);
+            sleep(interval);
+        }
+        let (flipped, restored) = if self.is_inverted() {
+            (Command::NormalDisplay, Command::InverseDisplay)
+        } else {
+            (Command::InverseDisplay, Command::NormalDisplay)
+        };
+        record(&mut report, self.send_command(flipped));
+        sleep(interval);
+        record(&mut report, self.send_command(restored));
+        record(&mut report, self.all_on(true));
+        sleep(interval);
+        record(&mut report, self.all_on(false));
+        record(&mut report, self.write_ram(&saved));
+        report
+    }
+}
@@ -0,0 +1,493 @@
+//! Helpers for laying out text on the display's character grid
+use core::fmt;
+
+use crate::{
+    Oled, OledError, OledInterface, OledResult, OLED_HEIGHT, OLED_PAGE_HEIGHT, OLED_WIDTH,
+};
+
+/// Width and height of a character cell in the built-in font, in pixels
+pub(crate) const CHAR_SIZE: u16 = 8;
+
+/// An iterator over the display lines of a piece of text, as returned by
+/// [`Oled::layout()`](struct.Oled.html#method.layout) and
+/// [`Oled::layout_words()`](struct.Oled.html#method.layout_words).
+///
+/// Each `\n` starts a new line, as does running out of columns.
+/// Lines are slices of the original text, without the `\n`.
+#[derive(Clone, Debug)]
+pub struct TextLines<'a> {
+    /// Text not yet split into lines, or `None` when done
+    remaining: Option<&'a str>,
+    /// Maximum number of characters per line
+    columns: usize,
+    /// Columns already taken up at the start of the first line
+    indent: usize,
+    /// Whether lines are broken at spaces where possible
+    words: bool,
+}
+
+impl<'a> TextLines<'a> {
+    /// Splits `text` into lines of at most `columns` characters
+    pub(crate) fn new(text: &'a str, columns: usize) -> Self {
+        Self {
+            remaining: Some(text),
+            columns: columns.max(1),
+            indent: 0,
+            words: false,
+        }
+    }
+
+    /// Breaks lines at the last space that fits, rather than mid-word.
+    /// Words longer than a whole line are still broken.
+    pub(crate) fn words(self) -> Self {
+        Self {
+            words: true,
+            ..self
+        }
+    }
+
+    /// Leaves room for `indent` characters already on the first line
+    pub(crate) fn indented(self, indent: usize) -> Self {
+        Self { indent, ..self }
+    }
+}
+
+impl<'a> Iterator for TextLines<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        let text = self.remaining?;
+        let columns = self.columns.saturating_sub(self.indent).max(1);
+        // after an indent, even a word right at the start can move to the next line
+        let indented = self.indent > 0;
+        self.indent = 0;
+        let mut end = text.len();
+        let mut next_start = None;
+        for (count, (index, char)) in text.char_indices().enumerate() {
+            if char == '\n' {
+                end = index;
+                next_start = Some(index + 1);
+                break;
+            }
+            if count == columns {
+                end = index;
+                next_start = Some(index);
+                if self.words {
+                    // the space a line breaks at is dropped
+                    if char == ' ' {
+                        next_start = Some(index + 1);
+                    } else if let Some(space) = text[..index]
+                        .rfind(' ')
+                        .filter(|space| *space > 0 || indented)
+                    {
+                        end = space;
+                        next_start = Some(space + 1);
+                    }
+                }
+                break;
+            }
+        }
+        self.remaining = next_start.map(|start| &text[start..]);
+        Some(&text[..end])
+    }
+}
+
+/// What happens to text that runs past the bottom row of the display,
+/// as used by [`Oled::put_text()`](struct.Oled.html#method.put_text)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Overflow {
+    /// The rest of the text isn't written
+    Clip,
+    /// Everything on the display moves up a row to make room, like a terminal
+    Scroll,
+}
+
+/// Where writing text in the built-in font's cells left the text cursor, as
+/// returned by [`Oled::put_string()`](struct.Oled.html#method.put_string) and
+/// [`Oled::put_char()`](struct.Oled.html#method.put_char)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct TextCursor {
+    /// Column of the cell the next character goes in
+    pub column: u8,
+    /// Row of the cell the next character goes in
+    pub row: u8,
+    /// How many times the text ran off the end of a row, and carried on at the
+    /// start of that row or the next, as the addressing mode has it
+    pub wraps: u16,
+}
+
+/// How text in the built-in font is drawn, as set by
+/// [`Oled::set_text_style()`](struct.Oled.html#method.set_text_style)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextStyle {
+    /// White on black
+    Normal,
+    /// Black on white, filling each character's whole 8x8 cell, eg to highlight
+    /// a selected menu item or a header
+    Inverted,
+}
+
+impl TextStyle {
+    /// A glyph's columns, as drawn in this style
+    pub(crate) fn apply(self, mut glyph: [u8; 8]) -> [u8; 8] {
+        if self == TextStyle::Inverted {
+            for column in glyph.iter_mut() {
+                *column = !*column;
+            }
+        }
+        glyph
+    }
+}
+
+impl<I> Oled<I> {
+    /// Splits text into the lines it would occupy on the display with the built-in font,
+    /// wrapping at every `\n` and at as many characters as fit across the display as
+    /// it's turned: [`TEXT_COLUMNS`](constant.TEXT_COLUMNS.html) on the NanoHat's panel,
+    /// 8 after a quarter turn, and fewer on a [smaller panel](enum.PanelSize.html).
+    /// # Example:
+    /// ```no_run
+    /// # use nanohat_oled::Oled;
+    /// # let oled = Oled::from_path("/dev/i2c-0").unwrap();
+    /// let lines: Vec<&str> = oled.layout("Temperature: 21.5C\nOK").collect();
+    /// assert_eq!(lines, ["Temperature: 21.", "5C", "OK"]);
+    /// ```
+    pub fn layout<'a>(&self, text: &'a str) -> TextLines<'a> {
+        TextLines::new(text, usize::from(self.text_grid().0))
+    }
+
+    /// Splits text into lines like [`layout()`](struct.Oled.html#method.layout), but
+    /// breaks lines at spaces, so words aren't split across lines unless they are
+    /// too long to fit on one. The space a line is broken at is dropped.
+    /// # Example:
+    /// ```no_run
+    /// # use nanohat_oled::Oled;
+    /// # let oled = Oled::from_path("/dev/i2c-0").unwrap();
+    /// let lines: Vec<&str> = oled.layout_words("Temperature: 21.5C\nOK").collect();
+    /// assert_eq!(lines, ["Temperature:", "21.5C", "OK"]);
+    /// ```
+    pub fn layout_words<'a>(&self, text: &'a str) -> TextLines<'a> {
+        TextLines::new(text, usize::from(self.text_grid().0)).words()
+    }
+
+    /// Returns the width and height in pixels that text would occupy, once laid out
+    /// as by [`layout()`](struct.Oled.html#method.layout).
+    pub fn measure_string(&self, text: &str) -> (u16, u16) {
+        let (columns, rows) = self.layout(text).fold((0, 0), |(columns, rows), line| {
+            (columns.max(line.chars().count() as u16), rows + 1)
+        });
+        (columns * CHAR_SIZE, rows * CHAR_SIZE)
+    }
+
+    /// Returns the style set by [`set_text_style()`](struct.Oled.html#method.set_text_style)
+    pub fn text_style(&self) -> TextStyle {
+        self.text_style
+    }
+
+    /// The number of text columns and rows that fit across and down the display
+    /// as it's turned, with the built-in font
+    pub(crate) fn text_grid(&self) -> (u8, u8) {
+        let (width, height) = self.dimensions();
+        ((width / CHAR_SIZE) as u8, (height / CHAR_SIZE) as u8)
+    }
+}
+
+impl<I: OledInterface> Oled<I> {
+    /// Sets how [`put_char()`](struct.Oled.html#method.put_char),
+    /// [`put_string()`](struct.Oled.html#method.put_string) and the other functions
+    /// that write text in the built-in font draw it, until it is set again.
+    /// Unlike [`Command::InverseDisplay`](enum.Command.html#variant.InverseDisplay),
+    /// [`TextStyle::Inverted`](enum.TextStyle.html#variant.Inverted) only affects
+    /// the text written while it is set.
+    /// # Example:
+    /// ```no_run
+    /// # use nanohat_oled::{Oled, OledResult, TextStyle};
+    /// # fn main() -> OledResult {
+    /// let mut oled = Oled::from_path("/dev/i2c-0")?;
+    /// oled.init()?;
+    /// oled.set_text_style(TextStyle::Inverted);
+    /// oled.put_string("    Settings    ")?;
+    /// oled.set_text_style(TextStyle::Normal);
+    /// oled.set_text_xy(0, 1)?;
+    /// oled.put_string("Brightness")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_text_style(&mut self, style: TextStyle) {
+        self.text_style = style;
+    }
+
+    /// Writes a string with its top left corner at any pixel position, rather than
+    /// snapping to the 8-pixel text cells of [`set_text_xy()`](struct.Oled.html#method.set_text_xy).
+    ///
+    /// Each glyph replaces the 8x8 pixels underneath it, leaving everything else on
+    /// the display alone, and text running off the right or bottom edge is clipped.
+    /// Only the pages and columns the text touches are sent to the display.
+    /// The text cursor is left at the top left of the display afterwards.
+    pub fn put_string_at(&mut self, x: u16, y: u16, text: &str) -> OledResult {
+        if x >= OLED_WIDTH || y >= OLED_HEIGHT || text.is_empty() {
+            return Ok(());
+        }
+        let mut buffer = *self.framebuffer();
+        let first_page = y / OLED_PAGE_HEIGHT;
+        let last_page =
+            ((y + CHAR_SIZE - 1) / OLED_PAGE_HEIGHT).min(OLED_HEIGHT / OLED_PAGE_HEIGHT - 1);
+        let shift = y % OLED_PAGE_HEIGHT;
+        let mut last_column = x;
+        for (index, char) in text.chars().enumerate() {
+            let start = x as usize + index * CHAR_SIZE as usize;
+            if start >= OLED_WIDTH as usize {
+                break;
+            }
+            for (offset, glyph_column) in self
+                .text_style
+                .apply(self.custom_glyphs.bitmap(char))
+                .iter()
+                .enumerate()
+            {
+                let column = start + offset;
+                if column >= OLED_WIDTH as usize {
+                    break;
+                }
+                // the glyph's 8 rows, spread across one or two pages
+                let bits = u16::from(*glyph_column) << shift;
+                let mask = 0xffu16 << shift;
+                for page in first_page..=last_page {
+                    let page_shift = (page - first_page) * OLED_PAGE_HEIGHT;
+                    let byte = &mut buffer[page as usize * OLED_WIDTH as usize + column];
+                    *byte = (*byte & !((mask >> page_shift) as u8)) | (bits >> page_shift) as u8;
+                }
+                last_column = column as u16;
+            }
+        }
+        self.write_window(
+            (x as u8, last_column as u8),
+            (first_page as u8, last_page as u8),
+            &buffer,
+        )
+    }
+
+    /// Writes a string in the built-in font blown up `scale` times, eg 2 for
+    /// 16x16 pixel characters, with its top left corner at `x`, `y`, for readouts
+    /// that have to be seen from a distance. Each pixel of a glyph becomes a
+    /// `scale` by `scale` block, and each glyph replaces the pixels underneath it.
+    /// Text running off the right or bottom edge is clipped, and only the part
+    /// of the display that changes is sent.
+    ///
+    /// Returns `InvalidArgument` if `scale` is 0.
+    /// # Example:
+    /// ```no_run
+    /// # use nanohat_oled::{Oled, OledResult};
+    /// # fn main() -> OledResult {
+    /// let mut oled = Oled::from_path("/dev/i2c-0")?;
+    /// oled.init()?;
+    /// // 12:34 at 3x is 120 pixels wide, filling most of the display
+    /// oled.put_string_scaled(4, 20, "12:34", 3)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn put_string_scaled(&mut self, x: u16, y: u16, text: &str, scale: u8) -> OledResult {
+        if scale == 0 {
+            return Err(OledError::InvalidArgument("text scale must be at least 1"));
+        }
+        let scale = u16::from(scale);
+        let cell = CHAR_SIZE * scale;
+        let (glyphs, style) = (self.custom_glyphs, self.text_style);
+        self.draw(|frame| {
+            let (width, height) = frame.dimensions();
+            for (index, char) in text.chars().enumerate() {
+                let left = x.saturating_add((index as u16).saturating_mul(cell));
+                if left >= width {
+                    break;
+                }
+                for (column, bits) in style.apply(glyphs.bitmap(char)).iter().enumerate() {
+                    for row in 0..CHAR_SIZE {
+                        let lit = bits & (1 << row) != 0;
+                        for dy in 0..scale {
+                            let py = y.saturating_add(row * scale + dy);
+                            if py >= height {
+                                break;
+                            }
+                            for dx in 0..scale {
+                                frame.set_pixel(left + column as u16 * scale + dx, py, lit);
+                            }
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    /// Returns the text cell the next character goes in, as its column and row,
+    /// eg to carry on writing after earlier text once something else has been
+    /// written elsewhere, with [`set_text_xy()`](struct.Oled.html#method.set_text_xy).
+    ///
+    /// Without a portrait rotation, the cursor is the display's RAM pointer, as
+    /// moved by the characters written and the
+    /// [`AddressingMode`](enum.AddressingMode.html). A pointer part way into a
+    /// cell, as left by graphics, counts as being in the next cell, and one past
+    /// the last column as being at the start of the next row.
+    /// # Example:
+    /// ```no_run
+    /// # use nanohat_oled::{Oled, OledResult};
+    /// # fn main() -> OledResult {
+    /// let mut oled = Oled::from_path("/dev/i2c-0")?;
+    /// oled.init()?;
+    /// oled.put_string("Uptime: ")?;
+    /// let (column, row) = oled.cursor();
+    /// oled.set_text_xy(0, 7)?;
+    /// oled.put_string("Updating...")?;
+    /// oled.set_text_xy(column, row)?;
+    /// oled.put_string("3 days")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn cursor(&self) -> (u8, u8) {
+        let (columns, rows) = self.text_grid();
+        match self.text_cell() {
+            (column, row) if column >= columns => (0, (row + 1) % rows.max(1)),
+            cell => cell,
+        }
+    }
+
+    /// The text cell the text cursor or RAM pointer is in, with a column past
+    /// the last one if it's run off the end of a row
+    fn text_cell(&self) -> (u8, u8) {
+        if self.rotation.is_portrait() {
+            self.text_cursor
+        } else {
+            let (column, page) = self.state.pointer();
+            ((column as u16).div_ceil(CHAR_SIZE) as u8, page)
+        }
+    }
+
+    /// Writes text starting at the current text cursor, laid out for reading:
+    /// `\n` moves to the start of the next row, and lines are broken at spaces
+    /// as by [`layout_words()`](struct.Oled.html#method.layout_words).
+    /// Text past the bottom row is dropped or scrolls the display, as `overflow` says.
+    /// Afterwards, the cursor is left just after the text, so further text follows on.
+    ///
+    /// Unlike [`put_string()`](struct.Oled.html#method.put_string), which writes
+    /// characters wherever the display's RAM pointer goes next, this works out each
+    /// row itself and moves the pointer to its start. That needs the display in
+    /// horizontal or page addressing mode, as each character's columns are written
+    /// along a page; in vertical mode they would run down the display instead.
+    /// # Example:
+    /// ```no_run
+    /// # use nanohat_oled::{Oled, OledResult, Overflow};
+    /// # fn main() -> OledResult {
+    /// let mut oled = Oled::from_path("/dev/i2c-0")?;
+    /// oled.init()?;
+    /// oled.put_text("Network up\nAddress: 192.168.1.20\n", Overflow::Scroll)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn put_text(&mut self, text: &str, overflow: Overflow) -> OledResult {
+        let (columns, rows) = self.text_grid();
+        let (mut column, mut row) = self.text_cell();
+        if column >= columns {
+            column = 0;
+            row += 1;
+        }
+        let lines = TextLines::new(text, columns as usize)
+            .words()
+            .indented(column as usize);
+        for (index, line) in lines.enumerate() {
+            if index > 0 {
+                column = 0;
+                row += 1;
+            }
+            if row >= rows {
+                match overflow {
+                    Overflow::Clip => return Ok(()),
+                    Overflow::Scroll => self.scroll_text_up()?,
+                }
+                row = rows - 1;
+            }
+            self.set_text_xy(column, row)?;
+            for char in line.chars() {
+                self.put_char(char)?;
+            }
+            column += line.chars().count() as u8;
+        }
+        Ok(())
+    }
+
+    /// Moves everything on the display up one text row, clearing the bottom row
+    fn scroll_text_up(&mut self) -> OledResult {
+        let mode = self.state.mode;
+        self.draw(|frame| {
+            let (width, height) = frame.dimensions();
+            for y in 0..height {
+                for x in 0..width {
+                    frame.set_pixel(x, y, frame.get_pixel(x, y + CHAR_SIZE));
+                }
+            }
+        })?;
+        // redrawing switches to horizontal mode, which text positioning can't use
+        if self.state.mode != mode {
+            self.set_addressing_mode(mode)?;
+        }
+        Ok(())
+    }
+}
+
+/// Passes formatted text to an [`Oled`](struct.Oled.html), keeping hold of the
+/// first error, since `fmt::Error` can't carry it
+struct TextWriter<'a, I> {
+    /// The display being written to
+    oled: &'a mut Oled<I>,
+    /// Why writing stopped, if it did
+    error: Option<OledError>,
+}
+
+impl<I: OledInterface> fmt::Write for TextWriter<'_, I> {
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        self.oled.put_text(text, Overflow::Scroll).map_err(|err| {
+            self.error = Some(err);
+            fmt::Error
+        })
+    }
+}
+
+impl<I: OledInterface> Oled<I> {
+    /// Writes formatted text at the text cursor, so that `write!` and `writeln!`
+    /// can be used on the display directly. Text is laid out as by
+    /// [`put_text()`](struct.Oled.html#method.put_text), scrolling once it reaches
+    /// the bottom row, like a terminal.
+    ///
+    /// Returns the error from the display if writing fails, or `InvalidArgument`
+    /// if one of the values being formatted reports an error.
+    /// # Example:
+    /// ```no_run
+    /// # use nanohat_oled::{Oled, OledResult};
+    /// # fn main() -> OledResult {
+    /// # let temperature = 21.5;
+    /// let mut oled = Oled::from_path("/dev/i2c-0")?;
+    /// oled.init()?;
+    /// writeln!(oled, "Temp: {:.1}C", temperature)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn write_fmt(&mut self, args: fmt::Arguments<'_>) -> OledResult {
+        let mut writer = TextWriter {
+            oled: self,
+            error: None,
+        };
+        fmt::write(&mut writer, args).map_err(|_| {
+            writer
+                .error
+                .unwrap_or(OledError::InvalidArgument("a value couldn't be formatted"))
+        })
+    }
+}
+
+/// Lets the display be used wherever a `fmt::Write` is expected. Text is written
+/// as by [`Oled::write_fmt()`](struct.Oled.html#method.write_fmt), but since
+/// `fmt::Error` carries no details, any error from the display is lost; call
+/// `write!` on the `Oled` itself to get it back.
+impl<I: OledInterface> fmt::Write for Oled<I> {
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        self.put_text(text, Overflow::Scroll)
+            .map_err(|_| fmt::Error)
+    }
+}
@@ -0,0 +1,264 @@
+//! Logging what is sent to the display, for debugging garbled output without a logic analyzer
+use log::*;
+use std::fmt;
+use std::io::Write;
+
+use crate::state::{argument_count, DisplayState};
+use crate::{Command, OledError, OledInterface, COMMAND_MODE, DATA_MODE};
+
+/// Commands named by their first byte alone
+const COMMANDS: [Command; 24] = [
+    Command::SetContrast,
+    Command::ContentFollowsRam,
+    Command::EntireDisplayOn,
+    Command::SetAddressingMode,
+    Command::DisplayOff,
+    Command::DisplayOn,
+    Command::NormalDisplay,
+    Command::InverseDisplay,
+    Command::SetPrechargePeriod,
+    Command::SetVcomhDeselectLevel,
+    Command::SetColumnAddress,
+    Command::SetPageAddress,
+    Command::SetVerticalScrollArea,
+    Command::RightHorizontalScroll,
+    Command::LeftHorizontalScroll,
+    Command::VerticalRightHorizontalScroll,
+    Command::VerticalLeftHorizontalScroll,
+    Command::SetFadeMode,
+    Command::DeactivateScroll,
+    Command::ActivateScroll,
+    Command::SegmentRemapNormal,
+    Command::SegmentRemapReversed,
+    Command::ComScanNormal,
+    Command::ComScanRemapped,
+];
+
+/// What a command and its arguments do, named as in
+/// [`Instruction`](enum.Instruction.html) where it has them
+struct Meaning<'a> {
+    /// The command byte
+    command: u8,
+    /// The arguments after it
+    args: &'a [u8],
+}
+
+impl fmt::Display for Meaning<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.command, self.args) {
+            (0x00..=0x0f, _) => write!(f, "SetColumnStartLow({:#x})", self.command),
+            (0x10..=0x17, _) => write!(f, "SetColumnStartHigh({:#x})", (self.command & 0x07) << 4),
+            (0x40..=0x7f, _) => write!(f, "SetStartLine({})", self.command & 0x3f),
+            (0xb0..=0xb7, _) => write!(f, "SetPageStart({})", self.command & 0x07),
+            (0x20, [mode]) => match mode & 0x03 {
+                0x00 => write!(f, "SetAddressingMode(Horizontal)"),
+                0x01 => write!(f, "SetAddressingMode(Vertical)"),
+                0x02 => write!(f, "SetAddressingMode(Page)"),
+                _ => write!(f, "SetAddressingMode(invalid)"),
+            },
+            (0x21, [start, end]) => write!(f, "SetColumnAddress({}, {})", start, end),
+            (0x22, [start, end]) => write!(f, "SetPageAddress({}, {})", start, end),
+            (0x81, [contrast]) => write!(f, "SetContrast({})", contrast),
+            (0x8d, [pump]) => write!(f, "ChargePump({})", pump & 0x04 != 0),
+            (0xa8, [rows]) => write!(f, "SetMultiplex({})", u16::from(*rows & 0x3f) + 1),
+            (0xad, [dc_dc]) => write!(f, "DcDc({})", dc_dc & 0x01 != 0),
+            (0xd3, [rows]) => write!(f, "SetDisplayOffset({})", rows & 0x3f),
+            (0xd5, [clock]) => write!(
+                f,
+                "SetClock {{ divide: {}, frequency: {} }}",
+                (clock & 0x0f) + 1,
+                clock >> 4
+            ),
+            (0xd9, [phases]) => write!(f, "SetPrecharge({}, {})", phases & 0x0f, phases >> 4),
+            (0xda, [pins]) => write!(
+                f,
+                "SetComPins {{ alternative: {}, left_right_remap: {} }}",
+                pins & 0x10 != 0,
+                pins & 0x20 != 0
+            ),
+            (0xdb, [level]) => write!(f, "SetVcomhLevel({:#04x})", level),
+            (byte, args) => {
+                match COMMANDS.iter().find(|command| u8::from(**command) == byte) {
+                    Some(command) => write!(f, "{:?}", command)?,
+                    None => write!(f, "unknown")?,
+                }
+                if !args.is_empty() {
+                    write!(f, "({:02x?})", args)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Data bytes written one after another, with no commands between them
+#[derive(Clone, Copy, Debug)]
+struct Burst {
+    /// Number of bytes
+    len: usize,
+    /// First and last column written to
+    columns: (u8, u8),
+    /// First and last page written to
+    pages: (u8, u8),
+}
+
+/// An [`OledInterface`](trait.OledInterface.html) that logs everything sent
+/// through the interface it wraps, at the trace level, for debugging garbled
+/// output, eg a window left set up wrong, or data landing on the wrong page.
+///
+/// Each command is logged with its arguments, once they've all been sent, and
+/// what it does, eg `command [81, 7f]: SetContrast(127)`. Data sent one
+/// transfer after another is summarised as one burst, with the pages and
+/// columns it landed on, eg `data: 1024 bytes, pages 0-7, columns 0-127`,
+/// logged once the next command is sent. Only what the wrapped interface
+/// delivers is logged.
+///
+/// With [`with_capture()`](#method.with_capture), the raw stream is written
+/// out too, eg to a file to compare a good run against a bad one.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{Oled, OledError, TraceInterface};
+/// # use std::fs::File;
+/// # fn main() -> Result<(), OledError> {
+/// let bus = Oled::from_path("/dev/i2c-0")?.into_interface();
+/// let traced = TraceInterface::new(bus).with_capture(File::create("oled.bin")?);
+/// let mut oled = Oled::new(traced);
+/// oled.init()?;
+/// oled.put_string("Hello")?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct TraceInterface<I> {
+    /// The interface traced
+    interface: I,
+    /// Where the raw stream is written, if anywhere
+    capture: Option<Box<dyn Write + Send>>,
+    /// The display's state, to tell where data lands
+    state: DisplayState,
+    /// A command still waiting on arguments, with those sent so far, and how
+    /// many bytes that is
+    pending: Option<([u8; 7], usize)>,
+    /// Data sent since the last command
+    burst: Option<Burst>,
+}
+
+impl<I: OledInterface> TraceInterface<I> {
+    /// Traces what is sent through `interface`
+    pub fn new(interface: I) -> Self {
+        TraceInterface {
+            interface,
+            capture: None,
+            state: DisplayState::default(),
+            pending: None,
+            burst: None,
+        }
+    }
+
+    /// Writes every transaction to `capture` too, as it would go over I2C: its
+    /// control byte, [`COMMAND_MODE`](constant.COMMAND_MODE.html) or
+    /// [`DATA_MODE`](constant.DATA_MODE.html), then the number of bytes in it,
+    /// as two bytes, least significant first, then the bytes themselves. If
+    /// writing fails, a warning is logged and capturing stops.
+    pub fn with_capture<W: Write + Send + 'static>(self, capture: W) -> Self {
+        TraceInterface {
+            capture: Some(Box::new(capture)),
+            ..self
+        }
+    }
+
+    /// The interface traced
+    pub fn interface(&mut self) -> &mut I {
+        &mut self.interface
+    }
+
+    /// Consumes the tracer, returning the interface it was tracing
+    pub fn into_interface(self) -> I {
+        self.interface
+    }
+
+    /// Writes a transaction to the capture, if there is one
+    fn capture(&mut self, mode: u8, bytes: &[u8]) {
+        let Some(capture) = &mut self.capture else {
+            return;
+        };
+        let written = bytes
+            .chunks(u16::MAX as usize)
+            .try_for_each(|chunk| {
+                let len = (chunk.len() as u16).to_le_bytes();
+                capture.write_all(&[mode, len[0], len[1]])?;
+                capture.write_all(chunk)
+            })
+            .and_then(|_| capture.flush());
+        if let Err(err) = written {
+            warn!("Stopped capturing display traffic: {}", err);
+            self.capture = None;
+        }
+    }
+
+    /// Logs `commands`, delivered to the display, along with the data sent before them
+    fn trace_commands(&mut self, commands: &[u8]) {
+        if let Some(burst) = self.burst.take() {
+            trace!(
+                "data: {} bytes, pages {}-{}, columns {}-{}",
+                burst.len,
+                burst.pages.0,
+                burst.pages.1,
+                burst.columns.0,
+                burst.columns.1
+            );
+        }
+        for byte in commands {
+            self.state.command(*byte);
+            let (mut bytes, mut len) = self.pending.take().unwrap_or(([0; 7], 0));
+            bytes[len] = *byte;
+            len += 1;
+            if len <= argument_count(bytes[0]) {
+                self.pending = Some((bytes, len));
+                continue;
+            }
+            let meaning = Meaning {
+                command: bytes[0],
+                args: &bytes[1..len],
+            };
+            trace!("command {:02x?}: {}", &bytes[..len], meaning);
+        }
+    }
+}
+
+impl<I: OledInterface> OledInterface for TraceInterface<I> {
+    fn send_command(&mut self, command: u8) -> Result<(), OledError> {
+        self.interface.send_command(command)?;
+        self.capture(COMMAND_MODE, &[command]);
+        self.trace_commands(&[command]);
+        Ok(())
+    }
+
+    fn send_data(&mut self, data: &[u8]) -> Result<(), OledError> {
+        self.interface.send_data(data)?;
+        self.capture(DATA_MODE, data);
+        for byte in data {
+            let (column, page) = self.state.pointer();
+            let burst = self.burst.get_or_insert(Burst {
+                len: 0,
+                columns: (column, column),
+                pages: (page, page),
+            });
+            burst.len += 1;
+            burst.columns = (burst.columns.0.min(column), burst.columns.1.max(column));
+            burst.pages = (burst.pages.0.min(page), burst.pages.1.max(page));
+            self.state.write(&[*byte]);
+        }
+        Ok(())
+    }
+
+    fn send_commands(&mut self, commands: &[u8]) -> Result<(), OledError> {
+        self.interface.send_commands(commands)?;
+        self.capture(COMMAND_MODE, commands);
+        self.trace_commands(commands);
+        Ok(())
+    }
+
+    fn max_transfer_len(&self) -> usize {
+        self.interface.max_transfer_len()
+    }
+}
@@ -0,0 +1,242 @@
+//! Animated changes from one screen to another
+use core::time::Duration;
+
+use crate::{Framebuffer, Oled, OledError, OledInterface};
+
+/// How a [`ScreenTransition`](struct.ScreenTransition.html) gets from one screen
+/// to the next
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Effect {
+    /// The new screen pushes the old one out to the left
+    SlideLeft,
+    /// The new screen is uncovered from the left edge, over the old one
+    Wipe,
+    /// The new screen shows through the old one, a pixel at a time, in a
+    /// scattered order
+    Dissolve,
+}
+
+/// Steps a dissolve is made in: pixels change over in this many batches
+const DISSOLVE_STEPS: u32 = 256;
+
+/// Where the pixel at `x`, `y` comes in a dissolve, from 0 to 255, scattered
+/// so that neighbouring pixels change at different times
+fn dissolve_rank(x: u16, y: u16) -> u32 {
+    let mut hash = (u32::from(x) << 16 | u32::from(y)).wrapping_mul(0x9e37_79b9);
+    hash ^= hash >> 15;
+    hash = hash.wrapping_mul(0x85eb_ca6b);
+    (hash >> 24) & 0xff
+}
+
+impl Effect {
+    /// The number of steps from the old screen to the new one, for screens
+    /// `width` pixels wide
+    fn steps(self, width: u16) -> u32 {
+        match self {
+            Effect::SlideLeft | Effect::Wipe => u32::from(width),
+            Effect::Dissolve => DISSOLVE_STEPS,
+        }
+    }
+
+    /// Whether the pixel at `x`, `y` is lit after `step` steps from `from` to `to`
+    fn pixel(self, from: &Framebuffer, to: &Framebuffer, x: u16, y: u16, step: u32) -> bool {
+        match self {
+            Effect::SlideLeft => {
+                let (width, _) = from.dimensions();
+                let source = u32::from(x) + step;
+                if source < u32::from(width) {
+                    from.get_pixel(source as u16, y)
+                } else {
+                    to.get_pixel((source - u32::from(width)) as u16, y)
+                }
+            }
+            Effect::Wipe => {
+                if u32::from(x) < step {
+                    to.get_pixel(x, y)
+                } else {
+                    from.get_pixel(x, y)
+                }
+            }
+            Effect::Dissolve => {
+                if dissolve_rank(x, y) < step {
+                    to.get_pixel(x, y)
+                } else {
+                    from.get_pixel(x, y)
+                }
+            }
+        }
+    }
+}
+
+/// An animated change from one screen to another, eg between the pages of a
+/// dashboard, which looks smoother than cutting straight to the next.
+///
+/// Each step is sent with [`Oled::flush()`](struct.Oled.html#method.flush), so
+/// only what changed goes over the bus. Steps are worked out from the time
+/// passed, so a slow bus drops steps rather than stretching the transition out.
+/// Like an [`Animation`](struct.Animation.html), the transition doesn't read a
+/// clock itself: [`tick()`](#method.tick) is told how much time has passed, or
+/// [`play()`](#method.play) runs it through, as
+/// [`Oled::transition()`](struct.Oled.html#method.transition) does.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{Effect, Framebuffer, Oled, OledResult, ScreenTransition};
+/// # use std::time::Duration;
+/// # fn main() -> OledResult {
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// let from = Framebuffer::from_bytes(*oled.framebuffer());
+/// let mut to = Framebuffer::new();
+/// to.draw_rect(0, 0, 128, 64, true);
+/// let mut slide = ScreenTransition::new(&from, &to, Effect::SlideLeft, Duration::from_millis(500));
+/// while let Some(wait) = slide.tick(&mut oled, Duration::from_millis(20))? {
+///     std::thread::sleep(wait.max(Duration::from_millis(20)));
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ScreenTransition<'a> {
+    /// The screen changed from
+    from: &'a Framebuffer,
+    /// The screen changed to
+    to: &'a Framebuffer,
+    /// How one changes into the other
+    effect: Effect,
+    /// How long the whole change takes
+    duration: Duration,
+    /// Time since the start
+    elapsed: Duration,
+    /// Step last drawn, if any
+    drawn: Option<u32>,
+}
+
+impl<'a> ScreenTransition<'a> {
+    /// Creates a transition from `from` to `to`, taking `duration`. The two
+    /// should be the same way round, and the way round the display is turned.
+    pub fn new(
+        from: &'a Framebuffer,
+        to: &'a Framebuffer,
+        effect: Effect,
+        duration: Duration,
+    ) -> Self {
+        ScreenTransition {
+            from,
+            to,
+            effect,
+            duration,
+            elapsed: Duration::ZERO,
+            drawn: None,
+        }
+    }
+
+    /// The number of steps from one screen to the other
+    fn steps(&self) -> u32 {
+        self.effect.steps(self.from.dimensions().0)
+    }
+
+    /// The step reached after the time passed so far
+    fn step(&self) -> u32 {
+        let steps = self.steps();
+        if self.elapsed >= self.duration {
+            return steps;
+        }
+        (self.elapsed.as_nanos() * u128::from(steps) / self.duration.as_nanos()) as u32
+    }
+
+    /// Whether the new screen is fully shown
+    pub fn is_finished(&self) -> bool {
+        self.drawn == Some(self.steps())
+    }
+
+    /// Goes back to the start, so the next tick draws the old screen and starts over
+    pub fn reset(&mut self) {
+        self.elapsed = Duration::ZERO;
+        self.drawn = None;
+    }
+
+    /// The picture `step` steps from the old screen to the new one
+    pub fn frame(&self, step: u32) -> Framebuffer {
+        let mut frame = self.to.clone();
+        let (width, height) = frame.dimensions();
+        for y in 0..height {
+            for x in 0..width {
+                frame.set_pixel(x, y, self.effect.pixel(self.from, self.to, x, y, step));
+            }
+        }
+        frame
+    }
+
+    /// Moves the transition on by `elapsed`, the time since the last tick, and
+    /// sends the step it has reached, if that has changed. The first tick always
+    /// draws, so it can be passed `Duration::ZERO`.
+    ///
+    /// Returns how long until the next step, or `None` once the new screen is
+    /// fully shown. Returns `InvalidDimensions` if the screens aren't the way
+    /// round the display is turned.
+    pub fn tick<I: OledInterface>(
+        &mut self,
+        oled: &mut Oled<I>,
+        elapsed: Duration,
+    ) -> Result<Option<Duration>, OledError> {
+        self.elapsed = self.elapsed.saturating_add(elapsed);
+        let step = self.step();
+        if self.drawn != Some(step) {
+            oled.flush(&self.frame(step))?;
+            self.drawn = Some(step);
+        }
+        if self.is_finished() {
+            return Ok(None);
+        }
+        let next = self.duration * (step + 1) / self.steps();
+        Ok(Some(next.saturating_sub(self.elapsed)))
+    }
+
+    /// Runs the transition from the start, sleeping between steps, and returns
+    /// once the new screen is fully shown
+    #[cfg(feature = "std")]
+    pub fn play<I: OledInterface>(&mut self, oled: &mut Oled<I>) -> Result<(), OledError> {
+        self.reset();
+        let mut last = std::time::Instant::now();
+        let mut elapsed = Duration::ZERO;
+        while let Some(wait) = self.tick(oled, elapsed)? {
+            std::thread::sleep(wait);
+            let now = std::time::Instant::now();
+            elapsed = now - last;
+            last = now;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I: OledInterface> Oled<I> {
+    /// Animates the display from `from` to `to` with `effect`, taking `duration`,
+    /// and returns once `to` is fully shown. See
+    /// [`ScreenTransition`](struct.ScreenTransition.html) to run it alongside
+    /// other work instead.
+    /// # Example:
+    /// ```no_run
+    /// # use nanohat_oled::{Effect, Framebuffer, Oled, OledResult};
+    /// # use std::time::Duration;
+    /// # fn main() -> OledResult {
+    /// let mut oled = Oled::from_path("/dev/i2c-0")?;
+    /// oled.init()?;
+    /// let mut first = Framebuffer::new();
+    /// first.fill_rect(0, 0, 64, 64, true);
+    /// let second = Framebuffer::new();
+    /// oled.flush(&first)?;
+    /// oled.transition(&first, &second, Effect::Wipe, Duration::from_millis(300))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn transition(
+        &mut self,
+        from: &Framebuffer,
+        to: &Framebuffer,
+        effect: Effect,
+        duration: Duration,
+    ) -> Result<(), OledError> {
+        ScreenTransition::new(from, to, effect, duration).play(self)
+    }
+}
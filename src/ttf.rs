@@ -0,0 +1,738 @@
+//! Drawing text in TrueType fonts, turning their outlines into lit and unlit pixels
+use std::convert::TryFrom;
+
+use crate::scale::MAX_PIXELS;
+use crate::{Framebuffer, Oled, OledError, OledInterface, OledResult};
+
+/// The error for a font that can't be read
+fn invalid(reason: &'static str) -> OledError {
+    OledError::InvalidArgument(reason)
+}
+
+/// Big-endian reads from part of a font file, which fail past its end
+#[derive(Clone, Copy, Debug)]
+struct Table<'a>(&'a [u8]);
+
+impl<'a> Table<'a> {
+    fn bytes<const N: usize>(&self, offset: usize) -> Option<[u8; N]> {
+        let bytes = self.0.get(offset..offset.checked_add(N)?)?;
+        let mut out = [0; N];
+        out.copy_from_slice(bytes);
+        Some(out)
+    }
+
+    fn u8(&self, offset: usize) -> Option<u8> {
+        self.0.get(offset).copied()
+    }
+
+    fn u16(&self, offset: usize) -> Option<u16> {
+        self.bytes(offset).map(u16::from_be_bytes)
+    }
+
+    fn i16(&self, offset: usize) -> Option<i16> {
+        self.bytes(offset).map(i16::from_be_bytes)
+    }
+
+    fn u32(&self, offset: usize) -> Option<u32> {
+        self.bytes(offset).map(u32::from_be_bytes)
+    }
+
+    /// A fixed-point number with 14 fractional bits, as used for scales
+    fn f2dot14(&self, offset: usize) -> Option<f32> {
+        self.i16(offset).map(|value| f32::from(value) / 16384.0)
+    }
+
+    /// The part of the table from `start` to `end`
+    fn range(&self, start: usize, end: usize) -> Option<Table<'a>> {
+        self.0.get(start..end).map(Table)
+    }
+}
+
+/// How characters are mapped to glyphs, from the font's `cmap` table
+#[derive(Clone, Copy, Debug)]
+enum Cmap<'a> {
+    /// Format 4: ranges of the Basic Multilingual Plane
+    Segments(Table<'a>),
+    /// Format 12: ranges of any code points
+    Groups(Table<'a>),
+}
+
+impl Cmap<'_> {
+    /// The glyph for `char`, or `None` if there isn't one
+    fn lookup(&self, char: char) -> Option<u16> {
+        let code = u32::from(char);
+        let glyph = match *self {
+            Cmap::Segments(table) => {
+                let code = u16::try_from(code).ok()?;
+                let segments = usize::from(table.u16(6)? / 2);
+                let ends = 14;
+                let starts = ends + segments * 2 + 2;
+                let deltas = starts + segments * 2;
+                let range_offsets = deltas + segments * 2;
+                let segment = (0..segments)
+                    .find(|segment| table.u16(ends + segment * 2).is_some_and(|end| end >= code))?;
+                let start = table.u16(starts + segment * 2)?;
+                if start > code {
+                    return None;
+                }
+                let delta = table.u16(deltas + segment * 2)?;
+                let range_offset = table.u16(range_offsets + segment * 2)?;
+                if range_offset == 0 {
+                    code.wrapping_add(delta)
+                } else {
+                    // the offset is from where it is stored, into the glyph array after it
+                    let at = range_offsets
+                        + segment * 2
+                        + usize::from(range_offset)
+                        + usize::from(code - start) * 2;
+                    match table.u16(at)? {
+                        0 => 0,
+                        glyph => glyph.wrapping_add(delta),
+                    }
+                }
+            }
+            Cmap::Groups(table) => {
+                let groups = usize::try_from(table.u32(12)?).ok()?;
+                (0..groups).find_map(|group| {
+                    let at = 16 + group * 12;
+                    let (start, end) = (table.u32(at)?, table.u32(at + 4)?);
+                    if (start..=end).contains(&code) {
+                        u16::try_from(table.u32(at + 8)?.checked_add(code - start)?).ok()
+                    } else {
+                        None
+                    }
+                })?
+            }
+        };
+        Some(glyph).filter(|glyph| *glyph != 0)
+    }
+}
+
+/// A 2D affine transform, `[a, b, c, d, e, f]` mapping `x`, `y` to
+/// `a * x + c * y + e`, `b * x + d * y + f`
+type Transform = [f32; 6];
+
+/// Applies `transform` to a point
+fn apply([a, b, c, d, e, f]: Transform, (x, y): (f32, f32)) -> (f32, f32) {
+    (a * x + c * y + e, b * x + d * y + f)
+}
+
+/// The transform that applies `inner`, then `outer`
+fn compose(outer: Transform, inner: Transform) -> Transform {
+    let [a, b, c, d, e, f] = outer;
+    let [ia, ib, ic, id, ie, if_] = inner;
+    [
+        a * ia + c * ib,
+        b * ia + d * ib,
+        a * ic + c * id,
+        b * ic + d * id,
+        a * ie + c * if_ + e,
+        b * ie + d * if_ + f,
+    ]
+}
+
+/// Point halfway between two others
+fn midpoint((x0, y0): (f32, f32), (x1, y1): (f32, f32)) -> (f32, f32) {
+    ((x0 + x1) / 2.0, (y0 + y1) / 2.0)
+}
+
+/// Components of composite glyphs can nest; this deep is surely a loop
+const MAX_COMPONENT_DEPTH: u8 = 8;
+
+/// A glyph outline, as line segments `[x0, y0, x1, y1]` in pixels
+#[derive(Default)]
+struct Outline {
+    segments: Vec<[f32; 4]>,
+}
+
+impl Outline {
+    fn line(&mut self, (x0, y0): (f32, f32), (x1, y1): (f32, f32)) {
+        self.segments.push([x0, y0, x1, y1]);
+    }
+
+    /// Adds a quadratic curve, split into enough lines that they don't stray from it
+    fn curve(&mut self, from: (f32, f32), control: (f32, f32), to: (f32, f32)) {
+        let deviation = (
+            from.0 - 2.0 * control.0 + to.0,
+            from.1 - 2.0 * control.1 + to.1,
+        );
+        let deviation = deviation.0 * deviation.0 + deviation.1 * deviation.1;
+        let steps = 1 + (3.0 * deviation).sqrt().sqrt() as usize;
+        let mut last = from;
+        for step in 1..=steps {
+            let t = step as f32 / steps as f32;
+            let (a, b, c) = ((1.0 - t) * (1.0 - t), 2.0 * t * (1.0 - t), t * t);
+            let point = (
+                a * from.0 + b * control.0 + c * to.0,
+                a * from.1 + b * control.1 + c * to.1,
+            );
+            self.line(last, point);
+            last = point;
+        }
+    }
+
+    /// Adds a closed contour of points, each on or off the curve
+    fn contour(&mut self, points: &[((f32, f32), bool)]) {
+        let Some(last) = points.last() else {
+            return;
+        };
+        // start from a point on the curve, or if there are none, between the last and first
+        let (start, first) = match points.iter().position(|(_, on)| *on) {
+            Some(index) => (points[index].0, index + 1),
+            None => (midpoint(last.0, points[0].0), 0),
+        };
+        let mut current = start;
+        let mut control = None;
+        for index in 0..points.len() {
+            let (point, on) = points[(first + index) % points.len()];
+            match (on, control.take()) {
+                (true, Some(control)) => {
+                    self.curve(current, control, point);
+                    current = point;
+                }
+                (true, None) => {
+                    self.line(current, point);
+                    current = point;
+                }
+                (false, Some(previous)) => {
+                    // two points off the curve imply one on it, between them
+                    let between = midpoint(previous, point);
+                    self.curve(current, previous, between);
+                    current = between;
+                    control = Some(point);
+                }
+                (false, None) => control = Some(point),
+            }
+        }
+        match control {
+            Some(control) => self.curve(current, control, start),
+            None => self.line(current, start),
+        }
+    }
+
+    /// Fills the outline, returning the top left corner of the lit pixels'
+    /// bounding box, its width, and whether each pixel in it is lit, or `None` if
+    /// none are. Returns `InvalidArgument` if the box has more than `MAX_PIXELS`.
+    ///
+    /// Each pixel is lit if the outline covers at least `threshold` out of 255 of it.
+    fn fill(&self, threshold: u8) -> Result<Option<Rendered>, OledError> {
+        let mut bounds = self
+            .segments
+            .iter()
+            .flat_map(|[x0, y0, x1, y1]| [(*x0, *y0), (*x1, *y1)]);
+        let Some(first) = bounds.next() else {
+            return Ok(None);
+        };
+        let (left, top, right, bottom) = bounds.fold(
+            (first.0, first.1, first.0, first.1),
+            |(l, t, r, b), (x, y)| (l.min(x), t.min(y), r.max(x), b.max(y)),
+        );
+        let (left, top) = (left.floor(), top.floor());
+        // a column spare on the right, for coverage spilling over from the last
+        let width = ((right.ceil() - left) as usize).saturating_add(2);
+        let height = (bottom.ceil() - top) as usize;
+        let pixels = width
+            .checked_mul(height)
+            .filter(|pixels| *pixels <= MAX_PIXELS)
+            .ok_or_else(|| invalid("font size too large"))?;
+        if height == 0 {
+            return Ok(None);
+        }
+        let mut area = vec![0.0; pixels + 2];
+        for [x0, y0, x1, y1] in &self.segments {
+            accumulate(
+                &mut area,
+                width,
+                (x0 - left, y0 - top),
+                (x1 - left, y1 - top),
+            );
+        }
+        let mut coverage = 0.0;
+        let lit: Vec<bool> = area[..pixels]
+            .iter()
+            .map(|delta| {
+                coverage += delta;
+                (coverage.abs().min(1.0) * 255.0).round() as u8 >= threshold
+            })
+            .collect();
+        Ok(Some(Rendered {
+            origin: (left as i32, top as i32),
+            width,
+            lit,
+        }))
+    }
+}
+
+/// Adds the area of each pixel that a line from `from` to `to` separates off to
+/// its right, so a running total along each row gives the area the outline covers
+fn accumulate(area: &mut [f32], width: usize, from: (f32, f32), to: (f32, f32)) {
+    if (from.1 - to.1).abs() < f32::EPSILON {
+        return;
+    }
+    let (direction, from, to) = if from.1 < to.1 {
+        (1.0, from, to)
+    } else {
+        (-1.0, to, from)
+    };
+    let slope = (to.0 - from.0) / (to.1 - from.1);
+    let mut x = from.0;
+    for row in from.1 as usize..to.1.ceil() as usize {
+        let start = row * width;
+        let dy = ((row + 1) as f32).min(to.1) - (row as f32).max(from.1);
+        let next_x = x + slope * dy;
+        let coverage = dy * direction;
+        let (x0, x1) = if x < next_x { (x, next_x) } else { (next_x, x) };
+        let x0_floor = x0.floor();
+        let x0_column = x0_floor as usize;
+        let x1_ceil = x1.ceil();
+        let x1_column = x1_ceil as usize;
+        if x1_column <= x0_column + 1 {
+            // the line stays within one pixel of this row
+            let middle = 0.5 * (x + next_x) - x0_floor;
+            area[start + x0_column] += coverage - coverage * middle;
+            area[start + x0_column + 1] += coverage * middle;
+        } else {
+            let step = (x1 - x0).recip();
+            let x0_fraction = x0 - x0_floor;
+            let first = 0.5 * step * (1.0 - x0_fraction) * (1.0 - x0_fraction);
+            let x1_fraction = x1 - x1_ceil + 1.0;
+            let last = 0.5 * step * x1_fraction * x1_fraction;
+            area[start + x0_column] += coverage * first;
+            if x1_column == x0_column + 2 {
+                area[start + x0_column + 1] += coverage * (1.0 - first - last);
+            } else {
+                let second = step * (1.5 - x0_fraction);
+                area[start + x0_column + 1] += coverage * (second - first);
+                for column in x0_column + 2..x1_column - 1 {
+                    area[start + column] += coverage * step;
+                }
+                let before_last = second + (x1_column - x0_column - 3) as f32 * step;
+                area[start + x1_column - 1] += coverage * (1.0 - before_last - last);
+            }
+            area[start + x1_column] += coverage * last;
+        }
+        x = next_x;
+    }
+}
+
+/// A glyph's pixels, ready to be drawn
+struct Rendered {
+    /// Position of the top left pixel
+    origin: (i32, i32),
+    /// Number of pixels in each row
+    width: usize,
+    /// Whether each pixel is lit, row by row
+    lit: Vec<bool>,
+}
+
+/// A TrueType font, for drawing text at any size, eg large numerals, or in scripts
+/// the built-in font doesn't cover.
+///
+/// The font borrows the contents of its `.ttf` file, which can be embedded with
+/// `include_bytes!`. Fonts with TrueType outlines are supported, including the
+/// first font in a collection, but not those with CFF (PostScript) outlines.
+/// Hinting and kerning are ignored.
+#[derive(Clone, Copy, Debug)]
+pub struct TtfFont<'a> {
+    /// Size of the em square the outlines are drawn in
+    units_per_em: u16,
+    /// Distance from the top of a line to the baseline, in font units
+    ascender: i16,
+    /// Distance from the baseline to the bottom of a line, in font units, negative
+    descender: i16,
+    /// Extra space between lines, in font units
+    line_gap: i16,
+    /// Number of glyphs
+    glyph_count: u16,
+    /// Whether `loca` holds 32-bit offsets, rather than 16-bit halved ones
+    long_offsets: bool,
+    /// Where each glyph is in `glyf`
+    loca: Table<'a>,
+    /// Glyph outlines
+    glyf: Table<'a>,
+    /// Glyph widths
+    hmtx: Table<'a>,
+    /// Number of glyphs in `hmtx` with their own width
+    h_metrics: u16,
+    /// Character to glyph mapping
+    cmap: Cmap<'a>,
+}
+
+impl<'a> TtfFont<'a> {
+    /// Reads a font from the contents of a TrueType (`.ttf`) file.
+    ///
+    /// Returns `InvalidArgument` if it isn't a TrueType font, or has no mapping
+    /// from Unicode characters to glyphs.
+    pub fn from_bytes(data: &'a [u8]) -> Result<Self, OledError> {
+        let file = Table(data);
+        let mut start = 0;
+        match file.u32(0) {
+            Some(0x0001_0000) | Some(0x7472_7565) => {}
+            // 'ttcf', a collection of fonts, whose first one is used
+            Some(0x7474_6366) => {
+                start = file.u32(12).ok_or(invalid("TrueType file is truncated"))? as usize
+            }
+            Some(0x4f54_544f) => return Err(invalid("font has CFF outlines, not TrueType")),
+            _ => return Err(invalid("not a TrueType font")),
+        }
+        let table = |tag: &[u8; 4]| -> Result<Table<'a>, OledError> {
+            let count = file
+                .u16(start + 4)
+                .ok_or(invalid("TrueType file is truncated"))?;
+            (0..usize::from(count))
+                .map(|index| start + 12 + index * 16)
+                .find(|record| data.get(*record..*record + 4) == Some(&tag[..]))
+                .and_then(|record| {
+                    let offset = file.u32(record + 8)? as usize;
+                    let length = file.u32(record + 12)? as usize;
+                    file.range(offset, offset.checked_add(length)?)
+                })
+                .ok_or(invalid("TrueType font is missing a table"))
+        };
+        let truncated = || invalid("TrueType table is truncated");
+        let head = table(b"head")?;
+        let hhea = table(b"hhea")?;
+        let maxp = table(b"maxp")?;
+        let units_per_em = head.u16(18).ok_or_else(truncated)?;
+        if units_per_em == 0 {
+            return Err(invalid("TrueType font has no em size"));
+        }
+        Ok(TtfFont {
+            units_per_em,
+            ascender: hhea.i16(4).ok_or_else(truncated)?,
+            descender: hhea.i16(6).ok_or_else(truncated)?,
+            line_gap: hhea.i16(8).ok_or_else(truncated)?,
+            glyph_count: maxp.u16(4).ok_or_else(truncated)?,
+            long_offsets: head.i16(50).ok_or_else(truncated)? != 0,
+            loca: table(b"loca")?,
+            glyf: table(b"glyf")?,
+            hmtx: table(b"hmtx")?,
+            h_metrics: hhea.u16(34).ok_or_else(truncated)?,
+            cmap: Self::find_cmap(table(b"cmap")?)
+                .ok_or(invalid("TrueType font has no Unicode mapping"))?,
+        })
+    }
+
+    /// Picks the Unicode mapping, preferring one covering all of Unicode
+    fn find_cmap(cmap: Table<'a>) -> Option<Cmap<'a>> {
+        let mut found = None;
+        for index in 0..usize::from(cmap.u16(2)?) {
+            let record = 4 + index * 8;
+            let unicode = matches!(
+                (cmap.u16(record)?, cmap.u16(record + 2)?),
+                (0, _) | (3, 1) | (3, 10)
+            );
+            let subtable = Table(cmap.0.get(cmap.u32(record + 4)? as usize..)?);
+            match subtable.u16(0) {
+                Some(12) if unicode => return Some(Cmap::Groups(subtable)),
+                Some(4) if unicode => found = Some(Cmap::Segments(subtable)),
+                _ => {}
+            }
+        }
+        found
+    }
+
+    /// Pixels per font unit at `px_size`
+    fn scale(&self, px_size: f32) -> f32 {
+        px_size / f32::from(self.units_per_em)
+    }
+
+    /// Distance from the top of one line to the top of the next at `px_size`
+    /// pixels per em, in pixels
+    pub fn line_height(&self, px_size: f32) -> u16 {
+        let units = i32::from(self.ascender) - i32::from(self.descender) + i32::from(self.line_gap);
+        (units as f32 * self.scale(px_size)).ceil().max(0.0) as u16
+    }
+
+    /// Width of a line of text at `px_size` pixels per em, in pixels
+    pub fn text_width(&self, text: &str, px_size: f32) -> u16 {
+        let units: f32 = text
+            .chars()
+            .map(|char| f32::from(self.advance(self.glyph_id(char))))
+            .sum();
+        (units * self.scale(px_size))
+            .ceil()
+            .min(f32::from(u16::MAX)) as u16
+    }
+
+    /// The glyph for `char`, or glyph 0, which fonts use for missing characters
+    fn glyph_id(&self, char: char) -> u16 {
+        self.cmap
+            .lookup(char)
+            .filter(|glyph| *glyph < self.glyph_count)
+            .unwrap_or(0)
+    }
+
+    /// How far glyph `id` moves the pen on, in font units
+    fn advance(&self, id: u16) -> u16 {
+        let metric = id.min(self.h_metrics.saturating_sub(1));
+        self.hmtx.u16(usize::from(metric) * 4).unwrap_or(0)
+    }
+
+    /// The outline data of glyph `id`, or `None` if it has none, as for a space
+    fn glyph_data(&self, id: u16) -> Result<Option<Table<'a>>, OledError> {
+        let id = usize::from(id);
+        let (start, end) = if self.long_offsets {
+            (self.loca.u32(id * 4), self.loca.u32(id * 4 + 4))
+        } else {
+            (
+                self.loca.u16(id * 2).map(|offset| u32::from(offset) * 2),
+                self.loca
+                    .u16(id * 2 + 2)
+                    .map(|offset| u32::from(offset) * 2),
+            )
+        };
+        match (start, end) {
+            (Some(start), Some(end)) if start == end => Ok(None),
+            (Some(start), Some(end)) => self
+                .glyf
+                .range(start as usize, end as usize)
+                .map(Some)
+                .ok_or(invalid("TrueType glyph is out of bounds")),
+            _ => Err(invalid("TrueType glyph is out of bounds")),
+        }
+    }
+
+    /// Adds the outline of glyph `id` to `outline`, transformed into pixels
+    fn outline(
+        &self,
+        id: u16,
+        transform: Transform,
+        depth: u8,
+        outline: &mut Outline,
+    ) -> Result<(), OledError> {
+        let Some(glyph) = self.glyph_data(id)? else {
+            return Ok(());
+        };
+        let bad = || invalid("TrueType glyph is invalid");
+        let contours = glyph.i16(0).ok_or_else(bad)?;
+        if contours < 0 {
+            return self.composite(glyph, transform, depth, outline);
+        }
+        let contours = contours as usize;
+        let ends: Vec<usize> = (0..contours)
+            .map(|contour| glyph.u16(10 + contour * 2).map(usize::from))
+            .collect::<Option<_>>()
+            .ok_or_else(bad)?;
+        let count = ends.last().map_or(0, |last| last + 1);
+        let instructions = usize::from(glyph.u16(10 + contours * 2).ok_or_else(bad)?);
+        let mut at = 12 + contours * 2 + instructions;
+        let mut flags = Vec::with_capacity(count);
+        while flags.len() < count {
+            let flag = glyph.u8(at).ok_or_else(bad)?;
+            at += 1;
+            flags.push(flag);
+            if flag & 0x08 != 0 {
+                let repeats = glyph.u8(at).ok_or_else(bad)?;
+                at += 1;
+                flags.extend((0..repeats).map(|_| flag));
+            }
+        }
+        flags.truncate(count);
+        // x coordinates, then y, each as a short or long delta from the last
+        let mut read_axis = |short: u8, same_or_positive: u8| -> Result<Vec<f32>, OledError> {
+            let mut value = 0i32;
+            flags
+                .iter()
+                .map(|flag| {
+                    if flag & short != 0 {
+                        let delta = i32::from(glyph.u8(at).ok_or_else(bad)?);
+                        at += 1;
+                        value += if flag & same_or_positive != 0 {
+                            delta
+                        } else {
+                            -delta
+                        };
+                    } else if flag & same_or_positive == 0 {
+                        value += i32::from(glyph.i16(at).ok_or_else(bad)?);
+                        at += 2;
+                    }
+                    Ok(value as f32)
+                })
+                .collect()
+        };
+        let xs = read_axis(0x02, 0x10)?;
+        let ys = read_axis(0x04, 0x20)?;
+        let mut first = 0;
+        let mut points = Vec::new();
+        for end in ends {
+            if end < first || end >= count {
+                return Err(bad());
+            }
+            points.clear();
+            points.extend((first..=end).map(|index| {
+                (
+                    apply(transform, (xs[index], ys[index])),
+                    flags[index] & 0x01 != 0,
+                )
+            }));
+            outline.contour(&points);
+            first = end + 1;
+        }
+        Ok(())
+    }
+
+    /// Adds the outlines of a composite glyph's components to `outline`
+    fn composite(
+        &self,
+        glyph: Table<'a>,
+        transform: Transform,
+        depth: u8,
+        outline: &mut Outline,
+    ) -> Result<(), OledError> {
+        if depth >= MAX_COMPONENT_DEPTH {
+            return Err(invalid("TrueType glyph components nest too deeply"));
+        }
+        let bad = || invalid("TrueType glyph is invalid");
+        let mut at = 10;
+        loop {
+            let flags = glyph.u16(at).ok_or_else(bad)?;
+            let component = glyph.u16(at + 2).ok_or_else(bad)?;
+            at += 4;
+            let (dx, dy) = if flags & 0x0001 != 0 {
+                at += 4;
+                (glyph.i16(at - 4), glyph.i16(at - 2))
+            } else {
+                at += 2;
+                let byte = |offset| glyph.u8(offset).map(|byte| i16::from(byte as i8));
+                (byte(at - 2), byte(at - 1))
+            };
+            let (dx, dy) = (dx.ok_or_else(bad)?, dy.ok_or_else(bad)?);
+            // components placed by matching points, rather than offsets, stay put
+            let (dx, dy) = if flags & 0x0002 != 0 {
+                (f32::from(dx), f32::from(dy))
+            } else {
+                (0.0, 0.0)
+            };
+            let (a, b, c, d) = if flags & 0x0008 != 0 {
+                at += 2;
+                let scale = glyph.f2dot14(at - 2).ok_or_else(bad)?;
+                (scale, 0.0, 0.0, scale)
+            } else if flags & 0x0040 != 0 {
+                at += 4;
+                let x = glyph.f2dot14(at - 4).ok_or_else(bad)?;
+                let y = glyph.f2dot14(at - 2).ok_or_else(bad)?;
+                (x, 0.0, 0.0, y)
+            } else if flags & 0x0080 != 0 {
+                at += 8;
+                let value = |offset| glyph.f2dot14(at - 8 + offset).ok_or_else(bad);
+                (value(0)?, value(2)?, value(4)?, value(6)?)
+            } else {
+                (1.0, 0.0, 0.0, 1.0)
+            };
+            let inner = compose(transform, [a, b, c, d, dx, dy]);
+            self.outline(component, inner, depth + 1, outline)?;
+            if flags & 0x0020 == 0 {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Turns each glyph of a line of text into pixels, from the top left of the
+    /// line at `x`, `y`, returning them and the `x` coordinate just past the end
+    fn render(
+        &self,
+        x: u16,
+        y: u16,
+        text: &str,
+        px_size: f32,
+        threshold: u8,
+    ) -> Result<(Vec<Rendered>, u16), OledError> {
+        if !(px_size.is_finite() && px_size > 0.0) {
+            return Err(invalid("font size must be positive"));
+        }
+        let scale = self.scale(px_size);
+        let baseline = f32::from(y) + f32::from(self.ascender) * scale;
+        let mut pen = f32::from(x);
+        let mut glyphs = Vec::new();
+        for char in text.chars() {
+            let id = self.glyph_id(char);
+            let mut outline = Outline::default();
+            // font units have y going up from the baseline, unlike the display
+            self.outline(
+                id,
+                [scale, 0.0, 0.0, -scale, pen, baseline],
+                0,
+                &mut outline,
+            )?;
+            glyphs.extend(outline.fill(threshold)?);
+            pen += f32::from(self.advance(id)) * scale;
+        }
+        Ok((glyphs, pen.ceil().min(f32::from(u16::MAX)) as u16))
+    }
+}
+
+impl Framebuffer {
+    /// Draws a line of text in a TrueType `font`, `px_size` pixels per em, with
+    /// the top left of the line at `x`, `y`, and returns the `x` coordinate just
+    /// past its end.
+    ///
+    /// Pixels the glyphs cover at least `threshold` out of 255 of are lit, so
+    /// lower thresholds make bolder text. The line, from `x` to its end and down
+    /// its [`line_height()`](struct.TtfFont.html#method.line_height), is cleared
+    /// first, so new text can be drawn over old. Anything off the display is
+    /// clipped. `\n` isn't treated specially.
+    ///
+    /// Returns `InvalidArgument`, leaving the framebuffer as it was, if `px_size`
+    /// isn't positive, or is so large that a glyph would cover more than about a
+    /// million pixels, or the font's data for a glyph is invalid.
+    pub fn draw_text_ttf(
+        &mut self,
+        x: u16,
+        y: u16,
+        text: &str,
+        font: &TtfFont<'_>,
+        px_size: f32,
+        threshold: u8,
+    ) -> Result<u16, OledError> {
+        let (glyphs, end) = font.render(x, y, text, px_size, threshold)?;
+        let (width, height) = self.dimensions();
+        for row in 0..i32::from(font.line_height(px_size).min(height)) {
+            for column in i32::from(x)..i32::from(end.min(width)) {
+                self.plot(column, i32::from(y) + row, false);
+            }
+        }
+        for glyph in glyphs {
+            let (left, top) = glyph.origin;
+            for (index, lit) in glyph.lit.iter().enumerate() {
+                if *lit {
+                    let (column, row) = (index % glyph.width, index / glyph.width);
+                    self.plot(left + column as i32, top + row as i32, true);
+                }
+            }
+        }
+        Ok(end)
+    }
+}
+
+impl<I: OledInterface> Oled<I> {
+    /// Writes a line of text in a TrueType `font`, `px_size` pixels per em, with
+    /// the top left of the line at `x`, `y`, as
+    /// [`Framebuffer::draw_text_ttf()`](struct.Framebuffer.html#method.draw_text_ttf)
+    /// does, sending only the part of the display that changes.
+    /// # Example:
+    /// ```no_run
+    /// # use nanohat_oled::{Oled, TtfFont};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let data = std::fs::read("/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf")?;
+    /// let font = TtfFont::from_bytes(&data)?;
+    /// let mut oled = Oled::from_path("/dev/i2c-0")?;
+    /// oled.init()?;
+    /// oled.put_string_ttf(0, 0, "12:34", &font, 40.0, 128)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn put_string_ttf(
+        &mut self,
+        x: u16,
+        y: u16,
+        text: &str,
+        font: &TtfFont<'_>,
+        px_size: f32,
+        threshold: u8,
+    ) -> OledResult {
+        let mut result = Ok(0);
+        self.draw(|frame| result = frame.draw_text_ttf(x, y, text, font, px_size, threshold))?;
+        result.map(|_| ())
+    }
+}
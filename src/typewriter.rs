@@ -0,0 +1,181 @@
+//! Text typed out a character at a time, with a blinking cursor
+use core::time::Duration;
+
+use crate::blink::BLINK_PERIOD;
+use crate::{Oled, OledError, OledInterface, OledResult};
+
+/// Text revealed a character at a time in the built-in font, as if typed,
+/// starting at the text cursor as for [`Oled::put_string()`](struct.Oled.html#method.put_string).
+/// An underscore cursor can blink where the next character goes, as it would in
+/// a terminal. It stays on while characters keep coming, and goes once the
+/// text is all typed.
+///
+/// Like an [`Animation`](struct.Animation.html), the typewriter doesn't read a
+/// clock itself: [`tick()`](#method.tick) is told how much time has passed, or
+/// [`play()`](#method.play) runs it through, as
+/// [`Oled::put_string_animated()`](struct.Oled.html#method.put_string_animated) does.
+/// # Example:
+/// ```no_run
+/// # use nanohat_oled::{Oled, OledResult, Typewriter};
+/// # fn main() -> OledResult {
+/// let mut oled = Oled::from_path("/dev/i2c-0")?;
+/// oled.init()?;
+/// oled.set_text_xy(0, 3)?;
+/// Typewriter::new("Connecting...", 12)
+///     .with_cursor(true)
+///     .play(&mut oled)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Typewriter<'a> {
+    /// The text being typed
+    text: &'a str,
+    /// Characters typed each second, or 0 to type them all at once
+    chars_per_second: u16,
+    /// Whether a cursor blinks where the next character goes
+    cursor: bool,
+    /// Characters in the text
+    len: usize,
+    /// Characters typed so far
+    typed: usize,
+    /// Time since the first character was typed
+    elapsed: Duration,
+    /// Whether the cursor is showing
+    cursor_drawn: bool,
+}
+
+impl<'a> Typewriter<'a> {
+    /// Types out `text` at `chars_per_second`, without a cursor. A rate of 0
+    /// types it all at once.
+    pub fn new(text: &'a str, chars_per_second: u16) -> Self {
+        Typewriter {
+            text,
+            chars_per_second,
+            cursor: false,
+            len: text.chars().count(),
+            typed: 0,
+            elapsed: Duration::ZERO,
+            cursor_drawn: false,
+        }
+    }
+
+    /// Blinks a cursor where the next character goes, if `cursor`
+    pub fn with_cursor(self, cursor: bool) -> Self {
+        Typewriter { cursor, ..self }
+    }
+
+    /// Whether the whole text has been typed, and the cursor taken away
+    pub fn is_finished(&self) -> bool {
+        self.typed == self.len && !self.cursor_drawn
+    }
+
+    /// Goes back to the start of the text. What has been typed isn't cleared,
+    /// and typing starts again from wherever the text cursor is.
+    pub fn reset(&mut self) {
+        self.typed = 0;
+        self.elapsed = Duration::ZERO;
+        self.cursor_drawn = false;
+    }
+
+    /// When the character at `index` is typed, counting from the first
+    fn typed_at(&self, index: usize) -> Duration {
+        if self.chars_per_second == 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_secs(1) * index as u32 / u32::from(self.chars_per_second)
+    }
+
+    /// The number of characters typed by the time passed so far
+    fn due(&self) -> usize {
+        if self.chars_per_second == 0 {
+            return self.len;
+        }
+        let due = self.elapsed.as_nanos() * u128::from(self.chars_per_second) / 1_000_000_000 + 1;
+        due.min(self.len as u128) as usize
+    }
+
+    /// Moves the typing on by `elapsed`, the time since the last tick, typing
+    /// the characters now due and blinking the cursor. The first tick types the
+    /// first character, so it can be passed `Duration::ZERO`.
+    ///
+    /// Returns how long until the next character or blink, or `None` once
+    /// the text is all typed.
+    pub fn tick<I: OledInterface>(
+        &mut self,
+        oled: &mut Oled<I>,
+        elapsed: Duration,
+    ) -> Result<Option<Duration>, OledError> {
+        if self.len == 0 {
+            return Ok(None);
+        }
+        if self.typed > 0 {
+            self.elapsed = self.elapsed.saturating_add(elapsed);
+        }
+        let due = self.due();
+        if due > self.typed {
+            // typing over the cursor, if it's showing
+            for char in self.text.chars().skip(self.typed).take(due - self.typed) {
+                oled.put_char(char)?;
+            }
+            self.typed = due;
+            self.cursor_drawn = false;
+        }
+        let since_typed = self.elapsed.saturating_sub(self.typed_at(self.typed - 1));
+        let finished = self.typed == self.len;
+        let blinks = since_typed.as_nanos() / BLINK_PERIOD.as_nanos();
+        let show_cursor = self.cursor && !finished && blinks.is_multiple_of(2);
+        if show_cursor != self.cursor_drawn {
+            oled.put_glyph_in_place(if show_cursor { '_' } else { ' ' })?;
+            self.cursor_drawn = show_cursor;
+        }
+        if finished {
+            return Ok(None);
+        }
+        let next_char = self.typed_at(self.typed).saturating_sub(self.elapsed);
+        if !self.cursor {
+            return Ok(Some(next_char));
+        }
+        let next_blink = BLINK_PERIOD * (blinks as u32 + 1) - since_typed;
+        Ok(Some(next_char.min(next_blink)))
+    }
+
+    /// Types out the text from the start, sleeping between characters, and
+    /// returns once it's all typed
+    #[cfg(feature = "std")]
+    pub fn play<I: OledInterface>(&mut self, oled: &mut Oled<I>) -> Result<(), OledError> {
+        self.reset();
+        let mut last = std::time::Instant::now();
+        let mut elapsed = Duration::ZERO;
+        while let Some(wait) = self.tick(oled, elapsed)? {
+            std::thread::sleep(wait);
+            let now = std::time::Instant::now();
+            elapsed = now - last;
+            last = now;
+        }
+        Ok(())
+    }
+}
+
+impl<I: OledInterface> Oled<I> {
+    /// Writes a character at the text cursor without moving it on, eg to show
+    /// a cursor where the next character goes
+    fn put_glyph_in_place(&mut self, char: char) -> OledResult {
+        let (pointer, text_cursor) = (self.state.pointer(), self.text_cursor);
+        self.put_char(char)?;
+        self.text_cursor = text_cursor;
+        match self.rotation.is_portrait() {
+            true => Ok(()),
+            false => self.move_pointer(pointer.0, pointer.1),
+        }
+    }
+
+    /// Writes a string like [`put_string()`](struct.Oled.html#method.put_string),
+    /// revealing it a character at a time, at `chars_per_second`, as if typed.
+    /// Blocks until done. See [`Typewriter`](struct.Typewriter.html) for a
+    /// blinking cursor, or to type alongside other work.
+    #[cfg(feature = "std")]
+    pub fn put_string_animated(&mut self, text: &str, chars_per_second: u16) -> OledResult {
+        Typewriter::new(text, chars_per_second).play(self)
+    }
+}
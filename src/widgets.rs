@@ -0,0 +1,250 @@
+//! Pieces of user interface that draw themselves into part of the display
+use crate::{
+    Framebuffer, Oled, OledError, OledInterface, OledResult, OLED_LAST_PAGE, OLED_PAGE_HEIGHT,
+    OLED_WIDTH,
+};
+
+/// A rectangle of the display, in pixels, that a [`Widget`](trait.Widget.html)
+/// is drawn in
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct Rect {
+    /// Left edge
+    pub x: u16,
+    /// Top edge
+    pub y: u16,
+    /// Width
+    pub width: u16,
+    /// Height
+    pub height: u16,
+}
+
+impl Rect {
+    /// A rectangle `width` by `height` pixels, with its top left corner at `x`, `y`
+    pub const fn new(x: u16, y: u16, width: u16, height: u16) -> Self {
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Splits off the top `height` rows, returning them and the rest, either of
+    /// which may be empty
+    pub fn split_top(self, height: u16) -> (Rect, Rect) {
+        let height = height.min(self.height);
+        (
+            Rect { height, ..self },
+            Rect {
+                y: self.y.saturating_add(height),
+                height: self.height - height,
+                ..self
+            },
+        )
+    }
+
+    /// Splits off the left `width` columns, returning them and the rest, either
+    /// of which may be empty
+    pub fn split_left(self, width: u16) -> (Rect, Rect) {
+        let width = width.min(self.width);
+        (
+            Rect { width, ..self },
+            Rect {
+                x: self.x.saturating_add(width),
+                width: self.width - width,
+                ..self
+            },
+        )
+    }
+
+    /// Returns whether the pixel at `x`, `y` is inside the rectangle
+    pub(crate) fn contains(&self, x: i32, y: i32) -> bool {
+        let (left, top) = (i32::from(self.x), i32::from(self.y));
+        x >= left
+            && y >= top
+            && x < left + i32::from(self.width)
+            && y < top + i32::from(self.height)
+    }
+
+    /// Shrinks the rectangle by `pixels` on every side
+    pub fn inset(self, pixels: u16) -> Rect {
+        let width = self.width.saturating_sub(pixels.saturating_mul(2));
+        let height = self.height.saturating_sub(pixels.saturating_mul(2));
+        Rect {
+            x: self.x.saturating_add(pixels.min(self.width / 2)),
+            y: self.y.saturating_add(pixels.min(self.height / 2)),
+            width,
+            height,
+        }
+    }
+}
+
+/// The range of values a widget shows, eg from an empty to a full bar
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Bounds {
+    /// Lowest value shown
+    min: f32,
+    /// Highest value shown
+    max: f32,
+}
+
+impl Bounds {
+    /// A range from `min` to `max`, or `InvalidArgument` with `error` unless
+    /// `max` is more than `min`
+    pub(crate) fn new(min: f32, max: f32, error: &'static str) -> Result<Self, OledError> {
+        if max <= min || !(max - min).is_finite() {
+            return Err(OledError::InvalidArgument(error));
+        }
+        Ok(Bounds { min, max })
+    }
+
+    /// `value`, brought into range; `NaN` is taken to be the minimum
+    pub(crate) fn clamp(&self, value: f32) -> f32 {
+        if value.is_nan() {
+            self.min
+        } else {
+            value.clamp(self.min, self.max)
+        }
+    }
+
+    /// How far the range reaches, from its lowest value to its highest
+    pub(crate) fn span(&self) -> f32 {
+        self.max - self.min
+    }
+
+    /// How far along the range `value` is, from 0 to 1
+    pub(crate) fn fraction(&self, value: f32) -> f32 {
+        (self.clamp(value) - self.min) / (self.max - self.min)
+    }
+}
+
+/// Something that draws itself into a [`Rect`](struct.Rect.html) of a
+/// [`Framebuffer`](struct.Framebuffer.html), eg a label, a bar or a chart.
+///
+/// Screens are made up of widgets, each in an area of its own. Widgets that
+/// keep track of whether they have changed since they were last rendered, by
+/// [`needs_render()`](#method.needs_render), are only redrawn by
+/// [`Oled::render_widgets()`](struct.Oled.html#method.render_widgets) when they
+/// have. A widget can be made of other widgets by rendering each of them in
+/// part of its own area, as split up by eg [`Rect::split_top()`](struct.Rect.html#method.split_top).
+/// # Example:
+/// ```
+/// use nanohat_oled::{Framebuffer, Rect, Widget};
+///
+/// /// A box that is lit when something is on
+/// struct Indicator {
+///     on: bool,
+///     changed: bool,
+/// }
+///
+/// impl Indicator {
+///     fn set(&mut self, on: bool) {
+///         self.changed |= on != self.on;
+///         self.on = on;
+///     }
+/// }
+///
+/// impl Widget for Indicator {
+///     fn render(&mut self, frame: &mut Framebuffer, area: Rect) {
+///         frame.fill_rect(area.x, area.y, area.width, area.height, false);
+///         frame.draw_rect(area.x, area.y, area.width, area.height, true);
+///         if self.on {
+///             let inner = area.inset(2);
+///             frame.fill_rect(inner.x, inner.y, inner.width, inner.height, true);
+///         }
+///         self.changed = false;
+///     }
+///
+///     fn needs_render(&self) -> bool {
+///         self.changed
+///     }
+/// }
+/// ```
+pub trait Widget {
+    /// Draws the widget into `area` of `frame`, leaving the rest of the frame alone
+    fn render(&mut self, frame: &mut Framebuffer, area: Rect);
+
+    /// Returns whether the widget has changed since it was last rendered, and so
+    /// needs rendering again. By default, widgets are always rendered.
+    fn needs_render(&self) -> bool {
+        true
+    }
+}
+
+impl<I: OledInterface> Oled<I> {
+    /// Renders each widget that [needs it](trait.Widget.html#method.needs_render)
+    /// in its area, sending only the part of the display that changes.
+    /// Nothing is sent if no widget needs rendering.
+    /// # Example:
+    /// ```no_run
+    /// # use nanohat_oled::{Framebuffer, Oled, OledResult, Rect, Widget};
+    /// # struct Clock;
+    /// # impl Widget for Clock {
+    /// #     fn render(&mut self, _: &mut Framebuffer, _: Rect) {}
+    /// # }
+    /// # struct Status;
+    /// # impl Widget for Status {
+    /// #     fn render(&mut self, _: &mut Framebuffer, _: Rect) {}
+    /// # }
+    /// # fn main() -> OledResult {
+    /// let mut oled = Oled::from_path("/dev/i2c-0")?;
+    /// oled.init()?;
+    /// let (mut clock, mut status) = (Clock, Status);
+    /// let (top, bottom) = Rect::new(0, 0, 128, 64).split_top(16);
+    /// oled.render_widgets(&mut [(&mut clock, top), (&mut status, bottom)])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn render_widgets(&mut self, widgets: &mut [(&mut dyn Widget, Rect)]) -> OledResult {
+        if !widgets.iter().any(|(widget, _)| widget.needs_render()) {
+            return Ok(());
+        }
+        self.draw(|frame| {
+            for (widget, area) in widgets.iter_mut() {
+                if widget.needs_render() {
+                    widget.render(frame, *area);
+                }
+            }
+        })
+    }
+
+    /// Renders a widget across the full width of pages `first_page` to `last_page`
+    /// (inclusive), and sends just those pages straight to the display, whether
+    /// or not the widget [needs it](trait.Widget.html#method.needs_render). This
+    /// saves comparing the rest of the display, eg for a progress bar updated
+    /// often while nothing else changes.
+    ///
+    /// Returns `InvalidArgument` if the pages are out of range or in the wrong
+    /// order, or if the display is turned a quarter turn, since its pages then
+    /// run down the screen rather than across it.
+    pub fn render_to_pages(
+        &mut self,
+        widget: &mut dyn Widget,
+        first_page: u8,
+        last_page: u8,
+    ) -> OledResult {
+        if first_page > last_page || last_page > OLED_LAST_PAGE {
+            return Err(OledError::InvalidArgument(
+                "pages must be in order, between 0 and 7",
+            ));
+        }
+        if self.rotation.is_portrait() {
+            return Err(OledError::InvalidArgument(
+                "pages don't run across the display in portrait orientations",
+            ));
+        }
+        let area = Rect::new(
+            0,
+            u16::from(first_page) * OLED_PAGE_HEIGHT,
+            OLED_WIDTH,
+            u16::from(last_page - first_page + 1) * OLED_PAGE_HEIGHT,
+        );
+        let mut frame = Framebuffer::with_layout(*self.framebuffer(), false);
+        widget.render(&mut frame, area);
+        self.write_window(
+            (0, (OLED_WIDTH - 1) as u8),
+            (first_page, last_page),
+            frame.as_bytes(),
+        )
+    }
+}
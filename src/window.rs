@@ -0,0 +1,100 @@
+//! Writing into a window of display RAM, to update part of the display on its own
+use crate::{
+    AddressingMode, Image, Instruction, Oled, OledError, OledInterface, OledResult, Rect,
+    MAX_INSTRUCTION_LEN, OLED_LAST_PAGE, OLED_WIDTH,
+};
+
+/// The window covering the whole of display RAM, as columns and pages
+const FULL_WINDOW: ((u8, u8), (u8, u8)) = ((0, (OLED_WIDTH - 1) as u8), (0, OLED_LAST_PAGE));
+
+impl<I: OledInterface> Oled<I> {
+    /// Limits RAM writes to columns `col_start` to `col_end` of pages
+    /// `page_start` to `page_end`, all inclusive, and moves the RAM pointer to
+    /// its top left, so that data sent with
+    /// [`send_array_data()`](struct.Oled.html#method.send_array_data) fills
+    /// the window a page at a time, wrapping back to its top left when full.
+    /// Switches to horizontal addressing if the display isn't already using
+    /// horizontal or vertical addressing, which are what the window applies to.
+    ///
+    /// The window stays until [`reset_draw_window()`](struct.Oled.html#method.reset_draw_window),
+    /// or until anything that writes the whole display, such as
+    /// [`clear_display()`](struct.Oled.html#method.clear_display), puts it back.
+    /// Returns `InvalidArgument`, without sending anything, if the window is
+    /// off the display or for the [`Controller::Sh1106`](enum.Controller.html#variant.Sh1106),
+    /// which has no window.
+    /// # Example:
+    /// ```no_run
+    /// # use nanohat_oled::{Oled, OledResult};
+    /// # fn main() -> OledResult {
+    /// let mut oled = Oled::from_path("/dev/i2c-0")?;
+    /// oled.init()?;
+    /// // a solid 8x16 block in the top right corner
+    /// oled.set_draw_window(120, 127, 0, 1)?;
+    /// oled.send_array_data(&[0xff; 16][..])?;
+    /// oled.reset_draw_window()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_draw_window(
+        &mut self,
+        col_start: u8,
+        col_end: u8,
+        page_start: u8,
+        page_end: u8,
+    ) -> OledResult {
+        if !self.config.controller().has_windows() {
+            return Err(OledError::InvalidArgument(
+                "the SH1106 has no column and page window",
+            ));
+        }
+        let mut columns = [0; MAX_INSTRUCTION_LEN];
+        let columns = Instruction::SetColumnAddress(col_start, col_end).encode(&mut columns)?;
+        let mut pages = [0; MAX_INSTRUCTION_LEN];
+        let pages = Instruction::SetPageAddress(page_start, page_end).encode(&mut pages)?;
+        if self.state.mode == AddressingMode::Page {
+            self.set_addressing_mode(AddressingMode::Horizontal)?;
+        }
+        let mut commands = [0; 2 * MAX_INSTRUCTION_LEN];
+        commands[..columns.len()].copy_from_slice(columns);
+        commands[columns.len()..].copy_from_slice(pages);
+        self.send_commands(&commands)
+    }
+
+    /// Puts the window set by [`set_draw_window()`](struct.Oled.html#method.set_draw_window)
+    /// back to the whole display, moving the RAM pointer to the top left
+    pub fn reset_draw_window(&mut self) -> OledResult {
+        let (columns, pages) = FULL_WINDOW;
+        self.set_draw_window(columns.0, columns.1, pages.0, pages.1)
+    }
+
+    /// Puts the window back to the whole display if it has been narrowed, for
+    /// writes that expect data to wrap at the edges of the display
+    pub(crate) fn widen_window(&mut self) -> OledResult {
+        if (self.state.columns, self.state.pages) == FULL_WINDOW {
+            return Ok(());
+        }
+        self.reset_draw_window()
+    }
+
+    /// Writes an image to `rect` of the screen, leaving the rest as it is, eg to
+    /// update a clock or a counter without sending the whole picture: only the
+    /// pages and columns `rect` covers are sent, through a
+    /// [draw window](struct.Oled.html#method.set_draw_window). Any part beyond the
+    /// right or bottom edge is clipped. The image and `threshold` are as for
+    /// [`draw_image_at()`](struct.Oled.html#method.draw_image_at), with `rect`'s
+    /// dimensions, and `InvalidDimensions` is returned if they don't match.
+    /// # Example:
+    /// ```no_run
+    /// # use nanohat_oled::{Oled, OledResult, Rect};
+    /// # fn main() -> OledResult {
+    /// # let digits = [0xffu8; 40 * 16];
+    /// let mut oled = Oled::from_path("/dev/i2c-0")?;
+    /// oled.init()?;
+    /// oled.draw_region(&digits, Rect::new(88, 0, 40, 16), 0x80)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn draw_region(&mut self, image: &Image, rect: Rect, threshold: u8) -> OledResult {
+        self.draw_image_at(image, rect.width, rect.height, rect.x, rect.y, threshold)
+    }
+}
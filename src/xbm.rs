@@ -0,0 +1,89 @@
+//! Loading XBM images, the C source format most OLED image converters write
+use crate::{Framebuffer, OledError, OLED_HEIGHT, OLED_WIDTH};
+
+/// Reads one byte of XBM pixel data, written in hex or decimal
+fn xbm_byte(value: &str) -> Option<u8> {
+    match value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        Some(hex) => u8::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
+
+impl Framebuffer {
+    /// Creates a 128x64 framebuffer from an XBM image, as written by GIMP
+    /// and ImageMagick, and used for u8g2 and image2cpp assets: C source
+    /// giving the width and height with `#define`s, then the pixels in a
+    /// `char` array. Being one bit per pixel already, it's shown as it is, lit
+    /// where a bit is set, with no threshold to choose. An image smaller than
+    /// the display goes in its top left corner.
+    ///
+    /// Returns `InvalidDimensions` if the image is empty or larger than the
+    /// display, and `InvalidArgument` if it isn't an XBM image or has the wrong
+    /// amount of pixel data.
+    /// # Example:
+    /// ```
+    /// use nanohat_oled::Framebuffer;
+    ///
+    /// let xbm = b"#define dot_width 3
+    /// #define dot_height 2
+    /// static unsigned char dot_bits[] = { 0x02, 0x05 };";
+    /// let frame = Framebuffer::from_xbm(xbm).unwrap();
+    /// assert!(frame.get_pixel(1, 0));
+    /// assert!(frame.get_pixel(0, 1) && frame.get_pixel(2, 1));
+    /// assert!(!frame.get_pixel(0, 0));
+    /// ```
+    pub fn from_xbm(xbm: &[u8]) -> Result<Self, OledError> {
+        const NOT_XBM: OledError = OledError::InvalidArgument("not an XBM image");
+        let text = core::str::from_utf8(xbm).map_err(|_| NOT_XBM)?;
+        let (mut width, mut height) = (None, None);
+        for line in text.lines() {
+            let mut words = line.split_whitespace();
+            if let (Some("#define"), Some(name), Some(value)) =
+                (words.next(), words.next(), words.next())
+            {
+                if name.ends_with("_width") {
+                    width = value.parse::<usize>().ok();
+                } else if name.ends_with("_height") {
+                    height = value.parse::<usize>().ok();
+                }
+            }
+        }
+        let (Some(width), Some(height)) = (width, height) else {
+            return Err(NOT_XBM);
+        };
+        if width == 0 || height == 0 || width > OLED_WIDTH as usize || height > OLED_HEIGHT as usize
+        {
+            return Err(OledError::InvalidDimensions);
+        }
+        let start = text.find('{').ok_or(NOT_XBM)? + 1;
+        let end = start + text[start..].find('}').ok_or(NOT_XBM)?;
+        // each row is padded to a whole byte, with the leftmost pixel in the
+        // lowest bit
+        let row_len = width.div_ceil(8);
+        let mut frame = Framebuffer::new();
+        let mut count = 0;
+        for value in text[start..end].split(',').map(str::trim) {
+            if value.is_empty() {
+                continue;
+            }
+            let byte = xbm_byte(value).ok_or(OledError::InvalidArgument("XBM data is corrupt"))?;
+            let (y, first) = (count / row_len, count % row_len * 8);
+            for bit in 0..8 {
+                let x = first + bit;
+                if y < height && x < width && byte & (1 << bit) != 0 {
+                    frame.set_pixel(x as u16, y as u16, true);
+                }
+            }
+            count += 1;
+        }
+        if count != row_len * height {
+            return Err(OledError::InvalidArgument(
+                "XBM pixel data doesn't match its dimensions",
+            ));
+        }
+        Ok(frame)
+    }
+}
@@ -0,0 +1,83 @@
+use nanohat_oled::{Animation, BlitOp, Framebuffer, Oled, OledError, Sprite};
+
+mod common;
+use common::{ms, NullInterface};
+
+/// Three 8x1 frames stacked in a sheet: left half, right half, then everything lit
+const SHEET: Sprite = Sprite::new(8, 3, &[0xf0, 0x0f, 0xff]);
+
+fn shown(oled: &Oled<NullInterface>) -> u8 {
+    oled.framebuffer()[0..8]
+        .iter()
+        .enumerate()
+        .fold(0, |bits, (x, byte)| bits | (byte & 1) << (7 - x))
+}
+
+#[test]
+fn draws_frames_as_they_fall_due() {
+    let durations = [ms(100), ms(50), ms(200)];
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    let mut animation = Animation::from_sheet(SHEET, 1, &durations).unwrap();
+    assert_eq!(animation.len(), 3);
+    assert_eq!(animation.tick(&mut oled, ms(0)).unwrap(), Some(ms(100)));
+    assert_eq!(shown(&oled), 0xf0);
+    assert_eq!(animation.tick(&mut oled, ms(60)).unwrap(), Some(ms(40)));
+    assert_eq!(shown(&oled), 0xf0);
+    // a late tick skips the frames it missed
+    assert_eq!(animation.tick(&mut oled, ms(170)).unwrap(), Some(ms(120)));
+    assert_eq!(animation.current_frame(), 2);
+    assert_eq!(shown(&oled), 0xff);
+    // and loops back round to the start
+    assert_eq!(animation.tick(&mut oled, ms(130)).unwrap(), Some(ms(90)));
+    assert_eq!(shown(&oled), 0xf0);
+}
+
+#[test]
+fn stops_on_last_frame_after_loops() {
+    let frames = [Sprite::new(8, 1, &[0x80]), Sprite::new(8, 1, &[0x40])];
+    let durations = [ms(10)];
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    let mut animation = Animation::new(&frames, &durations)
+        .unwrap()
+        .with_position(0, 8)
+        .with_op(BlitOp::Or)
+        .with_loops(Some(2));
+    animation.tick(&mut oled, ms(0)).unwrap();
+    assert_eq!(animation.tick(&mut oled, ms(30)).unwrap(), Some(ms(10)));
+    assert!(!animation.is_finished());
+    assert_eq!(animation.tick(&mut oled, ms(100)).unwrap(), None);
+    assert!(animation.is_finished());
+    assert_eq!(animation.current_frame(), 1);
+    let mut expected = Framebuffer::new();
+    expected.set_pixel(0, 8, true);
+    expected.set_pixel(1, 8, true);
+    assert_eq!(oled.framebuffer(), expected.as_bytes());
+
+    animation.reset();
+    assert_eq!(animation.current_frame(), 0);
+    assert!(!animation.is_finished());
+}
+
+#[test]
+fn plays_to_the_end() {
+    let durations = [ms(1)];
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    let mut animation = Animation::from_sheet(SHEET, 1, &durations)
+        .unwrap()
+        .with_loops(Some(1));
+    animation.play(&mut oled).unwrap();
+    assert_eq!(shown(&oled), 0xff);
+}
+
+#[test]
+fn checks_frames_and_durations() {
+    let invalid =
+        |result: Result<Animation, OledError>| matches!(result, Err(OledError::InvalidArgument(_)));
+    assert!(invalid(Animation::new(&[], &[ms(1)])));
+    assert!(invalid(Animation::from_sheet(SHEET, 2, &[ms(1)])));
+    assert!(invalid(Animation::from_sheet(SHEET, 1, &[ms(1), ms(1)])));
+    assert!(invalid(Animation::from_sheet(SHEET, 1, &[ms(0)])));
+}
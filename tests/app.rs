@@ -0,0 +1,290 @@
+#![cfg(feature = "buttons")]
+use nanohat_oled::{
+    App, ButtonEvent, Buttons, Framebuffer, Gesture, IdleManager, Input, Key, MockInterface, Oled,
+    OledError, Rect, Screen, ScreenManager, Transition, Widget,
+};
+use std::cell::RefCell;
+use std::fs;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+mod common;
+use common::button_values;
+
+/// What happened to each screen, by name
+type Log = Rc<RefCell<Vec<String>>>;
+
+/// A screen that logs what it's given, pushes another on K1, pops on K2 and
+/// quits on K3
+struct Logged {
+    name: &'static str,
+    log: Log,
+}
+
+impl Widget for Logged {
+    fn render(&mut self, frame: &mut Framebuffer, area: Rect) {
+        frame.fill_rect(area.x, area.y, area.width, area.height, false);
+        self.log
+            .borrow_mut()
+            .push(format!("{} rendered", self.name));
+    }
+
+    fn needs_render(&self) -> bool {
+        false
+    }
+}
+
+impl Screen for Logged {
+    fn handle(&mut self, gesture: Gesture) -> Transition {
+        self.log
+            .borrow_mut()
+            .push(format!("{} {:?}", self.name, gesture));
+        match gesture.key() {
+            Key::K1 => Transition::Push(Box::new(Logged {
+                name: "inner",
+                log: self.log.clone(),
+            })),
+            Key::K2 => Transition::Pop,
+            Key::K3 => Transition::Quit,
+        }
+    }
+
+    fn tick(&mut self, elapsed: Duration) -> Transition {
+        self.log
+            .borrow_mut()
+            .push(format!("{} ticked {}", self.name, elapsed.as_millis()));
+        Transition::Stay
+    }
+}
+
+#[test]
+fn dispatches_to_the_screen_on_top() {
+    let paths = button_values("app", ["0\n", "0\n", "0\n"]);
+    let log = Log::default();
+    let outer = Logged {
+        name: "outer",
+        log: log.clone(),
+    };
+    let mut app = App::new(
+        Oled::new(MockInterface::new()),
+        Buttons::from_paths(paths.clone()).unwrap(),
+        Box::new(outer),
+    );
+    let start = Instant::now();
+    let at = |ms| start + Duration::from_millis(ms);
+    let mut press = |ms, key: usize| {
+        fs::write(&paths[key], "1\n").unwrap();
+        assert!(app.step(at(ms)).unwrap());
+        fs::write(&paths[key], "0\n").unwrap();
+        app.step(at(ms + 50)).unwrap()
+    };
+    assert!(press(0, 0));
+    assert!(press(120, 1));
+    assert!(!press(300, 2));
+    assert_eq!(
+        *log.borrow(),
+        [
+            "outer rendered",
+            "outer Press(K1)",
+            "inner rendered",
+            "inner ticked 120",
+            "inner Press(K2)",
+            "outer rendered",
+            "outer ticked 180",
+            "outer Press(K3)",
+        ]
+    );
+}
+
+#[test]
+fn a_press_that_wakes_the_display_is_not_passed_on() {
+    let paths = button_values("app_wake", ["0\n", "0\n", "0\n"]);
+    let log = Log::default();
+    let outer = Logged {
+        name: "outer",
+        log: log.clone(),
+    };
+    let mut oled = Oled::new(MockInterface::new());
+    oled.set_auto_off(Some(Duration::from_secs(30)));
+    let mut app = App::new(
+        oled,
+        Buttons::from_paths(paths.clone()).unwrap(),
+        Box::new(outer),
+    )
+    .with_tick_interval(Duration::from_secs(10));
+    let start = Instant::now();
+    let at = |secs| start + Duration::from_secs(secs);
+    for secs in (0..=30).step_by(10) {
+        assert!(app.step(at(secs)).unwrap());
+    }
+    assert!(app.oled().is_asleep());
+
+    fs::write(&paths[2], "1\n").unwrap();
+    assert!(app.step(at(31)).unwrap());
+    assert!(!app.oled().is_asleep());
+    fs::write(&paths[2], "0\n").unwrap();
+    assert!(app.step(at(32)).unwrap());
+    fs::write(&paths[1], "1\n").unwrap();
+    app.step(at(33)).unwrap();
+    fs::write(&paths[1], "0\n").unwrap();
+    app.step(at(34)).unwrap();
+    assert_eq!(
+        *log.borrow(),
+        [
+            "outer rendered",
+            "outer ticked 10000",
+            "outer ticked 10000",
+            "outer ticked 10000",
+            "outer Press(K2)",
+        ]
+    );
+}
+
+#[test]
+fn puts_the_screen_back_after_the_screensaver() {
+    let paths = button_values("app_idle", ["0\n", "0\n", "0\n"]);
+    let log = Log::default();
+    let outer = Logged {
+        name: "outer",
+        log: log.clone(),
+    };
+    let saver_log = log.clone();
+    let idle = IdleManager::new(Duration::from_secs(20)).with_screensaver(move |frame, _| {
+        frame.clear();
+        frame.set_pixel(0, 0, true);
+        saver_log.borrow_mut().push("screensaver".into());
+    });
+    let mut app = App::new(
+        Oled::new(MockInterface::new()),
+        Buttons::from_paths(paths.clone()).unwrap(),
+        Box::new(outer),
+    )
+    .with_tick_interval(Duration::from_secs(10))
+    .with_idle(idle);
+    let start = Instant::now();
+    let at = |secs| start + Duration::from_secs(secs);
+    for secs in (0..=20).step_by(10) {
+        assert!(app.step(at(secs)).unwrap());
+    }
+    assert_eq!(app.oled().framebuffer()[0], 1);
+
+    fs::write(&paths[0], "1\n").unwrap();
+    assert!(app.step(at(21)).unwrap());
+    assert_eq!(app.oled().framebuffer()[0], 0);
+    fs::write(&paths[0], "0\n").unwrap();
+    assert!(app.step(at(22)).unwrap());
+    assert_eq!(
+        *log.borrow(),
+        [
+            "outer rendered",
+            "outer ticked 10000",
+            "screensaver",
+            "outer ticked 10000",
+        ]
+    );
+}
+
+/// An input giving set events, one poll at a time
+struct Scripted(Vec<Vec<ButtonEvent>>);
+
+impl Input for Scripted {
+    fn poll_events(&mut self) -> Result<Vec<ButtonEvent>, OledError> {
+        Ok(if self.0.is_empty() {
+            Vec::new()
+        } else {
+            self.0.remove(0)
+        })
+    }
+}
+
+#[test]
+fn takes_gestures_from_any_input() {
+    let log = Log::default();
+    let outer = Logged {
+        name: "outer",
+        log: log.clone(),
+    };
+    let input = Scripted(vec![
+        vec![ButtonEvent::Press(Key::K3)],
+        vec![ButtonEvent::Release(Key::K3)],
+    ]);
+    let mut app = App::new(Oled::new(MockInterface::new()), input, Box::new(outer));
+    let start = Instant::now();
+    assert!(app.step(start).unwrap());
+    assert!(!app.step(start + Duration::from_millis(50)).unwrap());
+    assert_eq!(*log.borrow(), ["outer rendered", "outer Press(K3)"]);
+}
+
+/// A screen that logs when it's entered, left and rendered, and quits on any
+/// gesture
+struct Hooked {
+    name: &'static str,
+    log: Log,
+}
+
+impl Hooked {
+    fn boxed(name: &'static str, log: &Log) -> Box<dyn Screen> {
+        Box::new(Hooked {
+            name,
+            log: log.clone(),
+        })
+    }
+}
+
+impl Widget for Hooked {
+    fn render(&mut self, _frame: &mut Framebuffer, _area: Rect) {
+        self.log
+            .borrow_mut()
+            .push(format!("{} rendered", self.name));
+    }
+
+    fn needs_render(&self) -> bool {
+        false
+    }
+}
+
+impl Screen for Hooked {
+    fn handle(&mut self, _gesture: Gesture) -> Transition {
+        Transition::Quit
+    }
+
+    fn on_enter(&mut self) {
+        self.log.borrow_mut().push(format!("{} entered", self.name));
+    }
+
+    fn on_exit(&mut self) {
+        self.log.borrow_mut().push(format!("{} left", self.name));
+    }
+}
+
+#[test]
+fn calls_hooks_as_screens_come_and_go() {
+    let log = Log::default();
+    let mut oled = Oled::new(MockInterface::new());
+    let mut screens = ScreenManager::new(Hooked::boxed("status", &log));
+    screens.push(Hooked::boxed("network", &log));
+    screens.draw(&mut oled).unwrap();
+    // nothing changed, so nothing is rendered
+    screens.draw(&mut oled).unwrap();
+    screens.replace(Hooked::boxed("system", &log));
+    assert_eq!(screens.len(), 2);
+    screens.pop();
+    screens.draw(&mut oled).unwrap();
+    assert!(!screens.handle(Gesture::Press(Key::K1)));
+    assert!(screens.is_empty());
+    assert_eq!(
+        *log.borrow(),
+        [
+            "status entered",
+            "status left",
+            "network entered",
+            "network rendered",
+            "network left",
+            "system entered",
+            "system left",
+            "status entered",
+            "status rendered",
+            "status left",
+        ]
+    );
+}
@@ -0,0 +1,115 @@
+use nanohat_oled::{Barcode, Framebuffer, Oled, OledError, Rect, Symbology, Widget};
+
+mod common;
+use common::NullInterface;
+
+/// The modules of `barcode` from `start`, as 1 for a bar and 0 for a space
+fn modules(barcode: &Barcode, start: u16, len: u16) -> String {
+    (start..start + len)
+        .map(|x| if barcode.get_module(x) { '1' } else { '0' })
+        .collect()
+}
+
+#[test]
+fn encodes_code128_with_a_check_symbol() {
+    let barcode = Barcode::new("PJJ123C", Symbology::Code128).unwrap();
+    // start, seven characters, check and stop
+    assert_eq!(barcode.modules(), 11 * 9 + 13);
+    assert_eq!(modules(&barcode, 0, 11), "11010010000");
+    // P
+    assert_eq!(modules(&barcode, 11, 11), "11101110110");
+    // the check symbol is 55
+    assert_eq!(modules(&barcode, 88, 11), "11101000110");
+    assert_eq!(modules(&barcode, 99, 13), "1100011101011");
+    assert!(!barcode.get_module(112));
+
+    // pairs of digits in code set C
+    let digits = Barcode::new("123456", Symbology::Code128).unwrap();
+    assert_eq!(digits.modules(), 11 * 5 + 13);
+    assert_eq!(modules(&digits, 0, 11), "11010011100");
+    // 12, then the check symbol, 44
+    assert_eq!(modules(&digits, 11, 11), "10110011100");
+    assert_eq!(modules(&digits, 44, 11), "10001101110");
+    assert_eq!(
+        Barcode::new("12345", Symbology::Code128).unwrap().modules(),
+        11 * 7 + 13
+    );
+}
+
+#[test]
+fn encodes_code39_between_stars() {
+    let barcode = Barcode::new("A-1", Symbology::Code39).unwrap();
+    assert_eq!(barcode.modules(), 15 * 5 + 4);
+    let star = "100010111011101";
+    assert_eq!(modules(&barcode, 0, 15), star);
+    assert!(!barcode.get_module(15));
+    assert_eq!(modules(&barcode, 16, 15), "111010100010111");
+    assert_eq!(modules(&barcode, 32, 15), "100010101110111");
+    assert_eq!(modules(&barcode, 48, 15), "111010001010111");
+    assert_eq!(modules(&barcode, 64, 15), star);
+    let dollar = Barcode::new("$", Symbology::Code39).unwrap();
+    assert_eq!(modules(&dollar, 16, 15), "100010001000101");
+}
+
+#[test]
+fn rejects_what_cant_be_encoded() {
+    let invalid = |text, symbology| {
+        matches!(
+            Barcode::new(text, symbology),
+            Err(OledError::InvalidArgument(_))
+        )
+    };
+    assert!(invalid("café", Symbology::Code128));
+    assert!(invalid("tab\t", Symbology::Code128));
+    assert!(invalid("abc", Symbology::Code39));
+    assert!(invalid("A*B", Symbology::Code39));
+    // more modules than display columns
+    assert!(invalid("ABCDEFGHIJKL", Symbology::Code128));
+    assert!(invalid("ABCDEFG", Symbology::Code39));
+    assert!(Barcode::new("ABCDEF", Symbology::Code39).is_ok());
+    assert!(Barcode::new("", Symbology::Code128).is_ok());
+}
+
+#[test]
+fn renders_bars_unlit_between_lit_quiet_zones() {
+    // 46 modules and two quiet zones of 5 fit twice across
+    let mut barcode = Barcode::new("12", Symbology::Code128)
+        .unwrap()
+        .with_quiet_zone(5);
+    let mut frame = Framebuffer::new();
+    frame.fill_rect(0, 0, 128, 8, true);
+    barcode.render(&mut frame, Rect::new(0, 0, 128, 8));
+    assert!(!frame.get_pixel(7, 0) && !frame.get_pixel(120, 7));
+    assert!(frame.get_pixel(8, 0) && frame.get_pixel(17, 7) && frame.get_pixel(119, 0));
+    // start C begins with a bar two modules wide, then a space
+    assert!(!frame.get_pixel(18, 0) && !frame.get_pixel(21, 7));
+    assert!(frame.get_pixel(22, 0));
+
+    // too wide to fit, so nothing is drawn
+    let mut wide = barcode.clone().with_module_width(3);
+    wide.render(&mut frame, Rect::new(0, 0, 128, 8));
+    assert!((0..128).all(|x| !frame.get_pixel(x, 0)));
+    let mut narrow = barcode.with_module_width(1);
+    narrow.render(&mut frame, Rect::new(0, 0, 128, 8));
+    assert!(frame.get_pixel(36, 0) && !frame.get_pixel(35, 0));
+}
+
+/// Whether the pixel at `x`, `y` of the display is lit
+fn lit(oled: &Oled<NullInterface>, x: u16, y: u16) -> bool {
+    oled.framebuffer()[usize::from(y / 8 * 128 + x)] & 1 << (y % 8) != 0
+}
+
+#[test]
+fn draws_barcodes_across_the_display() {
+    let mut oled = Oled::new(NullInterface);
+    let barcode = Barcode::new("PJJ123C", Symbology::Code128).unwrap();
+    assert!(matches!(
+        oled.draw_barcode(&barcode, 16, 32),
+        Err(OledError::InvalidArgument(_))
+    ));
+    let barcode = barcode.with_quiet_zone(8);
+    oled.draw_barcode(&barcode, 16, 32).unwrap();
+    assert!(lit(&oled, 0, 16) && lit(&oled, 127, 47));
+    assert!(!lit(&oled, 0, 15) && !lit(&oled, 0, 48));
+    assert!(!lit(&oled, 8, 16) && !lit(&oled, 8, 47) && lit(&oled, 10, 30));
+}
@@ -0,0 +1,95 @@
+use nanohat_oled::{BitmapFont, Font, Framebuffer};
+
+mod common;
+use common::lit;
+
+/// A 3x5 font with a descender: 'A', a hanging 'j', an unencoded glyph,
+/// and '_' as its default character
+const FONT: &str = "STARTFONT 2.1
+COMMENT a tiny test font
+FONT -test-tiny-medium-r-normal--6-60-75-75-c-40-iso10646-1
+SIZE 6 75 75
+FONTBOUNDINGBOX 3 6 0 -1
+STARTPROPERTIES 3
+FONT_ASCENT 5
+FONT_DESCENT 1
+DEFAULT_CHAR 95
+ENDPROPERTIES
+CHARS 4
+STARTCHAR A
+ENCODING 65
+SWIDTH 666 0
+DWIDTH 4 0
+BBX 3 5 0 0
+BITMAP
+40
+A0
+E0
+A0
+A0
+ENDCHAR
+STARTCHAR j
+ENCODING 106
+SWIDTH 500 0
+DWIDTH 3 0
+BBX 2 4 0 -1
+BITMAP
+40
+40
+40
+8000
+ENDCHAR
+STARTCHAR unencoded
+ENCODING -1
+DWIDTH 4 0
+BBX 3 1 0 0
+BITMAP
+E0
+ENDCHAR
+STARTCHAR underscore
+ENCODING 95
+DWIDTH 4 0
+BBX 3 1 0 -1
+BITMAP
+E0
+ENDCHAR
+ENDFONT
+";
+
+#[test]
+fn loads_bdf_glyphs() {
+    let font = BitmapFont::from_bdf_bytes(FONT.as_bytes()).unwrap();
+    assert_eq!(font.line_height(), 6);
+    assert_eq!(font.chars().collect::<Vec<_>>(), ['A', '_', 'j']);
+
+    let a = font.glyph('A').unwrap();
+    assert_eq!(a.dimensions(), (3, 5));
+    assert_eq!(a.offset(), (0, 0));
+    assert_eq!(a.advance(), 4);
+    assert!(a.get_pixel(1, 0) && !a.get_pixel(0, 0) && a.get_pixel(2, 2));
+
+    // 'j' drops one pixel below the baseline, so it sits one lower than its height implies
+    let j = font.glyph('j').unwrap();
+    assert_eq!(j.offset(), (0, 2));
+    assert!(j.get_pixel(0, 3) && !j.get_pixel(1, 3));
+
+    // characters the font doesn't have take the width of its default character
+    assert_eq!(font.text_width("Aj?"), 11);
+}
+
+#[test]
+fn draws_bdf_text() {
+    let font = BitmapFont::from_bdf_bytes(FONT.as_bytes()).unwrap();
+    let mut frame = Framebuffer::new();
+    assert_eq!(frame.draw_text(1, 0, "j", &font), 4);
+    assert_eq!(lit(&frame), [(2, 2), (2, 3), (2, 4), (1, 5)]);
+}
+
+#[test]
+fn rejects_bad_bdf_files() {
+    assert!(BitmapFont::from_bdf_bytes(b"not a font").is_err());
+    let short_row = FONT.replace("A0\nA0\nENDCHAR", "A0\nENDCHAR");
+    assert!(BitmapFont::from_bdf_bytes(short_row.as_bytes()).is_err());
+    let bad_hex = FONT.replace("E0\nA0", "G0\nA0");
+    assert!(BitmapFont::from_bdf_bytes(bad_hex.as_bytes()).is_err());
+}
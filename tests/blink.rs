@@ -0,0 +1,93 @@
+use nanohat_oled::{Blink, CaptureDevice, CursorStyle, Oled, OledError, Rect};
+
+mod common;
+use common::ms;
+
+#[test]
+fn inverts_the_region_every_period() {
+    let mut device = CaptureDevice::new();
+    {
+        let mut oled = Oled::new(&mut device);
+        oled.init().unwrap();
+        oled.draw(|frame| frame.set_pixel(2, 2, true)).unwrap();
+        let mut blink = Blink::new(Rect::new(0, 0, 4, 4)).with_period(ms(200));
+        assert_eq!(blink.tick(&mut oled, ms(150)).unwrap(), ms(50));
+        assert!(!blink.is_inverted());
+        assert_eq!(blink.tick(&mut oled, ms(100)).unwrap(), ms(150));
+        assert!(blink.is_inverted());
+        assert!(oled.framebuffer()[0] & 1 != 0);
+        // two periods at once leaves it as it was
+        assert_eq!(blink.tick(&mut oled, ms(400)).unwrap(), ms(150));
+        assert!(blink.is_inverted());
+        blink.stop(&mut oled).unwrap();
+        assert!(!blink.is_inverted());
+    }
+    assert!(device.is_lit(2, 2));
+    assert!(!device.is_lit(0, 0) && !device.is_lit(4, 4));
+}
+
+#[test]
+fn flashes_text_cells_inverted() {
+    let mut device = CaptureDevice::new();
+    {
+        let mut oled = Oled::new(&mut device);
+        oled.init().unwrap();
+        let mut blink = Blink::text(1, 2, 2);
+        assert_eq!(blink.rect(), Rect::new(8, 16, 16, 8));
+        blink.tick(&mut oled, ms(500)).unwrap();
+    }
+    assert!(device.is_lit(8, 16) && device.is_lit(23, 23));
+    assert!(!device.is_lit(7, 16) && !device.is_lit(24, 16) && !device.is_lit(8, 24));
+}
+
+#[test]
+fn shows_and_blinks_a_cursor() {
+    let mut device = CaptureDevice::new();
+    {
+        let mut oled = Oled::new(&mut device);
+        oled.init().unwrap();
+        oled.show_cursor(3, 1, CursorStyle::Underline).unwrap();
+    }
+    assert!(device.is_lit(24, 15) && device.is_lit(31, 15));
+    assert!(!device.is_lit(24, 14));
+    {
+        let mut oled = Oled::new(&mut device);
+        oled.init().unwrap();
+        oled.show_cursor(3, 1, CursorStyle::Bar).unwrap();
+        oled.tick_cursor(ms(499)).unwrap();
+    }
+    assert!(device.is_lit(24, 8) && device.is_lit(24, 15));
+    assert!(!device.is_lit(25, 8));
+    {
+        let mut oled = Oled::new(&mut device);
+        oled.init().unwrap();
+        oled.show_cursor(3, 1, CursorStyle::Block).unwrap();
+        oled.tick_cursor(ms(500)).unwrap();
+    }
+    assert!(!device.is_lit(24, 8) && !device.is_lit(31, 15));
+}
+
+#[test]
+fn moving_the_cursor_puts_back_what_was_under_it() {
+    let mut device = CaptureDevice::new();
+    {
+        let mut oled = Oled::new(&mut device);
+        oled.init().unwrap();
+        oled.show_cursor(0, 0, CursorStyle::Block).unwrap();
+        oled.show_cursor(1, 0, CursorStyle::Block).unwrap();
+        assert!(matches!(
+            oled.show_cursor(16, 0, CursorStyle::Block),
+            Err(OledError::OutOfBounds)
+        ));
+    }
+    assert!(!device.is_lit(0, 0));
+    assert!(device.is_lit(8, 0));
+    {
+        let mut oled = Oled::new(&mut device);
+        oled.init().unwrap();
+        oled.show_cursor(1, 0, CursorStyle::Block).unwrap();
+        oled.hide_cursor().unwrap();
+        oled.tick_cursor(ms(500)).unwrap();
+    }
+    assert!(!device.is_lit(8, 0));
+}
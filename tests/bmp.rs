@@ -0,0 +1,87 @@
+use nanohat_oled::{Framebuffer, Oled, OledError};
+
+mod common;
+use common::{lit, NullInterface};
+
+/// A BMP file `width` by `height`, stored bottom row first if `height` is
+/// positive, with the given color table and rows, each padded to four bytes
+fn bmp(width: i32, height: i32, bits: u16, palette: &[[u8; 4]], rows: &[&[u8]]) -> Vec<u8> {
+    let offset = 14 + 40 + palette.len() as u32 * 4;
+    let mut file = b"BM".to_vec();
+    file.extend(0u32.to_le_bytes());
+    file.extend(0u32.to_le_bytes());
+    file.extend(offset.to_le_bytes());
+    file.extend(40u32.to_le_bytes());
+    file.extend(width.to_le_bytes());
+    file.extend(height.to_le_bytes());
+    file.extend(1u16.to_le_bytes());
+    file.extend(bits.to_le_bytes());
+    file.extend([0; 16]);
+    file.extend((palette.len() as u32).to_le_bytes());
+    file.extend(0u32.to_le_bytes());
+    for color in palette {
+        file.extend(color);
+    }
+    for row in rows {
+        let mut row = row.to_vec();
+        row.resize(row.len().div_ceil(4) * 4, 0);
+        file.extend(row);
+    }
+    file
+}
+
+const BLACK_WHITE: [[u8; 4]; 2] = [[0, 0, 0, 0], [0xff, 0xff, 0xff, 0]];
+
+#[test]
+fn draws_one_bit_images_bottom_up() {
+    // a 3x2 image: bottom row lit at the left, top row at the right
+    let file = bmp(3, 2, 1, &BLACK_WHITE, &[&[0b1000_0000], &[0b0010_0000]]);
+    let mut frame = Framebuffer::new();
+    frame.draw_bmp(&file, 10, 20).unwrap();
+    assert_eq!(lit(&frame), [(12, 20), (10, 21)]);
+
+    // a palette can make index 0 the light one
+    let inverted = [BLACK_WHITE[1], BLACK_WHITE[0]];
+    let file = bmp(3, -1, 1, &inverted, &[&[0b1000_0000]]);
+    frame.clear();
+    frame.draw_bmp(&file, 0, 0).unwrap();
+    assert_eq!(lit(&frame), [(1, 0), (2, 0)]);
+}
+
+#[test]
+fn draws_grayscale_images_clipped_at_the_edges() {
+    let grays: Vec<[u8; 4]> = (0..=255).map(|gray| [gray, gray, gray, 0]).collect();
+    // top down, with a dark, a light and a mid-gray pixel
+    let file = bmp(3, -1, 8, &grays, &[&[0x10, 0xf0, 0x80]]);
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    oled.draw_bmp(&file, 125, 63).unwrap();
+    assert_eq!(oled.framebuffer()[7 * 128 + 125], 0);
+    assert_eq!(oled.framebuffer()[7 * 128 + 126], 0x80);
+    assert_eq!(oled.framebuffer()[7 * 128 + 127], 0x80);
+}
+
+#[test]
+fn rejects_other_images() {
+    let mut frame = Framebuffer::new();
+    assert!(matches!(
+        frame.draw_bmp(b"GIF89a", 0, 0),
+        Err(OledError::InvalidArgument(_))
+    ));
+    let mut truecolor = bmp(1, 1, 24, &[], &[&[0xff, 0xff, 0xff]]);
+    assert!(matches!(
+        frame.draw_bmp(&truecolor, 0, 0),
+        Err(OledError::InvalidArgument(_))
+    ));
+    truecolor.truncate(30);
+    assert!(matches!(
+        frame.draw_bmp(&truecolor, 0, 0),
+        Err(OledError::InvalidArgument(_))
+    ));
+    let short = bmp(8, 2, 1, &BLACK_WHITE, &[&[0xff]]);
+    assert!(matches!(
+        frame.draw_bmp(&short, 0, 0),
+        Err(OledError::InvalidArgument(_))
+    ));
+    assert!(lit(&frame).is_empty());
+}
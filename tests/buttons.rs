@@ -0,0 +1,53 @@
+#![cfg(feature = "buttons")]
+use nanohat_oled::{ButtonEvent, Buttons, Key, OledError};
+use std::fs;
+use std::time::Duration;
+
+mod common;
+use common::button_values as values;
+
+#[test]
+fn reports_presses_and_releases() {
+    let paths = values("presses", ["0\n", "1\n", "0\n"]);
+    let mut buttons = Buttons::from_paths(paths.clone()).unwrap();
+    // K2 was held already, so isn't a press
+    assert!(buttons.poll().unwrap().next().is_none());
+    assert!(buttons.is_pressed(Key::K2));
+
+    fs::write(&paths[0], "1\n").unwrap();
+    fs::write(&paths[1], "0\n").unwrap();
+    assert_eq!(
+        buttons.poll().unwrap().collect::<Vec<_>>(),
+        [ButtonEvent::Press(Key::K1), ButtonEvent::Release(Key::K2)]
+    );
+    assert!(buttons.is_pressed(Key::K1) && !buttons.is_pressed(Key::K2));
+    assert!(buttons.poll().unwrap().next().is_none());
+
+    fs::write(&paths[2], "1\n").unwrap();
+    let event = buttons.events(Duration::from_millis(1)).next().unwrap();
+    assert_eq!(event.unwrap().key(), Key::K3);
+}
+
+#[test]
+fn active_low_buttons_read_0_when_pressed() {
+    let paths = values("active-low", ["1\n", "1\n", "0\n"]);
+    let mut buttons = Buttons::from_paths(paths.clone())
+        .unwrap()
+        .with_active_low(true);
+    assert!(buttons.is_pressed(Key::K3) && !buttons.is_pressed(Key::K1));
+    fs::write(&paths[0], "0\n").unwrap();
+    assert_eq!(
+        buttons.poll().unwrap().collect::<Vec<_>>(),
+        [ButtonEvent::Press(Key::K1)]
+    );
+}
+
+#[test]
+fn missing_gpios_are_errors() {
+    let mut paths = values("missing", ["0\n", "0\n", "0\n"]);
+    paths[1].set_file_name("absent");
+    assert!(matches!(
+        Buttons::from_paths(paths),
+        Err(OledError::Gpio(_))
+    ));
+}
@@ -0,0 +1,62 @@
+use nanohat_oled::{AddressingMode, CaptureDevice, Framebuffer, Oled, Rotation, Sent};
+
+#[test]
+fn decodes_text_into_ram() {
+    let mut device = CaptureDevice::new();
+    let mut oled = Oled::new(&mut device);
+    oled.set_text_xy(2, 1).unwrap();
+    oled.put_string("Hi").unwrap();
+    let expected = *oled.framebuffer();
+
+    assert_eq!(device.ram(), &expected);
+    assert_eq!(device.commands(), [0xb1, 0x00, 0x11]);
+    assert_eq!(device.sent()[3], Sent::Data(0x00));
+    // the uprights of the H
+    assert!((8..15).all(|y| device.get_pixel(17, y) && device.get_pixel(21, y)));
+    assert!(!device.get_pixel(17, 15) && !device.get_pixel(17, 7));
+    // page addressing, past the two characters
+    assert_eq!(device.addressing_mode(), AddressingMode::Page);
+    assert_eq!(device.pointer(), (32, 1));
+}
+
+#[test]
+fn decodes_flushed_windows_independently_of_the_driver() {
+    let mut device = CaptureDevice::new();
+    device.set_max_transfer_len(31);
+    let mut oled = Oled::new(&mut device);
+    oled.init().unwrap();
+    let mut frame = Framebuffer::new();
+    frame.draw_line(0, 0, 127, 63, true);
+    frame.fill_rect(100, 10, 8, 20, true);
+    oled.flush(&frame).unwrap();
+
+    assert_eq!(device.framebuffer(), frame);
+    assert_eq!(device.addressing_mode(), AddressingMode::Horizontal);
+
+    device.clear();
+    assert!(device.sent().is_empty());
+    assert_eq!(device.framebuffer(), frame);
+}
+
+#[test]
+fn shows_what_the_panel_lights() {
+    let mut device = CaptureDevice::new();
+    let mut oled = Oled::new(&mut device);
+    let mut frame = Framebuffer::new();
+    frame.set_pixel(3, 4, true);
+    oled.flush_all(&frame).unwrap();
+    // dark until turned on, then mirrored until set up by init
+    assert!(device.get_pixel(3, 4) && !device.is_lit(3, 4));
+    Oled::new(&mut device).send_command(0xaf).unwrap();
+    assert!(device.is_lit(124, 59) && !device.is_lit(3, 4));
+
+    let mut oled = Oled::new(&mut device);
+    oled.init().unwrap();
+    oled.flush(&frame).unwrap();
+    oled.set_rotation(Rotation::Rotate180).unwrap();
+    assert!(device.is_lit(124, 59) && device.get_pixel(3, 4));
+
+    let mut oled = Oled::new(&mut device);
+    oled.all_on(true).unwrap();
+    assert!(device.is_lit(0, 0));
+}
@@ -0,0 +1,78 @@
+use nanohat_oled::{AnalogClock, DigitalClock, Framebuffer, Oled, OledError, Rect, Widget};
+
+mod common;
+use common::NullInterface;
+
+/// Renders `clock` across the whole display, returning what's shown
+fn rendered<W: Widget>(clock: &mut W) -> [u8; 1024] {
+    let mut oled = Oled::new(NullInterface);
+    oled.render_widgets(&mut [(clock, Rect::new(0, 0, 128, 64))])
+        .unwrap();
+    *oled.framebuffer()
+}
+
+/// What's shown after writing `text` at `x`, `y` at `scale`
+fn scaled(x: u16, y: u16, text: &str, scale: u8) -> [u8; 1024] {
+    let mut oled = Oled::new(NullInterface);
+    oled.put_string_scaled(x, y, text, scale).unwrap();
+    *oled.framebuffer()
+}
+
+#[test]
+fn shows_big_digits_centred() {
+    let mut clock = DigitalClock::new();
+    assert!(matches!(
+        clock.set_time(24, 0, 0),
+        Err(OledError::InvalidArgument(_))
+    ));
+    assert!(clock.set_time(0, 60, 0).is_err() && clock.set_time(0, 0, 60).is_err());
+    clock.set_time(12, 34, 56).unwrap();
+    assert_eq!(rendered(&mut clock)[..], scaled(4, 20, "12:34", 3)[..]);
+
+    // seconds only fit at twice the size
+    let mut clock = DigitalClock::new().with_seconds(true);
+    clock.set_time(12, 34, 56).unwrap();
+    assert_eq!(rendered(&mut clock)[..], scaled(0, 24, "12:34:56", 2)[..]);
+}
+
+#[test]
+fn rerenders_only_when_what_it_shows_changes() {
+    let mut clock = DigitalClock::new();
+    clock.set_time(9, 15, 0).unwrap();
+    assert!(clock.needs_render());
+    rendered(&mut clock);
+    clock.set_time(9, 15, 1).unwrap();
+    assert!(!clock.needs_render());
+
+    let mut clock = DigitalClock::new().with_blinking_colon(true);
+    clock.set_time(9, 15, 2).unwrap();
+    rendered(&mut clock);
+    clock.set_time(9, 15, 3).unwrap();
+    assert!(clock.needs_render());
+    assert_eq!(rendered(&mut clock)[..], scaled(4, 20, "09 15", 3)[..]);
+    clock.set_time(9, 15, 5).unwrap();
+    assert!(!clock.needs_render());
+}
+
+#[test]
+fn points_the_hands_at_the_time() {
+    let mut clock = AnalogClock::new();
+    assert!(clock.set_time(24, 0, 0).is_err());
+    clock.set_time(15, 0, 0).unwrap();
+    let mut frame = Framebuffer::new();
+    clock.render(&mut frame, Rect::new(0, 0, 64, 64));
+    // the face, and the hour hand pointing right and the minute hand up
+    assert!(frame.get_pixel(32, 1) && frame.get_pixel(63, 32) && frame.get_pixel(1, 32));
+    assert!((33..=47).all(|x| frame.get_pixel(x, 32)));
+    assert!((8..=31).all(|y| frame.get_pixel(32, y)));
+    assert!(!frame.get_pixel(20, 32) && !frame.get_pixel(32, 45));
+
+    clock.set_time(3, 0, 45).unwrap();
+    assert!(!clock.needs_render());
+    let mut clock = AnalogClock::new().with_second_hand(true);
+    clock.set_time(3, 0, 45).unwrap();
+    clock.render(&mut frame, Rect::new(0, 0, 64, 64));
+    assert!((5..=31).all(|x| frame.get_pixel(x, 32)));
+    clock.set_time(3, 0, 46).unwrap();
+    assert!(clock.needs_render());
+}
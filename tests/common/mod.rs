@@ -0,0 +1,65 @@
+#![allow(dead_code)]
+use nanohat_oled::{Framebuffer, Oled, OledError, OledInterface};
+
+/// An interface that discards everything sent to it
+pub struct NullInterface;
+
+impl OledInterface for NullInterface {
+    fn send_command(&mut self, _command: u8) -> Result<(), OledError> {
+        Ok(())
+    }
+
+    fn send_data(&mut self, _data: &[u8]) -> Result<(), OledError> {
+        Ok(())
+    }
+
+    fn max_transfer_len(&self) -> usize {
+        31
+    }
+}
+
+/// Files standing in for the `value` files of K1, K2 and K3, starting at `levels`
+#[cfg(feature = "buttons")]
+pub fn button_values(test: &str, levels: [&str; 3]) -> [std::path::PathBuf; 3] {
+    let dir = std::env::temp_dir().join(format!("nanohat-oled-{}-{}", test, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let paths = [dir.join("k1"), dir.join("k2"), dir.join("k3")];
+    for (path, level) in paths.iter().zip(&levels) {
+        std::fs::write(path, level).unwrap();
+    }
+    paths
+}
+
+/// Coordinates of every lit pixel, row by row
+pub fn lit(frame: &Framebuffer) -> Vec<(u16, u16)> {
+    let (width, height) = frame.dimensions();
+    (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .filter(|(x, y)| frame.get_pixel(*x, *y))
+        .collect()
+}
+
+/// What the display shows with each of `rows` written at the start of a text row
+pub fn rendered<S: AsRef<str>>(rows: &[S]) -> [u8; 1024] {
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    for (row, text) in rows.iter().enumerate() {
+        oled.set_text_xy(0, row as u8).unwrap();
+        oled.put_string(text.as_ref()).unwrap();
+    }
+    *oled.framebuffer()
+}
+
+/// What the display shows with `text` written from text column `column`, row `row`
+pub fn rendered_at(column: u8, row: u8, text: &str) -> [u8; 1024] {
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    oled.set_text_xy(column, row).unwrap();
+    oled.put_string(text).unwrap();
+    *oled.framebuffer()
+}
+
+/// `ms` milliseconds
+pub fn ms(ms: u64) -> std::time::Duration {
+    std::time::Duration::from_millis(ms)
+}
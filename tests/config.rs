@@ -0,0 +1,69 @@
+use nanohat_oled::{MockInterface, Oled, OledConfig, OledError};
+
+#[test]
+fn default_config_is_the_nanohat_setup() {
+    let mut plain = MockInterface::new();
+    Oled::new(&mut plain).init().unwrap();
+    let mut configured = MockInterface::new();
+    Oled::new(&mut configured)
+        .init_with(OledConfig::default())
+        .unwrap();
+    assert_eq!(plain.sent(), configured.sent());
+}
+
+#[test]
+fn sends_the_configured_settings() {
+    let config = OledConfig::DEFAULT
+        .with_multiplex_ratio(32)
+        .with_display_offset(4)
+        .with_clock(2, 15)
+        .with_precharge(2, 2)
+        .with_com_pins(false, true)
+        .with_vcomh(0x20)
+        .with_charge_pump(false);
+    let mut bus = MockInterface::new();
+    Oled::new(&mut bus).init_with(config).unwrap();
+    assert_eq!(
+        bus.commands()[..25],
+        [
+            0xae, 0x00, 0x10, 0x40, 0xb0, 0x81, 0x7f, 0xa1, 0xa6, 0xa8, 0x1f, 0xc8, 0xd3, 0x04,
+            0xd5, 0xf1, 0xd9, 0x22, 0xda, 0x22, 0xdb, 0x20, 0x8d, 0x10, 0xaf,
+        ]
+    );
+}
+
+#[test]
+fn rejects_settings_out_of_range_without_sending() {
+    let configs = [
+        OledConfig::DEFAULT.with_multiplex_ratio(15),
+        OledConfig::DEFAULT.with_multiplex_ratio(65),
+        OledConfig::DEFAULT.with_display_offset(64),
+        OledConfig::DEFAULT.with_clock(0, 8),
+        OledConfig::DEFAULT.with_clock(17, 8),
+        OledConfig::DEFAULT.with_clock(1, 16),
+        OledConfig::DEFAULT.with_precharge(0, 15),
+        OledConfig::DEFAULT.with_vcomh(0x41),
+    ];
+    for config in configs {
+        let mut bus = MockInterface::new();
+        let result = Oled::new(&mut bus).init_with(config);
+        assert!(
+            matches!(result, Err(OledError::InvalidArgument(_))),
+            "{:?}",
+            config
+        );
+        assert!(bus.sent().is_empty());
+    }
+}
+
+#[test]
+fn wake_leaves_an_external_supply_alone() {
+    let mut bus = MockInterface::new();
+    let mut oled = Oled::new(&mut bus);
+    oled.init_with(OledConfig::DEFAULT.with_charge_pump(false))
+        .unwrap();
+    oled.sleep().unwrap();
+    oled.wake().unwrap();
+    let commands = bus.commands();
+    assert_eq!(commands[commands.len() - 4..], [0xae, 0x8d, 0x10, 0xaf]);
+}
@@ -0,0 +1,137 @@
+use nanohat_oled::{Oled, OledConsole, Rotation, SCROLLBACK_ROWS};
+
+mod common;
+use common::{rendered, NullInterface};
+
+fn numbered(lines: std::ops::Range<usize>) -> Vec<String> {
+    lines.map(|line| format!("line {}", line)).collect()
+}
+
+fn console() -> OledConsole<NullInterface> {
+    OledConsole::new(Oled::new(NullInterface))
+}
+
+#[test]
+fn prints_after_the_last_text() {
+    let mut console = console();
+    console.print("Net").unwrap();
+    console.print("work ").unwrap();
+    console.println("up").unwrap();
+    // a full row doesn't leave a blank one after it
+    console.println("exactly sixteen!").unwrap();
+    console.print("a\n\nb").unwrap();
+    let expected = ["Network up", "exactly sixteen!", "a", "", "b"].map(String::from);
+    assert_eq!(*console.oled().framebuffer(), rendered(&expected));
+}
+
+#[test]
+fn scrolls_and_keeps_earlier_rows() {
+    let mut console = console();
+    for line in numbered(0..10) {
+        console.println(&line).unwrap();
+    }
+    assert_eq!(*console.oled().framebuffer(), rendered(&numbered(2..10)));
+
+    // only two rows have scrolled off
+    console.scroll_back(5).unwrap();
+    assert_eq!(console.scrollback_position(), 2);
+    assert_eq!(*console.oled().framebuffer(), rendered(&numbered(0..8)));
+    console.scroll_forward(1).unwrap();
+    assert_eq!(*console.oled().framebuffer(), rendered(&numbered(1..9)));
+
+    // new text brings the latest back into view
+    console.print("!").unwrap();
+    assert_eq!(console.scrollback_position(), 0);
+    let mut expected = numbered(3..10);
+    expected.push("!".into());
+    assert_eq!(*console.oled().framebuffer(), rendered(&expected));
+}
+
+#[test]
+fn discards_rows_past_the_scrollback() {
+    let mut console = console();
+    let count = 8 + SCROLLBACK_ROWS + 5;
+    for line in numbered(0..count) {
+        console.println(&line).unwrap();
+    }
+    console.scroll_back(usize::MAX).unwrap();
+    assert_eq!(console.scrollback_position(), SCROLLBACK_ROWS);
+    assert_eq!(*console.oled().framebuffer(), rendered(&numbered(5..13)));
+
+    console.clear().unwrap();
+    console.scroll_back(1).unwrap();
+    assert_eq!(console.scrollback_position(), 0);
+}
+
+fn rows(rows: &[&str]) -> Vec<String> {
+    rows.iter().map(|row| row.to_string()).collect()
+}
+
+#[test]
+fn writes_over_rows_after_carriage_returns() {
+    let mut console = console();
+    console.print("10%\r20%\r").unwrap();
+    console.print("30%").unwrap();
+    assert_eq!(*console.oled().framebuffer(), rendered(&rows(&["30%"])));
+    console.println("\rdone").unwrap();
+    console.print("\rnext").unwrap();
+    let expected = rows(&["done", "next"]);
+    assert_eq!(*console.oled().framebuffer(), rendered(&expected));
+}
+
+#[test]
+fn moves_the_cursor_and_erases() {
+    let mut console = console();
+    console.println("first row").unwrap();
+    console.println("second row").unwrap();
+    // rows below the text so far are filled in
+    console.print("\x1b[4;3Hxy\x1b[H>").unwrap();
+    let expected = rows(&[">irst row", "second row", "", "  xy"]);
+    assert_eq!(*console.oled().framebuffer(), rendered(&expected));
+
+    console.print("\x1b[2;7H\x1b[K\x1b[1;6f\x1b[1K").unwrap();
+    let expected = rows(&["      row", "second", "", "  xy"]);
+    assert_eq!(*console.oled().framebuffer(), rendered(&expected));
+    console.print("\x1b[2;3H\x1b[J").unwrap();
+    let expected = rows(&["      row", "se", "", ""]);
+    assert_eq!(*console.oled().framebuffer(), rendered(&expected));
+    console.print("\x1b[2J\x1b[Hclear").unwrap();
+    assert_eq!(*console.oled().framebuffer(), rendered(&rows(&["clear"])));
+}
+
+#[test]
+fn inverts_text_in_reverse_video() {
+    let mut console = console();
+    // split across writes, with sequences it leaves out along the way
+    console.print("\x1b[?25l\x1b[1;").unwrap();
+    console
+        .print("7mok\x1b[27m\x1b(B!\x1b[31m?\x1b[0m")
+        .unwrap();
+    let mut expected = Oled::new(NullInterface);
+    expected.set_text_xy(0, 0).unwrap();
+    expected.put_string_inverted("ok").unwrap();
+    expected.put_string("!?").unwrap();
+    expected.put_string(&" ".repeat(12)).unwrap();
+    assert_eq!(console.oled().framebuffer(), expected.framebuffer());
+}
+
+#[test]
+fn fits_a_display_turned_on_its_side() {
+    let mut oled = Oled::new(NullInterface);
+    oled.set_rotation(Rotation::Rotate90).unwrap();
+    let mut console = OledConsole::new(oled);
+    console.println("0123456789ABCDEF").unwrap();
+    for line in numbered(0..14) {
+        console.println(&line).unwrap();
+    }
+    // 8 columns, so the first line takes two rows, and 16 rows
+    let mut expected = Oled::new(NullInterface);
+    expected.set_rotation(Rotation::Rotate90).unwrap();
+    let mut rows = vec!["01234567".to_string(), "89ABCDEF".to_string()];
+    rows.extend(numbered(0..14));
+    for (row, text) in rows.iter().enumerate() {
+        expected.set_text_xy(0, row as u8).unwrap();
+        expected.put_string(text).unwrap();
+    }
+    assert_eq!(console.oled().framebuffer(), expected.framebuffer());
+}
@@ -0,0 +1,51 @@
+use nanohat_oled::{Controller, Framebuffer, MockInterface, Oled, OledConfig, Sent};
+
+fn init(bus: &mut MockInterface) -> Oled<&mut MockInterface> {
+    let mut oled = Oled::new(bus);
+    oled.init_with(OledConfig::DEFAULT.with_controller(Controller::Sh1106))
+        .unwrap();
+    oled
+}
+
+#[test]
+fn sh1106_setup_uses_page_addressing_and_dc_dc() {
+    let mut bus = MockInterface::new();
+    init(&mut bus);
+    let commands = bus.commands();
+    assert_eq!(commands[22..25], [0xad, 0x8b, 0xaf]);
+    // no addressing mode, and the display cleared a page at a time
+    assert_eq!(commands[25..29], [0xae, 0xb0, 0x02, 0x10]);
+    assert!(!commands.windows(2).any(|c| c == [0x20, 0x00]));
+    assert_eq!(bus.data().len(), 1024);
+}
+
+#[test]
+fn sh1106_writes_are_offset_by_two_columns() {
+    let mut bus = MockInterface::new();
+    let mut oled = init(&mut bus);
+    let mut frame = Framebuffer::new();
+    frame.fill_rect(0, 8, 4, 16, true);
+    oled.flush(&frame).unwrap();
+    assert_eq!(oled.framebuffer(), frame.as_bytes());
+    oled.set_text_xy(1, 2).unwrap();
+    oled.sleep().unwrap();
+
+    let sent = bus.sent();
+    let flushed = &sent[sent.len() - 23..];
+    let command = |byte| Sent::Command(byte);
+    let data = [Sent::Data(0xff); 4];
+    assert_eq!(flushed[..3], [command(0xb1), command(0x02), command(0x10)]);
+    assert_eq!(flushed[3..7], data);
+    assert_eq!(
+        flushed[7..10],
+        [command(0xb2), command(0x02), command(0x10)]
+    );
+    assert_eq!(flushed[10..14], data);
+    assert_eq!(
+        flushed[14..],
+        [0xb0, 0x02, 0x10, 0xb2, 0x0a, 0x10, 0xae, 0xad, 0x8a]
+            .iter()
+            .map(|byte| command(*byte))
+            .collect::<Vec<_>>()[..]
+    );
+}
@@ -0,0 +1,101 @@
+#![cfg(all(feature = "std", unix))]
+use nanohat_oled::{Daemon, Oled, OledError};
+
+mod common;
+use common::NullInterface;
+
+/// `bytes` in standard base64, with padding
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut text = String::new();
+    for chunk in bytes.chunks(3) {
+        let bits = chunk.iter().enumerate().fold(0u32, |bits, (i, byte)| {
+            bits | u32::from(*byte) << (16 - 8 * i)
+        });
+        for i in 0..4 {
+            if i <= chunk.len() {
+                text.push(ALPHABET[(bits >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                text.push('=');
+            }
+        }
+    }
+    text
+}
+
+fn daemon() -> Daemon<NullInterface> {
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    Daemon::new(oled)
+}
+
+#[test]
+fn writes_text_and_sets_contrast() {
+    let mut daemon = daemon();
+    daemon.handle_line("TEXT 2 1 Hello, world\n").unwrap();
+    daemon.handle_line("CONTRAST 40").unwrap();
+    let mut expected = Oled::new(NullInterface);
+    expected.set_text_xy(2, 1).unwrap();
+    expected.put_string("Hello, world").unwrap();
+    assert_eq!(daemon.oled().framebuffer(), expected.framebuffer());
+    assert_eq!(daemon.oled().contrast(), 40);
+
+    daemon.handle_line("CLEAR").unwrap();
+    assert!(daemon.oled().framebuffer().iter().all(|byte| *byte == 0));
+}
+
+#[test]
+fn shows_packed_and_grayscale_images() {
+    let mut daemon = daemon();
+    // the top left pixel of a packed image
+    let mut packed = vec![0u8; 1024];
+    packed[0] = 0x80;
+    daemon
+        .handle_line(&format!("IMG {}", base64(&packed)))
+        .unwrap();
+    assert_eq!(daemon.oled().framebuffer()[..2], [0x01, 0x00]);
+
+    // the second pixel of a grayscale one
+    let mut gray = vec![0u8; 128 * 64];
+    gray[1] = 0xff;
+    daemon
+        .handle_line(&format!("IMG {}", base64(&gray)))
+        .unwrap();
+    assert_eq!(daemon.oled().framebuffer()[..2], [0x00, 0x01]);
+
+    assert!(matches!(
+        daemon.handle_line("IMG AAAA"),
+        Err(OledError::InvalidDimensions)
+    ));
+    assert!(matches!(
+        daemon.handle_line("IMG not*base64"),
+        Err(OledError::InvalidArgument(_))
+    ));
+}
+
+#[test]
+fn skips_bad_lines_when_serving() {
+    let mut daemon = daemon();
+    assert!(matches!(
+        daemon.handle_line("BLINK"),
+        Err(OledError::InvalidArgument(_))
+    ));
+    let requests = "CONTRAST loud\nTEXT 20 0 off the edge\n\nTEXT 0 7 ok\r\nCONTRAST 9\n";
+    daemon.serve(requests.as_bytes()).unwrap();
+    let mut expected = Oled::new(NullInterface);
+    expected.set_text_xy(0, 7).unwrap();
+    expected.put_string("ok").unwrap();
+    assert_eq!(daemon.oled().framebuffer(), expected.framebuffer());
+    assert_eq!(daemon.oled().contrast(), 9);
+}
+
+#[test]
+fn leaves_anything_but_a_socket_in_place() {
+    let path = std::env::temp_dir().join(format!("nanohat-oled-socket-{}", std::process::id()));
+    std::fs::write(&path, b"keep me").unwrap();
+    let result = daemon().serve_socket(&path);
+    let contents = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(matches!(result, Err(OledError::InvalidArgument(_))));
+    assert_eq!(contents, b"keep me");
+}
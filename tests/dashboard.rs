@@ -0,0 +1,94 @@
+#![cfg(feature = "sysinfo")]
+use nanohat_oled::{BuiltinFont, Framebuffer, Rect, SystemDashboard, Widget};
+use std::fs;
+use std::path::PathBuf;
+
+/// A directory standing in for `/`, with `/proc` and `/sys` files for `test`
+fn fake_root(test: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("nanohat-oled-{}-{}", test, std::process::id()));
+    let write = |path: &str, contents: &str| {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    };
+    write("proc/sys/kernel/hostname", "nanopi\n");
+    write(
+        "proc/stat",
+        "cpu  100 0 100 750 50 0 0 0 0 0\ncpu0 100 0 100 750 50 0 0 0 0 0\n",
+    );
+    write(
+        "proc/meminfo",
+        "MemTotal:         512000 kB\nMemFree:          100000 kB\nMemAvailable:     384000 kB\n",
+    );
+    write("sys/class/thermal/thermal_zone0/temp", "45250\n");
+    root
+}
+
+#[test]
+fn reads_stats_from_proc_and_sys() {
+    let root = fake_root("reads-stats");
+    let mut dashboard = SystemDashboard::new().with_root(&root).with_disk(&root);
+    assert_eq!(dashboard.stats().hostname, None);
+    dashboard.refresh();
+    let stats = dashboard.stats().clone();
+    assert_eq!(stats.hostname.as_deref(), Some("nanopi"));
+    assert_eq!(stats.cpu, Some(20.0));
+    assert_eq!(stats.memory, Some((128_000 * 1024, 512_000 * 1024)));
+    assert_eq!(stats.temperature, Some(45.25));
+    let (used, total) = stats
+        .disk
+        .expect("df reports the temporary directory's disk");
+    assert!(used <= total && total > 0);
+
+    // busy for half the time since
+    fs::write(root.join("proc/stat"), "cpu  150 0 150 850 50 0 0 0\n").unwrap();
+    dashboard.refresh();
+    assert_eq!(dashboard.stats().cpu, Some(50.0));
+
+    fs::remove_file(root.join("proc/meminfo")).unwrap();
+    fs::write(root.join("proc/stat"), "intr 0\n").unwrap();
+    dashboard.refresh();
+    assert_eq!(dashboard.stats().memory, None);
+    assert_eq!(dashboard.stats().cpu, None);
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn shows_a_line_of_each() {
+    let root = fake_root("shows-lines");
+    let mut dashboard = SystemDashboard::new().with_root(&root);
+    assert!(dashboard.needs_render());
+    let mut frame = Framebuffer::new();
+    dashboard.render(&mut frame, Rect::new(0, 0, 128, 64));
+    assert!(!dashboard.needs_render());
+    assert_eq!(dashboard.stats().hostname.as_deref(), Some("nanopi"));
+
+    let font = BuiltinFont::PROPORTIONAL;
+    let mut expected = Framebuffer::new();
+    expected.draw_text(0, 0, "nanopi", &font);
+    expected.draw_text(0, 16, "CPU 20%", &font);
+    expected.draw_text(0, 24, "Mem 125/500MB", &font);
+    expected.draw_text(0, 40, "Temp 45.2C", &font);
+    for y in [0, 2, 3, 5].iter().map(|line| line * 8) {
+        for row in y..y + 8 {
+            for x in 0..128 {
+                assert_eq!(
+                    frame.get_pixel(x, row),
+                    expected.get_pixel(x, row),
+                    "{}, {}",
+                    x,
+                    row
+                );
+            }
+        }
+    }
+    assert!((48..64).all(|y| (0..128).all(|x| !frame.get_pixel(x, y))));
+
+    // nothing changed
+    dashboard.refresh();
+    assert!(!dashboard.needs_render());
+    fs::write(root.join("sys/class/thermal/thermal_zone0/temp"), "50000\n").unwrap();
+    dashboard.refresh();
+    assert!(dashboard.needs_render());
+    fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,279 @@
+#![cfg(all(feature = "dbus", unix))]
+use std::io::{Read, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::thread;
+
+use nanohat_oled::{DbusService, Oled, DBUS_NAME, DBUS_PATH};
+
+mod common;
+use common::{rendered_at, NullInterface};
+
+fn service() -> DbusService<NullInterface> {
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    DbusService::new(oled)
+}
+
+/// A socket for a fake bus, and its D-Bus address
+fn bus(test: &str) -> (UnixListener, String) {
+    let path =
+        std::env::temp_dir().join(format!("nanohat-oled-dbus-{}-{}", test, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path).unwrap();
+    let address = format!("unix:path={},guid=0123456789abcdef", path.display());
+    (listener, address)
+}
+
+/// Pads `out` to a multiple of `alignment`
+fn pad(out: &mut Vec<u8>, alignment: usize) {
+    while !out.len().is_multiple_of(alignment) {
+        out.push(0);
+    }
+}
+
+/// Appends a string, aligned from the start of `out`
+fn string(out: &mut Vec<u8>, value: &str) {
+    pad(out, 4);
+    out.extend_from_slice(&(value.len() as u32).to_le_bytes());
+    out.extend_from_slice(value.as_bytes());
+    out.push(0);
+}
+
+/// Puts together a message of type `kind`, with string-like header fields
+/// `fields`, the serial of the message it replies to, if any, and a body
+fn message(
+    kind: u8,
+    serial: u32,
+    fields: &[(u8, char, &str)],
+    reply_to: Option<u32>,
+    signature: &str,
+    body: &[u8],
+) -> Vec<u8> {
+    let mut out = vec![b'l', kind, 0, 1];
+    out.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    out.extend_from_slice(&serial.to_le_bytes());
+    out.extend_from_slice(&[0; 4]);
+    let signature_field = (8, 'g', signature);
+    let fields = fields
+        .iter()
+        .chain(Some(&signature_field).filter(|_| !signature.is_empty()));
+    for (code, kind, value) in fields {
+        pad(&mut out, 8);
+        out.extend_from_slice(&[*code, 1, *kind as u8, 0]);
+        if *kind == 'g' {
+            out.push(value.len() as u8);
+            out.extend_from_slice(value.as_bytes());
+            out.push(0);
+        } else {
+            string(&mut out, value);
+        }
+    }
+    if let Some(reply_to) = reply_to {
+        pad(&mut out, 8);
+        out.extend_from_slice(&[5, 1, b'u', 0]);
+        out.extend_from_slice(&reply_to.to_le_bytes());
+    }
+    let len = (out.len() - 16) as u32;
+    out[12..16].copy_from_slice(&len.to_le_bytes());
+    pad(&mut out, 8);
+    out.extend_from_slice(body);
+    out
+}
+
+/// A method call to the display's object
+fn call(path: &str, serial: u32, member: &str, signature: &str, body: &[u8]) -> Vec<u8> {
+    let fields = [
+        (1, 'o', path),
+        (2, 's', DBUS_NAME),
+        (3, 's', member),
+        (7, 's', ":1.2"),
+    ];
+    message(1, serial, &fields, None, signature, body)
+}
+
+/// Reads a whole message from the service, returning its type and all its bytes
+fn read_message(stream: &mut UnixStream) -> (u8, Vec<u8>) {
+    let mut data = vec![0; 16];
+    stream.read_exact(&mut data).unwrap();
+    assert_eq!(data[0], b'l');
+    let u32_at =
+        |at: usize| u32::from_le_bytes([data[at], data[at + 1], data[at + 2], data[at + 3]]);
+    let (body_len, fields_len) = (u32_at(4) as usize, u32_at(12) as usize);
+    let len = (16 + fields_len).div_ceil(8) * 8 + body_len;
+    data.resize(len, 0);
+    stream.read_exact(&mut data[16..]).unwrap();
+    (data[1], data)
+}
+
+fn contains(data: &[u8], part: &[u8]) -> bool {
+    data.windows(part.len()).any(|window| window == part)
+}
+
+/// Reads a line of the authentication exchange
+fn read_line(stream: &mut UnixStream) -> String {
+    let mut line = Vec::new();
+    let mut byte = [0];
+    while line.last() != Some(&b'\n') {
+        stream.read_exact(&mut byte).unwrap();
+        line.push(byte[0]);
+    }
+    String::from_utf8(line).unwrap()
+}
+
+/// Accepts the service's connection, authenticates it, and answers `Hello` and
+/// `RequestName`, replying `reply` to the latter
+fn accept(listener: &UnixListener, reply: u32) -> UnixStream {
+    let (mut stream, _) = listener.accept().unwrap();
+    assert_eq!(read_line(&mut stream), "\0AUTH EXTERNAL\r\n");
+    stream.write_all(b"DATA\r\n").unwrap();
+    assert_eq!(read_line(&mut stream), "DATA\r\n");
+    stream.write_all(b"OK 0123456789abcdef\r\n").unwrap();
+    assert_eq!(read_line(&mut stream), "BEGIN\r\n");
+
+    let (kind, hello) = read_message(&mut stream);
+    assert!(kind == 1 && contains(&hello, b"Hello"));
+    let mut body = Vec::new();
+    string(&mut body, ":1.1");
+    let serial = u32::from_le_bytes([hello[8], hello[9], hello[10], hello[11]]);
+    stream
+        .write_all(&message(2, 1, &[], Some(serial), "s", &body))
+        .unwrap();
+    // signals from the bus come in between
+    let fields = [(1, 'o', "/org/freedesktop/DBus"), (3, 's', "NameAcquired")];
+    stream
+        .write_all(&message(4, 2, &fields, None, "s", &body))
+        .unwrap();
+
+    let (kind, request) = read_message(&mut stream);
+    assert!(
+        kind == 1 && contains(&request, b"RequestName") && contains(&request, DBUS_NAME.as_bytes())
+    );
+    let serial = u32::from_le_bytes([request[8], request[9], request[10], request[11]]);
+    stream
+        .write_all(&message(2, 3, &[], Some(serial), "u", &reply.to_le_bytes()))
+        .unwrap();
+    stream
+}
+
+/// Sends a call and returns the type of the reply, checking it replies to the call
+fn round_trip(stream: &mut UnixStream, call: &[u8]) -> (u8, Vec<u8>) {
+    stream.write_all(call).unwrap();
+    let (kind, reply) = read_message(stream);
+    let mut reply_serial = vec![5, 1, b'u', 0];
+    reply_serial.extend_from_slice(&call[8..12]);
+    assert!(contains(&reply, &reply_serial));
+    (kind, reply)
+}
+
+#[test]
+fn carries_out_calls_and_replies() {
+    let (listener, address) = bus("calls");
+    let bus = thread::spawn(move || {
+        let mut stream = accept(&listener, 1);
+        let mut image = vec![0, 4, 0, 0];
+        image.extend_from_slice(&[0xff; 1024]);
+        let (kind, _) = round_trip(&mut stream, &call(DBUS_PATH, 10, "ShowImage", "ay", &image));
+        assert_eq!(kind, 2);
+        let (kind, _) = round_trip(&mut stream, &call(DBUS_PATH, 11, "Clear", "", &[]));
+        assert_eq!(kind, 2);
+        let mut text = vec![2, 1];
+        string(&mut text, "Hi");
+        let (kind, _) = round_trip(&mut stream, &call(DBUS_PATH, 12, "ShowText", "yys", &text));
+        assert_eq!(kind, 2);
+        let (kind, _) = round_trip(&mut stream, &call(DBUS_PATH, 13, "SetContrast", "y", &[40]));
+        assert_eq!(kind, 2);
+        let fields = [
+            (1, 'o', DBUS_PATH),
+            (2, 's', "org.freedesktop.DBus.Introspectable"),
+            (3, 's', "Introspect"),
+        ];
+        let (kind, xml) = round_trip(&mut stream, &message(1, 14, &fields, None, "", &[]));
+        assert!(kind == 2 && contains(&xml, b"<method name=\"ShowText\">"));
+    });
+    let mut service = service();
+    service.serve_address(&address).unwrap();
+    bus.join().unwrap();
+    assert_eq!(*service.oled().framebuffer(), rendered_at(2, 1, "Hi"));
+    assert_eq!(service.oled().contrast(), 40);
+}
+
+#[test]
+fn refuses_calls_it_cant_carry_out() {
+    let (listener, address) = bus("refuses");
+    let bus = thread::spawn(move || {
+        let mut stream = accept(&listener, 1);
+        let mut text = Vec::new();
+        string(&mut text, "Hi");
+        let refusals = [
+            (call(DBUS_PATH, 10, "ShowText", "s", &text), "InvalidArgs"),
+            (
+                call(
+                    DBUS_PATH,
+                    11,
+                    "ShowText",
+                    "yys",
+                    &[0, 40, 0, 0, 0, 0, 0, 0, 0],
+                ),
+                "InvalidArgs",
+            ),
+            (
+                call(DBUS_PATH, 12, "ShowImage", "ay", &[1, 0, 0, 0, 7]),
+                "InvalidArgs",
+            ),
+            (call(DBUS_PATH, 13, "Scroll", "", &[]), "UnknownMethod"),
+            (call("/other", 14, "Clear", "", &[]), "UnknownObject"),
+        ];
+        for (call, error) in &refusals {
+            let (kind, reply) = round_trip(&mut stream, call);
+            assert!(kind == 3 && contains(&reply, error.as_bytes()), "{}", error);
+        }
+        // and it carries on serving
+        let (kind, _) = round_trip(&mut stream, &call(DBUS_PATH, 15, "SetContrast", "y", &[9]));
+        assert_eq!(kind, 2);
+    });
+    let mut service = service();
+    service.serve_address(&address).unwrap();
+    bus.join().unwrap();
+    assert_eq!(service.oled().contrast(), 9);
+    assert!(service.oled().framebuffer().iter().all(|byte| *byte == 0));
+}
+
+#[test]
+fn reports_a_name_already_taken() {
+    let (listener, address) = bus("taken");
+    let bus = thread::spawn(move || {
+        // exists, and isn't queued for
+        accept(&listener, 3);
+    });
+    assert!(service().serve_address(&address).is_err());
+    bus.join().unwrap();
+    assert!(service()
+        .serve_address("tcp:host=localhost,port=1")
+        .is_err());
+}
+
+#[cfg(feature = "buttons")]
+#[test]
+fn signals_button_presses() {
+    use nanohat_oled::Buttons;
+
+    let paths = common::button_values("dbus", ["0\n", "0\n", "0\n"]);
+    let buttons = Buttons::from_paths(paths.clone()).unwrap();
+    let (listener, address) = bus("buttons");
+    let bus = thread::spawn(move || {
+        let mut stream = accept(&listener, 1);
+        std::fs::write(&paths[1], "1\n").unwrap();
+        let (kind, signal) = read_message(&mut stream);
+        assert_eq!(kind, 4);
+        assert!(contains(&signal, b"ButtonPressed") && contains(&signal, b"K2"));
+        std::fs::write(&paths[1], "0\n").unwrap();
+        let (kind, signal) = read_message(&mut stream);
+        assert_eq!(kind, 4);
+        assert!(contains(&signal, b"ButtonReleased") && contains(&signal, b"K2"));
+    });
+    service()
+        .with_buttons(buttons)
+        .serve_address(&address)
+        .unwrap();
+    bus.join().unwrap();
+}
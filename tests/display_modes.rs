@@ -0,0 +1,98 @@
+use nanohat_oled::{Brightness, Command, Contrast, MockInterface, Oled, Rotation};
+use std::time::Duration;
+
+#[test]
+fn flashes_and_restores_normal_display() {
+    let mut bus = MockInterface::new();
+    let mut oled = Oled::new(&mut bus);
+    oled.flash(2, Duration::ZERO).unwrap();
+    assert!(!oled.is_inverted());
+    assert_eq!(bus.commands(), [0xa7, 0xa6, 0xa7, 0xa6]);
+}
+
+#[test]
+fn restores_inverse_display() {
+    let mut bus = MockInterface::new();
+    let mut oled = Oled::new(&mut bus);
+    oled.send_command(Command::InverseDisplay).unwrap();
+    oled.flash(1, Duration::ZERO).unwrap();
+    assert!(oled.is_inverted());
+    assert_eq!(bus.commands(), [0xa7, 0xa6, 0xa7]);
+}
+
+#[test]
+fn all_on_leaves_ram_untouched() {
+    let mut bus = MockInterface::new();
+    let mut oled = Oled::new(&mut bus);
+    oled.init().unwrap();
+    oled.put_string("RAM").unwrap();
+    let before = *oled.framebuffer();
+    oled.all_on(true).unwrap();
+    oled.all_on(false).unwrap();
+    assert_eq!(*oled.framebuffer(), before);
+    assert_eq!(bus.commands()[bus.commands().len() - 2..], [0xa5, 0xa4]);
+}
+
+#[test]
+fn tracks_contrast() {
+    let mut bus = MockInterface::new();
+    let mut oled = Oled::new(&mut bus);
+    oled.init().unwrap();
+    assert_eq!(oled.contrast(), 0x7f);
+    oled.set_contrast(Contrast(0x10)).unwrap();
+    oled.adjust_contrast(-0x20).unwrap();
+    assert_eq!(oled.contrast(), 0x00);
+    oled.adjust_contrast(0x40).unwrap();
+    oled.send_command(Command::SetContrast).unwrap();
+    assert_eq!(oled.contrast(), 0x40);
+    oled.send_command(0xf0).unwrap();
+    assert_eq!(oled.contrast(), 0xf0);
+    oled.adjust_contrast(0x20).unwrap();
+    assert_eq!(oled.contrast(), 0xff);
+    oled.adjust_contrast(i16::MAX).unwrap();
+    assert_eq!(oled.contrast(), 0xff);
+    oled.adjust_contrast(i16::MIN).unwrap();
+    assert_eq!(oled.contrast(), 0x00);
+    let commands = bus.commands();
+    assert_eq!(
+        commands[commands.len() - 14..],
+        [0x81, 0x10, 0x81, 0x00, 0x81, 0x40, 0x81, 0xf0, 0x81, 0xff, 0x81, 0xff, 0x81, 0x00]
+    );
+}
+
+#[test]
+fn brightness_sets_contrast_precharge_and_vcomh() {
+    let mut bus = MockInterface::new();
+    let mut oled = Oled::new(&mut bus);
+    oled.set_brightness(Brightness::Dim).unwrap();
+    assert_eq!(oled.contrast(), 0x00);
+    oled.set_brightness(Brightness::Bright).unwrap();
+    assert_eq!(oled.contrast(), 0xff);
+    oled.set_brightness(Brightness::Normal).unwrap();
+    assert_eq!(
+        bus.commands(),
+        [
+            0x81, 0x00, 0xd9, 0x22, 0xdb, 0x00, // dim
+            0x81, 0xff, 0xd9, 0xf1, 0xdb, 0x40, // bright
+            0x81, 0x7f, 0xd9, 0xf1, 0xdb, 0x40, // as init leaves it
+        ]
+    );
+}
+
+#[test]
+fn rotates_and_rewrites_ram() {
+    let mut bus = MockInterface::new();
+    let mut oled = Oled::new(&mut bus);
+    oled.init().unwrap();
+    oled.put_string("Up").unwrap();
+    let before = *oled.framebuffer();
+    oled.set_rotation(Rotation::Rotate180).unwrap();
+    assert_eq!(*oled.framebuffer(), before);
+    let commands = bus.commands();
+    assert_eq!(
+        commands[commands.len() - 14..],
+        [0xa0, 0xc0, 0x21, 0, 127, 0x22, 0, 7, 0x21, 0, 127, 0x22, 0, 7]
+    );
+    let data = bus.data();
+    assert_eq!(data[data.len() - before.len()..], before);
+}
@@ -0,0 +1,113 @@
+use nanohat_oled::{Dither, Oled, OledError, Threshold, OLED_HEIGHT, OLED_WIDTH};
+
+mod common;
+use common::NullInterface;
+
+/// Number of lit pixels on the display
+fn lit(oled: &Oled<NullInterface>) -> u32 {
+    oled.framebuffer()
+        .iter()
+        .map(|byte| byte.count_ones())
+        .sum()
+}
+
+fn gray(level: u8) -> Vec<u8> {
+    vec![level; (OLED_WIDTH * OLED_HEIGHT) as usize]
+}
+
+#[test]
+fn dithering_keeps_average_brightness() {
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    for dither in [Dither::FloydSteinberg, Dither::Bayer] {
+        oled.draw_image_dithered(&gray(64), dither).unwrap();
+        let lit = lit(&oled);
+        assert!((1900..=2200).contains(&lit), "{:?} lit {}", dither, lit);
+        oled.draw_image_dithered(&gray(0), dither).unwrap();
+        assert_eq!(self::lit(&oled), 0);
+        oled.draw_image_dithered(&gray(255), dither).unwrap();
+        assert_eq!(self::lit(&oled), 8192);
+    }
+}
+
+#[test]
+fn bayer_lights_a_regular_pattern() {
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    oled.draw_image_dithered(&gray(128), Dither::Bayer).unwrap();
+    // half of each 4x4 tile, in a checkerboard
+    assert_eq!(lit(&oled), 4096);
+    assert_eq!(oled.framebuffer()[0] & 0x0f, 0b0101);
+    assert_eq!(oled.framebuffer()[1] & 0x0f, 0b1010);
+}
+
+#[test]
+fn threshold_matches_draw_image() {
+    let image: Vec<u8> = (0..(OLED_WIDTH * OLED_HEIGHT) as usize)
+        .map(|pixel| (pixel % 256) as u8)
+        .collect();
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    oled.draw_image(&image, 100).unwrap();
+    let expected = *oled.framebuffer();
+    oled.draw_image_dithered(&gray(0), Dither::Threshold(1))
+        .unwrap();
+    oled.draw_image_dithered(&image, Dither::Threshold(100))
+        .unwrap();
+    assert_eq!(*oled.framebuffer(), expected);
+    assert!(matches!(
+        oled.draw_image_dithered(&[0u8; 10], Dither::Bayer),
+        Err(OledError::InvalidDimensions)
+    ));
+}
+
+#[test]
+fn fits_images_of_any_size() {
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    // a 4x4 white square scales up to fill the display's height, centred
+    oled.draw_image_fit(&[255; 16], 4, 4, Dither::Threshold(128))
+        .unwrap();
+    let image = oled.to_image(1, 0);
+    assert_eq!(lit(&oled), 64 * 64);
+    assert_eq!(image[..32], [0; 32]);
+    assert_eq!(image[32..96], [1; 64]);
+
+    // a wide 256x32 image averages pairs of columns and rows, and is letterboxed
+    let stripes: Vec<u8> = (0..256 * 32)
+        .map(|pixel| if pixel % 2 == 0 { 255 } else { 0 })
+        .collect();
+    oled.draw_image_fit(&stripes, 256, 32, Dither::Threshold(127))
+        .unwrap();
+    let image = oled.to_image(1, 0);
+    assert_eq!(lit(&oled), 128 * 16);
+    assert!(image[..128 * 24].iter().all(|pixel| *pixel == 0));
+    assert!(image[128 * 24..128 * 40].iter().all(|pixel| *pixel == 1));
+
+    assert!(matches!(
+        oled.draw_image_fit(&[0; 10], 4, 4, Dither::Bayer),
+        Err(OledError::InvalidDimensions)
+    ));
+}
+
+#[test]
+fn picks_a_threshold_between_the_ink_and_the_paper() {
+    // a dim logo on a slightly less dim background: no fixed threshold suits it
+    let mut scan = gray(40);
+    for pixel in scan.iter_mut().take(1000) {
+        *pixel = 90;
+    }
+    let level = Threshold::Auto.level(&scan);
+    assert!((41..=90).contains(&level), "picked {}", level);
+    assert_eq!(Threshold::Fixed(7).level(&scan), 7);
+    assert_eq!(Threshold::Auto.level(&gray(200)), 0x80);
+
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    oled.draw_image(&scan, Threshold::Auto.level(&scan))
+        .unwrap();
+    assert_eq!(lit(&oled), 1000);
+    oled.draw_image_dithered(&scan, Dither::AutoThreshold)
+        .unwrap();
+    assert_eq!(lit(&oled), 1000);
+}
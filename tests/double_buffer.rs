@@ -0,0 +1,44 @@
+use nanohat_oled::{DoubleBuffer, MockInterface, Oled, Rotation};
+
+#[test]
+fn only_changes_are_sent_once_presented() {
+    let mut bus = MockInterface::new();
+    {
+        let mut screen = DoubleBuffer::new(Oled::new(&mut bus));
+        screen.draw(|frame| frame.fill_rect(0, 0, 8, 8, true));
+        screen.back().fill_rect(8, 0, 8, 8, true);
+        assert_eq!(screen.oled().framebuffer()[0], 0);
+        screen.present().unwrap();
+        assert_eq!(screen.oled().framebuffer()[..16], [0xff; 16]);
+        // kept, to draw the next frame on
+        assert!(screen.back().get_pixel(15, 7));
+    }
+    // the changed columns of the first page
+    assert_eq!(bus.data(), [0xff; 16]);
+}
+
+#[test]
+fn whole_frames_are_sent_without_diffing() {
+    let mut bus = MockInterface::new();
+    {
+        let mut screen = DoubleBuffer::new(Oled::new(&mut bus))
+            .with_diff(false)
+            .with_clear(true);
+        screen.back().set_pixel(0, 0, true);
+        screen.present().unwrap();
+        assert!(!screen.back().get_pixel(0, 0));
+        screen.present().unwrap();
+    }
+    assert_eq!(bus.data().len(), 2 * 1024);
+    assert_eq!(bus.data()[1024], 0);
+}
+
+#[test]
+fn frames_drawn_before_turning_the_display_are_dropped() {
+    let mut screen = DoubleBuffer::new(Oled::new(MockInterface::new()));
+    screen.back().fill_rect(0, 0, 128, 64, true);
+    screen.oled().set_rotation(Rotation::Rotate90).unwrap();
+    screen.present().unwrap();
+    assert!(screen.oled().framebuffer().iter().all(|&b| b == 0));
+    assert_eq!(screen.back().dimensions(), (64, 128));
+}
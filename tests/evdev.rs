@@ -0,0 +1,100 @@
+#![cfg(all(feature = "evdev", target_os = "linux"))]
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+
+use nanohat_oled::{ButtonEvent, EvdevInput, Input, Key};
+
+/// A `struct input_event` of type `kind`, with a time of zero
+fn event(kind: u16, code: u16, value: i32) -> Vec<u8> {
+    let mut event = vec![0; 2 * std::mem::size_of::<usize>()];
+    event.extend_from_slice(&kind.to_ne_bytes());
+    event.extend_from_slice(&code.to_ne_bytes());
+    event.extend_from_slice(&value.to_ne_bytes());
+    event
+}
+
+/// A file standing in for an input device, holding `events`
+fn device(test: &str, events: &[Vec<u8>]) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "nanohat-oled-evdev-{}-{}",
+        test,
+        std::process::id()
+    ));
+    std::fs::write(&path, events.concat()).unwrap();
+    path
+}
+
+#[test]
+fn maps_keys_and_drops_repeats() {
+    let path = device(
+        "keys",
+        &[
+            // up, held long enough to repeat, then let go
+            event(1, 103, 1),
+            event(1, 103, 2),
+            event(0, 0, 0),
+            event(1, 103, 0),
+            // a letter, which isn't mapped, then enter and escape
+            event(1, 30, 1),
+            event(1, 28, 1),
+            event(1, 1, 1),
+        ],
+    );
+    let mut keypad = EvdevInput::new(&path).unwrap();
+    assert_eq!(
+        keypad.poll_events().unwrap(),
+        [
+            ButtonEvent::Press(Key::K1),
+            ButtonEvent::Release(Key::K1),
+            ButtonEvent::Press(Key::K3),
+        ]
+    );
+    assert!(keypad.poll_events().unwrap().is_empty());
+
+    let mut keypad = EvdevInput::new(&path)
+        .unwrap()
+        .with_key(1, Key::K1)
+        .with_key(28, Key::K2);
+    let events = keypad.poll_events().unwrap();
+    assert_eq!(
+        events[2..],
+        [ButtonEvent::Press(Key::K2), ButtonEvent::Press(Key::K1)]
+    );
+}
+
+#[test]
+fn turns_steps_of_a_dial_into_presses() {
+    let path = device(
+        "axes",
+        &[
+            event(2, 7, 2),
+            event(2, 7, -1),
+            event(2, 0, 1),
+            event(2, 1, 5),
+        ],
+    );
+    let press = |key| [ButtonEvent::Press(key), ButtonEvent::Release(key)];
+    let mut encoder = EvdevInput::new(&path).unwrap();
+    assert_eq!(
+        encoder.poll_events().unwrap(),
+        [press(Key::K2), press(Key::K2), press(Key::K1)].concat()
+    );
+    // an encoder reporting on the X axis instead
+    let mut encoder = EvdevInput::new(&path)
+        .unwrap()
+        .with_axis(0, Key::K1, Key::K3);
+    assert_eq!(encoder.poll_events().unwrap()[6..], press(Key::K3));
+}
+
+#[test]
+fn waits_for_the_rest_of_an_event() {
+    let press = event(1, 108, 1);
+    let (start, rest) = press.split_at(5);
+    let path = device("partial", &[start.to_vec()]);
+    let mut keypad = EvdevInput::new(&path).unwrap();
+    assert!(keypad.poll_events().unwrap().is_empty());
+    let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+    file.write_all(rest).unwrap();
+    assert_eq!(keypad.poll_events().unwrap(), [ButtonEvent::Press(Key::K2)]);
+}
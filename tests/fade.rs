@@ -0,0 +1,84 @@
+use nanohat_oled::{CaptureDevice, Contrast, Fade, FadeInterval, FadeMode, MockInterface, Oled};
+
+mod common;
+use common::ms;
+
+#[test]
+fn fades_out_in_steps_then_turns_off() {
+    let mut device = CaptureDevice::new();
+    let mut oled = Oled::new(&mut device);
+    oled.init().unwrap();
+    oled.set_contrast(Contrast(0x80)).unwrap();
+    oled.put_string("fade").unwrap();
+    let before = *oled.framebuffer();
+    let mut fade = Fade::fade_out(ms(320));
+    // a step every 10ms
+    assert_eq!(fade.tick(&mut oled, ms(0)).unwrap(), Some(ms(10)));
+    assert_eq!(oled.contrast(), 0x80);
+    assert_eq!(fade.tick(&mut oled, ms(165)).unwrap(), Some(ms(5)));
+    assert_eq!(oled.contrast(), 0x40);
+    assert_eq!(fade.tick(&mut oled, ms(200)).unwrap(), None);
+    assert!(fade.is_finished());
+    // back to where it was, but off
+    assert_eq!(oled.contrast(), 0x80);
+    assert_eq!(*oled.framebuffer(), before);
+    assert_eq!(fade.tick(&mut oled, ms(10)).unwrap(), None);
+    let commands = device.commands();
+    assert_eq!(
+        commands[commands.len() - 5..],
+        [0x81, 0x00, 0xae, 0x81, 0x80]
+    );
+    assert!(!device.is_lit(1, 1) && device.ram() == &before);
+}
+
+#[test]
+fn fades_in_from_darkness() {
+    let mut device = CaptureDevice::new();
+    let mut oled = Oled::new(&mut device);
+    oled.init().unwrap();
+    oled.put_string("x").unwrap();
+    device.clear();
+    let mut oled = Oled::new(&mut device);
+    let mut fade = Fade::fade_in(ms(320));
+    fade.tick(&mut oled, ms(0)).unwrap();
+    fade.tick(&mut oled, ms(400)).unwrap();
+    // contrast down before turning on, then up to the reset level
+    assert_eq!(device.commands(), [0x81, 0x00, 0xaf, 0x81, 0x7f]);
+
+    // with no time to take, straight on
+    device.clear();
+    Oled::new(&mut device).fade_in(ms(0)).unwrap();
+    assert_eq!(device.commands(), [0x81, 0x7f, 0xaf]);
+    assert!(device.is_lit(1, 2));
+}
+
+#[test]
+fn fades_out_and_in_blocking() {
+    let mut device = CaptureDevice::new();
+    let mut oled = Oled::new(&mut device);
+    oled.init().unwrap();
+    oled.fade_out(ms(32)).unwrap();
+    oled.fade_in(ms(32)).unwrap();
+    assert_eq!(oled.contrast(), 0x7f);
+    let commands = device.commands();
+    assert_eq!(commands.last(), Some(&0x7f));
+    assert!(commands.contains(&0xae));
+}
+
+#[test]
+fn sets_the_controllers_fade_mode() {
+    let mut bus = MockInterface::new();
+    let mut oled = Oled::new(&mut bus);
+    oled.set_fade_mode(FadeMode::FadeOut(FadeInterval::Frames8))
+        .unwrap();
+    oled.set_fade_mode(FadeMode::Blink(FadeInterval::Frames128))
+        .unwrap();
+    oled.set_fade_mode(FadeMode::Blink(FadeInterval::Frames64))
+        .unwrap();
+    oled.set_fade_mode(FadeMode::Off).unwrap();
+    assert_eq!(oled.contrast(), 0x7f);
+    assert_eq!(
+        bus.commands(),
+        [0x23, 0x20, 0x23, 0x3f, 0x23, 0x37, 0x23, 0x00]
+    );
+}
@@ -0,0 +1,78 @@
+#![cfg(feature = "fbdev")]
+use std::path::PathBuf;
+
+use nanohat_oled::{Dither, FbMirror, Oled, OledError};
+
+mod common;
+use common::NullInterface;
+
+/// A file standing in for a framebuffer device, holding `contents`
+fn device(test: &str, contents: &[u8]) -> PathBuf {
+    let path =
+        std::env::temp_dir().join(format!("nanohat-oled-fb-{}-{}", test, std::process::id()));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+fn mirror(device: &PathBuf) -> FbMirror<NullInterface> {
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    FbMirror::new(oled)
+        .with_device(device)
+        .with_dither(Dither::Threshold(0x80))
+}
+
+#[test]
+fn mirrors_the_framebuffer_scaled_down() {
+    // 256x128 at 32 bits, white on the left half
+    let row = [[0xffu8; 4 * 128], [0x00; 4 * 128]].concat();
+    let path = device("scaled", &row.repeat(128));
+    let mut mirror = mirror(&path).with_geometry(256, 128, 32, 256 * 4);
+    mirror.mirror().unwrap();
+
+    let mut expected = Oled::new(NullInterface);
+    expected.init().unwrap();
+    let half = [[0xffu8; 64], [0x00; 64]].concat().repeat(64);
+    expected.draw_image(&half, 0x80).unwrap();
+    assert_eq!(mirror.oled().framebuffer(), expected.framebuffer());
+}
+
+#[test]
+fn mirrors_only_the_region() {
+    // 40x20 at 16 bits, with padding at the end of each row, and the region white
+    let (width, height, stride) = (40, 20, 96);
+    let mut contents = vec![0u8; stride * height];
+    for y in 4..12 {
+        for x in 8..24 {
+            contents[y * stride + x * 2..][..2].copy_from_slice(&[0xff, 0xff]);
+        }
+    }
+    let path = device("region", &contents);
+    let mut mirror = mirror(&path)
+        .with_geometry(width, height, 16, stride)
+        .with_region(8, 4, 16, 8);
+    mirror.mirror().unwrap();
+    assert!(mirror.oled().framebuffer().iter().all(|byte| *byte == 0xff));
+}
+
+#[test]
+fn refuses_regions_and_depths_it_cant_mirror() {
+    let path = device("refuses", &[0; 64 * 32 * 4]);
+    let mut mirror = mirror(&path)
+        .with_geometry(64, 32, 32, 64 * 4)
+        .with_region(32, 0, 64, 32);
+    assert!(matches!(mirror.mirror(), Err(OledError::OutOfBounds)));
+    let mut mirror = mirror.with_region(0, 0, 0, 32);
+    assert!(matches!(mirror.mirror(), Err(OledError::InvalidDimensions)));
+    let mut mirror = mirror
+        .with_region(0, 0, 64, 32)
+        .with_geometry(64, 32, 12, 96);
+    assert!(matches!(
+        mirror.mirror(),
+        Err(OledError::InvalidArgument(_))
+    ));
+    let mut mirror = mirror
+        .with_geometry(64, 32, 32, 64 * 4)
+        .with_device(path.with_extension("missing"));
+    assert!(matches!(mirror.mirror(), Err(OledError::I2c(_))));
+}
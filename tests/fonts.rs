@@ -0,0 +1,148 @@
+use std::borrow::Cow;
+
+use nanohat_oled::{
+    fit_text, BuiltinFont, Font, Framebuffer, Glyph, MockInterface, Oled, Sprite, Truncation,
+};
+
+mod common;
+use common::{lit, NullInterface};
+
+/// A font of 2x3 blocks, with a hanging `g` and no `?`
+struct Blocks;
+
+const BLOCK: Sprite = Sprite::new(2, 3, &[0xc0, 0xc0, 0xc0]);
+
+impl Font for Blocks {
+    fn line_height(&self) -> u16 {
+        5
+    }
+
+    fn glyph(&self, char: char) -> Option<Glyph<'_>> {
+        match char {
+            'a' => Some(Glyph::new(BLOCK, 3)),
+            'g' => Some(Glyph::new(BLOCK, 3).with_offset(0, 2)),
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn monospace_matches_text_cells() {
+    let mut frame = Framebuffer::new();
+    assert_eq!(frame.draw_text(8, 16, "Hi?", &BuiltinFont::MONOSPACE), 32);
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    oled.set_text_xy(1, 2).unwrap();
+    oled.put_string("Hi?").unwrap();
+    assert_eq!(frame.as_bytes(), oled.framebuffer());
+}
+
+#[test]
+fn proportional_trims_blank_columns() {
+    let font = BuiltinFont::PROPORTIONAL;
+    // 'i' is a single column, 'm' five, and a space three, each plus a column of spacing
+    assert_eq!(font.char_advance('i'), 2);
+    assert_eq!(font.char_advance('m'), 6);
+    assert_eq!(font.char_advance(' '), 3);
+    assert_eq!(font.text_width("mi mi"), 19);
+    // unknown characters are drawn as the same box put_char uses
+    assert_eq!(font.char_advance('\u{20ac}'), 9);
+
+    let mut frame = Framebuffer::new();
+    assert_eq!(frame.draw_text(0, 0, "ii", &font), 4);
+    assert_eq!(
+        lit(&frame),
+        [
+            (0, 0),
+            (2, 0),
+            (0, 2),
+            (2, 2),
+            (0, 3),
+            (2, 3),
+            (0, 4),
+            (2, 4),
+            (0, 5),
+            (2, 5),
+            (0, 6),
+            (2, 6)
+        ]
+    );
+}
+
+#[test]
+fn draws_custom_fonts() {
+    let mut frame = Framebuffer::new();
+    frame.fill_rect(0, 0, 12, 5, true);
+    // 'x' has no glyph and the font has no '?', so it is skipped
+    assert_eq!(Blocks.text_width("axg"), 6);
+    assert_eq!(frame.draw_text(1, 0, "axg", &Blocks), 7);
+    let mut expected = Framebuffer::new();
+    expected.set_pixel(0, 0, true);
+    expected.fill_rect(0, 0, 1, 5, true);
+    expected.fill_rect(1, 0, 2, 3, true);
+    expected.fill_rect(4, 2, 2, 3, true);
+    expected.fill_rect(7, 0, 5, 5, true);
+    assert_eq!(lit(&frame), lit(&expected));
+}
+
+#[test]
+fn sends_only_the_text() {
+    let font = BuiltinFont::PROPORTIONAL;
+    let text = "21.5C";
+    let width = font.text_width(text);
+    let mut oled = Oled::new(MockInterface::new());
+    oled.init().unwrap();
+    oled.put_string_font(128 - width, 0, text, &font).unwrap();
+    let bus = oled.into_interface();
+    // the spacing after the last glyph is blank, so unchanged
+    let commands = bus.commands();
+    assert_eq!(
+        commands[commands.len() - 12..commands.len() - 6],
+        [0x21, (128 - width) as u8, 126, 0x22, 0, 0]
+    );
+}
+
+#[test]
+fn fits_text_with_an_ellipsis() {
+    let font = BuiltinFont::MONOSPACE;
+    assert_eq!(fit_text("wlan0", 40, &font), "wlan0");
+    assert!(matches!(fit_text("wlan0", 40, &font), Cow::Borrowed(_)));
+    assert_eq!(fit_text("wlan0", 39, &font), "wla…");
+    assert_eq!(fit_text("wlan0", 4, &font), "…");
+    let font = BuiltinFont::PROPORTIONAL;
+    let fitted = fit_text("Restart the network", 50, &font);
+    assert!(fitted.ends_with('…') && font.text_width(&fitted) <= 50);
+    // fonts without one get three dots
+    assert_eq!(fit_text("aaaa", 5, &Blocks), "a...");
+}
+
+#[test]
+fn draws_labels_cut_short() {
+    let font = BuiltinFont::MONOSPACE;
+    let lit = |frame: &Framebuffer, left: u16, right: u16| {
+        (left..right).any(|x| (0..8).any(|y| frame.get_pixel(x, y)))
+    };
+    let mut frame = Framebuffer::new();
+    frame.fill_rect(0, 0, 128, 8, true);
+    frame.draw_text_fit(0, 0, 36, "MMMMMMMM", &font, Truncation::Clip);
+    // the label's line is cleared, up to its edge and no further
+    assert!(lit(&frame, 33, 36) && !(0..8).any(|y| frame.get_pixel(32, y)));
+    assert!((36..128).all(|x| (0..8).all(|y| frame.get_pixel(x, y))));
+
+    // the ellipsis is in the last whole cell, and nothing is past it
+    let mut frame = Framebuffer::new();
+    frame.draw_text_fit(0, 0, 36, "MMMMMMMM", &font, Truncation::Ellipsis);
+    assert!(lit(&frame, 16, 24) && frame.get_pixel(25, 6) && !lit(&frame, 32, 128));
+    assert!(!frame.get_pixel(26, 6) && !(0..6).any(|y| frame.get_pixel(25, y)));
+
+    // fading keeps fewer of the lit pixels towards the edge
+    let mut frame = Framebuffer::new();
+    frame.draw_text_fit(0, 0, 64, "████████████", &font, Truncation::Fade);
+    let count = |left: u16| {
+        (left..left + 4)
+            .map(|x| (0..8).filter(|y| frame.get_pixel(x, *y)).count())
+            .sum::<usize>()
+    };
+    assert_eq!(count(48), 32);
+    assert!(count(56) < 32 && count(60) < count(56) && !lit(&frame, 64, 128));
+}
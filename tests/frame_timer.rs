@@ -0,0 +1,38 @@
+#![cfg(feature = "std")]
+use nanohat_oled::{DoubleBuffer, FrameTimer, MockInterface, Oled};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+#[test]
+fn frames_on_time_wait_for_the_beat() {
+    let mut timer = FrameTimer::new(100);
+    assert_eq!(timer.period(), Duration::from_millis(10));
+    let started = Instant::now();
+    for _ in 0..3 {
+        // drawing takes part of each frame, which doesn't slow the rate
+        sleep(Duration::from_millis(4));
+        timer.wait();
+    }
+    assert!(started.elapsed() >= Duration::from_millis(30));
+    assert_eq!(timer.frames(), 3);
+}
+
+#[test]
+fn slow_frames_are_counted_as_dropped() {
+    let mut timer = FrameTimer::with_period(Duration::from_millis(10));
+    sleep(Duration::from_millis(35));
+    let dropped = timer.wait();
+    assert!(dropped >= 2, "{}", dropped);
+    assert_eq!(timer.dropped(), u64::from(dropped));
+    timer.reset();
+    assert_eq!((timer.frames(), timer.dropped()), (0, 0));
+}
+
+#[test]
+fn present_at_sends_the_frame() {
+    let mut screen = DoubleBuffer::new(Oled::new(MockInterface::new()));
+    let mut timer = FrameTimer::new(1000);
+    screen.back().set_pixel(0, 0, true);
+    screen.present_at(&mut timer).unwrap();
+    assert_eq!(screen.oled().framebuffer()[0], 0x01);
+}
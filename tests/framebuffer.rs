@@ -0,0 +1,236 @@
+use nanohat_oled::{
+    AddressingMode, Bitmap, BlitOp, Framebuffer, MockInterface, Oled, OLED_HEIGHT, OLED_WIDTH,
+};
+
+mod common;
+use common::NullInterface;
+
+fn diagonal() -> Vec<u8> {
+    let mut image = vec![0u8; (OLED_WIDTH * OLED_HEIGHT) as usize];
+    for y in 0..OLED_HEIGHT as usize {
+        image[y * OLED_WIDTH as usize + y] = 200;
+    }
+    image
+}
+
+#[test]
+fn starts_empty_after_init() {
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    assert!(oled.framebuffer().iter().all(|byte| *byte == 0));
+}
+
+#[test]
+fn tracks_drawn_image() {
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    oled.draw_image(&diagonal(), 128).unwrap();
+    let framebuffer = oled.framebuffer();
+    assert_eq!(framebuffer[0], 0x01);
+    assert_eq!(framebuffer[1], 0x02);
+    assert_eq!(framebuffer[7], 0x80);
+    assert_eq!(framebuffer[OLED_WIDTH as usize + 8], 0x01);
+    assert_eq!(framebuffer.iter().map(|b| b.count_ones()).sum::<u32>(), 64);
+}
+
+#[test]
+fn round_trips_through_image() {
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    oled.draw_image(&diagonal(), 128).unwrap();
+    assert_eq!(oled.to_image(200, 0), diagonal());
+}
+
+#[test]
+fn tracks_text_at_cursor() {
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    oled.set_text_xy(2, 1).unwrap();
+    oled.put_string("AB").unwrap();
+    let start = OLED_WIDTH as usize + 16;
+    assert_eq!(
+        &oled.framebuffer()[start..start + 16],
+        &[
+            0x00, 0x7E, 0x09, 0x09, 0x09, 0x7E, 0x00, 0x00, // A
+            0x00, 0x7F, 0x49, 0x49, 0x49, 0x36, 0x00, 0x00, // B
+        ]
+    );
+}
+
+#[test]
+fn wraps_text_onto_next_page() {
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    oled.set_text_xy(15, 0).unwrap();
+    oled.put_string("!!").unwrap();
+    let framebuffer = oled.framebuffer();
+    assert_eq!(framebuffer[15 * 8 + 2], 0x5F);
+    assert_eq!(framebuffer[OLED_WIDTH as usize + 2], 0x5F);
+}
+
+#[test]
+fn blit_combines_with_existing_contents() {
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    let mut square = vec![0u8; (OLED_WIDTH * OLED_HEIGHT) as usize];
+    for y in 0..8 {
+        for x in 0..8 {
+            square[y * OLED_WIDTH as usize + x] = 255;
+        }
+    }
+    oled.draw_image(&diagonal(), 128).unwrap();
+
+    oled.blit(&square, 128, BlitOp::Or).unwrap();
+    assert_eq!(oled.framebuffer()[..8], [0xff; 8]);
+    assert_eq!(oled.framebuffer()[OLED_WIDTH as usize + 8], 0x01);
+
+    oled.blit(&square, 128, BlitOp::Xor).unwrap();
+    assert_eq!(oled.framebuffer()[..8], [0x00; 8]);
+    assert_eq!(oled.framebuffer()[OLED_WIDTH as usize + 8], 0x01);
+
+    oled.blit(&square, 128, BlitOp::And).unwrap();
+    assert!(oled.framebuffer().iter().all(|byte| *byte == 0));
+
+    oled.blit(&diagonal(), 128, BlitOp::Copy).unwrap();
+    assert_eq!(oled.to_image(200, 0), diagonal());
+}
+
+#[test]
+fn draws_const_bitmap() {
+    const HALF: Bitmap<128, 64> = {
+        let mut rows = [[0u8; 128]; 64];
+        let mut y = 0;
+        while y < 32 {
+            rows[y] = [255; 128];
+            y += 1;
+        }
+        Bitmap::new(rows)
+    };
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    oled.draw_bitmap(&HALF, 128).unwrap();
+    let (top, bottom) = oled.framebuffer().split_at(512);
+    assert!(top.iter().all(|byte| *byte == 0xff));
+    assert!(bottom.iter().all(|byte| *byte == 0x00));
+}
+
+#[test]
+fn framebuffer_sets_and_clears_pixels() {
+    let mut frame = Framebuffer::new();
+    frame.set_pixel(3, 10, true);
+    frame.set_pixel(127, 63, true);
+    frame.set_pixel(128, 0, true);
+    frame.set_pixel(0, 64, true);
+    assert!(frame.get_pixel(3, 10));
+    assert!(frame.get_pixel(127, 63));
+    assert!(!frame.get_pixel(4, 10));
+    assert!(!frame.get_pixel(128, 0));
+    assert_eq!(frame.as_bytes()[OLED_WIDTH as usize + 3], 0x04);
+    assert_eq!(
+        frame.as_bytes().iter().map(|b| b.count_ones()).sum::<u32>(),
+        2
+    );
+
+    frame.set_pixel(3, 10, false);
+    assert!(!frame.get_pixel(3, 10));
+    frame.clear();
+    assert_eq!(frame, Framebuffer::default());
+}
+
+#[test]
+fn flush_replaces_display_in_any_mode() {
+    let mut frame = Framebuffer::new();
+    for y in 0..OLED_HEIGHT {
+        frame.set_pixel(y, y, true);
+    }
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    oled.set_addressing_mode(AddressingMode::Page).unwrap();
+    oled.set_text_xy(4, 3).unwrap();
+    oled.flush(&frame).unwrap();
+    assert_eq!(oled.framebuffer(), frame.as_bytes());
+    assert_eq!(oled.to_image(200, 0), diagonal());
+}
+
+#[test]
+fn flush_sends_only_changed_columns() {
+    let mut frame = Framebuffer::new();
+    frame.set_pixel(10, 0, true);
+    frame.set_pixel(12, 15, true);
+    frame.set_pixel(3, 63, true);
+    let mut bus = MockInterface::new();
+    let mut oled = Oled::new(&mut bus);
+    oled.set_addressing_mode(AddressingMode::Horizontal)
+        .unwrap();
+    oled.flush(&frame).unwrap();
+    assert_eq!(oled.framebuffer(), frame.as_bytes());
+    // nothing left to send
+    oled.flush(&frame).unwrap();
+    assert_eq!(
+        bus.commands()[2..],
+        [
+            0x21, 10, 12, 0x22, 0, 1, 0x21, 0, 127, 0x22, 0, 7, // pages 0 and 1
+            0x21, 3, 3, 0x22, 7, 7, 0x21, 0, 127, 0x22, 0, 7, // page 7
+        ]
+    );
+    assert_eq!(bus.data(), [0x01, 0, 0, 0, 0, 0x80, 0x80]);
+}
+
+#[test]
+fn flush_all_sends_everything() {
+    let mut bus = MockInterface::new();
+    let mut oled = Oled::new(&mut bus);
+    oled.flush_all(&Framebuffer::new()).unwrap();
+    assert_eq!(bus.data().len(), 1024);
+}
+
+#[test]
+fn draws_image_at_position_with_clipping() {
+    let mut bus = MockInterface::new();
+    let mut oled = Oled::new(&mut bus);
+    oled.init().unwrap();
+    oled.draw_image_at(&[255; 4], 2, 2, 10, 7, 128).unwrap();
+    // a 4x4 image in the bottom right corner is clipped to 2x2
+    oled.draw_image_at(&[255; 16], 4, 4, 126, 62, 128).unwrap();
+    // nothing to draw off screen, but the image is still checked
+    oled.draw_image_at(&[255; 4], 2, 2, 128, 0, 128).unwrap();
+    assert!(oled.draw_image_at(&[255; 3], 2, 2, 0, 0, 128).is_err());
+
+    let mut expected = Framebuffer::new();
+    expected.fill_rect(10, 7, 2, 2, true);
+    expected.fill_rect(126, 62, 2, 2, true);
+    assert_eq!(oled.framebuffer(), expected.as_bytes());
+    let sent = bus.commands();
+    assert_eq!(
+        sent[sent.len() - 24..],
+        [
+            0x21, 10, 11, 0x22, 0, 1, 0x21, 0, 127, 0x22, 0, 7, // straddles pages 0 and 1
+            0x21, 126, 127, 0x22, 7, 7, 0x21, 0, 127, 0x22, 0, 7, // clipped to page 7
+        ]
+    );
+    let data = bus.data();
+    assert_eq!(data[data.len() - 6..], [0x80, 0x80, 0x01, 0x01, 0xc0, 0xc0]);
+}
+
+#[test]
+fn redrawing_leaves_nothing_of_the_last_picture() {
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    oled.draw_image(&[255; (OLED_WIDTH * OLED_HEIGHT) as usize], 128)
+        .unwrap();
+    let mut frame = Framebuffer::new();
+    frame.set_pixel(5, 9, true);
+    oled.flush(&frame).unwrap();
+    oled.draw_fn(|x, y| (x, y) == (0, 0)).unwrap();
+    let mut expected = Framebuffer::new();
+    expected.set_pixel(0, 0, true);
+    assert_eq!(oled.framebuffer(), expected.as_bytes());
+    oled.draw_image(&diagonal(), 128).unwrap();
+    assert_eq!(
+        oled.framebuffer()
+            .iter()
+            .map(|b| b.count_ones())
+            .sum::<u32>(),
+        64
+    );
+}
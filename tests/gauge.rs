@@ -0,0 +1,62 @@
+use nanohat_oled::{Framebuffer, Gauge, OledError, Rect, Widget};
+
+/// Renders `gauge` into a fresh frame, in a 41 by 24 area with a dial of
+/// radius 20 centred on 20, 20
+fn render(gauge: &mut Gauge) -> Framebuffer {
+    let mut frame = Framebuffer::new();
+    frame.set_pixel(100, 5, true);
+    gauge.render(&mut frame, Rect::new(0, 0, 41, 24));
+    assert!(frame.get_pixel(100, 5));
+    frame
+}
+
+#[test]
+fn needle_points_to_the_value() {
+    let mut gauge = Gauge::new(-10.0, 10.0).unwrap().with_ticks(0);
+    assert!(gauge.needs_render());
+    let frame = render(&mut gauge);
+    assert!(!gauge.needs_render());
+    // the dial, and the needle pointing left along the bottom
+    assert!(frame.get_pixel(0, 20) && frame.get_pixel(20, 0) && frame.get_pixel(40, 20));
+    assert!(frame.get_pixel(10, 20) && !frame.get_pixel(20, 10));
+
+    gauge.set_value(0.0);
+    assert!(gauge.needs_render());
+    let frame = render(&mut gauge);
+    assert!(frame.get_pixel(20, 10) && !frame.get_pixel(10, 20));
+
+    gauge.set_value(25.0);
+    assert_eq!(gauge.value(), 10.0);
+    let frame = render(&mut gauge);
+    assert!(frame.get_pixel(30, 20) && !frame.get_pixel(20, 10));
+    gauge.set_value(10.0);
+    assert!(!gauge.needs_render());
+}
+
+#[test]
+fn draws_ticks_and_labels() {
+    let mut plain = Gauge::new(0.0, 1.0).unwrap().with_ticks(0);
+    let plain = render(&mut plain);
+    let mut ticked = Gauge::new(0.0, 1.0).unwrap().with_ticks(2);
+    let ticked = render(&mut ticked);
+    // a tick at the top, inside the dial
+    assert!(!plain.get_pixel(20, 2) && ticked.get_pixel(20, 2));
+
+    // labels shrink the dial to make room beneath its ends, at 6 and 34
+    let mut labelled = Gauge::new(0.0, 1.0).unwrap().with_labels("0", "1");
+    let labelled = render(&mut labelled);
+    let lit = |frame: &Framebuffer, x: u16| (16..24).any(|y| frame.get_pixel(x, y));
+    assert!(!labelled.get_pixel(0, 20));
+    assert!(labelled.get_pixel(6, 14) && labelled.get_pixel(34, 14));
+    assert!(lit(&labelled, 7) && lit(&labelled, 33));
+    assert!(!lit(&labelled, 20));
+}
+
+#[test]
+fn rejects_empty_ranges() {
+    assert!(matches!(
+        Gauge::new(5.0, -5.0),
+        Err(OledError::InvalidArgument(_))
+    ));
+    assert!(Gauge::new(f32::NEG_INFINITY, 0.0).is_err());
+}
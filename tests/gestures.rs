@@ -0,0 +1,98 @@
+#![cfg(feature = "buttons")]
+use nanohat_oled::{ButtonEvent, Gesture, GestureDetector, Key};
+use std::time::{Duration, Instant};
+
+/// Feeds `detector` each of `steps`, an event or none at a number of
+/// milliseconds in, and returns the gestures with when they were made
+fn run(
+    detector: &mut GestureDetector,
+    steps: &[(u64, Option<ButtonEvent>)],
+) -> Vec<(u64, Gesture)> {
+    let start = Instant::now();
+    let mut gestures = Vec::new();
+    for (ms, event) in steps {
+        let now = start + Duration::from_millis(*ms);
+        gestures.extend(detector.update(*event, now).map(|gesture| (*ms, gesture)));
+    }
+    gestures
+}
+
+/// An update every 10 ms from `from` to `to`, with no events
+fn idle(from: u64, to: u64) -> impl Iterator<Item = (u64, Option<ButtonEvent>)> {
+    (from..=to).step_by(10).map(|ms| (ms, None))
+}
+
+const PRESS: Option<ButtonEvent> = Some(ButtonEvent::Press(Key::K1));
+const RELEASE: Option<ButtonEvent> = Some(ButtonEvent::Release(Key::K1));
+
+#[test]
+fn short_presses_are_reported_when_let_go_despite_bouncing() {
+    let mut detector = GestureDetector::new();
+    let steps = [
+        (0, PRESS),
+        (2, RELEASE),
+        (4, PRESS),
+        (30, None),
+        (100, RELEASE),
+        (105, PRESS),
+        (110, RELEASE),
+        (200, None),
+    ];
+    assert_eq!(run(&mut detector, &steps), [(100, Gesture::Press(Key::K1))]);
+
+    // a release lost in the bouncing is caught once it settles
+    let steps = [(0, PRESS), (5, RELEASE), (30, None)];
+    assert_eq!(
+        run(&mut GestureDetector::new(), &steps),
+        [(30, Gesture::Press(Key::K1))]
+    );
+}
+
+#[test]
+fn long_presses_are_reported_while_held_and_repeat() {
+    let mut detector = GestureDetector::new().with_long_press(Duration::from_millis(500));
+    let mut steps = vec![(0, PRESS)];
+    steps.extend(idle(10, 900));
+    steps.push((910, RELEASE));
+    assert_eq!(
+        run(&mut detector, &steps),
+        [(500, Gesture::LongPress(Key::K1))]
+    );
+
+    let mut detector = GestureDetector::new()
+        .with_long_press(Duration::from_millis(500))
+        .with_repeat(Some(Duration::from_millis(200)));
+    let gestures = run(&mut detector, &steps);
+    assert_eq!(
+        gestures,
+        [
+            (500, Gesture::LongPress(Key::K1)),
+            (700, Gesture::Repeat(Key::K1)),
+            (900, Gesture::Repeat(Key::K1)),
+        ]
+    );
+}
+
+#[test]
+fn double_presses_wait_for_the_second() {
+    let mut detector = GestureDetector::new().with_double_press(Some(Duration::from_millis(300)));
+    let steps = [
+        (0, PRESS),
+        (100, RELEASE),
+        (250, PRESS),
+        (300, RELEASE),
+        (1000, None),
+        (1100, Some(ButtonEvent::Press(Key::K3))),
+        (1150, Some(ButtonEvent::Release(Key::K3))),
+        (1400, None),
+        (1460, None),
+    ];
+    assert_eq!(
+        run(&mut detector, &steps),
+        [
+            (250, Gesture::DoublePress(Key::K1)),
+            (1460, Gesture::Press(Key::K3)),
+        ]
+    );
+    assert_eq!(Gesture::Repeat(Key::K2).key(), Key::K2);
+}
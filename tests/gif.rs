@@ -0,0 +1,197 @@
+#![cfg(feature = "gif")]
+use nanohat_oled::{Dither, Framebuffer, Oled, OledError, PlayOptions};
+use std::collections::HashMap;
+
+mod common;
+use common::NullInterface;
+
+const OPTIONS: PlayOptions = PlayOptions {
+    dither: Dither::Threshold(128),
+    loops: Some(1),
+};
+
+/// LZW-compresses palette indices as a GIF encoder would
+fn compress(min_code_size: u8, indices: &[u8]) -> Vec<u8> {
+    let clear = 1u16 << min_code_size;
+    let end = clear + 1;
+    let mut table: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut next = end + 1;
+    let mut code_size = min_code_size + 1;
+    let mut out = Vec::new();
+    let (mut bits, mut bit_count) = (0u32, 0u8);
+    let mut emit = |code: u16, size: u8, out: &mut Vec<u8>| {
+        bits |= u32::from(code) << bit_count;
+        bit_count += size;
+        while bit_count >= 8 {
+            out.push(bits as u8);
+            bits >>= 8;
+            bit_count -= 8;
+        }
+    };
+    emit(clear, code_size, &mut out);
+    let mut current = u16::from(indices[0]);
+    for index in &indices[1..] {
+        if let Some(code) = table.get(&(current, *index)) {
+            current = *code;
+            continue;
+        }
+        emit(current, code_size, &mut out);
+        table.insert((current, *index), next);
+        next += 1;
+        if next == 4096 {
+            // the table is full, so start a new one
+            emit(clear, code_size, &mut out);
+            table.clear();
+            next = end + 1;
+            code_size = min_code_size + 1;
+        } else if next > 1 << code_size {
+            code_size += 1;
+        }
+        current = u16::from(*index);
+    }
+    emit(current, code_size, &mut out);
+    emit(end, code_size, &mut out);
+    emit(0, 7, &mut out);
+    out
+}
+
+/// Splits data into sub-blocks, ending with an empty one
+fn sub_blocks(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for chunk in data.chunks(255) {
+        out.push(chunk.len() as u8);
+        out.extend_from_slice(chunk);
+    }
+    out.push(0);
+    out
+}
+
+/// An image descriptor and its data, with a graphic control extension in front
+fn image(
+    left: u16,
+    top: u16,
+    width: u16,
+    height: u16,
+    control: [u8; 4],
+    indices: &[u8],
+) -> Vec<u8> {
+    let mut out = vec![0x21, 0xf9, 4];
+    out.extend_from_slice(&control);
+    out.push(0);
+    out.push(0x2c);
+    for value in [left, top, width, height] {
+        out.extend_from_slice(&value.to_le_bytes());
+    }
+    out.extend_from_slice(&[0x00, 2]);
+    out.extend(sub_blocks(&compress(2, indices)));
+    out
+}
+
+/// A GIF `width` by `height` pixels, with a black, white, gray and red palette,
+/// looping forever
+fn gif_sized(width: u16, height: u16, images: &[Vec<u8>]) -> Vec<u8> {
+    let mut out = b"GIF89a".to_vec();
+    out.extend_from_slice(&width.to_le_bytes());
+    out.extend_from_slice(&height.to_le_bytes());
+    out.extend_from_slice(&[0xf1, 0, 0]);
+    out.extend_from_slice(&[0, 0, 0, 255, 255, 255, 128, 128, 128, 255, 0, 0]);
+    out.extend_from_slice(b"\x21\xff\x0bNETSCAPE2.0\x03\x01\x00\x00\x00");
+    for image in images {
+        out.extend_from_slice(image);
+    }
+    out.push(0x3b);
+    out
+}
+
+/// As for [`gif_sized()`], with the same size as the display
+fn gif(images: &[Vec<u8>]) -> Vec<u8> {
+    gif_sized(128, 64, images)
+}
+
+fn played(data: &[u8]) -> Result<Framebuffer, OledError> {
+    let mut oled = Oled::new(NullInterface);
+    oled.init()?;
+    oled.play_gif(data, OPTIONS)?;
+    Ok(Framebuffer::from_bytes(*oled.framebuffer()))
+}
+
+#[test]
+fn composites_frames() {
+    // white everywhere, then a transparent frame with a black and a red block,
+    // which is cleared back to black once shown
+    let mut second = vec![2u8; 8 * 4];
+    second[..8].copy_from_slice(&[0; 8]);
+    second[8..16].copy_from_slice(&[3; 8]);
+    let third: Vec<u8> = (0..4 * 2).map(|_| 2).collect();
+    let data = gif(&[
+        image(0, 0, 128, 64, [0x00, 2, 0, 0], &[1; 128 * 64]),
+        image(10, 20, 8, 4, [0x09, 2, 0, 2], &second),
+        image(0, 0, 4, 2, [0x01, 2, 0, 2], &third),
+    ]);
+    let frame = played(&data).unwrap();
+    let dark: Vec<(u16, u16)> = (0..64)
+        .flat_map(|y| (0..128).map(move |x| (x, y)))
+        .filter(|(x, y)| !frame.get_pixel(*x, *y))
+        .collect();
+    // red is too dark to light a pixel; the transparent rows were left white,
+    // then disposal cleared the whole block
+    let expected: Vec<(u16, u16)> = (20..24)
+        .flat_map(|y| (10..18).map(move |x| (x, y)))
+        .collect();
+    assert_eq!(dark, expected);
+}
+
+#[test]
+fn scales_and_deinterlaces() {
+    // a 4x2 white-over-black image, interlaced, fills the width of the display
+    let mut data = b"GIF87a".to_vec();
+    data.extend_from_slice(&[4, 0, 2, 0, 0x80, 0, 0, 0, 0, 0, 255, 255, 255]);
+    data.push(0x2c);
+    data.extend_from_slice(&[0, 0, 0, 0, 4, 0, 2, 0, 0x40, 2]);
+    data.extend(sub_blocks(&compress(2, &[1, 1, 1, 1, 0, 0, 0, 0])));
+    let frame = played(&data).unwrap();
+    assert!(frame.get_pixel(0, 0) && frame.get_pixel(127, 31));
+    assert!(!frame.get_pixel(0, 32) && !frame.get_pixel(127, 63));
+}
+
+#[test]
+fn decodes_noise_past_a_full_table() {
+    // a large noisy image compresses badly, so fills the LZW table and forces
+    // it to start again; each value covers a 4x4 block, which scales down to a pixel
+    let mut seed = 1u32;
+    let noise: Vec<u8> = (0..128 * 64)
+        .map(|_| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            (seed >> 16) as u8 & 1
+        })
+        .collect();
+    let indices: Vec<u8> = (0..512 * 256)
+        .map(|offset| noise[(offset / 512 / 4) * 128 + offset % 512 / 4])
+        .collect();
+    let data = gif_sized(512, 256, &[image(0, 0, 512, 256, [0, 2, 0, 0], &indices)]);
+    let frame = played(&data).unwrap();
+    for (offset, index) in noise.iter().enumerate() {
+        let (x, y) = ((offset % 128) as u16, (offset / 128) as u16);
+        assert_eq!(frame.get_pixel(x, y), *index == 1, "pixel {}, {}", x, y);
+    }
+}
+
+#[test]
+fn rejects_bad_files() {
+    let invalid = |data: &[u8]| matches!(played(data), Err(OledError::InvalidArgument(_)));
+    assert!(invalid(b"PNG"));
+    assert!(invalid(b"GIF89a\x80\x00\x40\x00"));
+    let mut no_frames = gif(&[]);
+    assert!(invalid(&no_frames));
+    no_frames.truncate(no_frames.len() - 1);
+    no_frames.extend_from_slice(&[0x2c, 0, 0]);
+    assert!(invalid(&no_frames));
+}
+
+#[test]
+fn rejects_huge_images() {
+    let huge = |data: &[u8]| matches!(played(data), Err(OledError::InvalidDimensions));
+    assert!(huge(&gif_sized(65535, 65535, &[])));
+    let frame = image(0, 0, 65535, 65535, [0; 4], &[0]);
+    assert!(huge(&gif(&[frame])));
+}
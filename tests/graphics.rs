@@ -0,0 +1,341 @@
+use nanohat_oled::{Framebuffer, Oled, Stroke};
+
+mod common;
+use common::{lit, NullInterface};
+
+#[test]
+fn draws_lines_in_any_direction() {
+    let mut frame = Framebuffer::new();
+    frame.draw_line(3, 1, 0, 0, true);
+    assert_eq!(lit(&frame), [(0, 0), (1, 0), (2, 1), (3, 1)]);
+
+    frame.clear();
+    frame.draw_line(5, 2, 5, 5, true);
+    assert_eq!(lit(&frame), [(5, 2), (5, 3), (5, 4), (5, 5)]);
+}
+
+#[test]
+fn clips_shapes_at_edges() {
+    let mut frame = Framebuffer::new();
+    frame.draw_line(120, 60, 200, 60, true);
+    assert_eq!(lit(&frame).len(), 8);
+
+    frame.clear();
+    frame.fill_circle(0, 0, 2, true);
+    assert_eq!(
+        lit(&frame),
+        [
+            (0, 0),
+            (1, 0),
+            (2, 0),
+            (0, 1),
+            (1, 1),
+            (2, 1),
+            (0, 2),
+            (1, 2)
+        ]
+    );
+}
+
+#[test]
+fn draws_rectangles() {
+    let mut frame = Framebuffer::new();
+    frame.draw_rect(1, 1, 3, 3, true);
+    assert_eq!(
+        lit(&frame),
+        [
+            (1, 1),
+            (2, 1),
+            (3, 1),
+            (1, 2),
+            (3, 2),
+            (1, 3),
+            (2, 3),
+            (3, 3)
+        ]
+    );
+    frame.fill_rect(1, 1, 3, 3, true);
+    assert_eq!(lit(&frame).len(), 9);
+    frame.fill_rect(2, 2, 1, 1, false);
+    assert!(!frame.get_pixel(2, 2));
+
+    frame.clear();
+    frame.draw_rect(10, 10, 0, 5, true);
+    assert!(lit(&frame).is_empty());
+}
+
+#[test]
+fn draws_circles() {
+    let mut frame = Framebuffer::new();
+    frame.draw_circle(10, 10, 2, true);
+    assert_eq!(
+        lit(&frame),
+        [
+            (9, 8),
+            (10, 8),
+            (11, 8),
+            (8, 9),
+            (12, 9),
+            (8, 10),
+            (12, 10),
+            (8, 11),
+            (12, 11),
+            (9, 12),
+            (10, 12),
+            (11, 12),
+        ]
+    );
+    let outline = lit(&frame);
+    frame.fill_circle(10, 10, 2, true);
+    let filled = lit(&frame);
+    assert!(outline.iter().all(|pixel| filled.contains(pixel)));
+    assert_eq!(filled.len(), 21);
+}
+
+#[test]
+fn draws_arcs_of_circles() {
+    let mut circle = Framebuffer::new();
+    circle.draw_circle(10, 10, 5, true);
+    let mut frame = Framebuffer::new();
+    frame.draw_arc(10, 10, 5, 0.0, 180.0, true);
+    let top: Vec<_> = lit(&circle).into_iter().filter(|(_, y)| *y <= 10).collect();
+    assert_eq!(lit(&frame), top);
+
+    // the right half, running anticlockwise from below to above
+    let mut frame = Framebuffer::new();
+    frame.draw_arc(10, 10, 5, -90.0, 90.0, true);
+    assert!(lit(&frame).iter().all(|(x, _)| *x >= 10));
+    assert!(frame.get_pixel(10, 5) && frame.get_pixel(15, 10) && frame.get_pixel(10, 15));
+
+    let mut frame = Framebuffer::new();
+    frame.draw_arc(10, 10, 5, 90.0, 450.0, true);
+    assert_eq!(lit(&frame), lit(&circle));
+}
+
+#[test]
+fn draws_onto_current_display() {
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    oled.put_string("A").unwrap();
+    oled.draw(|frame| frame.draw_line(0, 63, 127, 63, true))
+        .unwrap();
+    assert_eq!(oled.framebuffer()[1], 0x7e);
+    assert!(oled.framebuffer()[7 * 128..]
+        .iter()
+        .all(|byte| *byte == 0x80));
+}
+
+#[test]
+fn fills_polygons() {
+    let mut frame = Framebuffer::new();
+    frame.fill_polygon(&[(1, 1), (4, 1), (4, 3), (1, 3)], true);
+    let mut rect = Framebuffer::new();
+    rect.fill_rect(1, 1, 4, 3, true);
+    assert_eq!(lit(&frame), lit(&rect));
+
+    frame.clear();
+    frame.fill_polygon(&[(0, 0), (4, 0), (0, 4)], true);
+    assert_eq!(
+        lit(&frame),
+        [
+            (0, 0),
+            (1, 0),
+            (2, 0),
+            (3, 0),
+            (4, 0),
+            (0, 1),
+            (1, 1),
+            (2, 1),
+            (3, 1),
+            (0, 2),
+            (1, 2),
+            (2, 2),
+            (0, 3),
+            (1, 3),
+            (0, 4)
+        ]
+    );
+
+    // a star crossing itself leaves its middle unfilled
+    frame.clear();
+    frame.fill_polygon(&[(20, 0), (30, 30), (5, 10), (35, 10), (10, 30)], true);
+    assert!(frame.get_pixel(20, 5) && frame.get_pixel(8, 11));
+    assert!(!frame.get_pixel(20, 16));
+    // clipped at the edges
+    frame.clear();
+    frame.fill_polygon(&[(120, 60), (200, 60), (200, 100), (120, 100)], true);
+    assert_eq!(lit(&frame).len(), 8 * 4);
+}
+
+#[test]
+fn flood_fills_inside_an_outline() {
+    let mut frame = Framebuffer::new();
+    frame.draw_rect(0, 0, 5, 5, true);
+    frame.draw_line(10, 0, 10, 63, true);
+    frame.flood_fill(2, 2, true);
+    let mut filled = Framebuffer::new();
+    filled.fill_rect(0, 0, 5, 5, true);
+    filled.draw_line(10, 0, 10, 63, true);
+    assert_eq!(lit(&frame), lit(&filled));
+
+    // the outside, round the corner of the box and up to the line
+    frame.flood_fill(6, 6, true);
+    assert!(frame.get_pixel(5, 0) && frame.get_pixel(9, 63) && frame.get_pixel(0, 63));
+    assert!(!frame.get_pixel(11, 0));
+
+    // clearing takes away what's joined, lit
+    frame.flood_fill(0, 0, false);
+    assert_eq!(lit(&frame).len(), 0);
+    frame.flood_fill(200, 0, true);
+    assert_eq!(lit(&frame).len(), 0);
+}
+
+#[test]
+fn draws_rounded_rectangles() {
+    let mut square = Framebuffer::new();
+    square.draw_rect(2, 2, 10, 6, true);
+    let mut frame = Framebuffer::new();
+    frame.draw_round_rect(2, 2, 10, 6, 0, true);
+    assert_eq!(lit(&frame), lit(&square));
+
+    frame.clear();
+    frame.draw_round_rect(0, 0, 10, 6, 2, true);
+    assert!(!frame.get_pixel(0, 0) && !frame.get_pixel(9, 5));
+    assert!(frame.get_pixel(2, 0) && frame.get_pixel(7, 0) && frame.get_pixel(0, 2));
+    assert!(frame.get_pixel(1, 0) && frame.get_pixel(0, 1) && frame.get_pixel(8, 5));
+    assert!(!frame.get_pixel(4, 3));
+
+    // filled, it covers the outline and everything inside
+    let mut filled = Framebuffer::new();
+    filled.fill_round_rect(0, 0, 10, 6, 2, true);
+    assert!(lit(&frame).iter().all(|(x, y)| filled.get_pixel(*x, *y)));
+    assert!(filled.get_pixel(4, 3) && !filled.get_pixel(0, 0));
+    // a radius too large for the rectangle is limited to what fits
+    frame.clear();
+    frame.fill_round_rect(0, 0, 5, 5, 20, true);
+    let mut circle = Framebuffer::new();
+    circle.fill_circle(2, 2, 2, true);
+    assert_eq!(lit(&frame), lit(&circle));
+}
+
+#[test]
+fn draws_ellipses() {
+    let mut frame = Framebuffer::new();
+    frame.draw_ellipse(10, 10, 6, 3, true);
+    let pixels = lit(&frame);
+    assert!(pixels.contains(&(4, 10)) && pixels.contains(&(16, 10)));
+    assert!(pixels.contains(&(10, 7)) && pixels.contains(&(10, 13)));
+    assert!(pixels
+        .iter()
+        .all(|(x, y)| (4..=16).contains(x) && (7..=13).contains(y)));
+    assert!(!frame.get_pixel(10, 10));
+
+    let mut filled = Framebuffer::new();
+    filled.fill_ellipse(10, 10, 6, 3, true);
+    assert!(pixels.iter().all(|(x, y)| filled.get_pixel(*x, *y)));
+    assert!(filled.get_pixel(10, 10) && !filled.get_pixel(4, 7));
+
+    // flat ellipses are lines
+    frame.clear();
+    frame.draw_ellipse(10, 10, 2, 0, true);
+    assert_eq!(
+        lit(&frame),
+        [(8, 10), (9, 10), (10, 10), (11, 10), (12, 10)]
+    );
+    frame.clear();
+    frame.fill_ellipse(10, 10, 0, 1, true);
+    assert_eq!(lit(&frame), [(10, 9), (10, 10), (10, 11)]);
+}
+
+#[test]
+fn fills_slices_of_circles() {
+    let mut circle = Framebuffer::new();
+    circle.fill_circle(10, 10, 5, true);
+    let mut frame = Framebuffer::new();
+    frame.fill_arc(10, 10, 5, 0.0, 90.0, true);
+    let quarter: Vec<_> = lit(&circle)
+        .into_iter()
+        .filter(|(x, y)| *x >= 10 && *y <= 10)
+        .collect();
+    assert_eq!(lit(&frame), quarter);
+
+    frame.clear();
+    frame.fill_arc(10, 10, 5, 0.0, 360.0, true);
+    assert_eq!(lit(&frame), lit(&circle));
+}
+
+#[test]
+fn draws_styled_lines() {
+    let mut frame = Framebuffer::new();
+    frame.draw_line_styled(0, 0, 9, 0, Stroke::DASHED, true);
+    assert_eq!(
+        lit(&frame),
+        [(0, 0), (1, 0), (2, 0), (3, 0), (8, 0), (9, 0)]
+    );
+
+    frame.clear();
+    frame.draw_line_styled(0, 0, 4, 0, Stroke::DOTTED, true);
+    assert_eq!(lit(&frame), [(0, 0), (2, 0), (4, 0)]);
+
+    // thick lines widen across their direction, centred on the thin line
+    frame.clear();
+    frame.draw_line_styled(5, 5, 7, 5, Stroke::SOLID.with_width(3), true);
+    let mut block = Framebuffer::new();
+    block.fill_rect(5, 4, 3, 3, true);
+    assert_eq!(lit(&frame), lit(&block));
+    frame.clear();
+    frame.draw_line_styled(5, 5, 5, 6, Stroke::new(2, 0xffff), true);
+    assert_eq!(lit(&frame), [(5, 5), (6, 5), (5, 6), (6, 6)]);
+
+    let mut thin = Framebuffer::new();
+    thin.draw_line(3, 1, 40, 20, true);
+    frame.clear();
+    frame.draw_line_styled(3, 1, 40, 20, Stroke::default(), true);
+    assert_eq!(lit(&frame), lit(&thin));
+    frame.clear();
+    frame.draw_line_styled(3, 1, 40, 20, Stroke::SOLID.with_width(0), true);
+    assert!(lit(&frame).is_empty());
+}
+
+#[test]
+fn draws_polylines_through_points_off_the_display() {
+    let mut frame = Framebuffer::new();
+    frame.draw_polyline(&[(-10, 5), (3, 5), (3, 7)], true);
+    assert_eq!(
+        lit(&frame),
+        [(0, 5), (1, 5), (2, 5), (3, 5), (3, 6), (3, 7)]
+    );
+
+    // a line crossing the display, between points far off it, is clipped to
+    // what's on it
+    frame.clear();
+    frame.draw_polyline(&[(-1_000_000, 10), (1_000_000, 10), (1_000_000, -5)], true);
+    assert_eq!(lit(&frame).len(), 128);
+    assert!(lit(&frame).iter().all(|(_, y)| *y == 10));
+    // and one missing it altogether draws nothing
+    frame.clear();
+    frame.draw_polyline(&[(-5, -5), (200, -1), (200, 70)], true);
+    assert!(lit(&frame).is_empty());
+
+    frame.clear();
+    frame.draw_polyline(&[(4, 4)], true);
+    assert_eq!(lit(&frame), [(4, 4)]);
+    frame.draw_polyline(&[], true);
+    assert_eq!(lit(&frame), [(4, 4)]);
+}
+
+#[test]
+fn closes_polygons() {
+    let mut frame = Framebuffer::new();
+    frame.draw_polygon(&[(1, 1), (4, 1), (4, 3), (1, 3)], true);
+    let mut rect = Framebuffer::new();
+    rect.draw_rect(1, 1, 4, 3, true);
+    assert_eq!(lit(&frame), lit(&rect));
+
+    // filled with a corner off the top left
+    frame.clear();
+    frame.fill_polygon(&[(-4, -4), (3, -4), (3, 3), (-4, 3)], true);
+    rect.clear();
+    rect.fill_rect(0, 0, 4, 4, true);
+    assert_eq!(lit(&frame), lit(&rect));
+}
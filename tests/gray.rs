@@ -0,0 +1,71 @@
+#![cfg(feature = "std")]
+use nanohat_oled::{Oled, OledError, TemporalGray};
+
+mod common;
+use common::NullInterface;
+
+/// How many frames of a cycle each pixel of the top row is lit in
+fn lit_counts(gray: &mut TemporalGray<NullInterface>) -> Vec<usize> {
+    let mut counts = vec![0; 128];
+    for _ in 0..gray.cycle_len() {
+        gray.step().unwrap();
+        let ram = gray.oled().framebuffer();
+        for (count, byte) in counts.iter_mut().zip(ram.iter()) {
+            *count += usize::from(byte & 1);
+        }
+    }
+    counts
+}
+
+#[test]
+fn lights_each_pixel_in_a_share_of_the_cycle() {
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    let mut gray = TemporalGray::new(oled);
+    assert_eq!(gray.cycle_len(), 3);
+    let image: Vec<u8> = (0..128 * 64)
+        .map(|index| [0, 85, 170, 255][index % 4])
+        .collect();
+    gray.set_image(&image).unwrap();
+    let counts = lit_counts(&mut gray);
+    assert_eq!(counts[..8], [0, 1, 2, 3, 0, 1, 2, 3]);
+
+    let mut gray = gray.with_grays(4);
+    assert_eq!(gray.cycle_len(), 5);
+    let image: Vec<u8> = (0..128 * 64).map(|index| (index % 128 * 2) as u8).collect();
+    gray.set_image(&image).unwrap();
+    let counts = lit_counts(&mut gray);
+    assert_eq!(
+        (counts[0], counts[13], counts[51], counts[127]),
+        (0, 1, 2, 5)
+    );
+}
+
+#[test]
+fn spreads_a_shade_over_the_cycle() {
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    let mut gray = TemporalGray::new(oled);
+    gray.set_image(&[85; 128 * 64]).unwrap();
+    // a third gray lights each pixel once a cycle, but not all in the same frame
+    for _ in 0..gray.cycle_len() {
+        gray.step().unwrap();
+        let lit: u32 = gray
+            .oled()
+            .framebuffer()
+            .iter()
+            .map(|byte| byte.count_ones())
+            .sum();
+        assert!((2000..=3500).contains(&lit), "{} pixels lit", lit);
+    }
+    assert_eq!(lit_counts(&mut gray), vec![1; 128]);
+}
+
+#[test]
+fn rejects_images_of_another_size() {
+    let mut gray = TemporalGray::new(Oled::new(NullInterface));
+    assert!(matches!(
+        gray.set_image(&[0; 64]),
+        Err(OledError::InvalidDimensions)
+    ));
+}
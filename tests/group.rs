@@ -0,0 +1,53 @@
+use nanohat_oled::{DisplayGroup, Framebuffer, GroupLayout, MockInterface, Oled, Rotation};
+
+#[test]
+fn side_by_side_draws_across_both_displays() {
+    let (mut left, mut right) = (MockInterface::new(), MockInterface::new());
+    let mut group = DisplayGroup::side_by_side(Oled::new(&mut left), Oled::new(&mut right));
+    assert_eq!(group.dimensions(), (256, 64));
+    group
+        .draw(|frame| {
+            assert_eq!(frame.dimensions(), (256, 64));
+            frame.draw_line(120, 10, 135, 10, true);
+            frame.set_pixel(255, 63, true);
+        })
+        .unwrap();
+    let [left_oled, right_oled] = group.into_displays();
+    let (left_frame, right_frame) = (
+        Framebuffer::from_bytes(*left_oled.framebuffer()),
+        Framebuffer::from_bytes(*right_oled.framebuffer()),
+    );
+    assert!((120..128).all(|x| left_frame.get_pixel(x, 10)));
+    assert!((0..8).all(|x| right_frame.get_pixel(x, 10)));
+    assert!(!right_frame.get_pixel(8, 10));
+    assert!(right_frame.get_pixel(127, 63));
+    assert!(!left_frame.get_pixel(127, 63));
+}
+
+#[test]
+fn mirrored_shows_the_same_on_both() {
+    let (mut first, mut second) = (MockInterface::new(), MockInterface::new());
+    {
+        let mut group = DisplayGroup::mirrored(Oled::new(&mut first), Oled::new(&mut second));
+        assert_eq!(group.layout(), GroupLayout::Mirrored);
+        assert_eq!(group.dimensions(), (128, 64));
+        group.for_each(|oled| oled.init()).unwrap();
+        group
+            .draw(|frame| frame.fill_rect(10, 10, 20, 20, true))
+            .unwrap();
+        let [first_oled, second_oled] = group.into_displays();
+        assert_eq!(first_oled.framebuffer(), second_oled.framebuffer());
+        assert!(Framebuffer::from_bytes(*second_oled.framebuffer()).get_pixel(29, 29));
+    }
+    assert_eq!(first.data(), second.data());
+}
+
+#[test]
+fn side_by_side_needs_unrotated_displays() {
+    let (mut left, mut right) = (MockInterface::new(), MockInterface::new());
+    let mut group = DisplayGroup::side_by_side(Oled::new(&mut left), Oled::new(&mut right));
+    group.displays()[1]
+        .set_rotation(Rotation::Rotate90)
+        .unwrap();
+    assert!(group.draw(|frame| frame.set_pixel(0, 0, true)).is_err());
+}
@@ -0,0 +1,135 @@
+#![cfg(feature = "http")]
+use std::io::{self, Cursor, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+
+use nanohat_oled::{Framebuffer, HttpDisplay, Oled};
+
+mod common;
+use common::NullInterface;
+
+const CHECKER: &[u8] = include_bytes!("data/checker.png");
+
+/// A connection with a request waiting to be read, keeping the reply
+struct Connection {
+    request: Cursor<Vec<u8>>,
+    reply: Vec<u8>,
+}
+
+impl Read for Connection {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.request.read(buf)
+    }
+}
+
+impl Write for Connection {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.reply.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn server() -> HttpDisplay<NullInterface> {
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    HttpDisplay::new(oled)
+}
+
+/// Sends `method` to `target` with `body`, returning the reply's status line
+fn request(
+    server: &mut HttpDisplay<NullInterface>,
+    method: &str,
+    target: &str,
+    body: &[u8],
+) -> String {
+    let mut request = format!(
+        "{} {} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n",
+        method,
+        target,
+        body.len()
+    )
+    .into_bytes();
+    request.extend_from_slice(body);
+    let mut connection = Connection {
+        request: Cursor::new(request),
+        reply: Vec::new(),
+    };
+    server.handle_connection(&mut connection).unwrap();
+    let reply = String::from_utf8(connection.reply).unwrap();
+    reply.lines().next().unwrap().to_string()
+}
+
+#[test]
+fn writes_text_draws_images_and_clears() {
+    let mut server = server();
+    assert_eq!(
+        request(&mut server, "POST", "/text?x=1&y=6", b"Hello\nworld"),
+        "HTTP/1.1 204 No Content"
+    );
+    let mut expected = Oled::new(NullInterface);
+    expected.init().unwrap();
+    expected.set_text_xy(1, 6).unwrap();
+    expected.put_string("Hello").unwrap();
+    expected.set_text_xy(1, 7).unwrap();
+    expected.put_string("world").unwrap();
+    assert_eq!(server.oled().framebuffer(), expected.framebuffer());
+
+    assert_eq!(
+        request(&mut server, "POST", "/clear", b""),
+        "HTTP/1.1 204 No Content"
+    );
+    assert_eq!(
+        request(&mut server, "POST", "/image?x=4&y=2", CHECKER),
+        "HTTP/1.1 204 No Content"
+    );
+    let mut expected = Framebuffer::new();
+    expected.draw_png(CHECKER, 4, 2).unwrap();
+    assert_eq!(server.oled().framebuffer(), expected.as_bytes());
+}
+
+#[test]
+fn refuses_what_it_cannot_do() {
+    let mut server = server();
+    assert_eq!(
+        request(&mut server, "GET", "/", b""),
+        "HTTP/1.1 404 Not Found"
+    );
+    assert_eq!(
+        request(&mut server, "GET", "/text", b""),
+        "HTTP/1.1 405 Method Not Allowed"
+    );
+    assert_eq!(
+        request(&mut server, "POST", "/text?y=8", b"off"),
+        "HTTP/1.1 400 Bad Request"
+    );
+    assert_eq!(
+        request(&mut server, "POST", "/text?x=one", b"Hi"),
+        "HTTP/1.1 400 Bad Request"
+    );
+    assert_eq!(
+        request(&mut server, "POST", "/image", b"not a PNG"),
+        "HTTP/1.1 400 Bad Request"
+    );
+    assert!(server.oled().framebuffer().iter().all(|byte| *byte == 0));
+}
+
+#[test]
+fn answers_over_tcp() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    thread::spawn(move || server().serve_listener(listener));
+    let mut stream = TcpStream::connect(address).unwrap();
+    stream
+        .write_all(b"POST /clear HTTP/1.1\r\nContent-Length: 0\r\n\r\n")
+        .unwrap();
+    let mut reply = String::new();
+    stream.read_to_string(&mut reply).unwrap();
+    assert!(
+        reply.starts_with("HTTP/1.1 204 No Content\r\n"),
+        "{}",
+        reply
+    );
+}
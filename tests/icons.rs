@@ -0,0 +1,81 @@
+use nanohat_oled::{Framebuffer, Icon, IconSize, Oled};
+
+mod common;
+use common::NullInterface;
+
+/// Every icon, at every level
+fn all() -> Vec<Icon> {
+    let mut icons: Vec<_> = (0..=Icon::MAX_WIFI_LEVEL).map(Icon::Wifi).collect();
+    icons.extend((0..=Icon::MAX_BATTERY_LEVEL).map(Icon::Battery));
+    icons.extend(vec![
+        Icon::ArrowUp,
+        Icon::ArrowDown,
+        Icon::ArrowLeft,
+        Icon::ArrowRight,
+        Icon::Play,
+        Icon::Pause,
+        Icon::Stop,
+        Icon::Warning,
+        Icon::Link,
+        Icon::Check,
+        Icon::Cross,
+    ]);
+    icons
+}
+
+/// The lit pixels of `icon` at `size`, row by row
+fn pixels(icon: Icon, size: IconSize) -> Vec<bool> {
+    let sprite = icon.sprite(size);
+    let side = size.pixels();
+    assert_eq!(sprite.dimensions(), (side, side));
+    (0..side)
+        .flat_map(|y| (0..side).map(move |x| (x, y)))
+        .map(|(x, y)| sprite.get_pixel(x, y).unwrap())
+        .collect()
+}
+
+#[test]
+fn icons_are_distinct_in_both_sizes() {
+    for size in [IconSize::Small, IconSize::Large].iter().copied() {
+        let drawn: Vec<_> = all().into_iter().map(|icon| pixels(icon, size)).collect();
+        for (i, icon) in drawn.iter().enumerate() {
+            assert!(icon.contains(&true), "{:?} is blank", all()[i]);
+            assert!(!drawn[..i].contains(icon), "{:?} is repeated", all()[i]);
+        }
+    }
+}
+
+#[test]
+fn levels_build_up_and_stop_at_the_highest() {
+    for size in [IconSize::Small, IconSize::Large].iter().copied() {
+        let count = |icon| pixels(icon, size).iter().filter(|lit| **lit).count();
+        for level in 0..Icon::MAX_WIFI_LEVEL {
+            assert!(count(Icon::Wifi(level)) < count(Icon::Wifi(level + 1)));
+        }
+        for level in 0..Icon::MAX_BATTERY_LEVEL {
+            assert!(count(Icon::Battery(level)) < count(Icon::Battery(level + 1)));
+        }
+        assert_eq!(pixels(Icon::Wifi(9), size), pixels(Icon::Wifi(3), size));
+        assert_eq!(
+            pixels(Icon::Battery(200), size),
+            pixels(Icon::Battery(4), size)
+        );
+    }
+}
+
+#[test]
+fn draws_icons_over_the_background() {
+    let mut frame = Framebuffer::new();
+    frame.fill_rect(0, 0, 20, 20, true);
+    frame.draw_icon(Icon::Stop, IconSize::Small, 2, 2);
+    // an unlit border around a lit square
+    assert!(frame.get_pixel(1, 1) && frame.get_pixel(10, 10));
+    assert!(!frame.get_pixel(2, 2) && !frame.get_pixel(9, 9));
+    assert!(frame.get_pixel(3, 3) && frame.get_pixel(8, 8));
+
+    let mut oled = Oled::new(NullInterface);
+    oled.draw_icon(Icon::Stop, IconSize::Large, 120, 56)
+        .unwrap();
+    let lit = |x: usize, y: usize| oled.framebuffer()[y / 8 * 128 + x] & 1 << (y % 8) != 0;
+    assert!(lit(123, 59) && lit(127, 63) && !lit(122, 59) && !lit(123, 58));
+}
@@ -0,0 +1,58 @@
+use nanohat_oled::{CaptureDevice, IdleManager, Oled};
+use std::time::Duration;
+
+fn secs(secs: u64) -> Duration {
+    Duration::from_secs(secs)
+}
+
+#[test]
+fn shows_the_screensaver_then_puts_the_frame_back() {
+    let mut device = CaptureDevice::new();
+    let mut oled = Oled::new(&mut device);
+    oled.init().unwrap();
+    oled.put_string("dashboard").unwrap();
+    let before = *oled.framebuffer();
+    let mut shown = Vec::new();
+    let mut idle = IdleManager::new(secs(60)).with_screensaver(|frame, time| {
+        shown.push(time.as_secs());
+        frame.clear();
+        frame.set_pixel(time.as_secs() as u16, 0, true);
+    });
+    idle.tick(&mut oled, secs(30)).unwrap();
+    assert!(!idle.touch(&mut oled).unwrap());
+    idle.tick(&mut oled, secs(50)).unwrap();
+    assert!(!idle.is_idle());
+    idle.tick(&mut oled, secs(10)).unwrap();
+    assert!(idle.is_idle());
+    idle.tick(&mut oled, secs(5)).unwrap();
+    assert_eq!(oled.framebuffer()[..6], [0, 0, 0, 0, 0, 1]);
+
+    assert!(idle.touch(&mut oled).unwrap());
+    assert!(!idle.is_idle());
+    assert_eq!(*oled.framebuffer(), before);
+    assert_eq!(shown, [0, 5]);
+    assert!(!oled.is_asleep() && device.ram() == &before);
+}
+
+#[test]
+fn without_a_screensaver_sleeps() {
+    let mut device = CaptureDevice::new();
+    let mut oled = Oled::new(&mut device);
+    oled.init().unwrap();
+    oled.put_string("dashboard").unwrap();
+    let mut idle = IdleManager::new(secs(60));
+    idle.tick(&mut oled, secs(60)).unwrap();
+    assert!(idle.is_idle() && oled.is_asleep());
+    idle.tick(&mut oled, secs(60)).unwrap();
+    assert!(idle.touch(&mut oled).unwrap());
+    assert!(!oled.is_asleep());
+    let commands = device.commands();
+    assert_eq!(
+        commands
+            .windows(3)
+            .filter(|c| c == &[0xae, 0x8d, 0x10])
+            .count(),
+        1
+    );
+    assert_eq!(commands[commands.len() - 3..], [0x8d, 0x14, 0xaf]);
+}
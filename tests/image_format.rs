@@ -0,0 +1,74 @@
+use nanohat_oled::{CaptureDevice, ImageFormat, Oled, OledError, Rotation};
+
+/// A grayscale test picture, in the encoding `format`, `width` by `height`
+fn picture(width: usize, height: usize, format: ImageFormat) -> Vec<u8> {
+    let lit = |x: usize, y: usize| (x * 3 + y * 5) % 7 < 3;
+    let mut image = vec![0u8; format.image_len(width as u16, height as u16)];
+    for y in 0..height {
+        for x in 0..width {
+            if !lit(x, y) {
+                continue;
+            }
+            match format {
+                ImageFormat::Gray(_) => image[y * width + x] = 0xff,
+                ImageFormat::RowMajor => image[y * width.div_ceil(8) + x / 8] |= 0x80 >> (x % 8),
+                ImageFormat::PageMajor => image[y / 8 * width + x] |= 1 << (y % 8),
+            }
+        }
+    }
+    image
+}
+
+/// What the display RAM holds after drawing the test picture as `format`
+fn drawn(rotation: Rotation, format: ImageFormat) -> Vec<u8> {
+    let mut device = CaptureDevice::new();
+    {
+        let mut oled = Oled::new(&mut device);
+        oled.init().unwrap();
+        oled.set_rotation(rotation).unwrap();
+        let (width, height) = oled.dimensions();
+        let image = picture(width as usize, height as usize, format);
+        oled.draw_image_as(&image, format).unwrap();
+    }
+    device.ram().to_vec()
+}
+
+#[test]
+fn packed_images_draw_as_grayscale_ones_do() {
+    for rotation in [Rotation::Rotate0, Rotation::Rotate90] {
+        let gray = drawn(rotation, ImageFormat::Gray(0x80));
+        assert!(gray.iter().any(|byte| *byte != 0));
+        assert_eq!(drawn(rotation, ImageFormat::RowMajor), gray);
+        assert_eq!(drawn(rotation, ImageFormat::PageMajor), gray);
+    }
+}
+
+#[test]
+fn page_major_images_are_sent_as_they_are() {
+    let image = picture(128, 64, ImageFormat::PageMajor);
+    let mut device = CaptureDevice::new();
+    {
+        let mut oled = Oled::new(&mut device);
+        oled.init().unwrap();
+        oled.draw_image_as(&image, ImageFormat::PageMajor).unwrap();
+        assert_eq!(&oled.framebuffer()[..], &image[..]);
+    }
+    assert_eq!(device.ram(), &image[..]);
+}
+
+#[test]
+fn images_must_fit_the_display() {
+    let mut device = CaptureDevice::new();
+    let mut oled = Oled::new(&mut device);
+    assert_eq!(ImageFormat::RowMajor.image_len(128, 64), 1024);
+    assert_eq!(ImageFormat::PageMajor.image_len(128, 64), 1024);
+    assert!(matches!(
+        oled.draw_image_as(&[0; 1023], ImageFormat::RowMajor),
+        Err(OledError::InvalidDimensions)
+    ));
+    oled.set_rotation(Rotation::Rotate90).unwrap();
+    assert_eq!(ImageFormat::RowMajor.image_len(64, 128), 1024);
+    assert!(oled
+        .draw_image_as(&[0; 1024], ImageFormat::RowMajor)
+        .is_ok());
+}
@@ -0,0 +1,91 @@
+use nanohat_oled::{
+    AddressingMode, Command, Instruction, MockInterface, Oled, OledError, MAX_INSTRUCTION_LEN,
+};
+
+fn encoded(instruction: Instruction) -> Result<Vec<u8>, OledError> {
+    let mut buffer = [0; MAX_INSTRUCTION_LEN];
+    instruction.encode(&mut buffer).map(|bytes| bytes.to_vec())
+}
+
+#[test]
+fn instructions_encode_with_their_arguments() {
+    let cases = [
+        (Command::DisplayOn.into(), vec![0xaf]),
+        (Instruction::SetContrast(0x20), vec![0x81, 0x20]),
+        (
+            Instruction::SetAddressingMode(AddressingMode::Page),
+            vec![0x20, 0x02],
+        ),
+        (
+            Instruction::SetColumnAddress(8, 119),
+            vec![0x21, 0x08, 0x77],
+        ),
+        (Instruction::SetPageAddress(2, 5), vec![0x22, 0x02, 0x05]),
+        (Instruction::SetColumnStart(0x5a), vec![0x0a, 0x15]),
+        (Instruction::SetPageStart(3), vec![0xb3]),
+        (Instruction::SetStartLine(17), vec![0x51]),
+        (Instruction::SetMultiplex(64), vec![0xa8, 0x3f]),
+        (Instruction::SetDisplayOffset(32), vec![0xd3, 0x20]),
+        (
+            Instruction::SetClock {
+                divide: 1,
+                frequency: 8,
+            },
+            vec![0xd5, 0x80],
+        ),
+        (
+            Instruction::SetComPins {
+                alternative: false,
+                left_right_remap: true,
+            },
+            vec![0xda, 0x22],
+        ),
+        (Instruction::SetPrecharge(1, 15), vec![0xd9, 0xf1]),
+        (Instruction::SetVcomhLevel(0x30), vec![0xdb, 0x30]),
+        (Instruction::ChargePump(true), vec![0x8d, 0x14]),
+        (Instruction::ChargePump(false), vec![0x8d, 0x10]),
+        (Instruction::DcDc(true), vec![0xad, 0x8b]),
+    ];
+    for (instruction, bytes) in cases.iter() {
+        assert_eq!(&encoded(*instruction).unwrap(), bytes, "{:?}", instruction);
+    }
+}
+
+#[test]
+fn arguments_out_of_range_are_rejected() {
+    let invalid = [
+        Instruction::SetColumnAddress(10, 9),
+        Instruction::SetColumnAddress(0, 128),
+        Instruction::SetPageAddress(0, 8),
+        Instruction::SetPageStart(8),
+        Instruction::SetStartLine(64),
+        Instruction::SetMultiplex(15),
+        Instruction::SetMultiplex(65),
+        Instruction::SetDisplayOffset(64),
+        Instruction::SetClock {
+            divide: 0,
+            frequency: 0,
+        },
+        Instruction::SetPrecharge(0, 2),
+        Instruction::SetVcomhLevel(0x08),
+    ];
+    for instruction in invalid.iter() {
+        assert!(
+            matches!(encoded(*instruction), Err(OledError::InvalidArgument(_))),
+            "{:?}",
+            instruction
+        );
+    }
+}
+
+#[test]
+fn send_instruction_sends_nothing_when_invalid() {
+    let mut bus = MockInterface::new();
+    let mut oled = Oled::new(&mut bus);
+    oled.send_instruction(Instruction::SetPageAddress(1, 3))
+        .unwrap();
+    assert!(oled
+        .send_instruction(Instruction::SetStartLine(70))
+        .is_err());
+    assert_eq!(bus.commands(), [0x22, 0x01, 0x03]);
+}
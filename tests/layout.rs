@@ -0,0 +1,45 @@
+use nanohat_oled::{Layout, Rect, Size};
+
+const SCREEN: Rect = Rect::new(0, 0, 128, 64);
+
+#[test]
+fn splits_rows_and_columns() {
+    let [bar, body, footer] =
+        Layout::rows([Size::Fixed(10), Size::Weight(1), Size::Fixed(8)]).split(SCREEN);
+    assert_eq!(bar, Rect::new(0, 0, 128, 10));
+    assert_eq!(body, Rect::new(0, 10, 128, 46));
+    assert_eq!(footer, Rect::new(0, 56, 128, 8));
+
+    let [left, right] = Layout::columns([Size::Weight(3), Size::Weight(1)])
+        .with_gap(4)
+        .split(body);
+    assert_eq!(left, Rect::new(0, 10, 93, 46));
+    assert_eq!(right, Rect::new(97, 10, 31, 46));
+}
+
+#[test]
+fn spreads_rounding_to_fill_the_area() {
+    let parts = Layout::columns([Size::Weight(1); 3]).split(Rect::new(0, 0, 128, 8));
+    let widths: Vec<u16> = parts.iter().map(|part| part.width).collect();
+    assert_eq!(widths, [42, 43, 43]);
+    assert_eq!(parts[2].x + parts[2].width, 128);
+    for pair in parts.windows(2) {
+        assert_eq!(pair[0].x + pair[0].width, pair[1].x);
+    }
+}
+
+#[test]
+fn gives_fixed_parts_what_fits_first() {
+    let [top, middle, bottom] =
+        Layout::rows([Size::Fixed(40), Size::Weight(1), Size::Fixed(40)]).split(SCREEN);
+    assert_eq!(top, Rect::new(0, 0, 128, 40));
+    assert_eq!(middle, Rect::new(0, 40, 128, 0));
+    assert_eq!(bottom, Rect::new(0, 40, 128, 24));
+
+    // gaps wider than the area leave nothing
+    let parts = Layout::columns([Size::Weight(1), Size::Fixed(4)])
+        .with_gap(200)
+        .split(SCREEN);
+    assert!(parts.iter().all(|part| part.width == 0));
+    assert!(parts.iter().all(|part| part.x <= 128));
+}
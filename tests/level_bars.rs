@@ -0,0 +1,76 @@
+use nanohat_oled::{Framebuffer, LevelBars, Rect, Widget};
+use std::time::Duration;
+
+mod common;
+use common::ms;
+
+/// Lit rows of column `x`, from `y0` up to `y1`
+fn lit(frame: &Framebuffer, x: u16, y0: u16, y1: u16) -> Vec<u16> {
+    (y0..y1).filter(|y| frame.get_pixel(x, *y)).collect()
+}
+
+#[test]
+fn draws_bars_side_by_side() {
+    let mut bars: LevelBars<3> = LevelBars::new(0.0, 10.0).unwrap().with_gap(2);
+    bars.set_levels(&[10.0, 5.0, 20.0, 7.0]);
+    assert_eq!(bars.levels(), &[10.0, 5.0, 10.0]);
+    let mut frame = Framebuffer::new();
+    frame.set_pixel(100, 0, true);
+    bars.render(&mut frame, Rect::new(0, 0, 32, 20));
+    assert!(!bars.needs_render());
+    // three bars 9 pixels wide, 2 apart, filling from the bottom
+    assert_eq!(lit(&frame, 0, 0, 20), (0..20).collect::<Vec<_>>());
+    assert_eq!(lit(&frame, 8, 0, 20).len(), 20);
+    assert!(lit(&frame, 9, 0, 20).is_empty());
+    assert_eq!(lit(&frame, 11, 0, 20), (10..20).collect::<Vec<_>>());
+    assert_eq!(lit(&frame, 22, 0, 20).len(), 20);
+    assert!(lit(&frame, 31, 0, 20).is_empty());
+    assert!(frame.get_pixel(100, 0));
+}
+
+#[test]
+fn bars_fall_back_and_peaks_hold() {
+    let mut bars: LevelBars<1> = LevelBars::new(0.0, 1.0)
+        .unwrap()
+        .with_decay(1.0)
+        .with_peak_hold(ms(500));
+    bars.set_level(0, 1.0);
+    bars.set_level(0, 0.2);
+    // quieter levels don't show straight away, but are fallen to
+    assert_eq!(bars.levels()[0], 1.0);
+    bars.tick(ms(250));
+    assert!((bars.levels()[0] - 0.75).abs() < 1e-3);
+    assert_eq!(bars.peaks()[0], 1.0);
+    bars.tick(ms(500));
+    assert!((bars.levels()[0] - 0.25).abs() < 1e-3);
+    // the peak held for half a second, then fell for a quarter
+    assert!((bars.peaks()[0] - 0.75).abs() < 1e-3);
+
+    let mut frame = Framebuffer::new();
+    bars.render(&mut frame, Rect::new(0, 0, 4, 20));
+    assert_eq!(lit(&frame, 0, 0, 20), [5, 15, 16, 17, 18, 19]);
+
+    // once settled, nothing changes
+    bars.tick(ms(5000));
+    bars.render(&mut frame, Rect::new(0, 0, 4, 20));
+    bars.tick(ms(40));
+    assert!(!bars.needs_render());
+    bars.set_level(0, 0.2);
+    assert!(!bars.needs_render());
+    bars.set_level(0, 0.3);
+    assert!(bars.needs_render());
+}
+
+#[test]
+fn peaks_can_be_left_off() {
+    let mut bars: LevelBars<1> = LevelBars::new(0.0, 1.0)
+        .unwrap()
+        .with_peak_hold(Duration::ZERO)
+        .with_decay(0.0);
+    bars.set_level(0, 1.0);
+    bars.set_level(0, 0.5);
+    let mut frame = Framebuffer::new();
+    bars.render(&mut frame, Rect::new(0, 0, 4, 20));
+    assert_eq!(lit(&frame, 0, 0, 20), (10..20).collect::<Vec<_>>());
+    assert!(LevelBars::<2>::new(1.0, 1.0).is_err());
+}
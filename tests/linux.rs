@@ -0,0 +1,12 @@
+#![cfg(feature = "linux")]
+use nanohat_oled::{Oled, OledError, OLED_ADDRESS};
+use std::fs::File;
+
+#[test]
+fn wraps_an_open_descriptor_without_touching_the_bus() {
+    let file = File::options().write(true).open("/dev/null").unwrap();
+    let mut oled = Oled::from_fd(file.into());
+    assert_eq!(oled.address(), OLED_ADDRESS);
+    // it isn't an I2C bus, so the first transaction fails
+    assert!(matches!(oled.send_command(0xaf), Err(OledError::I2c(_))));
+}
@@ -0,0 +1,120 @@
+#![cfg(feature = "std")]
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+use nanohat_oled::{LogTail, Oled, OledConfig, PanelSize, Rotation};
+
+mod common;
+use common::{rendered, NullInterface};
+
+fn log_path(test: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("nanohat-oled-{}-{}.log", test, std::process::id()))
+}
+
+fn append(path: &PathBuf, text: &str) {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .unwrap();
+    file.write_all(text.as_bytes()).unwrap();
+}
+
+#[test]
+fn follows_lines_as_they_are_added() {
+    let path = log_path("tail-follow");
+    let _ = fs::remove_file(&path);
+    let mut tail = LogTail::new(Oled::new(NullInterface), &path);
+    // nothing to show until the file is there
+    assert!(!tail.poll().unwrap());
+    append(&path, "Installing\r\npackage a very long name\nhalf");
+    assert!(tail.poll().unwrap());
+    let expected = ["Installing", "package a very l", "ong name"];
+    assert_eq!(*tail.oled().framebuffer(), rendered(&expected));
+    assert!(!tail.poll().unwrap());
+    append(&path, " done\n\tok\n");
+    assert!(tail.poll().unwrap());
+    assert_eq!(
+        tail.lines().collect::<Vec<_>>(),
+        ["Installing", "package a very long name", "half done", " ok"]
+    );
+    // a truncated log is read from the start again
+    fs::write(&path, "again\n").unwrap();
+    assert!(tail.poll().unwrap());
+    assert_eq!(tail.lines().last(), Some("again"));
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn keeps_the_last_lines_and_scrolls_back() {
+    let path = log_path("tail-scroll");
+    let lines: String = (0..12).map(|line| format!("line {}\n", line)).collect();
+    fs::write(&path, lines).unwrap();
+    let mut tail = LogTail::new(Oled::new(NullInterface), &path).with_lines(10);
+    tail.poll().unwrap();
+    assert_eq!(tail.lines().next(), Some("line 2"));
+    let latest = [
+        "line 4", "line 5", "line 6", "line 7", "line 8", "line 9", "line 10", "line 11",
+    ];
+    assert_eq!(*tail.oled().framebuffer(), rendered(&latest));
+
+    // only as far back as the lines kept
+    tail.scroll_back(5).unwrap();
+    assert_eq!(tail.scrollback_position(), 2);
+    let earliest = [
+        "line 2", "line 3", "line 4", "line 5", "line 6", "line 7", "line 8", "line 9",
+    ];
+    assert_eq!(*tail.oled().framebuffer(), rendered(&earliest));
+
+    // new lines don't move the screen while scrolled back
+    append(&path, "line 12\n");
+    tail.poll().unwrap();
+    assert_eq!(tail.scrollback_position(), 2);
+    let earlier = [
+        "line 3", "line 4", "line 5", "line 6", "line 7", "line 8", "line 9", "line 10",
+    ];
+    assert_eq!(*tail.oled().framebuffer(), rendered(&earlier));
+
+    tail.scroll_forward(10).unwrap();
+    assert_eq!(tail.scrollback_position(), 0);
+    assert_eq!(tail.lines().last(), Some("line 12"));
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn fits_a_smaller_panel() {
+    let path = log_path("tail-small");
+    fs::write(&path, "one\ntwo\nthree\nfour\nfive\n").unwrap();
+    let mut oled = Oled::new(NullInterface);
+    oled.init_with(OledConfig::DEFAULT.with_size(PanelSize::Panel96x16))
+        .unwrap();
+    let mut tail = LogTail::new(oled, &path);
+    assert!(tail.poll().unwrap());
+    fs::remove_file(&path).unwrap();
+    assert_eq!(*tail.oled().framebuffer(), rendered(&["four", "five"]));
+}
+
+#[test]
+fn fits_a_display_turned_on_its_side() {
+    let path = log_path("tail-portrait");
+    let lines: Vec<String> = (0..20).map(|line| format!("line {}", line)).collect();
+    fs::write(&path, format!("{}\n0123456789ABCDEF\n", lines.join("\n"))).unwrap();
+    let mut oled = Oled::new(NullInterface);
+    oled.set_rotation(Rotation::Rotate90).unwrap();
+    let mut tail = LogTail::new(oled, &path);
+    assert!(tail.poll().unwrap());
+    fs::remove_file(&path).unwrap();
+    // 16 rows of 8 columns, the last line wrapped onto two
+    let mut expected = Oled::new(NullInterface);
+    expected.set_rotation(Rotation::Rotate90).unwrap();
+    let rows = lines[6..]
+        .iter()
+        .map(String::as_str)
+        .chain(["01234567", "89ABCDEF"]);
+    for (row, text) in rows.enumerate() {
+        expected.set_text_xy(0, row as u8).unwrap();
+        expected.put_string(text).unwrap();
+    }
+    assert_eq!(tail.oled().framebuffer(), expected.framebuffer());
+}
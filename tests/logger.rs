@@ -0,0 +1,47 @@
+#![cfg(feature = "logger")]
+use log::{Level, LevelFilter, Log, Record};
+use nanohat_oled::{Oled, OledLogger};
+
+mod common;
+use common::{rendered, NullInterface};
+
+fn log(logger: &OledLogger<NullInterface>, level: Level, target: &str, message: &str) {
+    logger.log(
+        &Record::builder()
+            .level(level)
+            .target(target)
+            .args(format_args!("{}", message))
+            .build(),
+    );
+}
+
+#[test]
+fn shows_records_at_or_above_the_level() {
+    let logger = OledLogger::new(Oled::new(NullInterface)).with_level(LevelFilter::Warn);
+    log(&logger, Level::Error, "app", "disk full");
+    log(&logger, Level::Info, "app", "started");
+    log(
+        &logger,
+        Level::Warn,
+        "app::net",
+        "link down, retrying in 5s",
+    );
+    // the crate's own records would only be logged again by showing them
+    log(
+        &logger,
+        Level::Warn,
+        "nanohat_oled",
+        "retrying data transfer",
+    );
+    log(&logger, Level::Warn, "nanohat_oled_app", "battery low");
+    let mut console = logger.into_console();
+    assert_eq!(
+        *console.oled().framebuffer(),
+        rendered(&[
+            "E disk full",
+            "W link down, ret",
+            "rying in 5s",
+            "W battery low"
+        ])
+    );
+}
@@ -0,0 +1,59 @@
+use nanohat_oled::{Align, BuiltinFont, Marquee, Oled, TextRegion};
+
+mod common;
+use common::{ms, NullInterface};
+
+/// A full block, which lights its whole 8x8 cell in the monospace font
+const BLOCK: char = '\u{2588}';
+
+/// Columns lit in row `y` of the display
+fn shown(oled: &Oled<NullInterface>, y: u16) -> Vec<u16> {
+    let page = &oled.framebuffer()[(y / 8) as usize * 128..][..128];
+    (0..128)
+        .filter(|x| page[*x as usize] & (1 << (y % 8)) != 0)
+        .collect()
+}
+
+#[test]
+fn scrolls_text_through_the_region() {
+    let mut oled = Oled::new(NullInterface);
+    oled.draw(|frame| frame.set_pixel(20, 0, true)).unwrap();
+    let font = BuiltinFont::MONOSPACE;
+    // 24 pixels of text, then an 8 pixel gap, going round every 32 pixels
+    let text = format!("{}  ", BLOCK);
+    let mut marquee = Marquee::new(&text, &font)
+        .with_region(TextRegion::new(0, 0, 16, 8))
+        .with_speed(10)
+        .with_gap(8)
+        .with_loops(Some(1));
+    assert!(marquee.scrolls());
+    assert_eq!(marquee.tick(&mut oled, ms(0)).unwrap(), Some(ms(100)));
+    assert_eq!(shown(&oled, 0), [0, 1, 2, 3, 4, 5, 6, 7, 20]);
+    assert_eq!(marquee.tick(&mut oled, ms(250)).unwrap(), Some(ms(50)));
+    assert_eq!(shown(&oled, 0), [0, 1, 2, 3, 4, 5, 20]);
+    // the start comes round again after the gap
+    assert_eq!(marquee.tick(&mut oled, ms(2600)).unwrap(), Some(ms(50)));
+    assert_eq!(shown(&oled, 0), [4, 5, 6, 7, 8, 9, 10, 11, 20]);
+    // and once round is all it was asked for
+    assert_eq!(marquee.tick(&mut oled, ms(400)).unwrap(), None);
+    assert!(marquee.is_finished());
+    assert_eq!(shown(&oled, 0), [0, 1, 2, 3, 4, 5, 6, 7, 20]);
+
+    marquee.reset();
+    assert!(!marquee.is_finished());
+    assert_eq!(marquee.tick(&mut oled, ms(0)).unwrap(), Some(ms(100)));
+}
+
+#[test]
+fn holds_text_that_fits_still() {
+    let mut oled = Oled::new(NullInterface);
+    let font = BuiltinFont::MONOSPACE;
+    let text = BLOCK.to_string();
+    let region = TextRegion::new(0, 8, 16, 8).with_align(Align::Right);
+    let mut marquee = Marquee::new(&text, &font).with_region(region);
+    assert!(!marquee.scrolls());
+    assert_eq!(marquee.tick(&mut oled, ms(0)).unwrap(), None);
+    assert_eq!(shown(&oled, 8), [8, 9, 10, 11, 12, 13, 14, 15]);
+    assert_eq!(marquee.tick(&mut oled, ms(1000)).unwrap(), None);
+    assert_eq!(shown(&oled, 8), [8, 9, 10, 11, 12, 13, 14, 15]);
+}
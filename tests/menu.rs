@@ -0,0 +1,67 @@
+use nanohat_oled::{Framebuffer, Menu, Rect, Widget};
+
+/// Whether each of the first `rows` rows of 8 pixels is highlighted
+fn highlighted(frame: &Framebuffer, rows: u16) -> Vec<bool> {
+    (0..rows).map(|row| frame.get_pixel(127, row * 8)).collect()
+}
+
+#[test]
+fn moves_the_selection_round_the_items() {
+    let mut menu = Menu::new(&["Network", "Restart", "Shut down"]);
+    assert_eq!(menu.select(), Some(&"Network"));
+    menu.up();
+    assert_eq!(menu.select(), Some(&"Shut down"));
+    menu.down();
+    menu.down();
+    assert_eq!((menu.selected(), menu.select()), (1, Some(&"Restart")));
+    menu.set_selected(10);
+    assert_eq!(menu.selected(), 2);
+    assert!(menu.needs_render());
+
+    let mut empty = Menu::<&str>::new(&[]);
+    empty.down();
+    empty.up();
+    assert_eq!(empty.select(), None);
+}
+
+#[test]
+fn highlights_the_selected_item() {
+    let mut menu = Menu::new(&["One", "Two", "Three"]).with_selected(1);
+    let mut frame = Framebuffer::new();
+    menu.render(&mut frame, Rect::new(0, 0, 128, 64));
+    assert!(!menu.needs_render());
+    assert_eq!(highlighted(&frame, 4), [false, true, false, false]);
+    // "T" is lit in the first row of its cell, and unlit when highlighted
+    assert!(!frame.get_pixel(1, 8) && frame.get_pixel(1, 16));
+    assert!((0..128).all(|x| !frame.get_pixel(x, 24)));
+
+    menu.down();
+    assert!(menu.needs_render());
+    menu.down();
+    menu.up();
+    menu.render(&mut frame, Rect::new(0, 0, 128, 64));
+    assert_eq!(highlighted(&frame, 4), [false, false, true, false]);
+}
+
+#[test]
+fn pages_through_items_that_dont_fit() {
+    let items: Vec<String> = (0..10).map(|item| format!("Item {}", item)).collect();
+    let mut menu = Menu::new(&items).with_selected(7);
+    let mut frame = Framebuffer::new();
+    menu.render(&mut frame, Rect::new(0, 0, 128, 64));
+    assert!(highlighted(&frame, 8)[7]);
+
+    // the second page has the last two items
+    menu.down();
+    menu.render(&mut frame, Rect::new(0, 0, 128, 64));
+    assert_eq!(
+        highlighted(&frame, 8),
+        [true, false, false, false, false, false, false, false]
+    );
+    assert!((16..64).all(|y| (0..128).all(|x| !frame.get_pixel(x, y))));
+
+    // a smaller area has fewer rows to a page
+    let mut frame = Framebuffer::new();
+    menu.render(&mut frame, Rect::new(0, 0, 128, 24));
+    assert_eq!(highlighted(&frame, 3), [false, false, true]);
+}
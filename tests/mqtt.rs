@@ -0,0 +1,119 @@
+#![cfg(feature = "mqtt")]
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::thread;
+
+use nanohat_oled::{MqttDisplay, Oled, OledConfig, OledError, PanelSize};
+
+mod common;
+use common::{rendered_at, NullInterface};
+
+#[test]
+fn keeps_lines_on_a_smaller_panel() {
+    let mut oled = Oled::new(NullInterface);
+    let config = OledConfig::DEFAULT.with_size(PanelSize::Panel128x32);
+    oled.init_with(config).unwrap();
+    let mut display = MqttDisplay::new(oled);
+    display.handle_message("display/line/3", b"Last").unwrap();
+    assert!(matches!(
+        display.handle_message("display/line/4", b"Off"),
+        Err(OledError::InvalidArgument(_))
+    ));
+}
+
+fn display() -> MqttDisplay<NullInterface> {
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    MqttDisplay::new(oled)
+}
+
+/// Reads a packet from the client, returning its first byte and body
+fn read_packet(stream: &mut impl Read) -> (u8, Vec<u8>) {
+    let mut header = [0; 2];
+    stream.read_exact(&mut header).unwrap();
+    assert!(header[1] < 0x80, "test packets are short");
+    let mut body = vec![0; usize::from(header[1])];
+    stream.read_exact(&mut body).unwrap();
+    (header[0], body)
+}
+
+#[test]
+fn shows_messages_on_their_elements() {
+    let mut display = display().with_prefix("home/oled/");
+    display
+        .handle_message("home/oled/line/3", b"Door open")
+        .unwrap();
+    assert_eq!(
+        *display.oled().framebuffer(),
+        rendered_at(0, 3, "Door open")
+    );
+    // a shorter line replaces the whole row
+    display.handle_message("home/oled/line/3", b"Shut").unwrap();
+    assert_eq!(*display.oled().framebuffer(), rendered_at(0, 3, "Shut"));
+
+    display
+        .handle_message("home/oled/contrast", b"12\n")
+        .unwrap();
+    assert_eq!(display.oled().contrast(), 12);
+    let mut image = vec![0u8; 1024];
+    image[0] = 0x80;
+    display.handle_message("home/oled/image", &image).unwrap();
+    assert_eq!(display.oled().framebuffer()[..2], [0x01, 0x00]);
+    display.handle_message("home/oled/clear", b"").unwrap();
+    assert!(display.oled().framebuffer().iter().all(|byte| *byte == 0));
+
+    for topic in [
+        "home/oled/line/8",
+        "home/oled/line",
+        "home/oledx/clear",
+        "other/clear",
+    ] {
+        assert!(matches!(
+            display.handle_message(topic, b"x"),
+            Err(OledError::InvalidArgument(_))
+        ));
+    }
+}
+
+#[test]
+fn subscribes_and_shows_what_the_broker_sends() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let broker = listener.local_addr().unwrap();
+    let server = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let (kind, connect) = read_packet(&mut stream);
+        assert_eq!(kind, 0x10);
+        assert_eq!(connect[..7], *b"\x00\x04MQTT\x04");
+        assert!(connect.ends_with(b"\x00\x04pi-1"));
+        stream.write_all(&[0x20, 0x02, 0x00, 0x00]).unwrap();
+        let (kind, subscribe) = read_packet(&mut stream);
+        assert_eq!(kind, 0x82);
+        assert_eq!(subscribe, b"\x00\x01\x00\x09display/#\x00");
+        stream.write_all(&[0x90, 0x03, 0x00, 0x01, 0x00]).unwrap();
+        // one message the display can't show, then one at QoS 1
+        stream.write_all(b"\x30\x08\x00\x05otherx").unwrap();
+        stream
+            .write_all(b"\x32\x14\x00\x0edisplay/line/0\x00\x07Hi")
+            .unwrap();
+        let (kind, puback) = read_packet(&mut stream);
+        assert_eq!((kind, puback), (0x40, vec![0x00, 0x07]));
+    });
+    let mut display = display().with_client_id("pi-1");
+    display.run(broker).unwrap();
+    server.join().unwrap();
+    assert_eq!(*display.oled().framebuffer(), rendered_at(0, 0, "Hi"));
+}
+
+#[test]
+fn reports_a_refused_connection() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let broker = listener.local_addr().unwrap();
+    let server = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        read_packet(&mut stream);
+        // not authorized
+        stream.write_all(&[0x20, 0x02, 0x00, 0x05]).unwrap();
+    });
+    assert!(display().run(broker).is_err());
+    server.join().unwrap();
+}
@@ -0,0 +1,94 @@
+#![cfg(feature = "async")]
+use nanohat_oled::{
+    Controller, MockInterface, Offload, OledAsync, OledConfig, OledError, OledInterface,
+};
+use std::future::Future;
+use std::pin::pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake};
+use std::thread::{self, Thread};
+
+/// Wakes a task by unparking the thread running it
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Runs a future to completion on the current thread
+fn block_on<F: Future>(future: F) -> F::Output {
+    let waker = Arc::new(ThreadWaker(thread::current())).into();
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        thread::park();
+    }
+}
+
+/// A mock that can still be inspected after being moved to the worker thread
+#[derive(Clone, Default)]
+struct SharedMock(Arc<Mutex<MockInterface>>);
+
+impl OledInterface for SharedMock {
+    fn send_command(&mut self, command: u8) -> Result<(), OledError> {
+        self.0.lock().unwrap().send_command(command)
+    }
+
+    fn send_data(&mut self, data: &[u8]) -> Result<(), OledError> {
+        self.0.lock().unwrap().send_data(data)
+    }
+}
+
+/// An interface that panics on any data
+struct Panicking;
+
+impl OledInterface for Panicking {
+    fn send_command(&mut self, _command: u8) -> Result<(), OledError> {
+        Ok(())
+    }
+
+    fn send_data(&mut self, _data: &[u8]) -> Result<(), OledError> {
+        panic!("bus fell off");
+    }
+}
+
+#[test]
+fn sends_through_worker_thread_in_order() {
+    let mock = SharedMock::default();
+    let mut oled = OledAsync::new(Offload::new(mock.clone()));
+    block_on(async {
+        oled.set_text_xy(2, 1).await?;
+        oled.put_string("A").await
+    })
+    .unwrap();
+    let mock = mock.0.lock().unwrap();
+    assert_eq!(mock.commands(), [0xb1, 0x00, 0x11]);
+    assert_eq!(
+        mock.data(),
+        [0x00, 0x7E, 0x09, 0x09, 0x09, 0x7E, 0x00, 0x00]
+    );
+}
+
+#[test]
+fn reports_worker_failure_instead_of_hanging() {
+    let mut oled = OledAsync::new(Offload::new(Panicking));
+    let result = block_on(oled.put_char('A'));
+    assert!(matches!(result, Err(OledError::Interface)));
+    let result = block_on(oled.set_text_xy(0, 0));
+    assert!(matches!(result, Err(OledError::Interface)));
+}
+
+#[test]
+fn rejects_controllers_without_horizontal_addressing() {
+    let mock = SharedMock::default();
+    let mut oled = OledAsync::new(Offload::new(mock.clone()));
+    let config = OledConfig::DEFAULT.with_controller(Controller::Sh1106);
+    let result = block_on(oled.init_with(config));
+    assert!(matches!(result, Err(OledError::InvalidArgument(_))));
+    assert!(mock.0.lock().unwrap().sent().is_empty());
+}
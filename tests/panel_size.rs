@@ -0,0 +1,138 @@
+use nanohat_oled::{
+    Framebuffer, MockInterface, Oled, OledConfig, OledConsole, OledError, PanelSize, Rotation,
+    ScrollDirection, ScrollInterval, VerticalScrollArea,
+};
+
+fn init(bus: &mut MockInterface, size: PanelSize) -> Oled<&mut MockInterface> {
+    let mut oled = Oled::new(bus);
+    oled.init_with(OledConfig::DEFAULT.with_size(size)).unwrap();
+    oled
+}
+
+#[test]
+fn sets_up_the_multiplex_ratio_and_com_pins() {
+    for (size, multiplex, com_pins) in [
+        (PanelSize::Panel128x64, 0x3f, 0x12),
+        (PanelSize::Panel128x32, 0x1f, 0x02),
+        (PanelSize::Panel96x16, 0x0f, 0x02),
+    ] {
+        let mut bus = MockInterface::new();
+        let oled = init(&mut bus, size);
+        assert_eq!(oled.panel_size(), size);
+        assert_eq!(oled.dimensions(), (size.width(), size.height()));
+        let commands = bus.commands();
+        assert_eq!(commands[9..11], [0xa8, multiplex]);
+        assert_eq!(commands[18..20], [0xda, com_pins]);
+    }
+}
+
+#[test]
+fn keeps_text_and_images_on_the_panel() {
+    let mut bus = MockInterface::new();
+    let mut oled = init(&mut bus, PanelSize::Panel96x16);
+    assert_eq!(PanelSize::Panel96x16.text_cells(), (12, 2));
+    assert!(oled.set_text_xy(11, 1).is_ok());
+    assert!(matches!(
+        oled.set_text_xy(12, 0),
+        Err(OledError::OutOfBounds)
+    ));
+    assert!(matches!(
+        oled.set_text_xy(0, 2),
+        Err(OledError::OutOfBounds)
+    ));
+
+    assert!(matches!(
+        oled.draw_image(&[0xff; 128 * 64], 0x80),
+        Err(OledError::InvalidDimensions)
+    ));
+    oled.draw_image(&[0xff; 96 * 16], 0x80).unwrap();
+    let ram = oled.framebuffer();
+    assert!(ram[..96].iter().chain(&ram[128..224]).all(|&b| b == 0xff));
+    assert!(ram[96..128].iter().chain(&ram[224..]).all(|&b| b == 0));
+
+    oled.draw_fn(|x, y| x == 95 && y == 15).unwrap();
+    assert_eq!(oled.framebuffer()[128 + 95], 0x80);
+    assert!(matches!(
+        oled.set_rotation(Rotation::Rotate90),
+        Err(OledError::InvalidArgument(_))
+    ));
+}
+
+#[test]
+fn keeps_quarter_turns_to_128x64_panels() {
+    let mut oled = Oled::new(MockInterface::new());
+    oled.set_rotation(Rotation::Rotate270).unwrap();
+    let config = OledConfig::DEFAULT.with_size(PanelSize::Panel128x32);
+    assert!(matches!(
+        oled.init_with(config),
+        Err(OledError::InvalidArgument(_))
+    ));
+    assert_eq!(oled.rotation(), Rotation::Rotate270);
+    assert_eq!(oled.panel_size(), PanelSize::Panel128x64);
+    oled.set_rotation(Rotation::Rotate180).unwrap();
+    oled.init_with(config).unwrap();
+    assert_eq!(oled.dimensions(), (128, 32));
+}
+
+#[test]
+fn flushes_only_what_the_panel_shows() {
+    let mut bus = MockInterface::new();
+    let mut frame = Framebuffer::new();
+    frame.fill_rect(120, 24, 8, 40, true);
+    {
+        let mut oled = init(&mut bus, PanelSize::Panel128x32);
+        oled.flush(&frame).unwrap();
+        let ram = oled.framebuffer();
+        assert!(ram[3 * 128 + 120..4 * 128].iter().all(|&b| b == 0xff));
+        assert!(ram[4 * 128..].iter().all(|&b| b == 0));
+    }
+    let commands = bus.commands();
+    // a window of columns 120 to 127 on page 3 only
+    let window = commands.len() - 12;
+    assert_eq!(commands[window..window + 6], [0x21, 120, 127, 0x22, 3, 3]);
+
+    bus.clear();
+    let mut oled = init(&mut bus, PanelSize::Panel128x32);
+    let cleared = oled.framebuffer().len() * 8;
+    oled.flush_all(&frame).unwrap();
+    // after the bytes clearing the display on init, half of RAM
+    assert_eq!(bus.data().len(), cleared + 128 * 4);
+}
+
+#[test]
+fn fits_the_console_and_scrolling_to_the_panel() {
+    let mut bus = MockInterface::new();
+    let mut console = OledConsole::new(init(&mut bus, PanelSize::Panel128x32));
+    for line in 0..10 {
+        console.println(&format!("line {}", line)).unwrap();
+    }
+    let mut expected_bus = MockInterface::new();
+    let mut expected = init(&mut expected_bus, PanelSize::Panel128x32);
+    for row in 0..4 {
+        expected.set_text_xy(0, row).unwrap();
+        expected.put_string(&format!("line {}", row + 6)).unwrap();
+    }
+    assert_eq!(console.oled().framebuffer(), expected.framebuffer());
+
+    // the area can't be taller than the 32 rows driven
+    let oled = console.oled();
+    let mut scroll = |area| {
+        oled.start_vertical_horizontal_scroll(
+            area,
+            ScrollDirection::Right,
+            0,
+            3,
+            ScrollInterval::Frames5,
+            1,
+        )
+    };
+    assert!(matches!(
+        scroll(VerticalScrollArea::FULL_SCREEN),
+        Err(OledError::InvalidArgument(_))
+    ));
+    scroll(VerticalScrollArea {
+        fixed_rows: 8,
+        scroll_rows: 24,
+    })
+    .unwrap();
+}
@@ -0,0 +1,65 @@
+use nanohat_oled::{CaptureDevice, Framebuffer, Oled, Rotation};
+use std::time::Duration;
+
+fn mins(mins: u64) -> Duration {
+    Duration::from_secs(60 * mins)
+}
+
+#[test]
+fn moves_the_picture_a_row_at_a_time() {
+    let mut device = CaptureDevice::new();
+    let mut oled = Oled::new(&mut device);
+    oled.init().unwrap();
+    let mut frame = Framebuffer::new();
+    frame.set_pixel(10, 20, true);
+    oled.flush(&frame).unwrap();
+    oled.set_pixel_shift(Some(mins(3))).unwrap();
+    oled.tick_pixel_shift(mins(2)).unwrap();
+    assert_eq!(oled.pixel_shift(), 0);
+    device.clear();
+
+    let mut oled = Oled::new(&mut device);
+    oled.set_pixel_shift(Some(mins(3))).unwrap();
+    let mut shown = Vec::new();
+    for _ in 0..4 {
+        oled.tick_pixel_shift(mins(3)).unwrap();
+        shown.push(oled.pixel_shift());
+    }
+    assert_eq!(shown, [1, 0, -1, 0]);
+    assert_eq!(device.commands(), [0x40, 0x7f, 0x40, 0x41, 0x40]);
+    // drawing is unaffected, only what's shown moves
+    assert!(device.get_pixel(10, 20));
+
+    let mut oled = Oled::new(&mut device);
+    oled.set_pixel_shift(Some(mins(3))).unwrap();
+    oled.tick_pixel_shift(mins(3)).unwrap();
+    assert!(device.is_lit(10, 21) && !device.is_lit(10, 20));
+}
+
+#[test]
+fn moves_down_when_upside_down_too() {
+    let mut device = CaptureDevice::new();
+    let mut oled = Oled::new(&mut device);
+    oled.init().unwrap();
+    oled.set_rotation(Rotation::Rotate180).unwrap();
+    let mut frame = Framebuffer::new();
+    frame.set_pixel(10, 20, true);
+    oled.flush(&frame).unwrap();
+    oled.set_pixel_shift(Some(mins(3))).unwrap();
+    oled.tick_pixel_shift(mins(3)).unwrap();
+    // the panel is turned, so the picture's down is the panel's up
+    assert!(device.is_lit(117, 42) && !device.is_lit(117, 43));
+}
+
+#[test]
+fn stopping_puts_the_picture_back() {
+    let mut device = CaptureDevice::new();
+    let mut oled = Oled::new(&mut device);
+    oled.init().unwrap();
+    oled.set_pixel_shift(Some(mins(3))).unwrap();
+    oled.tick_pixel_shift(mins(3)).unwrap();
+    oled.set_pixel_shift(None).unwrap();
+    assert_eq!(oled.pixel_shift(), 0);
+    oled.tick_pixel_shift(mins(60)).unwrap();
+    assert_eq!(device.commands().last(), Some(&0x40));
+}
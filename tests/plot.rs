@@ -0,0 +1,115 @@
+use nanohat_oled::{BarChart, Framebuffer, LineChart, OledError, Rect, Scale, Sparkline, Widget};
+
+/// The lit row in each column from `x0` to `x1` of `frame`, if there is just one
+fn rows(frame: &Framebuffer, x0: u16, x1: u16) -> Vec<Option<u16>> {
+    (x0..x1)
+        .map(|x| {
+            let lit: Vec<_> = (0..64).filter(|y| frame.get_pixel(x, *y)).collect();
+            match lit[..] {
+                [y] => Some(y),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn sparklines_keep_a_rolling_window() {
+    let mut load = Sparkline::new(3).unwrap();
+    for value in 1..=5 {
+        load.push(value as f32);
+    }
+    assert_eq!(load.samples().collect::<Vec<_>>(), [3.0, 4.0, 5.0]);
+    assert!(load.needs_render());
+
+    // the latest at the right, scaled to fit, with the oldest at the bottom
+    let mut frame = Framebuffer::new();
+    load.render(&mut frame, Rect::new(0, 0, 10, 5));
+    assert!(!load.needs_render());
+    assert!(frame.get_pixel(7, 4) && frame.get_pixel(8, 2) && frame.get_pixel(9, 0));
+    assert!((0..7).all(|x| (0..5).all(|y| !frame.get_pixel(x, y))));
+
+    // fewer columns than samples shows the latest, fitted to those
+    let mut frame = Framebuffer::new();
+    load.render(&mut frame, Rect::new(0, 0, 2, 5));
+    assert!(frame.get_pixel(0, 4) && frame.get_pixel(1, 0) && !frame.get_pixel(2, 0));
+
+    load.clear();
+    assert_eq!(load.samples().count(), 0);
+    assert!(Sparkline::new(0).is_err());
+    assert!(Sparkline::new(129).is_err());
+}
+
+#[test]
+fn line_charts_stretch_across_their_area() {
+    let values = [0.0, 10.0, 5.0];
+    let mut chart = LineChart::new(&values).with_scale(Scale::fixed(0.0, 20.0).unwrap());
+    let mut frame = Framebuffer::new();
+    frame.set_pixel(100, 0, true);
+    chart.render(&mut frame, Rect::new(0, 0, 11, 21));
+    assert!(frame.get_pixel(100, 0));
+    assert!(frame.get_pixel(0, 20) && frame.get_pixel(5, 10) && frame.get_pixel(10, 15));
+    assert!((0..21).all(|y| !frame.get_pixel(11, y)));
+
+    // fitted to the values, inside axes
+    let mut chart = LineChart::new(&values).with_axes(true);
+    let mut frame = Framebuffer::new();
+    chart.render(&mut frame, Rect::new(0, 0, 13, 23));
+    assert!((0..23).all(|y| frame.get_pixel(0, y)));
+    assert!((0..13).all(|x| frame.get_pixel(x, 22)));
+    assert!(frame.get_pixel(2, 20) && frame.get_pixel(7, 0) && frame.get_pixel(12, 10));
+    assert!(!frame.get_pixel(1, 20) && !frame.get_pixel(2, 21));
+}
+
+#[test]
+fn gaps_and_flat_lines() {
+    let values = [3.0, 3.0, f32::NAN, 3.0];
+    let mut chart = LineChart::new(&values);
+    let mut frame = Framebuffer::new();
+    chart.render(&mut frame, Rect::new(0, 0, 4, 9));
+    assert_eq!(rows(&frame, 0, 4), [Some(4), Some(4), None, Some(4)]);
+    assert!((0..9).all(|y| !frame.get_pixel(2, y)));
+
+    assert!(matches!(
+        Scale::fixed(1.0, 0.0),
+        Err(OledError::InvalidArgument(_))
+    ));
+}
+
+#[test]
+fn bar_charts_grow_from_the_baseline() {
+    let values = [1.0, -1.0, 2.0];
+    let mut chart = BarChart::new(&values)
+        .with_scale(Scale::fixed(-2.0, 2.0).unwrap())
+        .with_bar_width(3)
+        .with_gap(2);
+    let mut frame = Framebuffer::new();
+    frame.set_pixel(100, 0, true);
+    chart.render(&mut frame, Rect::new(0, 0, 13, 9));
+    assert!(frame.get_pixel(100, 0));
+    // 0 is row 4, 1 is row 2, -1 row 6 and 2 row 0
+    let column = |x| {
+        (0..9)
+            .filter(|y| frame.get_pixel(x, *y))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(column(0), [2, 3, 4]);
+    assert_eq!(column(2), column(0));
+    assert!(column(3).is_empty() && column(4).is_empty());
+    assert_eq!(column(5), [4, 5, 6]);
+    // the last bar is cut off at the edge
+    assert_eq!(column(10), [0, 1, 2, 3, 4]);
+    assert_eq!(column(12), column(10));
+    assert!(!frame.get_pixel(13, 0));
+
+    // sharing the width, fitted to the values and the baseline of 0
+    let values = [1.0, 2.0];
+    let mut chart = BarChart::new(&values);
+    let mut frame = Framebuffer::new();
+    chart.render(&mut frame, Rect::new(0, 0, 9, 5));
+    let column = |x| (0..5).filter(|y| frame.get_pixel(x, *y)).count();
+    assert_eq!(
+        (0..9).map(column).collect::<Vec<_>>(),
+        [3, 3, 3, 3, 0, 5, 5, 5, 5]
+    );
+}
@@ -0,0 +1,72 @@
+#![cfg(feature = "png")]
+use nanohat_oled::{is_png, Framebuffer, Oled, OledError};
+
+mod common;
+use common::{lit, NullInterface};
+
+const CHECKER: &[u8] = include_bytes!("data/checker.png");
+
+/// The pixels of the checker image lit, drawn at `left`, `top`, clipped at the edges
+fn checker_at(left: u16, top: u16) -> Vec<(u16, u16)> {
+    (0..10u16)
+        .flat_map(|y| (0..40u16).map(move |x| (x, y)))
+        .filter(|(x, y)| (x / 3 + y / 2) % 2 == 0)
+        .map(|(x, y)| (x + left, y + top))
+        .filter(|(x, y)| *x < 128 && *y < 64)
+        .collect()
+}
+
+#[test]
+fn draws_compressed_filtered_gray_images_clipped() {
+    // every row of the checker uses a different filter
+    let mut frame = Framebuffer::new();
+    frame.draw_png(CHECKER, 2, 1).unwrap();
+    assert_eq!(lit(&frame), checker_at(2, 1));
+    let mut frame = Framebuffer::new();
+    frame.draw_png(CHECKER, 100, 60).unwrap();
+    assert_eq!(lit(&frame), checker_at(100, 60));
+
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    oled.draw_png(CHECKER, 0, 0).unwrap();
+    let mut expected = Framebuffer::new();
+    expected.draw_png(CHECKER, 0, 0).unwrap();
+    assert_eq!(oled.framebuffer(), expected.as_bytes());
+}
+
+#[test]
+fn takes_color_and_transparency_as_gray() {
+    // white, half-transparent white, red; green, black, light gray
+    let mut frame = Framebuffer::new();
+    frame
+        .draw_png(include_bytes!("data/rgba.png"), 0, 0)
+        .unwrap();
+    assert_eq!(lit(&frame), [(0, 0), (0, 1), (2, 1)]);
+    // 2-bit palette of black, white, transparent white and dark gray
+    let mut frame = Framebuffer::new();
+    frame
+        .draw_png(include_bytes!("data/palette.png"), 0, 0)
+        .unwrap();
+    assert_eq!(lit(&frame), [(0, 0), (4, 0), (5, 0), (9, 0)]);
+}
+
+#[test]
+fn rejects_what_it_cannot_draw() {
+    assert!(is_png(CHECKER));
+    assert!(!is_png(b"GIF89a") && !is_png(b"P4\n1 1\n\x80"));
+    let mut frame = Framebuffer::new();
+    assert!(matches!(
+        frame.draw_png(b"GIF89a", 0, 0),
+        Err(OledError::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        frame.draw_png(&CHECKER[..CHECKER.len() - 20], 0, 0),
+        Err(OledError::InvalidArgument(_))
+    ));
+    // damage the compressed pixels, just after the IDAT chunk's zlib header
+    let idat = CHECKER.windows(4).position(|kind| kind == b"IDAT").unwrap();
+    let mut damaged = CHECKER.to_vec();
+    damaged[idat + 6] ^= 0xff;
+    assert!(frame.draw_png(&damaged, 0, 0).is_err());
+    assert!(lit(&frame).is_empty());
+}
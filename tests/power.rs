@@ -0,0 +1,83 @@
+use nanohat_oled::{CaptureDevice, Oled};
+use std::time::Duration;
+
+fn secs(secs: u64) -> Duration {
+    Duration::from_secs(secs)
+}
+
+#[test]
+fn sleeps_and_wakes_with_the_charge_pump() {
+    let mut device = CaptureDevice::new();
+    let mut oled = Oled::new(&mut device);
+    oled.init().unwrap();
+    oled.put_string("zz").unwrap();
+    assert!(!oled.is_asleep());
+    device.clear();
+
+    let mut oled = Oled::new(&mut device);
+    oled.sleep().unwrap();
+    assert!(oled.is_asleep());
+    oled.wake().unwrap();
+    assert!(!oled.is_asleep());
+    assert_eq!(device.commands(), [0xae, 0x8d, 0x10, 0x8d, 0x14, 0xaf]);
+    // what was shown is still there
+    assert!(device.ram().iter().any(|&b| b != 0));
+}
+
+#[test]
+fn turns_off_after_the_timeout_and_wakes_on_draw() {
+    let mut device = CaptureDevice::new();
+    let mut oled = Oled::new(&mut device);
+    oled.init().unwrap();
+    oled.set_auto_off(Some(secs(30)));
+    oled.tick_auto_off(secs(20)).unwrap();
+    assert!(!oled.is_asleep());
+    // drawing while awake isn't activity
+    oled.put_string("a").unwrap();
+    oled.tick_auto_off(secs(10)).unwrap();
+    assert!(oled.is_asleep());
+    oled.tick_auto_off(secs(10)).unwrap();
+
+    oled.put_string("b").unwrap();
+    assert!(!oled.is_asleep());
+    let commands = device.commands();
+    assert!(commands.windows(3).any(|c| c == [0x8d, 0x14, 0xaf]));
+    assert_eq!(
+        commands.windows(2).filter(|c| c == &[0x8d, 0x10]).count(),
+        1
+    );
+}
+
+#[test]
+fn touching_starts_the_timeout_again() {
+    let mut device = CaptureDevice::new();
+    let mut oled = Oled::new(&mut device);
+    oled.init().unwrap();
+    oled.set_auto_off(Some(secs(30)));
+    oled.tick_auto_off(secs(20)).unwrap();
+    assert!(!oled.touch().unwrap());
+    oled.tick_auto_off(secs(20)).unwrap();
+    assert!(!oled.is_asleep());
+    oled.tick_auto_off(secs(10)).unwrap();
+    assert!(oled.is_asleep());
+    assert!(oled.touch().unwrap());
+    assert!(!oled.is_asleep());
+
+    oled.set_auto_off(None);
+    oled.tick_auto_off(secs(3600)).unwrap();
+    assert!(!oled.is_asleep());
+}
+
+#[test]
+fn put_to_sleep_by_hand_stays_asleep_when_drawn_to() {
+    let mut device = CaptureDevice::new();
+    let mut oled = Oled::new(&mut device);
+    oled.init().unwrap();
+    oled.set_auto_off(Some(secs(30)));
+    oled.sleep().unwrap();
+    oled.put_string("next").unwrap();
+    assert!(!oled.touch().unwrap());
+    assert!(oled.is_asleep());
+    assert!(!device.is_lit(1, 1));
+    assert!(device.ram().iter().any(|&b| b != 0));
+}
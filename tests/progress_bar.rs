@@ -0,0 +1,80 @@
+use nanohat_oled::{
+    Framebuffer, MockInterface, Oled, OledError, ProgressBar, Rect, Rotation, Widget,
+};
+
+/// Lit pixels in row `y` between `x0` and `x1`
+fn lit(frame: &Framebuffer, y: u16, x0: u16, x1: u16) -> Vec<u16> {
+    (x0..x1).filter(|x| frame.get_pixel(*x, y)).collect()
+}
+
+#[test]
+fn fills_in_proportion_to_the_value() {
+    let mut bar = ProgressBar::new(0.0, 200.0).unwrap();
+    assert!(bar.needs_render());
+    bar.set_value(50.0);
+    assert_eq!(bar.percentage(), 25);
+    let mut frame = Framebuffer::new();
+    frame.set_pixel(120, 5, true);
+    bar.render(&mut frame, Rect::new(0, 0, 100, 12));
+    assert!(!bar.needs_render());
+    // the outline, then a space, then a quarter of the 96 pixels inside
+    assert_eq!(lit(&frame, 0, 0, 128).len(), 100);
+    assert_eq!(
+        lit(&frame, 5, 0, 128),
+        std::iter::once(0)
+            .chain(2..26)
+            .chain(vec![99, 120])
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(lit(&frame, 1, 0, 100), [0, 99]);
+
+    bar.set_value(50.0);
+    assert!(!bar.needs_render());
+    bar.set_value(1000.0);
+    assert_eq!((bar.value(), bar.percentage()), (200.0, 100));
+    bar.set_value(f32::NAN);
+    assert_eq!(bar.value(), 0.0);
+    assert!(bar.needs_render());
+}
+
+#[test]
+fn labels_show_over_the_fill() {
+    let area = Rect::new(0, 0, 128, 12);
+    let inside = |frame: &Framebuffer| lit(frame, 5, 2, 126).len();
+    let mut bar = ProgressBar::new(0.0, 1.0).unwrap().with_label(true);
+    let mut frame = Framebuffer::new();
+    bar.render(&mut frame, area);
+    let label = inside(&frame);
+    // "0%", in the middle of an empty bar
+    assert!(label > 0);
+    assert!(lit(&frame, 5, 2, 126).iter().all(|x| (58..70).contains(x)));
+
+    bar.set_value(1.0);
+    bar.render(&mut frame, area);
+    // "100%" unlit in a full bar
+    assert!(inside(&frame) < 124);
+}
+
+#[test]
+fn rejects_empty_ranges() {
+    assert!(matches!(
+        ProgressBar::new(1.0, 1.0),
+        Err(OledError::InvalidArgument(_))
+    ));
+    assert!(ProgressBar::new(0.0, f32::NAN).is_err());
+}
+
+#[test]
+fn renders_straight_to_pages() {
+    let mut oled = Oled::new(MockInterface::new());
+    let mut bar = ProgressBar::new(0.0, 1.0).unwrap();
+    bar.set_value(1.0);
+    oled.render_to_pages(&mut bar, 6, 7).unwrap();
+    assert_eq!(oled.into_interface().data().len(), 256);
+
+    let mut oled = Oled::new(MockInterface::new());
+    assert!(oled.render_to_pages(&mut bar, 7, 6).is_err());
+    assert!(oled.render_to_pages(&mut bar, 7, 8).is_err());
+    oled.set_rotation(Rotation::Rotate90).unwrap();
+    assert!(oled.render_to_pages(&mut bar, 0, 1).is_err());
+}
@@ -0,0 +1,281 @@
+//! Checks the exact bytes sent over the bus for the basic display operations
+use nanohat_oled::{
+    MockInterface, Oled, OledError, OledInterface, Sent, TextStyle, MAX_CUSTOM_GLYPHS, OLED_HEIGHT,
+    OLED_WIDTH,
+};
+
+/// The documented setup sequence, per the SSD1306 and NanoHat OLED datasheets
+const INIT_COMMANDS: [u8; 25] = [
+    0xae, // display off
+    0x00, 0x10, // column address 0
+    0x40, // start line 0
+    0xb0, // page 0
+    0x81, 0x7f, // contrast
+    0xa1, // segment remap
+    0xa6, // normal display
+    0xa8, 0x3f, // multiplex ratio 1/64
+    0xc8, // remapped COM scan direction
+    0xd3, 0x00, // no display offset
+    0xd5, 0x80, // clock divide ratio
+    0xd9, 0xf1, // pre-charge period
+    0xda, 0x12, // COM pins
+    0xdb, 0x40, // VCOMH deselect level
+    0x8d, 0x14, // charge pump enabled
+    0xaf, // display on
+];
+
+#[test]
+fn init_sends_documented_sequence() {
+    let mut bus = MockInterface::new();
+    Oled::new(&mut bus).init().unwrap();
+    let mut expected: Vec<Sent> = INIT_COMMANDS.iter().map(|b| Sent::Command(*b)).collect();
+    // horizontal addressing mode
+    expected.extend([Sent::Command(0x20), Sent::Command(0x00)]);
+    // clear: display off, cursor home, blank RAM, display on
+    expected.extend([0xae, 0xb0, 0x00, 0x10].iter().map(|b| Sent::Command(*b)));
+    expected.extend(std::iter::repeat_n(
+        Sent::Data(0),
+        (OLED_WIDTH * OLED_HEIGHT) as usize,
+    ));
+    expected.push(Sent::Command(0xaf));
+    assert_eq!(bus.sent(), expected);
+}
+
+/// Records each single command, and each batch of commands, as one transaction
+#[derive(Default)]
+struct Transactions(Vec<Vec<u8>>);
+
+impl OledInterface for Transactions {
+    fn send_command(&mut self, command: u8) -> Result<(), OledError> {
+        self.0.push(vec![command]);
+        Ok(())
+    }
+
+    fn send_data(&mut self, _data: &[u8]) -> Result<(), OledError> {
+        Ok(())
+    }
+
+    fn send_commands(&mut self, commands: &[u8]) -> Result<(), OledError> {
+        self.0.push(commands.to_vec());
+        Ok(())
+    }
+}
+
+#[test]
+fn setup_and_cursor_moves_are_batched() {
+    let mut bus = Transactions::default();
+    let mut oled = Oled::new(&mut bus);
+    oled.init().unwrap();
+    oled.set_text_xy(2, 1).unwrap();
+    let transactions = &bus.0;
+    assert_eq!(transactions[0], INIT_COMMANDS);
+    assert_eq!(transactions.last().unwrap(), &[0xb1, 0x00, 0x11]);
+    // setup, addressing mode, and clearing's display off, cursor and display on
+    assert_eq!(transactions.len(), 6);
+}
+
+#[test]
+fn set_text_xy_addresses_page_and_column() {
+    let mut bus = MockInterface::new();
+    Oled::new(&mut bus).set_text_xy(2, 1).unwrap();
+    assert_eq!(bus.commands(), [0xb1, 0x00, 0x11]);
+
+    let mut bus = MockInterface::new();
+    Oled::new(&mut bus).set_text_xy(5, 7).unwrap();
+    assert_eq!(bus.commands(), [0xb7, 0x08, 0x12]);
+}
+
+#[test]
+fn set_position_addresses_any_pixel_column() {
+    let mut bus = MockInterface::new();
+    let mut oled = Oled::new(&mut bus);
+    oled.set_position(37, 3).unwrap();
+    oled.set_column_px(127).unwrap();
+    assert_eq!(bus.commands(), [0xb3, 0x05, 0x12, 0xb3, 0x0f, 0x17]);
+
+    let mut bus = MockInterface::new();
+    let mut oled = Oled::new(&mut bus);
+    for (x, page) in [(128, 0), (0, 8)] {
+        let result = oled.set_position(x, page);
+        assert!(matches!(result, Err(OledError::OutOfBounds)));
+    }
+    assert!(bus.sent().is_empty());
+}
+
+#[test]
+fn set_text_xy_rejects_cells_off_screen_without_sending() {
+    let mut bus = MockInterface::new();
+    let mut oled = Oled::new(&mut bus);
+    for (column, row) in [(16, 0), (0, 8)] {
+        let result = oled.set_text_xy(column, row);
+        assert!(matches!(result, Err(OledError::OutOfBounds)));
+    }
+    assert!(bus.sent().is_empty());
+}
+
+#[test]
+fn put_char_sends_font_bitmap() {
+    let mut bus = MockInterface::new();
+    Oled::new(&mut bus).put_char('A').unwrap();
+    assert_eq!(bus.data(), [0x00, 0x7E, 0x09, 0x09, 0x09, 0x7E, 0x00, 0x00]);
+    assert!(bus.commands().is_empty());
+}
+
+#[test]
+fn put_char_sends_box_for_unknown_glyph() {
+    let mut bus = MockInterface::new();
+    Oled::new(&mut bus).put_char('\u{2603}').unwrap();
+    assert_eq!(bus.data(), [0xff, 0x81, 0x81, 0x81, 0x81, 0x81, 0x81, 0xff]);
+}
+
+#[test]
+fn put_char_covers_code_page_437() {
+    let mut bus = MockInterface::new();
+    Oled::new(&mut bus)
+        .put_string("\u{b0}\u{2500}\u{2588}")
+        .unwrap();
+    assert_eq!(
+        bus.data(),
+        [
+            0x00, 0x02, 0x05, 0x05, 0x02, 0x00, 0x00, 0x00, // degree sign
+            0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, // box drawing horizontal
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, // full block
+        ]
+    );
+    // all of code page 437 past ASCII, as decoded to Unicode, has a bitmap of its own
+    let high = "\u{c7}\u{fc}\u{e9}\u{e2}\u{e4}\u{e0}\u{e5}\u{e7}\u{ea}\u{eb}\u{e8}\u{ef}\u{ee}\u{ec}\u{c4}\u{c5}\
+        \u{c9}\u{e6}\u{c6}\u{f4}\u{f6}\u{f2}\u{fb}\u{f9}\u{ff}\u{d6}\u{dc}\u{a2}\u{a3}\u{a5}\u{20a7}\u{192}\
+        \u{e1}\u{ed}\u{f3}\u{fa}\u{f1}\u{d1}\u{aa}\u{ba}\u{bf}\u{2310}\u{ac}\u{bd}\u{bc}\u{a1}\u{ab}\u{bb}\
+        \u{2591}\u{2592}\u{2593}\u{2502}\u{2524}\u{2561}\u{2562}\u{2556}\u{2555}\u{2563}\u{2551}\u{2557}\u{255d}\u{255c}\u{255b}\u{2510}\
+        \u{2514}\u{2534}\u{252c}\u{251c}\u{2500}\u{253c}\u{255e}\u{255f}\u{255a}\u{2554}\u{2569}\u{2566}\u{2560}\u{2550}\u{256c}\u{2567}\
+        \u{2568}\u{2564}\u{2565}\u{2559}\u{2558}\u{2552}\u{2553}\u{256b}\u{256a}\u{2518}\u{250c}\u{2588}\u{2584}\u{258c}\u{2590}\u{2580}\
+        \u{3b1}\u{df}\u{393}\u{3c0}\u{3a3}\u{3c3}\u{b5}\u{3c4}\u{3a6}\u{398}\u{3a9}\u{3b4}\u{221e}\u{3c6}\u{3b5}\u{2229}\
+        \u{2261}\u{b1}\u{2265}\u{2264}\u{2320}\u{2321}\u{f7}\u{2248}\u{b0}\u{2219}\u{b7}\u{221a}\u{207f}\u{b2}\u{25a0}\u{a0}";
+    assert_eq!(high.chars().count(), 128);
+    Oled::new(&mut bus).try_put_string(high).unwrap();
+}
+
+#[test]
+fn try_put_string_rejects_unknown_glyph_without_sending() {
+    let mut bus = MockInterface::new();
+    let result = Oled::new(&mut bus).try_put_string("20\u{2103}");
+    assert!(matches!(
+        result,
+        Err(OledError::UnsupportedGlyph('\u{2103}'))
+    ));
+    assert!(bus.sent().is_empty());
+
+    Oled::new(&mut bus).try_put_string("20\u{02da}").unwrap();
+    assert_eq!(bus.data().len(), 24);
+}
+
+#[test]
+fn registered_glyphs_are_drawn_with_the_built_in_font() {
+    const BATTERY: [u8; 8] = [0x00, 0x3e, 0x3e, 0x3e, 0x22, 0x22, 0x3e, 0x1c];
+    let mut bus = MockInterface::new();
+    let mut oled = Oled::new(&mut bus);
+    oled.register_glyph('\u{e000}', [0xff; 8]).unwrap();
+    oled.register_glyph('\u{e000}', BATTERY).unwrap();
+    // registered glyphs replace the built-in ones too
+    oled.register_glyph('!', [0x01; 8]).unwrap();
+    oled.try_put_string("\u{e000}!").unwrap();
+    assert!(oled.unregister_glyph('!'));
+    assert!(!oled.unregister_glyph('!'));
+    oled.put_char('!').unwrap();
+    assert!(oled.unregister_glyph('\u{e000}'));
+    assert!(matches!(
+        oled.try_put_string("\u{e000}"),
+        Err(OledError::UnsupportedGlyph('\u{e000}'))
+    ));
+    assert_eq!(
+        bus.data(),
+        [
+            0x00, 0x3e, 0x3e, 0x3e, 0x22, 0x22, 0x3e, 0x1c, // battery
+            0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, // registered !
+            0x00, 0x00, 0x5F, 0x00, 0x00, 0x00, 0x00, 0x00, // built-in !
+        ]
+    );
+}
+
+#[test]
+fn register_glyph_rejects_more_than_the_maximum() {
+    let mut oled = Oled::new(MockInterface::new());
+    let chars = (0xe000..).filter_map(char::from_u32);
+    for char in chars.clone().take(MAX_CUSTOM_GLYPHS) {
+        oled.register_glyph(char, [0; 8]).unwrap();
+    }
+    let extra = chars.clone().nth(MAX_CUSTOM_GLYPHS).unwrap();
+    assert!(matches!(
+        oled.register_glyph(extra, [0; 8]),
+        Err(OledError::InvalidArgument(_))
+    ));
+    // registered characters can still be replaced, and removing one makes room
+    oled.register_glyph('\u{e000}', [1; 8]).unwrap();
+    assert!(oled.unregister_glyph('\u{e001}'));
+    oled.register_glyph(extra, [0; 8]).unwrap();
+}
+
+#[test]
+fn text_style_inverts_glyphs_until_reset() {
+    let mut bus = MockInterface::new();
+    let mut oled = Oled::new(&mut bus);
+    oled.set_text_style(TextStyle::Inverted);
+    assert_eq!(oled.text_style(), TextStyle::Inverted);
+    oled.put_string("!").unwrap();
+    // put_char_inverted always inverts, rather than flipping the style back
+    oled.put_char_inverted('!').unwrap();
+    oled.set_text_style(TextStyle::Normal);
+    oled.put_char('!').unwrap();
+    assert_eq!(
+        bus.data(),
+        [
+            0xff, 0xff, 0xa0, 0xff, 0xff, 0xff, 0xff, 0xff, // inverted !
+            0xff, 0xff, 0xa0, 0xff, 0xff, 0xff, 0xff, 0xff, // inverted !
+            0x00, 0x00, 0x5F, 0x00, 0x00, 0x00, 0x00, 0x00, // !
+        ]
+    );
+}
+
+#[test]
+fn put_string_sends_glyphs_in_order() {
+    let mut bus = MockInterface::new();
+    Oled::new(&mut bus).put_string("!.").unwrap();
+    assert_eq!(
+        bus.data(),
+        [
+            0x00, 0x00, 0x5F, 0x00, 0x00, 0x00, 0x00, 0x00, // !
+            0x00, 0x60, 0x60, 0x00, 0x00, 0x00, 0x00, 0x00, // .
+        ]
+    );
+}
+
+#[test]
+fn draw_image_packs_rows_into_pages() {
+    let mut image = vec![0u8; (OLED_WIDTH * OLED_HEIGHT) as usize];
+    // top left pixel, pixel at row 9 of column 3, and bottom right pixel
+    image[0] = 0xff;
+    image[9 * OLED_WIDTH as usize + 3] = 0x80;
+    let last = image.len() - 1;
+    image[last] = 0x80;
+    // below the threshold, so stays dark
+    image[1] = 0x7f;
+
+    let mut bus = MockInterface::new();
+    Oled::new(&mut bus).draw_image(&image, 0x80).unwrap();
+    assert_eq!(bus.commands(), [0xb0, 0x00, 0x10]);
+    let data = bus.data();
+    assert_eq!(data.len(), 1024);
+    assert_eq!(data[0], 0x01);
+    assert_eq!(data[1], 0x00);
+    assert_eq!(data[OLED_WIDTH as usize + 3], 0x02);
+    assert_eq!(data[1023], 0x80);
+    assert_eq!(data.iter().filter(|byte| **byte != 0).count(), 3);
+}
+
+#[test]
+fn draw_image_rejects_wrong_size_without_sending() {
+    let mut bus = MockInterface::new();
+    let result = Oled::new(&mut bus).draw_image(&[0u8; 100], 0x80);
+    assert!(matches!(result, Err(OledError::InvalidDimensions)));
+    assert!(bus.sent().is_empty());
+}
@@ -0,0 +1,95 @@
+use nanohat_oled::{BitmapFont, Font, Framebuffer};
+
+/// A 4x2 glyph bitmap with only the pixel at `x`, `y` lit
+fn dot(x: u8, y: usize) -> [u8; 2] {
+    let mut rows = [0; 2];
+    rows[y] = 0x80 >> x;
+    rows
+}
+
+/// A PSF2 font of 10x2 glyphs, each with one pixel lit, followed by `table`
+fn psf2(count: u32, flags: u32, table: &[u8]) -> Vec<u8> {
+    let mut data = vec![0x72, 0xb5, 0x4a, 0x86];
+    // version, header size, flags, glyph count, glyph size, height, width
+    for field in [0, 32, flags, count, 4, 2, 10] {
+        data.extend_from_slice(&u32::to_le_bytes(field));
+    }
+    for index in 0..count {
+        data.extend_from_slice(&[0x80 >> index, 0, 0, 0x40]);
+    }
+    data.extend_from_slice(table);
+    data
+}
+
+#[test]
+fn maps_psf2_glyphs_through_their_unicode_table() {
+    // glyph 0 is both 'A' and the Greek capital alpha, glyph 1 the degree sign and,
+    // as a sequence with a combining ring, an 'o' it is never drawn for
+    let mut table = "A\u{391}".as_bytes().to_vec();
+    table.push(0xff);
+    table.extend_from_slice("\u{b0}".as_bytes());
+    table.push(0xfe);
+    table.extend_from_slice("o\u{30a}".as_bytes());
+    table.push(0xff);
+    let font = BitmapFont::from_psf_bytes(&psf2(2, 1, &table)).unwrap();
+    assert_eq!(font.line_height(), 2);
+    assert_eq!(font.chars().collect::<Vec<_>>(), ['A', '\u{b0}', '\u{391}']);
+    let degree = font.glyph('\u{b0}').unwrap();
+    assert_eq!(degree.dimensions(), (10, 2));
+    assert_eq!(degree.advance(), 10);
+    assert!(degree.get_pixel(1, 0) && degree.get_pixel(9, 1) && !degree.get_pixel(0, 0));
+    assert_eq!(font.glyph('A'), font.glyph('\u{391}'));
+    assert!(font.glyph('o').is_none());
+
+    let mut frame = Framebuffer::new();
+    assert_eq!(frame.draw_text(0, 4, "A\u{b0}", &font), 20);
+    let lit: Vec<_> = (0..20)
+        .flat_map(|x| (0..8).map(move |y| (x, y)))
+        .filter(|(x, y)| frame.get_pixel(*x, *y))
+        .collect();
+    assert_eq!(lit, [(0, 4), (9, 5), (11, 4), (19, 5)]);
+}
+
+#[test]
+fn numbers_psf_glyphs_without_a_table() {
+    let font = BitmapFont::from_psf_bytes(&psf2(3, 0, &[])).unwrap();
+    assert_eq!(font.chars().collect::<Vec<_>>(), ['\0', '\u{1}', '\u{2}']);
+    // glyphs past the end of a truncated table aren't drawn for anything
+    let font = BitmapFont::from_psf_bytes(&psf2(3, 1, b"A\xff")).unwrap();
+    assert_eq!(font.chars().collect::<Vec<_>>(), ['A']);
+}
+
+#[test]
+fn loads_psf1_fonts() {
+    // 256 glyphs, 2 rows high, with a Unicode table
+    let mut data = vec![0x36, 0x04, 0x02, 2];
+    for index in 0..256 {
+        data.extend_from_slice(&dot((index % 8) as u8, index / 128));
+    }
+    for index in 0..256u16 {
+        // every glyph is drawn for its own code point, and 'x' too for the first
+        data.extend_from_slice(&index.to_le_bytes());
+        if index == 0 {
+            data.extend_from_slice(&[b'x', 0, 0xfe, 0xff, b'y', 0]);
+        }
+        data.extend_from_slice(&[0xff, 0xff]);
+    }
+    let font = BitmapFont::from_psf_bytes(&data).unwrap();
+    assert_eq!(font.len(), 256);
+    assert_eq!(font.glyph('x'), font.glyph('\0'));
+    let glyph = font.glyph('\u{83}').unwrap();
+    assert_eq!(glyph.dimensions(), (8, 2));
+    assert!(glyph.get_pixel(3, 1) && !glyph.get_pixel(3, 0));
+    // 'y' only appears in a sequence, so 'y' keeps its own glyph
+    assert_ne!(font.glyph('y'), font.glyph('\0'));
+}
+
+#[test]
+fn rejects_bad_psf_files() {
+    assert!(BitmapFont::from_psf_bytes(b"not a font").is_err());
+    let font = psf2(2, 0, &[]);
+    assert!(BitmapFont::from_psf_bytes(&font[..font.len() - 1]).is_err());
+    let mut wrong_size = psf2(2, 0, &[]);
+    wrong_size[20] = 3;
+    assert!(BitmapFont::from_psf_bytes(&wrong_size).is_err());
+}
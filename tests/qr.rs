@@ -0,0 +1,94 @@
+#![cfg(feature = "qr")]
+use nanohat_oled::{Align, Framebuffer, Oled, OledError, QrCode, QrEcc, Rotation, QR_QUIET_ZONE};
+
+mod common;
+use common::NullInterface;
+
+#[test]
+fn encodes_in_the_smallest_version_that_fits() {
+    // checked against an independent decoder
+    let code = QrCode::new(b"HELLO WORLD", QrEcc::Medium).unwrap();
+    let expected = [
+        "111111101100101111111",
+        "100000100001001000001",
+        "101110100101001011101",
+        "101110101001001011101",
+        "101110101110101011101",
+        "100000101001001000001",
+        "111111101010101111111",
+        "000000001001100000000",
+        "100010111111011111001",
+        "000100001011100001111",
+        "001111110011011010010",
+        "111110001100010000000",
+        "111110101010101100110",
+        "000000001010111101011",
+        "111111101110101011010",
+        "100000100101110110011",
+        "101110101101011000110",
+        "101110100100100011011",
+        "101110100111000111000",
+        "100000100001010000000",
+        "111111101111111110101",
+    ];
+    for (y, row) in expected.iter().enumerate() {
+        let modules: String = (0..21)
+            .map(|x| {
+                if code.get_module(x, y as u16) {
+                    '1'
+                } else {
+                    '0'
+                }
+            })
+            .collect();
+        assert_eq!(modules, *row, "row {}", y);
+    }
+    assert!(!code.get_module(21, 0));
+
+    let size = |len, ecc| QrCode::new("x".repeat(len).as_bytes(), ecc).map(|code| code.size());
+    assert_eq!(size(17, QrEcc::Low).unwrap(), 21);
+    assert_eq!(size(18, QrEcc::Low).unwrap(), 25);
+    assert_eq!(size(230, QrEcc::Low).unwrap(), 53);
+    assert_eq!(size(98, QrEcc::High).unwrap(), 53);
+    assert!(matches!(
+        size(231, QrEcc::Low),
+        Err(OledError::InvalidArgument(_))
+    ));
+    assert!(size(99, QrEcc::High).is_err());
+}
+
+#[test]
+fn draws_dark_modules_unlit_in_a_lit_quiet_zone() {
+    let code = QrCode::new(b"HELLO WORLD", QrEcc::Medium).unwrap();
+    let mut frame = Framebuffer::new();
+    frame.draw_qr(&code, 10, 0, 2);
+    let side = (21 + QR_QUIET_ZONE * 2) * 2;
+    assert!(!frame.get_pixel(9, 0) && !frame.get_pixel(10 + side, 0));
+    assert!(frame.get_pixel(10, 0) && frame.get_pixel(9 + side, side - 1));
+    // the top left corner of the finder pattern, two pixels square
+    let corner = 10 + QR_QUIET_ZONE * 2;
+    assert!(frame.get_pixel(corner - 1, QR_QUIET_ZONE * 2));
+    assert!(!frame.get_pixel(corner, QR_QUIET_ZONE * 2));
+    assert!(!frame.get_pixel(corner + 1, QR_QUIET_ZONE * 2 + 1));
+}
+
+/// Whether the pixel at `x`, `y` of the display is lit, in landscape
+fn lit(oled: &Oled<NullInterface>, x: u16, y: u16) -> bool {
+    oled.framebuffer()[usize::from(y / 8 * 128 + x)] & 1 << (y % 8) != 0
+}
+
+#[test]
+fn scales_codes_to_fit_the_display() {
+    let mut oled = Oled::new(NullInterface);
+    oled.draw_qr("HELLO WORLD", QrEcc::Medium, Align::Right)
+        .unwrap();
+    // 29 modules with the quiet zone, two pixels each, centred down the display
+    assert!(lit(&oled, 127, 3) && lit(&oled, 70, 60));
+    assert!(!lit(&oled, 69, 3) && !lit(&oled, 127, 2) && !lit(&oled, 127, 61));
+
+    let mut oled = Oled::new(NullInterface);
+    oled.set_rotation(Rotation::Rotate90).unwrap();
+    let long = "x".repeat(200);
+    oled.draw_qr(&long, QrEcc::Low, Align::Left).unwrap();
+    assert!(oled.draw_qr(&long, QrEcc::High, Align::Left).is_err());
+}
@@ -0,0 +1,88 @@
+#![cfg(feature = "std")]
+use nanohat_oled::{CaptureDevice, Oled, OledError, OledInterface, Rotation};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// A device that can still be reset while an `Oled` is using it
+#[derive(Clone, Default)]
+struct SharedDevice(Rc<RefCell<CaptureDevice>>);
+
+impl OledInterface for SharedDevice {
+    fn send_command(&mut self, command: u8) -> Result<(), OledError> {
+        self.0.borrow_mut().send_command(command)
+    }
+
+    fn send_data(&mut self, data: &[u8]) -> Result<(), OledError> {
+        self.0.borrow_mut().send_data(data)
+    }
+}
+
+/// Fails the first `failures` commands with `error`
+struct Flaky {
+    failures: usize,
+    error: fn() -> OledError,
+    sent: Vec<u8>,
+}
+
+impl OledInterface for Flaky {
+    fn send_command(&mut self, command: u8) -> Result<(), OledError> {
+        if self.failures > 0 {
+            self.failures -= 1;
+            return Err((self.error)());
+        }
+        self.sent.push(command);
+        Ok(())
+    }
+
+    fn send_data(&mut self, _data: &[u8]) -> Result<(), OledError> {
+        Ok(())
+    }
+}
+
+#[test]
+fn commands_are_retried_on_transient_errors_only() {
+    let flaky = |error| Flaky {
+        failures: 2,
+        error,
+        sent: Vec::new(),
+    };
+    let mut oled = Oled::new(flaky(|| OledError::Interface));
+    oled.set_retries(2);
+    oled.send_command(0xaf).unwrap();
+    assert_eq!(oled.into_interface().sent, [0xaf]);
+
+    let mut oled = Oled::new(flaky(|| OledError::Interface));
+    oled.set_retries(1);
+    assert!(oled.send_command(0xaf).is_err());
+
+    let mut oled = Oled::new(flaky(|| {
+        OledError::I2c(std::io::Error::from(std::io::ErrorKind::NotFound))
+    }));
+    oled.set_retries(5);
+    assert!(oled.send_command(0xaf).is_err());
+    assert!(oled.into_interface().sent.is_empty());
+}
+
+#[test]
+fn recover_puts_back_the_picture_and_settings() {
+    let device = SharedDevice::default();
+    let mut oled = Oled::new(device.clone());
+    oled.init().unwrap();
+    oled.set_rotation(Rotation::Rotate180).unwrap();
+    oled.put_string("Hi").unwrap();
+    oled.send_command(0xa7).unwrap();
+    let before = device.0.borrow().ram().to_owned();
+    let pointer = device.0.borrow().pointer();
+
+    device.0.borrow_mut().reset();
+    assert!(!device.0.borrow().is_lit(1, 0));
+    oled.recover().unwrap();
+    let device = device.0.borrow();
+    assert_eq!(device.ram(), &before);
+    assert_eq!(device.pointer(), pointer);
+    let commands = device.commands();
+    // segment remap and COM scan for the rotation, then inversion, after setup
+    let setup = commands.len() - commands.iter().rev().position(|c| *c == 0xaf).unwrap();
+    assert!(commands[setup..].windows(2).any(|c| c == [0xa0, 0xc0]));
+    assert!(commands[setup..].contains(&0xa7));
+}
@@ -0,0 +1,112 @@
+use nanohat_oled::{Framebuffer, MockInterface, Oled, OledError, Rotation};
+
+mod common;
+use common::NullInterface;
+
+const A: [u8; 8] = [0x00, 0x7E, 0x09, 0x09, 0x09, 0x7E, 0x00, 0x00];
+const B: [u8; 8] = [0x00, 0x7F, 0x49, 0x49, 0x49, 0x36, 0x00, 0x00];
+
+fn portrait_oled() -> Oled<NullInterface> {
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    oled.set_rotation(Rotation::Rotate90).unwrap();
+    oled
+}
+
+/// Draws a glyph into a portrait framebuffer with its top left corner at `x`, `y`
+fn draw_glyph(frame: &mut Framebuffer, glyph: [u8; 8], x: u16, y: u16) {
+    for (column, bits) in glyph.iter().enumerate() {
+        for row in 0..8 {
+            if bits & (1 << row) != 0 {
+                frame.set_pixel(x + column as u16, y + row, true);
+            }
+        }
+    }
+}
+
+#[test]
+fn quarter_turns_use_hardware_for_the_half_turn() {
+    let mut bus = MockInterface::new();
+    let mut oled = Oled::new(&mut bus);
+    oled.set_rotation(Rotation::Rotate270).unwrap();
+    assert_eq!(oled.rotation(), Rotation::Rotate270);
+    assert_eq!(oled.dimensions(), (64, 128));
+    oled.set_rotation(Rotation::Rotate90).unwrap();
+    assert_eq!(oled.dimensions(), (64, 128));
+    let commands = bus.commands();
+    assert_eq!(commands[..2], [0xa0, 0xc0]);
+    assert_eq!(
+        commands[commands.len() - 14..commands.len() - 12],
+        [0xa1, 0xc8]
+    );
+}
+
+#[test]
+fn portrait_framebuffer_maps_into_panel_layout() {
+    let mut frame = Framebuffer::portrait();
+    assert_eq!(frame.dimensions(), (64, 128));
+    frame.set_pixel(0, 0, true);
+    frame.set_pixel(63, 127, true);
+    frame.set_pixel(64, 0, true);
+    assert!(frame.get_pixel(0, 0));
+    assert!(!frame.get_pixel(64, 0));
+    assert_eq!(frame.as_bytes()[7 * 128], 0x80);
+    assert_eq!(frame.as_bytes()[127], 0x01);
+    assert_eq!(
+        frame.as_bytes().iter().map(|b| b.count_ones()).sum::<u32>(),
+        2
+    );
+}
+
+#[test]
+fn draws_portrait_images() {
+    let mut image = vec![0u8; 64 * 128];
+    image[0] = 255;
+    image[64 * 128 - 1] = 255;
+    let mut oled = portrait_oled();
+    oled.draw_image(&image, 128).unwrap();
+    let mut expected = Framebuffer::portrait();
+    expected.set_pixel(0, 0, true);
+    expected.set_pixel(63, 127, true);
+    assert_eq!(oled.framebuffer(), expected.as_bytes());
+
+    oled.draw_fn(|x, y| x == 10 && y == 100).unwrap();
+    let mut expected = Framebuffer::portrait();
+    expected.set_pixel(10, 100, true);
+    assert_eq!(oled.framebuffer(), expected.as_bytes());
+}
+
+#[test]
+fn writes_portrait_text() {
+    let mut oled = portrait_oled();
+    oled.set_text_xy(7, 2).unwrap();
+    oled.put_string("AB").unwrap();
+    let mut expected = Framebuffer::portrait();
+    draw_glyph(&mut expected, A, 56, 16);
+    draw_glyph(&mut expected, B, 0, 24);
+    assert_eq!(oled.framebuffer(), expected.as_bytes());
+}
+
+#[test]
+fn flush_checks_orientation() {
+    let mut oled = portrait_oled();
+    assert!(matches!(
+        oled.flush(&Framebuffer::new()),
+        Err(OledError::InvalidDimensions)
+    ));
+    let mut frame = Framebuffer::portrait();
+    frame.draw_line(0, 0, 63, 127, true);
+    oled.flush(&frame).unwrap();
+    assert_eq!(oled.framebuffer(), frame.as_bytes());
+    oled.draw(|frame| frame.set_pixel(63, 0, true)).unwrap();
+    assert!(oled.framebuffer()[0] & 0x01 != 0);
+}
+
+#[test]
+fn draws_images_at_portrait_positions() {
+    let mut oled = portrait_oled();
+    oled.draw_image_at(&[255; 6], 3, 2, 62, 126, 128).unwrap();
+    let mut expected = Framebuffer::portrait();
+    expected.fill_rect(62, 126, 2, 2, true);
+    assert_eq!(oled.framebuffer(), expected.as_bytes());
+}
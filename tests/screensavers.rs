@@ -0,0 +1,103 @@
+use nanohat_oled::{
+    Bouncing, CaptureDevice, Framebuffer, GameOfLife, IdleManager, Oled, Screensaver, Sprite,
+    Starfield,
+};
+use std::time::Duration;
+
+const BLOCK: Sprite = Sprite::new(8, 4, &[0xff; 4]);
+
+fn lit(frame: &Framebuffer) -> usize {
+    frame
+        .as_bytes()
+        .iter()
+        .map(|byte| byte.count_ones() as usize)
+        .sum()
+}
+
+#[test]
+fn bounces_off_the_edges() {
+    let mut bouncing = Bouncing::new(BLOCK).with_speed(10, 3);
+    let mut frame = Framebuffer::new();
+    frame.fill_rect(0, 0, 128, 64, true);
+    bouncing.step(&mut frame);
+    assert_eq!(bouncing.position(), (10, 3));
+    assert_eq!(lit(&frame), 32);
+    assert!(frame.get_pixel(10, 3) && frame.get_pixel(17, 6));
+    let mut seen = Vec::new();
+    for _ in 0..40 {
+        bouncing.step(&mut frame);
+        seen.push(bouncing.position());
+    }
+    // it reaches the far edges, but never past them
+    assert!(seen.iter().any(|(x, _)| *x == 120));
+    assert!(seen.iter().any(|(_, y)| *y == 60));
+    assert!(seen.iter().all(|(x, y)| *x <= 120 && *y <= 60));
+    assert!(seen.iter().any(|(x, _)| *x == 0));
+}
+
+#[test]
+fn flies_through_stars() {
+    let mut one = Starfield::with_seed(7);
+    let mut other = Starfield::with_seed(7).with_speed(8);
+    let (mut frame, mut other_frame) = (Framebuffer::new(), Framebuffer::new());
+    for _ in 0..100 {
+        one.step(&mut frame);
+        other.step(&mut other_frame);
+        assert!(lit(&frame) > 0 && lit(&frame) <= 4 * 64);
+    }
+    assert_ne!(frame, other_frame);
+    let mut again = Starfield::with_seed(7);
+    let mut again_frame = Framebuffer::new();
+    for _ in 0..100 {
+        again.step(&mut again_frame);
+    }
+    assert_eq!(frame, again_frame);
+}
+
+#[test]
+fn plays_the_game_of_life() {
+    let mut life = GameOfLife::with_seed(3).with_limit(50);
+    let mut frame = Framebuffer::new();
+    life.step(&mut frame);
+    assert_eq!(life.generation(), 0);
+    let scattered = lit(&frame);
+    assert!(scattered > 1024 && scattered < 4096);
+    // each cell follows the rules, with the edges wrapping round
+    let before = frame.clone();
+    life.step(&mut frame);
+    assert_eq!(life.generation(), 1);
+    for (x, y) in [(0, 0), (5, 9), (127, 63), (64, 32), (100, 0)] {
+        let mut neighbours = 0;
+        for dy in [63, 0, 1] {
+            for dx in [127, 0, 1] {
+                if (dx, dy) != (0, 0) && before.get_pixel((x + dx) % 128, (y + dy) % 64) {
+                    neighbours += 1;
+                }
+            }
+        }
+        let alive = neighbours == 3 || (neighbours == 2 && before.get_pixel(x, y));
+        assert_eq!(frame.get_pixel(x, y), alive, "cell {}, {}", x, y);
+    }
+    // it's scattered again on the step after the limit
+    for _ in 0..49 {
+        life.step(&mut frame);
+    }
+    assert_eq!(life.generation(), 50);
+    life.step(&mut frame);
+    assert_eq!(life.generation(), 0);
+}
+
+#[test]
+fn steps_an_animation_while_idle() {
+    let mut device = CaptureDevice::new();
+    let mut oled = Oled::new(&mut device);
+    oled.init().unwrap();
+    let mut idle = IdleManager::new(Duration::from_secs(10)).with_animation(Bouncing::new(BLOCK));
+    idle.tick(&mut oled, Duration::from_secs(10)).unwrap();
+    idle.tick(&mut oled, Duration::from_secs(1)).unwrap();
+    assert!(idle.is_idle());
+    // stepped twice, so a column of the block covers rows 2 to 5
+    assert_eq!(oled.framebuffer()[1..4], [0x00, 0x3c, 0x3c]);
+    assert!(idle.touch(&mut oled).unwrap());
+    assert!(oled.framebuffer().iter().all(|byte| *byte == 0));
+}
@@ -0,0 +1,116 @@
+#![cfg(feature = "alloc")]
+use nanohat_oled::{assert_frame_eq, Framebuffer, OledError};
+
+/// A frame with a lit box round its edge and a lit pixel at 3, 1
+fn framed(mut frame: Framebuffer) -> Framebuffer {
+    let (width, height) = frame.dimensions();
+    frame.draw_rect(0, 0, width, height, true);
+    frame.set_pixel(3, 1, true);
+    frame
+}
+
+#[test]
+fn exports_pbm_with_lit_pixels_white() {
+    let pbm = framed(Framebuffer::new()).to_pbm();
+    let header = b"P4\n128 64\n";
+    assert!(pbm.starts_with(header));
+    let rows = &pbm[header.len()..];
+    assert_eq!(rows.len(), 16 * 64);
+    // set bits are black
+    assert!(rows[..16].iter().all(|byte| *byte == 0));
+    assert_eq!(rows[16], 0b0110_1111);
+    assert_eq!(rows[17], 0xff);
+    assert_eq!(rows[31], 0b1111_1110);
+
+    let pbm = framed(Framebuffer::portrait()).to_pbm();
+    let header = b"P4\n64 128\n";
+    assert!(pbm.starts_with(header));
+    assert_eq!(pbm.len(), header.len() + 8 * 128);
+    assert_eq!(pbm[header.len() + 8], 0b0110_1111);
+}
+
+#[cfg(feature = "png")]
+#[test]
+fn saves_png_with_lit_pixels_white() {
+    let path = std::env::temp_dir().join(format!("nanohat-oled-{}.png", std::process::id()));
+    framed(Framebuffer::new()).to_png(&path).unwrap();
+    let png = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(
+        png[..8],
+        [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n']
+    );
+    // IHDR: 128x64, 1-bit grayscale, with its checksum
+    assert_eq!(png[8..16], [0, 0, 0, 13, b'I', b'H', b'D', b'R']);
+    assert_eq!(png[16..29], [0, 0, 0, 128, 0, 0, 0, 64, 1, 0, 0, 0, 0]);
+    assert_eq!(png[29..33], [0xfa, 0xad, 0x42, 0xd2]);
+    // IDAT: a zlib stream of one stored block of 64 rows of a filter byte and 16 bytes
+    assert_eq!(png[33..41], [0, 0, 0x04, 0x4b, b'I', b'D', b'A', b'T']);
+    let rows = 41 + 7;
+    assert_eq!(png[41..rows], [0x78, 0x01, 1, 0x40, 0x04, 0xbf, 0xfb]);
+    assert!(png[rows..rows + 17]
+        .iter()
+        .all(|byte| *byte == 0xff || *byte == 0));
+    assert_eq!(png[rows + 17..rows + 20], [0, 0b1001_0000, 0]);
+    assert_eq!(
+        png[png.len() - 12..png.len() - 4],
+        [0, 0, 0, 0, b'I', b'E', b'N', b'D']
+    );
+}
+
+#[test]
+fn matches_committed_snapshot() {
+    let expected = Framebuffer::from_snapshot(include_str!("data/framed.txt")).unwrap();
+    assert_frame_eq!(framed(Framebuffer::new()), expected);
+    let snapshot = framed(Framebuffer::portrait()).to_snapshot();
+    assert_eq!(snapshot.lines().count(), 128);
+    assert!(snapshot.starts_with(&format!("{}\n#..#{}#\n", "#".repeat(64), ".".repeat(59))));
+    assert_eq!(
+        Framebuffer::from_snapshot(&format!("\n{}\n", snapshot)).unwrap(),
+        framed(Framebuffer::portrait())
+    );
+}
+
+#[test]
+fn rejects_snapshots_of_other_sizes_or_characters() {
+    let snapshot = framed(Framebuffer::new()).to_snapshot();
+    assert!(matches!(
+        Framebuffer::from_snapshot(&snapshot[129..]),
+        Err(OledError::InvalidDimensions)
+    ));
+    assert!(matches!(
+        Framebuffer::from_snapshot(&snapshot.replacen("..", ".", 1)),
+        Err(OledError::InvalidDimensions)
+    ));
+    assert!(matches!(
+        Framebuffer::from_snapshot(&snapshot.replacen('.', "o", 1)),
+        Err(OledError::InvalidArgument(_))
+    ));
+}
+
+#[test]
+fn describes_differing_rows() {
+    let mut frame = framed(Framebuffer::new());
+    assert_eq!(frame.diff(&framed(Framebuffer::new())), None);
+    frame.set_pixel(5, 2, true);
+    let diff = frame.diff(&framed(Framebuffer::new())).unwrap();
+    let mut lines = diff.lines();
+    assert_eq!(lines.next(), Some("row 2:"));
+    assert_eq!(
+        lines.next().unwrap(),
+        format!("  frame:    #....#{}#", ".".repeat(121))
+    );
+    lines.next();
+    assert_eq!(lines.next(), Some("                 ^"));
+    assert_eq!(lines.next(), Some("frame:"));
+    assert!(framed(Framebuffer::new())
+        .diff(&Framebuffer::portrait())
+        .unwrap()
+        .starts_with("frame is 128x64, but 64x128 was expected\n"));
+}
+
+#[test]
+#[should_panic(expected = "frames differ")]
+fn assert_frame_eq_panics_on_a_difference() {
+    assert_frame_eq!(framed(Framebuffer::new()), Framebuffer::new());
+}
@@ -0,0 +1,83 @@
+use nanohat_oled::{
+    MockInterface, Oled, OledError, ScrollDirection, ScrollInterval, VerticalScrollArea,
+};
+
+#[test]
+fn sets_up_vertical_horizontal_scroll() {
+    let mut bus = MockInterface::new();
+    let mut oled = Oled::new(&mut bus);
+    let area = VerticalScrollArea {
+        fixed_rows: 8,
+        scroll_rows: 56,
+    };
+    oled.start_vertical_horizontal_scroll(
+        area,
+        ScrollDirection::Left,
+        1,
+        7,
+        ScrollInterval::Frames2,
+        1,
+    )
+    .unwrap();
+    assert_eq!(
+        bus.commands(),
+        [0x2e, 0xa3, 8, 56, 0x2a, 0x00, 1, 0x07, 7, 1, 0x2f]
+    );
+}
+
+#[test]
+fn sets_up_horizontal_scroll() {
+    let mut bus = MockInterface::new();
+    let mut oled = Oled::new(&mut bus);
+    oled.start_horizontal_scroll(ScrollDirection::Right, 2, 3, ScrollInterval::Frames64)
+        .unwrap();
+    assert_eq!(
+        bus.commands(),
+        [0x2e, 0x26, 0x00, 2, 0x01, 3, 0x00, 0xff, 0x2f]
+    );
+    bus.clear();
+
+    let mut oled = Oled::new(&mut bus);
+    oled.start_diagonal_scroll(ScrollDirection::Right, 0, 7, ScrollInterval::Frames3, 2)
+        .unwrap();
+    assert_eq!(
+        bus.commands(),
+        [0x2e, 0xa3, 0, 64, 0x29, 0x00, 0, 0x04, 7, 2, 0x2f]
+    );
+}
+
+#[test]
+fn rejects_invalid_scroll_setup() {
+    let mut bus = MockInterface::new();
+    let mut oled = Oled::new(&mut bus);
+    let too_tall = VerticalScrollArea {
+        fixed_rows: 16,
+        scroll_rows: 56,
+    };
+    for (area, start, end, offset) in [
+        (too_tall, 0, 7, 1),
+        (VerticalScrollArea::FULL_SCREEN, 0, 8, 1),
+        (VerticalScrollArea::FULL_SCREEN, 4, 2, 1),
+        (VerticalScrollArea::FULL_SCREEN, 0, 7, 64),
+    ] {
+        let result = oled.start_vertical_horizontal_scroll(
+            area,
+            ScrollDirection::Right,
+            start,
+            end,
+            ScrollInterval::Frames5,
+            offset,
+        );
+        assert!(matches!(result, Err(OledError::InvalidArgument(_))));
+    }
+    for (start, end) in [(0, 8), (5, 4)] {
+        let result = oled.start_horizontal_scroll(
+            ScrollDirection::Left,
+            start,
+            end,
+            ScrollInterval::Frames2,
+        );
+        assert!(matches!(result, Err(OledError::InvalidArgument(_))));
+    }
+    assert!(bus.sent().is_empty());
+}
@@ -0,0 +1,91 @@
+use nanohat_oled::{Oled, OledError, OledInterface};
+
+/// An interface that fails a given transfer a set number of times
+struct FlakyInterface {
+    /// Every transfer that made it to the display
+    delivered: Vec<Vec<u8>>,
+    /// Number of transfers attempted so far
+    attempts: usize,
+    /// Index of the transfer that fails
+    fail_transfer: usize,
+    /// How many times that transfer fails before succeeding
+    failures_left: usize,
+}
+
+impl FlakyInterface {
+    fn new(fail_transfer: usize, failures: usize) -> Self {
+        Self {
+            delivered: Vec::new(),
+            attempts: 0,
+            fail_transfer,
+            failures_left: failures,
+        }
+    }
+}
+
+impl OledInterface for FlakyInterface {
+    fn send_command(&mut self, _command: u8) -> Result<(), OledError> {
+        Ok(())
+    }
+
+    fn send_data(&mut self, data: &[u8]) -> Result<(), OledError> {
+        self.attempts += 1;
+        if self.delivered.len() == self.fail_transfer && self.failures_left > 0 {
+            self.failures_left -= 1;
+            return Err(OledError::Interface);
+        }
+        self.delivered.push(data.to_vec());
+        Ok(())
+    }
+
+    fn max_transfer_len(&self) -> usize {
+        4
+    }
+}
+
+fn payload() -> Vec<u8> {
+    (0..10).collect()
+}
+
+#[test]
+fn splits_into_transfers() {
+    let mut bus = FlakyInterface::new(usize::MAX, 0);
+    Oled::new(&mut bus).send_array_data(&payload()[..]).unwrap();
+    assert_eq!(
+        bus.delivered,
+        vec![vec![0, 1, 2, 3], vec![4, 5, 6, 7], vec![8, 9]]
+    );
+}
+
+#[test]
+fn stops_at_failed_transfer_without_retries() {
+    let mut bus = FlakyInterface::new(1, 1);
+    let result = Oled::new(&mut bus).send_array_data(&payload()[..]);
+    assert!(matches!(result, Err(OledError::Interface)));
+    assert_eq!(bus.delivered, vec![vec![0, 1, 2, 3]]);
+    assert_eq!(bus.attempts, 2);
+}
+
+#[test]
+fn retries_only_the_failed_transfer() {
+    let mut bus = FlakyInterface::new(1, 2);
+    let mut oled = Oled::new(&mut bus);
+    oled.set_retries(2);
+    oled.send_array_data(&payload()[..]).unwrap();
+    assert_eq!(
+        bus.delivered,
+        vec![vec![0, 1, 2, 3], vec![4, 5, 6, 7], vec![8, 9]]
+    );
+    assert_eq!(bus.attempts, 5);
+}
+
+#[test]
+fn gives_up_after_retries_exhausted() {
+    let mut bus = FlakyInterface::new(2, 4);
+    let mut oled = Oled::new(&mut bus);
+    oled.set_retries(3);
+    let result = oled.send_array_data(&payload()[..]);
+    assert!(matches!(result, Err(OledError::Interface)));
+    assert_eq!(bus.delivered, vec![vec![0, 1, 2, 3], vec![4, 5, 6, 7]]);
+    assert_eq!(bus.attempts, 6);
+}
@@ -0,0 +1,47 @@
+#![cfg(feature = "std")]
+use nanohat_oled::{Framebuffer, MockInterface, Oled, SharedOled};
+use std::thread;
+use std::time::Duration;
+
+fn lit(oled: &Oled<MockInterface>, x: u16, y: u16) -> bool {
+    Framebuffer::from_bytes(*oled.framebuffer()).get_pixel(x, y)
+}
+
+#[test]
+fn draws_from_several_threads_wait_for_a_flush() {
+    let shared = SharedOled::new(Oled::new(MockInterface::new()));
+    let threads: Vec<_> = (0..4)
+        .map(|n| {
+            let shared = shared.clone();
+            thread::spawn(move || shared.draw(|frame| frame.set_pixel(n * 10, 0, true)))
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    shared.flush().unwrap();
+    let oled = shared.try_unwrap().ok().unwrap();
+    assert!((0..4).all(|n| lit(&oled, n * 10, 0)));
+    // sent as one window, across the changed columns of the first page
+    assert_eq!(oled.into_interface().data().len(), 31);
+}
+
+#[test]
+fn with_sees_what_was_drawn() {
+    let shared = SharedOled::new(Oled::new(MockInterface::new()));
+    shared.draw(|frame| frame.fill_rect(0, 0, 8, 8, true));
+    assert!(shared.with(|oled| lit(oled, 7, 7)).unwrap());
+    let other = shared.clone();
+    assert!(shared.try_unwrap().is_err());
+    assert!(other.try_unwrap().is_ok());
+}
+
+#[test]
+fn flusher_sends_everything_drawn_before_it_stops() {
+    let shared = SharedOled::new(Oled::new(MockInterface::new()));
+    let flusher = shared.spawn_flusher(Duration::from_secs(60));
+    shared.draw(|frame| frame.set_pixel(127, 63, true));
+    flusher.stop().unwrap();
+    let oled = shared.try_unwrap().ok().unwrap();
+    assert!(lit(&oled, 127, 63));
+}
@@ -0,0 +1,145 @@
+#![cfg(feature = "simulator")]
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread::{self, JoinHandle};
+use std::{env, fs, process};
+
+use nanohat_oled::{Framebuffer, Oled, OledError, WindowSimulator};
+
+/// What a fake X server replies to a connection setup: a little-endian server
+/// with one 24 bit screen, which takes requests of at most 400 bytes
+fn setup_reply() -> Vec<u8> {
+    let mut reply = vec![1, 0, 11, 0, 0, 0, 21, 0];
+    // release, resource IDs, motion buffer, vendor and request length
+    reply.extend(0u32.to_le_bytes());
+    reply.extend(0x0040_0000u32.to_le_bytes());
+    reply.extend(0x001f_ffffu32.to_le_bytes());
+    reply.extend(0u32.to_le_bytes());
+    reply.extend(4u16.to_le_bytes());
+    reply.extend(100u16.to_le_bytes());
+    // a screen and a format, LSB first bitmaps in 32 bit units, and keycodes
+    reply.extend([1, 1, 0, 0, 32, 32, 8, 255, 0, 0, 0, 0]);
+    reply.extend(b"test");
+    reply.extend([24, 32, 32, 0, 0, 0, 0, 0]);
+    // root window, colormap, white and black
+    for value in [0x100u32, 0x20, 0xff_ffff, 0] {
+        reply.extend(value.to_le_bytes());
+    }
+    // input masks, size, maps, visual and depths
+    reply.extend(0u32.to_le_bytes());
+    for value in [1920u16, 1080, 500, 300, 1, 1] {
+        reply.extend(value.to_le_bytes());
+    }
+    reply.extend(0x21u32.to_le_bytes());
+    reply.extend([0, 0, 24, 0]);
+    reply
+}
+
+/// The connection setup a fake X server was sent, and each request after it
+type Received = (Vec<u8>, Vec<Vec<u8>>);
+
+/// Starts a fake X server, returning its display name and a handle that gives
+/// what it received once the client is gone
+fn fake_server(reply: Vec<u8>) -> (String, JoinHandle<Received>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = thread::spawn(move || {
+        let (mut client, _) = listener.accept().unwrap();
+        let mut setup = vec![0u8; 12];
+        client.read_exact(&mut setup).unwrap();
+        let padded = |len: u16| usize::from(len).div_ceil(4) * 4;
+        let auth_len = padded(u16::from_le_bytes([setup[6], setup[7]]))
+            + padded(u16::from_le_bytes([setup[8], setup[9]]));
+        setup.resize(12 + auth_len, 0);
+        client.read_exact(&mut setup[12..]).unwrap();
+        client.write_all(&reply).unwrap();
+        (setup, requests(&mut client))
+    });
+    (format!("127.0.0.1:{}", port - 6000), server)
+}
+
+/// Every request sent until the client disconnects
+fn requests(client: &mut TcpStream) -> Vec<Vec<u8>> {
+    let mut requests = Vec::new();
+    let mut header = [0u8; 4];
+    while client.read_exact(&mut header).is_ok() {
+        let mut request = header.to_vec();
+        request.resize(
+            usize::from(u16::from_le_bytes([header[2], header[3]])) * 4,
+            0,
+        );
+        client.read_exact(&mut request[4..]).unwrap();
+        requests.push(request);
+    }
+    requests
+}
+
+#[test]
+fn draws_the_display_scaled_in_a_window() {
+    let authority = env::temp_dir().join(format!("nanohat-oled-xauth-{}", process::id()));
+    // a local entry, for any display
+    let mut entry = vec![1, 0];
+    for field in [&b""[..], b"", b"MIT-MAGIC-COOKIE-1", b"0123456789abcdef"] {
+        entry.extend((field.len() as u16).to_be_bytes());
+        entry.extend(field);
+    }
+    fs::write(&authority, entry).unwrap();
+    env::set_var("XAUTHORITY", &authority);
+
+    let (display, server) = fake_server(setup_reply());
+    let mut oled = Oled::new(WindowSimulator::open_display(&display, 2).unwrap());
+    oled.init().unwrap();
+    let mut frame = Framebuffer::new();
+    frame.set_pixel(5, 60, true);
+    oled.flush(&frame).unwrap();
+    drop(oled);
+    let (setup, requests) = server.join().unwrap();
+    fs::remove_file(authority).unwrap();
+
+    assert_eq!(&setup[..2], b"l\0");
+    assert!(setup.ends_with(b"MIT-MAGIC-COOKIE-1\0\x000123456789abcdef"));
+    // the window, its title, pixmap and graphics context, then showing it
+    let opcodes: Vec<u8> = requests.iter().map(|request| request[0]).collect();
+    assert_eq!(opcodes[..5], [1, 18, 53, 55, 8]);
+    assert_eq!(u16::from_le_bytes([requests[0][16], requests[0][17]]), 256);
+    assert_eq!(u16::from_le_bytes([requests[0][18], requests[0][19]]), 128);
+
+    // the last picture drawn, put together from strips of rows
+    let mut image = vec![0u8; 32 * 128];
+    let drawn = opcodes.iter().rposition(|opcode| *opcode == 61).unwrap();
+    let first = opcodes[..drawn]
+        .iter()
+        .rposition(|opcode| *opcode != 72 && *opcode != 2);
+    for request in &requests[first.unwrap() + 1..drawn] {
+        if request[0] == 72 {
+            assert!(request.len() <= 400);
+            let top = usize::from(u16::from_le_bytes([request[18], request[19]]));
+            let rows = &request[24..];
+            image[top * 32..top * 32 + rows.len()].copy_from_slice(rows);
+        }
+    }
+    let lit = |x: usize, y: usize| image[y * 32 + x / 8] & 1 << (x % 8) != 0;
+    assert!(lit(10, 120) && lit(11, 121));
+    assert_eq!(
+        (0..256 * 128).filter(|at| lit(at % 256, at / 256)).count(),
+        4
+    );
+}
+
+#[test]
+fn reports_a_refused_connection() {
+    let mut reply = vec![0, 6, 11, 0, 0, 0, 2, 0];
+    reply.extend(b"denied\0\0");
+    let (display, server) = fake_server(reply);
+    let result = WindowSimulator::open_display(&display, 4);
+    match result {
+        Err(OledError::I2c(err)) => assert!(err.to_string().ends_with("denied")),
+        _ => panic!("connection wasn't refused"),
+    }
+    server.join().unwrap();
+    assert!(matches!(
+        WindowSimulator::open_display("nowhere", 4),
+        Err(OledError::InvalidArgument(_))
+    ));
+    assert!(WindowSimulator::open_display(":0", 0).is_err());
+}
@@ -0,0 +1,48 @@
+#![cfg(feature = "spi")]
+use nanohat_oled::{Oled, OledInterface, SpiInterface};
+
+fn init() -> (Vec<u8>, Vec<u8>) {
+    let mut oled = Oled::new(SpiInterface::from_writers(Vec::new(), Vec::new()));
+    oled.init().unwrap();
+    oled.into_interface().into_writers()
+}
+
+#[test]
+fn setup_is_sent_as_commands_then_the_clear_as_data() {
+    let (spi, dc) = init();
+    assert_eq!(spi[..3], [0xae, 0x00, 0x10]);
+    assert_eq!(spi[24], 0xaf);
+    // D/C only changes when it has to, with the pointer put back after clearing
+    assert_eq!(dc, b"010");
+    assert!(spi.windows(8192).any(|w| w.iter().all(|&b| b == 0)));
+}
+
+#[test]
+fn dc_follows_commands_and_data() {
+    let mut spi = SpiInterface::from_writers(Vec::new(), Vec::new());
+    spi.send_data(&[0xff, 0x81]).unwrap();
+    spi.send_command(0xa7).unwrap();
+    spi.send_command(0xa6).unwrap();
+    spi.send_data(&[0x18]).unwrap();
+    assert_eq!(spi.max_transfer_len(), 4096);
+    let (spi, dc) = spi.into_writers();
+    assert_eq!(spi, [0xff, 0x81, 0xa7, 0xa6, 0x18]);
+    assert_eq!(dc, b"101");
+}
+
+#[test]
+fn opens_a_device_and_dc_value_file() {
+    let dir = std::env::temp_dir().join(format!("nanohat-oled-spi-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let (device, value) = (dir.join("spidev"), dir.join("value"));
+    std::fs::write(&device, b"").unwrap();
+    std::fs::write(&value, b"").unwrap();
+    {
+        let mut spi = SpiInterface::from_paths(&device, &value).unwrap();
+        spi.send_command(0xaf).unwrap();
+    }
+    assert_eq!(std::fs::read(&device).unwrap(), [0xaf]);
+    assert_eq!(std::fs::read(&value).unwrap(), b"0");
+    assert!(SpiInterface::from_paths(dir.join("missing"), &value).is_err());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
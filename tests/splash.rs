@@ -0,0 +1,51 @@
+use nanohat_oled::{include_image, Framebuffer, OledError};
+
+/// 16x8 pixels: a white top row, then rows white only on the right
+const SPLASH: Framebuffer = include_image!("data/splash.pbm");
+
+#[test]
+fn included_images_are_lit_where_white() {
+    assert!(SPLASH.get_pixel(0, 0) && SPLASH.get_pixel(15, 0));
+    assert!(!SPLASH.get_pixel(7, 1) && SPLASH.get_pixel(8, 7));
+    // outside the image
+    assert!(!SPLASH.get_pixel(16, 0) && !SPLASH.get_pixel(0, 8));
+}
+
+#[test]
+fn reports_images_that_cant_be_read() {
+    let frame = Framebuffer::try_from_pbm(include_bytes!("data/splash.pbm")).unwrap();
+    assert_eq!(frame, SPLASH);
+    for invalid in [&b"P5\n1 1\n\x00"[..], b"P4\n8", b"P4\n8 2\n\x00"] {
+        assert!(matches!(
+            Framebuffer::try_from_pbm(invalid),
+            Err(OledError::InvalidArgument(_))
+        ));
+    }
+    assert!(matches!(
+        Framebuffer::try_from_pbm(b"P4\n99999999999999999999999 1\n"),
+        Err(OledError::InvalidDimensions)
+    ));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn screenshots_read_back_as_drawn() {
+    let mut frame = Framebuffer::new();
+    frame.draw_rect(3, 5, 100, 50, true);
+    assert_eq!(Framebuffer::from_pbm(&frame.to_pbm()), frame);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn show_splash_clears_afterwards() {
+    use nanohat_oled::{CaptureDevice, Oled};
+    use std::time::Duration;
+
+    let mut device = CaptureDevice::new();
+    Oled::new(&mut device)
+        .show_splash(&SPLASH, Duration::ZERO)
+        .unwrap();
+    // the left half is only lit on the top row, the right half all the way down
+    assert_eq!(device.data()[..16], [[0x01; 8], [0xff; 8]].concat()[..]);
+    assert!(device.ram().iter().all(|&b| b == 0));
+}
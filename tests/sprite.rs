@@ -0,0 +1,83 @@
+use nanohat_oled::{BlitOp, Framebuffer, MockInterface, Oled, Sprite};
+
+/// A 10x2 sprite: the top row lit, the bottom row dark
+const BAR: Sprite = Sprite::new(10, 2, &[0xff, 0xc0, 0x00, 0x00]);
+
+/// A 2x2 checkerboard, masked to all but its top right pixel
+const CORNER: Sprite =
+    Sprite::new(2, 2, &[0b1000_0000, 0b0100_0000]).with_mask(&[0b1000_0000, 0b1100_0000]);
+
+#[test]
+fn reads_padded_rows() {
+    assert_eq!(BAR.dimensions(), (10, 2));
+    assert_eq!(BAR.get_pixel(9, 0), Some(true));
+    assert_eq!(BAR.get_pixel(9, 1), Some(false));
+    assert_eq!(BAR.get_pixel(10, 0), None);
+    assert_eq!(CORNER.get_pixel(0, 0), Some(true));
+    assert_eq!(CORNER.get_pixel(1, 0), None);
+}
+
+#[test]
+#[should_panic(expected = "sprite data doesn't match its dimensions")]
+fn rejects_short_data() {
+    Sprite::new(9, 1, &[0xff]);
+}
+
+#[test]
+fn combines_with_each_op() {
+    let lit = |frame: &Framebuffer| -> Vec<(u16, u16)> {
+        let mut lit = Vec::new();
+        for y in 0..2 {
+            for x in 0..10 {
+                if frame.get_pixel(x, y) {
+                    lit.push((x, y));
+                }
+            }
+        }
+        lit
+    };
+    let mut background = Framebuffer::new();
+    background.set_pixel(0, 0, true);
+    background.set_pixel(0, 1, true);
+    background.set_pixel(1, 0, true);
+
+    let mut frame = background.clone();
+    frame.blit_sprite(&CORNER, 0, 0, BlitOp::Copy);
+    // the masked out top right pixel keeps its background
+    assert_eq!(lit(&frame), [(0, 0), (1, 0), (1, 1)]);
+
+    let mut frame = background.clone();
+    frame.blit_sprite(&CORNER, 0, 0, BlitOp::Or);
+    assert_eq!(lit(&frame), [(0, 0), (1, 0), (0, 1), (1, 1)]);
+
+    let mut frame = background.clone();
+    frame.blit_sprite(&CORNER, 0, 0, BlitOp::And);
+    assert_eq!(lit(&frame), [(0, 0), (1, 0)]);
+
+    let mut frame = background.clone();
+    frame.blit_sprite(&CORNER, 0, 0, BlitOp::Xor);
+    assert_eq!(lit(&frame), [(1, 0), (0, 1), (1, 1)]);
+    frame.blit_sprite(&CORNER, 0, 0, BlitOp::Xor);
+    assert_eq!(frame, background);
+}
+
+#[test]
+fn clips_at_the_edges() {
+    let mut frame = Framebuffer::new();
+    frame.blit_sprite(&BAR, 124, 63, BlitOp::Copy);
+    let lit: Vec<u16> = (0..128).filter(|x| frame.get_pixel(*x, 63)).collect();
+    assert_eq!(lit, [124, 125, 126, 127]);
+}
+
+#[test]
+fn sends_only_the_sprite() {
+    let mut bus = MockInterface::new();
+    let mut oled = Oled::new(&mut bus);
+    oled.init().unwrap();
+    oled.blit_sprite(&BAR, 20, 8, BlitOp::Or).unwrap();
+    let mut expected = Framebuffer::new();
+    expected.blit_sprite(&BAR, 20, 8, BlitOp::Copy);
+    assert_eq!(oled.framebuffer(), expected.as_bytes());
+    let data = bus.data();
+    assert_eq!(data[data.len() - 10..], [0x01; 10]);
+}
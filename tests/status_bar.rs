@@ -0,0 +1,86 @@
+use nanohat_oled::{
+    BuiltinFont, Framebuffer, Icon, IconSize, Oled, OledError, Rect, StatusBar, Widget,
+};
+
+mod common;
+use common::NullInterface;
+
+/// Whether the 8x8 cell at `x`, `y` of `frame` shows `icon`
+fn shows(frame: &Framebuffer, icon: Icon, x: u16, y: u16) -> bool {
+    let sprite = icon.sprite(IconSize::Small);
+    (0..8).all(|dy| {
+        (0..8).all(|dx| sprite.get_pixel(dx, dy) == Some(frame.get_pixel(x + dx, y + dy)))
+    })
+}
+
+/// Whether any pixel of `frame` is lit in the columns `from` up to `to`
+fn any_lit(frame: &Framebuffer, from: u16, to: u16) -> bool {
+    (from..to).any(|x| (0..8).any(|y| frame.get_pixel(x, y)))
+}
+
+#[test]
+fn shows_indicators_from_the_left() {
+    let mut status = StatusBar::new();
+    assert!(status.needs_render());
+    let mut frame = Framebuffer::new();
+    frame.fill_rect(0, 0, 128, 16, true);
+    status.render(&mut frame, Rect::new(0, 0, 128, StatusBar::HEIGHT));
+    assert!(!status.needs_render());
+    assert!(!any_lit(&frame, 0, 128));
+    assert!(frame.get_pixel(0, 8));
+
+    status.set_wifi(Some(9));
+    status.set_alert(true);
+    assert!(status.needs_render());
+    status.render(&mut frame, Rect::new(0, 0, 128, StatusBar::HEIGHT));
+    assert!(shows(&frame, Icon::Wifi(3), 0, 0));
+    assert!(shows(&frame, Icon::Warning, 10, 0));
+
+    status.set_link(true);
+    status.render(&mut frame, Rect::new(0, 0, 128, StatusBar::HEIGHT));
+    assert!(shows(&frame, Icon::Link, 10, 0) && shows(&frame, Icon::Warning, 20, 0));
+
+    // nothing changes
+    status.set_link(true);
+    status.set_wifi(Some(3));
+    assert!(!status.needs_render());
+    status.set_wifi(None);
+    status.render(&mut frame, Rect::new(0, 0, 128, StatusBar::HEIGHT));
+    assert!(shows(&frame, Icon::Link, 0, 0));
+}
+
+#[test]
+fn shows_the_time_on_the_right() {
+    let mut status = StatusBar::new();
+    assert!(matches!(
+        status.set_time(24, 0),
+        Err(OledError::InvalidArgument(_))
+    ));
+    assert!(status.set_time(23, 60).is_err());
+    status.set_time(7, 5).unwrap();
+    let mut frame = Framebuffer::new();
+    status.render(&mut frame, Rect::new(0, 0, 128, StatusBar::HEIGHT));
+    let mut expected = Framebuffer::new();
+    expected.draw_text(88, 0, "07:05", &BuiltinFont::MONOSPACE);
+    assert_eq!(frame.as_bytes()[..], expected.as_bytes()[..]);
+
+    status.set_time(7, 5).unwrap();
+    assert!(!status.needs_render());
+    status.clear_time();
+    assert!(status.needs_render());
+    status.render(&mut frame, Rect::new(0, 0, 128, StatusBar::HEIGHT));
+    assert!(!any_lit(&frame, 0, 128));
+}
+
+#[test]
+fn updates_the_top_page_on_its_own() {
+    let mut oled = Oled::new(NullInterface);
+    oled.draw(|frame| frame.fill_rect(0, 0, 128, 64, true))
+        .unwrap();
+    let mut status = StatusBar::new();
+    status.set_alert(true);
+    oled.render_to_pages(&mut status, 0, 0).unwrap();
+    let buffer = oled.framebuffer();
+    assert!(buffer[8..128].iter().all(|column| *column == 0));
+    assert!(buffer[128..].iter().all(|column| *column == 0xff));
+}
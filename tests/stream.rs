@@ -0,0 +1,106 @@
+#![cfg(feature = "std")]
+use nanohat_oled::{Dither, FrameFormat, FrameStream, Framebuffer, Oled, OledConfig, PanelSize};
+use std::io::{self, Cursor, Read};
+use std::thread::sleep;
+use std::time::Duration;
+
+mod common;
+use common::NullInterface;
+
+/// A gray frame, white in the left `lit` columns and black in the rest
+fn gray_frame(lit: usize) -> Vec<u8> {
+    (0..128 * 64)
+        .map(|index| if index % 128 < lit { 255 } else { 0 })
+        .collect()
+}
+
+/// What the display shows with the left `lit` columns lit
+fn lit_columns(lit: u16) -> Framebuffer {
+    let mut frame = Framebuffer::new();
+    frame.fill_rect(0, 0, lit, 64, true);
+    frame
+}
+
+/// A reader that stalls before the frame at `stall`, past the others' due times
+struct Stalling {
+    frames: Cursor<Vec<u8>>,
+    stall: Option<u64>,
+}
+
+impl Read for Stalling {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        if self.stall == Some(self.frames.position() / (128 * 64)) {
+            sleep(Duration::from_millis(300));
+            self.stall = None;
+        }
+        let len = buffer.len().min(7000);
+        self.frames.read(&mut buffer[..len])
+    }
+}
+
+#[test]
+fn plays_gray_frames_until_the_stream_ends() {
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    let mut stream = FrameStream::new(oled);
+    let mut frames = gray_frame(10);
+    frames.extend(gray_frame(20));
+    // cut short, so left out
+    frames.extend(&gray_frame(30)[..100]);
+    stream
+        .play(&frames[..], FrameFormat::Gray(Dither::Threshold(0x80)), 50)
+        .unwrap();
+    assert_eq!((stream.shown(), stream.dropped()), (2, 0));
+    assert_eq!(stream.oled().framebuffer(), lit_columns(20).as_bytes());
+}
+
+#[test]
+fn plays_mono_frames_most_significant_bit_first() {
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    let mut stream = FrameStream::new(oled);
+    let mut frame = [0u8; 16 * 64];
+    frame[0] = 0b1010_0000;
+    frame[16 * 63 + 15] = 0x01;
+    stream.play(&frame[..], FrameFormat::Mono, 50).unwrap();
+    let mut expected = Framebuffer::new();
+    expected.set_pixel(0, 0, true);
+    expected.set_pixel(2, 0, true);
+    expected.set_pixel(127, 63, true);
+    assert_eq!(stream.oled().framebuffer(), expected.as_bytes());
+}
+
+#[test]
+fn drops_frames_held_up_by_a_slow_stream() {
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    let mut stream = FrameStream::new(oled);
+    let frames = (1..=5).flat_map(|frame| gray_frame(frame * 10)).collect();
+    let reader = Stalling {
+        frames: Cursor::new(frames),
+        stall: Some(1),
+    };
+    stream
+        .play(reader, FrameFormat::Gray(Dither::Threshold(0x80)), 20)
+        .unwrap();
+    // held up 300ms at 50ms a frame, the second to fourth are read and dropped
+    assert_eq!((stream.shown(), stream.dropped()), (2, 3));
+    assert_eq!(stream.oled().framebuffer(), lit_columns(50).as_bytes());
+}
+
+#[test]
+fn plays_frames_the_size_of_a_smaller_panel() {
+    let mut oled = Oled::new(NullInterface);
+    oled.init_with(OledConfig::DEFAULT.with_size(PanelSize::Panel128x32))
+        .unwrap();
+    let mut stream = FrameStream::new(oled);
+    let frame: Vec<u8> = (0..128 * 32)
+        .map(|index| if index % 128 < 8 { 255 } else { 0 })
+        .collect();
+    stream
+        .play(&frame[..], FrameFormat::Gray(Dither::FloydSteinberg), 50)
+        .unwrap();
+    let mut expected = Framebuffer::new();
+    expected.fill_rect(0, 0, 8, 32, true);
+    assert_eq!(stream.oled().framebuffer(), expected.as_bytes());
+}
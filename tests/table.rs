@@ -0,0 +1,64 @@
+use nanohat_oled::{Align, Framebuffer, Rect, Size, Table, TableColumn, Widget};
+
+/// Whether any pixel in the 8 rows from `y`, between `left` and `right`, is lit
+fn any_lit(frame: &Framebuffer, y: u16, left: u16, right: u16) -> bool {
+    (y..y + 8).any(|y| (left..right).any(|x| frame.get_pixel(x, y)))
+}
+
+fn columns() -> [TableColumn<'static>; 2] {
+    [
+        TableColumn::new("If", Size::Fixed(48)),
+        TableColumn::new("%", Size::Weight(1)).with_align(Align::Right),
+    ]
+}
+
+#[test]
+fn draws_a_header_in_inverted_text_and_aligns_cells() {
+    let rows = [["eth0", "7"], ["wlan0", "43"]];
+    let mut table = Table::new(columns(), &rows);
+    let mut frame = Framebuffer::new();
+    table.render(&mut frame, Rect::new(0, 0, 128, 64));
+    assert!(!table.needs_render());
+
+    // the header is lit, with its titles cut out of it
+    assert!(frame.get_pixel(127, 0) && frame.get_pixel(60, 7));
+    assert!(!(0..8).all(|y| (0..16).all(|x| frame.get_pixel(x, y))));
+    // "7" is against the right edge, and "eth0" against the left
+    assert!(any_lit(&frame, 8, 120, 128) && !any_lit(&frame, 8, 50, 120));
+    assert!(any_lit(&frame, 8, 0, 8) && !any_lit(&frame, 8, 32, 48));
+    assert!(any_lit(&frame, 16, 112, 120));
+    assert!(!any_lit(&frame, 24, 0, 128));
+
+    table.set_rows(&rows);
+    assert!(table.needs_render());
+}
+
+#[test]
+fn cuts_off_text_at_the_edge_of_its_column() {
+    let rows = [["enx00e04c680001", "100"]];
+    let mut table = Table::new(columns(), &rows).with_header(false).with_gap(8);
+    let mut frame = Framebuffer::new();
+    table.render(&mut frame, Rect::new(0, 0, 128, 64));
+    assert!(any_lit(&frame, 0, 40, 48));
+    // nothing in the gap, and the number is where it would be anyway
+    assert!(!any_lit(&frame, 0, 48, 56));
+    assert!(!any_lit(&frame, 0, 56, 104) && any_lit(&frame, 0, 104, 128));
+}
+
+#[test]
+fn scrolls_through_rows_that_dont_fit() {
+    let rows: Vec<[String; 2]> = (0..10)
+        .map(|row| [format!("p{}", row), row.to_string()])
+        .collect();
+    let mut table = Table::new(columns(), &rows);
+    table.set_first_row(20);
+    assert_eq!(table.first_row(), 9);
+    table.set_first_row(6);
+    assert!(table.needs_render());
+
+    // a header and three rows fit in 28 pixels, with the last row left out
+    let mut frame = Framebuffer::new();
+    table.render(&mut frame, Rect::new(0, 0, 128, 28));
+    assert!(any_lit(&frame, 8, 0, 128) && any_lit(&frame, 16, 0, 128));
+    assert!(!any_lit(&frame, 24, 0, 128));
+}
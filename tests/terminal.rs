@@ -0,0 +1,82 @@
+use nanohat_oled::{Framebuffer, Oled, Rotation, TerminalSimulator};
+
+/// The character shown for the pixels at `x`, `y` and the row below it
+fn cell(simulator: &TerminalSimulator<Vec<u8>>, x: u16, y: u16) -> char {
+    // a line for the top border, then a border character at the start of each line
+    let line = simulator
+        .frame()
+        .lines()
+        .nth(usize::from(y / 2) + 1)
+        .unwrap();
+    line.chars().nth(usize::from(x) + 1).unwrap()
+}
+
+#[test]
+fn draws_pixels_as_half_blocks() {
+    let mut oled = Oled::new(TerminalSimulator::from_writer(Vec::new()));
+    oled.init().unwrap();
+    let mut frame = Framebuffer::new();
+    frame.set_pixel(0, 0, true);
+    frame.set_pixel(1, 1, true);
+    frame.set_pixel(2, 0, true);
+    frame.set_pixel(2, 1, true);
+    oled.flush(&frame).unwrap();
+
+    let simulator = oled.into_interface();
+    let lines: Vec<&str> = simulator.frame().lines().collect();
+    assert_eq!(lines.len(), 34);
+    assert!(lines.iter().all(|line| line.chars().count() == 130));
+    assert!(lines[0].starts_with('┌') && lines[33].ends_with('┘'));
+    assert_eq!(&lines[1].chars().take(5).collect::<String>(), "│▀▄█ ");
+    assert!(lines[2..33]
+        .iter()
+        .all(|line| line.trim_matches('│').trim().is_empty()));
+}
+
+#[test]
+fn redraws_in_place_only_when_the_picture_changes() {
+    let mut oled = Oled::new(TerminalSimulator::from_writer(Vec::new()));
+    oled.init().unwrap();
+    let drawn = oled.into_interface();
+    // the empty panel, as soon as anything is sent
+    assert_eq!(drawn.frames(), 1);
+    let mut oled = Oled::new(drawn);
+
+    let mut frame = Framebuffer::new();
+    frame.fill_rect(10, 10, 4, 4, true);
+    oled.flush(&frame).unwrap();
+    oled.flush(&frame).unwrap();
+    oled.set_contrast(0x10).unwrap();
+    let simulator = oled.into_interface();
+    assert_eq!(simulator.frames(), 2);
+    assert_eq!(cell(&simulator, 10, 10), '█');
+
+    let out = String::from_utf8(simulator.into_writer()).unwrap();
+    assert!(out.starts_with("\x1b[2J\x1b[H┌"));
+    assert_eq!(out.matches("\x1b[H").count(), 2);
+}
+
+#[test]
+fn follows_display_commands() {
+    let mut simulator = TerminalSimulator::from_writer(Vec::new());
+    // blank until turned on
+    Oled::new(&mut simulator).all_on(true).unwrap();
+    assert_eq!(cell(&simulator, 0, 0), ' ');
+
+    let mut simulator = TerminalSimulator::from_writer(Vec::new());
+    let mut oled = Oled::new(&mut simulator);
+    oled.init().unwrap();
+    let mut frame = Framebuffer::new();
+    frame.set_pixel(0, 0, true);
+    frame.set_pixel(0, 1, true);
+    oled.flush(&frame).unwrap();
+    oled.set_rotation(Rotation::Rotate180).unwrap();
+    assert_eq!(cell(&simulator, 127, 62), '█');
+
+    Oled::new(&mut simulator).send_command(0xa7).unwrap();
+    assert_eq!(cell(&simulator, 127, 62), ' ');
+    assert_eq!(cell(&simulator, 0, 0), '█');
+
+    Oled::new(&mut simulator).send_command(0xae).unwrap();
+    assert_eq!(cell(&simulator, 0, 0), ' ');
+}
@@ -0,0 +1,69 @@
+#![cfg(feature = "std")]
+use nanohat_oled::{CaptureDevice, Oled, OledError, OledInterface, Pattern};
+use std::time::Duration;
+
+#[test]
+fn patterns_light_the_expected_pixels() {
+    let mut device = CaptureDevice::new();
+    let mut oled = Oled::new(&mut device);
+    oled.init().unwrap();
+    oled.show_test_pattern(Pattern::Checkerboard).unwrap();
+    assert!(device.get_pixel(0, 0) && device.get_pixel(8, 8));
+    assert!(!device.get_pixel(8, 0) && !device.get_pixel(7, 8));
+
+    let mut oled = Oled::new(&mut device);
+    oled.init().unwrap();
+    oled.show_test_pattern(Pattern::Border).unwrap();
+    assert!(device.get_pixel(0, 30) && device.get_pixel(127, 63) && device.get_pixel(64, 0));
+    assert!(!device.get_pixel(1, 1) && !device.get_pixel(126, 62));
+
+    let mut oled = Oled::new(&mut device);
+    oled.init().unwrap();
+    oled.show_test_pattern(Pattern::Gradient).unwrap();
+    let lit = |range: std::ops::Range<u16>| {
+        range
+            .flat_map(|x| (0..64).map(move |y| (x, y)))
+            .filter(|(x, y)| device.get_pixel(*x, *y))
+            .count()
+    };
+    assert!(lit(0..8) < lit(60..68) && lit(60..68) < lit(120..128));
+}
+
+/// An interface with nothing on the other end
+struct Missing;
+
+impl OledInterface for Missing {
+    fn send_command(&mut self, _command: u8) -> Result<(), OledError> {
+        Err(OledError::Interface)
+    }
+
+    fn send_data(&mut self, _data: &[u8]) -> Result<(), OledError> {
+        Err(OledError::Interface)
+    }
+}
+
+#[test]
+fn passes_and_puts_back_what_was_shown() {
+    let mut device = CaptureDevice::new();
+    let shown = {
+        let mut oled = Oled::new(&mut device);
+        oled.init().unwrap();
+        oled.put_string("Hello").unwrap();
+        let shown = *oled.framebuffer();
+        let report = oled.self_test(Duration::ZERO);
+        assert!(report.passed(), "{:?}", report);
+        assert_eq!(report.steps, 10);
+        assert!(!oled.is_inverted());
+        shown
+    };
+    assert_eq!(device.ram()[..], shown[..]);
+    assert!(device.is_lit(1, 1) && !device.is_lit(127, 63));
+}
+
+#[test]
+fn every_step_fails_without_a_display() {
+    let report = Oled::new(Missing).self_test(Duration::ZERO);
+    assert!(!report.passed());
+    assert_eq!(report.failures, report.steps);
+    assert!(matches!(report.last_error, Some(OledError::Interface)));
+}
@@ -0,0 +1,218 @@
+use nanohat_oled::{
+    AddressingMode, Oled, OledConfig, OledError, OledInterface, Overflow, PanelSize, Rotation,
+    TextCursor,
+};
+use std::fmt;
+
+mod common;
+use common::{rendered, NullInterface};
+
+fn lines(text: &str) -> Vec<&str> {
+    Oled::new(NullInterface).layout(text).collect()
+}
+
+fn word_lines(text: &str) -> Vec<&str> {
+    Oled::new(NullInterface).layout_words(text).collect()
+}
+
+#[test]
+fn wraps_at_sixteen_columns() {
+    assert_eq!(lines("Temperature: 21.5C"), ["Temperature: 21.", "5C"]);
+    assert_eq!(lines("exactly sixteen!"), ["exactly sixteen!"]);
+}
+
+#[test]
+fn honors_newlines() {
+    assert_eq!(lines("a\nb"), ["a", "b"]);
+    assert_eq!(
+        lines("exactly sixteen!\nnext"),
+        ["exactly sixteen!", "next"]
+    );
+    assert_eq!(lines("trailing\n"), ["trailing", ""]);
+    assert_eq!(lines(""), [""]);
+}
+
+#[test]
+fn measures_in_pixels() {
+    let oled = Oled::new(NullInterface);
+    assert_eq!(oled.measure_string("Hi"), (16, 8));
+    assert_eq!(oled.measure_string("one\nthree"), (40, 16));
+    assert_eq!(oled.measure_string("Temperature: 21.5C"), (128, 16));
+}
+
+#[test]
+fn wraps_to_the_display_as_turned() {
+    let mut oled = Oled::new(NullInterface);
+    oled.set_rotation(Rotation::Rotate90).unwrap();
+    let lines: Vec<&str> = oled.layout("ABCDEFGHIJKLMNOPQRST").collect();
+    assert_eq!(lines, ["ABCDEFGH", "IJKLMNOP", "QRST"]);
+    let lines: Vec<&str> = oled.layout_words("Temperature: 21.5C").collect();
+    assert_eq!(lines, ["Temperat", "ure:", "21.5C"]);
+    assert_eq!(oled.measure_string("ABCDEFGHIJKL"), (64, 16));
+
+    let mut oled = Oled::new(NullInterface);
+    oled.init_with(OledConfig::DEFAULT.with_size(PanelSize::Panel96x16))
+        .unwrap();
+    let lines: Vec<&str> = oled.layout("ABCDEFGHIJKLMNOPQRST").collect();
+    assert_eq!(lines, ["ABCDEFGHIJKL", "MNOPQRST"]);
+    assert_eq!(oled.measure_string("ABCDEFGHIJKLM"), (96, 16));
+}
+
+#[test]
+fn wraps_at_word_boundaries() {
+    assert_eq!(word_lines("Temperature: 21.5C"), ["Temperature:", "21.5C"]);
+    assert_eq!(
+        word_lines("exactly sixteen! next"),
+        ["exactly sixteen!", "next"]
+    );
+    assert_eq!(
+        word_lines("a supercalifragilistic word"),
+        ["a", "supercalifragili", "stic word"]
+    );
+    assert_eq!(word_lines("one\ntwo"), ["one", "two"]);
+}
+
+#[test]
+fn puts_text_on_rows() {
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    oled.put_text("Status:\nall systems go", Overflow::Clip)
+        .unwrap();
+    // carries on from where the last text left off
+    oled.put_text(" today", Overflow::Clip).unwrap();
+    assert_eq!(
+        *oled.framebuffer(),
+        rendered(&["Status:", "all systems go", "today"])
+    );
+}
+
+#[test]
+fn clips_or_scrolls_past_the_last_row() {
+    let text = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10";
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    oled.put_text(text, Overflow::Clip).unwrap();
+    assert_eq!(
+        *oled.framebuffer(),
+        rendered(&["1", "2", "3", "4", "5", "6", "7", "8"])
+    );
+
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    oled.put_text(text, Overflow::Scroll).unwrap();
+    assert_eq!(
+        *oled.framebuffer(),
+        rendered(&["3", "4", "5", "6", "7", "8", "9", "10"])
+    );
+    // still in page mode, so writing text at the cursor keeps working
+    oled.put_string("!").unwrap();
+    assert_eq!(
+        *oled.framebuffer(),
+        rendered(&["3", "4", "5", "6", "7", "8", "9", "10!"])
+    );
+}
+
+#[test]
+fn puts_text_in_portrait() {
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    oled.set_rotation(Rotation::Rotate90).unwrap();
+    oled.put_text("portrait is narrow", Overflow::Clip).unwrap();
+    let mut expected = Oled::new(NullInterface);
+    expected.init().unwrap();
+    expected.set_rotation(Rotation::Rotate90).unwrap();
+    for (row, text) in ["portrait", "is", "narrow"].iter().enumerate() {
+        expected.set_text_xy(0, row as u8).unwrap();
+        expected.put_string(text).unwrap();
+    }
+    assert_eq!(oled.framebuffer(), expected.framebuffer());
+}
+
+/// An interface whose data writes always fail
+struct BrokenData;
+
+impl OledInterface for BrokenData {
+    fn send_command(&mut self, _command: u8) -> Result<(), OledError> {
+        Ok(())
+    }
+
+    fn send_data(&mut self, _data: &[u8]) -> Result<(), OledError> {
+        Err(OledError::Interface)
+    }
+}
+
+#[test]
+fn formats_with_write_macros() {
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    writeln!(oled, "Temp: {:.1}C", 21.54).unwrap();
+    write!(oled, "{} of {}", 3, 4).unwrap();
+    assert_eq!(*oled.framebuffer(), rendered(&["Temp: 21.5C", "3 of 4"]));
+
+    // also usable through the trait, eg by generic code
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    fmt::Write::write_fmt(&mut oled, format_args!("{}\n{}", "a", "b")).unwrap();
+    assert_eq!(*oled.framebuffer(), rendered(&["a", "b"]));
+}
+
+#[test]
+fn write_macros_return_display_errors() {
+    struct Unformattable;
+
+    impl fmt::Display for Unformattable {
+        fn fmt(&self, _f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            Err(fmt::Error)
+        }
+    }
+
+    let mut oled = Oled::new(BrokenData);
+    assert!(matches!(write!(oled, "x"), Err(OledError::Interface)));
+    let mut oled = Oled::new(NullInterface);
+    assert!(matches!(
+        write!(oled, "{}", Unformattable),
+        Err(OledError::InvalidArgument(_))
+    ));
+}
+
+#[test]
+fn tracks_the_cursor_as_text_is_written() {
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    oled.set_text_xy(3, 2).unwrap();
+    assert_eq!(oled.cursor(), (3, 2));
+    let written = oled.put_string("Uptime: ").unwrap();
+    let expected = TextCursor {
+        column: 11,
+        row: 2,
+        wraps: 0,
+    };
+    assert_eq!(written, expected);
+    assert_eq!(oled.cursor(), (11, 2));
+    assert_eq!(oled.put_string("").unwrap(), expected);
+
+    // text off the end of a row carries on at the start of the next
+    let written = oled.put_string("3 days, 4 hours").unwrap();
+    assert_eq!((written.column, written.row, written.wraps), (10, 3, 1));
+    // or of the same one, in page addressing
+    oled.set_addressing_mode(AddressingMode::Page).unwrap();
+    oled.set_text_xy(15, 7).unwrap();
+    let written = oled.put_char_inverted('!').unwrap();
+    assert_eq!((written.column, written.row, written.wraps), (0, 7, 1));
+}
+
+#[test]
+fn tracks_the_cursor_in_portrait() {
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    oled.set_rotation(Rotation::Rotate90).unwrap();
+    oled.set_text_xy(6, 15).unwrap();
+    let written = oled.put_string("abcd").unwrap();
+    assert_eq!((written.column, written.row, written.wraps), (2, 0, 1));
+    assert_eq!(oled.cursor(), (2, 0));
+    // text carries on where it left off, after the cursor was moved away
+    oled.set_text_xy(0, 4).unwrap();
+    oled.put_string("elsewhere").unwrap();
+    oled.set_text_xy(written.column, written.row).unwrap();
+    assert_eq!(oled.put_char('e').unwrap().column, 3);
+}
@@ -0,0 +1,95 @@
+use nanohat_oled::{Framebuffer, MockInterface, Oled, OledError, OLED_WIDTH};
+
+const WIDTH: usize = OLED_WIDTH as usize;
+
+#[test]
+fn spans_two_pages_when_unaligned() {
+    let mut bus = MockInterface::new();
+    let mut oled = Oled::new(&mut bus);
+    oled.init().unwrap();
+    oled.put_string_at(10, 20, "A").unwrap();
+    let framebuffer = oled.framebuffer();
+    // 'A' is 0x00, 0x7E, 0x09, 0x09, 0x09, 0x7E, 0x00, 0x00, shifted down 4 rows
+    assert_eq!(framebuffer[2 * WIDTH + 11], 0xe0);
+    assert_eq!(framebuffer[3 * WIDTH + 11], 0x07);
+    assert_eq!(framebuffer[2 * WIDTH + 12], 0x90);
+    assert_eq!(framebuffer[3 * WIDTH + 12], 0x00);
+    assert_eq!(framebuffer.iter().filter(|byte| **byte != 0).count(), 7);
+}
+
+#[test]
+fn only_sends_affected_region() {
+    let mut bus = MockInterface::new();
+    Oled::new(&mut bus).put_string_at(120, 60, "Hi").unwrap();
+    // switches to horizontal addressing, then clipped to 8 columns and the last page
+    assert_eq!(
+        bus.commands(),
+        [0x20, 0x00, 0x21, 120, 127, 0x22, 7, 7, 0x21, 0, 127, 0x22, 0, 7]
+    );
+    assert_eq!(bus.data().len(), 8);
+}
+
+#[test]
+fn keeps_surrounding_pixels() {
+    let mut bus = MockInterface::new();
+    let mut oled = Oled::new(&mut bus);
+    oled.init().unwrap();
+    oled.draw_fn(|_, _| true).unwrap();
+    oled.put_string_at(0, 4, " ").unwrap();
+    let framebuffer = oled.framebuffer();
+    assert_eq!(framebuffer[0], 0x0f);
+    assert_eq!(framebuffer[WIDTH], 0xf0);
+    assert_eq!(framebuffer[8], 0xff);
+    assert_eq!(framebuffer[WIDTH + 8], 0xff);
+}
+
+#[test]
+fn cursor_writes_still_cover_whole_screen_afterwards() {
+    let mut bus = MockInterface::new();
+    let mut oled = Oled::new(&mut bus);
+    oled.init().unwrap();
+    oled.put_string_at(3, 3, "x").unwrap();
+    oled.set_text_xy(15, 0).unwrap();
+    oled.put_string("!!").unwrap();
+    assert_eq!(oled.framebuffer()[15 * 8 + 2], 0x5f);
+    assert_eq!(oled.framebuffer()[WIDTH + 2], 0x5f);
+}
+
+#[test]
+fn scales_glyphs_up() {
+    let mut oled = Oled::new(MockInterface::new());
+    oled.init().unwrap();
+    oled.put_string_scaled(0, 0, "-", 1).unwrap();
+    let mut plain = Oled::new(MockInterface::new());
+    plain.init().unwrap();
+    plain.put_string("-").unwrap();
+    assert_eq!(oled.framebuffer(), plain.framebuffer());
+
+    // '-' lights row 3 of columns 1 to 5
+    oled.put_string_scaled(100, 40, "-", 3).unwrap();
+    let frame = Framebuffer::from_bytes(*oled.framebuffer());
+    let lit: Vec<(u16, u16)> = (40..64)
+        .flat_map(|y| (100..128).map(move |x| (x, y)))
+        .filter(|(x, y)| frame.get_pixel(*x, *y))
+        .collect();
+    let expected: Vec<(u16, u16)> = (49..52)
+        .flat_map(|y| (103..118).map(move |x| (x, y)))
+        .collect();
+    assert_eq!(lit, expected);
+    assert!(matches!(
+        oled.put_string_scaled(0, 0, "-", 0),
+        Err(OledError::InvalidArgument(_))
+    ));
+}
+
+#[test]
+fn clips_scaled_text() {
+    let mut bus = MockInterface::new();
+    let mut oled = Oled::new(&mut bus);
+    oled.init().unwrap();
+    oled.put_string_scaled(112, 48, "--", 2).unwrap();
+    let frame = Framebuffer::from_bytes(*oled.framebuffer());
+    let lit = (0..128).filter(|x| frame.get_pixel(*x, 54)).count();
+    // only the first character fits, and only its columns 1 to 5
+    assert_eq!(lit, 10);
+}
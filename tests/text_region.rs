@@ -0,0 +1,56 @@
+use nanohat_oled::{Align, BuiltinFont, Framebuffer, Oled, TextRegion};
+
+mod common;
+use common::NullInterface;
+
+/// A full block, which lights its whole 8x8 cell in the monospace font
+const BLOCK: &str = "\u{2588}";
+
+/// The range of columns lit in the top row, and how many rows are lit in the first of them
+fn lit_span(frame: &Framebuffer) -> (Vec<u16>, usize) {
+    let columns: Vec<u16> = (0..128).filter(|x| frame.get_pixel(*x, 0)).collect();
+    let rows = columns
+        .first()
+        .map_or(0, |x| (0..64).filter(|y| frame.get_pixel(*x, *y)).count());
+    (columns, rows)
+}
+
+#[test]
+fn aligns_lines_in_the_region() {
+    let text = BLOCK.repeat(2);
+    let font = BuiltinFont::MONOSPACE;
+    for (align, start) in [(Align::Left, 10), (Align::Center, 22), (Align::Right, 34)] {
+        let mut frame = Framebuffer::new();
+        let region = TextRegion::new(10, 0, 40, 8).with_align(align);
+        assert!(frame.draw_text_in(&region, &text, &font));
+        assert_eq!(lit_span(&frame), ((start..start + 16).collect(), 8));
+    }
+}
+
+#[test]
+fn wraps_and_clips_to_the_region() {
+    let font = BuiltinFont::MONOSPACE;
+    let mut frame = Framebuffer::new();
+    frame.set_pixel(20, 0, true);
+    frame.set_pixel(30, 0, true);
+    frame.set_pixel(100, 20, true);
+    // three cells wide, so each pair of blocks gets a line; the third pair doesn't fit
+    let region = TextRegion::new(0, 0, 24, 12);
+    let text = [BLOCK, BLOCK, " ", BLOCK, BLOCK, " ", BLOCK, BLOCK].concat();
+    assert!(!frame.draw_text_in(&region, &text, &font));
+    // the second line is cut off at the bottom of the region
+    assert_eq!(lit_span(&frame), ((0..16).chain([30]).collect(), 12));
+    assert!(!frame.get_pixel(20, 0));
+    assert!(frame.get_pixel(100, 20));
+}
+
+#[test]
+fn puts_centred_text_on_the_display() {
+    let mut oled = Oled::new(NullInterface);
+    let region = TextRegion::new(0, 8, 128, 8).with_align(Align::Center);
+    oled.put_string_in(&region, BLOCK, &BuiltinFont::MONOSPACE)
+        .unwrap();
+    let lit: Vec<usize> = (0..1024).filter(|i| oled.framebuffer()[*i] != 0).collect();
+    assert_eq!(lit, (128 + 60..128 + 68).collect::<Vec<_>>());
+    assert!(lit.iter().all(|i| oled.framebuffer()[*i] == 0xff));
+}
@@ -0,0 +1,80 @@
+#![cfg(feature = "trace")]
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use nanohat_oled::{Framebuffer, MockInterface, Oled, TraceInterface};
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+/// A logger keeping the messages of the records logged by the tracer
+struct Recorder(Mutex<Vec<String>>);
+
+impl Log for Recorder {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= Level::Trace
+    }
+
+    fn log(&self, record: &Record) {
+        if record.target() == "nanohat_oled::trace" {
+            self.0.lock().unwrap().push(record.args().to_string());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static RECORDER: Recorder = Recorder(Mutex::new(Vec::new()));
+
+/// A writer whose bytes can be looked at once it's been handed over
+#[derive(Clone, Default)]
+struct Shared(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Shared {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(bytes);
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn logs_commands_and_summarises_data() {
+    log::set_logger(&RECORDER).unwrap();
+    log::set_max_level(LevelFilter::Trace);
+    let mut oled = Oled::new(TraceInterface::new(MockInterface::new()));
+    oled.init().unwrap();
+    RECORDER.0.lock().unwrap().clear();
+    let mut frame = Framebuffer::new();
+    frame.fill_rect(8, 8, 16, 16, true);
+    oled.flush(&frame).unwrap();
+    oled.set_contrast(0x40).unwrap();
+    let logged = RECORDER.0.lock().unwrap().clone();
+    assert_eq!(
+        logged,
+        [
+            "command [21, 08, 17]: SetColumnAddress(8, 23)",
+            "command [22, 01, 02]: SetPageAddress(1, 2)",
+            "data: 32 bytes, pages 1-2, columns 8-23",
+            "command [21, 00, 7f]: SetColumnAddress(0, 127)",
+            "command [22, 00, 07]: SetPageAddress(0, 7)",
+            "command [81, 40]: SetContrast(64)",
+        ]
+    );
+}
+
+#[test]
+fn captures_each_transaction() {
+    let capture = Shared::default();
+    let mut oled =
+        Oled::new(TraceInterface::new(MockInterface::new()).with_capture(capture.clone()));
+    oled.send_command(0xaf).unwrap();
+    oled.send_commands(&[0x81, 0x40]).unwrap();
+    oled.send_array_data(&[0xff, 0x00][..]).unwrap();
+    assert_eq!(
+        *capture.0.lock().unwrap(),
+        [0x00, 1, 0, 0xaf, 0x00, 2, 0, 0x81, 0x40, 0x40, 2, 0, 0xff, 0x00]
+    );
+    let mock = oled.into_interface().into_interface();
+    assert_eq!(mock.data(), [0xff, 0x00]);
+}
@@ -0,0 +1,61 @@
+#![cfg(feature = "std")]
+use nanohat_oled::{Effect, Framebuffer, MockInterface, Oled, OledError, ScreenTransition};
+use std::time::Duration;
+
+/// The left half lit, and the right half lit
+fn halves() -> (Framebuffer, Framebuffer) {
+    let mut left = Framebuffer::new();
+    left.fill_rect(0, 0, 64, 64, true);
+    let mut right = Framebuffer::new();
+    right.fill_rect(64, 0, 64, 64, true);
+    (left, right)
+}
+
+fn shown(oled: &Oled<MockInterface>) -> Framebuffer {
+    Framebuffer::from_bytes(*oled.framebuffer())
+}
+
+#[test]
+fn each_effect_runs_from_one_screen_to_the_other() {
+    let (left, right) = halves();
+    for effect in [Effect::SlideLeft, Effect::Wipe, Effect::Dissolve] {
+        let transition = ScreenTransition::new(&left, &right, effect, Duration::from_secs(1));
+        assert_eq!(transition.frame(0), left, "{:?}", effect);
+        let last = if effect == Effect::Dissolve { 256 } else { 128 };
+        assert_eq!(transition.frame(last), right, "{:?}", effect);
+    }
+    let dissolve = ScreenTransition::new(&left, &right, Effect::Dissolve, Duration::ZERO);
+    let half = dissolve.frame(128);
+    let lit = (0..64).filter(|y| half.get_pixel(100, *y)).count();
+    assert!(lit > 16 && lit < 48, "{}", lit);
+}
+
+#[test]
+fn ticks_step_through_by_time_and_stop_when_done() {
+    let (left, _) = halves();
+    let mut lit = Framebuffer::new();
+    lit.fill_rect(0, 0, 128, 64, true);
+    let mut oled = Oled::new(MockInterface::new());
+    oled.init().unwrap();
+    let mut slide = ScreenTransition::new(&left, &lit, Effect::SlideLeft, Duration::from_secs(1));
+    let wait = slide.tick(&mut oled, Duration::ZERO).unwrap();
+    assert_eq!(shown(&oled), left);
+    assert_eq!(wait, Some(Duration::from_secs(1) / 128));
+    // halfway, the unlit right of the old screen has reached the left edge
+    slide.tick(&mut oled, Duration::from_millis(500)).unwrap();
+    let frame = shown(&oled);
+    assert!(!frame.get_pixel(0, 0) && !frame.get_pixel(63, 63));
+    assert!(frame.get_pixel(64, 0) && frame.get_pixel(127, 63));
+    assert_eq!(slide.tick(&mut oled, Duration::from_secs(5)).unwrap(), None);
+    assert!(slide.is_finished());
+    assert_eq!(shown(&oled), lit);
+}
+
+#[test]
+fn screens_must_match_the_display_orientation() {
+    let (left, _) = halves();
+    let portrait = Framebuffer::portrait();
+    let mut oled = Oled::new(MockInterface::new());
+    let result = oled.transition(&left, &portrait, Effect::Wipe, Duration::ZERO);
+    assert!(matches!(result, Err(OledError::InvalidDimensions)));
+}
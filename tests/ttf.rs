@@ -0,0 +1,221 @@
+#![cfg(feature = "ttf")]
+use std::convert::TryInto;
+
+use nanohat_oled::{Framebuffer, Oled, OledError, TtfFont};
+
+mod common;
+use common::{lit, NullInterface};
+
+/// A glyph with one contour of (x, y, on the curve) points, in 16-bit deltas
+fn simple(points: &[(i16, i16, bool)]) -> Vec<u8> {
+    let mut glyph = Vec::new();
+    // one contour, a bounding box nothing reads, its last point, and no instructions
+    for value in [1, 0, 0, 0, 0, points.len() as i16 - 1, 0] {
+        glyph.extend_from_slice(&value.to_be_bytes());
+    }
+    glyph.extend(points.iter().map(|(_, _, on)| u8::from(*on)));
+    for axis in 0..2 {
+        let mut last = 0;
+        for (x, y, _) in points {
+            let value = if axis == 0 { *x } else { *y };
+            glyph.extend_from_slice(&(value - last).to_be_bytes());
+            last = value;
+        }
+    }
+    glyph
+}
+
+/// A glyph made of `component`, moved by `dx`, `dy`
+fn composite(component: u16, dx: i16, dy: i16) -> Vec<u8> {
+    let mut glyph = Vec::new();
+    for value in [-1i16, 0, 0, 0, 0] {
+        glyph.extend_from_slice(&value.to_be_bytes());
+    }
+    // offsets as words, and as x and y values
+    glyph.extend_from_slice(&0x0003u16.to_be_bytes());
+    glyph.extend_from_slice(&component.to_be_bytes());
+    glyph.extend_from_slice(&dx.to_be_bytes());
+    glyph.extend_from_slice(&dy.to_be_bytes());
+    glyph
+}
+
+fn be(values: &[u16]) -> Vec<u8> {
+    values
+        .iter()
+        .flat_map(|value| value.to_be_bytes())
+        .collect()
+}
+
+/// A font with 1000 units per em, handing 'A' a 500-unit square on the
+/// baseline, 'B' the same square raised 300 units, and 'O' a round blob,
+/// each 700 units wide
+fn font() -> Vec<u8> {
+    let square = simple(&[
+        (100, 0, true),
+        (100, 500, true),
+        (600, 500, true),
+        (600, 0, true),
+    ]);
+    let blob = simple(&[
+        (500, 0, false),
+        (0, 500, false),
+        (500, 1000, false),
+        (1000, 500, false),
+    ]);
+    let glyphs = [Vec::new(), square, composite(1, 0, 300), blob];
+    let mut glyf = Vec::new();
+    let mut loca = Vec::new();
+    for glyph in &glyphs {
+        loca.extend_from_slice(&(glyf.len() as u32).to_be_bytes());
+        glyf.extend_from_slice(glyph);
+    }
+    loca.extend_from_slice(&(glyf.len() as u32).to_be_bytes());
+
+    let mut head = vec![0; 54];
+    head[18..20].copy_from_slice(&1000u16.to_be_bytes());
+    head[50..52].copy_from_slice(&1u16.to_be_bytes());
+    let mut hhea = vec![0; 36];
+    hhea[4..6].copy_from_slice(&800i16.to_be_bytes());
+    hhea[6..8].copy_from_slice(&(-200i16).to_be_bytes());
+    hhea[34..36].copy_from_slice(&4u16.to_be_bytes());
+    let maxp = be(&[0, 0x5000, 4]);
+    let hmtx = be(&[0, 0, 700, 0, 700, 0, 700, 0]);
+    // segments 'A'-'B', 'O' and the final one, with their glyphs found by deltas
+    let mut cmap = be(&[0, 1, 3, 1, 0, 12]);
+    cmap.extend(be(&[4, 40, 0, 6, 0, 0, 0]));
+    cmap.extend(be(&[0x42, 0x4f, 0xffff, 0]));
+    cmap.extend(be(&[0x41, 0x4f, 0xffff]));
+    cmap.extend(be(&[1u16.wrapping_sub(0x41), 3u16.wrapping_sub(0x4f), 1]));
+    cmap.extend(be(&[0, 0, 0]));
+
+    let tables: [(&[u8; 4], Vec<u8>); 7] = [
+        (b"cmap", cmap),
+        (b"glyf", glyf),
+        (b"head", head),
+        (b"hhea", hhea),
+        (b"hmtx", hmtx),
+        (b"loca", loca),
+        (b"maxp", maxp),
+    ];
+    let mut file = be(&[1, 0, tables.len() as u16, 0, 0, 0]);
+    let mut offset = 12 + tables.len() * 16;
+    for (tag, data) in &tables {
+        file.extend_from_slice(&tag[..]);
+        file.extend_from_slice(&[0; 4]);
+        file.extend_from_slice(&(offset as u32).to_be_bytes());
+        file.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        offset += data.len();
+    }
+    for (_, data) in &tables {
+        file.extend_from_slice(data);
+    }
+    file
+}
+
+fn rect(x: u16, y: u16, width: u16, height: u16) -> Vec<(u16, u16)> {
+    (y..y + height)
+        .flat_map(|y| (x..x + width).map(move |x| (x, y)))
+        .collect()
+}
+
+#[test]
+fn measures_text() {
+    let data = font();
+    let font = TtfFont::from_bytes(&data).unwrap();
+    assert_eq!(font.line_height(10.0), 10);
+    assert_eq!(font.text_width("AB", 10.0), 14);
+    // characters the font doesn't have use its empty glyph 0
+    assert_eq!(font.text_width("A?", 10.0), 7);
+}
+
+#[test]
+fn draws_outlines_and_components() {
+    let data = font();
+    let font = TtfFont::from_bytes(&data).unwrap();
+    let mut frame = Framebuffer::new();
+    frame.fill_rect(0, 0, 20, 12, true);
+    assert_eq!(
+        frame.draw_text_ttf(0, 2, "AB", &font, 10.0, 128).unwrap(),
+        14
+    );
+    // the baseline is 8 pixels down the line, the squares 5 pixels wide and tall,
+    // and the line's 10 rows are cleared first, leaving what is above and beyond it
+    let mut expected = rect(0, 0, 20, 2);
+    expected.extend(rect(8, 2, 5, 3));
+    expected.extend(rect(14, 2, 6, 10));
+    expected.extend(rect(1, 5, 5, 5));
+    expected.extend(rect(8, 5, 5, 2));
+    expected.sort_by_key(|(x, y)| (*y, *x));
+    assert_eq!(lit(&frame), expected);
+}
+
+#[test]
+fn draws_curves() {
+    let data = font();
+    let font = TtfFont::from_bytes(&data).unwrap();
+    let mut frame = Framebuffer::new();
+    frame.draw_text_ttf(0, 0, "O", &font, 20.0, 128).unwrap();
+    let pixels = lit(&frame);
+    // the blob spans 20 pixels up from the baseline at 16, rounded at the corners
+    assert!(frame.get_pixel(10, 6));
+    assert!(pixels.iter().all(|(x, y)| *x < 20 && *y < 16));
+    for corner in [(0, 0), (19, 0), (0, 15), (19, 15), (2, 2)] {
+        assert!(!frame.get_pixel(corner.0, corner.1));
+    }
+    assert!(pixels.iter().all(|(x, y)| frame.get_pixel(19 - x, *y)));
+    // a lower threshold lights pixels the outline only partly covers
+    let mut bold = Framebuffer::new();
+    bold.draw_text_ttf(0, 0, "O", &font, 20.0, 1).unwrap();
+    assert!(lit(&bold).len() > pixels.len());
+}
+
+#[test]
+fn puts_text_on_the_display() {
+    let data = font();
+    let font = TtfFont::from_bytes(&data).unwrap();
+    let mut frame = Framebuffer::new();
+    frame
+        .draw_text_ttf(30, 20, "BOA", &font, 16.0, 128)
+        .unwrap();
+    let mut oled = Oled::new(NullInterface);
+    oled.init().unwrap();
+    oled.put_string_ttf(30, 20, "BOA", &font, 16.0, 128)
+        .unwrap();
+    assert_eq!(frame.as_bytes(), oled.framebuffer());
+    assert!(oled.put_string_ttf(0, 0, "A", &font, 0.0, 128).is_err());
+    assert!(oled
+        .put_string_ttf(0, 0, "A", &font, f32::NAN, 128)
+        .is_err());
+}
+
+#[test]
+fn rejects_bad_fonts() {
+    assert!(TtfFont::from_bytes(b"not a font").is_err());
+    assert!(TtfFont::from_bytes(b"OTTO\0\0\0\0\0\0\0\0").is_err());
+    let data = font();
+    assert!(TtfFont::from_bytes(&data[..100]).is_err());
+    // a glyph pointing past the end of the outlines fails to draw, leaving the frame alone
+    let mut broken = data.clone();
+    let loca = broken.windows(4).position(|tag| tag == b"loca").unwrap();
+    let offset = u32::from_be_bytes(broken[loca + 8..loca + 12].try_into().unwrap()) as usize;
+    broken[offset + 8..offset + 12].copy_from_slice(&0xffffu32.to_be_bytes());
+    let font = TtfFont::from_bytes(&broken).unwrap();
+    let mut frame = Framebuffer::new();
+    assert!(frame.draw_text_ttf(0, 0, "A", &font, 10.0, 128).is_err());
+    assert!(lit(&frame).is_empty());
+}
+
+#[test]
+fn rejects_sizes_too_large_to_draw() {
+    let data = font();
+    let font = TtfFont::from_bytes(&data).unwrap();
+    let mut frame = Framebuffer::new();
+    for px_size in [1.0e6, 1.0e30, f32::MAX] {
+        let result = frame.draw_text_ttf(0, 0, "A", &font, px_size, 128);
+        assert!(matches!(result, Err(OledError::InvalidArgument(_))));
+        assert!(lit(&frame).is_empty());
+    }
+    // taller than the display, but still drawn, clipped
+    frame.draw_text_ttf(0, 0, "A", &font, 200.0, 128).unwrap();
+    assert!(!lit(&frame).is_empty());
+}
@@ -0,0 +1,60 @@
+use nanohat_oled::{Oled, Typewriter};
+
+mod common;
+use common::{ms, NullInterface};
+
+/// The underscore cursor, as drawn in the built-in font
+const CURSOR: [u8; 8] = [0x00, 0x80, 0x80, 0x80, 0x80, 0x80, 0x00, 0x00];
+
+/// The 8x8 cell at text column `column` of the top row
+fn cell(oled: &Oled<NullInterface>, column: usize) -> [u8; 8] {
+    let mut cell = [0; 8];
+    cell.copy_from_slice(&oled.framebuffer()[column * 8..][..8]);
+    cell
+}
+
+#[test]
+fn types_a_character_at_a_time() {
+    let mut oled = Oled::new(NullInterface);
+    let mut typewriter = Typewriter::new("HI!", 10);
+    assert_eq!(typewriter.tick(&mut oled, ms(0)).unwrap(), Some(ms(100)));
+    assert_ne!(cell(&oled, 0), [0; 8]);
+    assert_eq!(cell(&oled, 1), [0; 8]);
+    assert_eq!(typewriter.tick(&mut oled, ms(150)).unwrap(), Some(ms(50)));
+    assert_ne!(cell(&oled, 1), [0; 8]);
+    assert_eq!(cell(&oled, 2), [0; 8]);
+    assert_eq!(typewriter.tick(&mut oled, ms(50)).unwrap(), None);
+    assert!(typewriter.is_finished());
+    assert_ne!(cell(&oled, 2), [0; 8]);
+
+    // the text cursor is left after the text, as put_string() leaves it
+    oled.put_char('_').unwrap();
+    assert_eq!(cell(&oled, 3), CURSOR);
+}
+
+#[test]
+fn blinks_the_cursor_at_the_insertion_point() {
+    let mut oled = Oled::new(NullInterface);
+    let mut typewriter = Typewriter::new("AB", 1).with_cursor(true);
+    assert_eq!(typewriter.tick(&mut oled, ms(0)).unwrap(), Some(ms(500)));
+    assert_eq!(cell(&oled, 1), CURSOR);
+    assert_eq!(typewriter.tick(&mut oled, ms(500)).unwrap(), Some(ms(500)));
+    assert_eq!(cell(&oled, 1), [0; 8]);
+    // typing the next character writes over the cursor, and takes it away
+    // once the text is done
+    assert_eq!(typewriter.tick(&mut oled, ms(500)).unwrap(), None);
+    assert_ne!(cell(&oled, 1), [0; 8]);
+    assert_ne!(cell(&oled, 1), CURSOR);
+    assert_eq!(cell(&oled, 2), [0; 8]);
+    assert!(typewriter.is_finished());
+}
+
+#[test]
+fn types_everything_at_once_at_zero_rate() {
+    let mut oled = Oled::new(NullInterface);
+    let mut typewriter = Typewriter::new("OK", 0).with_cursor(true);
+    assert_eq!(typewriter.tick(&mut oled, ms(0)).unwrap(), None);
+    assert_ne!(cell(&oled, 1), [0; 8]);
+    assert_eq!(cell(&oled, 2), [0; 8]);
+    assert_eq!(Typewriter::new("", 5).tick(&mut oled, ms(0)).unwrap(), None);
+}
@@ -0,0 +1,58 @@
+use nanohat_oled::{Framebuffer, MockInterface, Oled, Rect, Widget};
+
+/// A widget that fills its area, counting how often it is rendered
+#[derive(Default)]
+struct Filled {
+    changed: bool,
+    renders: usize,
+}
+
+impl Widget for Filled {
+    fn render(&mut self, frame: &mut Framebuffer, area: Rect) {
+        frame.fill_rect(area.x, area.y, area.width, area.height, true);
+        self.changed = false;
+        self.renders += 1;
+    }
+
+    fn needs_render(&self) -> bool {
+        self.changed
+    }
+}
+
+#[test]
+fn splits_rects() {
+    let area = Rect::new(0, 0, 128, 64);
+    assert_eq!(
+        area.split_top(16),
+        (Rect::new(0, 0, 128, 16), Rect::new(0, 16, 128, 48))
+    );
+    assert_eq!(area.split_left(200), (area, Rect::new(128, 0, 0, 64)));
+    assert_eq!(area.inset(2), Rect::new(2, 2, 124, 60));
+    assert_eq!(Rect::new(10, 10, 4, 4).inset(3), Rect::new(12, 12, 0, 0));
+}
+
+#[test]
+fn renders_only_widgets_that_changed() {
+    let mut oled = Oled::new(MockInterface::new());
+    let (top, bottom) = Rect::new(0, 0, 128, 64).split_top(8);
+    let mut header = Filled::default();
+    let mut body = Filled {
+        changed: true,
+        ..Filled::default()
+    };
+    oled.render_widgets(&mut [(&mut header, top), (&mut body, bottom)])
+        .unwrap();
+    assert_eq!((header.renders, body.renders), (0, 1));
+    let frame = *oled.framebuffer();
+    assert!(frame[..128].iter().all(|byte| *byte == 0));
+    assert!(frame[128..].iter().all(|byte| *byte == 0xff));
+
+    // nothing has changed, so nothing is sent
+    let mut bus = oled.into_interface();
+    bus.clear();
+    Oled::new(&mut bus)
+        .render_widgets(&mut [(&mut header, top), (&mut body, bottom)])
+        .unwrap();
+    assert_eq!((header.renders, body.renders), (0, 1));
+    assert!(bus.sent().is_empty());
+}
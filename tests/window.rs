@@ -0,0 +1,63 @@
+use nanohat_oled::{CaptureDevice, Controller, MockInterface, Oled, OledConfig, OledError, Rect};
+
+#[test]
+fn data_fills_the_draw_window() {
+    let mut device = CaptureDevice::new();
+    {
+        let mut oled = Oled::new(&mut device);
+        oled.init().unwrap();
+        oled.set_draw_window(120, 127, 2, 3).unwrap();
+        oled.send_array_data(&[0xff; 16][..]).unwrap();
+    }
+    assert!(device.is_lit(120, 16));
+    assert!(device.is_lit(127, 31));
+    assert!(!device.is_lit(119, 16));
+    assert!(!device.is_lit(127, 32));
+    // full, so wrapped back to the top left of the window
+    assert_eq!(device.pointer(), (120, 2));
+}
+
+#[test]
+fn windows_off_the_display_are_rejected_without_sending() {
+    let mut bus = MockInterface::new();
+    {
+        let mut oled = Oled::new(&mut bus);
+        for (columns, pages) in [((0, 128), (0, 7)), ((10, 9), (0, 7)), ((0, 127), (3, 8))] {
+            let result = oled.set_draw_window(columns.0, columns.1, pages.0, pages.1);
+            assert!(matches!(result, Err(OledError::InvalidArgument(_))));
+        }
+    }
+    assert!(bus.sent().is_empty());
+    let mut oled = Oled::new(MockInterface::new());
+    oled.init_with(OledConfig::DEFAULT.with_controller(Controller::Sh1106))
+        .unwrap();
+    let result = oled.set_draw_window(0, 7, 0, 0);
+    assert!(matches!(result, Err(OledError::InvalidArgument(_))));
+}
+
+#[test]
+fn draw_region_sends_only_the_pages_and_columns_covered() {
+    let mut device = CaptureDevice::new();
+    Oled::new(&mut device)
+        .draw_region(&[0xff; 16 * 8], Rect::new(8, 8, 16, 8), 0x80)
+        .unwrap();
+    assert_eq!(device.data().len(), 16);
+    assert!(device.get_pixel(8, 8) && device.get_pixel(23, 15));
+    assert!(!device.get_pixel(7, 8) && !device.get_pixel(24, 15));
+    // with the window put back afterwards
+    assert!(device.commands().ends_with(&[0x21, 0, 127, 0x22, 0, 7]));
+}
+
+#[test]
+fn clearing_puts_the_window_back() {
+    let mut device = CaptureDevice::new();
+    {
+        let mut oled = Oled::new(&mut device);
+        oled.init().unwrap();
+        oled.draw_region(&[0xff; 64], Rect::new(64, 32, 8, 8), 0x80)
+            .unwrap();
+        oled.set_draw_window(0, 7, 0, 0).unwrap();
+        oled.clear_display().unwrap();
+    }
+    assert!(device.ram().iter().all(|&b| b == 0));
+}
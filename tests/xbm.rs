@@ -0,0 +1,51 @@
+use nanohat_oled::{Framebuffer, OledError};
+
+mod common;
+use common::lit;
+
+#[test]
+fn loads_an_xbm_file() {
+    let frame = Framebuffer::from_xbm(include_bytes!("data/arrow.xbm")).unwrap();
+    let mut arrow = vec![(5, 0), (5, 1), (6, 1)];
+    arrow.extend((0..10).map(|x| (x, 2)));
+    arrow.extend([(5, 3), (6, 3), (5, 4)]);
+    assert_eq!(lit(&frame), arrow);
+}
+
+#[test]
+fn reads_decimal_and_uppercase_hex() {
+    let xbm = b"#define a_width 8\n#define a_height 2\nstatic char a_bits[] = {0XFF,1,};";
+    let frame = Framebuffer::from_xbm(xbm).unwrap();
+    let mut pixels: Vec<_> = (0..8).map(|x| (x, 0)).collect();
+    pixels.push((0, 1));
+    assert_eq!(lit(&frame), pixels);
+}
+
+#[test]
+fn rejects_what_isnt_an_xbm_image() {
+    assert!(matches!(
+        Framebuffer::from_xbm(b"P4\n1 1\n\x00"),
+        Err(OledError::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        Framebuffer::from_xbm(b"#define a_width 8\n#define a_height 2\nchar a_bits[] = {0xff};"),
+        Err(OledError::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        Framebuffer::from_xbm(b"#define a_width 8\n#define a_height 1\nchar a_bits[] = {0xfg};"),
+        Err(OledError::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        Framebuffer::from_xbm(b"#define a_width 129\n#define a_height 1\nchar a_bits[] = {};"),
+        Err(OledError::InvalidDimensions)
+    ));
+    for empty in [
+        &b"#define a_width 0\n#define a_height 1\nchar a_bits[] = {0xff};"[..],
+        b"#define a_width 8\n#define a_height 0\nchar a_bits[] = {};",
+    ] {
+        assert!(matches!(
+            Framebuffer::from_xbm(empty),
+            Err(OledError::InvalidDimensions)
+        ));
+    }
+}